@@ -15,4 +15,4 @@ sshpass -p password scp -P 50000 ./tmux.conf root@localhost:/home/tmux.conf
 
 # Start a new tmux session with environment variables set
 sshpass -p password ssh -p 50000 root@localhost \
-"cd /home && tmux -f ./tmux.conf new-session -d -s melvin_evaluation 'RUST_BACKTRACE=1 TRY_IMPORT_ORBIT=1 EXPORT_ORBIT=1 SKIP_RESET=1 DRS_BASE_URL=http://10.100.10.3:33000 /home/melvin-ob'"
\ No newline at end of file
+"cd /home && tmux -f ./tmux.conf new-session -d -s melvin_evaluation 'RUST_BACKTRACE=1 MELVIN_ORBIT_TRY_IMPORT=true MELVIN_ORBIT_EXPORT=true MELVIN_NETWORK_SKIP_RESET=true MELVIN_NETWORK_BASE_URL=http://10.100.10.3:33000 /home/melvin-ob'"
\ No newline at end of file
@@ -0,0 +1,41 @@
+//! Public facade of the MELVIN onboard software.
+//!
+//! The onboard subsystems are crate-private and wired together by
+//! [`run_configured_mission`](crate::run_configured_mission).
+//! This module re-exports their core types, so that analysis tools and alternative frontends
+//! can reuse them without copying modules. Only types and their documented public methods
+//! are part of this facade. Commanding MELVIN, the mode state machine and the DRS
+//! communication stay internal, the flight state is only exposed through the read-only
+//! [`FlightTelemetry`](flight::FlightTelemetry) view.
+
+/// Flight control: flight states and the backend-independent, read-only telemetry view.
+pub mod flight {
+    pub use crate::flight_control::{FlightState, FlightTelemetry};
+}
+
+/// Orbit types: closed orbits, orbit positions and burn sequences.
+pub mod orbit {
+    pub use crate::flight_control::orbit::{
+        BurnSequence, ClosedOrbit, IndexedOrbitPosition, OrbitBase, OrbitCharacteristics,
+        OrbitUsabilityError,
+    };
+}
+
+/// Scheduling: the task controller, the scheduled task types and the schedule simulator.
+pub mod scheduling {
+    pub use crate::scheduling::{
+        Clock, ScheduleSimReport, ScheduleSimulator, SimSample, SimViolation, TaskController,
+        VirtualClock, WallClock,
+    };
+    pub use crate::scheduling::task::{BaseTask, Task, TimeResolution};
+}
+
+/// Imaging: camera lenses and the map image buffers.
+pub mod imaging {
+    pub use crate::imaging::{CameraAngle, FullsizeMapImage, ThumbnailMapImage};
+}
+
+/// Math utilities shared by all subsystems.
+pub mod util {
+    pub use crate::util::{MapSize, Vec2D, VecAxis, WrapDirection};
+}
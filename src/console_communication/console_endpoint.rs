@@ -1,9 +1,12 @@
-use super::melvin_messages;
+use super::{delivery_tracker::DeliveryTracker, melvin_messages};
+use crate::http_handler::{BandwidthShaper, TrafficClass};
+use crate::util::MissionConfig;
 use crate::{info, warn};
 use prost::Message;
 use std::{
     io::{Cursor, ErrorKind},
-    sync::Arc,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
 };
 use tokio::{
     io::{AsyncReadExt, AsyncWriteExt},
@@ -33,23 +36,33 @@ pub(crate) struct ConsoleEndpoint {
     downstream: broadcast::Sender<Option<Arc<Vec<u8>>>>,
     /// Used to broadcast upstream events from consoles.
     upstream_event: broadcast::Sender<ConsoleEvent>,
+    /// Tracks the acknowledgments of critical downstream messages.
+    delivery: Arc<Mutex<DeliveryTracker>>,
     /// A channel sender to trigger endpoint shutdown.
     close_oneshot: Option<oneshot::Sender<()>>,
 }
 
 impl ConsoleEndpoint {
+    /// The interval in which unacknowledged critical messages are checked for re-sending.
+    const RESEND_INTERVAL: Duration = Duration::from_secs(1);
+
     /// Handles incoming data from the connected console. It listens for messages
-    /// and broadcasts them as upstream events.
+    /// and broadcasts them as upstream events. Acknowledgments are consumed by the
+    /// [`DeliveryTracker`], and rejected messages are re-sent.
     ///
     /// # Arguments
     /// - `socket`: The reading end of the connection.
     /// - `upstream_event_sender`: The sender used to broadcast received upstream events.
+    /// - `delivery`: The tracker of unacknowledged downstream messages.
+    /// - `downstream_sender`: The sender used to re-send rejected messages.
     ///
     /// # Errors
     /// Returns I/O errors if issues arise when reading data from the socket.
     async fn handle_connection_rx(
         socket: &mut ReadHalf<'_>,
         upstream_event_sender: &broadcast::Sender<ConsoleEvent>,
+        delivery: &Mutex<DeliveryTracker>,
+        downstream_sender: &broadcast::Sender<Option<Arc<Vec<u8>>>>,
     ) -> Result<(), std::io::Error> {
         loop {
             let length = socket.read_u32().await?;
@@ -57,11 +70,26 @@ impl ConsoleEndpoint {
             let mut buffer = vec![0u8; length as usize];
             socket.read_exact(&mut buffer).await?;
 
-            if let Ok(melvin_messages::Upstream { content: Some(content) }) =
+            let Ok(melvin_messages::Upstream { content: Some(content), protocol_version }) =
                 melvin_messages::Upstream::decode(&mut Cursor::new(buffer))
-            {
-                info!("Received upstream message: {content:?}");
-                upstream_event_sender.send(ConsoleEvent::Message(content)).unwrap();
+            else {
+                continue;
+            };
+            let mut tracker = delivery.lock().unwrap();
+            tracker.set_peer_version(protocol_version);
+            match content {
+                melvin_messages::UpstreamContent::Ack(ack) => tracker.ack(ack.message_id),
+                melvin_messages::UpstreamContent::Nack(nack) => {
+                    warn!("Console rejected message {}: {}", nack.message_id, nack.reason);
+                    if let Some(payload) = tracker.nack(nack.message_id, Instant::now()) {
+                        downstream_sender.send(Some(payload)).ok();
+                    }
+                }
+                msg => {
+                    drop(tracker);
+                    info!("Received upstream message: {msg:?}");
+                    upstream_event_sender.send(ConsoleEvent::Message(msg)).unwrap();
+                }
             }
         }
     }
@@ -81,6 +109,7 @@ impl ConsoleEndpoint {
         downstream_receiver: &mut broadcast::Receiver<Option<Arc<Vec<u8>>>>,
     ) -> Result<(), std::io::Error> {
         while let Ok(Some(message_buffer)) = downstream_receiver.recv().await {
+            BandwidthShaper::link().acquire(TrafficClass::Console, message_buffer.len()).await;
             socket.write_u32(message_buffer.len() as u32).await?;
             socket.write_all(&message_buffer).await?;
         }
@@ -99,11 +128,14 @@ impl ConsoleEndpoint {
         let downstream_sender = broadcast::Sender::new(5);
         let upstream_event_sender = broadcast::Sender::new(5);
         let (close_oneshot_sender, mut close_oneshot_receiver) = oneshot::channel();
+        let delivery = Arc::new(Mutex::new(DeliveryTracker::default()));
         let inst = Self {
             downstream: downstream_sender.clone(),
             upstream_event: upstream_event_sender.clone(),
+            delivery: Arc::clone(&delivery),
             close_oneshot: Some(close_oneshot_sender),
         };
+        Self::spawn_resend(Arc::downgrade(&delivery), downstream_sender.clone());
         tokio::spawn(async move {
             info!("Started Console Endpoint");
             let listener = TcpListener::bind("0.0.0.0:1337").await.unwrap();
@@ -114,6 +146,10 @@ impl ConsoleEndpoint {
                 };
 
                 if let Ok((mut socket, _)) = accept {
+                    // The protocol version is announced again with the first upstream message
+                    delivery.lock().unwrap().set_peer_version(0);
+                    let delivery_local = Arc::clone(&delivery);
+                    let downstream_sender_local = downstream_sender.clone();
                     let upstream_event_sender_local = upstream_event_sender.clone();
                     upstream_event_sender_local.send(ConsoleEvent::Connected).unwrap();
                     let mut downstream_receiver = downstream_sender.subscribe();
@@ -124,7 +160,12 @@ impl ConsoleEndpoint {
 
                         let result = tokio::select! {
                             res = ConsoleEndpoint::handle_connection_tx(&mut tx_socket, &mut downstream_receiver) => res,
-                            res = ConsoleEndpoint::handle_connection_rx(&mut rx_socket, &upstream_event_sender_local) => res
+                            res = ConsoleEndpoint::handle_connection_rx(
+                                &mut rx_socket,
+                                &upstream_event_sender_local,
+                                &delivery_local,
+                                &downstream_sender_local,
+                            ) => res
                         };
 
                         upstream_event_sender_local.send(ConsoleEvent::Disconnected).unwrap();
@@ -151,14 +192,66 @@ impl ConsoleEndpoint {
         inst
     }
 
+    /// Re-sends unacknowledged critical messages to connected consoles until the endpoint
+    /// is dropped.
+    ///
+    /// # Arguments
+    /// - `delivery`: The tracker of unacknowledged downstream messages.
+    /// - `downstream_sender`: The sender used to re-send messages.
+    fn spawn_resend(
+        delivery: std::sync::Weak<Mutex<DeliveryTracker>>,
+        downstream_sender: broadcast::Sender<Option<Arc<Vec<u8>>>>,
+    ) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Self::RESEND_INTERVAL);
+            loop {
+                interval.tick().await;
+                let Some(tracker) = delivery.upgrade() else { break };
+                if downstream_sender.receiver_count() == 0 {
+                    continue;
+                }
+                let due = tracker.lock().unwrap().due(Instant::now());
+                for payload in due {
+                    downstream_sender.send(Some(payload)).ok();
+                }
+            }
+        });
+    }
+
     /// Sends a downstream message to the operator console.
     ///
+    /// Consoles speaking protocol version `2` receive image payloads compressed with zstd
+    /// at `console_zstd_level` of the [`MissionConfig`] and have to acknowledge critical
+    /// messages, which are re-sent otherwise.
+    ///
     /// # Arguments
     /// - `msg`: A `DownstreamContent` message to send.
-    pub(crate) fn send_downstream(&self, msg: melvin_messages::DownstreamContent) {
-        let _ = self.downstream.send(Some(Arc::new(
-            melvin_messages::Downstream { content: Some(msg) }.encode_to_vec(),
-        )));
+    #[allow(clippy::cast_possible_wrap)]
+    pub(crate) fn send_downstream(&self, mut msg: melvin_messages::DownstreamContent) {
+        let level = MissionConfig::get().runtime.console_zstd_level;
+        // The version check and the tracking share one guard, so a reconnecting console can
+        // not receive a message encoded for the previous one
+        let mut tracker = self.delivery.lock().unwrap();
+        let v2 = tracker.peer_version() >= melvin_messages::PROTOCOL_VERSION;
+        if v2 && level > 0 {
+            msg.compress_payloads(level as i32);
+        }
+        let requires_ack = v2 && msg.is_critical();
+        let message_id = tracker.next_id();
+        let payload = Arc::new(
+            melvin_messages::Downstream {
+                content: Some(msg),
+                protocol_version: melvin_messages::PROTOCOL_VERSION,
+                message_id,
+                requires_ack,
+            }
+            .encode_to_vec(),
+        );
+        if requires_ack {
+            tracker.track(message_id, Arc::clone(&payload), Instant::now());
+        }
+        drop(tracker);
+        let _ = self.downstream.send(Some(payload));
     }
 
     /// Checks whether any console is currently connected to the endpoint.
@@ -180,9 +273,12 @@ impl ConsoleEndpoint {
 
 impl Drop for ConsoleEndpoint {
     /// Handles graceful shutdown of the `ConsoleEndpoint`. Signals the close channel
-    /// and notifies all downstream subscribers of disconnection.
+    /// and notifies all downstream subscribers of disconnection. Either side may already be gone
+    /// while the runtime shuts down.
     fn drop(&mut self) {
-        self.close_oneshot.take().unwrap().send(()).unwrap();
-        self.downstream.send(None).unwrap();
+        if let Some(close) = self.close_oneshot.take() {
+            close.send(()).ok();
+        }
+        self.downstream.send(None).ok();
     }
 }
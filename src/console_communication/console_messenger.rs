@@ -1,15 +1,30 @@
-use crate::flight_control::{FlightState, Supervisor};
-use crate::scheduling::TaskController;
-use crate::scheduling::task::{BaseTask, ImageTaskStatus};
-use crate::imaging::{CameraAngle, CameraController};
-use crate::util::Vec2D;
-use crate::info;
+use crate::flight_control::{
+    FlightComputer, FlightState, ManualCommand, ReplaySession, Supervisor,
+    orbit::{ClosedOrbit, OrbitCharacteristics, OrbitCoverageHeatmap},
+};
+use crate::mode_control::MODE_GRAPH;
+use crate::scheduling::{MissionTimeline, TaskController};
+use crate::scheduling::task::{BaseTask, ImageTaskStatus, Task};
+use crate::imaging::{
+    CameraAngle, CameraController, ImageCodec, TileId,
+    map_image::{EncodedImageExtract, ThumbnailMapImage},
+};
+use crate::objective::{BeaconController, OBJECTIVE_TRACKER, PosteriorStats, ScoreLedger};
+use crate::util::{
+    EVENT_BUS, IMAGING_POOL, ImgObjectiveId, LogEvent, MISSION_JOURNAL, MissionConfig,
+    SafetyEvent, Vec2D, ZoneRect, logger::JsonDump,
+};
+use crate::{info, warn};
 use super::{
     console_endpoint::{ConsoleEndpoint, ConsoleEvent},
+    load_shedder::{LoadShedder, OptionalTraffic, ShedLevel},
     melvin_messages,
 };
 
-use std::sync::Arc;
+use chrono::{DateTime, NaiveDate, Utc};
+use fixed::types::I32F32;
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::{RwLock, watch};
 
 /// Handles communication with the console.
 ///
@@ -25,30 +40,48 @@ pub struct ConsoleMessenger {
     supervisor: Arc<Supervisor>,
     /// A shared reference to the console endpoint, used for sending and receiving messages.
     endpoint: Arc<ConsoleEndpoint>,
+    /// The load-shedding policy for optional traffic.
+    shedder: Arc<LoadShedder>,
+    /// The mission timeline of the latest scheduling pass.
+    timeline: Arc<RwLock<Option<MissionTimeline>>>,
 }
 
 impl ConsoleMessenger {
+    /// The number of orbit buckets of a heatmap if the console does not request one.
+    const DEF_ORBIT_HEATMAP_BUCKETS: usize = 256;
+
     /// Starts the `ConsoleMessenger`, initializing the console endpoint.
     /// Listens for incoming console events asynchronously.
     ///
     /// # Arguments
     /// - `camera_controller`: Shared reference to `CameraController`.
     /// - `task_controller`: Shared reference to `TaskController`.
+    /// - `supervisor`: Shared reference to the `Supervisor`.
+    /// - `score`: Shared reference to the `ScoreLedger` shown in the score dashboard.
     ///
     /// # Returns
     /// An instance of `ConsoleMessenger`.
-    #[allow(clippy::cast_possible_wrap)]
+    #[allow(clippy::cast_possible_wrap, clippy::too_many_lines)]
     pub(crate) fn start(
         camera_controller: Arc<CameraController>,
         task_controller: Arc<TaskController>,
         supervisor: Arc<Supervisor>,
+        score: Arc<ScoreLedger>,
     ) -> Self {
         let endpoint = Arc::new(ConsoleEndpoint::start());
+        let shedder = Arc::new(LoadShedder::new());
+        let shedder_local = Arc::clone(&shedder);
+        tokio::spawn(async move { shedder_local.run_monitor().await });
+        Self::spawn_score_dashboard(Arc::clone(&endpoint), score, Arc::clone(&shedder));
+        Self::spawn_log_forwarding(Arc::clone(&endpoint));
+        Self::spawn_disk_space_alerts(Arc::clone(&endpoint));
         let mut receiver = endpoint.subscribe_upstream_events();
         let endpoint_local = endpoint.clone();
         let camera_controller_local = camera_controller.clone();
         let supervisor_local = supervisor.clone();
         let t_cont_local = task_controller.clone();
+        let timeline = Arc::new(RwLock::new(None));
+        let timeline_local = Arc::clone(&timeline);
         tokio::spawn(async move {
             while let Ok(event) = receiver.recv().await {
                 match event {
@@ -74,7 +107,7 @@ impl ConsoleMessenger {
                     }
                     ConsoleEvent::Message(melvin_messages::UpstreamContent::GetFullImage(_)) => {
                         if let Ok(encoded_image) =
-                            camera_controller_local.export_full_thumbnail_png().await
+                            camera_controller_local.export_full_thumbnail().await
                         {
                             endpoint_local.send_downstream(
                                 melvin_messages::DownstreamContent::Image(
@@ -94,8 +127,8 @@ impl ConsoleMessenger {
                         tokio::spawn(async move {
                             let objective_id = submit_objective.objective_id;
                             let result = c_cont_lock_local_clone
-                                .export_and_upload_objective_png(
-                                    objective_id as usize,
+                                .export_and_upload_objective_img(
+                                    ImgObjectiveId::new(objective_id as usize),
                                     Vec2D::new(
                                         submit_objective.offset_x,
                                         submit_objective.offset_y,
@@ -103,6 +136,7 @@ impl ConsoleMessenger {
                                     Vec2D::new(submit_objective.width, submit_objective.height),
                                     None,
                                     None,
+                                    None,
                                 )
                                 .await;
                             info!("Submitted objective '{objective_id}' with result: {result:?}");
@@ -120,12 +154,15 @@ impl ConsoleMessenger {
                         melvin_messages::UpstreamContent::ScheduleSecretObjective(objective),
                     ) => {
                         supervisor_local
-                            .schedule_secret_objective(objective.objective_id as usize, [
-                                objective.offset_x as i32,
-                                objective.offset_y as i32,
-                                (objective.offset_x + objective.width) as i32,
-                                (objective.offset_y + objective.height) as i32,
-                            ])
+                            .schedule_secret_objective(
+                                ImgObjectiveId::new(objective.objective_id as usize),
+                                ZoneRect::new(
+                                    objective.offset_x as i32,
+                                    objective.offset_y as i32,
+                                    (objective.offset_x + objective.width) as i32,
+                                    (objective.offset_y + objective.height) as i32,
+                                ),
+                            )
                             .await;
                     }
                     ConsoleEvent::Message(melvin_messages::UpstreamContent::SubmitDailyMap(_)) => {
@@ -145,22 +182,658 @@ impl ConsoleMessenger {
                             );
                         });
                     }
+                    ConsoleEvent::Message(melvin_messages::UpstreamContent::GetConfig(_)) => {
+                        Self::send_config_report(&endpoint_local, None);
+                    }
+                    ConsoleEvent::Message(melvin_messages::UpstreamContent::SetConfig(set)) => {
+                        let res = MissionConfig::set_override(&set.key, &set.value);
+                        Self::send_config_report(&endpoint_local, res.err().map(|e| e.to_string()));
+                    }
+                    ConsoleEvent::Message(melvin_messages::UpstreamContent::RevertConfig(_)) => {
+                        let res = MissionConfig::revert_last();
+                        Self::send_config_report(&endpoint_local, res.err().map(|e| e.to_string()));
+                    }
+                    ConsoleEvent::Message(melvin_messages::UpstreamContent::GetEventLog(req)) => {
+                        Self::send_event_log(&endpoint_local, req.count as usize);
+                    }
+                    ConsoleEvent::Message(melvin_messages::UpstreamContent::GetModeGraph(req)) => {
+                        Self::send_mode_graph(&endpoint_local, req.format());
+                    }
+                    ConsoleEvent::Message(
+                        melvin_messages::UpstreamContent::GetMissionTimeline(_),
+                    ) => {
+                        Self::send_timeline(&endpoint_local, timeline_local.read().await.as_ref());
+                    }
+                    ConsoleEvent::Message(
+                        melvin_messages::UpstreamContent::GetObjectiveLifecycles(_),
+                    ) => {
+                        Self::send_objective_lifecycles(&endpoint_local);
+                    }
+                    ConsoleEvent::Message(melvin_messages::UpstreamContent::GetMapTile(req)) => {
+                        let c_cont_lock_local_clone = camera_controller_local.clone();
+                        let endpoint_local_clone = endpoint_local.clone();
+                        tokio::spawn(async move {
+                            let camera = &c_cont_lock_local_clone;
+                            Self::send_map_tile(&endpoint_local_clone, camera, req).await;
+                        });
+                    }
+                    ConsoleEvent::Message(melvin_messages::UpstreamContent::EndMission(_)) => {
+                        supervisor_local.request_end_of_mission("operator console");
+                    }
+                    ConsoleEvent::Message(
+                        melvin_messages::UpstreamContent::GetReplaySession(request),
+                    ) => {
+                        let day = request
+                            .day
+                            .and_then(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok())
+                            .unwrap_or_else(|| Utc::now().date_naive());
+                        let session = supervisor_local.replay_session(day).await;
+                        let endpoint_local_clone = endpoint_local.clone();
+                        tokio::spawn(async move {
+                            Self::send_replay_session(&endpoint_local_clone, &session);
+                        });
+                    }
+                    ConsoleEvent::Message(melvin_messages::UpstreamContent::PinTask(pin)) => {
+                        if let Some(task) = Self::pinned_task(pin) {
+                            t_cont_local.schedule_pinned(task).await;
+                        } else {
+                            warn!("Rejected invalid pinned task from console.");
+                            Self::send_alert_from_endpoint(
+                                &endpoint_local,
+                                "Pinned task rejected: only switches to charge or acquisition \
+                                and image tasks can be pinned."
+                                    .to_string(),
+                            );
+                        }
+                        Self::send_tasklist_from_endpoint(&endpoint_local, &t_cont_local).await;
+                    }
+                    ConsoleEvent::Message(
+                        melvin_messages::UpstreamContent::CancelPinnedTasks(_),
+                    ) => {
+                        let removed = t_cont_local.cancel_pinned().await;
+                        info!("Cancelled {removed} pinned tasks from console.");
+                        Self::send_tasklist_from_endpoint(&endpoint_local, &t_cont_local).await;
+                    }
+                    ConsoleEvent::Message(melvin_messages::UpstreamContent::ManualCommand(
+                        msg,
+                    )) => {
+                        let Some(cmd) = Self::manual_command(msg) else {
+                            warn!("Rejected invalid manual command from console.");
+                            endpoint_local.send_downstream(
+                                melvin_messages::DownstreamContent::CommandResponse(
+                                    melvin_messages::CommandResponse {
+                                        command: "invalid".to_string(),
+                                        accepted: false,
+                                        reason: Some("malformed command".to_string()),
+                                    },
+                                ),
+                            );
+                            continue;
+                        };
+                        let supervisor_local_clone = Arc::clone(&supervisor_local);
+                        let endpoint_local_clone = endpoint_local.clone();
+                        tokio::spawn(async move {
+                            let res = supervisor_local_clone.execute_manual_command(cmd).await;
+                            endpoint_local_clone.send_downstream(
+                                melvin_messages::DownstreamContent::CommandResponse(
+                                    melvin_messages::CommandResponse {
+                                        command: cmd.to_string(),
+                                        accepted: res.is_ok(),
+                                        reason: res.err().map(|e| e.to_string()),
+                                    },
+                                ),
+                            );
+                        });
+                    }
                     _ => {}
                 }
             }
         });
-        Self { camera_controller, task_controller, supervisor, endpoint }
+        Self { camera_controller, task_controller, supervisor, endpoint, shedder, timeline }
     }
 
-    /// Sends a thumbnail image to the operator console.
+    /// Spawns a task sending the score dashboard to the operator console on every score change.
+    ///
+    /// # Arguments
+    /// - `endpoint`: The console endpoint.
+    /// - `score`: The score ledger to watch.
+    /// - `shedder`: The load-shedding policy throttling dashboard updates.
+    fn spawn_score_dashboard(
+        endpoint: Arc<ConsoleEndpoint>,
+        score: Arc<ScoreLedger>,
+        shedder: Arc<LoadShedder>,
+    ) {
+        let mut total_rx = score.subscribe();
+        tokio::spawn(async move {
+            while total_rx.changed().await.is_ok() {
+                if !endpoint.is_console_connected()
+                    || !shedder.admit(OptionalTraffic::ScoreDashboard)
+                {
+                    continue;
+                }
+                let entries = score
+                    .entries()
+                    .await
+                    .into_iter()
+                    .map(|e| melvin_messages::ScoreDashboardEntry {
+                        timestamp: e.t.timestamp_millis(),
+                        source: e.source.to_string(),
+                        points: e.points,
+                    })
+                    .collect();
+                endpoint.send_downstream(melvin_messages::DownstreamContent::ScoreDashboard(
+                    melvin_messages::ScoreDashboard { total_points: score.total().await, entries },
+                ));
+            }
+        });
+    }
+
+    /// Spawns a task forwarding log messages at or above `runtime.console_log_level` to the
+    /// operator console. Messages emitted while no console is connected are dropped.
+    ///
+    /// # Arguments
+    /// - `endpoint`: The console endpoint.
+    fn spawn_log_forwarding(endpoint: Arc<ConsoleEndpoint>) {
+        let mut log_rx = EVENT_BUS.subscribe::<LogEvent>();
+        tokio::spawn(async move {
+            while let Some(LogEvent::Message(level, message)) = log_rx.recv().await {
+                if !endpoint.is_console_connected() {
+                    continue;
+                }
+                endpoint.send_downstream(melvin_messages::DownstreamContent::LogMessage(
+                    melvin_messages::LogMessage {
+                        timestamp: Utc::now().timestamp_millis(),
+                        level: level.to_string(),
+                        message,
+                    },
+                ));
+            }
+        });
+    }
+
+    /// Spawns a task alerting the operator console whenever the free disk space falls below
+    /// `min_free_disk_mib`, as objective images are deleted to protect the map buffer.
+    ///
+    /// # Arguments
+    /// - `endpoint`: The console endpoint.
+    fn spawn_disk_space_alerts(endpoint: Arc<ConsoleEndpoint>) {
+        let mut safety_rx = EVENT_BUS.subscribe::<SafetyEvent>();
+        tokio::spawn(async move {
+            while let Some(event) = safety_rx.recv().await {
+                let SafetyEvent::DiskSpaceLow(free) = event else { continue };
+                let msg = format!(
+                    "Disk space low, {} MiB free! Objective images not awaiting an upload were \
+                     deleted.",
+                    free >> 20
+                );
+                Self::send_alert_from_endpoint(&endpoint, msg);
+            }
+        });
+    }
+
+    /// Spawns a task answering heatmap requests of the operator console with the
+    /// credible-region heatmaps and posterior statistics of all active beacons.
+    ///
+    /// # Arguments
+    /// - `beac_cont`: The beacon controller holding the beacon estimates.
+    #[allow(clippy::cast_possible_truncation)]
+    pub(crate) fn spawn_beacon_heatmaps(&self, beac_cont: Arc<BeaconController>) {
+        let endpoint = Arc::clone(&self.endpoint);
+        let mut receiver = endpoint.subscribe_upstream_events();
+        tokio::spawn(async move {
+            while let Ok(event) = receiver.recv().await {
+                let ConsoleEvent::Message(melvin_messages::UpstreamContent::GetBeaconHeatmaps(_)) =
+                    event
+                else {
+                    continue;
+                };
+                let posteriors: HashMap<_, _> =
+                    beac_cont.posteriors().await.into_iter().collect();
+                for heatmap in beac_cont.heatmaps().await {
+                    let encoded =
+                        IMAGING_POOL.run("heatmap_encode", || heatmap.encode_png()).await;
+                    let data = match encoded {
+                        Ok(data) => data,
+                        Err(e) => {
+                            warn!("Error encoding heatmap of beacon {}: {e}", heatmap.id());
+                            continue;
+                        }
+                    };
+                    endpoint.send_downstream(melvin_messages::DownstreamContent::BeaconHeatmap(
+                        melvin_messages::BeaconHeatmap {
+                            objective_id: heatmap.id().raw() as u32,
+                            offset_x: heatmap.offset().x(),
+                            offset_y: heatmap.offset().y(),
+                            width: heatmap.size().x(),
+                            height: heatmap.size().y(),
+                            region_size: heatmap.region_size() as u32,
+                            guess_estimate: heatmap.guess_estimate() as u32,
+                            data,
+                            posterior: posteriors.get(&heatmap.id()).map(Self::posterior_msg),
+                        },
+                    ));
+                }
+            }
+        });
+    }
+
+    /// Spawns a task answering orbit heatmap requests of the operator console with the
+    /// coverage heatmap of the closed orbit ground track.
+    ///
+    /// # Arguments
+    /// - `c_orbit`: The closed orbit whose coverage is rendered.
+    #[allow(clippy::cast_possible_truncation)]
+    pub(crate) fn spawn_orbit_heatmap(&self, c_orbit: Arc<RwLock<ClosedOrbit>>) {
+        let endpoint = Arc::clone(&self.endpoint);
+        let mut receiver = endpoint.subscribe_upstream_events();
+        tokio::spawn(async move {
+            while let Ok(event) = receiver.recv().await {
+                let ConsoleEvent::Message(melvin_messages::UpstreamContent::GetOrbitHeatmap(req)) =
+                    event
+                else {
+                    continue;
+                };
+                let buckets = match req.buckets {
+                    0 => Self::DEF_ORBIT_HEATMAP_BUCKETS,
+                    n => n as usize,
+                };
+                let heatmap = {
+                    let orbit = c_orbit.read().await;
+                    IMAGING_POOL
+                        .run("orbit_heatmap", || {
+                            OrbitCoverageHeatmap::render(&orbit, buckets, CameraAngle::Wide)
+                        })
+                        .await
+                };
+                let encoded = IMAGING_POOL.run("heatmap_encode", || heatmap.encode_png()).await;
+                let data = match encoded {
+                    Ok(data) => data,
+                    Err(e) => {
+                        warn!("Error encoding orbit heatmap: {e}");
+                        continue;
+                    }
+                };
+                endpoint.send_downstream(melvin_messages::DownstreamContent::OrbitHeatmap(
+                    melvin_messages::OrbitHeatmap {
+                        width: heatmap.width(),
+                        height: heatmap.height(),
+                        bucket_len: heatmap.bucket_len() as u32,
+                        bucket_coverage: heatmap.coverage().to_vec(),
+                        data,
+                    },
+                ));
+            }
+        });
+    }
+
+    /// Spawns a task answering coverage forecast requests of the operator console with the
+    /// expected completion of the closed orbit under the current task schedule.
+    ///
+    /// # Arguments
+    /// - `c_orbit`: The closed orbit whose completion is forecast.
+    /// - `o_ch`: A watch on the characteristics of the current orbit phase.
+    /// - `f_cont`: The flight computer providing the current flight state.
+    #[allow(clippy::cast_possible_truncation)]
+    pub(crate) fn spawn_coverage_forecast(
+        &self,
+        c_orbit: Arc<RwLock<ClosedOrbit>>,
+        o_ch: watch::Receiver<OrbitCharacteristics>,
+        f_cont: Arc<RwLock<FlightComputer>>,
+    ) {
+        let endpoint = Arc::clone(&self.endpoint);
+        let t_cont = Arc::clone(&self.task_controller);
+        let mut receiver = endpoint.subscribe_upstream_events();
+        tokio::spawn(async move {
+            while let Ok(event) = receiver.recv().await {
+                let ConsoleEvent::Message(
+                    melvin_messages::UpstreamContent::GetCoverageForecast(_),
+                ) = event
+                else {
+                    continue;
+                };
+                let i_entry = o_ch.borrow().i_entry();
+                let state = f_cont.read().await.state();
+                let forecast = {
+                    let orbit = c_orbit.read().await;
+                    t_cont.coverage_forecast(&orbit, i_entry, state, None).await
+                };
+                let windows = forecast
+                    .windows()
+                    .iter()
+                    .map(|w| melvin_messages::CoverageWindow {
+                        start: w.start().timestamp_millis(),
+                        end: w.end().timestamp_millis(),
+                        new_secs: w.new_secs() as u32,
+                        gain: w.gain(),
+                    })
+                    .collect();
+                endpoint.send_downstream(melvin_messages::DownstreamContent::CoverageForecast(
+                    melvin_messages::CoverageForecast {
+                        orbit_secs: forecast.orbit_secs() as u32,
+                        remaining_secs: forecast.remaining_secs() as u32,
+                        completion: forecast.completion().map(|t| t.timestamp_millis()),
+                        windows,
+                    },
+                ));
+            }
+        });
+    }
+
+    /// Sends the effective mission configuration and the origin of each value to the console.
+    ///
+    /// # Arguments
+    /// - `endpoint`: The console endpoint.
+    /// - `error`: An optional error of the preceding configuration command.
+    fn send_config_report(endpoint: &ConsoleEndpoint, error: Option<String>) {
+        let entries = MissionConfig::describe()
+            .into_iter()
+            .map(|e| melvin_messages::ConfigReportEntry {
+                key: e.key,
+                value: e.value,
+                source: e.source.to_string(),
+                overridable: e.overridable,
+            })
+            .collect();
+        endpoint.send_downstream(melvin_messages::DownstreamContent::ConfigReport(
+            melvin_messages::ConfigReport { entries, error },
+        ));
+    }
+
+    /// Sends a recorded replay session to the console and persists it as a session file.
+    ///
+    /// The stored thumbnails of the session are loaded from disk and attached to the frames.
+    ///
+    /// # Arguments
+    /// - `endpoint`: The console endpoint.
+    /// - `session`: The replay session to send.
+    fn send_replay_session(endpoint: &ConsoleEndpoint, session: &ReplaySession) {
+        session.dump_json();
+        let points = session
+            .points
+            .iter()
+            .map(|p| melvin_messages::ReplayPoint {
+                timestamp: p.t.timestamp_millis(),
+                state: Self::satellite_state(p.state) as i32,
+                position_x: p.pos.x().to_num(),
+                position_y: p.pos.y().to_num(),
+                battery: p.battery.to_num(),
+                fuel: p.fuel.to_num(),
+            })
+            .collect();
+        let size = ThumbnailMapImage::thumbnail_size();
+        let frames = session
+            .frames
+            .iter()
+            .map(|f| melvin_messages::ReplayFrame {
+                timestamp: f.t.timestamp_millis(),
+                image: std::fs::read(&f.path).ok().map(|data| {
+                    melvin_messages::Image::from_encoded_image_extract(EncodedImageExtract {
+                        offset: Vec2D::new(0, 0),
+                        size,
+                        codec: ImageCodec::detect(&data).unwrap_or_default(),
+                        data,
+                    })
+                }),
+            })
+            .collect();
+        info!("Sending replay session of {} to console.", session.day);
+        endpoint.send_downstream(melvin_messages::DownstreamContent::ReplaySession(
+            melvin_messages::ReplaySession { day: session.day.to_string(), points, frames },
+        ));
+    }
+
+    /// Maps a [`FlightState`] to its console representation.
+    ///
+    /// # Arguments
+    /// - `state`: The flight state to map.
+    fn satellite_state(state: FlightState) -> melvin_messages::SatelliteState {
+        match state {
+            FlightState::Charge => melvin_messages::SatelliteState::Charge,
+            FlightState::Acquisition => melvin_messages::SatelliteState::Acquisition,
+            FlightState::Deployment => melvin_messages::SatelliteState::Deployment,
+            FlightState::Transition => melvin_messages::SatelliteState::Transition,
+            FlightState::Comms => melvin_messages::SatelliteState::Communication,
+            FlightState::Safe => melvin_messages::SatelliteState::Safe,
+        }
+    }
+
+    /// Sends the most recent entries of the mission journal to the console, each as a JSON
+    /// object in chronological order.
+    ///
+    /// # Arguments
+    /// - `endpoint`: The console endpoint.
+    /// - `count`: The maximum number of entries.
+    fn send_event_log(endpoint: &ConsoleEndpoint, count: usize) {
+        let entries = MISSION_JOURNAL
+            .last(count)
+            .iter()
+            .filter_map(|entry| serde_json::to_string(entry).ok())
+            .collect();
+        endpoint.send_downstream(melvin_messages::DownstreamContent::EventLog(
+            melvin_messages::EventLog { entries },
+        ));
+    }
+
+    /// Sends the graph of the global mode transitions to the console.
+    ///
+    /// # Arguments
+    /// - `endpoint`: The console endpoint.
+    /// - `format`: The requested export format.
+    fn send_mode_graph(endpoint: &ConsoleEndpoint, format: melvin_messages::ModeGraphFormat) {
+        let data = match format {
+            melvin_messages::ModeGraphFormat::Dot => MODE_GRAPH.to_dot(),
+            melvin_messages::ModeGraphFormat::Json => MODE_GRAPH.to_json(),
+        };
+        endpoint.send_downstream(melvin_messages::DownstreamContent::ModeGraph(
+            melvin_messages::ModeGraph { format: format as i32, data },
+        ));
+    }
+
+    /// Sends the lifecycles of all tracked objectives to the console.
+    ///
+    /// # Arguments
+    /// - `endpoint`: The console endpoint.
+    #[allow(clippy::cast_possible_truncation)]
+    fn send_objective_lifecycles(endpoint: &ConsoleEndpoint) {
+        let lifecycles = OBJECTIVE_TRACKER
+            .lifecycles()
+            .iter()
+            .map(|l| melvin_messages::ObjectiveLifecycle {
+                kind: l.objective().kind().to_string(),
+                objective_id: l.objective().raw_id() as u32,
+                transitions: l
+                    .transitions()
+                    .iter()
+                    .map(|t| melvin_messages::ObjectiveTransition {
+                        timestamp: t.t().timestamp_millis(),
+                        stage: t.stage().to_string(),
+                        reason: t.reason().map(str::to_string),
+                    })
+                    .collect(),
+            })
+            .collect();
+        endpoint.send_downstream(melvin_messages::DownstreamContent::ObjectiveLifecycles(
+            melvin_messages::ObjectiveLifecycles { lifecycles },
+        ));
+    }
+
+    /// Sends a tile of the map pyramid to the console, without an image if the tile does not
+    /// exist.
+    ///
+    /// # Arguments
+    /// - `endpoint`: The console endpoint.
+    /// - `camera_controller`: The camera controller holding the map.
+    /// - `req`: The requested zoom level, column and row.
+    async fn send_map_tile(
+        endpoint: &ConsoleEndpoint,
+        camera_controller: &CameraController,
+        req: melvin_messages::GetMapTile,
+    ) {
+        let id = TileId { zoom: req.zoom, x: req.x, y: req.y };
+        let (image, grid) = match camera_controller.export_map_tile(id).await {
+            Ok(tile) => tile,
+            Err(e) => {
+                warn!("Failed to export map tile {id:?}: {e}");
+                return;
+            }
+        };
+        endpoint.send_downstream(melvin_messages::DownstreamContent::MapTile(
+            melvin_messages::MapTile {
+                zoom: req.zoom,
+                x: req.x,
+                y: req.y,
+                grid_x: grid.x(),
+                grid_y: grid.y(),
+                image: image.map(melvin_messages::Image::from_encoded_image_extract),
+            },
+        ));
+    }
+
+    /// Maps the [`PosteriorStats`] of a beacon to its console representation.
+    ///
+    /// # Arguments
+    /// - `stats`: The posterior statistics to map.
+    fn posterior_msg(stats: &PosteriorStats) -> melvin_messages::BeaconPosterior {
+        let [[cov_xx, cov_xy], [_, cov_yy]] = stats.covariance();
+        melvin_messages::BeaconPosterior {
+            centroid_x: stats.centroid().x().to_num(),
+            centroid_y: stats.centroid().y().to_num(),
+            cov_xx,
+            cov_xy,
+            cov_yy,
+            error_radius: stats.error_radius(),
+            candidates: stats
+                .candidates()
+                .iter()
+                .map(|(c, share)| melvin_messages::BeaconCandidate {
+                    x: c.x().to_num(),
+                    y: c.y().to_num(),
+                    share: *share,
+                })
+                .collect(),
+        }
+    }
+
+    /// Drops all optional console traffic, e.g. thumbnails and task list updates, while
+    /// power saving is enabled. Alerts and answers to console requests are still sent.
+    ///
+    /// # Arguments
+    /// - `enabled`: Whether power saving is enabled.
+    pub(crate) fn set_power_saving(&self, enabled: bool) {
+        self.shedder.set_floor(if enabled { ShedLevel::Silent } else { ShedLevel::Nominal });
+    }
+
+    /// Sends an operator alert to the console.
     ///
     /// If the console is not connected, this method does nothing.
     ///
     /// # Arguments
+    /// - `message`: The alert text.
+    pub(crate) fn send_alert(&self, message: String) {
+        Self::send_alert_from_endpoint(&self.endpoint, message);
+    }
+
+    /// Sends an operator alert to the console.
+    ///
+    /// If the console is not connected, this method does nothing.
+    ///
+    /// # Arguments
+    /// - `endpoint`: The console endpoint.
+    /// - `message`: The alert text.
+    fn send_alert_from_endpoint(endpoint: &ConsoleEndpoint, message: String) {
+        if !endpoint.is_console_connected() {
+            return;
+        }
+        endpoint.send_downstream(melvin_messages::DownstreamContent::Alert(
+            melvin_messages::Alert { timestamp: Utc::now().timestamp_millis(), message },
+        ));
+    }
+
+    /// Converts an operator task pinned from the console into a [`Task`].
+    ///
+    /// Only switches to [`FlightState::Charge`] or [`FlightState::Acquisition`] and image tasks
+    /// can be pinned, as they are valid in every orbital mode.
+    ///
+    /// # Arguments
+    /// - `pin`: The pinned task message.
+    ///
+    /// # Returns
+    /// The task to pin, or `None` if the message is invalid.
+    fn pinned_task(pin: melvin_messages::PinTask) -> Option<Task> {
+        let t = DateTime::from_timestamp_millis(pin.scheduled_on)?;
+        match pin.task? {
+            melvin_messages::PinTaskType::SwitchState(state) => {
+                let target = match melvin_messages::SatelliteState::try_from(state).ok()? {
+                    melvin_messages::SatelliteState::Charge => FlightState::Charge,
+                    melvin_messages::SatelliteState::Acquisition => FlightState::Acquisition,
+                    _ => return None,
+                };
+                Some(Task::switch_target(target, t))
+            }
+            melvin_messages::PinTaskType::TakeImage(image) => {
+                let lens = match melvin_messages::Lens::try_from(image.lens).ok()? {
+                    melvin_messages::Lens::Narrow => CameraAngle::Narrow,
+                    melvin_messages::Lens::Normal => CameraAngle::Normal,
+                    melvin_messages::Lens::Wide => CameraAngle::Wide,
+                };
+                let pos = Vec2D::new(image.position_x, image.position_y);
+                Some(Task::image_task(pos, lens, t))
+            }
+        }
+    }
+
+    /// Converts a manual command from the console into a [`ManualCommand`].
+    ///
+    /// Every flight state can be requested, the legality is checked by the
+    /// [`Supervisor`] before the command is executed.
+    ///
+    /// # Arguments
+    /// - `cmd`: The manual command message.
+    ///
+    /// # Returns
+    /// The command, or `None` if the message is invalid.
+    fn manual_command(cmd: melvin_messages::ManualCommand) -> Option<ManualCommand> {
+        match cmd.command? {
+            melvin_messages::ManualCommandType::SetState(state) => {
+                let target = match melvin_messages::SatelliteState::try_from(state).ok()? {
+                    melvin_messages::SatelliteState::None => return None,
+                    melvin_messages::SatelliteState::Deployment => FlightState::Deployment,
+                    melvin_messages::SatelliteState::Safe => FlightState::Safe,
+                    melvin_messages::SatelliteState::Communication => FlightState::Comms,
+                    melvin_messages::SatelliteState::Charge => FlightState::Charge,
+                    melvin_messages::SatelliteState::Acquisition => FlightState::Acquisition,
+                    melvin_messages::SatelliteState::Transition => FlightState::Transition,
+                };
+                Some(ManualCommand::SetState(target))
+            }
+            melvin_messages::ManualCommandType::SetVelocity(vel) => {
+                let x = I32F32::checked_from_num(vel.velocity_x)?;
+                let y = I32F32::checked_from_num(vel.velocity_y)?;
+                Some(ManualCommand::SetVel(Vec2D::new(x, y)))
+            }
+            melvin_messages::ManualCommandType::SetLens(lens) => {
+                let angle = match melvin_messages::Lens::try_from(lens).ok()? {
+                    melvin_messages::Lens::Narrow => CameraAngle::Narrow,
+                    melvin_messages::Lens::Normal => CameraAngle::Normal,
+                    melvin_messages::Lens::Wide => CameraAngle::Wide,
+                };
+                Some(ManualCommand::SetLens(angle))
+            }
+            melvin_messages::ManualCommandType::AbortMode(_) => Some(ManualCommand::AbortMode),
+        }
+    }
+
+    /// Sends a thumbnail image to the operator console.
+    ///
+    /// If the console is not connected or thumbnails are currently shed, this method does
+    /// nothing.
+    ///
+    /// # Arguments
     /// - `offset`: The offset coordinates for the thumbnail image.
     /// - `angle`: The camera angle for the thumbnail.
     pub(crate) fn send_thumbnail(&self, offset: Vec2D<u32>, angle: CameraAngle) {
-        if !self.endpoint.is_console_connected() {
+        if !self.endpoint.is_console_connected() || !self.shedder.admit(OptionalTraffic::Thumbnail)
+        {
             return;
         }
         let endpoint_local = self.endpoint.clone();
@@ -170,7 +843,7 @@ impl ConsoleMessenger {
                 return;
             }
             if let Ok(encoded_image) =
-                camera_controller_local.export_thumbnail_png(offset, angle).await
+                camera_controller_local.export_thumbnail(offset, angle).await
             {
                 endpoint_local.send_downstream(melvin_messages::DownstreamContent::Image(
                     melvin_messages::Image::from_encoded_image_extract(encoded_image),
@@ -179,10 +852,75 @@ impl ConsoleMessenger {
         });
     }
 
+    /// Notifies the operator console about a batch of thumbnail updates with a single message.
+    ///
+    /// A single update is sent as its thumbnail area, several updates as one full thumbnail.
+    /// If the console is not connected or thumbnails are currently shed, this method does
+    /// nothing.
+    ///
+    /// # Arguments
+    /// - `updated`: The offsets and camera angles of the updated captures.
+    pub(crate) fn send_thumbnail_batch(&self, updated: &[(Vec2D<u32>, CameraAngle)]) {
+        match updated {
+            [] => {}
+            [(offset, angle)] => self.send_thumbnail(*offset, *angle),
+            _ => {
+                if !self.endpoint.is_console_connected()
+                    || !self.shedder.admit(OptionalTraffic::Thumbnail)
+                {
+                    return;
+                }
+                let endpoint_local = self.endpoint.clone();
+                let camera_controller_local = self.camera_controller.clone();
+                tokio::spawn(async move {
+                    if let Ok(encoded_image) =
+                        camera_controller_local.export_full_thumbnail().await
+                    {
+                        endpoint_local.send_downstream(melvin_messages::DownstreamContent::Image(
+                            melvin_messages::Image::from_encoded_image_extract(encoded_image),
+                        ));
+                    }
+                });
+            }
+        }
+    }
+
+    /// Replaces the mission timeline offered to the operator console.
+    ///
+    /// # Arguments
+    /// - `timeline`: The timeline of the latest scheduling pass.
+    pub(crate) async fn update_timeline(&self, timeline: MissionTimeline) {
+        *self.timeline.write().await = Some(timeline);
+    }
+
+    /// Sends the mission timeline as JSON and SVG to the console, or an alert if no schedule
+    /// was computed yet.
+    ///
+    /// # Arguments
+    /// - `endpoint`: The console endpoint.
+    /// - `latest`: The timeline of the latest scheduling pass.
+    fn send_timeline(endpoint: &ConsoleEndpoint, latest: Option<&MissionTimeline>) {
+        let Some(timeline) = latest else {
+            Self::send_alert_from_endpoint(endpoint, "No mission timeline yet.".to_string());
+            return;
+        };
+        endpoint.send_downstream(melvin_messages::DownstreamContent::MissionTimeline(
+            melvin_messages::MissionTimeline {
+                generated: timeline.generated().timestamp_millis(),
+                json: timeline.to_json(),
+                svg: timeline.to_svg(),
+            },
+        ));
+    }
+
     /// Sends the task list to the operator console.
     ///
-    /// If the console is not connected, this method does nothing.
+    /// If the console is not connected or task list updates are currently shed, this method
+    /// does nothing.
     pub(crate) async fn send_tasklist(&self) {
+        if !self.shedder.admit(OptionalTraffic::TaskList) {
+            return;
+        }
         ConsoleMessenger::send_tasklist_from_endpoint(&self.endpoint, &self.task_controller).await;
     }
 
@@ -203,6 +941,7 @@ impl ConsoleMessenger {
             .iter()
             .map(|task| melvin_messages::Task {
                 scheduled_on: task.t().timestamp_millis(),
+                pinned: task.is_pinned(),
                 task: Some(match task.task_type() {
                     BaseTask::TakeImage(take_image) => {
                         let actual_position = if let ImageTaskStatus::Done { actual_pos, .. } =
@@ -220,16 +959,9 @@ impl ConsoleMessenger {
                         })
                     }
                     BaseTask::SwitchState(state) => {
-                        melvin_messages::TaskType::SwitchState(match state.target_state() {
-                            FlightState::Charge => melvin_messages::SatelliteState::Charge,
-                            FlightState::Acquisition => {
-                                melvin_messages::SatelliteState::Acquisition
-                            }
-                            FlightState::Deployment => melvin_messages::SatelliteState::Deployment,
-                            FlightState::Transition => melvin_messages::SatelliteState::Transition,
-                            FlightState::Comms => melvin_messages::SatelliteState::Communication,
-                            FlightState::Safe => melvin_messages::SatelliteState::Safe,
-                        } as i32)
+                        melvin_messages::TaskType::SwitchState(
+                            Self::satellite_state(state.target_state()) as i32,
+                        )
                     }
                     BaseTask::ChangeVelocity(velocity_change_task) => {
                         melvin_messages::TaskType::VelocityChange(melvin_messages::BurnSequence {
@@ -0,0 +1,146 @@
+use crate::warn;
+use std::{
+    collections::BTreeMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// A critical downstream message awaiting its acknowledgment.
+struct PendingMessage {
+    /// The encoded message.
+    payload: Arc<Vec<u8>>,
+    /// The time the message was last sent.
+    sent: Instant,
+    /// The number of times the message was sent.
+    attempts: u32,
+}
+
+/// Tracks the delivery of downstream messages to consoles speaking protocol version `2`.
+///
+/// Every downstream message gets a message ID. Critical messages are kept until the console
+/// acknowledges them and are re-sent if no acknowledgment arrives in time or the console
+/// rejects them. Messages are given up after [`DeliveryTracker::MAX_ATTEMPTS`].
+#[derive(Default)]
+pub(crate) struct DeliveryTracker {
+    /// The ID of the last sent message.
+    last_id: u64,
+    /// The protocol version of the connected console, `0` if unknown.
+    peer_version: u32,
+    /// The unacknowledged critical messages by ID.
+    pending: BTreeMap<u64, PendingMessage>,
+}
+
+impl DeliveryTracker {
+    /// The time after which an unacknowledged message is re-sent.
+    pub(crate) const ACK_TIMEOUT: Duration = Duration::from_secs(5);
+    /// The maximum number of times a message is sent.
+    const MAX_ATTEMPTS: u32 = 5;
+    /// The maximum number of tracked messages, the oldest is dropped beyond.
+    const MAX_PENDING: usize = 64;
+
+    /// Returns a new, unique message ID.
+    pub(crate) fn next_id(&mut self) -> u64 {
+        self.last_id += 1;
+        self.last_id
+    }
+
+    /// Returns the protocol version of the connected console, `0` if unknown.
+    pub(crate) fn peer_version(&self) -> u32 { self.peer_version }
+
+    /// Updates the protocol version of the connected console.
+    ///
+    /// # Arguments
+    /// * `version` – The version announced by the console, `0` for a new connection.
+    pub(crate) fn set_peer_version(&mut self, version: u32) { self.peer_version = version; }
+
+    /// Starts tracking a sent critical message.
+    ///
+    /// # Arguments
+    /// * `id` – The message ID.
+    /// * `payload` – The encoded message.
+    /// * `now` – The time the message was sent.
+    pub(crate) fn track(&mut self, id: u64, payload: Arc<Vec<u8>>, now: Instant) {
+        if self.pending.len() >= Self::MAX_PENDING {
+            self.pending.pop_first();
+        }
+        self.pending.insert(id, PendingMessage { payload, sent: now, attempts: 1 });
+    }
+
+    /// Marks a message as delivered.
+    ///
+    /// # Arguments
+    /// * `id` – The acknowledged message ID.
+    pub(crate) fn ack(&mut self, id: u64) { self.pending.remove(&id); }
+
+    /// Handles a rejected message.
+    ///
+    /// # Arguments
+    /// * `id` – The rejected message ID.
+    /// * `now` – The current time.
+    ///
+    /// # Returns
+    /// The message to re-send immediately, or `None` if it is not tracked or given up.
+    pub(crate) fn nack(&mut self, id: u64, now: Instant) -> Option<Arc<Vec<u8>>> {
+        let msg = self.pending.get_mut(&id)?;
+        if msg.attempts >= Self::MAX_ATTEMPTS {
+            warn!("Giving up console message {id} after {} rejections.", msg.attempts);
+            self.pending.remove(&id);
+            return None;
+        }
+        msg.attempts += 1;
+        msg.sent = now;
+        Some(Arc::clone(&msg.payload))
+    }
+
+    /// Returns the messages whose acknowledgment timed out and marks them as re-sent.
+    ///
+    /// # Arguments
+    /// * `now` – The current time.
+    pub(crate) fn due(&mut self, now: Instant) -> Vec<Arc<Vec<u8>>> {
+        self.pending.retain(|id, msg| {
+            let keep = msg.attempts < Self::MAX_ATTEMPTS || now < msg.sent + Self::ACK_TIMEOUT;
+            if !keep {
+                warn!("Giving up unacknowledged console message {id}.");
+            }
+            keep
+        });
+        self.pending
+            .values_mut()
+            .filter(|msg| now >= msg.sent + Self::ACK_TIMEOUT)
+            .map(|msg| {
+                msg.attempts += 1;
+                msg.sent = now;
+                Arc::clone(&msg.payload)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resend_until_ack() {
+        let mut tracker = DeliveryTracker::default();
+        let start = Instant::now();
+        let (alert, report) = (tracker.next_id(), tracker.next_id());
+        tracker.track(alert, Arc::new(vec![1]), start);
+        tracker.track(report, Arc::new(vec![2]), start);
+        assert!(tracker.due(start).is_empty());
+
+        let timeout = start + DeliveryTracker::ACK_TIMEOUT;
+        assert_eq!(tracker.due(timeout).len(), 2);
+        tracker.ack(alert);
+        assert_eq!(tracker.nack(report, timeout).as_deref(), Some(&vec![2]));
+
+        let (mut now, mut resent) = (timeout + DeliveryTracker::ACK_TIMEOUT, 0);
+        while !tracker.due(now).is_empty() {
+            resent += 1;
+            now += DeliveryTracker::ACK_TIMEOUT;
+        }
+        // Sent initially, after the first timeout, after the rejection and until given up
+        assert_eq!(resent, 2);
+        assert!(tracker.nack(report, now).is_none());
+    }
+}
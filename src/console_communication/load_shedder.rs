@@ -0,0 +1,192 @@
+use crate::{info, warn};
+use std::{
+    sync::{
+        Mutex,
+        atomic::{AtomicU8, Ordering},
+    },
+    time::Duration,
+};
+use tokio::time::Instant;
+
+/// The degree to which optional console traffic is currently reduced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(super) enum ShedLevel {
+    /// All traffic is sent.
+    Nominal = 0,
+    /// Optional traffic is throttled.
+    Reduced = 1,
+    /// Thumbnails are dropped and the remaining optional traffic is throttled heavily.
+    Minimal = 2,
+    /// All optional traffic is dropped. Only entered on request, e.g. to save power.
+    Silent = 3,
+}
+
+impl ShedLevel {
+    /// Converts the stored representation back into a [`ShedLevel`].
+    fn from_u8(val: u8) -> Self {
+        match val {
+            0 => Self::Nominal,
+            1 => Self::Reduced,
+            2 => Self::Minimal,
+            _ => Self::Silent,
+        }
+    }
+}
+
+/// Console traffic that may be throttled or dropped under load.
+///
+/// Alerts, submission responses and answers to explicit console requests are never shed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum OptionalTraffic {
+    /// Thumbnails of freshly taken images.
+    Thumbnail = 0,
+    /// Unsolicited task list updates.
+    TaskList = 1,
+    /// Score dashboard updates.
+    ScoreDashboard = 2,
+}
+
+impl OptionalTraffic {
+    /// Returns the minimum interval between two messages at the given level.
+    ///
+    /// # Returns
+    /// * `None` if the traffic is dropped entirely at that level.
+    fn min_interval(self, level: ShedLevel) -> Option<Duration> {
+        match (self, level) {
+            (_, ShedLevel::Nominal) => Some(Duration::ZERO),
+            (Self::Thumbnail, ShedLevel::Reduced) => Some(Duration::from_secs(5)),
+            (Self::Thumbnail, ShedLevel::Minimal) | (_, ShedLevel::Silent) => None,
+            (_, ShedLevel::Reduced) => Some(Duration::from_secs(30)),
+            (_, ShedLevel::Minimal) => Some(Duration::from_secs(120)),
+        }
+    }
+}
+
+/// Load-shedding policy for optional console traffic.
+///
+/// Encoding thumbnails and task lists competes with acquisition, planning and exports for
+/// the async worker threads. The shedder periodically probes the scheduling latency of the
+/// runtime, i.e. how late a timer-driven task is woken, and reduces optional traffic while
+/// the smoothed latency stays above the configured thresholds. Levels are only lowered once
+/// the latency has dropped well below the threshold again to avoid flapping. A floor raises
+/// the level independently of the latency.
+#[derive(Debug)]
+pub(super) struct LoadShedder {
+    /// The [`ShedLevel`] derived from the measured latency.
+    level: AtomicU8,
+    /// The [`ShedLevel`] the current level never falls below.
+    floor: AtomicU8,
+    /// The time the last message of each [`OptionalTraffic`] kind was let through.
+    last_sent: Mutex<[Option<Instant>; 3]>,
+}
+
+impl LoadShedder {
+    /// Interval between two latency probes.
+    const PROBE_DT: Duration = Duration::from_millis(500);
+    /// Weight of the latest probe in the smoothed latency.
+    const EWMA_ALPHA: f64 = 0.3;
+    /// Smoothed latency in milliseconds above which traffic is reduced.
+    const REDUCED_LATENCY_MS: f64 = 25.0;
+    /// Smoothed latency in milliseconds above which traffic is minimized.
+    const MINIMAL_LATENCY_MS: f64 = 100.0;
+    /// Share of a threshold the latency has to fall below to lower the level again.
+    const RECOVERY_FACTOR: f64 = 0.5;
+
+    /// Creates a new [`LoadShedder`] letting all traffic through.
+    pub(super) fn new() -> Self {
+        Self {
+            level: AtomicU8::new(ShedLevel::Nominal as u8),
+            floor: AtomicU8::new(ShedLevel::Nominal as u8),
+            last_sent: Mutex::new([None; 3]),
+        }
+    }
+
+    /// Returns the current [`ShedLevel`].
+    pub(super) fn level(&self) -> ShedLevel { self.measured_level().max(self.floor()) }
+
+    /// Returns the [`ShedLevel`] derived from the measured latency.
+    fn measured_level(&self) -> ShedLevel {
+        ShedLevel::from_u8(self.level.load(Ordering::Relaxed))
+    }
+
+    /// Returns the [`ShedLevel`] the current level never falls below.
+    fn floor(&self) -> ShedLevel { ShedLevel::from_u8(self.floor.load(Ordering::Relaxed)) }
+
+    /// Sets the [`ShedLevel`] the current level never falls below.
+    ///
+    /// # Arguments
+    /// * `floor` – The new floor, [`ShedLevel::Nominal`] to follow the latency only.
+    pub(super) fn set_floor(&self, floor: ShedLevel) {
+        let prev = ShedLevel::from_u8(self.floor.swap(floor as u8, Ordering::Relaxed));
+        if prev != floor {
+            info!("Console load shedding floor set to {floor:?}.");
+        }
+    }
+
+    /// Decides whether a message of optional traffic may be sent now and records it if so.
+    ///
+    /// # Arguments
+    /// * `traffic` – The kind of the message.
+    ///
+    /// # Returns
+    /// * `true` if the message should be sent, `false` if it is shed.
+    pub(super) fn admit(&self, traffic: OptionalTraffic) -> bool {
+        let Some(min_dt) = traffic.min_interval(self.level()) else {
+            return false;
+        };
+        let now = Instant::now();
+        let mut last_sent = self.last_sent.lock().unwrap();
+        let last = &mut last_sent[traffic as usize];
+        if last.is_some_and(|t| now.duration_since(t) < min_dt) {
+            return false;
+        }
+        *last = Some(now);
+        true
+    }
+
+    /// Computes the level for a smoothed latency, applying hysteresis to the current level.
+    ///
+    /// # Arguments
+    /// * `current` – The current level.
+    /// * `latency_ms` – The smoothed scheduling latency in milliseconds.
+    fn next_level(current: ShedLevel, latency_ms: f64) -> ShedLevel {
+        let (reduced, minimal) = (Self::REDUCED_LATENCY_MS, Self::MINIMAL_LATENCY_MS);
+        if latency_ms > minimal {
+            ShedLevel::Minimal
+        } else if latency_ms > reduced {
+            let holds_minimal = latency_ms > minimal * Self::RECOVERY_FACTOR;
+            if current == ShedLevel::Minimal && holds_minimal {
+                ShedLevel::Minimal
+            } else {
+                ShedLevel::Reduced
+            }
+        } else if current == ShedLevel::Nominal || latency_ms < reduced * Self::RECOVERY_FACTOR {
+            ShedLevel::Nominal
+        } else {
+            ShedLevel::Reduced
+        }
+    }
+
+    /// Periodically probes the scheduling latency and updates the shed level.
+    pub(super) async fn run_monitor(&self) {
+        let mut smoothed_ms = 0.0;
+        loop {
+            let start = Instant::now();
+            tokio::time::sleep(Self::PROBE_DT).await;
+            let lag = start.elapsed().saturating_sub(Self::PROBE_DT);
+            let lag_ms = lag.as_secs_f64() * 1000.0;
+            smoothed_ms = Self::EWMA_ALPHA * lag_ms + (1.0 - Self::EWMA_ALPHA) * smoothed_ms;
+            let current = self.measured_level();
+            let next = Self::next_level(current, smoothed_ms);
+            if next == current {
+                continue;
+            }
+            self.level.store(next as u8, Ordering::Relaxed);
+            if next > current {
+                warn!("Console load shedding raised to {next:?} at {smoothed_ms:.1}ms latency.");
+            } else {
+                info!("Console load shedding lowered to {next:?} at {smoothed_ms:.1}ms latency.");
+            }
+        }
+    }
+}
@@ -1,9 +1,18 @@
-use crate::imaging::map_image::EncodedImageExtract;
+use crate::imaging::{ImageCodec, map_image::EncodedImageExtract};
+
+/// The protocol version spoken by MELVIN. Version `1` consoles send no version and ignore
+/// message IDs, so acknowledgments and payload compression are only used with version `2`.
+pub const PROTOCOL_VERSION: u32 = 2;
 
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Upstream {
-    #[prost(oneof = "UpstreamContent", tags = "1, 2, 3, 4, 5, 6, 7")]
+    #[prost(
+        oneof = "UpstreamContent",
+        tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25"
+    )]
     pub content: Option<UpstreamContent>,
+    #[prost(uint32, tag = "100")]
+    pub protocol_version: u32,
 }
 
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -13,8 +22,17 @@ pub struct Ping {
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Downstream {
-    #[prost(oneof = "DownstreamContent", tags = "1, 2, 3, 4, 5")]
+    #[prost(
+        oneof = "DownstreamContent",
+        tags = "1, 2, 3, 4, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20"
+    )]
     pub content: Option<DownstreamContent>,
+    #[prost(uint32, tag = "100")]
+    pub protocol_version: u32,
+    #[prost(uint64, tag = "101")]
+    pub message_id: u64,
+    #[prost(bool, tag = "102")]
+    pub requires_ack: bool,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Pong {
@@ -33,20 +51,66 @@ pub struct Image {
     pub offset_y: u32,
     #[prost(bytes = "vec", tag = "5")]
     pub data: Vec<u8>,
+    #[prost(enumeration = "ImageEncoding", tag = "6")]
+    pub encoding: i32,
+    #[prost(enumeration = "PayloadCompression", tag = "7")]
+    pub compression: i32,
 }
 
 impl Image {
     pub(crate) fn from_encoded_image_extract(encoded_image: EncodedImageExtract) -> Self {
+        let encoding = match encoded_image.codec {
+            ImageCodec::Png => ImageEncoding::Png,
+            ImageCodec::Qoi => ImageEncoding::Qoi,
+            ImageCodec::Lz4 => ImageEncoding::Lz4,
+            ImageCodec::Jpeg => ImageEncoding::Jpeg,
+            ImageCodec::WebpLossless | ImageCodec::WebpLossy => ImageEncoding::Webp,
+        };
         Self {
             height: encoded_image.size.y(),
             width: encoded_image.size.x(),
             offset_x: encoded_image.offset.x(),
             offset_y: encoded_image.offset.y(),
             data: encoded_image.data,
+            encoding: encoding as i32,
+            compression: PayloadCompression::None as i32,
+        }
+    }
+
+    /// Compresses the image data with zstd if this shrinks it.
+    ///
+    /// # Arguments
+    /// * `level` - The zstd compression level.
+    pub(crate) fn compress(&mut self, level: i32) {
+        if self.compression != PayloadCompression::None as i32 {
+            return;
+        }
+        if let Ok(compressed) = zstd::bulk::compress(&self.data, level) {
+            if compressed.len() < self.data.len() {
+                self.data = compressed;
+                self.compression = PayloadCompression::Zstd as i32;
+            }
         }
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub enum PayloadCompression {
+    None = 0,
+    Zstd = 1,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub enum ImageEncoding {
+    Png = 0,
+    Qoi = 1,
+    Lz4 = 2,
+    Jpeg = 3,
+    Webp = 4,
+}
+
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Telemetry {
     #[prost(int64, tag = "1")]
@@ -81,6 +145,267 @@ pub struct SubmitResponse {
     pub objective_id: Option<u32>,
 }
 
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ScoreDashboard {
+    #[prost(double, tag = "1")]
+    pub total_points: f64,
+    #[prost(message, repeated, tag = "2")]
+    pub entries: Vec<ScoreDashboardEntry>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ScoreDashboardEntry {
+    #[prost(int64, tag = "1")]
+    pub timestamp: i64,
+    #[prost(string, tag = "2")]
+    pub source: String,
+    #[prost(double, tag = "3")]
+    pub points: f64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Alert {
+    #[prost(int64, tag = "1")]
+    pub timestamp: i64,
+    #[prost(string, tag = "2")]
+    pub message: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct LogMessage {
+    #[prost(int64, tag = "1")]
+    pub timestamp: i64,
+    #[prost(string, tag = "2")]
+    pub level: String,
+    #[prost(string, tag = "3")]
+    pub message: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MissionTimeline {
+    #[prost(int64, tag = "1")]
+    pub generated: i64,
+    #[prost(string, tag = "2")]
+    pub json: String,
+    #[prost(string, tag = "3")]
+    pub svg: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct CommandResponse {
+    #[prost(string, tag = "1")]
+    pub command: String,
+    #[prost(bool, tag = "2")]
+    pub accepted: bool,
+    #[prost(string, optional, tag = "3")]
+    pub reason: Option<String>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ConfigReport {
+    #[prost(message, repeated, tag = "1")]
+    pub entries: Vec<ConfigReportEntry>,
+    #[prost(string, optional, tag = "2")]
+    pub error: Option<String>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ConfigReportEntry {
+    #[prost(string, tag = "1")]
+    pub key: String,
+    #[prost(string, tag = "2")]
+    pub value: String,
+    #[prost(string, tag = "3")]
+    pub source: String,
+    #[prost(bool, tag = "4")]
+    pub overridable: bool,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReplaySession {
+    #[prost(string, tag = "1")]
+    pub day: String,
+    #[prost(message, repeated, tag = "2")]
+    pub points: Vec<ReplayPoint>,
+    #[prost(message, repeated, tag = "3")]
+    pub frames: Vec<ReplayFrame>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReplayPoint {
+    #[prost(int64, tag = "1")]
+    pub timestamp: i64,
+    #[prost(enumeration = "SatelliteState", tag = "2")]
+    pub state: i32,
+    #[prost(int32, tag = "3")]
+    pub position_x: i32,
+    #[prost(int32, tag = "4")]
+    pub position_y: i32,
+    #[prost(float, tag = "5")]
+    pub battery: f32,
+    #[prost(float, tag = "6")]
+    pub fuel: f32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReplayFrame {
+    #[prost(int64, tag = "1")]
+    pub timestamp: i64,
+    #[prost(message, optional, tag = "2")]
+    pub image: Option<Image>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BeaconHeatmap {
+    #[prost(uint32, tag = "1")]
+    pub objective_id: u32,
+    #[prost(uint32, tag = "2")]
+    pub offset_x: u32,
+    #[prost(uint32, tag = "3")]
+    pub offset_y: u32,
+    #[prost(uint32, tag = "4")]
+    pub width: u32,
+    #[prost(uint32, tag = "5")]
+    pub height: u32,
+    #[prost(uint32, tag = "6")]
+    pub region_size: u32,
+    #[prost(uint32, tag = "7")]
+    pub guess_estimate: u32,
+    #[prost(bytes = "vec", tag = "8")]
+    pub data: Vec<u8>,
+    #[prost(message, optional, tag = "9")]
+    pub posterior: Option<BeaconPosterior>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OrbitHeatmap {
+    #[prost(uint32, tag = "1")]
+    pub width: u32,
+    #[prost(uint32, tag = "2")]
+    pub height: u32,
+    #[prost(uint32, tag = "3")]
+    pub bucket_len: u32,
+    #[prost(float, repeated, tag = "4")]
+    pub bucket_coverage: Vec<f32>,
+    #[prost(bytes = "vec", tag = "5")]
+    pub data: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EventLog {
+    #[prost(string, repeated, tag = "1")]
+    pub entries: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ModeGraph {
+    #[prost(enumeration = "ModeGraphFormat", tag = "1")]
+    pub format: i32,
+    #[prost(string, tag = "2")]
+    pub data: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub enum ModeGraphFormat {
+    Dot = 0,
+    Json = 1,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ObjectiveLifecycles {
+    #[prost(message, repeated, tag = "1")]
+    pub lifecycles: Vec<ObjectiveLifecycle>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ObjectiveLifecycle {
+    #[prost(string, tag = "1")]
+    pub kind: String,
+    #[prost(uint32, tag = "2")]
+    pub objective_id: u32,
+    #[prost(message, repeated, tag = "3")]
+    pub transitions: Vec<ObjectiveTransition>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ObjectiveTransition {
+    #[prost(int64, tag = "1")]
+    pub timestamp: i64,
+    #[prost(string, tag = "2")]
+    pub stage: String,
+    #[prost(string, optional, tag = "3")]
+    pub reason: Option<String>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MapTile {
+    #[prost(uint32, tag = "1")]
+    pub zoom: u32,
+    #[prost(uint32, tag = "2")]
+    pub x: u32,
+    #[prost(uint32, tag = "3")]
+    pub y: u32,
+    #[prost(uint32, tag = "4")]
+    pub grid_x: u32,
+    #[prost(uint32, tag = "5")]
+    pub grid_y: u32,
+    #[prost(message, optional, tag = "6")]
+    pub image: Option<Image>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CoverageForecast {
+    #[prost(uint32, tag = "1")]
+    pub orbit_secs: u32,
+    #[prost(uint32, tag = "2")]
+    pub remaining_secs: u32,
+    #[prost(int64, optional, tag = "3")]
+    pub completion: Option<i64>,
+    #[prost(message, repeated, tag = "4")]
+    pub windows: Vec<CoverageWindow>,
+}
+
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct CoverageWindow {
+    #[prost(int64, tag = "1")]
+    pub start: i64,
+    #[prost(int64, tag = "2")]
+    pub end: i64,
+    #[prost(uint32, tag = "3")]
+    pub new_secs: u32,
+    #[prost(float, tag = "4")]
+    pub gain: f32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BeaconPosterior {
+    #[prost(uint32, tag = "1")]
+    pub centroid_x: u32,
+    #[prost(uint32, tag = "2")]
+    pub centroid_y: u32,
+    #[prost(double, tag = "3")]
+    pub cov_xx: f64,
+    #[prost(double, tag = "4")]
+    pub cov_xy: f64,
+    #[prost(double, tag = "5")]
+    pub cov_yy: f64,
+    #[prost(double, tag = "6")]
+    pub error_radius: f64,
+    #[prost(message, repeated, tag = "7")]
+    pub candidates: Vec<BeaconCandidate>,
+}
+
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct BeaconCandidate {
+    #[prost(uint32, tag = "1")]
+    pub x: u32,
+    #[prost(uint32, tag = "2")]
+    pub y: u32,
+    #[prost(double, tag = "3")]
+    pub share: f64,
+}
+
 #[derive(Clone, PartialEq, prost::Oneof)]
 pub enum DownstreamContent {
     #[prost(message, tag = "1")]
@@ -93,6 +418,69 @@ pub enum DownstreamContent {
     SubmitResponse(SubmitResponse),
     #[prost(message, tag = "6")]
     TaskList(TaskList),
+    #[prost(message, tag = "7")]
+    ScoreDashboard(ScoreDashboard),
+    #[prost(message, tag = "8")]
+    ConfigReport(ConfigReport),
+    #[prost(message, tag = "9")]
+    Alert(Alert),
+    #[prost(message, tag = "10")]
+    ReplaySession(ReplaySession),
+    #[prost(message, tag = "11")]
+    BeaconHeatmap(BeaconHeatmap),
+    #[prost(message, tag = "12")]
+    EventLog(EventLog),
+    #[prost(message, tag = "13")]
+    OrbitHeatmap(OrbitHeatmap),
+    #[prost(message, tag = "14")]
+    ModeGraph(ModeGraph),
+    #[prost(message, tag = "15")]
+    ObjectiveLifecycles(ObjectiveLifecycles),
+    #[prost(message, tag = "16")]
+    MapTile(MapTile),
+    #[prost(message, tag = "17")]
+    CoverageForecast(CoverageForecast),
+    #[prost(message, tag = "18")]
+    CommandResponse(CommandResponse),
+    #[prost(message, tag = "19")]
+    LogMessage(LogMessage),
+    #[prost(message, tag = "20")]
+    MissionTimeline(MissionTimeline),
+}
+
+impl DownstreamContent {
+    /// Returns `true` if the message must be acknowledged by the console and is re-sent
+    /// until it is.
+    pub(crate) fn is_critical(&self) -> bool {
+        matches!(
+            self,
+            Self::SubmitResponse(_)
+                | Self::Alert(_)
+                | Self::ConfigReport(_)
+                | Self::CommandResponse(_)
+        )
+    }
+
+    /// Compresses the image payloads of the message.
+    ///
+    /// # Arguments
+    /// * `level` - The zstd compression level.
+    pub(crate) fn compress_payloads(&mut self, level: i32) {
+        match self {
+            Self::Image(image) => image.compress(level),
+            Self::MapTile(tile) => {
+                if let Some(image) = tile.image.as_mut() {
+                    image.compress(level);
+                }
+            }
+            Self::ReplaySession(session) => {
+                session.frames.iter_mut().filter_map(|f| f.image.as_mut()).for_each(|image| {
+                    image.compress(level);
+                });
+            }
+            _ => {}
+        }
+    }
 }
 
 #[derive(Clone, PartialEq, prost::Oneof)]
@@ -111,10 +499,128 @@ pub enum UpstreamContent {
     SubmitDailyMap(SubmitDailyMap),
     #[prost(message, tag = "7")]
     ScheduleSecretObjective(ObjectiveArea),
+    #[prost(message, tag = "8")]
+    GetConfig(GetConfig),
+    #[prost(message, tag = "9")]
+    SetConfig(SetConfig),
+    #[prost(message, tag = "10")]
+    RevertConfig(RevertConfig),
+    #[prost(message, tag = "11")]
+    EndMission(EndMission),
+    #[prost(message, tag = "12")]
+    GetReplaySession(GetReplaySession),
+    #[prost(message, tag = "13")]
+    GetBeaconHeatmaps(GetBeaconHeatmaps),
+    #[prost(message, tag = "14")]
+    GetEventLog(GetEventLog),
+    #[prost(message, tag = "15")]
+    GetOrbitHeatmap(GetOrbitHeatmap),
+    #[prost(message, tag = "16")]
+    Ack(Ack),
+    #[prost(message, tag = "17")]
+    Nack(Nack),
+    #[prost(message, tag = "18")]
+    PinTask(PinTask),
+    #[prost(message, tag = "19")]
+    CancelPinnedTasks(CancelPinnedTasks),
+    #[prost(message, tag = "20")]
+    GetModeGraph(GetModeGraph),
+    #[prost(message, tag = "21")]
+    GetObjectiveLifecycles(GetObjectiveLifecycles),
+    #[prost(message, tag = "22")]
+    GetMapTile(GetMapTile),
+    #[prost(message, tag = "23")]
+    GetCoverageForecast(GetCoverageForecast),
+    #[prost(message, tag = "24")]
+    ManualCommand(ManualCommand),
+    #[prost(message, tag = "25")]
+    GetMissionTimeline(GetMissionTimeline),
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct Ack {
+    #[prost(uint64, tag = "1")]
+    pub message_id: u64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Nack {
+    #[prost(uint64, tag = "1")]
+    pub message_id: u64,
+    #[prost(string, tag = "2")]
+    pub reason: String,
 }
 #[derive(Clone, Copy, PartialEq, prost::Message)]
 pub struct GetFullImage {}
 
+/// An operator task the scheduler keeps and plans around.
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct PinTask {
+    #[prost(int64, tag = "1")]
+    pub scheduled_on: i64,
+    #[prost(oneof = "PinTaskType", tags = "2, 3")]
+    pub task: Option<PinTaskType>,
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Oneof)]
+pub enum PinTaskType {
+    #[prost(enumeration = "SatelliteState", tag = "2")]
+    SwitchState(i32),
+    #[prost(message, tag = "3")]
+    TakeImage(PinImage),
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct PinImage {
+    #[prost(uint32, tag = "1")]
+    pub position_x: u32,
+    #[prost(uint32, tag = "2")]
+    pub position_y: u32,
+    #[prost(enumeration = "Lens", tag = "3")]
+    pub lens: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub enum Lens {
+    Narrow = 0,
+    Normal = 1,
+    Wide = 2,
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct CancelPinnedTasks {}
+
+/// A flight command issued manually by an operator.
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct ManualCommand {
+    #[prost(oneof = "ManualCommandType", tags = "1, 2, 3, 4")]
+    pub command: Option<ManualCommandType>,
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Oneof)]
+pub enum ManualCommandType {
+    #[prost(enumeration = "SatelliteState", tag = "1")]
+    SetState(i32),
+    #[prost(message, tag = "2")]
+    SetVelocity(SetVelocity),
+    #[prost(enumeration = "Lens", tag = "3")]
+    SetLens(i32),
+    #[prost(message, tag = "4")]
+    AbortMode(AbortMode),
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct SetVelocity {
+    #[prost(float, tag = "1")]
+    pub velocity_x: f32,
+    #[prost(float, tag = "2")]
+    pub velocity_y: f32,
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct AbortMode {}
+
 #[derive(Clone, PartialEq, prost::Message)]
 pub struct ObjectiveArea {
     #[prost(uint32, tag = "1")]
@@ -138,6 +644,69 @@ pub struct GetSnapshotDiffImage {}
 #[derive(Clone, PartialEq, prost::Message)]
 pub struct CreateSnapshotImage {}
 
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct GetConfig {}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SetConfig {
+    #[prost(string, tag = "1")]
+    pub key: String,
+    #[prost(string, tag = "2")]
+    pub value: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct RevertConfig {}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct EndMission {}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct GetBeaconHeatmaps {}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct GetOrbitHeatmap {
+    #[prost(uint32, tag = "1")]
+    pub buckets: u32,
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct GetEventLog {
+    #[prost(uint32, tag = "1")]
+    pub count: u32,
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct GetModeGraph {
+    #[prost(enumeration = "ModeGraphFormat", tag = "1")]
+    pub format: i32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct GetObjectiveLifecycles {}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct GetMapTile {
+    #[prost(uint32, tag = "1")]
+    pub zoom: u32,
+    #[prost(uint32, tag = "2")]
+    pub x: u32,
+    #[prost(uint32, tag = "3")]
+    pub y: u32,
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct GetCoverageForecast {}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct GetMissionTimeline {}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct GetReplaySession {
+    #[prost(string, optional, tag = "1")]
+    pub day: Option<String>,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
 #[repr(i32)]
 pub enum SatelliteState {
@@ -187,11 +756,14 @@ pub struct TaskList {
 }
 
 #[derive(Clone, PartialEq, prost::Message)]
+#[allow(clippy::struct_field_names)]
 pub struct Task {
     #[prost(int64, tag = "1")]
     pub scheduled_on: i64,
     #[prost(oneof = "TaskType", tags = "2,3,4")]
     pub task: Option<TaskType>,
+    #[prost(bool, tag = "5")]
+    pub pinned: bool,
 }
 
 #[derive(Clone, PartialEq, prost::Oneof)]
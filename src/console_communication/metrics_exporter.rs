@@ -0,0 +1,125 @@
+use super::telemetry_endpoint::TelemetrySnapshot;
+use crate::util::MetricsSnapshot;
+use std::fmt::{Display, Write};
+
+/// The content type of the Prometheus text exposition format.
+pub(crate) const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Renders MELVINs metrics in the Prometheus text exposition format, served as `/metrics` by
+/// the [`TelemetryEndpoint`](super::TelemetryEndpoint).
+///
+/// Gauges are taken from the current [`TelemetrySnapshot`], counters from the
+/// [`MissionMetrics`](crate::util::MISSION_METRICS) and the worker pools.
+pub(crate) struct MetricsExporter {
+    /// The rendered exposition.
+    out: String,
+}
+
+impl MetricsExporter {
+    /// Renders all metrics.
+    ///
+    /// # Arguments
+    /// * `telemetry` – The current telemetry.
+    /// * `counters` – The current mission counters.
+    /// * `pools` – The name and cumulative running time in seconds of each worker pool.
+    ///
+    /// # Returns
+    /// * The exposition as a `String`.
+    pub(crate) fn render(
+        telemetry: &TelemetrySnapshot,
+        counters: &MetricsSnapshot,
+        pools: &[(&str, f64)],
+    ) -> String {
+        let mut exp = Self { out: String::new() };
+        exp.family("melvin_battery", "gauge", "Current battery level.");
+        exp.sample("melvin_battery", &[], telemetry.battery);
+        exp.family("melvin_fuel", "gauge", "Remaining fuel.");
+        exp.sample("melvin_fuel", &[], telemetry.fuel);
+        exp.family("melvin_task_queue_depth", "gauge", "Number of scheduled tasks.");
+        exp.sample("melvin_task_queue_depth", &[], telemetry.task_queue_len);
+        exp.family("melvin_orbit_coverage_percent", "gauge", "Imaged share of the closed orbit.");
+        exp.sample("melvin_orbit_coverage_percent", &[], telemetry.orbit_coverage);
+        exp.family("melvin_flight_state", "gauge", "Current flight state, labelled.");
+        exp.sample("melvin_flight_state", &[("state", &telemetry.state.to_string())], 1);
+        exp.family("melvin_mode", "gauge", "Active global mode, labelled.");
+        exp.sample("melvin_mode", &[("mode", telemetry.mode)], 1);
+
+        exp.family("melvin_pictures_taken_total", "counter", "Images received and decoded.");
+        exp.sample("melvin_pictures_taken_total", &[], counters.pictures_taken);
+        exp.family("melvin_picture_failures_total", "counter", "Failed image acquisitions.");
+        exp.sample("melvin_picture_failures_total", &[], counters.picture_failures);
+        exp.family("melvin_http_errors_total", "counter", "Failed DRS requests by kind.");
+        for (kind, count) in [
+            ("transient", counters.http_transient_errors),
+            ("other", counters.http_other_errors),
+            ("rejected", counters.http_rejected),
+        ] {
+            exp.sample("melvin_http_errors_total", &[("kind", kind)], count);
+        }
+        exp.family("melvin_mode_switches_total", "counter", "Global mode switches.");
+        exp.sample("melvin_mode_switches_total", &[], counters.mode_switches);
+        exp.family(
+            "melvin_pool_busy_seconds_total",
+            "counter",
+            "Running time of worker pool jobs, the planning pool holds the scheduling time.",
+        );
+        for (name, busy_secs) in pools {
+            exp.sample("melvin_pool_busy_seconds_total", &[("pool", name)], busy_secs);
+        }
+        exp.out
+    }
+
+    /// Writes the `HELP` and `TYPE` lines of a metric family.
+    fn family(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.out, "# HELP {name} {help}\n# TYPE {name} {kind}");
+    }
+
+    /// Writes a single sample, escaping the label values.
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: impl Display) {
+        self.out.push_str(name);
+        if !labels.is_empty() {
+            let rendered: Vec<_> = labels
+                .iter()
+                .map(|(k, v)| {
+                    let escaped = v.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+                    format!("{k}=\"{escaped}\"")
+                })
+                .collect();
+            let _ = write!(self.out, "{{{}}}", rendered.join(","));
+        }
+        let _ = writeln!(self.out, " {value}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flight_control::FlightState;
+    use chrono::Utc;
+
+    #[test]
+    fn test_render_metrics() {
+        let telemetry = TelemetrySnapshot {
+            observed_at: Utc::now(),
+            pos: [0.0, 0.0],
+            vel: [4.0, 7.0],
+            state: FlightState::Acquisition,
+            battery: 87.5,
+            fuel: 99.0,
+            mode: "InOrbitMode",
+            task_queue_len: 12,
+            orbit_coverage: 42.0,
+        };
+        let counters =
+            MetricsSnapshot { pictures_taken: 31, http_rejected: 2, ..Default::default() };
+        let out = MetricsExporter::render(&telemetry, &counters, &[("planning", 1.5)]);
+        assert!(out.contains("# TYPE melvin_battery gauge\nmelvin_battery 87.5\n"));
+        assert!(out.contains("melvin_task_queue_depth 12\n"));
+        assert!(out.contains("melvin_flight_state{state=\"Acquisition\"} 1\n"));
+        assert!(out.contains("melvin_mode{mode=\"InOrbitMode\"} 1\n"));
+        assert!(out.contains("melvin_pictures_taken_total 31\n"));
+        assert!(out.contains("melvin_http_errors_total{kind=\"rejected\"} 2\n"));
+        assert!(out.contains("melvin_pool_busy_seconds_total{pool=\"planning\"} 1.5\n"));
+        assert!(out.lines().all(|l| l.starts_with("# ") || l.split(' ').count() == 2));
+    }
+}
@@ -1,10 +1,19 @@
 //! This module provides the main components for handling communication with the console.
 //! It includes the `console_endpoint` module for managing console endpoints,
 //! the `console_messenger` module for messaging functionality,
-//! and the `melvin_messages` module for defining message structures and protocols.
+//! the `delivery_tracker` module re-sending unacknowledged critical messages,
+//! the `load_shedder` module for reducing optional traffic under load,
+//! the `melvin_messages` module for defining message structures and protocols,
+//! the `metrics_exporter` module rendering Prometheus metrics,
+//! and the `telemetry_endpoint` module serving JSON telemetry and metrics to dashboards over HTTP.
 
 mod console_endpoint;
 mod console_messenger;
+mod delivery_tracker;
+mod load_shedder;
 mod melvin_messages;
+mod metrics_exporter;
+mod telemetry_endpoint;
 
 pub use console_messenger::ConsoleMessenger;
+pub(crate) use telemetry_endpoint::{TelemetryEndpoint, TelemetrySnapshot, TelemetrySource};
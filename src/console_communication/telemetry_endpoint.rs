@@ -0,0 +1,190 @@
+use super::metrics_exporter::{METRICS_CONTENT_TYPE, MetricsExporter};
+use crate::flight_control::FlightState;
+use crate::util::{IMAGING_POOL, MISSION_METRICS, MissionConfig, PLANNING_POOL};
+use crate::{info, warn};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::oneshot,
+};
+
+/// The maximum accepted size of a request head in bytes
+const MAX_REQUEST_LEN: usize = 4096;
+
+/// A point-in-time view on MELVINs state as served by the [`TelemetryEndpoint`].
+#[derive(Debug, Clone, serde::Serialize)]
+pub(crate) struct TelemetrySnapshot {
+    /// The time of the last applied observation.
+    pub(crate) observed_at: DateTime<Utc>,
+    /// The current position in map coordinates.
+    pub(crate) pos: [f64; 2],
+    /// The current velocity.
+    pub(crate) vel: [f64; 2],
+    /// The current flight state.
+    pub(crate) state: FlightState,
+    /// The current battery level.
+    pub(crate) battery: f64,
+    /// The remaining fuel.
+    pub(crate) fuel: f64,
+    /// The name of the active global mode.
+    pub(crate) mode: &'static str,
+    /// The number of scheduled tasks.
+    pub(crate) task_queue_len: usize,
+    /// The imaged share of the closed orbit in percent.
+    pub(crate) orbit_coverage: f64,
+}
+
+/// A provider of [`TelemetrySnapshot`]s.
+#[async_trait]
+pub(crate) trait TelemetrySource: Send + Sync {
+    /// Collects the current telemetry.
+    async fn snapshot(&self) -> TelemetrySnapshot;
+}
+
+/// The `TelemetryEndpoint` serves MELVINs telemetry as JSON over plain HTTP, so dashboards can
+/// poll it without speaking the console protocol.
+///
+/// `GET /telemetry` returns the current [`TelemetrySnapshot`], `GET /metrics` the telemetry and
+/// mission counters in the Prometheus text exposition format. Every other path is answered
+/// with `404` and every other method with `405`.
+pub(crate) struct TelemetryEndpoint {
+    /// A channel sender to trigger endpoint shutdown.
+    close_oneshot: Option<oneshot::Sender<()>>,
+}
+
+impl TelemetryEndpoint {
+    /// Starts the `TelemetryEndpoint` on the port given by `network.telemetry_port` of the
+    /// [`MissionConfig`].
+    ///
+    /// # Arguments
+    /// - `source`: The provider of the served telemetry.
+    ///
+    /// # Returns
+    /// An instance of `TelemetryEndpoint`, dropping it stops the listener.
+    pub(crate) fn start(source: Arc<dyn TelemetrySource>) -> Self {
+        let port = MissionConfig::get().network.telemetry_port;
+        let (close_oneshot_sender, mut close_oneshot_receiver) = oneshot::channel();
+        tokio::spawn(async move {
+            let listener = match TcpListener::bind(("0.0.0.0", port)).await {
+                Ok(listener) => listener,
+                Err(e) => {
+                    warn!("Telemetry Endpoint unavailable, binding port {port} failed: {e}.");
+                    return;
+                }
+            };
+            info!("Started Telemetry Endpoint on port {port}");
+            loop {
+                let accept = tokio::select! {
+                    accept = listener.accept() => accept,
+                    _ = &mut close_oneshot_receiver => break
+                };
+                let Ok((socket, _)) = accept else { break };
+                let source_local = Arc::clone(&source);
+                tokio::spawn(async move {
+                    if let Err(e) = Self::handle_connection(socket, source_local.as_ref()).await {
+                        warn!("Telemetry request failed: {e}");
+                    }
+                });
+            }
+        });
+        Self { close_oneshot: Some(close_oneshot_sender) }
+    }
+
+    /// Answers a single HTTP request and closes the connection.
+    ///
+    /// # Arguments
+    /// - `socket`: The connection to the client.
+    /// - `source`: The provider of the served telemetry.
+    ///
+    /// # Errors
+    /// Returns I/O errors if issues arise when reading or writing the socket.
+    async fn handle_connection(
+        mut socket: TcpStream,
+        source: &dyn TelemetrySource,
+    ) -> Result<(), std::io::Error> {
+        let mut head = Vec::new();
+        let mut buffer = [0u8; 512];
+        while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+            let n = socket.read(&mut buffer).await?;
+            if n == 0 {
+                return Ok(());
+            }
+            head.extend_from_slice(&buffer[..n]);
+            if head.len() > MAX_REQUEST_LEN {
+                return Self::respond(&mut socket, "413 Payload Too Large", "{}").await;
+            }
+        }
+        let head_str = String::from_utf8_lossy(&head);
+        let mut request_line = head_str.lines().next().unwrap_or_default().split_whitespace();
+        let (method, path) = (request_line.next(), request_line.next());
+        match (method, path.map(|p| p.split('?').next().unwrap_or(p))) {
+            (Some("GET"), Some("/telemetry")) => {
+                let body = serde_json::to_string(&source.snapshot().await)
+                    .map_err(std::io::Error::other)?;
+                Self::respond(&mut socket, "200 OK", &body).await
+            }
+            (Some("GET"), Some("/metrics")) => {
+                let pools = [&*PLANNING_POOL, &*IMAGING_POOL].map(|p| (p.name(), p.busy_secs()));
+                let counters = MISSION_METRICS.snapshot();
+                let body = MetricsExporter::render(&source.snapshot().await, &counters, &pools);
+                Self::respond_as(&mut socket, "200 OK", METRICS_CONTENT_TYPE, &body).await
+            }
+            (Some("GET"), _) => Self::respond(&mut socket, "404 Not Found", "{}").await,
+            _ => Self::respond(&mut socket, "405 Method Not Allowed", "{}").await,
+        }
+    }
+
+    /// Writes a JSON response and shuts the connection down.
+    ///
+    /// # Arguments
+    /// - `socket`: The connection to the client.
+    /// - `status`: The HTTP status line suffix, e.g. `200 OK`.
+    /// - `body`: The JSON body.
+    ///
+    /// # Errors
+    /// Returns I/O errors if issues arise when writing the socket.
+    async fn respond(
+        socket: &mut TcpStream,
+        status: &str,
+        body: &str,
+    ) -> Result<(), std::io::Error> {
+        Self::respond_as(socket, status, "application/json", body).await
+    }
+
+    /// Writes a response with the given content type and shuts the connection down.
+    ///
+    /// # Arguments
+    /// - `socket`: The connection to the client.
+    /// - `status`: The HTTP status line suffix, e.g. `200 OK`.
+    /// - `content_type`: The content type of the body.
+    /// - `body`: The body.
+    ///
+    /// # Errors
+    /// Returns I/O errors if issues arise when writing the socket.
+    async fn respond_as(
+        socket: &mut TcpStream,
+        status: &str,
+        content_type: &str,
+        body: &str,
+    ) -> Result<(), std::io::Error> {
+        let response = format!(
+            "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\n\
+             Access-Control-Allow-Origin: *\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        socket.write_all(response.as_bytes()).await?;
+        socket.shutdown().await
+    }
+}
+
+impl Drop for TelemetryEndpoint {
+    /// Signals the listener to stop accepting connections.
+    fn drop(&mut self) {
+        if let Some(close) = self.close_oneshot.take() {
+            let _ = close.send(());
+        }
+    }
+}
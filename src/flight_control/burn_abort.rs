@@ -0,0 +1,154 @@
+use super::{EnvelopeViolation, orbit::BurnSequence};
+use crate::util::{JournalEvent, MISSION_JOURNAL, Vec2D};
+use crate::warn;
+use chrono::{DateTime, Utc};
+use fixed::types::I32F32;
+
+/// Record of a burn sequence that was aborted before its last velocity change.
+///
+/// Returned by [`FlightComputer::execute_burn`](super::FlightComputer::execute_burn) once the
+/// velocity was ramped back or a velocity change was rejected by the
+/// [`SafetyEnvelope`](super::safety_envelope::SafetyEnvelope), so that the executing mode can
+/// re-plan from the new state.
+#[derive(Debug, Clone)]
+pub struct BurnAborted {
+    /// The time the burn sequence started.
+    started: DateTime<Utc>,
+    /// The number of velocity changes completed before the abort.
+    steps_done: usize,
+    /// The number of velocity changes in the burn sequence.
+    steps_total: usize,
+    /// The position at the time of the abort.
+    pos: Vec2D<I32F32>,
+    /// The velocity at the time of the abort.
+    vel: Vec2D<I32F32>,
+    /// The velocity that was restored after the abort.
+    rollback_vel: Vec2D<I32F32>,
+    /// The violation of the safety envelope that stopped the burn, if any.
+    rejection: Option<EnvelopeViolation>,
+}
+
+impl BurnAborted {
+    /// Creates a new [`BurnAborted`] record, logs it and records it in the mission journal.
+    ///
+    /// # Arguments
+    /// * `started` – The time the burn sequence started.
+    /// * `steps` – The number of completed and of total velocity changes.
+    /// * `pos` – The position at the time of the abort.
+    /// * `vel` – The velocity at the time of the abort.
+    /// * `rollback_vel` – The velocity that was restored.
+    pub(super) fn record(
+        started: DateTime<Utc>,
+        steps: (usize, usize),
+        pos: Vec2D<I32F32>,
+        vel: Vec2D<I32F32>,
+        rollback_vel: Vec2D<I32F32>,
+    ) -> Self {
+        let (steps_done, steps_total) = steps;
+        let dt = (Utc::now() - started).num_seconds();
+        warn!(
+            "Burn sequence aborted after {steps_done}/{steps_total} steps and {dt}s at {pos}. \
+             Velocity {vel:.2} was ramped back to {rollback_vel:.2}."
+        );
+        MISSION_JOURNAL.record(JournalEvent::BurnAborted {
+            steps_done,
+            steps_total,
+            pos,
+            vel,
+            rollback_vel,
+        });
+        Self { started, steps_done, steps_total, pos, vel, rollback_vel, rejection: None }
+    }
+
+    /// Creates a new [`BurnAborted`] record for a burn stopped by the safety envelope, logs it
+    /// and records it in the mission journal. The velocity is held, not ramped back.
+    ///
+    /// # Arguments
+    /// * `started` – The time the burn sequence started.
+    /// * `steps` – The number of completed and of total velocity changes.
+    /// * `pos` – The position at the time of the rejection.
+    /// * `vel` – The velocity at the time of the rejection.
+    /// * `violation` – The violated limit.
+    pub(super) fn rejected(
+        started: DateTime<Utc>,
+        steps: (usize, usize),
+        pos: Vec2D<I32F32>,
+        vel: Vec2D<I32F32>,
+        violation: EnvelopeViolation,
+    ) -> Self {
+        let (steps_done, steps_total) = steps;
+        warn!(
+            "Burn sequence rejected after {steps_done}/{steps_total} steps at {pos}: {violation}. \
+             Holding velocity {vel:.2}."
+        );
+        MISSION_JOURNAL.record(JournalEvent::BurnAborted {
+            steps_done,
+            steps_total,
+            pos,
+            vel,
+            rollback_vel: vel,
+        });
+        let rejection = Some(violation);
+        Self { started, steps_done, steps_total, pos, vel, rollback_vel: vel, rejection }
+    }
+
+    /// Returns the velocities an aborted burn is ramped back along.
+    ///
+    /// The completed velocity changes are undone in reverse order, starting with the one in
+    /// progress, so that MELVIN retraces the accelerations of the burn until `rollback_vel`.
+    ///
+    /// # Arguments
+    /// * `burn` – The aborted burn sequence.
+    /// * `steps_done` – The number of velocity changes completed before the abort.
+    /// * `rollback_vel` – The velocity to restore.
+    pub(super) fn rollback_path(
+        burn: &BurnSequence,
+        steps_done: usize,
+        rollback_vel: Vec2D<I32F32>,
+    ) -> Vec<Vec2D<I32F32>> {
+        let done = &burn.sequence_vel()[..steps_done.min(burn.sequence_vel().len())];
+        done.iter().rev().copied().chain(std::iter::once(rollback_vel)).collect()
+    }
+
+    /// Returns the time the burn sequence started.
+    pub fn started(&self) -> DateTime<Utc> { self.started }
+
+    /// Returns the number of velocity changes completed before the abort.
+    pub fn steps_done(&self) -> usize { self.steps_done }
+
+    /// Returns the number of velocity changes in the burn sequence.
+    pub fn steps_total(&self) -> usize { self.steps_total }
+
+    /// Returns the position at the time of the abort.
+    pub fn pos(&self) -> Vec2D<I32F32> { self.pos }
+
+    /// Returns the velocity at the time of the abort.
+    pub fn vel(&self) -> Vec2D<I32F32> { self.vel }
+
+    /// Returns the velocity that was restored after the abort.
+    pub fn rollback_vel(&self) -> Vec2D<I32F32> { self.rollback_vel }
+
+    /// Returns the violation of the safety envelope that stopped the burn, if any.
+    pub fn rejection(&self) -> Option<EnvelopeViolation> { self.rejection }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flight_control::orbit::IndexedOrbitPosition;
+
+    #[test]
+    fn test_rollback_path() {
+        let vel = |x: i32| Vec2D::new(I32F32::from_num(x), I32F32::lit("5"));
+        let pos = Vec2D::new(I32F32::lit("1000"), I32F32::lit("1000"));
+        let start_i = IndexedOrbitPosition::new(0, 1000, pos);
+        let vels = Box::from([vel(11), vel(12), vel(13), vel(14)]);
+        let burn = BurnSequence::new(start_i, Box::from([pos; 4]), vels, 4, 100, I32F32::ZERO, 0);
+        let pre_burn = vel(10);
+        // All completed velocity changes are undone in reverse order
+        let path = BurnAborted::rollback_path(&burn, 3, pre_burn);
+        assert_eq!(path, [vel(13), vel(12), vel(11), pre_burn]);
+        assert_eq!(BurnAborted::rollback_path(&burn, 0, pre_burn), [pre_burn]);
+        assert_eq!(BurnAborted::rollback_path(&burn, 9, pre_burn).len(), 5);
+    }
+}
@@ -0,0 +1,170 @@
+use fixed::types::I32F32;
+use num::Zero;
+
+/// Piecewise linear battery charge rate of a single [`FlightState`](super::FlightState)
+/// as a function of the current battery level.
+///
+/// A curve with a single support point is constant and reproduces the linear battery
+/// model exactly. Curves with multiple support points are interpolated linearly between
+/// the points and held constant beyond the first and last point.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ChargeCurve {
+    /// Support points `(battery level, charge rate per second)` sorted by battery level.
+    points: Vec<(I32F32, I32F32)>,
+}
+
+impl ChargeCurve {
+    /// Upper bound of simulated seconds when integrating a non-constant curve.
+    const MAX_INTEGRATION_SECS: i64 = 200_000;
+    /// The maximum battery level considered when fitting a curve.
+    const MAX_FIT_BATT: I32F32 = I32F32::lit("100.0");
+
+    /// Creates a constant [`ChargeCurve`].
+    ///
+    /// # Arguments
+    /// * `rate` – The charge rate per second at every battery level.
+    pub fn constant(rate: I32F32) -> Self { Self { points: vec![(I32F32::zero(), rate)] } }
+
+    /// Creates a [`ChargeCurve`] from arbitrary support points.
+    ///
+    /// # Arguments
+    /// * `points` – `(battery level, charge rate per second)` pairs in any order.
+    ///
+    /// # Returns
+    /// * `None` if no support point is given.
+    pub fn from_points(mut points: Vec<(I32F32, I32F32)>) -> Option<Self> {
+        if points.is_empty() {
+            return None;
+        }
+        points.sort_by_key(|p| p.0);
+        points.dedup_by_key(|p| p.0);
+        Some(Self { points })
+    }
+
+    /// Fits a [`ChargeCurve`] to observed `(battery level, charge rate)` samples.
+    ///
+    /// The battery range is split into `bins` equally sized bins. Each non-empty bin
+    /// contributes its mean battery level and mean rate as a support point.
+    ///
+    /// # Arguments
+    /// * `samples` – Observed battery levels and the charge rate measured there.
+    /// * `bins` – Number of battery level bins.
+    ///
+    /// # Returns
+    /// * `None` if no sample could be assigned to a bin.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn fit(samples: &[(I32F32, I32F32)], bins: usize) -> Option<Self> {
+        let n_bins = bins.max(1);
+        let bin_width = Self::MAX_FIT_BATT / I32F32::from_num(n_bins);
+        let mut acc = vec![(I32F32::zero(), I32F32::zero(), 0usize); n_bins];
+        for &(batt, rate) in samples {
+            let clamped = batt.clamp(I32F32::zero(), Self::MAX_FIT_BATT);
+            let bin = (clamped / bin_width).floor().to_num::<usize>().min(n_bins - 1);
+            acc[bin].0 += clamped;
+            acc[bin].1 += rate;
+            acc[bin].2 += 1;
+        }
+        let points = acc
+            .into_iter()
+            .filter(|(_, _, n)| *n > 0)
+            .map(|(b, r, n)| (b / I32F32::from_num(n), r / I32F32::from_num(n)))
+            .collect();
+        Self::from_points(points)
+    }
+
+    /// Returns the lowest charge rate of the curve, i.e. the slowest charging or the fastest
+    /// discharging rate.
+    pub fn min_rate(&self) -> I32F32 {
+        self.points.iter().map(|p| p.1).min().unwrap_or(I32F32::zero())
+    }
+
+    /// Returns `true` if the curve has the same rate at every battery level.
+    pub fn is_constant(&self) -> bool { self.points.len() == 1 }
+
+    /// Returns the interpolated charge rate at a given battery level.
+    ///
+    /// # Arguments
+    /// * `batt` – The battery level.
+    pub fn rate_at(&self, batt: I32F32) -> I32F32 {
+        let first = self.points[0];
+        let last = self.points[self.points.len() - 1];
+        if batt <= first.0 {
+            return first.1;
+        }
+        if batt >= last.0 {
+            return last.1;
+        }
+        let upper = self.points.partition_point(|p| p.0 <= batt);
+        let (b0, r0) = self.points[upper - 1];
+        let (b1, r1) = self.points[upper];
+        r0 + (r1 - r0) * (batt - b0) / (b1 - b0)
+    }
+
+    /// Predicts the battery level after a given number of seconds.
+    ///
+    /// # Arguments
+    /// * `batt` – The initial battery level.
+    /// * `secs` – The number of seconds spent following this curve.
+    pub fn batt_after(&self, batt: I32F32, secs: i64) -> I32F32 {
+        if self.is_constant() {
+            return batt + self.points[0].1 * I32F32::from_num(secs);
+        }
+        (0..secs.clamp(0, Self::MAX_INTEGRATION_SECS)).fold(batt, |b, _| b + self.rate_at(b))
+    }
+
+    /// Computes the number of seconds needed to get from one battery level to another.
+    ///
+    /// # Arguments
+    /// * `from` – The initial battery level.
+    /// * `to` – The target battery level.
+    ///
+    /// # Returns
+    /// * The (fractional) number of seconds, or `I32F32::MAX` if `to` is never reached.
+    pub fn secs_to_reach(&self, from: I32F32, to: I32F32) -> I32F32 {
+        if from == to {
+            return I32F32::zero();
+        }
+        let reaches = |rate: I32F32| (rate > 0 && to > from) || (rate < 0 && to < from);
+        if self.is_constant() {
+            let rate = self.points[0].1;
+            return if reaches(rate) { (to - from) / rate } else { I32F32::MAX };
+        }
+        let mut batt = from;
+        for secs in 0..Self::MAX_INTEGRATION_SECS {
+            let rate = self.rate_at(batt);
+            if !reaches(rate) {
+                return I32F32::MAX;
+            }
+            let next = batt + rate;
+            if (next - to).signum() != (batt - to).signum() || next == to {
+                return I32F32::from_num(secs) + (to - batt) / rate;
+            }
+            batt = next;
+        }
+        I32F32::MAX
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_charge_curve_fit_and_integration() {
+        let lin = ChargeCurve::constant(I32F32::lit("0.5"));
+        assert_eq!(lin.batt_after(I32F32::lit("10.0"), 20), I32F32::lit("20.0"));
+        assert_eq!(lin.secs_to_reach(I32F32::lit("10.0"), I32F32::lit("20.0")), 20);
+
+        // charging slows down linearly towards a full battery
+        let samples: Vec<_> = (0..100)
+            .map(|b| (I32F32::from_num(b), I32F32::from_num(f64::from(100 - b) / 500.0)))
+            .collect();
+        let curve = ChargeCurve::fit(&samples, 10).unwrap();
+        assert!(!curve.is_constant());
+        assert!(curve.rate_at(I32F32::lit("20.0")) > curve.rate_at(I32F32::lit("80.0")));
+        let secs = curve.secs_to_reach(I32F32::lit("20.0"), I32F32::lit("80.0"));
+        let reached = curve.batt_after(I32F32::lit("20.0"), secs.ceil().to_num::<i64>());
+        assert!(reached >= I32F32::lit("80.0") && reached < I32F32::lit("80.5"));
+        assert_eq!(curve.secs_to_reach(I32F32::lit("80.0"), I32F32::lit("20.0")), I32F32::MAX);
+    }
+}
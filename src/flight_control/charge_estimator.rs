@@ -0,0 +1,152 @@
+use super::{FlightState, charge_curve::ChargeCurve};
+use crate::util::Vec2D;
+use chrono::{DateTime, Utc};
+use fixed::types::I32F32;
+use std::collections::{HashMap, VecDeque};
+
+/// The start of an observation window over which a charge rate is measured.
+#[derive(Debug, Clone, Copy)]
+struct WindowStart {
+    /// The time of the observation.
+    t: DateTime<Utc>,
+    /// The flight state during the window.
+    state: FlightState,
+    /// The battery level at the start of the window.
+    batt: I32F32,
+    /// The velocity during the window.
+    vel: Vec2D<I32F32>,
+}
+
+/// Online estimator fitting the charge rates of the scheduled flight states to telemetry.
+///
+/// The nominal rates of [`FlightState::get_charge_rate`] drift from the observed ones, e.g.
+/// after safe mode events. The estimator measures the battery change over windows of
+/// consecutive observations in an unchanged flight state and velocity, and periodically fits
+/// a [`ChargeCurve`] to the collected samples. Windows in which the battery is clamped at
+/// its bounds or MELVIN accelerates are discarded, as are implausible rates.
+#[derive(Debug, Default)]
+pub(crate) struct ChargeEstimator {
+    /// The start of the current observation window.
+    window: Option<WindowStart>,
+    /// The measured `(battery level, charge rate)` samples per flight state.
+    samples: HashMap<FlightState, VecDeque<(I32F32, I32F32)>>,
+    /// The number of samples per flight state collected since its last fit.
+    since_fit: HashMap<FlightState, usize>,
+}
+
+impl ChargeEstimator {
+    /// The flight states whose charge rates are estimated.
+    const STATES: [FlightState; 3] =
+        [FlightState::Charge, FlightState::Acquisition, FlightState::Comms];
+    /// The minimum length of an observation window in seconds.
+    const MIN_WINDOW_S: i64 = 10;
+    /// Windows longer than this are discarded, as observations were missed.
+    const MAX_WINDOW_S: i64 = 60;
+    /// The maximum number of samples kept per flight state.
+    const MAX_SAMPLES: usize = 360;
+    /// The minimum number of samples before a curve is fitted.
+    const MIN_SAMPLES: usize = 12;
+    /// The number of new samples after which a curve is refitted.
+    const REFIT_SAMPLES: usize = 6;
+    /// The number of battery level bins of a fitted curve.
+    const FIT_BINS: usize = 5;
+    /// The maximum deviation of a measured rate from the nominal rate, as a factor.
+    const MAX_RATE_FACTOR: I32F32 = I32F32::lit("3.0");
+    /// The battery margin to the bounds within which the battery counts as clamped.
+    const CLAMP_MARGIN: I32F32 = I32F32::lit("0.5");
+
+    /// Feeds an observation into the estimator.
+    ///
+    /// # Arguments
+    /// * `t` – The time of the observation.
+    /// * `state` – The observed flight state.
+    /// * `batt` – The observed battery level.
+    /// * `max_batt` – The observed maximum battery level.
+    /// * `vel` – The observed velocity.
+    ///
+    /// # Returns
+    /// The flight state and its refitted [`ChargeCurve`], if a refit is due.
+    pub(crate) fn observe(
+        &mut self,
+        t: DateTime<Utc>,
+        state: FlightState,
+        batt: I32F32,
+        max_batt: I32F32,
+        vel: Vec2D<I32F32>,
+    ) -> Option<(FlightState, ChargeCurve)> {
+        let clamped = batt <= Self::CLAMP_MARGIN || batt >= max_batt - Self::CLAMP_MARGIN;
+        let current = WindowStart { t, state, batt, vel };
+        let Some(start) = self.window else {
+            self.window = (!clamped).then_some(current);
+            return None;
+        };
+        let dt = (t - start.t).num_seconds();
+        if clamped || start.state != state || start.vel != vel || dt > Self::MAX_WINDOW_S {
+            self.window = (!clamped).then_some(current);
+            return None;
+        }
+        if dt < Self::MIN_WINDOW_S {
+            return None;
+        }
+        self.window = Some(current);
+        let rate = (batt - start.batt) / I32F32::from_num(dt);
+        self.record(state, (start.batt + batt) / 2, rate)
+    }
+
+    /// Records a measured charge rate and refits the curve of the flight state if due.
+    ///
+    /// # Arguments
+    /// * `state` – The flight state.
+    /// * `batt` – The mean battery level during the measurement.
+    /// * `rate` – The measured charge rate per second.
+    fn record(
+        &mut self,
+        state: FlightState,
+        batt: I32F32,
+        rate: I32F32,
+    ) -> Option<(FlightState, ChargeCurve)> {
+        let nominal = state.get_charge_rate();
+        let plausible = rate.signum() == nominal.signum()
+            && rate.abs() <= nominal.abs() * Self::MAX_RATE_FACTOR;
+        if !Self::STATES.contains(&state) || !plausible {
+            return None;
+        }
+        let samples = self.samples.entry(state).or_default();
+        if samples.len() == Self::MAX_SAMPLES {
+            samples.pop_front();
+        }
+        samples.push_back((batt, rate));
+        let since_fit = self.since_fit.entry(state).or_default();
+        *since_fit += 1;
+        if samples.len() < Self::MIN_SAMPLES || *since_fit < Self::REFIT_SAMPLES {
+            return None;
+        }
+        *since_fit = 0;
+        let fit_samples: Vec<_> = samples.iter().copied().collect();
+        ChargeCurve::fit(&fit_samples, Self::FIT_BINS).map(|curve| (state, curve))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+
+    #[test]
+    fn test_charge_rate_calibration() {
+        let mut estimator = ChargeEstimator::default();
+        let (start, vel) = (Utc::now(), Vec2D::new(I32F32::lit("4.0"), I32F32::lit("7.0")));
+        let max_batt = I32F32::lit("90.0");
+        let mut fitted = None;
+        for s in 0..600 {
+            let t = start + TimeDelta::seconds(s);
+            let batt = I32F32::lit("10.0") + I32F32::lit("0.08") * I32F32::from_num(s);
+            let state = if s % 200 < 5 { FlightState::Transition } else { FlightState::Charge };
+            fitted = estimator.observe(t, state, batt.min(max_batt), max_batt, vel).or(fitted);
+        }
+        let (state, curve) = fitted.unwrap();
+        assert_eq!(state, FlightState::Charge);
+        let rate = curve.rate_at(I32F32::lit("40.0"));
+        assert!((rate - I32F32::lit("0.08")).abs() < I32F32::lit("0.001"));
+    }
+}
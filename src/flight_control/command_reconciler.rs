@@ -0,0 +1,111 @@
+use super::FlightState;
+use crate::imaging::CameraAngle;
+use crate::util::{Vec2D, logger::JsonDump};
+use crate::warn;
+use chrono::{DateTime, Utc};
+use fixed::types::I32F32;
+
+/// A control command sent to the backend, whose effect is verified by later observations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ControlCommand {
+    /// A state change to the given target state.
+    State(FlightState),
+    /// A velocity change to the given target velocity.
+    Vel(Vec2D<I32F32>),
+    /// A camera angle change to the given target angle.
+    Angle(CameraAngle),
+}
+
+impl ControlCommand {
+    /// Checks whether reissuing this command is safe in the currently observed state.
+    ///
+    /// Commands are never reissued while in `Transition` or `Safe`, as they would race with
+    /// the ongoing transition or the safe mode recovery. Velocity and angle changes are
+    /// only accepted by the backend in `Acquisition`.
+    ///
+    /// # Arguments
+    /// * `state` – The currently observed flight state.
+    pub(crate) fn reissue_allowed(self, state: FlightState) -> bool {
+        match self {
+            ControlCommand::State(_) => {
+                !matches!(state, FlightState::Transition | FlightState::Safe)
+            }
+            ControlCommand::Vel(_) | ControlCommand::Angle(_) => {
+                state == FlightState::Acquisition
+            }
+        }
+    }
+}
+
+/// A single discrepancy between a commanded and an observed value.
+#[derive(Debug, Clone, serde::Serialize)]
+pub(crate) struct Discrepancy {
+    /// The time the discrepancy was detected.
+    t: DateTime<Utc>,
+    /// The command that did not stick.
+    commanded: String,
+    /// The observed state, velocity and angle at detection time.
+    observed: String,
+    /// The number of times the command was reissued before this detection.
+    attempt: u8,
+    /// Whether the command was reissued after this detection.
+    reissued: bool,
+}
+
+/// Log of all discrepancies between commanded values and subsequent observations.
+///
+/// Commands may silently be dropped by the backend, e.g. when racing with a transition.
+/// The [`FlightComputer`](super::FlightComputer) records every such drop here before
+/// reissuing the command, so that they can be analyzed after the mission.
+#[derive(Debug, Default, serde::Serialize)]
+pub(crate) struct ReconciliationLog {
+    /// The recorded discrepancies in chronological order.
+    entries: Vec<Discrepancy>,
+    /// The number of reissued commands that stuck afterward.
+    resolved: usize,
+}
+
+impl ReconciliationLog {
+    /// The maximum number of times a dropped command is reissued.
+    pub(crate) const MAX_REISSUES: u8 = 2;
+
+    /// Records a discrepancy, logs it and dumps the updated log.
+    ///
+    /// # Arguments
+    /// * `cmd` – The command that did not stick.
+    /// * `observed` – A description of the observed values.
+    /// * `attempt` – The number of times the command was reissued so far.
+    /// * `reissued` – Whether the command is reissued now.
+    pub(crate) fn record(
+        &mut self,
+        cmd: ControlCommand,
+        observed: String,
+        attempt: u8,
+        reissued: bool,
+    ) {
+        let action = if reissued { "Reissuing" } else { "Not reissuing" };
+        warn!("Command {cmd:?} not reflected in observation ({observed}). {action} command.");
+        self.entries.push(Discrepancy {
+            t: Utc::now(),
+            commanded: format!("{cmd:?}"),
+            observed,
+            attempt,
+            reissued,
+        });
+        self.dump_json();
+    }
+
+    /// Marks the last reissued command as resolved and dumps the updated log.
+    pub(crate) fn mark_resolved(&mut self) {
+        self.resolved += 1;
+        self.dump_json();
+    }
+}
+
+impl JsonDump for ReconciliationLog {
+    /// Returns the file name for the JSON dump of the reconciliation log.
+    fn file_name(&self) -> String { "reconciliation_log".to_string() }
+
+    /// Returns the directory name for the reconciliation log JSON file.
+    fn dir_name(&self) -> &'static str { "reconciliation" }
+}
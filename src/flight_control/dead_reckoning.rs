@@ -0,0 +1,137 @@
+use super::FlightComputer;
+use crate::util::Vec2D;
+use chrono::{DateTime, TimeDelta, Utc};
+use fixed::types::I32F32;
+
+/// Dead-reckoning estimate of MELVINs position between two observations.
+///
+/// Observations are polled only every few seconds and are already older than their reception,
+/// so control loops acting on the last observed position steer on a stale state. The estimate
+/// is anchored at the position and velocity of the latest observation at its timestamp and
+/// propagated from there, accelerating with [`FlightComputer::ACC_CONST`] towards the last
+/// commanded velocity. Observations that are not newer than the anchor are ignored.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct DeadReckoning {
+    /// The position at the anchor time.
+    pos: Vec2D<I32F32>,
+    /// The velocity at the anchor time.
+    vel: Vec2D<I32F32>,
+    /// The timestamp of the observation the estimate is anchored at.
+    t: DateTime<Utc>,
+    /// The last commanded velocity.
+    target_vel: Vec2D<I32F32>,
+    /// The time the last velocity command was issued.
+    command_t: DateTime<Utc>,
+    /// The distance between the propagated and the observed position at the last fusion.
+    innovation: I32F32,
+}
+
+impl DeadReckoning {
+    /// The maximum time an estimate is propagated beyond its anchor.
+    const MAX_PROPAGATION: TimeDelta = TimeDelta::seconds(30);
+
+    /// Creates an estimate anchored at an observed state.
+    ///
+    /// # Arguments
+    /// * `pos` – The observed position.
+    /// * `vel` – The observed velocity.
+    /// * `t` – The timestamp of the observation.
+    pub(crate) fn new(pos: Vec2D<I32F32>, vel: Vec2D<I32F32>, t: DateTime<Utc>) -> Self {
+        Self { pos, vel, t, target_vel: vel, command_t: t, innovation: I32F32::ZERO }
+    }
+
+    /// Fuses an observation into the estimate by re-anchoring it at the observed state.
+    ///
+    /// # Arguments
+    /// * `pos` – The observed position.
+    /// * `vel` – The observed velocity.
+    /// * `t` – The timestamp of the observation.
+    ///
+    /// # Returns
+    /// * `false` if the observation is not newer than the anchor and was ignored.
+    pub(crate) fn fuse(
+        &mut self,
+        pos: Vec2D<I32F32>,
+        vel: Vec2D<I32F32>,
+        t: DateTime<Utc>,
+    ) -> bool {
+        if t <= self.t {
+            return false;
+        }
+        self.innovation = self.predict(t).0.unwrapped_to(&pos).abs();
+        (self.pos, self.vel, self.t) = (pos, vel, t);
+        true
+    }
+
+    /// Records a velocity command, which the estimate accelerates towards from `t` on.
+    ///
+    /// # Arguments
+    /// * `vel` – The commanded velocity.
+    /// * `t` – The time the command was issued.
+    pub(crate) fn command_vel(&mut self, vel: Vec2D<I32F32>, t: DateTime<Utc>) {
+        self.target_vel = vel;
+        self.command_t = t;
+    }
+
+    /// Returns the distance between the propagated and the observed position at the last
+    /// fusion.
+    pub(crate) fn innovation(&self) -> I32F32 { self.innovation }
+
+    /// Propagates the estimate to a point in time.
+    ///
+    /// The estimate coasts with the anchored velocity until the last velocity command, then
+    /// accelerates towards the commanded velocity. Times before the anchor return the anchored
+    /// state, times more than `MAX_PROPAGATION` after it are clamped.
+    ///
+    /// # Arguments
+    /// * `t` – The point in time.
+    ///
+    /// # Returns
+    /// * The wrapped position and the velocity at `t`.
+    pub(crate) fn predict(&self, t: DateTime<Utc>) -> (Vec2D<I32F32>, Vec2D<I32F32>) {
+        let end = t.clamp(self.t, self.t + Self::MAX_PROPAGATION);
+        let acc_start = self.command_t.clamp(self.t, end);
+        let coast_dt = Self::secs(acc_start - self.t);
+        let acc_dt = Self::secs(end - acc_start);
+        let dv = self.vel.to(&self.target_vel);
+        let ramp_dt = (dv.abs() / FlightComputer::ACC_CONST).min(acc_dt);
+        let acc = dv.normalize() * FlightComputer::ACC_CONST;
+        let pos = self.pos
+            + self.vel * (coast_dt + ramp_dt)
+            + acc * (ramp_dt * ramp_dt / 2)
+            + self.target_vel * (acc_dt - ramp_dt);
+        let vel = if ramp_dt < acc_dt { self.target_vel } else { self.vel + acc * ramp_dt };
+        (pos.wrap_around_map(), vel)
+    }
+
+    /// Converts a [`TimeDelta`] to fractional seconds.
+    fn secs(dt: TimeDelta) -> I32F32 { I32F32::from_num(dt.num_milliseconds()) / 1000 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dead_reckoning_propagation() {
+        let t0 = Utc::now();
+        let at = |ms: i64| t0 + TimeDelta::milliseconds(ms);
+        let vel = Vec2D::new(I32F32::lit("4.0"), I32F32::lit("3.0"));
+        let start = Vec2D::new(I32F32::lit("100"), I32F32::lit("200"));
+        let mut dr = DeadReckoning::new(start, vel, t0);
+        assert_eq!(dr.predict(at(-5000)).0, start);
+        assert_eq!(dr.predict(at(2500)).0, Vec2D::new(I32F32::lit("110"), I32F32::lit("207.5")));
+
+        // Accelerating by 0.4 in x takes 20s after the command at 10s
+        dr.command_vel(Vec2D::new(I32F32::lit("4.4"), I32F32::lit("3.0")), at(10_000));
+        let (pos, v) = dr.predict(at(30_000));
+        assert!(v.euclid_distance(&Vec2D::new(I32F32::lit("4.4"), I32F32::lit("3.0"))) < 0.001);
+        assert!((pos.x() - I32F32::lit("224")).abs() < I32F32::lit("0.01"));
+
+        assert!(!dr.fuse(Vec2D::zero(), vel, at(-1000)));
+        let observed = Vec2D::new(I32F32::lit("121"), I32F32::lit("215"));
+        assert!(dr.fuse(observed, vel, at(5000)));
+        assert_eq!(dr.innovation(), I32F32::ONE);
+        assert_eq!(dr.predict(at(5000)).0, observed);
+    }
+}
@@ -1,6 +1,14 @@
 use super::{
+    burn_abort::BurnAborted,
+    charge_estimator::ChargeEstimator,
+    dead_reckoning::DeadReckoning,
+    command_reconciler::{ControlCommand, ReconciliationLog},
     flight_state::FlightState,
+    manual_command::{CommandRejected, ManualCommand},
     orbit::{BurnSequence, ClosedOrbit, IndexedOrbitPosition},
+    safety_envelope::{EnvelopeViolation, SafetyEnvelope},
+    turn_cache::TURN_CACHE,
+    watchdog::RecoveryAction,
 };
 use crate::http_handler::{
     http_client,
@@ -10,10 +18,13 @@ use crate::http_handler::{
         request_common::{JSONBodyHTTPRequestType, NoBodyHTTPRequestType},
         reset_get::ResetRequest,
     },
+    http_response::observation::ObservationResponse,
 };
 use crate::imaging::CameraAngle;
-use crate::util::{Vec2D, WrapDirection, helpers::MAX_DEC};
-use crate::{STATIC_ORBIT_VEL, error, fatal, info, log, log_burn, warn};
+use crate::util::{
+    JournalEvent, MISSION_JOURNAL, Vec2D, WrapDirection, helpers::MAX_DEC, logger::JsonDump,
+};
+use crate::{error, fatal, info, log, log_burn, warn};
 use crate::scheduling::TaskController;
 use chrono::{DateTime, TimeDelta, Utc};
 use fixed::types::{I32F32, I64F64};
@@ -23,7 +34,8 @@ use std::{
     sync::Arc,
     time::{Duration, Instant},
 };
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, OwnedMutexGuard, RwLock};
+use tokio_util::sync::CancellationToken;
 
 pub type TurnsClockCClockTup = (
     Vec<(Vec2D<I32F32>, Vec2D<I32F32>)>,
@@ -62,8 +74,20 @@ pub struct FlightComputer {
     fuel_left: I32F32,
     /// Timestamp marking the last observation update from the satellite.
     last_observation_timestamp: DateTime<Utc>,
+    /// Position estimate propagated from the latest observation.
+    dead_reckoning: DeadReckoning,
     /// HTTP client for sending requests for satellite operations.
     request_client: Arc<http_client::HTTPClient>,
+    /// Log of control commands that were not reflected in subsequent observations.
+    reconciliation_log: ReconciliationLog,
+    /// Recovery requested by the supervisor watchdog, consumed by the next safe escape.
+    pending_recovery: Option<RecoveryAction>,
+    /// Online estimator calibrating the charge rates from consecutive observations.
+    charge_estimator: ChargeEstimator,
+    /// Whether a burn sequence is executing, which blocks manual commands.
+    burn_active: bool,
+    /// Serializes manual commands with scheduled state switches, lens changes and burn starts.
+    command_lock: Arc<Mutex<()>>,
 }
 
 impl FlightComputer {
@@ -93,6 +117,8 @@ impl FlightComputer {
     /// Maximum time spend in acquisition between burns for orbit returns (this is the distance
     /// travelled during acceleration/brake (2*25) which leaves a maximum of 110 at max speed according to `MAX_OR_VEL_CHANGE_DEV`)
     const MAX_OR_ACQ_TIME: I32F32 = I32F32::lit("156");
+    /// Deviation of the dead-reckoning estimate from an observation that is logged
+    const DR_MAX_INNOVATION: I32F32 = I32F32::lit("5.0");
     /// Minimum battery used in decision-making for after safe transition
     const AFTER_SAFE_MIN_BATT: I32F32 = I32F32::lit("50");
     /// Minimum battery needed to exit safe mode
@@ -101,6 +127,8 @@ impl FlightComputer {
     const DEF_BRAKE_ABS: I32F32 = I32F32::lit("1.0");
     /// Maximum burn time for detumbling
     const MAX_DETUMBLE_DT: TimeDelta = TimeDelta::seconds(20);
+    /// Change of a calibrated charge rate above which the calibration is logged
+    const CHARGE_RATE_LOG_DEV: I32F32 = I32F32::lit("0.002");
     /// Legal Target States for State Change
     const LEGAL_TARGET_STATES: [FlightState; 3] = [
         FlightState::Acquisition,
@@ -122,7 +150,7 @@ impl FlightComputer {
     ///
     /// # Returns
     /// A fully initialized `FlightComputer` with up-to-date field values.
-    pub async fn new(request_client: Arc<http_client::HTTPClient>) -> FlightComputer {
+    pub(crate) async fn new(request_client: Arc<http_client::HTTPClient>) -> FlightComputer {
         let mut return_controller = FlightComputer {
             current_pos: Vec2D::new(I32F32::zero(), I32F32::zero()),
             current_vel: Vec2D::new(I32F32::zero(), I32F32::zero()),
@@ -133,7 +161,13 @@ impl FlightComputer {
             max_battery: I32F32::zero(),
             fuel_left: I32F32::zero(),
             last_observation_timestamp: Utc::now(),
+            dead_reckoning: DeadReckoning::new(Vec2D::zero(), Vec2D::zero(), DateTime::UNIX_EPOCH),
             request_client,
+            reconciliation_log: ReconciliationLog::default(),
+            pending_recovery: None,
+            charge_estimator: ChargeEstimator::default(),
+            burn_active: false,
+            command_lock: Arc::new(Mutex::new(())),
         };
         return_controller.update_observation().await;
         if return_controller.current_state == FlightState::Transition {
@@ -193,6 +227,24 @@ impl FlightComputer {
         (Vec2D::new(trunc_x, trunc_y), Vec2D::new(dev_x, dev_y))
     }
 
+    /// Returns the maximum speed reduction achieved by [`FlightComputer::detumble_to`], i.e. the
+    /// braking step applied once per second over the maximum detumble time.
+    pub fn max_detumble_brake() -> I32F32 {
+        Self::DEF_BRAKE_ABS * I32F32::from_num(Self::MAX_DETUMBLE_DT.num_seconds())
+    }
+
+    /// Returns the possible turns of MELVIN like [`FlightComputer::compute_possible_turns`],
+    /// reusing the tables of the last planning call if the rounded velocity did not change.
+    /// The tables are computed from the rounded velocity, so that all velocities sharing a cache
+    /// entry get the same tables.
+    ///
+    /// # Arguments
+    /// - `init_vel`: A `Vec2D<I32F32>` representing the initial velocity of the satellite.
+    pub fn possible_turns(init_vel: Vec2D<I32F32>) -> Arc<TurnsClockCClockTup> {
+        let (vel, _) = Self::round_vel(init_vel);
+        TURN_CACHE.get_or_compute(vel, || Self::compute_possible_turns(vel))
+    }
+
     /// Precomputes possible turns of MELVIN, splitting paths into clockwise and counterclockwise
     /// directions based on the initial velocity. These precomputed paths are useful for calculating
     /// optimal burns.
@@ -270,6 +322,13 @@ impl FlightComputer {
     /// A `Vec2D` representing the current satellite position.
     pub fn current_pos(&self) -> Vec2D<I32F32> { self.current_pos }
 
+    /// Retrieves the position estimated for the current time, propagated from the latest
+    /// observation by dead reckoning.
+    ///
+    /// # Returns
+    /// - A `Vec2D<I32F32>` representing the estimated position.
+    pub fn estimated_pos_now(&self) -> Vec2D<I32F32> { self.dead_reckoning.predict(Utc::now()).0 }
+
     /// Retrieves the current position of the satellite.
     ///
     /// # Returns
@@ -319,31 +378,131 @@ impl FlightComputer {
     /// - A `Option<FlightState>` denoting the target state of the commanded state change.
     pub fn target_state(&self) -> Option<FlightState> { self.target_state }
 
+    /// Retrieves the timestamp of the last observation update.
+    ///
+    /// # Returns
+    /// - A `DateTime<Utc>` denoting when the last observation was taken.
+    pub fn last_observation_timestamp(&self) -> DateTime<Utc> { self.last_observation_timestamp }
+
+    /// Returns `true` while a burn sequence is executing.
+    pub fn burn_active(&self) -> bool { self.burn_active }
+
+    /// Acquires the command lock, which is held from the checks of a command until its
+    /// control request is done, so that manual and scheduled commands never interleave.
+    ///
+    /// # Arguments
+    /// - `self_lock`: A `RwLock<Self>` reference to the active flight computer.
+    pub(crate) async fn lock_commands(self_lock: &RwLock<Self>) -> OwnedMutexGuard<()> {
+        let command_lock = Arc::clone(&self_lock.read().await.command_lock);
+        command_lock.lock_owned().await
+    }
+
+    /// Validates a manual operator command against the legality checks of the control
+    /// primitives.
+    ///
+    /// Commands are blocked while a burn sequence is executing. State changes must target a
+    /// legal target state and are rejected during `Transition` and `Safe`, velocity and lens
+    /// changes are only accepted in `Acquisition`. Velocities are checked against the
+    /// [`SafetyEnvelope`].
+    ///
+    /// # Arguments
+    /// - `cmd`: The manual command to check.
+    ///
+    /// # Returns
+    /// - The command to execute, with the velocity rounded to the decimals of the backend and
+    ///   clamped to the safety envelope.
+    ///
+    /// # Errors
+    /// - A [`CommandRejected`] naming the failed interlock.
+    pub(crate) fn check_manual_command(
+        &self,
+        cmd: ManualCommand,
+    ) -> Result<ManualCommand, CommandRejected> {
+        if self.burn_active {
+            return Err(CommandRejected::BurnInProgress);
+        }
+        if let ManualCommand::SetState(state) = cmd
+            && !Self::LEGAL_TARGET_STATES.contains(&state)
+        {
+            return Err(CommandRejected::IllegalTargetState(state));
+        }
+        if cmd.control().is_some_and(|ctrl| !ctrl.reissue_allowed(self.current_state)) {
+            return Err(CommandRejected::WrongState(self.current_state));
+        }
+        Ok(match cmd {
+            ManualCommand::SetVel(vel) => {
+                let (state, fuel) = (self.current_state, self.fuel_left);
+                let rounded = Self::round_vel(vel).0;
+                SafetyEnvelope::check_vel(state, self.current_vel, rounded, fuel)
+                    .map(ManualCommand::SetVel)
+                    .map_err(CommandRejected::Envelope)?
+            }
+            other => other,
+        })
+    }
+
+    /// Dumps the log of commands that were not reflected in subsequent observations.
+    pub(crate) fn dump_reconciliation_log(&self) -> std::io::Result<()> {
+        self.reconciliation_log.try_dump_json()
+    }
+
     /// Retrieves a clone of the HTTP client used by the flight computer for sending requests.
     ///
     /// # Returns
     /// - An `Arc<http_client::HTTPClient>` which represents the HTTP client instance.
-    pub fn client(&self) -> Arc<http_client::HTTPClient> { Arc::clone(&self.request_client) }
+    pub(crate) fn client(&self) -> Arc<http_client::HTTPClient> { Arc::clone(&self.request_client) }
 
     /// Sends a reset request to the satellite's HTTP control system.
     ///
     /// This function invokes an HTTP request to reset MELVIN, and it expects the request to succeed.
     ///
+    /// # Arguments
+    /// * `self_lock`: A shared `RwLock` containing the `FlightComputer` instance
+    ///
     /// # Panics
     /// - If the reset request fails, this method will panic with an error message.
-    pub async fn reset(&mut self) {
-        ResetRequest {}
-            .send_request(&self.request_client)
-            .await
-            .unwrap_or_else(|_| fatal!("Failed to reset"));
+    pub async fn reset(self_lock: &RwLock<Self>) {
+        if !Self::try_reset(self_lock).await {
+            fatal!("Failed to reset");
+        }
+    }
+
+    /// Sends a reset request to the satellite's HTTP control system without panicking.
+    ///
+    /// The flight computer is not locked while the reset is in progress, so that observations
+    /// are still applied.
+    ///
+    /// # Arguments
+    /// * `self_lock`: A shared `RwLock` containing the `FlightComputer` instance
+    ///
+    /// # Returns
+    /// - `true` if the reset request succeeded.
+    pub(crate) async fn try_reset(self_lock: &RwLock<Self>) -> bool {
+        let client = self_lock.read().await.client();
+        if (ResetRequest {}.send_request(&client).await).is_err() {
+            return false;
+        }
         Self::wait_for_duration(Duration::from_secs(4), false).await;
-        self.target_state = None;
+        self_lock.write().await.target_state = None;
         log!("Reset request complete.");
+        true
     }
 
     /// Indicates that a `Supervisor` detected a safe mode event
     pub fn safe_detected(&mut self) { self.target_state = Some(FlightState::Safe); }
 
+    /// Requests a recovery through the safe-mode path, as if a safe mode event was detected.
+    ///
+    /// # Arguments
+    /// - `action`: The recovery action, applied by the next call to [`Self::escape_safe`].
+    pub(crate) fn request_recovery(&mut self, action: RecoveryAction) {
+        self.pending_recovery = Some(action);
+        self.safe_detected();
+    }
+
+    /// Returns whether a requested recovery has not yet been finished by [`Self::escape_safe`].
+    pub(crate) fn recovery_pending(&self) -> bool { self.pending_recovery.is_some() }
+
     /// Waits for a given amount of time with debug prints, this is a static method.
     ///
     /// # Arguments
@@ -381,7 +540,8 @@ impl FlightComputer {
         timeout_millis: u32,
         poll_interval: u16,
         mute: bool,
-    ) where
+    ) -> bool
+    where
         F: Fn(&Self) -> bool,
     {
         if !mute {
@@ -399,7 +559,7 @@ impl FlightComputer {
                         log!("Condition met after {dt} ms");
                     }
                 }
-                return;
+                return true;
             }
             tokio::time::sleep(Duration::from_millis(u64::from(poll_interval))).await;
         }
@@ -408,18 +568,78 @@ impl FlightComputer {
         } else {
             warn!("Condition not met after {timeout_millis:#?} ms");
         }
+        false
+    }
+
+    /// Verifies that a control command is reflected in the observations and reissues it
+    /// if it was silently dropped by the backend.
+    ///
+    /// Each discrepancy is recorded in the reconciliation log. The command is only reissued
+    /// while it is safe to do so, and at most `ReconciliationLog::MAX_REISSUES` times.
+    ///
+    /// # Arguments
+    /// * `self_lock`: A shared `RwLock` containing the `FlightComputer` instance
+    /// * `cmd`: The issued control command.
+    /// * `(condition, rationale)`: The condition that holds once the command stuck.
+    /// * `mute`: Whether to suppress non-critical logging.
+    async fn reconcile<F>(
+        self_lock: &RwLock<Self>,
+        cmd: ControlCommand,
+        (condition, rationale): (F, String),
+        mute: bool,
+    ) where
+        F: Fn(&Self) -> bool + Copy,
+    {
+        for attempt in 0..=ReconciliationLog::MAX_REISSUES {
+            let cond = (condition, rationale.clone());
+            if Self::wait_for_condition(self_lock, cond, Self::DEF_COND_TO, Self::DEF_COND_PI, mute)
+                .await
+            {
+                if attempt > 0 {
+                    self_lock.write().await.reconciliation_log.mark_resolved();
+                }
+                return;
+            }
+            let reissue = {
+                let mut f_cont = self_lock.write().await;
+                let observed = format!(
+                    "state {}, vel {}, angle {}",
+                    f_cont.current_state, f_cont.current_vel, f_cont.current_angle
+                );
+                let reissue = attempt < ReconciliationLog::MAX_REISSUES
+                    && cmd.reissue_allowed(f_cont.current_state);
+                f_cont.reconciliation_log.record(cmd, observed, attempt, reissue);
+                reissue
+            };
+            if !reissue {
+                return;
+            }
+            match cmd {
+                ControlCommand::State(state) => Self::set_state(self_lock, state).await,
+                ControlCommand::Vel(vel) => {
+                    if Self::set_vel(self_lock, vel, mute).await.is_err() {
+                        return;
+                    }
+                }
+                ControlCommand::Angle(angle) => Self::set_angle(self_lock, angle).await,
+            }
+        }
     }
 
     /// This method is used to escape a safe mode event by first waiting for the minimum charge
-    /// and then transitioning back to an operational state.
+    /// and then transitioning back to an operational state. A recovery requested by the
+    /// supervisor watchdog is handled the same way and consumed once the escape finished, so
+    /// that the watchdog does not detect the same fault again while it is in progress.
     ///
     /// # Arguments
     /// * `self_lock`: A shared `RwLock` containing the `FlightComputer` instance
     /// * `force_charge`: A variable indicating whether the `FlightState` after escaping should be forced to `FlightState::Charge`
     pub async fn escape_safe(self_lock: Arc<RwLock<Self>>, force_charge: bool) {
+        let recovery = self_lock.read().await.pending_recovery;
+        let charge = force_charge || recovery == Some(RecoveryAction::ForceCharge);
         let target_state = {
             let init_batt = self_lock.read().await.current_battery();
-            if init_batt <= Self::AFTER_SAFE_MIN_BATT || force_charge {
+            if init_batt <= Self::AFTER_SAFE_MIN_BATT || charge {
                 FlightState::Charge
             } else {
                 FlightState::Acquisition
@@ -436,7 +656,7 @@ impl FlightComputer {
                 lock.current_state
             };
         }
-        if curr_state != FlightState::Safe {
+        if curr_state != FlightState::Safe && recovery.is_none() {
             error!("State is not safe but {}", curr_state);
         }
         let cond_min_charge = (
@@ -451,7 +671,10 @@ impl FlightComputer {
             false,
         )
         .await;
-        Self::set_state_wait(self_lock, target_state).await;
+        Self::set_state_wait(Arc::clone(&self_lock), target_state).await;
+        let mut f_cont = self_lock.write().await;
+        f_cont.target_state = None;
+        f_cont.pending_recovery = None;
     }
 
     /// A small helper method which waits for the current transition phase to end.
@@ -482,12 +705,11 @@ impl FlightComputer {
     #[allow(clippy::cast_possible_wrap)]
     pub async fn get_to_comms(self_lock: Arc<RwLock<Self>>) -> DateTime<Utc> {
         if self_lock.read().await.state() == FlightState::Comms {
-            let batt_diff =
-                self_lock.read().await.current_battery() - TaskController::MIN_BATTERY_THRESHOLD;
-            let rem_t = (batt_diff / FlightState::Comms.get_charge_rate()).abs().ceil();
-            let add_t = TimeDelta::seconds(rem_t.to_num::<i64>()).min(TimeDelta::seconds(
-                TaskController::IN_COMMS_SCHED_SECS as i64,
-            ));
+            let batt = self_lock.read().await.current_battery();
+            let min_batt = TaskController::min_battery_threshold();
+            let rem_t = Self::charge_secs(FlightState::Comms, batt, min_batt);
+            let add_t = TimeDelta::seconds(rem_t as i64)
+                .min(TaskController::IN_COMMS_SCHED_DT.to_delta());
             return Utc::now() + add_t;
         }
         let charge_dt = Self::get_charge_dt_comms(&self_lock).await;
@@ -500,7 +722,7 @@ impl FlightComputer {
         } else {
             FlightComputer::set_state_wait(self_lock, FlightState::Comms).await;
         }
-        Utc::now() + TimeDelta::seconds(TaskController::IN_COMMS_SCHED_SECS as i64)
+        Utc::now() + TaskController::IN_COMMS_SCHED_DT
     }
 
     /// A helper method used to get out of [`FlightState::Comms`] and back to an operational [`FlightState`].
@@ -514,8 +736,9 @@ impl FlightComputer {
             (f_cont.state(), f_cont.current_battery())
         };
         if state == FlightState::Comms {
-            let half_batt =
-                (TaskController::MAX_BATTERY_THRESHOLD + TaskController::MIN_BATTERY_THRESHOLD) / 2;
+            let half_batt = (TaskController::max_battery_threshold()
+                + TaskController::min_battery_threshold())
+                / 2;
             if batt > half_batt {
                 FlightComputer::set_state_wait(Arc::clone(&self_lock), FlightState::Acquisition)
                     .await;
@@ -534,10 +757,10 @@ impl FlightComputer {
     pub async fn get_to_comms_t_est(self_lock: Arc<RwLock<Self>>) -> DateTime<Utc> {
         let t_time = FlightState::Charge.td_dt_to(FlightState::Comms);
         if self_lock.read().await.state() == FlightState::Comms {
-            let batt_diff =
-                self_lock.read().await.current_battery() - TaskController::MIN_BATTERY_THRESHOLD;
-            let rem_t = (batt_diff / FlightState::Comms.get_charge_rate().abs()).abs().ceil();
-            return Utc::now() + TimeDelta::seconds(rem_t.to_num::<i64>());
+            let batt = self_lock.read().await.current_battery();
+            let min_batt = TaskController::min_battery_threshold();
+            let rem_t = Self::charge_secs(FlightState::Comms, batt, min_batt);
+            return Utc::now() + TimeDelta::seconds(rem_t as i64);
         }
         let charge_dt = Self::get_charge_dt_comms(&self_lock).await;
 
@@ -548,12 +771,12 @@ impl FlightComputer {
         }
     }
 
-    /// A helper method used to perform an acceleration maneuver to get to `STATIC_ORBIT_VEL`.
+    /// A helper method used to perform an acceleration maneuver to get to an orbit velocity.
     ///
     /// # Arguments
     /// * `self_lock`: A shared `RwLock` containing the [`FlightComputer`] instance
-    pub async fn get_to_static_orbit_vel(self_lock: &Arc<RwLock<Self>>) {
-        let orbit_vel = Vec2D::from(STATIC_ORBIT_VEL);
+    /// * `orbit_vel`: The velocity of the orbit, e.g. of the current closed orbit
+    pub async fn get_to_orbit_vel(self_lock: &Arc<RwLock<Self>>, orbit_vel: Vec2D<I32F32>) {
         let (batt, vel) = {
             let f_cont = self_lock.read().await;
             (f_cont.current_battery(), f_cont.current_vel())
@@ -566,9 +789,9 @@ impl FlightComputer {
         );
         let charge_needed = {
             let acq_acc_db =
-                FlightState::Acquisition.get_charge_rate() + FlightState::ACQ_ACC_ADDITION;
+                FlightState::Acquisition.planning_rate() + FlightState::ACQ_ACC_ADDITION;
             let or_vel_corr_db = I32F32::from_num(vel_change_dt.as_secs()) * acq_acc_db;
-            TaskController::MIN_BATTERY_THRESHOLD + or_vel_corr_db.abs()
+            TaskController::min_battery_threshold() + or_vel_corr_db.abs()
         };
         log!("Getting back to orbit velocity {orbit_vel}. Minimum charge needed: {charge_needed}");
         if batt < charge_needed {
@@ -578,7 +801,10 @@ impl FlightComputer {
         if !matches!(state, FlightState::Acquisition) {
             FlightComputer::set_state_wait(Arc::clone(self_lock), FlightState::Acquisition).await;
         }
-        FlightComputer::set_vel_wait(Arc::clone(self_lock), orbit_vel, true).await;
+        let to_orbit_vel = FlightComputer::set_vel_wait(Arc::clone(self_lock), orbit_vel, true);
+        if let Err(violation) = to_orbit_vel.await {
+            error!("Could not get back to orbit velocity {orbit_vel}: {violation}.");
+        }
     }
 
     /// A helper method calculating the charge difference for a transition to `FlightState::Comms`.
@@ -589,10 +815,25 @@ impl FlightComputer {
     /// # Returns
     /// A `u64` resembling the necessary number of charging seconds
     async fn get_charge_dt_comms(self_lock: &Arc<RwLock<Self>>) -> u64 {
-        let batt_diff = (self_lock.read().await.current_battery()
-            - TaskController::MIN_COMMS_START_CHARGE)
-            .min(I32F32::zero());
-        (-batt_diff / FlightState::Charge.get_charge_rate()).ceil().to_num::<u64>()
+        let batt = self_lock.read().await.current_battery();
+        if batt >= TaskController::MIN_COMMS_START_CHARGE {
+            return 0;
+        }
+        Self::charge_secs(FlightState::Charge, batt, TaskController::MIN_COMMS_START_CHARGE)
+    }
+
+    /// Computes the whole seconds needed in `state` to get from one battery level to another.
+    ///
+    /// # Arguments
+    /// * `state`: The [`FlightState`] whose charge curve is followed
+    /// * `from`: The initial battery level
+    /// * `to`: The target battery level
+    ///
+    /// # Returns
+    /// The rounded up number of seconds, or `0` if `to` is never reached in `state`
+    fn charge_secs(state: FlightState, from: I32F32, to: I32F32) -> u64 {
+        let dt = state.charge_dt(from, to);
+        if dt == I32F32::MAX { 0 } else { dt.ceil().to_num::<u64>() }
     }
 
     /// A helper method used to charge to the maximum battery threshold.
@@ -622,9 +863,15 @@ impl FlightComputer {
         } else {
             FlightComputer::set_state_wait(Arc::clone(self_lock), FlightState::Charge).await;
         }
+        // The battery may have passed the target during the transition
         let batt = self_lock.read().await.current_battery();
-        let dt = (target_batt - batt) / FlightState::Charge.get_charge_rate();
-        Self::wait_for_duration(Duration::from_secs(dt.to_num::<u64>()), false).await;
+        if batt >= target_batt {
+            return;
+        }
+        let dt = Self::charge_secs(FlightState::Charge, batt, target_batt);
+        if dt > 0 {
+            Self::wait_for_duration(Duration::from_secs(dt), false).await;
+        }
     }
 
     /// Transitions the satellite to a new operational state and waits for transition completion.
@@ -633,17 +880,39 @@ impl FlightComputer {
     /// - `self_lock`: A `RwLock<Self>` reference to the active flight computer.
     /// - `new_state`: The target operational state.
     pub async fn set_state_wait(self_lock: Arc<RwLock<Self>>, new_state: FlightState) {
+        let cmd_guard = Self::lock_commands(&self_lock).await;
+        if let Err(reason) = Self::try_set_state_wait(self_lock, new_state, &cmd_guard).await {
+            fatal!("State can't be changed to {new_state}: {reason}");
+        }
+    }
+
+    /// Transitions the satellite to a new operational state like
+    /// [`FlightComputer::set_state_wait`] for a caller already holding the command lock.
+    ///
+    /// # Arguments
+    /// - `self_lock`: A `RwLock<Self>` reference to the active flight computer.
+    /// - `new_state`: The target operational state.
+    /// - `_cmd_guard`: The guard of the command lock, see [`FlightComputer::lock_commands`].
+    ///
+    /// # Errors
+    /// - A [`CommandRejected`] if `new_state` is no legal target state or the satellite is in
+    ///   `Transition`.
+    pub(crate) async fn try_set_state_wait(
+        self_lock: Arc<RwLock<Self>>,
+        new_state: FlightState,
+        _cmd_guard: &OwnedMutexGuard<()>,
+    ) -> Result<(), CommandRejected> {
         let init_state = { self_lock.read().await.current_state };
         if new_state == init_state {
             log!("State already set to {new_state}");
-            return;
+            return Ok(());
         } else if !Self::LEGAL_TARGET_STATES.contains(&new_state) {
-            fatal!("State {new_state} is not a legal target state");
+            return Err(CommandRejected::IllegalTargetState(new_state));
         } else if init_state == FlightState::Transition {
-            fatal!(" State cant be changed when in {init_state}");
+            return Err(CommandRejected::WrongState(init_state));
         }
         self_lock.write().await.target_state = Some(new_state);
-        self_lock.read().await.set_state(new_state).await;
+        Self::set_state(&self_lock, new_state).await;
 
         let transition_t = init_state.dt_to(new_state);
 
@@ -652,44 +921,46 @@ impl FlightComputer {
             |cont: &FlightComputer| cont.state() == new_state,
             format!("State equals {new_state}"),
         );
-        Self::wait_for_condition(
-            &self_lock,
-            cond,
-            Self::DEF_COND_TO,
-            Self::DEF_COND_PI,
-            false,
-        )
-        .await;
+        Self::reconcile(&self_lock, ControlCommand::State(new_state), cond, false).await;
         self_lock.write().await.target_state = None;
+        Ok(())
     }
 
     /// Adjusts the velocity of the satellite and waits until the target velocity is reached.
     ///
+    /// The velocity is checked against the [`SafetyEnvelope`] first and may be clamped to its
+    /// speed limit.
+    ///
     /// # Arguments
     /// - `self_lock`: A `RwLock<Self>` reference to the active flight computer.
     /// - `new_vel`: The target velocity vector.
-    pub async fn set_vel_wait(self_lock: Arc<RwLock<Self>>, new_vel: Vec2D<I32F32>, mute: bool) {
-        let (current_state, current_vel) = {
-            let f_cont_read = self_lock.read().await;
-            (f_cont_read.state(), f_cont_read.current_vel())
+    ///
+    /// # Errors
+    /// - An [`EnvelopeViolation`] if the velocity was rejected and not commanded.
+    pub async fn set_vel_wait(
+        self_lock: Arc<RwLock<Self>>,
+        new_vel: Vec2D<I32F32>,
+        mute: bool,
+    ) -> Result<(), EnvelopeViolation> {
+        let (current_vel, vel) = {
+            let f_cont = self_lock.read().await;
+            let (state, vel, fuel) = (f_cont.state(), f_cont.current_vel(), f_cont.fuel_left());
+            (vel, SafetyEnvelope::check_vel(state, vel, new_vel, fuel)?)
         };
-        if current_state != FlightState::Acquisition {
-            fatal!("Velocity cant be changed in state {current_state}");
-        }
         let vel_change_dt = Duration::from_secs_f32(
-            (new_vel.euclid_distance(&current_vel) / Self::ACC_CONST).to_num::<f32>(),
+            (vel.euclid_distance(&current_vel) / Self::ACC_CONST).to_num::<f32>(),
         );
-        self_lock.read().await.set_vel(new_vel, mute).await;
+        Self::set_vel(&self_lock, vel, mute).await?;
         if vel_change_dt.as_secs() > 0 {
             Self::wait_for_duration(vel_change_dt, mute).await;
         }
-        let comp_new_vel = Self::round_vel_expand(new_vel);
+        let comp_new_vel = Self::round_vel_expand(vel);
         let cond = (
             |cont: &FlightComputer| Self::round_vel_expand(cont.current_vel()) == comp_new_vel,
-            format!("Vel (Scaled) equals {new_vel}"),
+            format!("Vel (Scaled) equals {vel}"),
         );
-        Self::wait_for_condition(&self_lock, cond, Self::DEF_COND_TO, Self::DEF_COND_PI, mute)
-            .await;
+        Self::reconcile(&self_lock, ControlCommand::Vel(vel), cond, mute).await;
+        Ok(())
     }
 
     /// Adjusts the satellite's camera angle and waits until the target angle is reached.
@@ -704,59 +975,147 @@ impl FlightComputer {
     ///   If not, it panics with a fatal error.
     /// - Sets the new angle and waits until the system confirms it has been applied.
     pub async fn set_angle_wait(self_lock: Arc<RwLock<Self>>, new_angle: CameraAngle) {
+        let cmd_guard = Self::lock_commands(&self_lock).await;
+        if let Err(reason) = Self::try_set_angle_wait(self_lock, new_angle, &cmd_guard).await {
+            fatal!("Angle can't be changed to {new_angle}: {reason}");
+        }
+    }
+
+    /// Adjusts the camera angle like [`FlightComputer::set_angle_wait`] for a caller already
+    /// holding the command lock.
+    ///
+    /// # Arguments
+    /// - `self_lock`: A `RwLock<Self>` reference to the active flight computer.
+    /// - `new_angle`: The target camera angle.
+    /// - `_cmd_guard`: The guard of the command lock, see [`FlightComputer::lock_commands`].
+    ///
+    /// # Errors
+    /// - [`CommandRejected::WrongState`] if the satellite is not in `Acquisition`.
+    pub(crate) async fn try_set_angle_wait(
+        self_lock: Arc<RwLock<Self>>,
+        new_angle: CameraAngle,
+        _cmd_guard: &OwnedMutexGuard<()>,
+    ) -> Result<(), CommandRejected> {
         let (current_angle, current_state) = {
             let f_cont_read = self_lock.read().await;
             (f_cont_read.current_angle, f_cont_read.state())
         };
         if current_angle == new_angle {
             log!("Angle already set to {new_angle}");
-            return;
+            return Ok(());
         }
         if current_state != FlightState::Acquisition {
-            fatal!("Angle cant be changed in state {current_state}");
+            return Err(CommandRejected::WrongState(current_state));
         }
 
-        self_lock.read().await.set_angle(new_angle).await;
+        Self::set_angle(&self_lock, new_angle).await;
         let cond = (
             |cont: &FlightComputer| cont.current_angle() == new_angle,
             format!("Lens equals {new_angle}"),
         );
-        Self::wait_for_condition(
-            &self_lock,
-            cond,
-            Self::DEF_COND_TO,
-            Self::DEF_COND_PI,
-            false,
-        )
-        .await;
+        Self::reconcile(&self_lock, ControlCommand::Angle(new_angle), cond, false).await;
+        Ok(())
     }
 
     /// Executes a sequence of thruster burns that affect the trajectory of MELVIN.
     ///
+    /// The whole sequence is checked against the [`SafetyEnvelope`] before the first velocity
+    /// change. If `abort` is cancelled before the last velocity change, the burn stops and the
+    /// completed velocity changes are undone in reverse order, ramping back to the pre-burn
+    /// velocity, i.e. the velocity of the closed orbit the burn started from.
+    ///
     /// # Arguments
     /// - `self_lock`: A `RwLock<Self>` reference to the active flight computer.
     /// - `burn_sequence`: A reference to the sequence of executed thruster burns.
-    pub async fn execute_burn(self_lock: Arc<RwLock<Self>>, burn: &BurnSequence) {
+    /// - `abort`: A token aborting the burn when cancelled.
+    ///
+    /// # Errors
+    /// Returns a [`BurnAborted`] record once an aborted burn was rolled back, or if the burn
+    /// was rejected by the [`SafetyEnvelope`].
+    ///
+    /// # Cancel safety
+    /// Manual commands are unblocked again if the returned future is dropped mid-burn.
+    pub async fn execute_burn(
+        self_lock: Arc<RwLock<Self>>,
+        burn: &BurnSequence,
+        abort: &CancellationToken,
+    ) -> Result<(), BurnAborted> {
         let burn_start = Utc::now();
-        for vel_change in burn.sequence_vel() {
-            let st = tokio::time::Instant::now();
-            let dt = Duration::from_secs(1);
-            FlightComputer::set_vel_wait(Arc::clone(&self_lock), *vel_change, true).await;
-            let el = st.elapsed();
-            if el < dt {
-                tokio::time::sleep(dt).await;
+        let steps_total = burn.sequence_vel().len();
+        // Manual commands checked before the burn start are done before it is marked active
+        let cmd_guard = Self::lock_commands(&self_lock).await;
+        let rollback_vel = {
+            let mut f_cont = self_lock.write().await;
+            let (state, vel, fuel) = (f_cont.state(), f_cont.current_vel(), f_cont.fuel_left());
+            if let Err(violation) = SafetyEnvelope::check_burn(state, vel, burn, fuel) {
+                let (steps, pos) = ((0, steps_total), f_cont.current_pos());
+                return Err(BurnAborted::rejected(burn_start, steps, pos, vel, violation));
+            }
+            f_cont.burn_active = true;
+            vel
+        };
+        let _burn_guard = BurnActiveGuard(Arc::clone(&self_lock));
+        drop(cmd_guard);
+        for (steps_done, vel_change) in burn.sequence_vel().iter().enumerate() {
+            let step = async {
+                let st = tokio::time::Instant::now();
+                let dt = Duration::from_secs(1);
+                FlightComputer::set_vel_wait(Arc::clone(&self_lock), *vel_change, true).await?;
+                let el = st.elapsed();
+                if el < dt {
+                    tokio::time::sleep(dt).await;
+                }
+                Ok(())
+            };
+            tokio::select! {
+                res = step => if let Err(violation) = res {
+                    let (pos, vel) = {
+                        let mut f_cont = self_lock.write().await;
+                        f_cont.burn_active = false;
+                        (f_cont.current_pos(), f_cont.current_vel())
+                    };
+                    let steps = (steps_done, steps_total);
+                    return Err(BurnAborted::rejected(burn_start, steps, pos, vel, violation));
+                },
+                () = abort.cancelled() => {
+                    let (pos, vel) = {
+                        let f_cont = self_lock.read().await;
+                        (f_cont.current_pos(), f_cont.current_vel())
+                    };
+                    log_burn!("Aborting burn sequence, ramping back to {rollback_vel:.2}.");
+                    for step_vel in BurnAborted::rollback_path(burn, steps_done, rollback_vel) {
+                        let rollback =
+                            FlightComputer::set_vel_wait(Arc::clone(&self_lock), step_vel, false);
+                        if let Err(violation) = rollback.await {
+                            error!("Could not ramp back to {step_vel:.2}: {violation}.");
+                            break;
+                        }
+                    }
+                    self_lock.write().await.burn_active = false;
+                    let steps = (steps_done, steps_total);
+                    return Err(BurnAborted::record(burn_start, steps, pos, vel, rollback_vel));
+                }
             }
         }
         let target_pos = burn.sequence_pos().last().unwrap();
         let target_vel = burn.sequence_vel().last().unwrap();
         let (pos, vel) = {
-            let f_cont = self_lock.read().await;
+            let mut f_cont = self_lock.write().await;
+            f_cont.burn_active = false;
             (f_cont.current_pos(), f_cont.current_vel())
         };
         let burn_dt = (Utc::now() - burn_start).num_seconds();
         log_burn!(
             "Burn sequence finished after {burn_dt}s! Position: {pos}, Velocity: {vel:.2}, expected Position: {target_pos:.0}, expected Velocity: {target_vel:.2}."
         );
+        MISSION_JOURNAL.record(JournalEvent::BurnExecuted {
+            duration_s: burn_dt,
+            pos,
+            vel,
+            target_pos: *target_pos,
+            target_vel: *target_vel,
+        });
+        Ok(())
     }
 
     /// Executes an orbit return maneuver in a loop until the current position is recognized and assigned an orbit index.
@@ -764,7 +1123,13 @@ impl FlightComputer {
     /// # Arguments
     /// * `self_lock`: A shared `RwLock` containing the [`FlightComputer`] instance
     /// * `c_o`: A shared `RwLock` containing the [`ClosedOrbit`] instance
-    pub async fn or_maneuver(self_lock: Arc<RwLock<Self>>, c_o: Arc<RwLock<ClosedOrbit>>) -> usize {
+    ///
+    /// # Returns
+    /// * The new orbit index, `None` if a correction was rejected by the [`SafetyEnvelope`].
+    pub async fn or_maneuver(
+        self_lock: Arc<RwLock<Self>>,
+        c_o: Arc<RwLock<ClosedOrbit>>,
+    ) -> Option<usize> {
         if self_lock.read().await.state() != FlightState::Acquisition {
             FlightComputer::set_state_wait(Arc::clone(&self_lock), FlightState::Acquisition).await;
         }
@@ -783,17 +1148,23 @@ impl FlightComputer {
             log_burn!(
                 "Correction velocity is {corr_v:.2}, ramping by {dv:.2}. Hold time will be {h_dt}s."
             );
-            FlightComputer::set_vel_wait(Arc::clone(&self_lock), corr_v, false).await;
-            if h_dt > 0 {
-                FlightComputer::wait_for_duration(Duration::from_secs(h_dt), false).await;
+            let correction = async {
+                FlightComputer::set_vel_wait(Arc::clone(&self_lock), corr_v, false).await?;
+                if h_dt > 0 {
+                    FlightComputer::wait_for_duration(Duration::from_secs(h_dt), false).await;
+                }
+                FlightComputer::set_vel_wait(Arc::clone(&self_lock), vel, false).await
+            };
+            if let Err(violation) = correction.await {
+                error!("Orbit Return Deviation Compensation stopped: {violation}.");
+                return None;
             }
-            FlightComputer::set_vel_wait(Arc::clone(&self_lock), vel, false).await;
             pos = self_lock.read().await.current_pos();
         }
         let dt = (Utc::now() - start).num_seconds();
         let entry_i = o_unlocked.get_i(pos).unwrap();
         info!("Orbit Return Deviation Compensation finished in {dt}s. New Orbit Index: {entry_i}");
-        entry_i
+        Some(entry_i)
     }
 
     /// Helper method calculating the maximum charge needed for an orbit return maneuver.
@@ -801,7 +1172,7 @@ impl FlightComputer {
     /// # Returns
     /// * An `I32F32`, the maximum battery level
     pub fn max_or_maneuver_charge() -> I32F32 {
-        let acq_db = FlightState::Acquisition.get_charge_rate();
+        let acq_db = FlightState::Acquisition.planning_rate();
         let acq_acc_db = acq_db + FlightState::ACQ_ACC_ADDITION;
         Self::MAX_OR_ACQ_ACC_TIME * acq_acc_db + Self::MAX_OR_ACQ_TIME * acq_db
     }
@@ -840,8 +1211,10 @@ impl FlightComputer {
             let f_cont = self_lock.read().await;
             (f_cont.current_vel(), f_cont.state())
         };
-        if state == FlightState::Acquisition {
-            FlightComputer::set_vel_wait(Arc::clone(&self_lock), vel, true).await;
+        if state == FlightState::Acquisition
+            && let Err(violation) = FlightComputer::set_vel_wait(self_lock, vel, true).await
+        {
+            warn!("Could not stop the ongoing velocity change: {violation}.");
         }
     }
 
@@ -858,13 +1231,13 @@ impl FlightComputer {
     ) {
         log!("Starting turn for second target");
         let start = Utc::now();
-        let pos = self_lock.read().await.current_pos();
+        let pos = self_lock.read().await.estimated_pos_now();
         let mut last_to_target = pos.unwrapped_to(&target);
         let ticker = 0;
         loop {
             let (pos, vel) = {
                 let f_cont = self_lock.read().await;
-                (f_cont.current_pos(), f_cont.current_vel())
+                (f_cont.estimated_pos_now(), f_cont.current_vel())
             };
 
             let to_target = pos.unwrapped_to(&target);
@@ -873,7 +1246,10 @@ impl FlightComputer {
                 let wait_dt = dt.to_num::<u64>()
                     + TaskController::ZO_IMAGE_FIRST_DEL.num_seconds().to_u64().unwrap();
                 log!("Overshot target! Holding velocity change and waiting for 5s!");
-                FlightComputer::set_vel_wait(Arc::clone(&self_lock), vel, true).await;
+                let hold = FlightComputer::set_vel_wait(Arc::clone(&self_lock), vel, true);
+                if let Err(violation) = hold.await {
+                    warn!("Could not hold the velocity: {violation}.");
+                }
                 FlightComputer::wait_for_duration(Duration::from_secs(wait_dt), false).await;
                 return;
             }
@@ -897,7 +1273,10 @@ impl FlightComputer {
                 log!("Turning timeout after {turn_dt}s with remaining DX: {dx:.2} and dt {dt:2}s");
                 FlightComputer::stop_ongoing_burn(Arc::clone(&self_lock)).await;
             }
-            self_lock.write().await.set_vel(new_vel, true).await;
+            if Self::set_vel(&self_lock, new_vel, true).await.is_err() {
+                log!("Aborting turn for second target after rejected velocity command.");
+                return;
+            }
             tokio::time::sleep(Duration::from_secs(1)).await;
         }
     }
@@ -922,7 +1301,7 @@ impl FlightComputer {
         let max_speed = lens.get_max_speed();
         let detumble_start = Utc::now();
 
-        let start_pos = self_lock.read().await.current_pos();
+        let start_pos = self_lock.read().await.estimated_pos_now();
         let mut to_target = start_pos.to(&target);
         let mut dt;
         let mut dx;
@@ -931,7 +1310,7 @@ impl FlightComputer {
         loop {
             let (pos, vel) = {
                 let f_locked = self_lock.read().await;
-                (f_locked.current_pos(), f_locked.current_vel())
+                (f_locked.estimated_pos_now(), f_locked.current_vel())
             };
             to_target = pos.to(&target);
 
@@ -972,12 +1351,21 @@ impl FlightComputer {
                 );
                 FlightComputer::stop_ongoing_burn(Arc::clone(&self_lock)).await;
                 FlightComputer::set_angle_wait(Arc::clone(&self_lock), lens).await;
-                return (Utc::now() + TimeDelta::seconds(dt.to_num::<i64>()), target);
+                let (hit_pos, hit_vel) = {
+                    let f_locked = self_lock.read().await;
+                    (f_locked.estimated_pos_now(), f_locked.current_vel())
+                };
+                let hit_ms = hit_pos.to(&target).abs() / hit_vel.abs() * I32F32::from_num(1000);
+                return (Utc::now() + TimeDelta::milliseconds(hit_ms.to_num::<i64>()), target);
             }
             if overspeed {
-                FlightComputer::set_vel_wait(Arc::clone(&self_lock), new_vel, true).await;
+                let braking = FlightComputer::set_vel_wait(Arc::clone(&self_lock), new_vel, true);
+                if let Err(violation) = braking.await {
+                    warn!("Could not brake to {new_vel:.2}: {violation}.");
+                }
             } else {
-                self_lock.write().await.set_vel(new_vel, true).await;
+                // A rejection is logged, the next detumbling step commands a corrected velocity
+                Self::set_vel(&self_lock, new_vel, true).await.ok();
             }
             tokio::time::sleep(Duration::from_secs(1)).await;
         }
@@ -996,81 +1384,158 @@ impl FlightComputer {
     ///
     /// # Arguments
     /// * A mutable reference to the `FlightComputer` instance
-    pub async fn update_observation(&mut self) {
-        if let Ok(obs) = (ObservationRequest {}.send_request(&self.request_client).await) {
-            self.current_pos =
-                Vec2D::from((I32F32::from_num(obs.pos_x()), I32F32::from_num(obs.pos_y())));
-            self.current_vel =
-                Vec2D::from((I32F32::from_num(obs.vel_x()), I32F32::from_num(obs.vel_y())));
-            self.current_state = FlightState::from(obs.state());
-            self.current_angle = CameraAngle::from(obs.angle());
-            self.last_observation_timestamp = obs.timestamp();
-            self.current_battery =
-                I32F32::from_num(obs.battery()).clamp(Self::MIN_0, Self::MAX_100);
-            self.max_battery =
-                I32F32::from_num(obs.max_battery()).clamp(Self::MIN_0, Self::MAX_100);
-            self.fuel_left = I32F32::from_num(obs.fuel()).clamp(Self::MIN_0, Self::MAX_100);
-        } else {
-            error!("Unnoticed HTTP Error in updateObservation()");
+    ///
+    /// # Returns
+    /// * `true` if a new observation was applied.
+    pub async fn update_observation(&mut self) -> bool {
+        match (ObservationRequest {}).send_request(&self.request_client).await {
+            Ok(obs) => {
+                self.apply_observation(&obs);
+                true
+            }
+            Err(e) => {
+                error!("HTTP Error in update_observation() after retries: {e:?}");
+                false
+            }
+        }
+    }
+
+    /// Updates the satellite's internal fields from an already received observation,
+    /// e.g. one pushed by the backend observation stream.
+    ///
+    /// # Arguments
+    /// * `obs` – The observation received from the backend.
+    pub(crate) fn apply_observation(&mut self, obs: &ObservationResponse) {
+        self.current_pos =
+            Vec2D::from((I32F32::from_num(obs.pos_x()), I32F32::from_num(obs.pos_y())));
+        let vel = Vec2D::from((I32F32::from_num(obs.vel_x()), I32F32::from_num(obs.vel_y())));
+        if vel != self.current_vel {
+            TURN_CACHE.invalidate_unless(Self::round_vel(vel).0);
+        }
+        self.current_vel = vel;
+        if !self.dead_reckoning.fuse(self.current_pos, vel, obs.timestamp()) {
+            warn!("Ignoring outdated observation from {} for dead reckoning.", obs.timestamp());
+        } else if self.dead_reckoning.innovation() > Self::DR_MAX_INNOVATION {
+            let innovation = self.dead_reckoning.innovation();
+            warn!("Dead reckoning was off by {innovation:.2}px at the last observation.");
+        }
+        self.current_state = FlightState::from(obs.state());
+        self.current_angle = CameraAngle::from(obs.angle());
+        self.last_observation_timestamp = obs.timestamp();
+        self.current_battery = I32F32::from_num(obs.battery()).clamp(Self::MIN_0, Self::MAX_100);
+        self.max_battery = I32F32::from_num(obs.max_battery()).clamp(Self::MIN_0, Self::MAX_100);
+        self.fuel_left = I32F32::from_num(obs.fuel()).clamp(Self::MIN_0, Self::MAX_100);
+        let fitted = self.charge_estimator.observe(
+            self.last_observation_timestamp,
+            self.current_state,
+            self.current_battery,
+            self.max_battery,
+            self.current_vel,
+        );
+        if let Some((state, curve)) = fitted {
+            let (old, new) = (state.planning_rate(), curve.min_rate());
+            if (new - old).abs() > Self::CHARGE_RATE_LOG_DEV {
+                log!("Calibrated {state} charge rate from {old:.4} to {new:.4} per second.");
+            }
+            state.set_charge_curve(curve);
         }
     }
 
     /// Sets the satellite’s `FlightState`.
     ///
+    /// The request is sent without holding the lock on the flight computer.
+    ///
     /// # Arguments
+    /// - `self_lock`: A `RwLock<Self>` reference to the active flight computer.
     /// - `new_state`: The new operational state.
-    async fn set_state(&self, new_state: FlightState) {
-        let req = ControlSatelliteRequest {
-            vel_x: self.current_vel.x().to_f64().unwrap(),
-            vel_y: self.current_vel.y().to_f64().unwrap(),
-            camera_angle: self.current_angle.into(),
-            state: new_state.into(),
+    async fn set_state(self_lock: &RwLock<Self>, new_state: FlightState) {
+        let (req, client) = {
+            let f_cont = self_lock.read().await;
+            let req = ControlSatelliteRequest {
+                vel_x: f_cont.current_vel.x().to_f64().unwrap(),
+                vel_y: f_cont.current_vel.y().to_f64().unwrap(),
+                camera_angle: f_cont.current_angle.into(),
+                state: new_state.into(),
+            };
+            (req, f_cont.client())
         };
-        if req.send_request(&self.request_client).await.is_ok() {
-            info!("State change started to {new_state}");
-        } else {
-            error!("Unnoticed HTTP Error in set_state()");
+        match req.send_request(&client).await {
+            Ok(_) => info!("State change started to {new_state}"),
+            Err(e) => error!("HTTP Error in set_state() after retries: {e:?}"),
         }
     }
 
     /// Sets the satellite’s velocity. The input velocity should only have two decimal places after comma.
     ///
+    /// Velocities rejected by the [`SafetyEnvelope`] are logged and not commanded. The request
+    /// is sent without holding the lock on the flight computer, which is only locked again to
+    /// record the commanded velocity.
+    ///
     /// # Arguments
+    /// - `self_lock`: A `RwLock<Self>` reference to the active flight computer.
     /// - `new_vel`: The new velocity.
-    async fn set_vel(&self, new_vel: Vec2D<I32F32>, mute: bool) {
-        let (vel, _) = Self::round_vel(new_vel);
-        let req = ControlSatelliteRequest {
-            vel_x: vel.x().to_f64().unwrap(),
-            vel_y: vel.y().to_f64().unwrap(),
-            camera_angle: self.current_angle.into(),
-            state: self.current_state.into(),
+    ///
+    /// # Errors
+    /// - An [`EnvelopeViolation`] if the velocity was rejected and not commanded.
+    async fn set_vel(
+        self_lock: &RwLock<Self>,
+        new_vel: Vec2D<I32F32>,
+        mute: bool,
+    ) -> Result<(), EnvelopeViolation> {
+        let (vel, req, client) = {
+            let f_cont = self_lock.read().await;
+            let (state, fuel) = (f_cont.current_state, f_cont.fuel_left);
+            let checked = SafetyEnvelope::check_vel(state, f_cont.current_vel, new_vel, fuel);
+            let (vel, _) = match checked {
+                Ok(vel) => Self::round_vel(vel),
+                Err(violation) => {
+                    warn!("Rejected velocity command {new_vel:.2}: {violation}.");
+                    return Err(violation);
+                }
+            };
+            let req = ControlSatelliteRequest {
+                vel_x: vel.x().to_f64().unwrap(),
+                vel_y: vel.y().to_f64().unwrap(),
+                camera_angle: f_cont.current_angle.into(),
+                state: f_cont.current_state.into(),
+            };
+            (vel, req, f_cont.client())
         };
 
-        if req.send_request(&self.request_client).await.is_ok() {
-            if !mute {
-                info!("Velocity change commanded to [{}, {}]", vel.x(), vel.y());
+        match req.send_request(&client).await {
+            Ok(_) => {
+                self_lock.write().await.dead_reckoning.command_vel(vel, Utc::now());
+                if !mute {
+                    info!("Velocity change commanded to [{}, {}]", vel.x(), vel.y());
+                }
             }
-        } else {
-            error!("Unnoticed HTTP Error in set_state()");
+            Err(e) => error!("HTTP Error in set_vel() after retries: {e:?}"),
         }
+        Ok(())
     }
 
     /// Sets the satellite’s `CameraAngle`
     ///
+    /// The request is sent without holding the lock on the flight computer.
+    ///
     /// # Arguments
+    /// - `self_lock`: A `RwLock<Self>` reference to the active flight computer.
     /// - `new_angle`: The new Camera Angle.
-    async fn set_angle(&self, new_angle: CameraAngle) {
-        let req = ControlSatelliteRequest {
-            vel_x: self.current_vel.x().to_f64().unwrap(),
-            vel_y: self.current_vel.y().to_f64().unwrap(),
-            camera_angle: new_angle.into(),
-            state: self.current_state.into(),
+    async fn set_angle(self_lock: &RwLock<Self>, new_angle: CameraAngle) {
+        let (req, client) = {
+            let f_cont = self_lock.read().await;
+            let req = ControlSatelliteRequest {
+                vel_x: f_cont.current_vel.x().to_f64().unwrap(),
+                vel_y: f_cont.current_vel.y().to_f64().unwrap(),
+                camera_angle: new_angle.into(),
+                state: f_cont.current_state.into(),
+            };
+            (req, f_cont.client())
         };
 
-        if req.send_request(&self.request_client).await.is_ok() {
-            info!("Angle change commanded to {new_angle}");
-        } else {
-            error!("Unnoticed HTTP Error in set_state()");
+        match req.send_request(&client).await {
+            Ok(_) => info!("Angle change commanded to {new_angle}"),
+            Err(e) => error!("HTTP Error in set_angle() after retries: {e:?}"),
         }
     }
 
@@ -1096,7 +1561,83 @@ impl FlightComputer {
     /// # Returns
     /// - An `I32F32` representing the satellite’s predicted battery level
     pub fn batt_in_dt(&self, dt: TimeDelta) -> I32F32 {
-        self.current_battery
-            + (self.current_state.get_charge_rate() * I32F32::from_num(dt.num_seconds()))
+        self.current_state.batt_in_dt_from(self.current_battery, dt)
+    }
+}
+
+/// Clears the `burn_active` flag of a [`FlightComputer`] when dropped, so that a burn sequence
+/// whose future is dropped mid-burn does not block manual commands for good.
+struct BurnActiveGuard(Arc<RwLock<FlightComputer>>);
+
+impl Drop for BurnActiveGuard {
+    fn drop(&mut self) {
+        if let Ok(mut f_cont) = self.0.try_write() {
+            f_cont.burn_active = false;
+        } else if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            let f_cont_lock = Arc::clone(&self.0);
+            handle.spawn(async move { f_cont_lock.write().await.burn_active = false });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http_handler::http_client::HTTPClient;
+
+    #[tokio::test]
+    async fn test_dropped_burn_unblocks_manual_commands() {
+        let f_cont = FlightComputer::new(Arc::new(HTTPClient::simulated())).await;
+        let f_cont_lock = Arc::new(RwLock::new(f_cont));
+        f_cont_lock.write().await.burn_active = true;
+        let cmd = ManualCommand::AbortMode;
+        let rejected = f_cont_lock.read().await.check_manual_command(cmd);
+        assert_eq!(rejected, Err(CommandRejected::BurnInProgress));
+
+        let burn = async {
+            let _burn_guard = BurnActiveGuard(Arc::clone(&f_cont_lock));
+            std::future::pending::<()>().await;
+        };
+        assert!(tokio::time::timeout(Duration::from_millis(10), burn).await.is_err());
+        assert!(!f_cont_lock.read().await.burn_active());
+        assert_eq!(f_cont_lock.read().await.check_manual_command(cmd), Ok(cmd));
+
+        // A guard dropped while the flight computer is locked clears the flag afterward.
+        f_cont_lock.write().await.burn_active = true;
+        let held = f_cont_lock.read().await;
+        drop(BurnActiveGuard(Arc::clone(&f_cont_lock)));
+        assert!(held.burn_active());
+        drop(held);
+        tokio::task::yield_now().await;
+        assert!(!f_cont_lock.read().await.burn_active());
+    }
+
+    #[tokio::test]
+    async fn test_manual_commands_are_serialized_and_rejected() {
+        let f_cont = FlightComputer::new(Arc::new(HTTPClient::simulated())).await;
+        let f_cont_lock = Arc::new(RwLock::new(f_cont));
+        f_cont_lock.write().await.current_state = FlightState::Transition;
+        let cmd_guard = FlightComputer::lock_commands(&f_cont_lock).await;
+        let lock = Arc::clone(&f_cont_lock);
+        let res = FlightComputer::try_set_state_wait(lock, FlightState::Charge, &cmd_guard).await;
+        assert_eq!(res, Err(CommandRejected::WrongState(FlightState::Transition)));
+        let lock = Arc::clone(&f_cont_lock);
+        let res = FlightComputer::try_set_state_wait(lock, FlightState::Safe, &cmd_guard).await;
+        assert_eq!(res, Err(CommandRejected::IllegalTargetState(FlightState::Safe)));
+
+        f_cont_lock.write().await.current_state = FlightState::Charge;
+        f_cont_lock.write().await.current_angle = CameraAngle::Normal;
+        let lock = Arc::clone(&f_cont_lock);
+        let res = FlightComputer::try_set_angle_wait(lock, CameraAngle::Wide, &cmd_guard).await;
+        assert_eq!(res, Err(CommandRejected::WrongState(FlightState::Charge)));
+
+        // Scheduled switches wait until the manual command releases the command lock.
+        let lock = Arc::clone(&f_cont_lock);
+        let scheduled = FlightComputer::set_state_wait(lock, FlightState::Charge);
+        tokio::pin!(scheduled);
+        let pending = tokio::time::timeout(Duration::from_millis(10), &mut scheduled).await;
+        assert!(pending.is_err());
+        drop(cmd_guard);
+        assert!(tokio::time::timeout(Duration::from_millis(10), scheduled).await.is_ok());
     }
 }
@@ -1,8 +1,13 @@
+use super::charge_curve::ChargeCurve;
 use crate::{DT_0, fatal};
 use chrono::TimeDelta;
 use fixed::types::I32F32;
 use num::Zero;
-use std::{collections::HashMap, sync::LazyLock, time::Duration};
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, RwLock},
+    time::Duration,
+};
 use strum_macros::Display;
 
 /// Represents the various states of MELVINs flight system.
@@ -18,7 +23,7 @@ use strum_macros::Display;
 /// - `Charge`: State where the system is primarily charging its batteries.
 /// - `Comms`: State where the system is communicating through the high-gain antenna to receive beacon pings.
 /// - `Safe`: A safe mode, typically activated in the event of an anomaly or low power.
-#[derive(Debug, Display, PartialEq, Eq, Clone, Copy, Hash)]
+#[derive(Debug, Display, PartialEq, Eq, Clone, Copy, Hash, serde::Serialize)]
 pub enum FlightState {
     Charge = 0,
     Acquisition = 1,
@@ -51,11 +56,61 @@ impl FlightState {
         }
     }
 
+    /// Returns the [`ChargeCurve`] used for battery predictions in this flight state.
+    ///
+    /// If no curve was configured via `set_charge_curve`, a constant curve with
+    /// the nominal `get_charge_rate()` is returned.
+    pub fn charge_curve(self) -> ChargeCurve {
+        CHARGE_CURVES
+            .read()
+            .unwrap()
+            .get(&self)
+            .cloned()
+            .unwrap_or_else(|| ChargeCurve::constant(self.get_charge_rate()))
+    }
+
+    /// Configures the [`ChargeCurve`] for this flight state, e.g. after fitting it to telemetry.
+    ///
+    /// # Arguments
+    /// - `curve`: The new charge curve.
+    pub fn set_charge_curve(self, curve: ChargeCurve) {
+        CHARGE_CURVES.write().unwrap().insert(self, curve);
+    }
+
+    /// Returns the conservative charge rate of the configured curve for maneuver planning,
+    /// i.e. the slowest charging or fastest discharging rate over all battery levels.
+    pub fn planning_rate(self) -> I32F32 { self.charge_curve().min_rate() }
+
+    /// Returns the charge rate at a given battery level according to the configured curve.
+    pub fn charge_rate_at(self, batt: I32F32) -> I32F32 { self.charge_curve().rate_at(batt) }
+
+    /// Predicts the battery level after staying in this flight state for `dt`.
+    ///
+    /// # Arguments
+    /// - `batt`: The initial battery level.
+    /// - `dt`: The time spent in this flight state.
+    pub fn batt_in_dt_from(self, batt: I32F32, dt: TimeDelta) -> I32F32 {
+        self.charge_curve().batt_after(batt, dt.num_seconds())
+    }
+
+    /// Computes the seconds needed in this flight state to get from one battery level to another.
+    ///
+    /// # Arguments
+    /// - `from`: The initial battery level.
+    /// - `to`: The target battery level.
+    ///
+    /// # Returns
+    /// The (fractional) number of seconds, or `I32F32::MAX` if `to` is never reached.
+    pub fn charge_dt(self, from: I32F32, to: I32F32) -> I32F32 {
+        self.charge_curve().secs_to_reach(from, to)
+    }
+
     /// Maps a usize from the dynamic scheduling program to a [`FlightState`].
     pub fn from_dp_usize(i: usize) -> Self {
         match i {
             1 => FlightState::Acquisition,
             0 => FlightState::Charge,
+            2 => FlightState::Comms,
             _ => panic!("Invalid state"),
         }
     }
@@ -77,6 +132,11 @@ impl FlightState {
             .unwrap_or_else(|| fatal!("({self}, {other}) not in TRANSITION_DELAY_LOOKUP"))
     }
 
+    /// Returns the transition time to another mode, or `None` if the transition is not allowed.
+    pub fn try_dt_to(self, other: Self) -> Option<Duration> {
+        TRANS_DEL.get(&(self, other)).copied()
+    }
+
     pub fn td_dt_to(self, other: Self) -> TimeDelta {
         TimeDelta::from_std(*TRANS_DEL.get(&(self, other)).unwrap_or_else(|| {
             fatal!("({self}, {other}) not in TRANSITION_DELAY_LOOKUP")
@@ -129,6 +189,10 @@ impl From<FlightState> for &'static str {
     }
 }
 
+/// Configured non-nominal charge curves per [`FlightState`].
+static CHARGE_CURVES: LazyLock<RwLock<HashMap<FlightState, ChargeCurve>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
 /// A static lookup table defining the delays needed
 /// for transitioning between different flight states.
 ///
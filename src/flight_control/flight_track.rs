@@ -0,0 +1,124 @@
+use super::{FlightState, FlightTelemetry};
+use crate::util::{Vec2D, logger::JsonDump};
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
+use fixed::types::I32F32;
+use std::path::PathBuf;
+
+/// A single downsampled sample of the recorded flight track.
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+pub(crate) struct TrackPoint {
+    /// The observation timestamp of the sample.
+    pub(crate) t: DateTime<Utc>,
+    /// The observed position.
+    pub(crate) pos: Vec2D<I32F32>,
+    /// The observed flight state.
+    pub(crate) state: FlightState,
+    /// The observed battery level.
+    pub(crate) battery: I32F32,
+    /// The observed remaining fuel.
+    pub(crate) fuel: I32F32,
+}
+
+/// A thumbnail of the map stored on disk at a given time.
+#[derive(Debug, Clone, serde::Serialize)]
+pub(crate) struct ThumbFrame {
+    /// The time the thumbnail was taken.
+    pub(crate) t: DateTime<Utc>,
+    /// The path of the stored image file.
+    pub(crate) path: PathBuf,
+}
+
+/// A replayable session of a single mission day.
+///
+/// Aggregates the flight track and the thumbnail history of the day into one timeline,
+/// which the operator console scrubs through to review where MELVIN flew, what was imaged
+/// and when it charged.
+#[derive(Debug, serde::Serialize)]
+pub(crate) struct ReplaySession {
+    /// The mission day covered by the session.
+    pub(crate) day: NaiveDate,
+    /// The track samples of the day in chronological order.
+    pub(crate) points: Vec<TrackPoint>,
+    /// The thumbnails of the day in chronological order.
+    pub(crate) frames: Vec<ThumbFrame>,
+}
+
+impl JsonDump for ReplaySession {
+    /// Returns a unique filename based on the covered day.
+    fn file_name(&self) -> String { format!("session_{}", self.day) }
+
+    /// Specifies the output directory for dumped replay sessions.
+    fn dir_name(&self) -> &'static str { "replay" }
+}
+
+/// Downsampled recording of the flight track and the thumbnail history.
+///
+/// Observations arrive every few hundred milliseconds, so only one sample per `TRACK_DT`
+/// is kept, plus every sample at which the flight state changed. Samples older than
+/// `RETENTION` are discarded, the stored thumbnails are kept on disk.
+#[derive(Debug, Default)]
+pub(crate) struct FlightTrack {
+    /// The recorded track samples in chronological order.
+    points: Vec<TrackPoint>,
+    /// The recorded thumbnails in chronological order.
+    frames: Vec<ThumbFrame>,
+}
+
+impl FlightTrack {
+    /// Minimum time between two track samples without a state change.
+    const TRACK_DT: TimeDelta = TimeDelta::seconds(60);
+    /// Time span for which samples are kept in memory.
+    const RETENTION: TimeDelta = TimeDelta::days(3);
+    /// Directory the thumbnails are stored in.
+    pub(crate) const FRAME_DIR: &'static str = "./replay";
+
+    /// Records a track sample if the last one is older than `TRACK_DT` or the flight state
+    /// changed since.
+    ///
+    /// # Arguments
+    /// * `tel` – The telemetry of the last observation.
+    pub(crate) fn record(&mut self, tel: &impl FlightTelemetry) {
+        let t = tel.observed_at();
+        let due = self
+            .points
+            .last()
+            .is_none_or(|last| last.state != tel.flight_state() || t - last.t >= Self::TRACK_DT);
+        if !due {
+            return;
+        }
+        self.points.push(TrackPoint {
+            t,
+            pos: tel.pos(),
+            state: tel.flight_state(),
+            battery: tel.battery(),
+            fuel: tel.fuel(),
+        });
+        let cutoff = t - Self::RETENTION;
+        self.points.retain(|p| p.t >= cutoff);
+        self.frames.retain(|f| f.t >= cutoff);
+    }
+
+    /// Records a stored thumbnail.
+    ///
+    /// # Arguments
+    /// * `t` – The time the thumbnail was taken.
+    /// * `path` – The path of the stored image file.
+    pub(crate) fn add_frame(&mut self, t: DateTime<Utc>, path: PathBuf) {
+        self.frames.push(ThumbFrame { t, path });
+    }
+
+    /// Aggregates the recorded track and thumbnails of a single day.
+    ///
+    /// # Arguments
+    /// * `day` – The mission day to aggregate.
+    ///
+    /// # Returns
+    /// * The [`ReplaySession`] of that day.
+    pub(crate) fn session(&self, day: NaiveDate) -> ReplaySession {
+        ReplaySession {
+            day,
+            points: self.points.iter().filter(|p| p.t.date_naive() == day).copied().collect(),
+            frames: self.frames.iter().filter(|f| f.t.date_naive() == day).cloned().collect(),
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use crate::util::{ImgObjectiveId, MissionConfig};
+use fixed::types::I32F32;
+use std::{collections::HashMap, sync::Mutex};
+
+/// The reason a fuel reservation was rejected.
+#[derive(Debug, Clone, Copy)]
+pub struct FuelBudgetError {
+    /// The requested amount of fuel.
+    pub requested: I32F32,
+    /// The fuel available for reservations.
+    pub available: I32F32,
+}
+
+impl std::fmt::Display for FuelBudgetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "requested {:.2} fuel, only {:.2} available above the safety margin",
+            self.requested, self.available
+        )
+    }
+}
+
+/// Central accounting of the fuel promised to planned maneuvers.
+///
+/// Every planned exit burn reserves its fuel until it is executed or cancelled. Plans that
+/// would push the remaining fuel, minus all reservations, below the configured safety margin
+/// are rejected, so that concurrently planned maneuvers cannot overcommit the tank.
+#[derive(Debug, Default)]
+pub struct FuelBudget {
+    /// The reserved fuel per targeted zoned objective.
+    reservations: Mutex<HashMap<ImgObjectiveId, I32F32>>,
+}
+
+impl FuelBudget {
+    /// Creates a new [`FuelBudget`] without reservations.
+    pub fn new() -> Self { Self::default() }
+
+    /// Returns the configured safety margin that is never reserved.
+    fn safety_margin() -> I32F32 {
+        I32F32::from_num(MissionConfig::get().runtime.fuel_safety_margin)
+    }
+
+    /// Returns the fuel that may still be reserved for a maneuver.
+    ///
+    /// # Arguments
+    /// - `id`: The objective the maneuver targets, its own reservation counts as available.
+    /// - `fuel_left`: The fuel currently left in the tank.
+    pub fn available_for(&self, id: ImgObjectiveId, fuel_left: I32F32) -> I32F32 {
+        let reservations = self.reservations.lock().unwrap();
+        let reserved: I32F32 =
+            reservations.iter().filter(|(res_id, _)| **res_id != id).map(|(_, f)| *f).sum();
+        (fuel_left - reserved - Self::safety_margin()).max(I32F32::ZERO)
+    }
+
+    /// Reserves fuel for a planned maneuver, replacing an earlier reservation for the same
+    /// objective.
+    ///
+    /// # Arguments
+    /// - `id`: The objective the maneuver targets.
+    /// - `amount`: The fuel needed by the maneuver.
+    /// - `fuel_left`: The fuel currently left in the tank.
+    ///
+    /// # Errors
+    /// A [`FuelBudgetError`] if the reservation would cut into the safety margin.
+    pub fn reserve(
+        &self,
+        id: ImgObjectiveId,
+        amount: I32F32,
+        fuel_left: I32F32,
+    ) -> Result<(), FuelBudgetError> {
+        let available = self.available_for(id, fuel_left);
+        if amount > available {
+            return Err(FuelBudgetError { requested: amount, available });
+        }
+        self.reservations.lock().unwrap().insert(id, amount);
+        Ok(())
+    }
+
+    /// Releases the reservation of a completed or cancelled maneuver.
+    ///
+    /// # Arguments
+    /// - `id`: The objective the maneuver targeted.
+    ///
+    /// # Returns
+    /// The released amount of fuel, if a reservation existed.
+    pub fn release(&self, id: ImgObjectiveId) -> Option<I32F32> {
+        self.reservations.lock().unwrap().remove(&id)
+    }
+
+    /// Returns the total reserved fuel.
+    pub fn reserved(&self) -> I32F32 {
+        self.reservations.lock().unwrap().values().copied().sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuel_reservations() {
+        let budget = FuelBudget::new();
+        let (a, b) = (ImgObjectiveId::new(1), ImgObjectiveId::new(2));
+        let margin = FuelBudget::safety_margin();
+        let fuel_left = margin + I32F32::lit("20");
+        assert!(budget.reserve(a, I32F32::lit("15"), fuel_left).is_ok());
+        let err = budget.reserve(b, I32F32::lit("10"), fuel_left).unwrap_err();
+        assert_eq!(err.available, I32F32::lit("5"));
+        // Re-planning the same maneuver may use its own reservation
+        assert!(budget.reserve(a, I32F32::lit("18"), fuel_left).is_ok());
+        assert_eq!(budget.reserved(), I32F32::lit("18"));
+        assert_eq!(budget.release(a), Some(I32F32::lit("18")));
+        assert!(budget.reserve(b, I32F32::lit("10"), fuel_left).is_ok());
+        assert_eq!(budget.release(a), None);
+    }
+}
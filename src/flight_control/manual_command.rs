@@ -0,0 +1,67 @@
+use super::{EnvelopeViolation, FlightState, command_reconciler::ControlCommand};
+use crate::imaging::CameraAngle;
+use crate::util::Vec2D;
+use fixed::types::I32F32;
+
+/// A manual flight command issued by an operator from the console.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ManualCommand {
+    /// A state change to the given target state.
+    SetState(FlightState),
+    /// A velocity change to the given target velocity.
+    SetVel(Vec2D<I32F32>),
+    /// A camera angle change to the given lens.
+    SetLens(CameraAngle),
+    /// Aborts the current global mode, dropping its schedule.
+    AbortMode,
+}
+
+impl ManualCommand {
+    /// Returns the control command sent to the backend, if the command issues one.
+    pub(super) fn control(self) -> Option<ControlCommand> {
+        match self {
+            Self::SetState(state) => Some(ControlCommand::State(state)),
+            Self::SetVel(vel) => Some(ControlCommand::Vel(vel)),
+            Self::SetLens(angle) => Some(ControlCommand::Angle(angle)),
+            Self::AbortMode => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ManualCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SetState(state) => write!(f, "set state {state}"),
+            Self::SetVel(vel) => write!(f, "set velocity {vel}"),
+            Self::SetLens(angle) => write!(f, "set lens {angle}"),
+            Self::AbortMode => write!(f, "abort mode"),
+        }
+    }
+}
+
+/// The reason a [`ManualCommand`] was rejected by the safety interlocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CommandRejected {
+    /// A burn sequence is executing.
+    BurnInProgress,
+    /// The requested state is no legal target state.
+    IllegalTargetState(FlightState),
+    /// The command is not accepted in the current state.
+    WrongState(FlightState),
+    /// The velocity violates the safety envelope.
+    Envelope(EnvelopeViolation),
+    /// The active global mode does not accept an abort.
+    NotAbortable,
+}
+
+impl std::fmt::Display for CommandRejected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BurnInProgress => write!(f, "a burn sequence is executing"),
+            Self::IllegalTargetState(state) => write!(f, "{state} is no legal target state"),
+            Self::WrongState(state) => write!(f, "not accepted in state {state}"),
+            Self::Envelope(violation) => write!(f, "{violation}"),
+            Self::NotAbortable => write!(f, "the active mode cannot be aborted"),
+        }
+    }
+}
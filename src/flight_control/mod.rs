@@ -1,12 +1,32 @@
 //! This module provides core components and functionality for the flight system,
-//! including the flight computer, flight state management, orbit calculations, 
-//! and supervision logic.
+//! including the flight computer, flight state management, orbit calculations, burn aborts,
+//! the safety envelope of velocity commands and supervision logic.
 
+mod burn_abort;
+mod charge_curve;
+mod charge_estimator;
+mod command_reconciler;
+mod dead_reckoning;
 mod flight_computer;
 mod flight_state;
+mod flight_track;
+mod fuel_budget;
+mod manual_command;
 pub(crate) mod orbit;
+mod safety_envelope;
 mod supervisor;
+mod telemetry;
+mod turn_cache;
+mod watchdog;
 
+pub use burn_abort::BurnAborted;
 pub use flight_computer::FlightComputer;
 pub use flight_state::FlightState;
-pub use supervisor::Supervisor;
\ No newline at end of file
+pub use fuel_budget::{FuelBudget, FuelBudgetError};
+pub(crate) use manual_command::{CommandRejected, ManualCommand};
+pub(crate) use flight_track::{FlightTrack, ReplaySession};
+pub use safety_envelope::EnvelopeViolation;
+pub use supervisor::Supervisor;
+pub use telemetry::FlightTelemetry;
+pub use watchdog::RecoveryAction;
+pub(crate) use watchdog::EscalatingPolicy;
\ No newline at end of file
@@ -0,0 +1,93 @@
+use super::BurnSequence;
+use crate::flight_control::FlightComputer;
+use crate::util::Vec2D;
+use fixed::types::I32F32;
+use num::Zero;
+
+/// Impact-point dispersion of a [`BurnSequence`] under realistic execution errors.
+///
+/// The backend only accepts velocities quantized to `VEL_BE_MAX_DECIMAL` decimals and
+/// commands are issued with second-granularity timing. The analysis perturbs every burn
+/// second by one quantization step per axis and shifts the burn start by up to
+/// `TIMING_ERR_S` seconds, and records how far the resulting impact points scatter around
+/// the nominal one.
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub struct BurnSensitivity {
+    /// The unwrapped impact point of the unperturbed sequence.
+    nominal_impact: Vec2D<I32F32>,
+    /// The maximum impact-point deviation caused by velocity quantization alone.
+    quant_dispersion: I32F32,
+    /// The maximum impact-point deviation caused by timing errors alone.
+    timing_dispersion: I32F32,
+    /// The maximum impact-point deviation over all perturbations.
+    dispersion: I32F32,
+}
+
+impl BurnSensitivity {
+    /// Maximum deviation of the burn start in seconds.
+    const TIMING_ERR_S: i64 = 1;
+
+    /// Perturbs a burn sequence within the error bounds and measures the impact dispersion.
+    ///
+    /// # Arguments
+    /// * `bs` – The planned burn sequence.
+    ///
+    /// # Returns
+    /// * The resulting [`BurnSensitivity`].
+    pub fn analyze(bs: &BurnSequence) -> Self {
+        let step = I32F32::ONE
+            / I32F32::from_num(10i32.pow(u32::from(FlightComputer::VEL_BE_MAX_DECIMAL)));
+        let nominal_impact = Self::perturbed_impact(bs, Vec2D::zero(), 0);
+        let signs = [(1, 1), (1, -1), (-1, 1), (-1, -1)];
+        let mut quant_dispersion = I32F32::zero();
+        let mut timing_dispersion = I32F32::zero();
+        let mut dispersion = I32F32::zero();
+        for t_err in -Self::TIMING_ERR_S..=Self::TIMING_ERR_S {
+            let timing_only = Self::perturbed_impact(bs, Vec2D::zero(), t_err);
+            timing_dispersion = timing_dispersion.max(timing_only.euclid_distance(&nominal_impact));
+            for (sx, sy) in signs {
+                let vel_err = Vec2D::new(step * I32F32::from_num(sx), step * I32F32::from_num(sy));
+                let impact = Self::perturbed_impact(bs, vel_err, t_err);
+                let dev = impact.euclid_distance(&nominal_impact);
+                if t_err == 0 {
+                    quant_dispersion = quant_dispersion.max(dev);
+                }
+                dispersion = dispersion.max(dev);
+            }
+        }
+        Self { nominal_impact, quant_dispersion, timing_dispersion, dispersion }
+    }
+
+    /// Propagates a burn sequence with a constant velocity error per burn second and a
+    /// shifted burn start up to the planned imaging time.
+    ///
+    /// # Arguments
+    /// * `bs` – The planned burn sequence.
+    /// * `vel_err` – The velocity error of every burn second.
+    /// * `t_err` – The delay of the burn start in seconds, negative for an early start.
+    ///
+    /// # Returns
+    /// * The unwrapped impact point.
+    fn perturbed_impact(bs: &BurnSequence, vel_err: Vec2D<I32F32>, t_err: i64) -> Vec2D<I32F32> {
+        let vels = bs.sequence_vel();
+        let mut pos = bs.sequence_pos()[0] + vels[0] * I32F32::from_num(t_err);
+        for vel in &vels[1..] {
+            pos = pos + *vel + vel_err;
+        }
+        let exit_vel = *vels.last().unwrap() + vel_err;
+        let detumble_dt = i64::try_from(bs.detumble_dt()).unwrap_or(i64::MAX);
+        pos + exit_vel * I32F32::from_num((detumble_dt - t_err).max(0))
+    }
+
+    /// Returns the unwrapped impact point of the unperturbed sequence.
+    pub fn nominal_impact(&self) -> Vec2D<I32F32> { self.nominal_impact }
+
+    /// Returns the maximum impact-point deviation over all perturbations.
+    pub fn dispersion(&self) -> I32F32 { self.dispersion }
+
+    /// Returns the maximum impact-point deviation caused by velocity quantization alone.
+    pub fn quant_dispersion(&self) -> I32F32 { self.quant_dispersion }
+
+    /// Returns the maximum impact-point deviation caused by timing errors alone.
+    pub fn timing_dispersion(&self) -> I32F32 { self.timing_dispersion }
+}
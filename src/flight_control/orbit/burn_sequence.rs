@@ -1,19 +1,20 @@
-use super::index::IndexedOrbitPosition;
-use crate::util::{Vec2D, helpers};
+use super::{BurnSensitivity, OrbitPositionTable, index::IndexedOrbitPosition};
+use crate::util::{ImgObjectiveId, MissionConfig, TimeBudget, Vec2D, helpers};
 use crate::flight_control::{FlightComputer,
     flight_computer::TurnsClockCClockTup, FlightState,
 };
 use crate::scheduling::TaskController;
-use chrono::{TimeDelta, Utc};
+use chrono::Utc;
 use fixed::types::I32F32;
 use num::Zero;
+use std::sync::Arc;
 use crate::util::logger::JsonDump;
 
 /// Represents a sequence of corrective burns for orbital adjustments.
 ///
 /// The [`BurnSequence`] contains position and velocity sequences, along with
 /// timing and cost information, for controlling orbit behavior.
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct BurnSequence {
     /// The orbital position where the sequence starts.
     start_i: IndexedOrbitPosition,
@@ -61,7 +62,7 @@ impl BurnSequence {
         rem_angle_dev: I32F32,
         second_target_add_dt: usize,
     ) -> Self {
-        let acq_db = FlightState::Acquisition.get_charge_rate();
+        let acq_db = FlightState::Acquisition.planning_rate();
         let acq_acc_db = acq_db + FlightState::ACQ_ACC_ADDITION;
         let trunc_detumble_time = detumble_dt.saturating_sub(TaskController::MANEUVER_MIN_DETUMBLE_DT);
         let acq_charge_dt =
@@ -81,8 +82,8 @@ impl BurnSequence {
         };
 
         let poss_charge = (I32F32::from_num(poss_charge_dt)
-            * FlightState::Charge.get_charge_rate())
-        .clamp(I32F32::zero(), TaskController::MAX_BATTERY_THRESHOLD);
+            * FlightState::Charge.planning_rate())
+        .clamp(I32F32::zero(), TaskController::max_battery_threshold());
         let acq_acc_time = I32F32::from_num(acc_dt + TaskController::MANEUVER_MIN_DETUMBLE_DT);
 
         let mut min_fuel = acq_acc_time * FlightComputer::ACC_CONST + Self::ADD_FUEL_CONST;
@@ -90,7 +91,7 @@ impl BurnSequence {
         let min_acc_acq_batt = (I32F32::from_num(acq_acc_time) * acq_acc_db).abs();
         let min_acq_batt = (I32F32::from_num(acq_time) * acq_db).abs();
         let mut add_acq_secs =
-            2 * usize::try_from(TaskController::ZO_IMAGE_FIRST_DEL.num_seconds()).unwrap_or(0);
+            2 * TimeBudget::from_delta(TaskController::ZO_IMAGE_FIRST_DEL).as_secs();
 
         if second_target_add_dt > 0 {
             add_acq_secs += second_target_add_dt;
@@ -99,7 +100,10 @@ impl BurnSequence {
 
         let second_need = (I32F32::from_num(add_acq_secs) * acq_acc_db).abs();
         let add_charge = (second_need - poss_charge).max(I32F32::zero());
-        let min_charge = TaskController::MIN_BATTERY_THRESHOLD + min_acc_acq_batt + min_acq_batt + add_charge;
+        let min_charge = TaskController::min_battery_threshold()
+            + min_acc_acq_batt
+            + min_acq_batt
+            + add_charge;
 
         Self {
             start_i,
@@ -142,14 +146,15 @@ impl BurnSequence {
 ///
 /// This includes the final sequence, associated cost, primary and (optional) secondary targets,
 /// and target metadata for logging/export.
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ExitBurnResult {
     sequence: BurnSequence,
     cost: I32F32,
     target_pos: Vec2D<I32F32>,
     add_target: Option<Vec2D<I32F32>>,
     unwrapped_target: Vec2D<I32F32>,
-    target_id: usize,
+    target_id: ImgObjectiveId,
+    sensitivity: BurnSensitivity,
 }
 
 impl JsonDump for ExitBurnResult {
@@ -169,6 +174,7 @@ impl ExitBurnResult {
     /// * `unwrapped_target` - The unwrapped target position in the orbital map.
     /// * `cost` - The total cost of the burn sequence.
     /// * `target_id` - An identifier for the target.
+    /// * `sensitivity` - The impact-point dispersion of the sequence.
     ///
     /// # Returns
     /// A new instance of [`ExitBurnResult`].
@@ -177,7 +183,8 @@ impl ExitBurnResult {
         target: (Vec2D<I32F32>, Vec2D<I32F32>),
         unwrapped_target: Vec2D<I32F32>,
         cost: I32F32,
-        target_id: usize,
+        target_id: ImgObjectiveId,
+        sensitivity: BurnSensitivity,
     ) -> Self {
         let target_pos = target.0;
         let add_target = if target.1 == Vec2D::zero() {
@@ -185,7 +192,35 @@ impl ExitBurnResult {
         } else {
             Some((target.0 + target.1).wrap_around_map())
         };
-        Self { sequence, cost, target_pos, add_target, unwrapped_target, target_id }
+        Self { sequence, cost, target_pos, add_target, unwrapped_target, target_id, sensitivity }
+    }
+
+    /// Creates a straight burn from `(1000, 1000)` towards a target at `(5000, 1000)` for tests.
+    ///
+    /// # Arguments
+    /// * `start_i` - The orbit position at which the burn starts.
+    /// * `cost` - The total cost of the burn sequence.
+    /// * `target_id` - An identifier for the target.
+    #[cfg(test)]
+    pub(crate) fn straight_fixture(
+        start_i: IndexedOrbitPosition,
+        cost: I32F32,
+        target_id: ImgObjectiveId,
+    ) -> Self {
+        let pos = |x: i32, y: i32| Vec2D::new(I32F32::from_num(x), I32F32::from_num(y));
+        let vel = pos(10, 0);
+        let seq = BurnSequence::new(
+            start_i,
+            Box::from([pos(1000, 1000), pos(1010, 1000)]),
+            Box::from([vel, vel]),
+            100,
+            1000,
+            I32F32::ZERO,
+            0,
+        );
+        let sensitivity = BurnSensitivity::analyze(&seq);
+        let target = pos(5000, 1000);
+        Self::new(seq, (target, Vec2D::zero()), target, cost, target_id, sensitivity)
     }
 
     /// Returns the total cost of the burn sequence.
@@ -202,6 +237,21 @@ impl ExitBurnResult {
 
     /// Returns the unwrapped target position.
     pub fn unwrapped_target(&self) -> &Vec2D<I32F32> { &self.unwrapped_target }
+
+    /// Returns the impact-point dispersion of the burn sequence.
+    pub fn sensitivity(&self) -> &BurnSensitivity { &self.sensitivity }
+
+    /// Returns a copy whose burn may start with the battery reserve lowered from
+    /// [`TaskController::min_battery_threshold()`] to `floor`.
+    ///
+    /// # Arguments
+    /// * `floor` - The battery level the burn may leave at least.
+    pub fn with_charge_floor(&self, floor: I32F32) -> Self {
+        let mut relaxed = self.clone();
+        let reserve_cut = (TaskController::min_battery_threshold() - floor).max(I32F32::zero());
+        relaxed.sequence.min_charge -= reserve_cut;
+        relaxed
+    }
 }
 
 /// A struct responsible for evaluating potential burn sequences for an orbit.
@@ -214,6 +264,8 @@ pub struct BurnSequenceEvaluator<'a> {
     i: IndexedOrbitPosition,
     /// The current velocity vector in 2D space.
     vel: Vec2D<I32F32>,
+    /// The precomputed positions along the current trajectory up to `max_dt`.
+    track: OrbitPositionTable,
     /// A slice of target positions and their secondary offsets.
     targets: &'a [(Vec2D<I32F32>, Vec2D<I32F32>)],
     /// The maximum allowable delta time for a burn sequence.
@@ -225,7 +277,7 @@ pub struct BurnSequenceEvaluator<'a> {
     /// The maximum angular deviation for the burn sequence.
     max_angle_dev: I32F32,
     /// Precomputed tuples of clockwise and counterclockwise turns for the sequence.
-    turns: TurnsClockCClockTup,
+    turns: Arc<TurnsClockCClockTup>,
     /// The current best computed burn result, if one exists.
     best_burn: Option<ExitBurnResult>,
     /// The available fuel for the evaluator to use.
@@ -233,7 +285,11 @@ pub struct BurnSequenceEvaluator<'a> {
     /// The dynamic weight assigned to fuel usage during scoring.
     dynamic_fuel_w: I32F32,
     /// The identifier for the current target being evaluated.
-    target_id: usize,
+    target_id: ImgObjectiveId,
+    /// The maximum tolerated impact-point dispersion.
+    max_dispersion: I32F32,
+    /// The angle to the target below which both turn directions are evaluated.
+    ambiguity_band: I32F32,
 }
 
 impl<'a> BurnSequenceEvaluator<'a> {
@@ -249,6 +305,8 @@ impl<'a> BurnSequenceEvaluator<'a> {
     const ANGLE_DEV_W: I32F32 = I32F32::lit("1.5");
     /// Weight assigned to additional target angle deviation.
     const ADD_ANGLE_DEV_W: I32F32 = I32F32::lit("3.0");
+    /// Weight assigned to the impact-point dispersion relative to the tolerated one.
+    const DISPERSION_W: I32F32 = I32F32::lit("2.0");
 
     /// Constructs a new `BurnSequenceEvaluator` object
     #[allow(clippy::too_many_arguments)]
@@ -259,9 +317,9 @@ impl<'a> BurnSequenceEvaluator<'a> {
         min_dt: usize,
         max_dt: usize,
         max_off_orbit_dt: usize,
-        turns: TurnsClockCClockTup,
+        turns: Arc<TurnsClockCClockTup>,
         fuel_left: I32F32,
-        target_id: usize,
+        target_id: ImgObjectiveId,
     ) -> Self {
         let max_angle_dev = {
             let vel_perp = vel.perp_unit(true) * FlightComputer::ACC_CONST;
@@ -277,6 +335,7 @@ impl<'a> BurnSequenceEvaluator<'a> {
         Self {
             i,
             vel,
+            track: OrbitPositionTable::with_config(i.pos(), vel, max_dt + 1),
             targets,
             max_dt,
             min_dt,
@@ -287,9 +346,26 @@ impl<'a> BurnSequenceEvaluator<'a> {
             dynamic_fuel_w,
             target_id,
             best_burn: None,
+            max_dispersion: I32F32::MAX,
+            ambiguity_band: I32F32::from_num(
+                MissionConfig::get().runtime.exit_turn_ambiguity_deg,
+            ),
         }
     }
 
+    /// Returns the precomputed positions along the current trajectory.
+    pub fn track(&self) -> &OrbitPositionTable { &self.track }
+
+    /// Restricts the evaluator to burn sequences whose impact-point dispersion stays within
+    /// the margin of the target zone.
+    ///
+    /// # Arguments
+    /// - `max_dispersion`: The maximum tolerated impact-point dispersion.
+    pub fn with_max_dispersion(mut self, max_dispersion: I32F32) -> Self {
+        self.max_dispersion = max_dispersion;
+        self
+    }
+
     /// Evaluates whether a burn sequence at a specific `dt` is viable and better than existing sequences.
     ///
     /// # Arguments
@@ -297,37 +373,95 @@ impl<'a> BurnSequenceEvaluator<'a> {
     /// - `max_needed_batt`: Upper bound for acceptable battery consumption.
     ///
     /// # Behavior
-    /// Builds and scores a candidate burn. Updates `best_burn` if it's better
-    /// and satisfies fuel/charge constraints.
-    #[allow(clippy::cast_possible_wrap)]
+    /// Builds and scores a candidate burn in the turn direction facing the target. If the
+    /// target lies within `ambiguity_band` of the velocity, the clockwise test is unreliable
+    /// and both turn directions are built instead. Candidates whose impact point
+    /// disperses beyond `max_dispersion` are rejected, the remaining ones are penalized by
+    /// their dispersion. Updates `best_burn` if it's better and satisfies fuel/charge
+    /// constraints.
     pub fn process_dt(&mut self, dt: usize, max_needed_batt: I32F32) {
-        let pos = (self.i.pos() + self.vel * I32F32::from_num(dt)).wrap_around_map().round();
-        let bs_i = self.i.new_from_future_pos(pos, self.i.t() + TimeDelta::seconds(dt as i64));
+        let pos = self.track.pos_at(dt).round();
+        let bs_i = self.i.new_from_future_pos(pos, self.i.t() + TimeBudget::secs(dt));
 
         let n_target = *self.targets.iter().min_by_key(|t| pos.unwrapped_to(&t.0).abs()).unwrap();
         let shortest_dir = pos.unwrapped_to(&n_target.0);
 
-        if self.vel.angle_to(&shortest_dir).abs() > Self::NINETY_DEG {
+        let target_angle = self.vel.angle_to(&shortest_dir).abs();
+        if target_angle > Self::NINETY_DEG {
             return;
         }
-        let (turns_in_dir, break_cond) = {
+        let turns = Arc::clone(&self.turns);
+        let ((turns_in_dir, break_cond), (turns_opposite, opposite_cond)) = {
+            let cw = (turns.0.as_slice(), false);
+            let ccw = (turns.1.as_slice(), true);
             if shortest_dir.is_clockwise_to(&self.vel).unwrap_or(false) {
-                (&self.turns.0, false)
+                (cw, ccw)
             } else {
-                (&self.turns.1, true)
+                (ccw, cw)
             }
         };
-        if let Some(b) = self.build_burn_sequence(bs_i, turns_in_dir, break_cond, &n_target) {
-            let cost = self.get_bs_cost(&b);
-            let add_cost = Self::get_add_target_cost(&b, &n_target);
-            let curr_cost = self.best_burn.as_ref().map_or(I32F32::MAX, ExitBurnResult::cost);
-            if curr_cost > cost.saturating_add(add_cost)
-                && b.min_charge() <= max_needed_batt
-                && b.min_fuel() <= self.fuel_left
-            {
-                let unwrapped_target = Self::get_unwrapped_target(&b, &n_target.0);
-                self.best_burn = Some(ExitBurnResult::new(b, n_target, unwrapped_target, cost, self.target_id));
+        if target_angle >= self.ambiguity_band {
+            if let Some(b) = self.build_burn_sequence(bs_i, turns_in_dir, break_cond, &n_target) {
+                self.consider_burn(b, n_target, max_needed_batt, false);
             }
+            return;
+        }
+        if let Some(b) = self.build_burn_sequence(bs_i, turns_in_dir, break_cond, &n_target) {
+            self.consider_burn(b, n_target, max_needed_batt, false);
+        }
+        if let Some(b) = self.build_burn_sequence(bs_i, turns_opposite, opposite_cond, &n_target) {
+            self.consider_burn(b, n_target, max_needed_batt, true);
+        }
+    }
+
+    /// Returns the distance by which the burn sequence misses the target after detumbling.
+    ///
+    /// # Arguments
+    /// - `b`: The burn sequence.
+    /// - `tar`: The target position.
+    fn impact_miss(b: &BurnSequence, tar: &Vec2D<I32F32>) -> I32F32 {
+        let impact_pos = *b.sequence_pos().last().unwrap()
+            + *b.sequence_vel().last().unwrap() * I32F32::from_num(b.detumble_dt());
+        impact_pos.wrap_around_map().unwrapped_to(tar).abs()
+    }
+
+    /// Scores a candidate burn sequence and keeps it if it beats the current best burn.
+    ///
+    /// # Arguments
+    /// - `b`: The candidate burn sequence.
+    /// - `n_target`: The target position and secondary offset of the candidate.
+    /// - `max_needed_batt`: Upper bound for acceptable battery consumption.
+    /// - `require_hit`: Whether the candidate must hit the target within its own dispersion.
+    ///   Turns away from the target often stop right away and merely graze it.
+    fn consider_burn(
+        &mut self,
+        b: BurnSequence,
+        n_target: (Vec2D<I32F32>, Vec2D<I32F32>),
+        max_needed_batt: I32F32,
+        require_hit: bool,
+    ) {
+        let sensitivity = BurnSensitivity::analyze(&b);
+        if sensitivity.dispersion() > self.max_dispersion
+            || require_hit && Self::impact_miss(&b, &n_target.0) > sensitivity.dispersion()
+        {
+            return;
+        }
+        let cost = self.get_bs_cost(&b) + self.get_dispersion_cost(&sensitivity);
+        let add_cost = Self::get_add_target_cost(&b, &n_target);
+        let curr_cost = self.best_burn.as_ref().map_or(I32F32::MAX, ExitBurnResult::cost);
+        if curr_cost > cost.saturating_add(add_cost)
+            && b.min_charge() <= max_needed_batt
+            && b.min_fuel() <= self.fuel_left
+        {
+            let unwrapped_target = Self::get_unwrapped_target(&b, &n_target.0);
+            self.best_burn = Some(ExitBurnResult::new(
+                b,
+                n_target,
+                unwrapped_target,
+                cost,
+                self.target_id,
+                sensitivity,
+            ));
         }
     }
 
@@ -451,6 +585,23 @@ impl<'a> BurnSequenceEvaluator<'a> {
         add_angle_dev * Self::ADD_ANGLE_DEV_W
     }
 
+    /// Calculates the cost factor for the impact-point dispersion of a burn sequence
+    ///
+    /// # Arguments
+    /// * `sensitivity`: the dispersion of the burn sequence to be evaluated
+    ///
+    /// # Returns
+    /// The `I32F32`-cost factor, zero if no dispersion limit is set.
+    fn get_dispersion_cost(&self, sensitivity: &BurnSensitivity) -> I32F32 {
+        if self.max_dispersion == I32F32::MAX {
+            return I32F32::zero();
+        }
+        let norm_dispersion =
+            helpers::normalize_fixed32(sensitivity.dispersion(), I32F32::zero(), self.max_dispersion)
+                .unwrap_or(I32F32::zero());
+        Self::DISPERSION_W * norm_dispersion
+    }
+
     /// Calculates the normalized cost factor for a burn sequence
     ///
     /// # Arguments
@@ -68,6 +68,18 @@ impl OrbitCharacteristics {
         self.mode_switches += 1;
     }
 
+    /// Resets the characteristics to a replanned closed orbit starting at the current position.
+    ///
+    /// # Arguments
+    /// * `c_orbit`: The replanned closed orbit.
+    /// * `now_pos`: The current position, the first position of the replanned orbit
+    pub fn replan(&mut self, c_orbit: &ClosedOrbit, now_pos: Vec2D<I32F32>) {
+        self.img_dt = c_orbit.max_image_dt();
+        self.orbit_full_period = c_orbit.period().0.to_num::<usize>();
+        self.i_entry = IndexedOrbitPosition::new(0, self.orbit_full_period, now_pos);
+        self.mode_switches += 1;
+    }
+
     /// Marks the end of an orbital mode after re-entering the orbit.
     ///
     /// # Arguments
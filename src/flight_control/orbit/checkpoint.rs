@@ -0,0 +1,92 @@
+use super::ClosedOrbit;
+use crate::util::MissionConfig;
+use crate::{log, warn};
+use std::{sync::Arc, time::Duration};
+use tokio::sync::{Mutex, RwLock};
+
+/// Persists the coverage progress of the [`ClosedOrbit`] at regular intervals and on mode
+/// switches, so that a crash loses at most one checkpoint interval of coverage.
+///
+/// Checkpoints are only written if `orbit.export` is set and the orbit changed since the
+/// last checkpoint. Every checkpoint atomically replaces the regular orbit export, which can be
+/// restored with `orbit.try_import`.
+#[derive(Debug)]
+pub struct OrbitCheckpointer {
+    /// The orbit to persist.
+    orbit: Arc<RwLock<ClosedOrbit>>,
+    /// The payload checksum of the last written checkpoint.
+    last_crc: Mutex<Option<u32>>,
+}
+
+impl OrbitCheckpointer {
+    /// The delay before re-checking the configuration while periodic checkpoints are disabled.
+    const DISABLED_POLL: Duration = Duration::from_secs(60);
+
+    /// Creates a new [`OrbitCheckpointer`].
+    ///
+    /// # Arguments
+    /// - `orbit`: The orbit to persist.
+    pub fn new(orbit: Arc<RwLock<ClosedOrbit>>) -> Self {
+        Self { orbit, last_crc: Mutex::new(None) }
+    }
+
+    /// Writes a checkpoint if the orbit changed since the last one.
+    ///
+    /// # Arguments
+    /// - `reason`: The trigger of the checkpoint, used for logging.
+    ///
+    /// # Returns
+    /// - `true` if a checkpoint was written.
+    pub async fn checkpoint(&self, reason: &str) -> bool {
+        if !ClosedOrbit::export_enabled() {
+            return false;
+        }
+        self.write(reason).await
+    }
+
+    /// Writes a final checkpoint if the orbit changed since the last one, even if periodic
+    /// checkpoints are disabled by `orbit.export`.
+    ///
+    /// # Returns
+    /// - `true` if a checkpoint was written.
+    pub async fn flush(&self) -> bool { self.write("shutdown").await }
+
+    /// Encodes the orbit and replaces the orbit export if its checksum changed.
+    ///
+    /// # Arguments
+    /// - `reason`: The trigger of the checkpoint, used for logging.
+    async fn write(&self, reason: &str) -> bool {
+        let mut last_crc = self.last_crc.lock().await;
+        let (crc, bytes) = match self.orbit.read().await.encode_export() {
+            Ok(export) => export,
+            Err(e) => {
+                warn!("Failed to encode orbit checkpoint: {e}");
+                return false;
+            }
+        };
+        if *last_crc == Some(crc) {
+            return false;
+        }
+        if let Err(e) = ClosedOrbit::write_export(ClosedOrbit::DEF_FILEPATH, &bytes) {
+            warn!("Failed to write orbit checkpoint: {e}");
+            return false;
+        }
+        log!("Orbit checkpoint written ({reason}, crc {crc:08x}).");
+        *last_crc = Some(crc);
+        true
+    }
+
+    /// Periodically writes checkpoints every `orbit_checkpoint_interval_min` minutes.
+    /// An interval of `0` pauses the periodic checkpoints.
+    pub async fn run(&self) {
+        loop {
+            let interval_min = MissionConfig::get().runtime.orbit_checkpoint_interval_min;
+            if interval_min == 0 {
+                tokio::time::sleep(Self::DISABLED_POLL).await;
+                continue;
+            }
+            tokio::time::sleep(Duration::from_secs(u64::from(interval_min) * 60)).await;
+            self.checkpoint("periodic").await;
+        }
+    }
+}
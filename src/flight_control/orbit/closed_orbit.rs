@@ -1,15 +1,19 @@
-use super::orbit_base::OrbitBase;
-use crate::util::{Vec2D, VecAxis};
+use super::{
+    coverage_forecast::CoverageForecast, index::IndexedOrbitPosition, orbit_base::OrbitBase,
+    position_table::OrbitPositionTable, segment_index::OrbitSegmentIndex,
+};
+use crate::util::{MapSize, MissionConfig, Vec2D, VecAxis, helpers::crc32};
 use crate::imaging::CameraAngle;
-use crate::{fatal, warn};
-use bincode::{error::EncodeError, config::{Configuration, Fixint, LittleEndian}};
+use crate::warn;
+use bincode::{error::{DecodeError, EncodeError}, config::{Configuration, Fixint, LittleEndian}};
 use bitvec::{
     bitbox,
     order::Lsb0,
-    prelude::{BitBox, BitRef},
+    prelude::{BitBox, BitRef, BitSlice},
 };
+use chrono::{DateTime, Utc};
 use fixed::types::I32F32;
-use std::env;
+use std::{io::Write, sync::OnceLock};
 use strum_macros::Display;
 
 /// Represents a single segment of the orbit path between two points.
@@ -26,7 +30,7 @@ pub(super) struct OrbitSegment {
 
 impl OrbitSegment {
     /// Creates a new orbit segment from two positions.
-    fn new(start: Vec2D<I32F32>, end: Vec2D<I32F32>) -> Self {
+    pub(super) fn new(start: Vec2D<I32F32>, end: Vec2D<I32F32>) -> Self {
         let delta = end - start;
         Self { start, end, delta }
     }
@@ -94,8 +98,85 @@ pub struct ClosedOrbit {
     max_image_dt: I32F32,
     /// A bitvector indicating the completion status of orbit segments.
     done: BitBox<usize, Lsb0>,
+    /// A bitvector marking orbit seconds that were imaged over featureless terrain, exported
+    /// after the orbit itself.
+    #[serde(skip)]
+    featureless: BitBox<usize, Lsb0>,
     /// A vector containing all of the orbits segments.
     segments: Vec<OrbitSegment>,
+    /// The lazily precomputed positions over one orbit period.
+    #[serde(skip)]
+    pos_table: OnceLock<OrbitPositionTable>,
+    /// The lazily built spatial index of the segments.
+    #[serde(skip)]
+    segment_index: OnceLock<OrbitSegmentIndex>,
+}
+
+/// Header preceding a serialized [`ClosedOrbit`] in a versioned orbit export.
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+struct OrbitExportHeader {
+    /// Magic bytes identifying a versioned orbit export.
+    magic: [u8; 4],
+    /// The schema version of the serialized orbit.
+    version: u32,
+}
+
+impl OrbitExportHeader {
+    /// Magic bytes at the start of every versioned orbit export.
+    const MAGIC: [u8; 4] = *b"MOBX";
+    /// Version of exports written before the header was introduced.
+    const LEGACY_VERSION: u32 = 1;
+    /// Version of exports with a header but without a checksum.
+    const UNCHECKED_VERSION: u32 = 2;
+    /// Version of exports whose payload is protected by a CRC-32 following the header.
+    const CHECKED_VERSION: u32 = 3;
+    /// Version written by the current export, the checked payload additionally holds the
+    /// featureless flags after the orbit.
+    const CURRENT_VERSION: u32 = 4;
+}
+
+/// Represents possible errors that can occur when importing an exported orbit.
+#[derive(Debug)]
+pub enum OrbitImportError {
+    /// The export file could not be read.
+    Io(std::io::Error),
+    /// The export has a schema version this build can not load.
+    UnknownVersion(u32),
+    /// The export of the given version could not be decoded.
+    Decode(u32, DecodeError),
+    /// The stored and the computed checksum of the payload differ.
+    Checksum(u32, u32),
+}
+
+impl std::fmt::Display for OrbitImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "orbit export unreadable: {e}"),
+            Self::UnknownVersion(v) => write!(f, "unknown orbit export version {v}"),
+            Self::Decode(v, e) => write!(f, "corrupted orbit export (version {v}): {e}"),
+            Self::Checksum(stored, actual) => {
+                write!(f, "orbit export checksum mismatch ({stored:08x} != {actual:08x})")
+            }
+        }
+    }
+}
+
+/// Represents possible errors that can occur when exporting an orbit.
+#[derive(Debug)]
+pub enum OrbitExportError {
+    /// The orbit could not be encoded.
+    Encode(EncodeError),
+    /// The export file could not be written.
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for OrbitExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Encode(e) => write!(f, "orbit not encodable: {e}"),
+            Self::Io(e) => write!(f, "orbit export not writable: {e}"),
+        }
+    }
 }
 
 /// Represents possible errors that can occur when creating or verifying an orbit.
@@ -108,12 +189,8 @@ pub enum OrbitUsabilityError {
 }
 
 impl ClosedOrbit {
-    /// ENV Var marking that the orbit configuration should be exported
-    const EXPORT_ORBIT_ENV: &'static str = "EXPORT_ORBIT";
-    /// ENV Var marking that it should be tried to import the orbit configuration
-    const TRY_IMPORT_ENV: &'static str = "TRY_IMPORT_ORBIT";
     /// File were the orbit should be serialized to/deserialized from
-    const DEF_FILEPATH: &'static str = "orbit.bin";
+    pub(super) const DEF_FILEPATH: &'static str = "orbit.bin";
     /// Creates a new [`ClosedOrbit`] instance using a given [`OrbitBase`] and [`CameraAngle`].
     ///
     /// # Arguments
@@ -132,57 +209,201 @@ impl ClosedOrbit {
                 Some(max_image_dt) => {
                     let segments = Self::compute_segments(base_orbit.fp(), base_orbit.vel());
                     let done = bitbox![usize, Lsb0; 0; period.0.to_num::<usize>()];
-                    Ok(Self { base_orbit, period, max_image_dt, done, segments })
+                    let featureless = done.clone();
+                    Ok(Self {
+                        base_orbit,
+                        period,
+                        max_image_dt,
+                        done,
+                        featureless,
+                        segments,
+                        pos_table: OnceLock::new(),
+                        segment_index: OnceLock::new(),
+                    })
                 }
             },
         }
     }
 
     /// Clears all completion tracking for the orbit.
+    ///
+    /// If `orbit.skip_featureless` is set, seconds already imaged over featureless terrain stay
+    /// marked as done, so that re-imaging concentrates on feature-rich terrain.
     pub fn clear_done(&mut self) {
-        self.done.fill(false);
+        if Self::skip_featureless() {
+            self.done.copy_from_bitslice(&self.featureless);
+        } else {
+            self.done.fill(false);
+        }
+    }
+
+    /// Carries the done and featureless flags over from a previous orbit.
+    ///
+    /// The indices of both orbits are aligned at the index of the previous orbit closest to
+    /// the first position of this orbit. Flags are only carried over where the aligned
+    /// positions of both orbits are at most `max_dist` apart, i.e. along the shared part of
+    /// the ground track.
+    ///
+    /// # Arguments
+    /// - `old`: The previous orbit.
+    /// - `max_dist`: The maximum distance of aligned positions.
+    ///
+    /// # Returns
+    /// - The number of orbit seconds whose flags were carried over.
+    pub fn carry_coverage_from(&mut self, old: &ClosedOrbit, max_dist: I32F32) -> usize {
+        let fp = *self.base_orbit.fp();
+        let old_len = old.done.len();
+        let Some(offset) = (0..old_len).min_by_key(|i| old.pos_at(*i).unwrapped_to(&fp).abs())
+        else {
+            return 0;
+        };
+        let mut carried = 0;
+        for i in 0..self.done.len() {
+            let old_i = (offset + i) % old_len;
+            if self.pos_at(i).unwrapped_to(&old.pos_at(old_i)).abs() <= max_dist {
+                self.done.set(i, old.done[old_i]);
+                self.featureless.set(i, old.featureless[old_i]);
+                carried += 1;
+            }
+        }
+        carried
     }
 
-    /// Tries to import a previously serialized orbit if environment variable `TRY_IMPORT_ORBIT=1`.
-    pub fn try_from_env() -> Option<Self> {
-        if env::var(Self::TRY_IMPORT_ENV).is_ok_and(|s| s == "1") {
-            Self::import_from(Self::DEF_FILEPATH).ok()
+    /// Returns `true` if featureless orbit seconds should be skipped once imaged.
+    pub fn skip_featureless() -> bool {
+        MissionConfig::get().orbit.skip_featureless
+    }
+
+    /// Tries to import a previously serialized orbit if `orbit.try_import` is set.
+    ///
+    /// If the export is missing, corrupted or of an unknown format version, a warning is
+    /// logged and `None` is returned so that the orbit is re-established instead.
+    pub fn try_import_default() -> Option<Self> {
+        if MissionConfig::get().orbit.try_import {
+            match Self::import_default() {
+                Ok(orbit) => Some(orbit),
+                Err(e) => {
+                    warn!("Failed to import orbit: {e}. Re-establishing orbit.");
+                    None
+                }
+            }
         } else {
             None
-        }        
+        }
+    }
+
+    /// Imports the orbit previously exported to the default file, regardless of
+    /// `orbit.try_import`.
+    ///
+    /// # Errors
+    /// An [`OrbitImportError`] if the export is missing, corrupted or of an unknown version.
+    pub fn import_default() -> Result<Self, OrbitImportError> {
+        Self::import_from(Self::DEF_FILEPATH)
     }
 
-    /// Tries to export the current orbit to disk if `EXPORT_ORBIT=1` is set in the environment.
+    /// Tries to export the current orbit to disk if `orbit.export` is set.
     pub fn try_export_default(&self) {
-        if env::var(Self::EXPORT_ORBIT_ENV).is_ok_and(|s| s == "1") {
+        if Self::export_enabled() {
             self.export_to(Self::DEF_FILEPATH).unwrap_or_else(|e| {
                 warn!("Failed to export orbit: {}", e);
             });
         }
     }
 
+    /// Returns `true` if `orbit.export` is set.
+    pub fn export_enabled() -> bool { MissionConfig::get().orbit.export }
+
     /// Deserializes a saved orbit from disk.
-    fn import_from(filename: &'static str) -> Result<Self, std::io::Error> {
-        let mut file = std::fs::OpenOptions::new().read(true).open(filename)?;
-        bincode::serde::decode_from_std_read(&mut file, Self::get_serde_config()).map_err(|e| {
-            fatal!("Failed to import orbit from {}: {}", filename, e);
-        })
+    ///
+    /// Versioned exports are identified by the [`OrbitExportHeader`] magic bytes, exports
+    /// without a header are loaded as the legacy (version 1) format. The payload of checked
+    /// exports is verified against its stored CRC-32. Exports before the current version hold
+    /// no featureless flags, which are then cleared.
+    ///
+    /// # Arguments
+    /// - `filename`: The path of the orbit export.
+    ///
+    /// # Returns
+    /// - `Ok(ClosedOrbit)` if the export could be decoded.
+    /// - `Err(OrbitImportError)` if the file is unreadable, corrupted or of an unknown version.
+    pub(super) fn import_from(filename: &str) -> Result<Self, OrbitImportError> {
+        let bytes = std::fs::read(filename).map_err(OrbitImportError::Io)?;
+        let config = Self::get_serde_config();
+        let (version, body) = match bincode::serde::decode_from_slice::<OrbitExportHeader, _>(
+            &bytes, config,
+        ) {
+            Ok((header, len)) if header.magic == OrbitExportHeader::MAGIC => {
+                (header.version, &bytes[len..])
+            }
+            _ => (OrbitExportHeader::LEGACY_VERSION, &bytes[..]),
+        };
+        let payload = match version {
+            OrbitExportHeader::LEGACY_VERSION | OrbitExportHeader::UNCHECKED_VERSION => body,
+            OrbitExportHeader::CHECKED_VERSION | OrbitExportHeader::CURRENT_VERSION => {
+                let (stored, len) = bincode::serde::decode_from_slice::<u32, _>(body, config)
+                    .map_err(|e| OrbitImportError::Decode(version, e))?;
+                let actual = crc32(&body[len..]);
+                if stored != actual {
+                    return Err(OrbitImportError::Checksum(stored, actual));
+                }
+                &body[len..]
+            }
+            unknown => return Err(OrbitImportError::UnknownVersion(unknown)),
+        };
+        let (mut orbit, len): (Self, usize) = bincode::serde::decode_from_slice(payload, config)
+            .map_err(|e| OrbitImportError::Decode(version, e))?;
+        orbit.featureless = if version == OrbitExportHeader::CURRENT_VERSION {
+            bincode::serde::decode_from_slice::<BitBox<usize, Lsb0>, _>(&payload[len..], config)
+                .map_err(|e| OrbitImportError::Decode(version, e))?
+                .0
+        } else {
+            bitbox![usize, Lsb0; 0; orbit.done.len()]
+        };
+        if orbit.featureless.len() != orbit.done.len() {
+            warn!("Featureless flags of the orbit export do not match its length, clearing them.");
+            orbit.featureless = bitbox![usize, Lsb0; 0; orbit.done.len()];
+        }
+        Ok(orbit)
     }
 
-    /// Serializes the orbit to a given file path using fixed-size encoding.
-    fn export_to(&self, filename: &'static str) -> Result<(), EncodeError> {
-        let mut file = std::fs::OpenOptions::new()
-            .create(true)
-            .write(true)
-            .truncate(true)
-            .open(filename)
-            .unwrap();
-        bincode::serde::encode_into_std_write(self, &mut file, Self::get_serde_config())?;
-        Ok(())
+    /// Serializes the orbit with a versioned header to a given file path using fixed-size
+    /// encoding.
+    pub(super) fn export_to(&self, filename: &str) -> Result<(), OrbitExportError> {
+        let (_, bytes) = self.encode_export().map_err(OrbitExportError::Encode)?;
+        Self::write_export(filename, &bytes).map_err(OrbitExportError::Io)
+    }
+
+    /// Encodes the orbit as a versioned export, the payload is protected by a CRC-32.
+    ///
+    /// # Returns
+    /// - The checksum of the payload and the complete export.
+    pub(super) fn encode_export(&self) -> Result<(u32, Vec<u8>), EncodeError> {
+        let config = Self::get_serde_config();
+        let header = OrbitExportHeader {
+            magic: OrbitExportHeader::MAGIC,
+            version: OrbitExportHeader::CURRENT_VERSION,
+        };
+        let mut payload = bincode::serde::encode_to_vec(self, config)?;
+        payload.extend(bincode::serde::encode_to_vec(&self.featureless, config)?);
+        let crc = crc32(&payload);
+        let mut bytes = bincode::serde::encode_to_vec(&header, config)?;
+        bytes.extend(bincode::serde::encode_to_vec(crc, config)?);
+        bytes.extend(payload);
+        Ok((crc, bytes))
+    }
+
+    /// Atomically replaces the export at `filename`: the bytes are written and synced to a
+    /// temporary file first, which is then renamed, so a crash never leaves a partial export.
+    pub(super) fn write_export(filename: &str, bytes: &[u8]) -> std::io::Result<()> {
+        let tmp = format!("{filename}.tmp");
+        let mut file = std::fs::File::create(&tmp)?;
+        file.write_all(bytes)?;
+        file.sync_all()?;
+        std::fs::rename(tmp, filename)
     }
 
     /// Returns a `bincode` serialization config with little-endian fixed-width layout.
-    fn get_serde_config() -> Configuration<LittleEndian, Fixint> {
+    pub(super) fn get_serde_config() -> Configuration<LittleEndian, Fixint> {
         bincode::config::standard().with_little_endian().with_fixed_int_encoding()
     }
 
@@ -259,7 +480,176 @@ impl ClosedOrbit {
             .for_each(|mut b| *b = true);
     }
 
+    /// Marks a specified range of orbit segments as not completed, so they are imaged again.
+    ///
+    /// # Arguments
+    /// - `first_i`: The first index of the range to reopen.
+    /// - `last_i`: The last index of the range to reopen.
+    pub fn mark_undone(&mut self, first_i: usize, last_i: usize) {
+        self.done[first_i..=last_i].fill(false);
+    }
+
+    /// Reopens all completed orbit seconds whose position lies within a map region, so that
+    /// the region is imaged again.
+    ///
+    /// # Arguments
+    /// - `offset`: The top-left corner of the region.
+    /// - `size`: The dimensions of the region.
+    /// - `margin`: The distance by which the region is extended on every side, e.g. half the
+    ///   side length of the imaging footprint.
+    ///
+    /// # Returns
+    /// - The number of reopened orbit seconds.
+    pub fn mark_region_undone(
+        &mut self,
+        offset: Vec2D<u32>,
+        size: Vec2D<u32>,
+        margin: u32,
+    ) -> usize {
+        let map = u32::map_size();
+        let covers = |p: I32F32, start: u32, len: u32, map_len: u32| {
+            let rel = p.to_num::<i64>() - i64::from(start) + i64::from(margin);
+            rel.rem_euclid(i64::from(map_len)) < i64::from(len) + 2 * i64::from(margin)
+        };
+        let mut reopened = 0;
+        for i in 0..self.done.len() {
+            let pos = self.pos_at(i);
+            let hit = covers(pos.x(), offset.x(), size.x(), map.x())
+                && covers(pos.y(), offset.y(), size.y(), map.y());
+            if hit && self.done[i] {
+                self.done.set(i, false);
+                reopened += 1;
+            }
+        }
+        reopened
+    }
+
+    /// Rasterizes the map pixels imaged during all orbit seconds marked as done.
+    ///
+    /// # Arguments
+    /// - `lens`: The lens whose square footprint is centered on every done orbit position.
+    ///
+    /// # Returns
+    /// - One flag per map pixel, row by row.
+    #[allow(
+        clippy::cast_possible_wrap,
+        clippy::cast_sign_loss,
+        clippy::cast_possible_truncation
+    )]
+    pub fn done_footprint(&self, lens: CameraAngle) -> BitBox<usize, Lsb0> {
+        let map = u32::map_size();
+        let (width, height) = (map.x() as usize, map.y() as usize);
+        let mut footprint = bitbox![usize, Lsb0; 0; width * height];
+        let side = usize::from(lens.get_square_side_length());
+        for i in self.done.iter_ones() {
+            let pos = self.pos_at(i);
+            let half = (side / 2) as i64;
+            let left = (pos.x().to_num::<i64>() - half).rem_euclid(width as i64) as usize;
+            let top = (pos.y().to_num::<i64>() - half).rem_euclid(height as i64) as usize;
+            // The part of every row left of the map edge and the one wrapped around it
+            let (first, wrapped) = (side.min(width - left), side.saturating_sub(width - left));
+            for dy in 0..side {
+                let row = ((top + dy) % height) * width;
+                footprint[row + left..row + left + first].fill(true);
+                footprint[row..row + wrapped].fill(true);
+            }
+        }
+        footprint
+    }
+
+    /// Sets the featureless flag of a specific orbit second.
+    ///
+    /// # Arguments
+    /// - `i`: The orbit index to classify.
+    /// - `is_featureless`: Whether the terrain imaged at this index was featureless.
+    pub fn set_featureless(&mut self, i: usize, is_featureless: bool) {
+        self.featureless.set(i, is_featureless);
+    }
+
+    /// Returns `true` if the given orbit second was imaged over featureless terrain.
+    ///
+    /// # Arguments
+    /// - `i`: The orbit index to check.
+    pub fn is_featureless(&self, i: usize) -> bool { self.featureless[i] }
+
+    /// Returns the done-bitmap with one flag per orbit second.
+    pub(super) fn done(&self) -> &BitSlice<usize, Lsb0> { &self.done }
+
+    /// Forecasts when the orbit will be fully imaged and how much each planned acquisition
+    /// window adds to the coverage.
+    ///
+    /// # Arguments
+    /// - `i_entry`: The indexed position of the orbit entry, mapping times to orbit indices.
+    /// - `windows`: The start and end times of the planned acquisition windows.
+    /// - `now`: The current time.
+    pub fn forecast_coverage(
+        &self,
+        i_entry: IndexedOrbitPosition,
+        windows: &[(DateTime<Utc>, DateTime<Utc>)],
+        now: DateTime<Utc>,
+    ) -> CoverageForecast {
+        CoverageForecast::extrapolate(self, i_entry, windows, now)
+    }
+
+    /// Returns `true` if all orbit seconds are marked as done.
+    pub fn is_fully_done(&self) -> bool { self.done.all() }
+
+    /// Returns the number of consecutive orbit seconds from `i` on that are already marked as
+    /// done, wrapping around the end of the orbit.
+    ///
+    /// # Arguments
+    /// - `i`: The first orbit index to check.
+    /// - `max`: The maximum number of seconds to count.
+    pub fn done_ahead(&self, i: usize, max: usize) -> usize {
+        let len = self.done.len();
+        (0..max.min(len)).take_while(|k| self.done[(i + k) % len]).count()
+    }
+
+    /// Returns the map position of the orbit at a specific orbit index.
+    ///
+    /// # Arguments
+    /// - `i`: The orbit index.
+    pub fn pos_at(&self, i: usize) -> Vec2D<I32F32> {
+        self.pos_table().pos_at(i % self.period.0.to_num::<usize>())
+    }
+
+    /// Returns the position table over one orbit period, building it on first use.
+    pub fn pos_table(&self) -> &OrbitPositionTable {
+        self.pos_table.get_or_init(|| {
+            let (fp, vel) = (*self.base_orbit.fp(), *self.base_orbit.vel());
+            OrbitPositionTable::with_config(fp, vel, self.period.0.to_num::<usize>())
+        })
+    }
+
+    /// Returns the spatial index of the segments, building it on first use.
+    fn segment_index(&self) -> &OrbitSegmentIndex {
+        self.segment_index.get_or_init(|| OrbitSegmentIndex::new(&self.segments))
+    }
+
+    /// Returns the axis and the signed deviation along it from a position to the closest
+    /// orbit segment.
+    ///
+    /// Only the segments near the position are checked. If none of them is within
+    /// [`OrbitSegmentIndex::EXACT_RADIUS`], all segments are scanned.
+    ///
+    /// # Arguments
+    /// - `pos`: The position to check.
     pub fn get_closest_deviation(&self, pos: Vec2D<I32F32>) -> (VecAxis, I32F32) {
+        let near = self
+            .segment_index()
+            .candidates(&pos)
+            .iter()
+            .map(|i| self.segments[*i as usize].get_proj_dist(&pos))
+            .min_by(|a, b| a.1.abs().cmp(&b.1.abs()));
+        match near {
+            Some(dev) if dev.1.abs() <= OrbitSegmentIndex::EXACT_RADIUS => dev,
+            _ => self.scan_closest_deviation(pos),
+        }
+    }
+
+    /// Returns the closest deviation like [`ClosedOrbit::get_closest_deviation`] by scanning
+    /// all segments.
+    pub(super) fn scan_closest_deviation(&self, pos: Vec2D<I32F32>) -> (VecAxis, I32F32) {
         self.segments
             .iter()
             .map(|seg| seg.get_proj_dist(&pos))
@@ -294,6 +684,16 @@ impl ClosedOrbit {
     /// - `true`: If the position will be visited during the orbit.
     /// - `false`: Otherwise.
     pub fn will_visit(&self, pos: Vec2D<I32F32>) -> bool {
+        self.segment_index()
+            .candidates(&pos)
+            .iter()
+            .any(|i| self.segments[*i as usize].get_abs_dist(&pos).abs() < I32F32::lit("1.0"))
+    }
+
+    /// Checks whether a position will be visited like [`ClosedOrbit::will_visit`] by scanning
+    /// all segments.
+    #[cfg(test)]
+    pub(super) fn scan_will_visit(&self, pos: Vec2D<I32F32>) -> bool {
         self.segments
             .iter()
             .map(|seg| seg.get_abs_dist(&pos))
@@ -308,8 +708,8 @@ impl ClosedOrbit {
         if self.will_visit(pos) {
             let step = *self.base_orbit.vel();
             let step_abs = step.abs();
-            let mut i_pos = *self.base_orbit.fp();
             for i in 0..self.period.0.to_num::<usize>() {
+                let i_pos = self.pos_at(i);
                 let mut dx_abs = i_pos.euclid_distance(&pos);
                 if dx_abs < step_abs * 2 {
                     let mut next = (i_pos + step).wrap_around_map();
@@ -321,7 +721,6 @@ impl ClosedOrbit {
                     }
                     return Some(i + add_i);
                 }
-                i_pos = (i_pos + step).wrap_around_map();
             }
         }
         None
@@ -336,4 +735,18 @@ impl ClosedOrbit {
         let length = I32F32::from_num(self.done.len());
         zeros / length
     }
+
+    /// Returns the share of done orbit seconds in each of `buckets` equally long runs of
+    /// consecutive orbit indices.
+    ///
+    /// # Arguments
+    /// - `buckets`: The number of buckets, capped at the orbit period.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn coverage_buckets(&self, buckets: usize) -> Vec<f32> {
+        let bucket_len = self.done.len().div_ceil(buckets.clamp(1, self.done.len().max(1)));
+        self.done
+            .chunks(bucket_len.max(1))
+            .map(|chunk| chunk.count_ones() as f32 / chunk.len() as f32)
+            .collect()
+    }
 }
@@ -0,0 +1,138 @@
+use super::{ClosedOrbit, IndexedOrbitPosition};
+use chrono::{DateTime, TimeDelta, Utc};
+use std::fmt::{Display, Formatter};
+
+/// The orbit coverage a planned acquisition window adds to the closed orbit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindowGain {
+    /// The start of the window.
+    start: DateTime<Utc>,
+    /// The end of the window.
+    end: DateTime<Utc>,
+    /// The number of orbit seconds first imaged in this window.
+    new_secs: usize,
+    /// The share of the orbit first imaged in this window.
+    gain: f32,
+}
+
+impl WindowGain {
+    /// Returns the start of the window.
+    pub fn start(&self) -> DateTime<Utc> { self.start }
+    /// Returns the end of the window.
+    pub fn end(&self) -> DateTime<Utc> { self.end }
+    /// Returns the number of orbit seconds first imaged in this window.
+    pub fn new_secs(&self) -> usize { self.new_secs }
+    /// Returns the share of the orbit first imaged in this window.
+    pub fn gain(&self) -> f32 { self.gain }
+}
+
+/// A forecast of when the closed orbit will be fully imaged.
+///
+/// The planned acquisition windows are replayed on a copy of the done-bitmap, giving the
+/// marginal coverage of every window. If the plan does not complete the orbit, the time to
+/// completion is extrapolated linearly from the coverage rate of the planned horizon.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoverageForecast {
+    /// The time the forecast was made.
+    made_at: DateTime<Utc>,
+    /// The number of seconds in one orbit period.
+    orbit_secs: usize,
+    /// The number of orbit seconds not imaged yet.
+    remaining_secs: usize,
+    /// The marginal coverage of every planned acquisition window.
+    windows: Vec<WindowGain>,
+    /// The expected time of full coverage, if the plan makes any progress.
+    completion: Option<DateTime<Utc>>,
+}
+
+impl CoverageForecast {
+    /// Extrapolates the completion of a closed orbit from planned acquisition windows, see
+    /// [`ClosedOrbit::forecast_coverage`].
+    ///
+    /// # Arguments
+    /// * `orbit` - The closed orbit.
+    /// * `i_entry` - The indexed position of the orbit entry, mapping times to orbit indices.
+    /// * `windows` - The start and end times of the planned acquisition windows.
+    /// * `now` - The current time, windows are only counted from here on.
+    ///
+    /// # Returns
+    /// The resulting [`CoverageForecast`].
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_possible_wrap
+    )]
+    pub(super) fn extrapolate(
+        orbit: &ClosedOrbit,
+        i_entry: IndexedOrbitPosition,
+        windows: &[(DateTime<Utc>, DateTime<Utc>)],
+        now: DateTime<Utc>,
+    ) -> Self {
+        let mut planned = orbit.done().to_bitvec();
+        let orbit_secs = planned.len();
+        let remaining_secs = planned.count_zeros();
+        let mut remaining = remaining_secs;
+        let mut completion = (remaining == 0).then_some(now);
+        let mut gains = Vec::with_capacity(windows.len());
+        for (w_start, end) in windows {
+            let start = (*w_start).max(now);
+            let secs = usize::try_from((*end - start).num_seconds()).unwrap_or(0);
+            let first_i = i_entry.index_then(start);
+            let mut new_secs = 0;
+            for k in 0..secs.min(orbit_secs) {
+                let i = (first_i + k) % orbit_secs;
+                if planned[i] {
+                    continue;
+                }
+                planned.set(i, true);
+                new_secs += 1;
+                remaining -= 1;
+                if remaining == 0 && completion.is_none() {
+                    completion = Some(start + TimeDelta::seconds(k as i64));
+                }
+            }
+            let gain = new_secs as f32 / orbit_secs.max(1) as f32;
+            gains.push(WindowGain { start, end: *end, new_secs, gain });
+        }
+        let horizon_end = gains.last().map_or(now, |w| w.end);
+        let planned_secs = remaining_secs - remaining;
+        let horizon = (horizon_end - now).num_seconds();
+        if completion.is_none() && planned_secs > 0 && horizon > 0 {
+            let rest = remaining as i64 * horizon / planned_secs as i64;
+            completion = Some(horizon_end + TimeDelta::seconds(rest));
+        }
+        Self { made_at: now, orbit_secs, remaining_secs, windows: gains, completion }
+    }
+
+    /// Returns the number of seconds in one orbit period.
+    pub fn orbit_secs(&self) -> usize { self.orbit_secs }
+    /// Returns the number of orbit seconds not imaged yet.
+    pub fn remaining_secs(&self) -> usize { self.remaining_secs }
+    /// Returns the marginal coverage of every planned acquisition window.
+    pub fn windows(&self) -> &[WindowGain] { &self.windows }
+    /// Returns the expected time of full coverage, or `None` if the plan makes no progress.
+    pub fn completion(&self) -> Option<DateTime<Utc>> { self.completion }
+    /// Returns the number of orbit seconds first imaged by the planned windows.
+    pub fn planned_secs(&self) -> usize { self.windows.iter().map(WindowGain::new_secs).sum() }
+}
+
+impl Display for CoverageForecast {
+    #[allow(clippy::cast_precision_loss)]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} of {} orbit seconds remaining, {} planned in {} acquisition windows, ",
+            self.remaining_secs,
+            self.orbit_secs,
+            self.planned_secs(),
+            self.windows.len()
+        )?;
+        match self.completion {
+            Some(t) => {
+                let hours = (t - self.made_at).num_minutes() as f32 / 60.0;
+                write!(f, "full coverage expected at {} (in {hours:.1}h)", t.format("%d %H:%M:%S"))
+            }
+            None => write!(f, "full coverage not reached by the current plan"),
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use super::ClosedOrbit;
+use crate::imaging::{CameraAngle, ThumbnailMapImage};
+use fixed::types::I32F32;
+use image::{Rgba, RgbaImage, codecs::png::PngEncoder};
+use std::io::Cursor;
+
+/// A map overlay visualizing which parts of the closed orbit ground track were imaged.
+///
+/// The orbit is split into buckets of consecutive orbit indices. The swath of every bucket is
+/// drawn at thumbnail scale and colored by the share of its orbit seconds marked as done, from
+/// red (none) to green (all). Poorly covered buckets are drawn last, so that gaps stay visible
+/// where the ground track crosses itself.
+#[derive(Debug)]
+pub struct OrbitCoverageHeatmap {
+    /// The number of orbit indices per bucket.
+    bucket_len: usize,
+    /// The share of done orbit seconds per bucket.
+    coverage: Vec<f32>,
+    /// The rendered overlay.
+    image: RgbaImage,
+}
+
+impl OrbitCoverageHeatmap {
+    /// The number of orbit indices between two drawn swath samples.
+    const SAMPLE_STEP: usize = 10;
+    /// The opacity of the drawn swath.
+    const ALPHA: u8 = 160;
+
+    /// Renders the coverage heatmap of a closed orbit.
+    ///
+    /// # Arguments
+    /// * `orbit` - The closed orbit.
+    /// * `buckets` - The number of buckets the orbit is split into.
+    /// * `lens` - The lens defining the width of the drawn swath.
+    ///
+    /// # Returns
+    /// The rendered [`OrbitCoverageHeatmap`].
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn render(orbit: &ClosedOrbit, buckets: usize, lens: CameraAngle) -> Self {
+        let coverage = orbit.coverage_buckets(buckets);
+        let period = orbit.period().0.to_num::<usize>();
+        let bucket_len = period.div_ceil(coverage.len().max(1)).max(1);
+        let size = ThumbnailMapImage::thumbnail_size();
+        let scale = ThumbnailMapImage::THUMBNAIL_SCALE_FACTOR;
+        let half = (u32::from(lens.get_square_side_length()) / scale / 2).max(1);
+        let mut order: Vec<usize> = (0..coverage.len()).collect();
+        order.sort_by(|a, b| coverage[*b].total_cmp(&coverage[*a]));
+
+        let mut image = RgbaImage::new(size.x(), size.y());
+        for bucket in order {
+            let color = Self::heat_color(coverage[bucket]);
+            let end = ((bucket + 1) * bucket_len).min(period);
+            for i in (bucket * bucket_len..end).step_by(Self::SAMPLE_STEP) {
+                let pos = orbit.pos_at(i) / I32F32::from_num(scale);
+                let (cx, cy) = (pos.x().to_num::<u32>(), pos.y().to_num::<u32>());
+                for dx in 0..2 * half {
+                    for dy in 0..2 * half {
+                        let x = (cx + size.x() + dx - half) % size.x();
+                        let y = (cy + size.y() + dy - half) % size.y();
+                        image.put_pixel(x, y, color);
+                    }
+                }
+            }
+        }
+        Self { bucket_len, coverage, image }
+    }
+
+    /// Maps the done share of a bucket to a color between red and green.
+    ///
+    /// # Arguments
+    /// * `share` - The share of done orbit seconds.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn heat_color(share: f32) -> Rgba<u8> {
+        let green = (share.clamp(0.0, 1.0) * 255.0).round() as u8;
+        Rgba([255 - green, green, 0, Self::ALPHA])
+    }
+
+    /// Encodes the overlay as a PNG.
+    ///
+    /// # Returns
+    /// The encoded PNG data or an error if the encoding failed.
+    pub fn encode_png(&self) -> Result<Vec<u8>, image::ImageError> {
+        let mut writer = Cursor::new(Vec::<u8>::new());
+        self.image.write_with_encoder(PngEncoder::new(&mut writer))?;
+        Ok(writer.into_inner())
+    }
+
+    /// Returns the number of orbit indices per bucket.
+    pub fn bucket_len(&self) -> usize { self.bucket_len }
+    /// Returns the share of done orbit seconds per bucket.
+    pub fn coverage(&self) -> &[f32] { &self.coverage }
+    /// Returns the width of the overlay in pixels.
+    pub fn width(&self) -> u32 { self.image.width() }
+    /// Returns the height of the overlay in pixels.
+    pub fn height(&self) -> u32 { self.image.height() }
+}
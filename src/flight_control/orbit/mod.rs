@@ -3,10 +3,17 @@
 //! and indexed orbit positions. 
 
 mod burn_sequence;
+mod burn_sensitivity;
 mod characteristics;
+mod checkpoint;
 mod closed_orbit;
+mod coverage_forecast;
+mod coverage_heatmap;
 mod index;
 mod orbit_base;
+mod orbit_profile;
+mod position_table;
+mod segment_index;
 
 #[cfg(test)]
 mod tests;
@@ -14,8 +21,14 @@ mod tests;
 pub use burn_sequence::BurnSequence;
 pub use burn_sequence::BurnSequenceEvaluator;
 pub use burn_sequence::ExitBurnResult;
+pub use burn_sensitivity::BurnSensitivity;
 pub use characteristics::OrbitCharacteristics;
+pub use checkpoint::OrbitCheckpointer;
 pub use closed_orbit::ClosedOrbit;
 pub use closed_orbit::OrbitUsabilityError;
+pub use coverage_forecast::CoverageForecast;
+pub use coverage_heatmap::OrbitCoverageHeatmap;
 pub use index::IndexedOrbitPosition;
 pub use orbit_base::OrbitBase;
+pub use orbit_profile::OrbitProfile;
+pub use position_table::OrbitPositionTable;
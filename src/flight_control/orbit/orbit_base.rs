@@ -36,6 +36,16 @@ impl OrbitBase {
         }
     }
     
+    /// Creates a planned [`OrbitBase`] starting now, e.g. to validate a velocity before it is
+    /// reached.
+    ///
+    /// # Arguments
+    /// - `fp`: The initial position of the orbit.
+    /// - `vel`: The planned velocity of the orbit.
+    pub fn planned(fp: Vec2D<I32F32>, vel: Vec2D<I32F32>) -> Self {
+        Self { init_timestamp: Utc::now(), fp, vel }
+    }
+
     /// Test initialize an orbit base
     #[cfg(test)]
     pub fn test(pos: Vec2D<I32F32>, vel: Vec2D<I32F32>) -> Self {
@@ -123,6 +133,30 @@ impl OrbitBase {
         }
     }
 
+    /// Calculates the share of the image side length by which adjacent ground tracks overlap.
+    ///
+    /// # Arguments
+    /// - `used_lens`: The camera lens configuration (field of view).
+    /// - `periods`: A tuple containing the orbit periods `(tts, t_x, t_y)`.
+    ///
+    /// # Returns
+    /// - The overlap share, negative if there are gaps between adjacent ground tracks.
+    pub fn track_overlap(
+        &self,
+        used_lens: CameraAngle,
+        periods: (I32F32, I32F32, I32F32),
+    ) -> I32F32 {
+        let img_side_length = I32F32::from_num(used_lens.get_square_side_length());
+        let spacing = if self.vel.x() / Vec2D::<I32F32>::map_size().x()
+            < self.vel.y() / Vec2D::<I32F32>::map_size().y()
+        {
+            Vec2D::<I32F32>::map_size().y() / (periods.0 / periods.1)
+        } else {
+            Vec2D::<I32F32>::map_size().x() / (periods.0 / periods.2)
+        };
+        I32F32::ONE - spacing / img_side_length
+    }
+
     /// Returns the footpoint of the orbit base
     pub fn fp(&self) -> &Vec2D<I32F32> { &self.fp }
     /// Returns the constant velocity of the orbit base
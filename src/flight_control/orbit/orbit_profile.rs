@@ -0,0 +1,74 @@
+use super::{ClosedOrbit, OrbitBase, OrbitUsabilityError};
+use crate::imaging::CameraAngle;
+use crate::util::Vec2D;
+use fixed::types::I32F32;
+
+/// A named orbit injection profile, selectable per mission phase via the
+/// [`MissionConfig`](crate::util::MissionConfig).
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct OrbitProfile {
+    /// The unique name of the profile.
+    pub name: String,
+    /// The orbit velocity in px/s.
+    pub vel: (f64, f64),
+    /// The camera angle the orbit is imaged with.
+    pub lens: CameraAngle,
+    /// The minimum share of the image side length by which adjacent ground tracks overlap.
+    pub min_overlap: f64,
+}
+
+impl OrbitProfile {
+    /// Velocity difference below which an orbit counts as flown with this profile.
+    const VEL_TOLERANCE: I32F32 = I32F32::lit("0.01");
+
+    /// Returns the orbit velocity as a fixed-point vector.
+    pub fn vel(&self) -> Vec2D<I32F32> {
+        Vec2D::new(I32F32::from_num(self.vel.0), I32F32::from_num(self.vel.1))
+    }
+
+    /// Returns `true` if an orbit with velocity `vel` is flown with this profile.
+    ///
+    /// # Arguments
+    /// - `vel`: The velocity of the orbit.
+    pub fn matches(&self, vel: Vec2D<I32F32>) -> bool {
+        self.vel().euclid_distance(&vel) < Self::VEL_TOLERANCE
+    }
+
+    /// Creates a [`ClosedOrbit`] for this profile, checking closure and the desired overlap.
+    ///
+    /// # Arguments
+    /// - `base`: The base orbit, e.g. taken from the flight computer after reaching `vel`.
+    ///
+    /// # Returns
+    /// - `Ok(ClosedOrbit)` if the orbit is closed and its ground tracks overlap as desired.
+    /// - `Err(OrbitUsabilityError)` otherwise.
+    pub fn closed_orbit(&self, base: OrbitBase) -> Result<ClosedOrbit, OrbitUsabilityError> {
+        let periods = base.period().ok_or(OrbitUsabilityError::OrbitNotClosed)?;
+        if base.track_overlap(self.lens, periods) < I32F32::from_num(self.min_overlap) {
+            return Err(OrbitUsabilityError::OrbitNotEnoughOverlap);
+        }
+        ClosedOrbit::new(base, self.lens)
+    }
+
+    /// Checks whether an orbit with this profile's velocity would be usable, before reaching it.
+    ///
+    /// # Arguments
+    /// - `pos`: The position the orbit would start at.
+    pub fn check(&self, pos: Vec2D<I32F32>) -> Result<(), OrbitUsabilityError> {
+        self.closed_orbit(OrbitBase::planned(pos, self.vel())).map(|_| ())
+    }
+
+    /// Checks the profile parameters for consistency.
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        let (name, speed) = (&self.name, self.vel().abs());
+        if name.is_empty() {
+            Err("orbit profile names must not be empty".to_string())
+        } else if speed <= I32F32::ZERO || speed > self.lens.get_max_speed() {
+            Err(format!("orbit profile {name} exceeds the speed of its lens"))
+        } else if !(0.0..1.0).contains(&self.min_overlap) {
+            Err(format!("orbit profile {name} overlap must be within [0, 1)"))
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use crate::util::{MissionConfig, Vec2D};
+use fixed::types::{I32F32, I96F32};
+
+/// Precomputed map positions along a linear trajectory, e.g. a closed orbit.
+///
+/// Planning loops query the position after `dt` seconds many times over long horizons. The
+/// table stores every `stride`-th position, intermediate seconds are extrapolated from the
+/// preceding entry. A stride of `1` makes lookups exact, larger strides trade lookup cost
+/// for memory. A stride of `0` disables the table and every position is computed directly.
+#[derive(Debug, Clone)]
+pub struct OrbitPositionTable {
+    /// The position at `dt = 0`.
+    origin: Vec2D<I32F32>,
+    /// The constant velocity along the trajectory.
+    vel: Vec2D<I32F32>,
+    /// The number of seconds between two stored positions, `0` if disabled.
+    stride: usize,
+    /// The stored, wrapped positions at multiples of `stride`.
+    positions: Box<[Vec2D<I32F32>]>,
+}
+
+impl OrbitPositionTable {
+    /// Creates a new [`OrbitPositionTable`] covering `len` seconds.
+    ///
+    /// # Arguments
+    /// - `origin`: The position at `dt = 0`.
+    /// - `vel`: The constant velocity along the trajectory.
+    /// - `len`: The number of seconds covered by the table.
+    /// - `stride`: The number of seconds between two stored positions, `0` disables the table.
+    pub fn new(origin: Vec2D<I32F32>, vel: Vec2D<I32F32>, len: usize, stride: usize) -> Self {
+        let positions = if stride == 0 {
+            Box::default()
+        } else {
+            (0..len).step_by(stride).map(|dt| Self::compute(origin, vel, dt)).collect()
+        };
+        Self { origin, vel, stride, positions }
+    }
+
+    /// Creates a new [`OrbitPositionTable`] covering `len` seconds with the stride configured
+    /// in the [`MissionConfig`].
+    ///
+    /// # Arguments
+    /// - `origin`: The position at `dt = 0`.
+    /// - `vel`: The constant velocity along the trajectory.
+    /// - `len`: The number of seconds covered by the table.
+    pub fn with_config(origin: Vec2D<I32F32>, vel: Vec2D<I32F32>, len: usize) -> Self {
+        let stride = MissionConfig::get().runtime.orbit_table_stride_s as usize;
+        Self::new(origin, vel, len, stride)
+    }
+
+    /// Computes the wrapped position after `dt` seconds without the table.
+    fn compute(origin: Vec2D<I32F32>, vel: Vec2D<I32F32>, dt: usize) -> Vec2D<I32F32> {
+        let pos = origin.to_num::<I96F32>() + vel.to_num::<I96F32>() * I96F32::from_num(dt);
+        pos.to_num::<I32F32>().wrap_around_map()
+    }
+
+    /// Returns the wrapped position after `dt` seconds.
+    ///
+    /// Times beyond the covered range are computed directly.
+    ///
+    /// # Arguments
+    /// - `dt`: The number of seconds after the origin.
+    pub fn pos_at(&self, dt: usize) -> Vec2D<I32F32> {
+        let Some(base) = dt.checked_div(self.stride).and_then(|i| self.positions.get(i)) else {
+            return Self::compute(self.origin, self.vel, dt);
+        };
+        let rem = dt % self.stride;
+        if rem == 0 { *base } else { (*base + self.vel * I32F32::from_num(rem)).wrap_around_map() }
+    }
+
+    /// Returns the number of seconds between two stored positions, `0` if disabled.
+    pub fn stride(&self) -> usize { self.stride }
+
+    /// Returns the approximate memory held by the stored positions in bytes.
+    pub fn memory_bytes(&self) -> usize { size_of_val(&*self.positions) }
+}
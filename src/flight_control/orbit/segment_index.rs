@@ -0,0 +1,87 @@
+use super::closed_orbit::OrbitSegment;
+use crate::util::{MapSize, Vec2D};
+use fixed::types::I32F32;
+
+/// Uniform grid over the map listing the orbit segments passing near each cell.
+///
+/// Queries like [`ClosedOrbit::will_visit`](super::ClosedOrbit::will_visit) are polled in
+/// control loops and used to scan every segment of the orbit. The index samples each segment
+/// at half a cell and registers it in the cell of every sample and the eight cells around
+/// it, wrapping around the map edges, so the candidates of a cell contain every segment passing within
+/// [`OrbitSegmentIndex::EXACT_RADIUS`] of any position in it.
+#[derive(Debug, Clone)]
+pub(super) struct OrbitSegmentIndex {
+    /// The number of cell columns.
+    cols: usize,
+    /// The number of cell rows.
+    rows: usize,
+    /// The ascending segment indices registered in each cell, row by row.
+    cells: Box<[Box<[u32]>]>,
+}
+
+impl OrbitSegmentIndex {
+    /// The side length of a cell in px.
+    const CELL_SIZE: u32 = 128;
+    /// The distance up to which the candidates of a position are guaranteed to contain every
+    /// segment.
+    pub(super) const EXACT_RADIUS: I32F32 = I32F32::lit("64");
+
+    /// Builds the index of the given segments.
+    ///
+    /// # Arguments
+    /// - `segments`: The segments of the orbit.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    pub(super) fn new(segments: &[OrbitSegment]) -> Self {
+        let map = Vec2D::<u32>::map_size();
+        let cols = map.x().div_ceil(Self::CELL_SIZE) as usize;
+        let rows = map.y().div_ceil(Self::CELL_SIZE) as usize;
+        let mut grid = vec![Vec::new(); cols * rows];
+        let step = f64::from(Self::CELL_SIZE) / 2.0;
+        for (i, seg) in segments.iter().enumerate() {
+            let (x0, y0) = (seg.start().x().to_num::<f64>(), seg.start().y().to_num::<f64>());
+            let (dx, dy) = (seg.end().x().to_num::<f64>() - x0, seg.end().y().to_num::<f64>() - y0);
+            let samples = (dx.hypot(dy) / step).ceil().max(1.0) as usize;
+            for k in 0..=samples {
+                let t = k as f64 / samples as f64;
+                let (cx, cy) = Self::cell_of(x0 + dx * t, y0 + dy * t, cols, rows);
+                for y in [cy + rows - 1, cy, cy + 1].map(|y| y % rows) {
+                    for x in [cx + cols - 1, cx, cx + 1].map(|x| x % cols) {
+                        let cell: &mut Vec<u32> = &mut grid[y * cols + x];
+                        if cell.last() != Some(&(i as u32)) {
+                            cell.push(i as u32);
+                        }
+                    }
+                }
+            }
+        }
+        let cells = grid.into_iter().map(Vec::into_boxed_slice).collect();
+        Self { cols, rows, cells }
+    }
+
+    /// Returns the indices of all segments possibly passing within
+    /// [`OrbitSegmentIndex::EXACT_RADIUS`] of a position, in ascending order.
+    ///
+    /// # Arguments
+    /// - `pos`: The position to look up.
+    pub(super) fn candidates(&self, pos: &Vec2D<I32F32>) -> &[u32] {
+        let (cx, cy) = Self::cell_of(pos.x().to_num(), pos.y().to_num(), self.cols, self.rows);
+        &self.cells[cy * self.cols + cx]
+    }
+
+    /// Returns the column and row of the cell containing a point, wrapped around the map.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    fn cell_of(x: f64, y: f64, cols: usize, rows: usize) -> (usize, usize) {
+        let (map, size) = (Vec2D::<f64>::map_size(), f64::from(Self::CELL_SIZE));
+        let cx = (x.rem_euclid(map.x()) / size).floor() as usize % cols;
+        let cy = (y.rem_euclid(map.y()) / size).floor() as usize % rows;
+        (cx, cy)
+    }
+}
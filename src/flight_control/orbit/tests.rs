@@ -1,11 +1,19 @@
 use crate::STATIC_ORBIT_VEL;
 use crate::imaging::CameraAngle;
-use crate::util::{MapSize, Vec2D};
-use super::{ClosedOrbit, OrbitBase};
+use crate::util::{MapSize, Vec2D, helpers::crc32};
+use super::{
+    ClosedOrbit, IndexedOrbitPosition, OrbitBase, OrbitCoverageHeatmap, OrbitPositionTable,
+    OrbitProfile, OrbitUsabilityError,
+    closed_orbit::{OrbitImportError, OrbitSegment},
+    coverage_forecast::WindowGain,
+    segment_index::OrbitSegmentIndex,
+};
+use chrono::TimeDelta;
 use fixed::types::I32F32;
 use itertools::Itertools;
 use num::Zero;
 use rand::Rng;
+use std::time::Instant;
 
 #[test]
 fn test_orbit_segments_and_closest() {
@@ -52,6 +60,274 @@ fn test_orbit_get_i() {
     }
 }
 
+#[test]
+fn test_orbit_segment_index_matches_scan() {
+    let closed_orbit = init_orbit();
+    let near_orbit = get_near_orbit_positions(&closed_orbit, 1000, 100.0);
+    let positions = (0..1000).map(|_| get_rand_pos()).chain(near_orbit);
+    for pos in positions {
+        assert_eq!(closed_orbit.will_visit(pos), closed_orbit.scan_will_visit(pos), "{pos}");
+        let dev = closed_orbit.get_closest_deviation(pos);
+        assert_eq!(dev, closed_orbit.scan_closest_deviation(pos), "{pos}");
+    }
+}
+
+#[test]
+fn test_orbit_segment_index_wraps_at_seam() {
+    let map = Vec2D::<I32F32>::map_size();
+    let across_x = OrbitSegment::new(
+        Vec2D::new(map.x() - I32F32::lit("100"), I32F32::lit("500")),
+        Vec2D::new(map.x() + I32F32::lit("100"), I32F32::lit("500")),
+    );
+    let across_y = OrbitSegment::new(
+        Vec2D::new(I32F32::lit("5000"), I32F32::lit("-100")),
+        Vec2D::new(I32F32::lit("5000"), I32F32::lit("100")),
+    );
+    let index = OrbitSegmentIndex::new(&[across_x, across_y]);
+    let past_x = Vec2D::new(I32F32::lit("60"), I32F32::lit("500"));
+    assert_eq!(index.candidates(&past_x), &[0]);
+    let past_y = Vec2D::new(I32F32::lit("5000"), map.y() - I32F32::lit("60"));
+    assert_eq!(index.candidates(&past_y), &[1]);
+}
+
+/// Compares the indexed and the scanning orbit lookups.
+///
+/// Run with `cargo test --release bench_orbit_segment_index -- --ignored --nocapture`.
+#[test]
+#[ignore = "benchmark"]
+fn bench_orbit_segment_index() {
+    let closed_orbit = init_orbit();
+    println!("{} segments", closed_orbit.segments().len());
+    let random: Vec<_> = (0..20_000).map(|_| get_rand_pos()).collect();
+    let near_orbit = get_near_orbit_positions(&closed_orbit, 20_000, 50.0);
+    for (name, positions) in [("random", random), ("near orbit", near_orbit)] {
+        let start = Instant::now();
+        let scanned = positions.iter().filter(|p| closed_orbit.scan_will_visit(**p)).count();
+        let scan_visit = start.elapsed();
+        let start = Instant::now();
+        let indexed = positions.iter().filter(|p| closed_orbit.will_visit(**p)).count();
+        let index_visit = start.elapsed();
+        assert_eq!(scanned, indexed);
+        let start = Instant::now();
+        for p in &positions {
+            _ = closed_orbit.scan_closest_deviation(*p);
+        }
+        let scan_dev = start.elapsed();
+        let start = Instant::now();
+        for p in &positions {
+            _ = closed_orbit.get_closest_deviation(*p);
+        }
+        let index_dev = start.elapsed();
+        println!("{name} will_visit: scan {scan_visit:?}, index {index_visit:?}");
+        println!("{name} get_closest_deviation: scan {scan_dev:?}, index {index_dev:?}");
+    }
+}
+
+#[test]
+fn test_position_table_strides() {
+    let origin = get_rand_pos();
+    let vel = Vec2D::from(STATIC_ORBIT_VEL);
+    let direct = OrbitPositionTable::new(origin, vel, 0, 0);
+    let exact = OrbitPositionTable::new(origin, vel, 18000, 1);
+    let strided = OrbitPositionTable::new(origin, vel, 18000, 60);
+    assert_eq!(exact.memory_bytes(), 60 * strided.memory_bytes());
+    for dt in [0, 1, 59, 60, 4321, 17999, 25000] {
+        let expected = direct.pos_at(dt);
+        assert_eq!(exact.pos_at(dt), expected);
+        assert!(strided.pos_at(dt).euclid_distance(&expected) < I32F32::lit("0.01"));
+    }
+}
+
+#[test]
+fn test_orbit_done_ahead() {
+    let mut closed_orbit = init_orbit();
+    let len = closed_orbit.period().0.to_num::<usize>();
+    assert_eq!(closed_orbit.done_ahead(0, 100), 0);
+    closed_orbit.mark_done(10, 59);
+    assert_eq!(closed_orbit.done_ahead(10, 100), 50);
+    assert_eq!(closed_orbit.done_ahead(20, 15), 15);
+    closed_orbit.mark_done(len - 5, len - 1);
+    assert_eq!(closed_orbit.done_ahead(len - 5, 100), 5);
+    closed_orbit.mark_done(0, 9);
+    assert_eq!(closed_orbit.done_ahead(len - 5, 100), 65);
+}
+
+#[test]
+fn test_orbit_mark_region_undone() {
+    let mut closed_orbit = init_orbit();
+    let len = closed_orbit.period().0.to_num::<usize>();
+    closed_orbit.mark_done(0, len - 1);
+    let pos = closed_orbit.pos_at(1000).wrap_around_map();
+    let corner = Vec2D::new(pos.x().to_num::<u32>(), pos.y().to_num::<u32>());
+    let reopened = closed_orbit.mark_region_undone(corner, Vec2D::new(1, 1), 0);
+    assert!(reopened >= 1);
+    assert_eq!(closed_orbit.done_ahead(1000, 1), 0);
+    assert_eq!(closed_orbit.mark_region_undone(corner, Vec2D::new(1, 1), 0), 0);
+    let all = closed_orbit.mark_region_undone(Vec2D::new(0, 0), u32::map_size(), 0);
+    assert_eq!(all + reopened, len);
+}
+
+#[test]
+fn test_orbit_done_footprint() {
+    let mut closed_orbit = init_orbit();
+    let width = u32::map_size().x() as usize;
+    assert!(closed_orbit.done_footprint(CameraAngle::Narrow).not_any());
+    closed_orbit.mark_done(1000, 1000);
+    let footprint = closed_orbit.done_footprint(CameraAngle::Narrow);
+    let side = usize::from(CameraAngle::Narrow.get_square_side_length());
+    assert_eq!(footprint.count_ones(), side * side);
+    let pos = closed_orbit.pos_at(1000).wrap_around_map();
+    let (x, y) = (pos.x().to_num::<usize>(), pos.y().to_num::<usize>());
+    assert!(footprint[y * width + x]);
+    let far = (x + side) % width;
+    assert!(!footprint[y * width + far]);
+}
+
+#[test]
+fn test_orbit_carry_coverage() {
+    let mut old = init_orbit();
+    let len = old.period().0.to_num::<usize>();
+    old.mark_done(100, 199);
+    let vel = Vec2D::from(STATIC_ORBIT_VEL);
+    let max_dist = I32F32::lit("150");
+    let new_base = OrbitBase::test(old.pos_at(150), vel);
+    let mut replanned = ClosedOrbit::new(new_base, CameraAngle::Narrow).unwrap();
+    assert_eq!(replanned.carry_coverage_from(&old, max_dist), len);
+    assert_eq!(replanned.done_ahead(0, 100), 50);
+    assert_eq!(replanned.done_ahead(len - 50, 100), 100);
+
+    let shifted = old.pos_at(150) + Vec2D::new(I32F32::zero(), I32F32::lit("1000"));
+    let shifted_base = OrbitBase::test(shifted.wrap_around_map(), vel);
+    let mut far = ClosedOrbit::new(shifted_base, CameraAngle::Narrow).unwrap();
+    assert_eq!(far.carry_coverage_from(&old, max_dist), 0);
+}
+
+#[test]
+fn test_orbit_coverage_heatmap() {
+    let mut closed_orbit = init_orbit();
+    let len = closed_orbit.period().0.to_num::<usize>();
+    closed_orbit.mark_done(0, len / 4 - 1);
+    let buckets = closed_orbit.coverage_buckets(4);
+    assert_eq!(buckets.len(), 4);
+    assert!((buckets[0] - 1.0).abs() < 0.01 && buckets[1..].iter().all(|b| *b < 0.01));
+    let heatmap = OrbitCoverageHeatmap::render(&closed_orbit, 4, CameraAngle::Narrow);
+    assert_eq!(heatmap.bucket_len(), len.div_ceil(4));
+    assert!(heatmap.encode_png().is_ok_and(|png| !png.is_empty()));
+}
+
+#[test]
+fn test_coverage_forecast() {
+    let mut closed_orbit = init_orbit();
+    let len = closed_orbit.period().0.to_num::<usize>();
+    closed_orbit.mark_done(0, len / 2 - 1);
+    let i_entry = IndexedOrbitPosition::new(0, len, closed_orbit.pos_at(0));
+    let now = i_entry.t();
+    let secs = |i: usize| i64::try_from(i).unwrap();
+    let at = |i: usize| now + TimeDelta::seconds(secs(i));
+
+    // The overlapping second window adds nothing, the rest is extrapolated from the first one
+    let windows = [(at(len / 4), at(3 * len / 4)), (at(len / 2), at(len / 2 + 100))];
+    let forecast = closed_orbit.forecast_coverage(i_entry, &windows, now);
+    assert_eq!(forecast.remaining_secs(), len - len / 2);
+    let gains: Vec<_> = forecast.windows().iter().map(WindowGain::new_secs).collect();
+    assert_eq!(gains, [3 * len / 4 - len / 2, 0]);
+    let horizon = secs(len / 2 + 100);
+    let rest = secs(len - 3 * len / 4) * horizon / secs(3 * len / 4 - len / 2);
+    assert_eq!(forecast.completion(), Some(at(len / 2 + 100) + TimeDelta::seconds(rest)));
+
+    // A plan imaging the rest of the orbit completes it in its last new second
+    let full = [(at(len / 2), at(len))];
+    let forecast = closed_orbit.forecast_coverage(i_entry, &full, now);
+    assert_eq!(forecast.completion(), Some(at(len - 1)));
+    assert_eq!(forecast.planned_secs(), len - len / 2);
+    assert!(closed_orbit.forecast_coverage(i_entry, &[], now).completion().is_none());
+}
+
+#[test]
+fn test_orbit_export_versions() {
+    let mut closed_orbit = init_orbit();
+    closed_orbit.set_featureless(1, true);
+    let dir = std::env::temp_dir().join(format!("melvin_orbit_export_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let current = dir.join("melvin_orbit_v4.bin").to_string_lossy().to_string();
+    closed_orbit.export_to(&current).unwrap();
+    let imported = ClosedOrbit::import_from(&current).unwrap();
+    assert_eq!(imported.period(), closed_orbit.period());
+    assert_eq!(imported.get_coverage(), closed_orbit.get_coverage());
+    assert!(imported.is_featureless(1) && !imported.is_featureless(0));
+
+    let mut corrupted_bytes = std::fs::read(&current).unwrap();
+    *corrupted_bytes.last_mut().unwrap() ^= 0xFF;
+    std::fs::write(&current, corrupted_bytes).unwrap();
+    assert!(matches!(
+        ClosedOrbit::import_from(&current),
+        Err(OrbitImportError::Checksum(_, _))
+    ));
+
+    let checked = dir.join("melvin_orbit_v3.bin").to_string_lossy().to_string();
+    let config = ClosedOrbit::get_serde_config();
+    let payload = bincode::serde::encode_to_vec(&closed_orbit, config).unwrap();
+    let mut checked_bytes = b"MOBX".to_vec();
+    checked_bytes.extend_from_slice(&3u32.to_le_bytes());
+    checked_bytes.extend_from_slice(&crc32(&payload).to_le_bytes());
+    checked_bytes.extend(payload);
+    std::fs::write(&checked, checked_bytes).unwrap();
+    let imported = ClosedOrbit::import_from(&checked).unwrap();
+    assert_eq!(imported.period(), closed_orbit.period());
+    assert!(!imported.is_featureless(1));
+
+    let unchecked = dir.join("melvin_orbit_v2.bin").to_string_lossy().to_string();
+    let mut unchecked_bytes = b"MOBX".to_vec();
+    unchecked_bytes.extend_from_slice(&2u32.to_le_bytes());
+    unchecked_bytes.extend(
+        bincode::serde::encode_to_vec(&closed_orbit, ClosedOrbit::get_serde_config()).unwrap(),
+    );
+    std::fs::write(&unchecked, unchecked_bytes).unwrap();
+    let imported = ClosedOrbit::import_from(&unchecked).unwrap();
+    assert_eq!(imported.period(), closed_orbit.period());
+
+    let legacy = dir.join("melvin_orbit_v1.bin").to_string_lossy().to_string();
+    let legacy_bytes =
+        bincode::serde::encode_to_vec(&closed_orbit, ClosedOrbit::get_serde_config()).unwrap();
+    std::fs::write(&legacy, legacy_bytes).unwrap();
+    let imported = ClosedOrbit::import_from(&legacy).unwrap();
+    assert_eq!(imported.period(), closed_orbit.period());
+
+    let unknown = dir.join("melvin_orbit_v99.bin").to_string_lossy().to_string();
+    let mut unknown_bytes = b"MOBX".to_vec();
+    unknown_bytes.extend_from_slice(&99u32.to_le_bytes());
+    std::fs::write(&unknown, unknown_bytes).unwrap();
+    assert!(matches!(
+        ClosedOrbit::import_from(&unknown),
+        Err(OrbitImportError::UnknownVersion(99))
+    ));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_orbit_profile_usability() {
+    let (vel_x, vel_y) = STATIC_ORBIT_VEL;
+    let mut profile = OrbitProfile {
+        name: "static".to_string(),
+        vel: (vel_x.to_num(), vel_y.to_num()),
+        lens: CameraAngle::Wide,
+        min_overlap: 0.25,
+    };
+    let pos = get_rand_pos();
+    assert!(profile.validate().is_ok());
+    assert!(profile.check(pos).is_ok());
+    let orbit = profile.closed_orbit(OrbitBase::test(pos, profile.vel())).unwrap();
+    assert_eq!(*orbit.base_orbit_ref().vel(), Vec2D::from(STATIC_ORBIT_VEL));
+
+    profile.min_overlap = 0.9;
+    assert!(matches!(profile.check(pos), Err(OrbitUsabilityError::OrbitNotEnoughOverlap)));
+    profile.lens = CameraAngle::Narrow;
+    profile.min_overlap = 0.0;
+    assert!(matches!(profile.check(pos), Err(OrbitUsabilityError::OrbitNotEnoughOverlap)));
+    profile.vel = (60.0, 1.0);
+    assert!(profile.validate().is_err());
+}
+
 fn init_orbit() -> ClosedOrbit {
     let init_pos = get_rand_pos();
     let o_b = OrbitBase::test(init_pos, Vec2D::from(STATIC_ORBIT_VEL));
@@ -78,6 +354,21 @@ fn get_rand_orbit_pos(orbit: &ClosedOrbit) -> (Vec2D<I32F32>, usize) {
     )
 }
 
+fn get_near_orbit_positions(orbit: &ClosedOrbit, n: usize, max_dev: f64) -> Vec<Vec2D<I32F32>> {
+    let mut rng = rand::rng();
+    let period = orbit.period().0.to_num::<usize>();
+    (0..n)
+        .map(|_| {
+            let dev = Vec2D::new(
+                rng.random_range(-max_dev..max_dev),
+                rng.random_range(-max_dev..max_dev),
+            );
+            let on_orbit = orbit.pos_at(rng.random_range(0..period));
+            (on_orbit + Vec2D::<I32F32>::from_real(&dev)).wrap_around_map()
+        })
+        .collect()
+}
+
 fn get_rand_pos() -> Vec2D<I32F32> {
     let mut rng = rand::rng();
     Vec2D::new(
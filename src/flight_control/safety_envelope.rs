@@ -0,0 +1,190 @@
+use super::{FlightComputer, FlightState, orbit::BurnSequence};
+use crate::util::{MapSize, MissionConfig, Vec2D};
+use crate::warn;
+use fixed::types::I32F32;
+
+/// The reason a velocity command or a burn sequence was rejected by the [`SafetyEnvelope`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvelopeViolation {
+    /// Velocity changes are not accepted in the current state.
+    WrongState(FlightState),
+    /// The commanded speed exceeds the speed limit of the envelope lens.
+    Overspeed {
+        /// The commanded speed.
+        speed: I32F32,
+        /// The speed limit.
+        max: I32F32,
+    },
+    /// The velocity change would burn the fuel below the configured floor.
+    FuelFloor {
+        /// The fuel burned by the velocity change.
+        cost: I32F32,
+        /// The fuel currently left.
+        left: I32F32,
+        /// The configured fuel floor.
+        floor: I32F32,
+    },
+    /// A position of a burn sequence lies outside the map, i.e. was not wrapped.
+    OffMap(Vec2D<I32F32>),
+}
+
+impl std::fmt::Display for EnvelopeViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::WrongState(state) => write!(f, "velocity can not be changed in state {state}"),
+            Self::Overspeed { speed, max } => {
+                write!(f, "speed {speed:.2} exceeds the limit of {max:.2}")
+            }
+            Self::FuelFloor { cost, left, floor } => write!(
+                f,
+                "burning {cost:.2} of {left:.2} fuel would fall below the floor of {floor:.2}"
+            ),
+            Self::OffMap(pos) => write!(f, "burn position {pos:.0} lies outside the map"),
+        }
+    }
+}
+
+/// Validation of velocity commands and burn sequences against the configured safety limits.
+///
+/// Every commanded speed must stay within the speed limit of the `runtime.envelope_lens`, so
+/// that the map can always be imaged with at least this resolution. Faster commands are
+/// clamped to the limit if `runtime.envelope_clamp_speed` is set and rejected otherwise.
+/// Velocity changes that would burn the fuel below `runtime.envelope_fuel_floor` are
+/// rejected. Burn sequences are checked as a whole before their first velocity change and
+/// are never clamped, as this would miss the planned target.
+pub struct SafetyEnvelope;
+
+impl SafetyEnvelope {
+    /// Returns the speed limit of the envelope lens.
+    fn max_speed() -> I32F32 { MissionConfig::get().runtime.envelope_lens.get_max_speed() }
+
+    /// Returns the fuel that is never burned by velocity changes.
+    fn fuel_floor() -> I32F32 {
+        I32F32::from_num(MissionConfig::get().runtime.envelope_fuel_floor)
+    }
+
+    /// Returns the fuel burned by changing the velocity from `from` to `to`.
+    pub fn fuel_cost(from: Vec2D<I32F32>, to: Vec2D<I32F32>) -> I32F32 {
+        from.euclid_distance(&to) / FlightComputer::ACC_CONST * FlightComputer::FUEL_CONST
+    }
+
+    /// Checks that burning `cost` leaves at least the fuel floor.
+    fn check_fuel(cost: I32F32, left: I32F32) -> Result<(), EnvelopeViolation> {
+        let floor = Self::fuel_floor();
+        if cost > I32F32::ZERO && left - cost < floor {
+            return Err(EnvelopeViolation::FuelFloor { cost, left, floor });
+        }
+        Ok(())
+    }
+
+    /// Checks a velocity command.
+    ///
+    /// # Arguments
+    /// - `state`: The current flight state.
+    /// - `current_vel`: The current velocity.
+    /// - `new_vel`: The commanded velocity.
+    /// - `fuel_left`: The fuel currently left.
+    ///
+    /// # Returns
+    /// - The velocity to command, clamped to the speed limit if configured.
+    ///
+    /// # Errors
+    /// - An [`EnvelopeViolation`] naming the violated limit.
+    pub fn check_vel(
+        state: FlightState,
+        current_vel: Vec2D<I32F32>,
+        new_vel: Vec2D<I32F32>,
+        fuel_left: I32F32,
+    ) -> Result<Vec2D<I32F32>, EnvelopeViolation> {
+        if state != FlightState::Acquisition {
+            return Err(EnvelopeViolation::WrongState(state));
+        }
+        let (speed, max) = (new_vel.abs(), Self::max_speed());
+        let vel = if speed <= max {
+            new_vel
+        } else if MissionConfig::get().runtime.envelope_clamp_speed {
+            let clamped = FlightComputer::round_vel(new_vel.normalize() * max).0;
+            warn!("Clamped velocity command {new_vel:.2} to {clamped:.2}.");
+            clamped
+        } else {
+            return Err(EnvelopeViolation::Overspeed { speed, max });
+        };
+        Self::check_fuel(Self::fuel_cost(current_vel, vel), fuel_left)?;
+        Ok(vel)
+    }
+
+    /// Checks a burn sequence before its execution.
+    ///
+    /// # Arguments
+    /// - `state`: The current flight state.
+    /// - `current_vel`: The velocity before the burn.
+    /// - `burn`: The burn sequence.
+    /// - `fuel_left`: The fuel currently left.
+    ///
+    /// # Errors
+    /// - An [`EnvelopeViolation`] naming the first violated limit.
+    pub fn check_burn(
+        state: FlightState,
+        current_vel: Vec2D<I32F32>,
+        burn: &BurnSequence,
+        fuel_left: I32F32,
+    ) -> Result<(), EnvelopeViolation> {
+        if state != FlightState::Acquisition {
+            return Err(EnvelopeViolation::WrongState(state));
+        }
+        let max = Self::max_speed();
+        if let Some(vel) = burn.sequence_vel().iter().find(|vel| vel.abs() > max) {
+            return Err(EnvelopeViolation::Overspeed { speed: vel.abs(), max });
+        }
+        let map = Vec2D::<I32F32>::map_size();
+        let on_map = |pos: &&Vec2D<I32F32>| {
+            (I32F32::ZERO..map.x()).contains(&pos.x()) && (I32F32::ZERO..map.y()).contains(&pos.y())
+        };
+        if let Some(pos) = burn.sequence_pos().iter().find(|pos| !on_map(pos)) {
+            return Err(EnvelopeViolation::OffMap(*pos));
+        }
+        let cost = burn
+            .sequence_vel()
+            .iter()
+            .scan(current_vel, |prev, vel| {
+                Some(Self::fuel_cost(std::mem::replace(prev, *vel), *vel))
+            })
+            .sum();
+        Self::check_fuel(cost, fuel_left)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_vel() {
+        let fuel = I32F32::lit("100");
+        let vel = Vec2D::new(I32F32::lit("6.4"), I32F32::lit("7.4"));
+        let held = SafetyEnvelope::check_vel(FlightState::Acquisition, vel, vel, fuel);
+        assert_eq!(held.unwrap(), vel);
+        assert!(matches!(
+            SafetyEnvelope::check_vel(FlightState::Charge, vel, vel, fuel),
+            Err(EnvelopeViolation::WrongState(FlightState::Charge))
+        ));
+
+        // Clamped to the speed limit of the default envelope lens
+        let at_max = Vec2D::new(SafetyEnvelope::max_speed(), I32F32::ZERO);
+        let fast = Vec2D::new(I32F32::lit("300"), I32F32::ZERO);
+        let clamped = SafetyEnvelope::check_vel(FlightState::Acquisition, at_max, fast, fuel);
+        assert_eq!(clamped.unwrap(), at_max);
+
+        // Changing the speed by 2 takes 100s and burns 3 fuel
+        let faster = vel + Vec2D::new(I32F32::lit("1.2"), I32F32::lit("1.6"));
+        let cost = SafetyEnvelope::fuel_cost(vel, faster);
+        assert!((cost - I32F32::lit("3")).abs() < I32F32::lit("0.001"));
+        let low_fuel = SafetyEnvelope::fuel_floor() + I32F32::lit("2");
+        assert!(matches!(
+            SafetyEnvelope::check_vel(FlightState::Acquisition, vel, faster, low_fuel),
+            Err(EnvelopeViolation::FuelFloor { .. })
+        ));
+        // Holding the velocity burns no fuel
+        assert!(SafetyEnvelope::check_vel(FlightState::Acquisition, vel, vel, low_fuel).is_ok());
+    }
+}
@@ -1,19 +1,30 @@
-use super::{FlightComputer, FlightState};
-use crate::imaging::CameraController;
-use crate::objective::{BeaconObjective, KnownImgObjective};
+use super::{
+    CommandRejected, FlightComputer, FlightState, FlightTrack, ManualCommand, ReplaySession,
+    orbit::ClosedOrbit,
+    watchdog::{ObservationHealth, RecoveryAction, RecoveryPolicy, Watchdog, WatchdogIncident},
+};
+use crate::imaging::{CameraController, DailyMapUpload};
+use crate::objective::{BeaconObjective, KnownImgObjective, LifecycleStage, OBJECTIVE_TRACKER};
 use crate::http_handler::{
     ZoneType, ImageObjective,
     http_request::{
         objective_list_get::ObjectiveListRequest, request_common::NoBodyHTTPRequestType,
     },
+    http_response::objective_list::ObjectiveListResponse,
+    observation_stream::ObservationStream,
+};
+use crate::util::{
+    BeaconEvent, BeaconObjectiveId, EVENT_BUS, ImgObjectiveId, MissionConfig, ObjectiveEvent,
+    ObjectiveId, ObjectiveKind, SafetyEvent, ZoneRect, logger::JsonDump,
 };
 use crate::{DT_0_STD, error, event, fatal, info, log, warn, obj};
-use chrono::{DateTime, NaiveTime, TimeDelta, TimeZone, Utc};
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeDelta, TimeZone, Utc};
+use fixed::types::I32F32;
 use futures::StreamExt;
 use reqwest_eventsource::{Event, EventSource};
-use std::{collections::HashSet, env, sync::Arc, time::Duration};
+use std::{collections::{HashMap, HashSet}, env, path::Path, sync::Arc, time::Duration};
 use tokio::{
-    sync::{Notify, RwLock, broadcast, mpsc, mpsc::Receiver},
+    sync::{RwLock, mpsc, mpsc::Receiver, watch},
     time::Instant,
 };
 
@@ -27,16 +38,24 @@ use tokio::{
 pub struct Supervisor {
     /// Lock-protected reference to the [`FlightComputer`], used for updating the global observation.
     f_cont_lock: Arc<RwLock<FlightComputer>>,
-    /// Notifier that signals when a safe-mode transition is detected.
-    safe_mon: Arc<Notify>,
+    /// Watch flag that is `true` while an unplanned safe mode is active.
+    safe_mon: watch::Sender<bool>,
+    /// Watch flag that is `true` once the end-of-mission routine was requested.
+    eom_mon: watch::Sender<bool>,
     /// Channel for sending newly discovered zoned objectives to the main scheduling system.
     zo_mon: mpsc::Sender<KnownImgObjective>,
     /// Channel for sending active beacon objectives to the main scheduling system.
     bo_mon: mpsc::Sender<BeaconObjective>,
-    /// Broadcast channel for relaying real-time mission announcements or telemetry updates.
-    event_hub: broadcast::Sender<(DateTime<Utc>, String)>,
     /// In-memory buffer of currently known secret imaging objectives that await triggering.
     current_secret_objectives: RwLock<Vec<ImageObjective>>,
+    /// Downsampled recording of the flight track and the thumbnail history for replays.
+    track: RwLock<FlightTrack>,
+    /// Watch on the health of the observation updates, checked by the watchdog.
+    obs_health: watch::Sender<ObservationHealth>,
+    /// Watch flag that is `false` while the circuit breaker of the DRS client is open.
+    backend_up: watch::Sender<bool>,
+    /// Watch flag that is `true` while the active global mode accepts an operator abort.
+    abortable: watch::Sender<bool>,
 }
 
 impl Supervisor {
@@ -48,6 +67,28 @@ impl Supervisor {
     const B_O_MIN_DT: TimeDelta = TimeDelta::minutes(20);
     /// Environment variable used to skip known objectives by ID (comma-separated).
     const ENV_SKIP_OBJ: &'static str = "SKIP_OBJ";
+    /// Time before the mission end at which the end-of-mission routine is started.
+    const EOM_LEAD: TimeDelta = TimeDelta::minutes(15);
+    /// Interval at which map thumbnails are stored for replays.
+    const REPLAY_FRAME_INTERVAL: Duration = Duration::from_secs(600);
+    /// Time of day (UTC) at which the daily map is uploaded.
+    const DAILY_MAP_UPLOAD_T: NaiveTime = NaiveTime::from_hms_opt(22, 55, 0).unwrap();
+    /// Number of times a failed daily map upload is resumed.
+    const DAILY_MAP_RESUMES: u32 = 5;
+    /// Delay before resuming a failed daily map upload.
+    const DAILY_MAP_RESUME_DELAY: Duration = Duration::from_secs(60);
+    /// Interval at which the watchdog checks for faults.
+    const WATCHDOG_INTERVAL: Duration = Duration::from_secs(5);
+    /// Minimum time after a triggered recovery before the watchdog checks again.
+    const WATCHDOG_COOLDOWN: Duration = Duration::from_secs(120);
+    /// Interval at which the deviation from the closed orbit is checked.
+    const ORBIT_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+    /// Number of consecutive checks the deviation must persist before it is reported.
+    const ORBIT_DEVIATION_CHECKS: usize = 3;
+    /// Minimum time after a reported deviation before the orbit is checked again.
+    const ORBIT_REPLAN_COOLDOWN: Duration = Duration::from_secs(600);
+    /// Interval at which the free disk space is checked.
+    const STORAGE_CHECK_INTERVAL: Duration = Duration::from_secs(300);
 
     /// Creates a new [`Supervisor`] instance and returns associated receivers
     /// for zoned and beacon objectives.
@@ -66,35 +107,148 @@ impl Supervisor {
     ) {
         let (tx_obj, rx_obj) = mpsc::channel(10);
         let (tx_beac, rx_beac) = mpsc::channel(10);
-        let (event_send, _) = broadcast::channel(10);
         (
             Self {
                 f_cont_lock,
-                safe_mon: Arc::new(Notify::new()),
+                safe_mon: watch::Sender::new(false),
+                eom_mon: watch::Sender::new(false),
                 zo_mon: tx_obj,
                 bo_mon: tx_beac,
-                event_hub: event_send,
                 current_secret_objectives: RwLock::new(vec![]),
+                track: RwLock::new(FlightTrack::default()),
+                obs_health: watch::Sender::new(ObservationHealth::new()),
+                backend_up: watch::Sender::new(true),
+                abortable: watch::Sender::new(false),
             },
             rx_obj,
             rx_beac,
         )
     }
 
-    /// Returns a clone of the safe-mode notifier.
-    pub(crate) fn safe_mon(&self) -> Arc<Notify> { Arc::clone(&self.safe_mon) }
+    /// Returns a new receiver for the safe-mode flag.
+    pub(crate) fn safe_mon(&self) -> watch::Receiver<bool> { self.safe_mon.subscribe() }
+
+    /// Returns a new receiver for the end-of-mission flag.
+    pub(crate) fn eom_mon(&self) -> watch::Receiver<bool> { self.eom_mon.subscribe() }
+
+    /// Sets whether the active global mode accepts an operator abort.
+    pub(crate) fn set_abortable(&self, abortable: bool) { self.abortable.send_replace(abortable); }
+
+    /// Tracks persistent DRS outages reported by the circuit breaker of the HTTP client.
+    ///
+    /// While the backend is unreachable, objective polling is paused.
+    pub(crate) async fn run_link_monitor(&self) {
+        let mut safety_rx = EVENT_BUS.subscribe::<SafetyEvent>();
+        while let Some(event) = safety_rx.recv().await {
+            match event {
+                SafetyEvent::BackendOutage(start) => {
+                    warn!("DRS outage since {start}, pausing objective polling.");
+                    self.backend_up.send_replace(false);
+                }
+                SafetyEvent::BackendRestored(start, end) => {
+                    let secs = (end - start).num_seconds();
+                    info!("DRS outage ended after {secs}s, resuming objective polling.");
+                    self.backend_up.send_replace(true);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Requests the end-of-mission routine. Repeated requests are ignored.
+    ///
+    /// # Arguments
+    /// * `source` – A description of the requester used for logging.
+    pub(crate) fn request_end_of_mission(&self, source: &str) {
+        if !self.eom_mon.send_replace(true) {
+            info!("End of mission requested by {source}!");
+            EVENT_BUS.publish(SafetyEvent::EndOfMission);
+        }
+    }
 
-    /// Subscribes to the event hub to receive mission announcement broadcasts.
-    pub(crate) fn subscribe_event_hub(&self) -> broadcast::Receiver<(DateTime<Utc>, String)> {
-        self.event_hub.subscribe()
+    /// Validates and executes a manual operator command.
+    ///
+    /// The command lock of the [`FlightComputer`] is held from the checks until the control
+    /// request is done, so manual commands never interleave with scheduled state switches,
+    /// lens changes or burn starts.
+    /// Accepted control commands are executed until their target is reached, an abort is
+    /// published as [`SafetyEvent::OperatorAbort`] and handled by the active global mode. Aborts
+    /// are rejected while the active mode does not accept them, control commands if the safety
+    /// envelope or the flight state rejects them on execution.
+    ///
+    /// # Arguments
+    /// * `cmd` – The manual command issued by the operator.
+    ///
+    /// # Returns
+    /// The executed command or the reason it was rejected or failed.
+    pub(crate) async fn execute_manual_command(
+        &self,
+        cmd: ManualCommand,
+    ) -> Result<ManualCommand, CommandRejected> {
+        // Held until the control request is done, so no scheduled command interleaves
+        let cmd_guard = FlightComputer::lock_commands(&self.f_cont_lock).await;
+        let interlocks = self.f_cont_lock.read().await.check_manual_command(cmd);
+        let res = interlocks.and_then(|checked| {
+            if checked == ManualCommand::AbortMode && !*self.abortable.borrow() {
+                Err(CommandRejected::NotAbortable)
+            } else {
+                Ok(checked)
+            }
+        });
+        let checked = match res {
+            Ok(checked) => checked,
+            Err(reason) => {
+                warn!("Rejected manual command '{cmd}': {reason}.");
+                return Err(reason);
+            }
+        };
+        info!("Executing manual command '{checked}'.");
+        let f_cont = Arc::clone(&self.f_cont_lock);
+        let exec_res = match checked {
+            ManualCommand::SetState(state) => {
+                FlightComputer::try_set_state_wait(f_cont, state, &cmd_guard).await
+            }
+            ManualCommand::SetVel(vel) => FlightComputer::set_vel_wait(f_cont, vel, false)
+                .await
+                .map_err(CommandRejected::Envelope),
+            ManualCommand::SetLens(angle) => {
+                FlightComputer::try_set_angle_wait(f_cont, angle, &cmd_guard).await
+            }
+            ManualCommand::AbortMode => {
+                EVENT_BUS.publish(SafetyEvent::OperatorAbort);
+                Ok(())
+            }
+        };
+        if let Err(reason) = exec_res {
+            warn!("Manual command '{checked}' failed: {reason}.");
+            return Err(reason);
+        }
+        Ok(checked)
     }
 
-    /// Listens to the `/announcements` Event Source endpoint and broadcasts messages to subscribers.
+    /// Requests the end-of-mission routine `EOM_LEAD` before the given mission end.
+    ///
+    /// # Arguments
+    /// * `mission_end` – The time at which the mission window closes.
+    pub(crate) async fn run_mission_clock(&self, mission_end: DateTime<Utc>) {
+        let trigger_t = mission_end - Self::EOM_LEAD;
+        info!("Mission clock set: end-of-mission routine starts at {trigger_t}.");
+        tokio::time::sleep((trigger_t - Utc::now()).to_std().unwrap_or(DT_0_STD)).await;
+        self.request_end_of_mission("mission clock");
+    }
+
+    /// Listens to the `/announcements` Event Source endpoint and publishes messages on the
+    /// beacon topic of the [`EVENT_BUS`].
     ///
     /// Automatically closes on error and logs termination as fatal.
+    /// In dry-run mode there is no announcement stream and the hub stays idle.
     pub(crate) async fn run_announcement_hub(&self) {
         let url = {
             let client = self.f_cont_lock.read().await.client();
+            if client.simulated_backend().is_some() {
+                log!("Dry-run: no announcement stream available.");
+                return std::future::pending().await;
+            }
             client.url().to_string()
         };
         let mut es = EventSource::get(url + "/announcements");
@@ -103,7 +257,7 @@ impl Supervisor {
                 Ok(Event::Open) => log!("Starting event supervisor loop!"),
                 Ok(Event::Message(msg)) => {
                     let msg_str = format!("{msg:#?}");
-                    if self.event_hub.send((Utc::now(), msg_str)).is_err() {
+                    if !EVENT_BUS.publish(BeaconEvent::Announcement(Utc::now(), msg_str)) {
                         event!("No Receiver for: {msg:#?}");
                     }
                 }
@@ -118,29 +272,128 @@ impl Supervisor {
 
     /// Triggers daily full map export and upload at 22:55 UTC.
     ///
-    /// This repeats daily and logs errors upon failure.
+    /// This repeats daily and logs errors upon failure. A failed upload is resumed a few
+    /// times, re-sending only the chunks not yet confirmed by the DRS.
     ///
     /// # Arguments
     /// * `c_cont` – Shared reference to the `CameraController`.
     pub(crate) async fn run_daily_map_uploader(&self, c_cont: Arc<CameraController>) {
-        let now = Utc::now();
-        let end_of_day = NaiveTime::from_hms_opt(22, 55, 0).unwrap();
-        let upload_t = now.date_naive().and_time(end_of_day);
-        let mut next_upload_t = Utc.from_utc_datetime(&upload_t);
+        let mut next_upload_t = Self::daily_map_upload_t(Utc::now());
         loop {
             let next_upload_dt = (next_upload_t - Utc::now()).to_std().unwrap_or(DT_0_STD);
             tokio::time::sleep(next_upload_dt).await;
             c_cont.export_full_snapshot().await.unwrap_or_else(|e| {
                 error!("Error exporting full snapshot: {e}.");
             });
-            c_cont.upload_daily_map_png().await.unwrap_or_else(|e| {
-                error!("Error uploading Daily Map: {e}.");
-            });
-            info!("Successfully uploaded Daily Map!");
+            match c_cont.start_daily_map_upload() {
+                Ok(mut upload) => Self::upload_daily_map(&c_cont, &mut upload).await,
+                Err(e) => error!("Error uploading Daily Map: {e}."),
+            }
             next_upload_t = next_upload_t.checked_add_signed(TimeDelta::days(1)).unwrap();
         }
     }
 
+    /// Monitors the free disk space of the imaging pipeline.
+    ///
+    /// A full disk corrupts the memory-mapped map buffer, so once the free space falls below
+    /// `min_free_disk_mib`, all objective images neither waiting for an upload retry nor being
+    /// uploaded are deleted and a [`SafetyEvent::DiskSpaceLow`] alerts the operator console.
+    /// The event is repeated only after the free space recovered in between.
+    ///
+    /// # Arguments
+    /// * `c_cont` – Shared reference to the `CameraController`.
+    pub(crate) async fn run_storage_monitor(&self, c_cont: Arc<CameraController>) {
+        let mut low = false;
+        loop {
+            let report = c_cont.storage_report().await;
+            let min_free = MissionConfig::get().imaging.min_free_disk();
+            match report.free_bytes() {
+                Some(free) if free < min_free => {
+                    let freed = c_cont.reclaim_disk_space().await;
+                    if !low {
+                        error!("Disk space low, {report}! Freed {freed} bytes of images.");
+                        EVENT_BUS.publish(SafetyEvent::DiskSpaceLow(free));
+                    }
+                    low = true;
+                }
+                Some(_) => {
+                    if low {
+                        info!("Disk space recovered, {report}.");
+                    }
+                    low = false;
+                }
+                None => {}
+            }
+            tokio::time::sleep(Self::STORAGE_CHECK_INTERVAL).await;
+        }
+    }
+
+    /// Uploads the daily map, resuming the upload after failures.
+    ///
+    /// # Arguments
+    /// * `c_cont` – Shared reference to the `CameraController`.
+    /// * `upload` – The progress of the upload.
+    async fn upload_daily_map(c_cont: &Arc<CameraController>, upload: &mut DailyMapUpload) {
+        for attempt in 0..=Self::DAILY_MAP_RESUMES {
+            if attempt > 0 {
+                tokio::time::sleep(Self::DAILY_MAP_RESUME_DELAY).await;
+                log!("Resuming Daily Map upload at {:.0}%.", upload.progress() * 100.0);
+            }
+            match c_cont.resume_daily_map_upload(upload).await {
+                Ok(()) => {
+                    info!("Successfully uploaded Daily Map!");
+                    return;
+                }
+                Err(e) => error!("Error uploading Daily Map: {e}."),
+            }
+        }
+        error!("Giving up Daily Map upload at {:.0}%.", upload.progress() * 100.0);
+    }
+
+    /// Returns the daily map upload time on the day of `t`.
+    ///
+    /// # Arguments
+    /// * `t` – A time on the requested day.
+    pub(crate) fn daily_map_upload_t(t: DateTime<Utc>) -> DateTime<Utc> {
+        Utc.from_utc_datetime(&t.date_naive().and_time(Self::DAILY_MAP_UPLOAD_T))
+    }
+
+    /// Periodically stores a map thumbnail for replays and dumps the session of the day.
+    ///
+    /// # Arguments
+    /// * `c_cont` – Shared reference to the `CameraController`.
+    pub(crate) async fn run_replay_recorder(&self, c_cont: Arc<CameraController>) {
+        if let Err(e) = std::fs::create_dir_all(FlightTrack::FRAME_DIR) {
+            error!("Error creating replay directory: {e}. Not recording thumbnails.");
+            return;
+        }
+        loop {
+            tokio::time::sleep(Self::REPLAY_FRAME_INTERVAL).await;
+            let now = Utc::now();
+            let ext = CameraController::internal_codec().extension();
+            let file_name = format!("thumb_{}.{ext}", now.timestamp());
+            let path = Path::new(FlightTrack::FRAME_DIR).join(file_name);
+            let stored = c_cont
+                .export_full_thumbnail()
+                .await
+                .and_then(|img| std::fs::write(&path, img.data).map_err(Into::into))
+                .map_err(|e| e.to_string());
+            match stored {
+                Ok(()) => self.track.write().await.add_frame(now, path),
+                Err(e) => warn!("Error storing replay thumbnail: {e}."),
+            }
+            self.replay_session(now.date_naive()).await.dump_json();
+        }
+    }
+
+    /// Aggregates the recorded flight track and thumbnails of a single day.
+    ///
+    /// # Arguments
+    /// * `day` – The mission day to aggregate.
+    pub(crate) async fn replay_session(&self, day: NaiveDate) -> ReplaySession {
+        self.track.read().await.session(day)
+    }
+
     /// Receive and schedule a secret objective `id` and assigns coordinates to it if valid.
     /// This is called by the user console when assigning a zone to a secret objective.
     ///
@@ -148,8 +401,8 @@ impl Supervisor {
     ///
     /// # Arguments
     /// * `id` – Unique identifier of the objective.
-    /// * `zone` – Assigned coordinates `[x_1, y_1, x_2, y_2]`.
-    pub(crate) async fn schedule_secret_objective(&self, id: usize, zone: [i32; 4]) {
+    /// * `zone` – Assigned zone.
+    pub(crate) async fn schedule_secret_objective(&self, id: ImgObjectiveId, zone: ZoneRect) {
         let mut secret_obj = self.current_secret_objectives.write().await;
         if let Some(pos) =
             secret_obj.iter().position(|obj| obj.id() == id && obj.end() > Utc::now() && obj.start() < Utc::now() + TimeDelta::hours(4))
@@ -160,7 +413,22 @@ impl Supervisor {
         }
     }
 
+    /// Returns the secret objectives that were not yet assigned a zone.
+    pub(crate) async fn pending_secret_objectives(&self) -> Vec<ImageObjective> {
+        self.current_secret_objectives.read().await.clone()
+    }
+
+    /// Drops a secret objective whose zone was found autonomously, so that it is no longer
+    /// offered for zone assignment.
+    ///
+    /// # Arguments
+    /// * `id` – Unique identifier of the objective.
+    pub(crate) async fn resolve_secret_objective(&self, id: ImgObjectiveId) {
+        self.current_secret_objectives.write().await.retain(|obj| obj.id() != id);
+    }
+
     /// Main observation loop that:
+    /// - Receives pushed observations from the backend stream, falling back to polling.
     /// - Monitors for safe-mode transitions.
     /// - Periodically polls objectives from the backend.
     /// - Filters and sends active objectives to downstream systems.
@@ -169,31 +437,69 @@ impl Supervisor {
     #[allow(clippy::cast_precision_loss, clippy::too_many_lines)]
     pub(crate) async fn run_obs_obj_mon(&self) {
         let mut last_objective_check = Utc::now() - Self::OBJ_UPDATE_INTERVAL;
-        let mut id_list: HashSet<usize> = HashSet::new();
+        let mut id_list: HashSet<ImgObjectiveId> = HashSet::new();
+        let mut beac_id_list: HashSet<BeaconObjectiveId> = HashSet::new();
+        let mut announced: HashMap<ImgObjectiveId, DateTime<Utc>> = HashMap::new();
         Self::prefill_id_list(&mut id_list);
+        Self::prefill_id_list(&mut beac_id_list);
+        let mut obs_stream = ObservationStream::new(&self.f_cont_lock.read().await.client());
         log!("Starting obs/obj supervisor loop!");
         loop {
+            let pushed_obs = obs_stream.next_observation(Self::OBS_UPDATE_INTERVAL).await;
             let mut f_cont = self.f_cont_lock.write().await;
             // Update observation and fetch new position
-            f_cont.update_observation().await;
+            let obs_ok = if let Some(obs) = &pushed_obs {
+                f_cont.apply_observation(obs);
+                true
+            } else {
+                f_cont.update_observation().await
+            };
+            let mut outage = None;
+            self.obs_health.send_modify(|health| outage = health.record(obs_ok));
+            if let Some((start, end)) = outage {
+                info!("Observation link restored after {}s.", (end - start).num_seconds());
+                EVENT_BUS.publish(SafetyEvent::LinkRestored(start, end));
+            }
+            self.track.write().await.record(&*f_cont);
             let last_update = Instant::now();
 
-            let is_safe_trans = {
+            let (is_safe_trans, in_safe) = {
                 let current_state = f_cont.state();
                 let target_state = f_cont.target_state();
-                current_state == FlightState::Transition && target_state.is_none()
+                (
+                    current_state == FlightState::Transition && target_state.is_none(),
+                    current_state == FlightState::Safe
+                        || target_state == Some(FlightState::Safe),
+                )
             };
             if is_safe_trans {
                 warn!("Unplanned Safe Mode Transition Detected! Notifying!");
-                self.safe_mon.notify_one();
-                self.f_cont_lock.write().await.safe_detected();
+                f_cont.safe_detected();
+            }
+            let safe_changed = self.safe_mon.send_if_modified(|safe| {
+                let changed = *safe != (is_safe_trans || in_safe);
+                *safe = is_safe_trans || in_safe;
+                changed
+            });
+            if safe_changed {
+                let in_safe_mode = *self.safe_mon.borrow();
+                EVENT_BUS.publish(if in_safe_mode {
+                    SafetyEvent::SafeModeEntered
+                } else {
+                    SafetyEvent::SafeModeLeft
+                });
             }
 
             drop(f_cont); // Release the lock early to avoid blocking
 
-            if last_objective_check + Self::OBJ_UPDATE_INTERVAL < Utc::now() {
-                let handle = self.f_cont_lock.read().await.client();
-                let objective_list = ObjectiveListRequest {}.send_request(&handle).await.unwrap();
+            let backend_up = *self.backend_up.borrow();
+            let fetched =
+                if backend_up && last_objective_check + Self::OBJ_UPDATE_INTERVAL < Utc::now() {
+                    self.fetch_objective_list().await
+                } else {
+                    None
+                };
+            if let Some(objective_list) = fetched {
                 let mut send_img_objs = vec![];
                 let mut send_beac_objs = vec![];
 
@@ -205,6 +511,7 @@ impl Supervisor {
                     let is_future_short = img_obj.end() < Utc::now() + TimeDelta::hours(5);
                     if !id_list.contains(&img_obj.id()) {
                         if is_secret {
+                            OBJECTIVE_TRACKER.record(img_obj.id(), LifecycleStage::Discovered);
                             secret_list.push(img_obj.clone());
                             id_list.insert(img_obj.id());
                         } else if obj_on || (is_future && is_future_short) {
@@ -216,32 +523,165 @@ impl Supervisor {
                 drop(secret_list);
                 for b_o in objective_list.beacon_objectives() {
                     let obj_on = b_o.start() < Utc::now() && b_o.end() > Utc::now();
-                    if obj_on && !id_list.contains(&b_o.id()) {
+                    if obj_on && !beac_id_list.contains(&b_o.id()) {
                         send_beac_objs.push(BeaconObjective::from(b_o.clone()));
                     }
                 }
+                Self::detect_removed_zos(&mut announced, objective_list.img_objectives());
                 for obj in send_img_objs {
                     id_list.insert(obj.id());
+                    announced.insert(obj.id(), obj.end());
+                    OBJECTIVE_TRACKER.record(obj.id(), LifecycleStage::Discovered);
                     self.zo_mon.send(obj).await.unwrap();
                 }
                 for beac_obj in send_beac_objs {
-                    id_list.insert(beac_obj.id());
+                    beac_id_list.insert(beac_obj.id());
+                    OBJECTIVE_TRACKER.record(beac_obj.id(), LifecycleStage::Discovered);
                     self.bo_mon.send(beac_obj).await.unwrap();
                 }
                 last_objective_check = Utc::now();
             }
 
-            tokio::time::sleep_until(last_update + Self::OBS_UPDATE_INTERVAL).await;
+            // A live stream paces the loop itself, polling needs a fixed interval
+            if !obs_stream.is_live() {
+                tokio::time::sleep_until(last_update + Self::OBS_UPDATE_INTERVAL).await;
+            }
         }
     }
 
+    /// Runs the watchdog, which monitors the observation updates and the flight state and
+    /// triggers a recovery chosen by `policy` whenever a fault is detected.
+    ///
+    /// All recoveries are handed to the active mode through the safe-mode path; a full reset
+    /// additionally resets the satellite first. While a requested recovery is pending, no
+    /// further faults are checked.
+    ///
+    /// # Arguments
+    /// * `policy` – The [`RecoveryPolicy`] deciding on the action for each fault.
+    pub(crate) async fn run_watchdog(&self, mut policy: impl RecoveryPolicy) {
+        let mut watchdog = Watchdog::default();
+        log!("Starting supervisor watchdog!");
+        loop {
+            tokio::time::sleep(Self::WATCHDOG_INTERVAL).await;
+            let (state, pending) = {
+                let f_cont = self.f_cont_lock.read().await;
+                (f_cont.state(), f_cont.recovery_pending())
+            };
+            if pending {
+                watchdog.clear();
+                continue;
+            }
+            let now = Instant::now();
+            let health = *self.obs_health.borrow();
+            let Some(fault) = watchdog.check(health, state, now) else { continue };
+            let Some(action) = policy.plan(fault, now) else {
+                warn!("Watchdog detected {fault:?}, no recovery configured!");
+                WatchdogIncident::new(fault, None, true).dump_json();
+                watchdog.clear();
+                tokio::time::sleep(Self::WATCHDOG_COOLDOWN).await;
+                continue;
+            };
+            error!("Watchdog detected {fault:?}! Recovering with {action}.");
+            let success = action != RecoveryAction::FullReset
+                || FlightComputer::try_reset(&self.f_cont_lock).await;
+            if !success {
+                error!("Watchdog reset failed, falling back to mode re-initialization!");
+            }
+            self.f_cont_lock.write().await.request_recovery(action);
+            WatchdogIncident::new(fault, Some(action), success).dump_json();
+            watchdog.clear();
+            tokio::time::sleep(Self::WATCHDOG_COOLDOWN).await;
+        }
+    }
+
+    /// Monitors the deviation of MELVINs position from the closed orbit.
+    ///
+    /// While MELVIN flies at an unchanged velocity in `Charge`, `Acquisition` or `Comms`, the
+    /// distance to the closest orbit segment is checked periodically. If it exceeds
+    /// `orbit_replan_deviation_px` on consecutive checks, a [`SafetyEvent::OrbitDeviation`] is
+    /// published, so that the closed orbit can be replanned from the current state.
+    ///
+    /// # Arguments
+    /// * `c_orbit` – The shared closed orbit.
+    pub(crate) async fn run_orbit_monitor(&self, c_orbit: Arc<RwLock<ClosedOrbit>>) {
+        let (mut streak, mut last_vel) = (0, None);
+        loop {
+            tokio::time::sleep(Self::ORBIT_CHECK_INTERVAL).await;
+            let max_dev = MissionConfig::get().runtime.orbit_replan_deviation_px;
+            let (state, pos, vel) = {
+                let f_cont = self.f_cont_lock.read().await;
+                (f_cont.state(), f_cont.current_pos(), f_cont.current_vel())
+            };
+            let stable = last_vel.replace(vel) == Some(vel)
+                && matches!(
+                    state,
+                    FlightState::Charge | FlightState::Acquisition | FlightState::Comms
+                );
+            let (_, dev) = c_orbit.read().await.get_closest_deviation(pos);
+            if max_dev <= 0.0 || !stable || dev.abs() <= I32F32::from_num(max_dev) {
+                streak = 0;
+                continue;
+            }
+            streak += 1;
+            if streak < Self::ORBIT_DEVIATION_CHECKS {
+                continue;
+            }
+            warn!("Deviating {}px from the closed orbit at {pos}!", dev.abs());
+            EVENT_BUS.publish(SafetyEvent::OrbitDeviation(dev.abs()));
+            (streak, last_vel) = (0, None);
+            tokio::time::sleep(Self::ORBIT_REPLAN_COOLDOWN).await;
+        }
+    }
+
+    /// Requests the current objective list from the backend.
+    ///
+    /// # Returns
+    /// The objective list, or `None` if the request failed and is retried on the next tick.
+    async fn fetch_objective_list(&self) -> Option<ObjectiveListResponse> {
+        let handle = self.f_cont_lock.read().await.client();
+        ObjectiveListRequest {}
+            .send_request(&handle)
+            .await
+            .map_err(|e| warn!("Objective list request failed, retrying next tick: {e}."))
+            .ok()
+    }
+
+    /// Detects announced zoned objectives that vanished from the backend objective list
+    /// before their end and publishes their removal. Vanished objectives that were not
+    /// completed are marked as failed in the [`OBJECTIVE_TRACKER`].
+    ///
+    /// # Arguments
+    /// * `announced` – The announced objectives by ID with their end time.
+    /// * `img_objectives` – The image objectives of the current backend objective list.
+    fn detect_removed_zos(
+        announced: &mut HashMap<ImgObjectiveId, DateTime<Utc>>,
+        img_objectives: &[ImageObjective],
+    ) {
+        let listed: HashSet<ImgObjectiveId> = img_objectives.iter().map(ImageObjective::id).collect();
+        announced.retain(|id, end| {
+            if listed.contains(id) {
+                return true;
+            }
+            if *end > Utc::now() {
+                obj!("Zoned Objective {id} was deleted by the backend!");
+                OBJECTIVE_TRACKER.fail(*id, "deleted by the backend");
+                if !EVENT_BUS.publish(ObjectiveEvent::ZoRemoved(*id)) {
+                    warn!("No receiver for removal of Zoned Objective {id}.");
+                }
+            } else {
+                OBJECTIVE_TRACKER.fail(*id, "expired");
+            }
+            false
+        });
+    }
+
     /// Reads the environment variable `SKIP_OBJ` and adds valid IDs to the internal filter list.
     ///
     /// Used to prevent repeat processing of already completed or irrelevant objectives.
     ///
     /// # Arguments
-    /// * `id_list` – A mutable reference to the set of objective IDs.
-    fn prefill_id_list(id_list: &mut HashSet<usize>) {
+    /// * `id_list` – A mutable reference to the set of objective IDs of one kind.
+    fn prefill_id_list<K: ObjectiveKind>(id_list: &mut HashSet<ObjectiveId<K>>) {
         let done_ids: Vec<Option<usize>> = env::var(Self::ENV_SKIP_OBJ)
             .unwrap_or_default()
             .split(',')
@@ -250,8 +690,26 @@ impl Supervisor {
             .map(|s| s.parse::<usize>().ok())
             .collect();
         for done_id in done_ids.into_iter().flatten() {
-            info!("Prefilling done obj id list with id: {done_id}");
-            id_list.insert(done_id);
+            info!("Prefilling done {} id list with id: {done_id}", K::NAME);
+            id_list.insert(ObjectiveId::new(done_id));
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http_handler::http_client::HTTPClient;
+
+    #[tokio::test]
+    async fn test_abort_rejected_outside_abortable_modes() {
+        let f_cont = FlightComputer::new(Arc::new(HTTPClient::simulated())).await;
+        let (supervisor, _, _) = Supervisor::new(Arc::new(RwLock::new(f_cont)));
+        let res = supervisor.execute_manual_command(ManualCommand::AbortMode).await;
+        assert_eq!(res, Err(CommandRejected::NotAbortable));
+
+        supervisor.set_abortable(true);
+        let res = supervisor.execute_manual_command(ManualCommand::AbortMode).await;
+        assert_eq!(res, Ok(ManualCommand::AbortMode));
+    }
+}
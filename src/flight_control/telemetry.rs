@@ -0,0 +1,39 @@
+use super::{FlightComputer, FlightState};
+use crate::imaging::CameraAngle;
+use crate::util::Vec2D;
+use chrono::{DateTime, Utc};
+use fixed::types::I32F32;
+
+/// Read-only view on the last observed state of MELVIN.
+///
+/// This is the backend-independent interface of the flight computer for analysis tools
+/// and alternative frontends, which may implement it for recorded or simulated telemetry.
+pub trait FlightTelemetry {
+    /// Returns the current position in map coordinates.
+    fn pos(&self) -> Vec2D<I32F32>;
+    /// Returns the current velocity.
+    fn vel(&self) -> Vec2D<I32F32>;
+    /// Returns the current flight state.
+    fn flight_state(&self) -> FlightState;
+    /// Returns the current camera angle.
+    fn angle(&self) -> CameraAngle;
+    /// Returns the current battery level.
+    fn battery(&self) -> I32F32;
+    /// Returns the maximum battery capacity.
+    fn battery_max(&self) -> I32F32;
+    /// Returns the remaining fuel.
+    fn fuel(&self) -> I32F32;
+    /// Returns the timestamp of the last observation.
+    fn observed_at(&self) -> DateTime<Utc>;
+}
+
+impl FlightTelemetry for FlightComputer {
+    fn pos(&self) -> Vec2D<I32F32> { self.current_pos() }
+    fn vel(&self) -> Vec2D<I32F32> { self.current_vel() }
+    fn flight_state(&self) -> FlightState { self.state() }
+    fn angle(&self) -> CameraAngle { self.current_angle() }
+    fn battery(&self) -> I32F32 { self.current_battery() }
+    fn battery_max(&self) -> I32F32 { self.max_battery() }
+    fn fuel(&self) -> I32F32 { self.fuel_left() }
+    fn observed_at(&self) -> DateTime<Utc> { self.last_observation_timestamp() }
+}
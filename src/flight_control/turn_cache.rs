@@ -0,0 +1,86 @@
+use super::flight_computer::TurnsClockCClockTup;
+use crate::util::Vec2D;
+use fixed::types::I32F32;
+use std::sync::{Arc, Mutex};
+
+/// The process-wide cache of the most recently computed turn tables.
+pub(super) static TURN_CACHE: TurnCache = TurnCache::new();
+
+/// Single-entry memoization of the turn tables used for burn planning.
+///
+/// Burns are almost always planned from the static orbit velocity, so the tables of a single
+/// velocity are kept until MELVIN observes a different one.
+pub(super) struct TurnCache {
+    /// The rounded velocity and the turn tables computed for it.
+    entry: Mutex<Option<(Vec2D<I32F32>, Arc<TurnsClockCClockTup>)>>,
+}
+
+impl TurnCache {
+    /// Creates a new, empty [`TurnCache`].
+    const fn new() -> Self { Self { entry: Mutex::new(None) } }
+
+    /// Returns the turn tables cached for `vel`, computing and caching them on a miss.
+    ///
+    /// # Arguments
+    /// * `vel` – The rounded initial velocity.
+    /// * `compute` – Computes the turn tables of the initial velocity.
+    pub(super) fn get_or_compute(
+        &self,
+        vel: Vec2D<I32F32>,
+        compute: impl FnOnce() -> TurnsClockCClockTup,
+    ) -> Arc<TurnsClockCClockTup> {
+        let mut entry = self.entry.lock().unwrap();
+        if let Some((cached_vel, turns)) = entry.as_ref() {
+            if *cached_vel == vel {
+                return Arc::clone(turns);
+            }
+        }
+        let turns = Arc::new(compute());
+        *entry = Some((vel, Arc::clone(&turns)));
+        turns
+    }
+
+    /// Drops the cached turn tables unless they were computed for `vel`.
+    ///
+    /// # Arguments
+    /// * `vel` – The rounded current velocity.
+    pub(super) fn invalidate_unless(&self, vel: Vec2D<I32F32>) {
+        let mut entry = self.entry.lock().unwrap();
+        if entry.as_ref().is_some_and(|(cached_vel, _)| *cached_vel != vel) {
+            *entry = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flight_control::FlightComputer;
+
+    #[test]
+    fn test_turn_cache() {
+        let cache = TurnCache::new();
+        let vel = Vec2D::new(I32F32::lit("6.4"), I32F32::lit("7.4"));
+        let compute = || FlightComputer::compute_possible_turns(vel);
+        let first = cache.get_or_compute(vel, compute);
+        let second = cache.get_or_compute(vel, || unreachable!("cached turns were recomputed"));
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(*first, FlightComputer::compute_possible_turns(vel));
+
+        cache.invalidate_unless(vel);
+        let kept = cache.get_or_compute(vel, || unreachable!("cached turns were dropped"));
+        assert!(Arc::ptr_eq(&first, &kept));
+
+        let other = Vec2D::new(I32F32::lit("-3.0"), I32F32::lit("4.5"));
+        cache.invalidate_unless(other);
+        let recomputed = cache.get_or_compute(vel, compute);
+        assert!(!Arc::ptr_eq(&first, &recomputed));
+
+        // Unrounded velocities get the tables of their rounded velocity
+        let unrounded = Vec2D::new(I32F32::lit("6.4031"), I32F32::lit("7.3996"));
+        let (rounded, _) = FlightComputer::round_vel(unrounded);
+        let turns = FlightComputer::possible_turns(unrounded);
+        assert_eq!(*turns, FlightComputer::compute_possible_turns(rounded));
+        assert_eq!(*turns, *FlightComputer::possible_turns(rounded));
+    }
+}
@@ -0,0 +1,246 @@
+use super::FlightState;
+use crate::util::{MissionConfig, logger::JsonDump};
+use chrono::{DateTime, TimeDelta, Utc};
+use std::time::Duration;
+use strum_macros::Display as StrumDisplay;
+use tokio::time::Instant;
+
+/// Faults detected by the watchdog of the [`Supervisor`](super::Supervisor).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub(crate) enum WatchdogFault {
+    /// No observation was applied for the given number of seconds.
+    StaleObservation(u64),
+    /// The given number of consecutive observation requests failed.
+    HttpFailures(u32),
+    /// The satellite remained in a state that no mode commands for longer than the grace period.
+    UnexpectedState(FlightState),
+}
+
+/// Recovery actions the watchdog can trigger, ordered by severity.
+///
+/// Every action ends in the regular safe-mode path, so the active mode escapes through its
+/// `safe_handler` and re-initializes itself.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    StrumDisplay,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum RecoveryAction {
+    /// Aborts the active mode and re-initializes it.
+    ReInit,
+    /// Like [`RecoveryAction::ReInit`], but escapes to `Charge` regardless of the battery.
+    ForceCharge,
+    /// Resets the satellite through the DRS before re-initializing the mode.
+    FullReset,
+}
+
+impl RecoveryAction {
+    /// Returns the next more severe action.
+    fn escalated(self) -> Self {
+        match self {
+            Self::ReInit => Self::ForceCharge,
+            Self::ForceCharge | Self::FullReset => Self::FullReset,
+        }
+    }
+}
+
+/// A strategy mapping detected faults to recovery actions.
+pub(crate) trait RecoveryPolicy: Send {
+    /// Chooses the recovery for a detected fault.
+    ///
+    /// # Arguments
+    /// * `fault` – The detected fault.
+    /// * `now` – The time of detection.
+    ///
+    /// # Returns
+    /// The action to take, or `None` if the fault should only be reported.
+    fn plan(&mut self, fault: WatchdogFault, now: Instant) -> Option<RecoveryAction>;
+}
+
+/// The default [`RecoveryPolicy`]: applies the configured action for each fault and escalates
+/// one level whenever the previous recovery did not hold for the escalation window.
+#[derive(Debug, Default)]
+pub(crate) struct EscalatingPolicy {
+    /// The last triggered action and its time.
+    last: Option<(RecoveryAction, Instant)>,
+}
+
+impl RecoveryPolicy for EscalatingPolicy {
+    fn plan(&mut self, fault: WatchdogFault, now: Instant) -> Option<RecoveryAction> {
+        let cfg = &MissionConfig::get().runtime;
+        let base = match fault {
+            WatchdogFault::StaleObservation(_) => cfg.watchdog_stale_obs_action,
+            WatchdogFault::HttpFailures(_) => cfg.watchdog_http_action,
+            WatchdogFault::UnexpectedState(_) => cfg.watchdog_state_action,
+        };
+        let window = Duration::from_secs(u64::from(cfg.watchdog_escalation_window_s));
+        let action = match self.last {
+            Some((prev, t)) if now.duration_since(t) < window => prev.escalated().max(base),
+            _ => base,
+        };
+        self.last = Some((action, now));
+        Some(action)
+    }
+}
+
+/// Health of the observation updates, maintained by the observation loop.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ObservationHealth {
+    /// The time the last observation was applied.
+    last_ok: Instant,
+    /// The number of consecutive failed observation requests.
+    http_failures: u32,
+    /// The time of the first failed request of the current failure streak.
+    outage_start: Option<DateTime<Utc>>,
+}
+
+impl ObservationHealth {
+    /// The minimum length of a failure streak that counts as a link outage.
+    const MIN_OUTAGE: TimeDelta = TimeDelta::seconds(10);
+
+    /// Creates a new, healthy [`ObservationHealth`].
+    pub(crate) fn new() -> Self {
+        Self { last_ok: Instant::now(), http_failures: 0, outage_start: None }
+    }
+
+    /// Records the outcome of an observation update.
+    ///
+    /// # Arguments
+    /// * `ok` – Whether an observation was applied.
+    ///
+    /// # Returns
+    /// The start and end of the link outage if this update ended one.
+    pub(crate) fn record(&mut self, ok: bool) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        let now = Utc::now();
+        if ok {
+            self.last_ok = Instant::now();
+            self.http_failures = 0;
+            let start = self.outage_start.take()?;
+            (now - start >= Self::MIN_OUTAGE).then_some((start, now))
+        } else {
+            self.http_failures += 1;
+            self.outage_start.get_or_insert(now);
+            None
+        }
+    }
+}
+
+/// Fault detection of the supervisor watchdog.
+#[derive(Debug, Default)]
+pub(crate) struct Watchdog {
+    /// The unexpected state the satellite is currently in and when it was first seen.
+    unexpected_since: Option<(FlightState, Instant)>,
+}
+
+impl Watchdog {
+    /// States that no mode remains in for long during regular operation.
+    const UNEXPECTED_STATES: [FlightState; 3] =
+        [FlightState::Deployment, FlightState::Transition, FlightState::Safe];
+
+    /// Checks the current observation health and flight state for faults.
+    ///
+    /// # Arguments
+    /// * `health` – The current observation health.
+    /// * `state` – The last observed flight state.
+    /// * `now` – The time of the check.
+    ///
+    /// # Returns
+    /// The most severe detected fault, if any.
+    pub(crate) fn check(
+        &mut self,
+        health: ObservationHealth,
+        state: FlightState,
+        now: Instant,
+    ) -> Option<WatchdogFault> {
+        let cfg = &MissionConfig::get().runtime;
+        if health.http_failures >= cfg.watchdog_max_http_failures {
+            return Some(WatchdogFault::HttpFailures(health.http_failures));
+        }
+        let obs_age = now.duration_since(health.last_ok).as_secs();
+        if obs_age >= u64::from(cfg.watchdog_stale_obs_s) {
+            return Some(WatchdogFault::StaleObservation(obs_age));
+        }
+        if !Self::UNEXPECTED_STATES.contains(&state) {
+            self.unexpected_since = None;
+            return None;
+        }
+        let since = match self.unexpected_since {
+            Some((prev, since)) if prev == state => since,
+            _ => self.unexpected_since.insert((state, now)).1,
+        };
+        let grace = Duration::from_secs(u64::from(cfg.watchdog_state_grace_s));
+        (now.duration_since(since) >= grace).then_some(WatchdogFault::UnexpectedState(state))
+    }
+
+    /// Resets the detection after a recovery was triggered.
+    pub(crate) fn clear(&mut self) { self.unexpected_since = None; }
+}
+
+/// A record of a fault detected by the watchdog and the triggered recovery.
+#[derive(Debug, serde::Serialize)]
+pub(crate) struct WatchdogIncident {
+    /// The time of detection.
+    t: DateTime<Utc>,
+    /// The detected fault.
+    fault: WatchdogFault,
+    /// The triggered recovery, if any.
+    action: Option<RecoveryAction>,
+    /// Whether all requests of the recovery succeeded.
+    success: bool,
+}
+
+impl WatchdogIncident {
+    /// Creates a new [`WatchdogIncident`] detected now.
+    ///
+    /// # Arguments
+    /// * `fault` – The detected fault.
+    /// * `action` – The triggered recovery, if any.
+    /// * `success` – Whether all requests of the recovery succeeded.
+    pub(crate) fn new(fault: WatchdogFault, action: Option<RecoveryAction>, success: bool) -> Self {
+        Self { t: Utc::now(), fault, action, success }
+    }
+}
+
+impl JsonDump for WatchdogIncident {
+    /// Returns a unique filename based on the detection time.
+    fn file_name(&self) -> String { format!("incident_{}", self.t.format("%Y-%m-%dT%H-%M-%S")) }
+
+    /// Specifies the output directory for dumped watchdog incidents.
+    fn dir_name(&self) -> &'static str { "watchdog" }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recovery_escalation() {
+        let mut policy = EscalatingPolicy::default();
+        let cfg = &MissionConfig::get().runtime;
+        let window = Duration::from_secs(u64::from(cfg.watchdog_escalation_window_s));
+        let base = cfg.watchdog_stale_obs_action;
+        let fault = WatchdogFault::StaleObservation(120);
+        let t0 = Instant::now();
+
+        assert_eq!(policy.plan(fault, t0), Some(base));
+        let mut expected = base;
+        for i in 1..4 {
+            expected = expected.escalated();
+            let t = t0 + Duration::from_secs(60 * i);
+            assert_eq!(policy.plan(fault, t), Some(expected));
+        }
+        assert_eq!(expected, RecoveryAction::FullReset);
+        // A recovery that held for the escalation window starts over at the configured action.
+        let later = t0 + Duration::from_secs(180) + window;
+        assert_eq!(policy.plan(fault, later), Some(base));
+    }
+}
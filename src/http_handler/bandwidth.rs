@@ -0,0 +1,157 @@
+use crate::util::MissionConfig;
+use std::{
+    sync::{LazyLock, Mutex},
+    time::Duration,
+};
+use tokio::time::Instant;
+
+/// Classes of traffic sharing MELVINs network link.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TrafficClass {
+    /// Bulk image downloads from the DRS backend.
+    Bulk,
+    /// Interactive traffic to the operator console.
+    Console,
+}
+
+/// A simple token bucket measured in bytes.
+#[derive(Debug)]
+struct TokenBucket {
+    /// The currently available tokens, negative while in debt.
+    tokens: f64,
+    /// The maximum number of tokens that can be accumulated.
+    burst: f64,
+    /// The last time the bucket was refilled.
+    last: Instant,
+}
+
+impl TokenBucket {
+    /// Creates a new, full [`TokenBucket`].
+    fn new(burst: f64) -> Self { Self { tokens: burst, burst, last: Instant::now() } }
+
+    /// Takes `bytes` tokens from the bucket after refilling it with the given rate.
+    ///
+    /// # Arguments
+    /// * `bytes` – The number of bytes to send.
+    /// * `rate` – The refill rate in bytes per second.
+    ///
+    /// # Returns
+    /// * The time to wait until the taken tokens are paid off.
+    fn take(&mut self, bytes: f64, rate: f64) -> Duration {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate).min(self.burst) - bytes;
+        self.last = now;
+        if self.tokens >= 0.0 { Duration::ZERO } else { Duration::from_secs_f64(-self.tokens / rate) }
+    }
+}
+
+/// Shapes the traffic on MELVINs network link with one token bucket per [`TrafficClass`].
+///
+/// Console traffic may always use the full link rate. While the console was active
+/// recently, bulk downloads are limited to a share of the link, so that thumbnail pushes
+/// stay responsive during dense acquisition. Bulk traffic is slowed down, never paused.
+#[derive(Debug)]
+pub(crate) struct BandwidthShaper {
+    /// The link rate in bytes per second.
+    link_rate: f64,
+    /// The token buckets for bulk and console traffic and the last console activity.
+    state: Mutex<(TokenBucket, TokenBucket, Option<Instant>)>,
+}
+
+/// The global shaper for MELVINs network link.
+static LINK_SHAPER: LazyLock<BandwidthShaper> = LazyLock::new(BandwidthShaper::from_config);
+
+impl BandwidthShaper {
+    /// The share of the link left for bulk traffic while the console is active.
+    const BULK_SHARE_WHILE_CONSOLE: f64 = 0.4;
+    /// Console traffic within this duration counts as console activity.
+    const CONSOLE_ACTIVE_WINDOW: Duration = Duration::from_secs(3);
+    /// The burst size of each bucket in seconds of link rate.
+    const BURST_SECS: f64 = 0.5;
+
+    /// Returns the global shaper for MELVINs network link.
+    pub(crate) fn link() -> &'static BandwidthShaper { &LINK_SHAPER }
+
+    /// Creates a new [`BandwidthShaper`] with the link rate `network.link_rate_kibps` of the
+    /// [`MissionConfig`].
+    fn from_config() -> Self { Self::new(MissionConfig::get().network.link_rate_kibps * 1024.0) }
+
+    /// Creates a new [`BandwidthShaper`].
+    ///
+    /// # Arguments
+    /// * `link_rate` – The link rate in bytes per second.
+    pub(crate) fn new(link_rate: f64) -> Self {
+        let burst = link_rate * Self::BURST_SECS;
+        Self {
+            link_rate,
+            state: Mutex::new((TokenBucket::new(burst), TokenBucket::new(burst), None)),
+        }
+    }
+
+    /// Computes the delay needed before `bytes` of the given traffic class may be sent.
+    ///
+    /// # Arguments
+    /// * `class` – The traffic class.
+    /// * `bytes` – The number of bytes to send.
+    #[allow(clippy::cast_precision_loss)]
+    pub(crate) fn reserve(&self, class: TrafficClass, bytes: usize) -> Duration {
+        let mut state = self.state.lock().unwrap();
+        let (bulk, console, last_console) = &mut *state;
+        match class {
+            TrafficClass::Console => {
+                *last_console = Some(Instant::now());
+                console.take(bytes as f64, self.link_rate)
+            }
+            TrafficClass::Bulk => {
+                let console_active =
+                    last_console.is_some_and(|t| t.elapsed() < Self::CONSOLE_ACTIVE_WINDOW);
+                let share = if console_active { Self::BULK_SHARE_WHILE_CONSOLE } else { 1.0 };
+                bulk.take(bytes as f64, self.link_rate * share)
+            }
+        }
+    }
+
+    /// Waits until `bytes` of the given traffic class may be sent.
+    ///
+    /// # Arguments
+    /// * `class` – The traffic class.
+    /// * `bytes` – The number of bytes to send.
+    pub(crate) async fn acquire(&self, class: TrafficClass, bytes: usize) {
+        let delay = self.reserve(class, bytes);
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_bandwidth_shaper_delays() {
+        let shaper = BandwidthShaper::new(1000.0);
+        // Both buckets start with a burst of half a second of link rate
+        assert_eq!(shaper.reserve(TrafficClass::Console, 500), Duration::ZERO);
+        assert_eq!(shaper.reserve(TrafficClass::Console, 250), Duration::from_millis(250));
+        // Bulk traffic only gets its share of the link while the console is active
+        assert_eq!(shaper.reserve(TrafficClass::Bulk, 700), Duration::from_millis(500));
+
+        let idle = BandwidthShaper::CONSOLE_ACTIVE_WINDOW + Duration::from_secs(1);
+        tokio::time::advance(idle).await;
+        // The debt is paid off, the bucket refills up to the burst and gets the full link again
+        assert_eq!(shaper.reserve(TrafficClass::Bulk, 1000), Duration::from_millis(500));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_bandwidth_shaper_acquire() {
+        let shaper = BandwidthShaper::new(1000.0);
+        let start = Instant::now();
+        shaper.acquire(TrafficClass::Console, 500).await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+        shaper.acquire(TrafficClass::Console, 1000).await;
+        let waited = start.elapsed();
+        assert!(waited >= Duration::from_secs(1) && waited < Duration::from_millis(1010));
+    }
+}
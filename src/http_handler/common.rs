@@ -1,5 +1,6 @@
 use super::http_request::request_common::RequestError;
 use super::http_response::response_common::ResponseError;
+use crate::util::{BeaconObjectiveId, ImgObjectiveId, ZoneRect};
 use chrono::{DateTime, Utc};
 use strum_macros::Display;
 
@@ -12,7 +13,7 @@ use strum_macros::Display;
 #[serde(untagged)]
 pub(crate) enum ZoneType {
     /// A rectangular zone defined by `[x, y, x_2, y_2]`.
-    KnownZone([i32; 4]),
+    KnownZone(ZoneRect),
     /// A secret zone.
     SecretZone(String),
 }
@@ -24,7 +25,7 @@ pub(crate) enum ZoneType {
 #[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
 pub(crate) struct ImageObjective {
     /// Unique identifier for the objective.
-    id: usize,
+    id: ImgObjectiveId,
     /// Human-readable name of the objective.
     name: String,
     /// UTC timestamp marking when the objective becomes active.
@@ -47,7 +48,7 @@ pub(crate) struct ImageObjective {
 
 impl ImageObjective {
     /// Returns the objective’s unique identifier.
-    pub(crate) fn id(&self) -> usize { self.id }
+    pub(crate) fn id(&self) -> ImgObjectiveId { self.id }
     /// Returns the UTC start time for the objective.
     pub(crate) fn start(&self) -> DateTime<Utc> { self.start }
     /// Returns the UTC end time for the objective.
@@ -68,7 +69,7 @@ impl ImageObjective {
 #[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
 pub struct BeaconObjective {
     /// Unique identifier for the beacon objective.
-    id: usize,
+    id: BeaconObjectiveId,
     /// Display name of the beacon objective.
     name: String,
     /// UTC start time for the valid measurement window.
@@ -87,7 +88,7 @@ impl BeaconObjective {
     /// Returns the beacon objective’s name.
     pub(crate) fn name(&self) -> &str { self.name.as_str() }
     /// Returns the unique ID of the objective.
-    pub(crate) fn id(&self) -> usize { self.id }
+    pub(crate) fn id(&self) -> BeaconObjectiveId { self.id }
     /// Returns the number of attempts made to fulfill the objective.
     pub(crate) fn attempts_made(&self) -> u32 { self.attempts_made }
     /// Returns the UTC start time of the objective.
@@ -99,7 +100,7 @@ impl BeaconObjective {
 }
 
 /// A time slot during which communication (e.g., console downlink) is enabled.
-#[derive(serde::Deserialize, Debug)]
+#[derive(serde::Deserialize, Debug, Clone)]
 pub struct CommunicationSlot {
     /// Unique ID of the communication slot.
     id: usize,
@@ -113,10 +114,24 @@ pub struct CommunicationSlot {
 
 impl CommunicationSlot {
     /// Returns whether this communication slot is currently enabled.
-    fn is_enabled(&self) -> bool { self.enabled }
+    pub(crate) fn is_enabled(&self) -> bool { self.enabled }
 
     /// Returns the unique identifier for this slot.
-    fn id(&self) -> usize { self.id }
+    pub(crate) fn id(&self) -> usize { self.id }
+
+    /// Sets whether communication is enabled for this slot.
+    pub(crate) fn set_enabled(&mut self, enabled: bool) { self.enabled = enabled; }
+
+    /// Returns the UTC time when the slot opens.
+    pub(crate) fn start(&self) -> DateTime<Utc> { self.start }
+
+    /// Returns the UTC time when the slot closes.
+    pub(crate) fn end(&self) -> DateTime<Utc> { self.end }
+
+    /// Returns `true` if the slot overlaps the time window from `start` to `end`.
+    pub(crate) fn overlaps(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> bool {
+        self.start < end && start < self.end
+    }
 }
 
 /// Represents an achievement milestone defined by the simulation backend.
@@ -164,3 +179,8 @@ pub enum HTTPError {
 }
 
 impl std::error::Error for HTTPError {}
+
+impl From<reqwest::Error> for HTTPError {
+    /// Maps errors of a streamed response body to [`HTTPError::HTTPResponseError`].
+    fn from(e: reqwest::Error) -> Self { Self::HTTPResponseError(ResponseError::from(e)) }
+}
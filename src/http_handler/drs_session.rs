@@ -0,0 +1,257 @@
+use super::http_request::request_common::HTTPRequestMethod;
+use crate::{error, warn};
+use serde_json::{Value, json};
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    fs::File,
+    io::{BufWriter, Read, Write},
+    path::Path,
+    sync::Mutex,
+};
+use tokio_util::sync::CancellationToken;
+
+/// A recorded DRS request together with its response.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct SessionEntry {
+    /// The request method, endpoint, query and body identifying the request.
+    key: String,
+    /// The HTTP status code of the response.
+    status: u16,
+    /// The value of the `Content-Type` header of the response, if any.
+    content_type: Option<String>,
+    /// The raw response body, e.g. JSON or the bytes of an image.
+    body: Vec<u8>,
+}
+
+/// Whether a [`DrsSession`] records or replays the DRS responses.
+#[derive(Debug)]
+enum SessionMode {
+    /// Appends all exchanged requests to the session file.
+    Recording(Mutex<BufWriter<File>>),
+    /// Answers all requests from the recorded responses, by request key in recording order.
+    Replaying(Mutex<HashMap<String, VecDeque<SessionEntry>>>),
+}
+
+/// A session file capturing all DRS request/response pairs of a live run.
+///
+/// While recording, every exchanged request is appended to the session file as a
+/// length-prefixed `bincode` frame, including the byte streams of downloaded images. A replayed
+/// session answers each request with the next recorded response to an identical request, i.e.
+/// with the same method, endpoint, query and body. Given the same request sequence, a replay
+/// therefore reproduces the responses of the live run deterministically. A request that was
+/// never recorded means the replay diverged from the live run, which cancels
+/// [`DrsSession::diverged`].
+#[derive(Debug)]
+pub(crate) struct DrsSession {
+    /// The recording or replay state.
+    mode: SessionMode,
+    /// Cancelled once a replayed request was not recorded.
+    diverged: CancellationToken,
+}
+
+impl DrsSession {
+    /// Creates a new session file to record into.
+    ///
+    /// # Arguments
+    /// * `path` – The path of the session file, an existing file is truncated.
+    ///
+    /// # Errors
+    /// An I/O error if the file could not be created.
+    pub(crate) fn record(path: &Path) -> std::io::Result<Self> {
+        let writer = BufWriter::new(File::create(path)?);
+        let mode = SessionMode::Recording(Mutex::new(writer));
+        Ok(Self { mode, diverged: CancellationToken::new() })
+    }
+
+    /// Loads a recorded session file for replay.
+    ///
+    /// # Arguments
+    /// * `path` – The path of the session file.
+    ///
+    /// # Errors
+    /// An I/O error if the file could not be read or holds a corrupted frame.
+    pub(crate) fn replay(path: &Path) -> std::io::Result<Self> {
+        let mut data = Vec::new();
+        File::open(path)?.read_to_end(&mut data)?;
+        let mut entries: HashMap<String, VecDeque<SessionEntry>> = HashMap::new();
+        let mut rest = data.as_slice();
+        while let Some((len_bytes, frame)) = rest.split_first_chunk::<4>() {
+            let len = u32::from_le_bytes(*len_bytes) as usize;
+            let Some((encoded, next)) = frame.split_at_checked(len) else {
+                return Err(std::io::Error::other("truncated session frame"));
+            };
+            let (entry, _): (SessionEntry, _) =
+                bincode::serde::decode_from_slice(encoded, bincode::config::standard())
+                    .map_err(std::io::Error::other)?;
+            entries.entry(entry.key.clone()).or_default().push_back(entry);
+            rest = next;
+        }
+        let mode = SessionMode::Replaying(Mutex::new(entries));
+        Ok(Self { mode, diverged: CancellationToken::new() })
+    }
+
+    /// Returns a token that is cancelled once a replayed request was not recorded.
+    pub(crate) fn diverged(&self) -> &CancellationToken { &self.diverged }
+
+    /// Builds the key identifying a request.
+    ///
+    /// # Arguments
+    /// * `method` – The request method.
+    /// * `endpoint` – The request endpoint.
+    /// * `query` – The query parameters, sorted by name.
+    /// * `body` – The JSON-encoded request body, if any.
+    fn key(
+        method: &HTTPRequestMethod,
+        endpoint: &str,
+        query: &HashMap<&str, String>,
+        body: Option<&Value>,
+    ) -> String {
+        let sorted: BTreeMap<_, _> = query.iter().collect();
+        let encoded = body.map_or(String::new(), Value::to_string);
+        format!("{method:?} {endpoint} {sorted:?} {encoded}")
+    }
+
+    /// Records a received response if the session is recording.
+    ///
+    /// The response body is consumed and returned within a rebuilt, equivalent response.
+    ///
+    /// # Arguments
+    /// * `method` – The request method.
+    /// * `endpoint` – The request endpoint.
+    /// * `query` – The query parameters.
+    /// * `body` – The JSON-encoded request body, if any.
+    /// * `response` – The received response.
+    ///
+    /// # Errors
+    /// A `reqwest::Error` if the response body could not be received.
+    pub(crate) async fn capture(
+        &self,
+        method: &HTTPRequestMethod,
+        endpoint: &str,
+        query: &HashMap<&str, String>,
+        body: Option<&Value>,
+        response: reqwest::Response,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        let SessionMode::Recording(writer) = &self.mode else { return Ok(response) };
+        let status = response.status().as_u16();
+        let content_type = response
+            .headers()
+            .get(http::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(String::from);
+        let entry = SessionEntry {
+            key: Self::key(method, endpoint, query, body),
+            status,
+            content_type,
+            body: response.bytes().await?.to_vec(),
+        };
+        if let Err(e) = Self::append(&mut writer.lock().unwrap(), &entry) {
+            warn!("Failed to record DRS response to {endpoint}: {e}");
+        }
+        Ok(Self::response(entry.status, entry.content_type.as_deref(), entry.body))
+    }
+
+    /// Appends an entry to the session file as a length-prefixed frame.
+    ///
+    /// # Arguments
+    /// * `writer` – The writer of the session file.
+    /// * `entry` – The recorded request and response.
+    fn append(writer: &mut BufWriter<File>, entry: &SessionEntry) -> std::io::Result<()> {
+        let frame = bincode::serde::encode_to_vec(entry, bincode::config::standard())
+            .map_err(std::io::Error::other)?;
+        let len = u32::try_from(frame.len()).map_err(std::io::Error::other)?;
+        writer.write_all(&len.to_le_bytes())?;
+        writer.write_all(&frame)?;
+        // Flushed per entry, so that the session survives a crash of the live run
+        writer.flush()
+    }
+
+    /// Answers a request with the next recorded response to an identical request.
+    ///
+    /// # Arguments
+    /// * `method` – The request method.
+    /// * `endpoint` – The request endpoint.
+    /// * `query` – The query parameters.
+    /// * `body` – The JSON-encoded request body, if any.
+    ///
+    /// # Returns
+    /// The recorded response, or `None` if this session is recording.
+    /// Requests without a remaining recorded response are answered with a client error and
+    /// mark the replay as diverged.
+    pub(crate) fn respond(
+        &self,
+        method: &HTTPRequestMethod,
+        endpoint: &str,
+        query: &HashMap<&str, String>,
+        body: Option<&Value>,
+    ) -> Option<reqwest::Response> {
+        let SessionMode::Replaying(entries) = &self.mode else { return None };
+        let key = Self::key(method, endpoint, query, body);
+        let next = entries.lock().unwrap().get_mut(&key).and_then(VecDeque::pop_front);
+        Some(next.map_or_else(
+            || {
+                if !self.diverged.is_cancelled() {
+                    error!("Replay diverged from the recorded session: {key} was not recorded.");
+                    self.diverged.cancel();
+                }
+                let detail = json!({ "detail": format!("{key} was not recorded") });
+                Self::response(400, Some("application/json"), detail.to_string().into_bytes())
+            },
+            |entry| Self::response(entry.status, entry.content_type.as_deref(), entry.body),
+        ))
+    }
+
+    /// Builds a response with the given status, content type and body.
+    ///
+    /// # Arguments
+    /// * `status` – The HTTP status code.
+    /// * `content_type` – The value of the `Content-Type` header, if any.
+    /// * `body` – The raw response body.
+    fn response(status: u16, content_type: Option<&str>, body: Vec<u8>) -> reqwest::Response {
+        let mut builder = http::Response::builder().status(status);
+        if let Some(value) = content_type {
+            builder = builder.header(http::header::CONTENT_TYPE, value);
+        }
+        reqwest::Response::from(builder.body(body).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http_handler::{
+        http_client::HTTPClient,
+        http_request::{observation_get::ObservationRequest, request_common::NoBodyHTTPRequestType},
+        simulated_drs::SimulatedDrs,
+    };
+
+    #[tokio::test]
+    async fn test_record_and_replay() {
+        let path = std::env::temp_dir().join(format!("melvin_drs_{}.bin", std::process::id()));
+        let (sim, none) = (SimulatedDrs::new(), HashMap::new());
+        let get = HTTPRequestMethod::Get;
+        let recording = DrsSession::record(&path).unwrap();
+        let mut recorded = Vec::new();
+        for endpoint in ["/observation", "/observation", "/image"] {
+            let live = sim.respond(&get, endpoint, &none, None);
+            let resp = recording.capture(&get, endpoint, &none, None, live).await.unwrap();
+            recorded.push(resp.bytes().await.unwrap());
+        }
+        drop(recording);
+
+        let client = HTTPClient::replaying(&path).unwrap();
+        for expected in &recorded[..2] {
+            let obs = ObservationRequest {}.send_request(&client).await.unwrap();
+            let live: Value = serde_json::from_slice(expected).unwrap();
+            assert_eq!(u64::from(obs.pos_x()), live["width_x"].as_u64().unwrap());
+            assert_eq!(obs.state(), live["state"]);
+        }
+        let session = client.session().unwrap();
+        assert!(!session.diverged().is_cancelled());
+        assert!(ObservationRequest {}.send_request(&client).await.is_err());
+        assert!(session.diverged().is_cancelled());
+        let img = session.respond(&get, "/image", &none, None).unwrap();
+        assert_eq!(img.bytes().await.unwrap(), recorded[2]);
+        std::fs::remove_file(path).ok();
+    }
+}
@@ -1,14 +1,32 @@
+use super::{
+    drs_session::DrsSession, latency::CommandLatency, retry::CircuitBreaker,
+    simulated_drs::SimulatedDrs,
+};
+use std::path::Path;
+
 /// A simple wrapper around `reqwest::Client` used to manage HTTP requests
 /// with a preconfigured base URL and default settings.
 ///
 /// This client is used for making REST API calls to the DRS backend.
 /// It sets a fixed timeout and allows easy reuse of the HTTP client infrastructure.
+/// Transient failures are retried by the request traits, guarded by a [`CircuitBreaker`].
+/// In dry-run mode, requests are answered by a [`SimulatedDrs`] instead of the network.
+/// All exchanged requests may be recorded to a [`DrsSession`] and replayed from it later.
+/// The round trips of satellite control commands are measured in a [`CommandLatency`].
 #[derive(Debug)]
 pub(crate) struct HTTPClient {
     /// The underlying `reqwest::Client` used to perform HTTP requests.
     client: reqwest::Client,
     /// Base URL for the API, prepended to all endpoint paths. 
     base_url: String,
+    /// The simulated backend answering all requests in dry-run mode.
+    simulated: Option<SimulatedDrs>,
+    /// The circuit breaker shared by all requests of this client.
+    breaker: CircuitBreaker,
+    /// The session all requests are recorded to or replayed from.
+    session: Option<DrsSession>,
+    /// The measured round-trip latency of satellite control commands.
+    latency: CommandLatency,
 }
 
 impl HTTPClient {
@@ -29,11 +47,55 @@ impl HTTPClient {
                 .build()
                 .unwrap(),
             base_url: String::from(base_url),
+            simulated: None,
+            breaker: CircuitBreaker::default(),
+            session: None,
+            latency: CommandLatency::default(),
         }
     }
 
+    /// Constructs a new dry-run `HTTPClient` backed by a [`SimulatedDrs`].
+    ///
+    /// No request of this client reaches the network, streaming endpoints are unavailable.
+    pub(crate) fn simulated() -> HTTPClient {
+        HTTPClient { simulated: Some(SimulatedDrs::new()), ..Self::new("sim://drs") }
+    }
+
+    /// Constructs a new `HTTPClient` recording all requests and responses to a session file.
+    ///
+    /// # Arguments
+    /// * `base_url` – The root URL for all HTTP requests.
+    /// * `path` – The path of the session file.
+    ///
+    /// # Errors
+    /// An I/O error if the session file could not be created.
+    pub(crate) fn recording(base_url: &str, path: &Path) -> std::io::Result<HTTPClient> {
+        Ok(HTTPClient { session: Some(DrsSession::record(path)?), ..Self::new(base_url) })
+    }
+
+    /// Constructs a new `HTTPClient` answering all requests from a recorded session file.
+    ///
+    /// No request of this client reaches the network, streaming endpoints are unavailable.
+    ///
+    /// # Arguments
+    /// * `path` – The path of the session file.
+    ///
+    /// # Errors
+    /// An I/O error if the session file could not be read.
+    pub(crate) fn replaying(path: &Path) -> std::io::Result<HTTPClient> {
+        Ok(HTTPClient { session: Some(DrsSession::replay(path)?), ..Self::new("replay://drs") })
+    }
+
     /// Returns a reference to the internal `reqwest::Client`.
     pub(super) fn client(&self) -> &reqwest::Client { &self.client }
     /// Returns the base URL that the client was initialized with.
     pub(crate) fn url(&self) -> &str { self.base_url.as_str() }
+    /// Returns the simulated backend if this is a dry-run client.
+    pub(crate) fn simulated_backend(&self) -> Option<&SimulatedDrs> { self.simulated.as_ref() }
+    /// Returns the session all requests are recorded to or replayed from, if any.
+    pub(crate) fn session(&self) -> Option<&DrsSession> { self.session.as_ref() }
+    /// Returns the circuit breaker guarding all requests of this client.
+    pub(crate) fn breaker(&self) -> &CircuitBreaker { &self.breaker }
+    /// Returns the measured round-trip latency of satellite control commands.
+    pub(crate) fn latency(&self) -> &CommandLatency { &self.latency }
 }
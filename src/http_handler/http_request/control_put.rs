@@ -1,4 +1,5 @@
 use super::control_satellite::ControlSatelliteResponse;
+use crate::http_handler::RetryClass;
 use super::request_common::{HTTPRequestMethod, HTTPRequestType, JSONBodyHTTPRequestType};
 
 /// Request type for the /control endpoint.
@@ -28,4 +29,6 @@ impl HTTPRequestType for ControlSatelliteRequest {
     fn endpoint(&self) -> &'static str { "/control" }
     /// The corresponding HTTP Request Method.
     fn request_method(&self) -> HTTPRequestMethod { HTTPRequestMethod::Put }
+    /// The retry budget class of the request.
+    fn retry_class(&self) -> RetryClass { RetryClass::Command }
 }
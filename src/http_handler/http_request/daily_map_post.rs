@@ -1,18 +1,39 @@
 use super::daily_map::DailyMapResponse;
+use crate::http_handler::RetryClass;
 use super::request_common::{
-    HTTPRequestMethod, HTTPRequestType, MultipartBodyHTTPRequestType,
+    HTTPRequestMethod, HTTPRequestType, MultipartBodyHTTPRequestType, RequestError,
 };
-use std::{io, path::Path};
+use std::{collections::HashMap, io, ops::Range, path::Path};
 use std::path::PathBuf;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
 
 /// Request type for the /dailyMap endpoint.
 #[derive(Debug)]
 pub(crate) struct DailyMapRequest {
     /// File path to the current daily map png image file.
     image_path: PathBuf,
+    /// The byte range of the file sent by this request and the total file size, if the
+    /// upload is chunked.
+    chunk: Option<(Range<u64>, u64)>,
 }
 
 impl MultipartBodyHTTPRequestType for DailyMapRequest {
+    /// Assembles the multipart form body from the image file, or from the requested byte
+    /// range of it for a chunked upload.
+    async fn body(&self) -> Result<reqwest::multipart::Form, RequestError> {
+        let Some((range, _)) = &self.chunk else {
+            let file_part = reqwest::multipart::Part::file(self.image_path()).await?;
+            return Ok(reqwest::multipart::Form::new().part("image", file_part));
+        };
+        let mut file = tokio::fs::File::open(self.image_path()).await?;
+        file.seek(io::SeekFrom::Start(range.start)).await?;
+        let mut data = Vec::new();
+        file.take(range.end - range.start).read_to_end(&mut data).await?;
+        let file_name = self.image_path().file_name().map(|n| n.to_string_lossy().into_owned());
+        let part = reqwest::multipart::Part::bytes(data).file_name(file_name.unwrap_or_default());
+        Ok(reqwest::multipart::Form::new().part("image", part))
+    }
+
     /// returns the path for the multipart image file.
     fn image_path(&self) -> &PathBuf { &self.image_path }
 }
@@ -24,6 +45,28 @@ impl HTTPRequestType for DailyMapRequest {
     fn endpoint(&self) -> &'static str { "/dailyMap" }
     /// The corresponding HTTP Request Method.
     fn request_method(&self) -> HTTPRequestMethod { HTTPRequestMethod::Post }
+    /// The `Content-Range` header of a chunked upload.
+    fn header_params(&self) -> reqwest::header::HeaderMap {
+        let mut headers = reqwest::header::HeaderMap::default();
+        if let Some((range, total)) = &self.chunk {
+            let value = format!("bytes {}-{}/{total}", range.start, range.end - 1);
+            if let Ok(header) = reqwest::header::HeaderValue::from_str(&value) {
+                headers.insert(reqwest::header::CONTENT_RANGE, header);
+            }
+        }
+        headers
+    }
+    /// The byte offset and total size of a chunked upload.
+    fn query_params(&self) -> HashMap<&str, String> {
+        let mut query = HashMap::new();
+        if let Some((range, total)) = &self.chunk {
+            query.insert("offset", range.start.to_string());
+            query.insert("total", total.to_string());
+        }
+        query
+    }
+    /// The retry budget class of the request.
+    fn retry_class(&self) -> RetryClass { RetryClass::Upload }
 }
 
 impl DailyMapRequest {
@@ -42,6 +85,23 @@ impl DailyMapRequest {
                 "Path is not a valid file",
             ));
         }
-        Ok(Self { image_path: path.to_path_buf() })
+        Ok(Self { image_path: path.to_path_buf(), chunk: None })
+    }
+
+    /// Constructs a new `DailyMapRequest` uploading a byte range of the full snapshot.
+    ///
+    /// # Arguments
+    /// * `image_path` – The path to the full snapshot.
+    /// * `range` – The byte range of the snapshot sent by this request.
+    /// * `total` – The total size of the snapshot in bytes.
+    pub(crate) fn chunk<P: AsRef<Path>>(
+        image_path: P,
+        range: Range<u64>,
+        total: u64,
+    ) -> Result<Self, io::Error> {
+        if range.is_empty() || range.end > total {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Invalid chunk range"));
+        }
+        Ok(Self { chunk: Some((range, total)), ..Self::new(image_path)? })
     }
 }
@@ -11,7 +11,7 @@ use super::http_response::{
 
 mod achievements_get;
 pub(crate) mod announcements_get;
-mod available_slots_get;
+pub(crate) mod available_slots_get;
 pub(crate) mod beacon_position_put;
 mod configure_simulation_put;
 pub(crate) mod control_put;
@@ -19,7 +19,7 @@ mod create_backup_get;
 pub(crate) mod daily_map_post;
 mod delete_objective_delete;
 mod modify_objective_put;
-mod modify_slot_put;
+pub(crate) mod modify_slot_put;
 pub(crate) mod objective_image_post;
 pub(crate) mod objective_list_get;
 pub(crate) mod observation_get;
@@ -1,4 +1,6 @@
 use super::objective_image::ObjectiveImageResponse;
+use crate::util::ImgObjectiveId;
+use crate::http_handler::RetryClass;
 use super::request_common::{
     HTTPRequestMethod, HTTPRequestType, MultipartBodyHTTPRequestType,
 };
@@ -10,7 +12,7 @@ pub(crate) struct ObjectiveImageRequest {
     /// The path where the image png file is stored.
     image_path: PathBuf,
     /// The objective id.
-    objective_id: usize,
+    objective_id: ImgObjectiveId,
 }
 
 impl MultipartBodyHTTPRequestType for ObjectiveImageRequest {
@@ -25,6 +27,8 @@ impl HTTPRequestType for ObjectiveImageRequest {
     fn endpoint(&self) -> &'static str { "/image" }
     /// The corresponding HTTP Request Method.
     fn request_method(&self) -> HTTPRequestMethod { HTTPRequestMethod::Post }
+    /// The retry budget class of the request.
+    fn retry_class(&self) -> RetryClass { RetryClass::Upload }
     /// A `HashMap` containing the query param key value pairs
     fn query_params(&self) -> HashMap<&str, String> {
         let mut query = HashMap::new();
@@ -35,7 +39,7 @@ impl HTTPRequestType for ObjectiveImageRequest {
 
 impl ObjectiveImageRequest {
     /// Creates a new `ObjectiveImageRequest` from an id and a png file path.
-    pub fn new(objective_id: usize, image_path: PathBuf) -> Self {
+    pub fn new(objective_id: ImgObjectiveId, image_path: PathBuf) -> Self {
         Self { image_path, objective_id }
     }
 }
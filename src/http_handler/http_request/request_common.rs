@@ -1,6 +1,11 @@
 use super::response_common::{HTTPResponseType, ResponseError};
-use crate::http_handler::{HTTPError, http_client::HTTPClient};
-use std::{fmt::Debug, io::ErrorKind};
+use crate::http_handler::{
+    HTTPError,
+    http_client::HTTPClient,
+    retry::{RetryClass, send_with_retry},
+    simulated_drs::SimulatedDrs,
+};
+use std::{fmt::Debug, io::ErrorKind, time::Instant};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use strum_macros::Display;
@@ -29,6 +34,16 @@ pub(crate) trait HTTPRequestType {
         HashMap::new()
     }
 
+    /// Returns the retry budget class of the request.
+    ///
+    /// Defaults to [`RetryClass::Poll`] for `GET` requests and [`RetryClass::Never`] otherwise.
+    fn retry_class(&self) -> RetryClass {
+        match self.request_method() {
+            HTTPRequestMethod::Get => RetryClass::Poll,
+            _ => RetryClass::Never,
+        }
+    }
+
     /// Creates the base `RequestBuilder` from the HTTP client, applying method and URL.
     ///
     /// # Arguments
@@ -45,6 +60,58 @@ pub(crate) trait HTTPRequestType {
             HTTPRequestMethod::Delete => client.client().delete(compound_url),
         }
     }
+
+    /// Answers the request from the simulated backend of a dry-run client.
+    ///
+    /// # Arguments
+    /// * `sim` – The simulated DRS backend.
+    /// * `body` – The JSON-encoded request body, if any.
+    fn simulate(&self, sim: &SimulatedDrs, body: Option<&serde_json::Value>) -> reqwest::Response {
+        sim.respond(&self.request_method(), self.endpoint(), &self.query_params(), body)
+    }
+
+    /// Answers the request from the session replayed by the client.
+    ///
+    /// # Arguments
+    /// * `client` – The HTTP client.
+    /// * `body` – The JSON-encoded request body, if any.
+    ///
+    /// # Returns
+    /// * The recorded response, or `None` if the client does not replay a session.
+    fn replay(
+        &self,
+        client: &HTTPClient,
+        body: Option<&serde_json::Value>,
+    ) -> Option<reqwest::Response> {
+        let session = client.session()?;
+        session.respond(&self.request_method(), self.endpoint(), &self.query_params(), body)
+    }
+
+    /// Sends the request built by `request`, recording the response if the client records a
+    /// session. The round trip of satellite control commands is measured by the client.
+    ///
+    /// # Arguments
+    /// * `client` – The HTTP client.
+    /// * `body` – The JSON-encoded request body, if any.
+    /// * `request` – The fully built request.
+    ///
+    /// # Returns
+    /// * The received response or a `reqwest::Error`.
+    async fn send_recorded(
+        &self,
+        client: &HTTPClient,
+        body: Option<&serde_json::Value>,
+        request: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        let sent = Instant::now();
+        let response = request.send().await?;
+        if self.retry_class() == RetryClass::Command {
+            client.latency().record(sent.elapsed());
+        }
+        let Some(session) = client.session() else { return Ok(response) };
+        let (method, query) = (self.request_method(), self.query_params());
+        session.capture(&method, self.endpoint(), &query, body, response).await
+    }
 }
 
 
@@ -106,7 +173,7 @@ pub(crate) trait JSONBodyHTTPRequestType: HTTPRequestType {
         headers
     }
 
-    /// Sends the request with a JSON-encoded body.
+    /// Sends the request with a JSON-encoded body, retrying transient failures.
     ///
     /// # Arguments
     /// * `client` – The shared HTTP client instance.
@@ -117,24 +184,33 @@ pub(crate) trait JSONBodyHTTPRequestType: HTTPRequestType {
         &self,
         client: &HTTPClient,
     ) -> Result<<Self::Response as HTTPResponseType>::ParsedResponseType, HTTPError> {
-        let response = self
-            .get_request_base(client)
-            .headers(self.header_params_with_content_type())
-            .query(&self.query_params())
-            .json(&self.body())
-            .send()
-            .await;
-        let resp = response.map_err(ResponseError::from);
-        Self::Response::read_response(resp.map_err(HTTPError::HTTPResponseError)?)
-            .await
-            .map_err(HTTPError::HTTPResponseError)
+        send_with_retry(client.breaker(), self.retry_class(), || async move {
+            let body = serde_json::to_value(self.body()).ok();
+            let response = if let Some(sim) = client.simulated_backend() {
+                Ok(self.simulate(sim, body.as_ref()))
+            } else if let Some(replayed) = self.replay(client, body.as_ref()) {
+                Ok(replayed)
+            } else {
+                let request = self
+                    .get_request_base(client)
+                    .headers(self.header_params_with_content_type())
+                    .query(&self.query_params())
+                    .json(&self.body());
+                self.send_recorded(client, body.as_ref(), request).await
+            };
+            let resp = response.map_err(ResponseError::from);
+            Self::Response::read_response(resp.map_err(HTTPError::HTTPResponseError)?)
+                .await
+                .map_err(HTTPError::HTTPResponseError)
+        })
+        .await
     }
 }
 
 
 /// Trait for requests that do not include a request body.
 pub(crate) trait NoBodyHTTPRequestType: HTTPRequestType {
-    /// Sends a request with no body.
+    /// Sends a request with no body, retrying transient failures.
     ///
     /// # Arguments
     /// * `client` – The HTTP client instance.
@@ -145,16 +221,24 @@ pub(crate) trait NoBodyHTTPRequestType: HTTPRequestType {
         &self,
         client: &HTTPClient,
     ) -> Result<<Self::Response as HTTPResponseType>::ParsedResponseType, HTTPError> {
-        let response = self
-            .get_request_base(client)
-            .headers(self.header_params())
-            .query(&self.query_params())
-            .send()
-            .await;
-        let resp = response.map_err(ResponseError::from);
-        Self::Response::read_response(resp.map_err(HTTPError::HTTPResponseError)?)
-            .await
-            .map_err(HTTPError::HTTPResponseError)
+        send_with_retry(client.breaker(), self.retry_class(), || async move {
+            let response = if let Some(sim) = client.simulated_backend() {
+                Ok(self.simulate(sim, None))
+            } else if let Some(replayed) = self.replay(client, None) {
+                Ok(replayed)
+            } else {
+                let request = self
+                    .get_request_base(client)
+                    .headers(self.header_params())
+                    .query(&self.query_params());
+                self.send_recorded(client, None, request).await
+            };
+            let resp = response.map_err(ResponseError::from);
+            Self::Response::read_response(resp.map_err(HTTPError::HTTPResponseError)?)
+                .await
+                .map_err(HTTPError::HTTPResponseError)
+        })
+        .await
     }
 }
 
@@ -174,7 +258,9 @@ pub(crate) trait MultipartBodyHTTPRequestType: HTTPRequestType {
     /// Returns the absolute or relative path to the image file.
     fn image_path(&self) -> &PathBuf;
 
-    /// Sends the multipart form request.
+    /// Sends the multipart form request, retrying transient failures.
+    ///
+    /// The form is rebuilt from the image file for every attempt.
     ///
     /// # Arguments
     /// * `client` – The HTTP client instance.
@@ -185,17 +271,28 @@ pub(crate) trait MultipartBodyHTTPRequestType: HTTPRequestType {
         &self,
         client: &HTTPClient,
     ) -> Result<<Self::Response as HTTPResponseType>::ParsedResponseType, HTTPError> {
-        let response = self
-            .get_request_base(client)
-            .headers(self.header_params())
-            .query(&self.query_params())
-            .multipart(self.body().await.map_err(HTTPError::HTTPRequestError)?)
-            .send()
-            .await;
-        let resp = response.map_err(ResponseError::from);
-        Self::Response::read_response(resp.map_err(HTTPError::HTTPResponseError)?)
-            .await
-            .map_err(HTTPError::HTTPResponseError)
+        send_with_retry(client.breaker(), self.retry_class(), || async move {
+            let response = if let Some(sim) = client.simulated_backend() {
+                let file =
+                    tokio::fs::metadata(self.image_path()).await.map_err(RequestError::from);
+                file.map_err(HTTPError::HTTPRequestError)?;
+                Ok(self.simulate(sim, None))
+            } else if let Some(replayed) = self.replay(client, None) {
+                Ok(replayed)
+            } else {
+                let request = self
+                    .get_request_base(client)
+                    .headers(self.header_params())
+                    .query(&self.query_params())
+                    .multipart(self.body().await.map_err(HTTPError::HTTPRequestError)?);
+                self.send_recorded(client, None, request).await
+            };
+            let resp = response.map_err(ResponseError::from);
+            Self::Response::read_response(resp.map_err(HTTPError::HTTPResponseError)?)
+                .await
+                .map_err(HTTPError::HTTPResponseError)
+        })
+        .await
     }
 }
 
@@ -1,3 +1,4 @@
+use crate::http_handler::RetryClass;
 use super::request_common::{HTTPRequestMethod, HTTPRequestType, NoBodyHTTPRequestType};
 use super::reset;
 
@@ -15,4 +16,6 @@ impl HTTPRequestType for ResetRequest {
     fn endpoint(&self) -> &'static str { "/reset" }
     /// The corresponding HTTP Request Method.
     fn request_method(&self) -> HTTPRequestMethod { HTTPRequestMethod::Get }
+    /// The retry budget class of the request.
+    fn retry_class(&self) -> RetryClass { RetryClass::Never }
 }
@@ -11,4 +11,12 @@ pub(crate) struct AvailableSlotsResponse {
     slots: Vec<CommunicationSlot>,
 }
 
+impl AvailableSlotsResponse {
+    /// Returns the number of already used communication slots.
+    pub(crate) fn slots_used(&self) -> usize { self.communication_slots_used }
+
+    /// Consumes the response and returns the remaining communication slots.
+    pub(crate) fn into_slots(self) -> Vec<CommunicationSlot> { self.slots }
+}
+
 impl SerdeJSONBodyHTTPResponseType for AvailableSlotsResponse {}
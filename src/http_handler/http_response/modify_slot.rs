@@ -14,4 +14,12 @@ pub(crate) struct ModifySlotResponse {
     enabled: bool,
 }
 
+impl ModifySlotResponse {
+    /// Returns the id of the modified slot.
+    pub(crate) fn id(&self) -> usize { self.id }
+
+    /// Returns whether the slot is booked after the modification.
+    pub(crate) fn enabled(&self) -> bool { self.enabled }
+}
+
 impl SerdeJSONBodyHTTPResponseType for ModifySlotResponse {}
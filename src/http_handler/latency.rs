@@ -0,0 +1,110 @@
+use crate::util::MissionConfig;
+use chrono::TimeDelta;
+use std::{sync::Mutex, time::Duration};
+
+/// The smoothed round-trip statistics of a [`CommandLatency`].
+#[derive(Debug, Clone, Copy, Default, serde::Serialize)]
+pub(crate) struct LatencyStats {
+    /// The number of measured round trips.
+    samples: usize,
+    /// The smoothed round-trip time in milliseconds.
+    round_trip_ms: f64,
+    /// The smoothed mean deviation of the round-trip time in milliseconds.
+    jitter_ms: f64,
+}
+
+impl LatencyStats {
+    /// Returns the number of measured round trips.
+    pub(crate) fn samples(&self) -> usize { self.samples }
+    /// Returns the smoothed round-trip time in milliseconds.
+    pub(crate) fn round_trip_ms(&self) -> f64 { self.round_trip_ms }
+    /// Returns the smoothed mean deviation of the round-trip time in milliseconds.
+    pub(crate) fn jitter_ms(&self) -> f64 { self.jitter_ms }
+}
+
+/// Round-trip latency estimate of the satellite control commands of an
+/// [`HTTPClient`](super::http_client::HTTPClient).
+///
+/// The round-trip time and its jitter are smoothed like the retransmission timer of TCP
+/// (RFC 6298). A command takes effect once the backend received it, so tasks issuing commands
+/// are fired earlier by the estimated one-way latency, i.e. half the round trip.
+#[derive(Debug, Default)]
+pub(crate) struct CommandLatency {
+    /// The smoothed statistics of all measured round trips.
+    stats: Mutex<LatencyStats>,
+}
+
+impl CommandLatency {
+    /// The gain of a new sample on the smoothed round-trip time.
+    const RTT_GAIN: f64 = 0.125;
+    /// The gain of a new sample on the smoothed jitter.
+    const JITTER_GAIN: f64 = 0.25;
+    /// The number of samples below which no lead is applied.
+    const MIN_SAMPLES: usize = 3;
+
+    /// Records the measured round trip of a command.
+    ///
+    /// # Arguments
+    /// * `rtt` – The time from sending the command to receiving the response headers.
+    pub(crate) fn record(&self, rtt: Duration) {
+        let rtt_ms = rtt.as_secs_f64() * 1000.0;
+        let mut stats = self.stats.lock().unwrap();
+        if stats.samples == 0 {
+            stats.round_trip_ms = rtt_ms;
+            stats.jitter_ms = rtt_ms / 2.0;
+        } else {
+            let dev = (stats.round_trip_ms - rtt_ms).abs();
+            stats.jitter_ms += Self::JITTER_GAIN * (dev - stats.jitter_ms);
+            stats.round_trip_ms += Self::RTT_GAIN * (rtt_ms - stats.round_trip_ms);
+        }
+        stats.samples += 1;
+    }
+
+    /// Returns a copy of the current statistics.
+    pub(crate) fn stats(&self) -> LatencyStats { *self.stats.lock().unwrap() }
+
+    /// Returns the time commands are fired ahead of their due time.
+    ///
+    /// This is the estimated one-way latency, bounded by `runtime.latency_lead_max_ms`, or
+    /// zero while fewer than [`CommandLatency::MIN_SAMPLES`] round trips were measured.
+    #[allow(clippy::cast_possible_truncation)]
+    pub(crate) fn lead(&self) -> TimeDelta {
+        let stats = self.stats();
+        let max_ms = i64::from(MissionConfig::get().runtime.latency_lead_max_ms);
+        if stats.samples < Self::MIN_SAMPLES {
+            return TimeDelta::zero();
+        }
+        let one_way_ms = (stats.round_trip_ms / 2.0).round() as i64;
+        TimeDelta::milliseconds(one_way_ms.clamp(0, max_ms))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_latency_lead() {
+        let latency = CommandLatency::default();
+        latency.record(Duration::from_millis(400));
+        latency.record(Duration::from_millis(400));
+        assert_eq!(latency.lead(), TimeDelta::zero());
+        latency.record(Duration::from_millis(400));
+        assert_eq!(latency.lead(), TimeDelta::milliseconds(200));
+        assert_eq!(latency.stats().samples(), 3);
+
+        // A spike moves the estimate by an eighth and raises the jitter
+        let jitter = latency.stats().jitter_ms();
+        latency.record(Duration::from_millis(1200));
+        assert!((latency.stats().round_trip_ms() - 500.0).abs() < 1e-9);
+        assert!(latency.stats().jitter_ms() > jitter);
+        assert_eq!(latency.lead(), TimeDelta::milliseconds(250));
+
+        // The lead is bounded by the configured maximum
+        for _ in 0..100 {
+            latency.record(Duration::from_secs(60));
+        }
+        let max_ms = i64::from(MissionConfig::get().runtime.latency_lead_max_ms);
+        assert_eq!(latency.lead(), TimeDelta::milliseconds(max_ms));
+    }
+}
@@ -1,12 +1,21 @@
 //! This module provides core structs, enums, and utilities for interacting with the DRS backend system.
 //! It includes functionalities such as retrieving the objective list or the most recent observation.
 
+mod bandwidth;
 mod common;
+mod drs_session;
 pub mod http_client;
 pub mod http_request;
 pub mod http_response;
+mod latency;
+pub(crate) mod observation_stream;
+mod retry;
+mod simulated_drs;
 
+pub(crate) use bandwidth::{BandwidthShaper, TrafficClass};
+pub(crate) use retry::RetryClass;
 pub use common::BeaconObjective;
+pub(crate) use common::CommunicationSlot;
 pub use common::HTTPError;
 pub(crate) use common::ImageObjective;
 pub(crate) use common::ZoneType;
\ No newline at end of file
@@ -0,0 +1,150 @@
+use super::http_client::HTTPClient;
+use super::http_response::observation::ObservationResponse;
+use crate::{log, warn};
+use futures::StreamExt;
+use reqwest_eventsource::{Event, EventSource};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Push-based observation client for the DRS backend.
+///
+/// If the backend offers a streaming observation channel (Server-Sent Events),
+/// [`ObservationStream`] yields each pushed observation as soon as it arrives.
+/// If the stream is unavailable or breaks, it disconnects and reports no observation,
+/// so callers can transparently fall back to polling `/observation` until the
+/// next reconnection attempt. Dry-run clients never connect and always poll.
+pub(crate) struct ObservationStream {
+    /// The base URL of the backend, `None` if the backend offers no stream.
+    url: Option<String>,
+    /// The currently open event source, if the stream is connected.
+    es: Option<EventSource>,
+    /// Earliest point in time for the next connection attempt.
+    next_connect: Instant,
+    /// Whether the stream has delivered at least one observation since the last connect.
+    confirmed: bool,
+}
+
+impl ObservationStream {
+    /// The streaming endpoint of the backend.
+    const STREAM_ENDPOINT: &'static str = "/observation/stream";
+    /// Backoff before reconnecting after the stream failed or turned out to be unavailable.
+    const RECONNECT_BACKOFF: Duration = Duration::from_secs(60);
+
+    /// Creates a new [`ObservationStream`] for the given backend client.
+    /// The connection is established lazily on the first call to `next_observation`.
+    ///
+    /// # Arguments
+    /// * `client` – The HTTP client holding the backend base URL.
+    pub(crate) fn new(client: &HTTPClient) -> Self {
+        Self {
+            url: client.simulated_backend().is_none().then(|| client.url().to_string()),
+            es: None,
+            next_connect: Instant::now(),
+            confirmed: false,
+        }
+    }
+
+    /// Returns `true` if the stream is connected and has already delivered observations.
+    pub(crate) fn is_live(&self) -> bool { self.es.is_some() && self.confirmed }
+
+    /// Waits for the next pushed observation.
+    ///
+    /// # Arguments
+    /// * `timeout` – Maximum time to wait for a pushed observation.
+    ///
+    /// # Returns
+    /// * `Some(ObservationResponse)` if an observation was pushed in time.
+    /// * `None` if the stream is unavailable, failed, or timed out; the caller should poll instead.
+    pub(crate) async fn next_observation(
+        &mut self,
+        timeout: Duration,
+    ) -> Option<ObservationResponse> {
+        if self.es.is_none() {
+            let url = self.url.as_ref()?;
+            if Instant::now() < self.next_connect {
+                return None;
+            }
+            self.es = Some(EventSource::get(url.clone() + Self::STREAM_ENDPOINT));
+            self.confirmed = false;
+        }
+        let deadline = Instant::now() + timeout;
+        loop {
+            let es = self.es.as_mut()?;
+            match tokio::time::timeout_at(deadline, es.next()).await {
+                Ok(Some(Ok(Event::Open))) => log!("Observation stream opened!"),
+                Ok(Some(Ok(Event::Message(msg)))) => {
+                    match serde_json::from_str::<ObservationResponse>(&msg.data) {
+                        Ok(obs) => {
+                            if !self.confirmed {
+                                log!("Receiving pushed observations, polling suspended.");
+                                self.confirmed = true;
+                            }
+                            return Some(obs);
+                        }
+                        Err(e) => {
+                            self.disconnect(&format!("Malformed pushed observation: {e}"));
+                            return None;
+                        }
+                    }
+                }
+                Ok(Some(Err(e))) => {
+                    self.disconnect(&format!("Observation stream error: {e}"));
+                    return None;
+                }
+                Ok(None) => {
+                    self.disconnect("Observation stream ended");
+                    return None;
+                }
+                Err(_) => return None,
+            }
+        }
+    }
+
+    /// Closes the current stream and schedules the next reconnection attempt.
+    ///
+    /// # Arguments
+    /// * `reason` – Human-readable reason for the disconnect.
+    fn disconnect(&mut self, reason: &str) {
+        if let Some(mut es) = self.es.take() {
+            es.close();
+        }
+        if self.confirmed {
+            warn!("{reason}. Falling back to observation polling.");
+        } else {
+            log!("{reason}. Observation streaming unavailable, polling instead.");
+        }
+        self.confirmed = false;
+        self.next_connect = Instant::now() + Self::RECONNECT_BACKOFF;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http_handler::http_request::{
+        observation_get::ObservationRequest, request_common::NoBodyHTTPRequestType,
+    };
+
+    #[tokio::test]
+    async fn test_stream_unavailable_falls_back_to_polling() {
+        // The simulated DRS offers no stream, so every observation is polled
+        let client = HTTPClient::simulated();
+        let mut stream = ObservationStream::new(&client);
+        assert!(stream.next_observation(Duration::from_millis(100)).await.is_none());
+        assert!(!stream.is_live());
+        let polled = ObservationRequest {}.send_request(&client).await.unwrap();
+        assert_eq!(polled.state(), "charge");
+        assert!((polled.battery() - 100.0).abs() < f64::EPSILON);
+
+        // A backend refusing the stream is only reconnected after the backoff
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+        let mut refused = ObservationStream::new(&HTTPClient::new(&url));
+        assert!(refused.next_observation(Duration::from_secs(5)).await.is_none());
+        assert!(refused.es.is_none() && !refused.is_live());
+        assert!(refused.next_connect > Instant::now());
+        assert!(refused.next_observation(Duration::from_secs(5)).await.is_none());
+        assert!(refused.es.is_none());
+    }
+}
@@ -0,0 +1,203 @@
+use super::{HTTPError, http_response::response_common::ResponseError};
+use crate::util::{EVENT_BUS, MISSION_METRICS, MissionConfig, SafetyEvent};
+use crate::{error, info};
+use chrono::{DateTime, Utc};
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Retry budget classes of the DRS endpoints.
+///
+/// Each request type declares its class, the number of retries per class is taken from the
+/// [`MissionConfig`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RetryClass {
+    /// Idempotent polling requests, e.g. observations, objectives and images.
+    Poll,
+    /// Satellite control commands, which only set target values and are thus idempotent.
+    Command,
+    /// Image uploads.
+    Upload,
+    /// Requests that must never be repeated, e.g. beacon guesses or simulation resets.
+    Never,
+}
+
+impl RetryClass {
+    /// Returns the number of retries following a failed first attempt.
+    fn retries(self) -> u32 {
+        let config = MissionConfig::get();
+        let runtime = &config.runtime;
+        match self {
+            RetryClass::Poll => runtime.http_poll_retries,
+            RetryClass::Command => runtime.http_command_retries,
+            RetryClass::Upload => runtime.http_upload_retries,
+            RetryClass::Never => 0,
+        }
+    }
+}
+
+/// The upper bound of a single backoff delay.
+const MAX_BACKOFF: Duration = Duration::from_secs(10);
+
+/// Computes the exponential backoff delay before a retry.
+///
+/// The delay doubles with every retry, is capped at [`MAX_BACKOFF`] and scaled by a jitter
+/// factor between `0.5` and `1.0` so that concurrent requests do not retry in lockstep.
+///
+/// # Arguments
+/// * `base_ms` – The delay before the first retry in milliseconds.
+/// * `retry` – The zero-based number of the upcoming retry.
+/// * `jitter` – A random value in `[0, 1)`.
+pub(crate) fn backoff_delay(base_ms: u32, retry: u32, jitter: f64) -> Duration {
+    let exp = u64::from(base_ms).saturating_mul(1 << retry.min(16));
+    let capped = Duration::from_millis(exp).min(MAX_BACKOFF);
+    capped.mul_f64(0.5 + jitter.clamp(0.0, 1.0) / 2.0)
+}
+
+/// Returns `true` if the error indicates a transient backend or link failure worth retrying.
+fn is_transient(err: &HTTPError) -> bool {
+    matches!(
+        err,
+        HTTPError::HTTPResponseError(ResponseError::InternalServer | ResponseError::NoConnection)
+    )
+}
+
+/// The mutable state of a [`CircuitBreaker`].
+#[derive(Debug, Default)]
+struct BreakerState {
+    /// The number of consecutive transient failures.
+    failures: u32,
+    /// The time the breaker opened, `None` while it is closed.
+    open_since: Option<DateTime<Utc>>,
+    /// The earliest time the open breaker lets the next probe request pass.
+    next_probe: Option<Instant>,
+}
+
+/// Circuit breaker shared by all requests of an [`HTTPClient`](super::http_client::HTTPClient).
+///
+/// After `http_breaker_threshold` consecutive transient failures the breaker opens, publishes
+/// [`SafetyEvent::BackendOutage`] and rejects requests without touching the network. Every
+/// `http_breaker_cooldown_s` a single probe request is let through; the first success closes
+/// the breaker again and publishes [`SafetyEvent::BackendRestored`].
+#[derive(Debug, Default)]
+pub(crate) struct CircuitBreaker {
+    /// The lock-protected breaker state.
+    state: Mutex<BreakerState>,
+}
+
+impl CircuitBreaker {
+    /// Returns `true` if the breaker is open.
+    pub(crate) fn is_open(&self) -> bool { self.state.lock().unwrap().open_since.is_some() }
+
+    /// Returns whether a request may be sent now, consuming the probe slot of an open breaker.
+    fn permits(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        match state.next_probe {
+            Some(t) if Instant::now() < t => false,
+            Some(_) => {
+                state.next_probe = Some(Instant::now() + Self::cooldown());
+                true
+            }
+            None => true,
+        }
+    }
+
+    /// Records the outcome of a request.
+    ///
+    /// # Arguments
+    /// * `reachable` – Whether the backend answered, successfully or with a client error.
+    fn record(&self, reachable: bool) {
+        let mut state = self.state.lock().unwrap();
+        let now = Utc::now();
+        if reachable {
+            state.failures = 0;
+            state.next_probe = None;
+            if let Some(start) = state.open_since.take() {
+                info!("DRS backend reachable again after {}s.", (now - start).num_seconds());
+                EVENT_BUS.publish(SafetyEvent::BackendRestored(start, now));
+            }
+            return;
+        }
+        state.failures += 1;
+        if state.open_since.is_some() {
+            return;
+        }
+        if state.failures >= MissionConfig::get().runtime.http_breaker_threshold {
+            error!("DRS backend unreachable after {} failed requests!", state.failures);
+            state.open_since = Some(now);
+            state.next_probe = Some(Instant::now() + Self::cooldown());
+            EVENT_BUS.publish(SafetyEvent::BackendOutage(now));
+        }
+    }
+
+    /// Returns the configured time between two probes of an open breaker.
+    fn cooldown() -> Duration {
+        Duration::from_secs(u64::from(MissionConfig::get().runtime.http_breaker_cooldown_s))
+    }
+}
+
+/// Sends a request with retries and exponential backoff, guarded by a [`CircuitBreaker`].
+///
+/// Only transient failures (5xx, timeouts and connection errors) are retried, and only as long
+/// as the budget of the `class` allows and the breaker stays closed. Requests rejected by an
+/// open breaker fail with [`ResponseError::NoConnection`].
+///
+/// # Arguments
+/// * `breaker` – The circuit breaker of the client.
+/// * `class` – The retry budget class of the request.
+/// * `attempt` – Performs a single attempt of the request.
+pub(crate) async fn send_with_retry<T, F, Fut>(
+    breaker: &CircuitBreaker,
+    class: RetryClass,
+    mut attempt: F,
+) -> Result<T, HTTPError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, HTTPError>>,
+{
+    let retries = class.retries();
+    let mut retry = 0;
+    loop {
+        if !breaker.permits() {
+            MISSION_METRICS.record_http_rejected();
+            return Err(HTTPError::HTTPResponseError(ResponseError::NoConnection));
+        }
+        let result = attempt().await;
+        let transient = result.as_ref().err().is_some_and(is_transient);
+        breaker.record(!transient);
+        if result.is_err() {
+            MISSION_METRICS.record_http_error(transient);
+        }
+        if !transient || retry >= retries || breaker.is_open() {
+            return result;
+        }
+        let base_ms = MissionConfig::get().runtime.http_retry_base_ms;
+        let jitter = rand::random::<f64>();
+        tokio::time::sleep(backoff_delay(base_ms, retry, jitter)).await;
+        retry += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_and_breaker() {
+        assert_eq!(backoff_delay(200, 0, 1.0), Duration::from_millis(200));
+        assert_eq!(backoff_delay(200, 2, 0.0), Duration::from_millis(400));
+        assert_eq!(backoff_delay(200, 30, 1.0), MAX_BACKOFF);
+
+        let breaker = CircuitBreaker::default();
+        let threshold = MissionConfig::get().runtime.http_breaker_threshold;
+        for _ in 1..threshold {
+            breaker.record(false);
+        }
+        assert!(!breaker.is_open() && breaker.permits());
+        breaker.record(false);
+        assert!(breaker.is_open() && !breaker.permits());
+        breaker.record(true);
+        assert!(!breaker.is_open() && breaker.permits());
+    }
+}
@@ -0,0 +1,357 @@
+use super::http_request::request_common::HTTPRequestMethod;
+use crate::flight_control::{FlightComputer, FlightState};
+use crate::imaging::CameraAngle;
+use crate::util::MapSize;
+use chrono::{DateTime, TimeDelta, Utc};
+use fixed::types::I32F32;
+use image::{ImageFormat, RgbImage};
+use serde_json::{Value, json};
+use std::{collections::HashMap, io::Cursor, sync::Mutex};
+
+/// Deterministic stand-in for the DRS backend used in dry-run mode.
+///
+/// The simulator answers the same endpoints as the DRS and integrates position, velocity,
+/// battery, fuel and flight state transitions second by second between two requests, using the
+/// same rates and delays the onboard planning assumes. Given the same request sequence at the
+/// same points in time it always produces the same responses.
+#[derive(Debug)]
+pub(crate) struct SimulatedDrs {
+    /// The lock-protected simulated satellite state.
+    state: Mutex<SimState>,
+}
+
+/// The satellite state tracked by the [`SimulatedDrs`].
+#[derive(Debug, Clone)]
+struct SimState {
+    /// The point in time the state refers to.
+    t: DateTime<Utc>,
+    /// The wrapped position on the map.
+    pos: (f64, f64),
+    /// The current velocity.
+    vel: (f64, f64),
+    /// The commanded velocity, approached with constant acceleration in acquisition.
+    target_vel: (f64, f64),
+    /// The battery level.
+    battery: f64,
+    /// The remaining fuel.
+    fuel: f64,
+    /// The current flight state.
+    state: FlightState,
+    /// The target state and end time of an ongoing transition.
+    transition: Option<(FlightState, DateTime<Utc>)>,
+    /// The current camera angle.
+    angle: CameraAngle,
+    /// The number of images taken.
+    images_taken: u32,
+    /// The distance travelled since the start.
+    distance_covered: f64,
+}
+
+impl SimState {
+    /// The maximum battery level.
+    const MAX_BATTERY: f64 = 100.0;
+    /// The velocity at the start of the simulation.
+    const START_VEL: (f64, f64) = (4.35, 5.49);
+
+    /// Creates the initial state at time `t`, fully charged and in charge mode.
+    fn new(t: DateTime<Utc>) -> Self {
+        Self {
+            t,
+            pos: (0.0, 0.0),
+            vel: Self::START_VEL,
+            target_vel: Self::START_VEL,
+            battery: Self::MAX_BATTERY,
+            fuel: 100.0,
+            state: FlightState::Charge,
+            transition: None,
+            angle: CameraAngle::Normal,
+            images_taken: 0,
+            distance_covered: 0.0,
+        }
+    }
+
+    /// Advances the state to `now` in steps of at most one second.
+    fn advance_to(&mut self, now: DateTime<Utc>) {
+        while self.t < now {
+            let step = (now - self.t).min(TimeDelta::seconds(1));
+            self.step(step.as_seconds_f64());
+            self.t += step;
+            if let Some((target, end)) = self.transition {
+                if self.t >= end {
+                    self.state = target;
+                    self.transition = None;
+                }
+            }
+        }
+    }
+
+    /// Integrates motion, fuel and battery over `dt` seconds.
+    ///
+    /// # Arguments
+    /// * `dt` – The step length in seconds, at most one.
+    fn step(&mut self, dt: f64) {
+        let mut rate = self.state.charge_rate_at(self.battery_fixed()).to_num::<f64>();
+        let dv = (self.target_vel.0 - self.vel.0, self.target_vel.1 - self.vel.1);
+        let dv_abs = dv.0.hypot(dv.1);
+        if self.state == FlightState::Acquisition && dv_abs > 0.0 && self.fuel > 0.0 {
+            let max_dv = FlightComputer::ACC_CONST.to_num::<f64>() * dt;
+            self.vel = if dv_abs <= max_dv {
+                self.target_vel
+            } else {
+                let scale = max_dv / dv_abs;
+                (self.vel.0 + dv.0 * scale, self.vel.1 + dv.1 * scale)
+            };
+            let acc_dt = dt * (dv_abs / max_dv).min(1.0);
+            self.fuel = (self.fuel - FlightComputer::FUEL_CONST.to_num::<f64>() * acc_dt).max(0.0);
+            rate += FlightState::ACQ_ACC_ADDITION.to_num::<f64>() * (acc_dt / dt);
+        }
+        let map = f64::map_size();
+        self.pos = (
+            (self.pos.0 + self.vel.0 * dt).rem_euclid(map.x()),
+            (self.pos.1 + self.vel.1 * dt).rem_euclid(map.y()),
+        );
+        self.distance_covered += self.vel.0.hypot(self.vel.1) * dt;
+        if self.state != FlightState::Transition {
+            self.battery = (self.battery + rate * dt).clamp(0.0, Self::MAX_BATTERY);
+        }
+        if self.battery <= 0.0 && self.state != FlightState::Safe {
+            self.state = FlightState::Safe;
+            self.transition = None;
+            self.target_vel = self.vel;
+        }
+    }
+
+    /// Returns the battery level as the fixed-point type used by the charge curves.
+    fn battery_fixed(&self) -> I32F32 { I32F32::from_num(self.battery) }
+
+    /// Serializes the state like the `/observation` endpoint of the DRS.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn observation(&self) -> Value {
+        let round_2 = |v: f64| (v * 100.0).round() / 100.0;
+        let angle: &'static str = self.angle.into();
+        let state: &'static str = self.state.into();
+        json!({
+            "state": state,
+            "angle": angle,
+            "simulation_speed": 1,
+            "width_x": self.pos.0.floor() as u16,
+            "height_y": self.pos.1.floor() as u16,
+            "vx": round_2(self.vel.0),
+            "vy": round_2(self.vel.1),
+            "battery": round_2(self.battery),
+            "max_battery": Self::MAX_BATTERY,
+            "fuel": round_2(self.fuel),
+            "distance_covered": self.distance_covered,
+            "area_covered": {"narrow": 0.0, "normal": 0.0, "wide": 0.0},
+            "data_volume": {"data_volume_sent": 0, "data_volume_received": 0},
+            "images_taken": self.images_taken,
+            "active_time": 0.0,
+            "objectives_done": 0,
+            "objectives_points": 0,
+            "timestamp": self.t,
+        })
+    }
+
+    /// Applies a `/control` request body.
+    ///
+    /// Velocity and camera angle changes are only accepted in acquisition mode, state changes
+    /// start a transition with the nominal delay.
+    ///
+    /// # Arguments
+    /// * `body` – The JSON body of the request.
+    fn control(&mut self, body: &Value) -> Result<Value, String> {
+        let vel_x = body["vel_x"].as_f64().ok_or("Missing vel_x")?;
+        let vel_y = body["vel_y"].as_f64().ok_or("Missing vel_y")?;
+        let angle = body["camera_angle"].as_str().ok_or("Missing camera_angle")?;
+        let state = body["state"].as_str().ok_or("Missing state")?;
+        let req_state = FlightState::from(state);
+        if req_state != self.state {
+            if self.transition.is_some() {
+                return Err("Satellite is already in transition".to_string());
+            }
+            let delay = self
+                .state
+                .try_dt_to(req_state)
+                .ok_or(format!("Cannot switch from {} to {req_state}", self.state))?;
+            let end = self.t + TimeDelta::from_std(delay).unwrap_or_default();
+            self.transition = Some((req_state, end));
+            self.state = FlightState::Transition;
+        }
+        if self.state == FlightState::Acquisition {
+            self.target_vel = (vel_x, vel_y);
+            self.angle = CameraAngle::from(angle);
+        }
+        let angle: &'static str = self.angle.into();
+        let state: &'static str = self.state.into();
+        Ok(json!({
+            "vel_x": self.target_vel.0,
+            "vel_y": self.target_vel.1,
+            "camera_angle": angle,
+            "state": state,
+            "status": "Successfully changed the state of the satellite",
+        }))
+    }
+
+    /// Renders the image of the current camera footprint as PNG.
+    ///
+    /// The pixel colors only depend on the map position, so repeated shots of the same area
+    /// are identical.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn shoot_image(&mut self) -> Result<Vec<u8>, String> {
+        if self.state != FlightState::Acquisition {
+            return Err("Images can only be taken in acquisition".to_string());
+        }
+        let side = u32::from(self.angle.get_square_side_length());
+        let (x0, y0) = (self.pos.0 as u32, self.pos.1 as u32);
+        let map = u32::map_size();
+        let img = RgbImage::from_fn(side, side, |x, y| {
+            let (mx, my) = ((x0 + x) % map.x(), (y0 + y) % map.y());
+            image::Rgb([(mx / 85) as u8, (my / 43) as u8, ((mx ^ my) & 0xFF) as u8])
+        });
+        let mut png = Vec::new();
+        img.write_to(&mut Cursor::new(&mut png), ImageFormat::Png).map_err(|e| e.to_string())?;
+        self.images_taken += 1;
+        Ok(png)
+    }
+}
+
+impl SimulatedDrs {
+    /// Creates a new [`SimulatedDrs`] starting at the current time.
+    pub(crate) fn new() -> Self { Self::starting_at(Utc::now()) }
+
+    /// Creates a new [`SimulatedDrs`] starting at `t`.
+    ///
+    /// # Arguments
+    /// * `t` – The simulation start time.
+    pub(crate) fn starting_at(t: DateTime<Utc>) -> Self {
+        Self { state: Mutex::new(SimState::new(t)) }
+    }
+
+    /// Answers a request at the current time.
+    ///
+    /// # Arguments
+    /// * `method` – The HTTP method of the request.
+    /// * `endpoint` – The endpoint path of the request.
+    /// * `query` – The query parameters of the request.
+    /// * `body` – The JSON body of the request, if any.
+    pub(crate) fn respond(
+        &self,
+        method: &HTTPRequestMethod,
+        endpoint: &str,
+        query: &HashMap<&str, String>,
+        body: Option<&Value>,
+    ) -> reqwest::Response {
+        self.respond_at(Utc::now(), method, endpoint, query, body)
+    }
+
+    /// Answers a request after advancing the simulation to `now`.
+    ///
+    /// # Arguments
+    /// * `now` – The time the request is answered at.
+    /// * `method` – The HTTP method of the request.
+    /// * `endpoint` – The endpoint path of the request.
+    /// * `query` – The query parameters of the request.
+    /// * `body` – The JSON body of the request, if any.
+    pub(crate) fn respond_at(
+        &self,
+        now: DateTime<Utc>,
+        method: &HTTPRequestMethod,
+        endpoint: &str,
+        query: &HashMap<&str, String>,
+        body: Option<&Value>,
+    ) -> reqwest::Response {
+        let mut state = self.state.lock().unwrap();
+        state.advance_to(now);
+        let res = match (method, endpoint) {
+            (HTTPRequestMethod::Get, "/observation") => Ok(state.observation()),
+            (HTTPRequestMethod::Put, "/control") => {
+                body.ok_or("Missing body".to_string()).and_then(|b| state.control(b))
+            }
+            (HTTPRequestMethod::Get, "/image") => {
+                return match state.shoot_image() {
+                    Ok(png) => Self::response(200, "image/png", png),
+                    Err(e) => Self::error(&e),
+                };
+            }
+            (HTTPRequestMethod::Get, "/objective") => {
+                Ok(json!({"zoned_objectives": [], "beacon_objectives": []}))
+            }
+            (HTTPRequestMethod::Put, "/beacon") => {
+                let id = query.get("beacon_id").map_or("", String::as_str);
+                Ok(json!({"status": format!("Could not find beacon {id}"), "attempts_made": 0}))
+            }
+            (HTTPRequestMethod::Post, "/image" | "/dailyMap") => Ok(json!("Image uploaded")),
+            (HTTPRequestMethod::Get, "/achievements") => Ok(json!({"achievements": []})),
+            (HTTPRequestMethod::Get, "/slots") => {
+                Ok(json!({"communication_slots_used": 0, "slots": []}))
+            }
+            (HTTPRequestMethod::Get, "/reset") => {
+                *state = SimState::new(now);
+                Ok(json!("Simulation reset"))
+            }
+            (HTTPRequestMethod::Get, "/annoucements") => {
+                return Self::response(200, "text/event-stream", Vec::new());
+            }
+            _ => Err(format!("{method:?} {endpoint} is not simulated")),
+        };
+        match res {
+            Ok(json) => Self::response(200, "application/json", json.to_string().into_bytes()),
+            Err(e) => Self::error(&e),
+        }
+    }
+
+    /// Builds a client error response with a DRS-style `detail` body.
+    ///
+    /// # Arguments
+    /// * `detail` – The error explanation.
+    fn error(detail: &str) -> reqwest::Response {
+        let body = json!({ "detail": detail }).to_string().into_bytes();
+        Self::response(400, "application/json", body)
+    }
+
+    /// Builds a response with the given status, content type and body.
+    ///
+    /// # Arguments
+    /// * `status` – The HTTP status code.
+    /// * `content_type` – The value of the `Content-Type` header.
+    /// * `body` – The raw response body.
+    fn response(status: u16, content_type: &'static str, body: Vec<u8>) -> reqwest::Response {
+        let resp = http::Response::builder()
+            .status(status)
+            .header(http::header::CONTENT_TYPE, content_type)
+            .body(body)
+            .unwrap();
+        reqwest::Response::from(resp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_simulated_transition_and_observation() {
+        let t0 = Utc::now();
+        let sim = SimulatedDrs::starting_at(t0);
+        let none = HashMap::new();
+        let control =
+            json!({"vel_x": 4.35, "vel_y": 5.49, "camera_angle": "wide", "state": "acquisition"});
+        let put = HTTPRequestMethod::Put;
+        let resp = sim.respond_at(t0, &put, "/control", &none, Some(&control));
+        assert!(resp.status().is_success());
+
+        let get = |t| sim.respond_at(t, &HTTPRequestMethod::Get, "/observation", &none, None);
+        let obs: Value = get(t0 + TimeDelta::seconds(100)).json().await.unwrap();
+        assert_eq!(obs["state"], "transition");
+        assert_eq!(obs["battery"], 100.0);
+        let obs: Value = get(t0 + TimeDelta::seconds(280)).json().await.unwrap();
+        assert_eq!(obs["state"], "acquisition");
+        assert!((1217..=1218).contains(&obs["width_x"].as_u64().unwrap()));
+        assert!(obs["battery"].as_f64().unwrap() < 100.0);
+
+        let t_img = t0 + TimeDelta::seconds(281);
+        let img = sim.respond_at(t_img, &HTTPRequestMethod::Get, "/image", &none, None);
+        let png = img.bytes().await.unwrap();
+        assert_eq!(image::load_from_memory(&png).unwrap().width(), 800);
+    }
+}
@@ -1,7 +1,21 @@
-use super::{CameraAngle, cycle_state::CycleState, map_image::*};
+use super::{
+    CameraAngle, CoveragePlanner, DailyMapUpload, ImageCodec, ImagingError, LensPolicy,
+    capture_health::{CaptureHealth, CaptureTransition},
+    capture_log::CaptureLog,
+    cycle_state::CycleState,
+    map_image::*,
+    mosaic_completeness::MosaicCompleteness,
+    offset_estimator::OffsetEstimator,
+    tile_classifier::FeaturelessMap,
+    thumbnail_deferral::ThumbnailDeferral,
+    tile_pyramid::{TileId, TilePyramid},
+    image_store::{ImageStore, StorageReport},
+    upload_queue::{PendingUpload, UploadQueue},
+};
 use crate::console_communication::ConsoleMessenger;
 use crate::flight_control::FlightComputer;
 use crate::http_handler::{
+    BandwidthShaper, HTTPError, TrafficClass,
     http_client::HTTPClient,
     http_request::{
         daily_map_post::DailyMapRequest,
@@ -11,21 +25,21 @@ use crate::http_handler::{
     },
 };
 use crate::mode_control::PeriodicImagingEndSignal::{self, KillLastImage, KillNow};
-use crate::util::Vec2D;
-use crate::{DT_0_STD, error, fatal, info, log, obj};
+use crate::util::{
+    IMAGING_POOL, ImgObjectiveId, MISSION_METRICS, MapSize, MissionConfig, Vec2D,
+    logger::JsonDump,
+};
+use crate::{DT_0_STD, error, fatal, info, log, obj, warn};
 use chrono::{DateTime, TimeDelta, Utc};
 use fixed::types::I32F32;
 use futures::StreamExt;
-use image::{GenericImageView, ImageReader, Pixel, RgbImage, imageops::Lanczos3};
+use image::{ImageReader, RgbImage, imageops::Lanczos3};
 use std::{
-    fs,
     path::{Path, PathBuf},
     {io::Cursor, sync::Arc},
 };
 use tokio::{
-    fs::File,
-    io::AsyncWriteExt,
-    sync::{Mutex, RwLock, oneshot},
+    sync::{Mutex, RwLock, oneshot, watch},
 };
 
 /// A struct for managing camera-related operations and map snapshots.
@@ -36,16 +50,35 @@ pub struct CameraController {
     fullsize_map_image: RwLock<FullsizeMapImage>,
     /// The lock-protected thumbnail map image.
     thumbnail_map_image: RwLock<ThumbnailMapImage>,
+    /// The lock-protected content classification of already imaged map cells.
+    featureless_map: RwLock<FeaturelessMap>,
     /// The HTTP client for sending requests.
     request_client: Arc<HTTPClient>,
+    /// The lock-protected health of map captures.
+    capture_health: Mutex<CaptureHealth>,
+    /// The lock-protected log of successful map captures.
+    capture_log: Mutex<CaptureLog>,
+    /// The lock-protected map captures whose thumbnail update was deferred on low battery.
+    deferred_thumbnails: Mutex<ThumbnailDeferral>,
+    /// The lock-protected tile pyramid of the full-size map for console zooming.
+    tile_pyramid: Mutex<TilePyramid>,
+    /// The lock-protected queue of rejected objective image uploads.
+    upload_queue: Mutex<UploadQueue>,
+    /// The storage manager of the exported objective images, only accessed in the
+    /// [`IMAGING_POOL`] as it blocks on file I/O.
+    image_store: std::sync::Mutex<ImageStore>,
+    /// The lock-protected map regions quarantined at startup, until they are reopened.
+    quarantined: Mutex<Vec<(Vec2D<u32>, Vec2D<u32>)>>,
 }
 
 /// Path to the binary map buffer file.
-const MAP_BUFFER_PATH: &str = "map.bin";
+pub(crate) const MAP_BUFFER_PATH: &str = "map.bin";
 /// Path to the full-size snapshot file.
 const SNAPSHOT_FULL_PATH: &str = "snapshot_full.png";
-/// Path to the thumbnail snapshot file.
-const SNAPSHOT_THUMBNAIL_PATH: &str = "snapshot_thumb.png";
+/// Path to the thumbnail snapshot file, encoded with the internal [`ImageCodec`].
+const SNAPSHOT_THUMBNAIL_PATH: &str = "snapshot_thumb.img";
+/// Path to the persisted queue of rejected objective image uploads.
+const UPLOAD_QUEUE_PATH: &str = "pending_uploads.json";
 
 impl CameraController {
     /// Constant minimum delay to perform another image.
@@ -66,23 +99,38 @@ impl CameraController {
     ///
     /// A new instance of [`CameraController`].
     pub fn start(base_path: String, request_client: Arc<HTTPClient>) -> Self {
-        let fullsize_map_image =
+        let mut fullsize_map_image =
             FullsizeMapImage::open(Path::new(&base_path).join(MAP_BUFFER_PATH));
+        let quarantined = fullsize_map_image.verify_integrity(fullsize_map_image.integrity_tiles());
         let thumbnail_map_image =
             ThumbnailMapImage::from_snapshot(Path::new(&base_path).join(SNAPSHOT_THUMBNAIL_PATH));
-        if let Err(e) = fs::create_dir_all(Self::ZO_IMG_FOLDER) {
-            fatal!("Failed to create objective image directory: {e}!");
-        }
+        let upload_queue = UploadQueue::open(Path::new(&base_path).join(UPLOAD_QUEUE_PATH));
+        let budget = MissionConfig::get().imaging.zo_img_budget();
+        let image_store = ImageStore::open(Self::ZO_IMG_FOLDER, budget).unwrap_or_else(|e| {
+            fatal!("Failed to open objective image directory: {e}!");
+        });
         Self {
             fullsize_map_image: RwLock::new(fullsize_map_image),
             thumbnail_map_image: RwLock::new(thumbnail_map_image),
+            featureless_map: RwLock::new(FeaturelessMap::new()),
             request_client,
+            capture_health: Mutex::new(CaptureHealth::new()),
+            capture_log: Mutex::new(CaptureLog::default()),
+            deferred_thumbnails: Mutex::new(ThumbnailDeferral::default()),
+            tile_pyramid: Mutex::new(TilePyramid::new(u32::map_size())),
+            upload_queue: Mutex::new(upload_queue),
+            image_store: std::sync::Mutex::new(image_store),
+            quarantined: Mutex::new(quarantined),
             base_path,
         }
     }
 
     /// Scores the offset by comparing the decoded image against the map base image.
     ///
+    /// If the best match within the small window still differs in more pixels than allowed by
+    /// `offset_match_max_mismatch` of the [`MissionConfig`], an [`OffsetEstimator`] searches
+    /// the wider `offset_search_radius_px` and its estimate is taken if it matches better.
+    ///
     /// # Arguments
     ///
     /// * `decoded_image` - The decoded image to match.
@@ -92,7 +140,7 @@ impl CameraController {
     /// # Returns
     ///
     /// The best scored offset as `Vec2D<i32>`.
-    #[allow(clippy::cast_sign_loss, clippy::cast_possible_wrap)]
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_wrap, clippy::cast_precision_loss)]
     fn score_offset(
         decoded_image: &RgbImage,
         base: &FullsizeMapImage,
@@ -108,19 +156,7 @@ impl CameraController {
                 )
                 .wrap_around_map()
                 .to_unsigned();
-                let map_image_view = base.vec_view(
-                    current_offset,
-                    Vec2D::new(decoded_image.width(), decoded_image.height()),
-                );
-                let mut score: i32 = map_image_view
-                    .pixels()
-                    .zip(decoded_image.pixels())
-                    .map(
-                        |((_, _, existing_pixel), new_pixel)| {
-                            if existing_pixel.to_rgb() == new_pixel.to_rgb() { 0 } else { -1 }
-                        },
-                    )
-                    .sum();
+                let mut score = base.match_score(current_offset, decoded_image);
 
                 score -= additional_offset_x.abs() + additional_offset_y.abs();
                 if score > best_score {
@@ -129,6 +165,24 @@ impl CameraController {
                 }
             }
         }
+        let config = MissionConfig::get();
+        let runtime = &config.runtime;
+        let pixels = f64::from(decoded_image.width() * decoded_image.height());
+        let mismatch = f64::from(best_score.saturating_neg()) / pixels;
+        if runtime.offset_search_radius_px == 0 || mismatch <= runtime.offset_match_max_mismatch {
+            return best_additional_offset;
+        }
+        let estimator = OffsetEstimator::new(runtime.offset_search_radius_px);
+        if let Some((wide_offset, score)) = estimator.estimate(decoded_image, base, offset) {
+            let wide_score = score - wide_offset.x().abs() - wide_offset.y().abs();
+            if wide_score > best_score {
+                warn!(
+                    "Image offset drifted by {wide_offset}, {:.0}% mismatch in the small window.",
+                    mismatch * 100.0
+                );
+                return wide_offset;
+            }
+        }
         best_additional_offset
     }
 
@@ -148,15 +202,20 @@ impl CameraController {
         &self,
         f_cont_locked: Arc<RwLock<FlightComputer>>,
         angle: CameraAngle,
-    ) -> Result<(Vec2D<I32F32>, Vec2D<i32>, RgbImage), Box<dyn std::error::Error + Send + Sync>>
+    ) -> Result<(Vec2D<I32F32>, Vec2D<i32>, RgbImage), ImagingError>
     {
         let (position, collected_png) = {
             let mut f_cont = f_cont_locked.write().await;
-            let ((), collected_png) =
+            let (_, collected_png) =
                 tokio::join!(f_cont.update_observation(), self.fetch_image_data());
             (f_cont.current_pos(), collected_png)
         };
-        let decoded_image = Self::decode_png_data(&collected_png?, angle)?;
+        let png = collected_png.inspect_err(|_| MISSION_METRICS.record_picture(false))?;
+        let decoded_image = IMAGING_POOL
+            .run("png_decode", || Self::decode_png_data(&png, angle))
+            .await
+            .inspect_err(|_| MISSION_METRICS.record_picture(false))?;
+        MISSION_METRICS.record_picture(true);
         let angle_const = angle.get_square_side_length() / 2;
         let offset: Vec2D<i32> = Vec2D::new(
             position.x().round().to_num::<i32>() - i32::from(angle_const),
@@ -168,6 +227,8 @@ impl CameraController {
 
     /// Captures an image, processes it, and stores it in the map buffer.
     ///
+    /// The thumbnail update is deferred if [`CameraController::defer_thumbnail`] applies.
+    ///
     /// # Arguments
     /// * `f_cont_locked` - The lock-protected flight computer.
     /// * `angle` - The camera angle and field of view.
@@ -179,23 +240,42 @@ impl CameraController {
         &self,
         f_cont_locked: Arc<RwLock<FlightComputer>>,
         angle: CameraAngle,
-    ) -> Result<(Vec2D<I32F32>, Vec2D<u32>), Box<dyn std::error::Error + Send + Sync>> {
+    ) -> Result<(Vec2D<I32F32>, Vec2D<u32>), ImagingError> {
+        let batt = f_cont_locked.read().await.current_battery();
         let (pos, offset, decoded_image) = self.get_image(f_cont_locked, angle).await?;
 
         let tot_offset_u32 = {
             let mut fullsize_map_image = self.fullsize_map_image.write().await;
-            let best_additional_offset =
-                Self::score_offset(&decoded_image, &fullsize_map_image, offset.to_unsigned());
-            let tot_offset: Vec2D<u32> =
-                (offset + best_additional_offset).wrap_around_map().to_unsigned();
-            fullsize_map_image.update_area(tot_offset, &decoded_image);
+            let tot_offset = IMAGING_POOL
+                .run("offset_scoring", || {
+                    let best_additional_offset = Self::score_offset(
+                        &decoded_image,
+                        &fullsize_map_image,
+                        offset.to_unsigned(),
+                    );
+                    let tot_offset: Vec2D<u32> =
+                        (offset + best_additional_offset).wrap_around_map().to_unsigned();
+                    fullsize_map_image.update_area(tot_offset, &decoded_image).map(|()| tot_offset)
+                })
+                .await?;
+            let mut tile_pyramid = self.tile_pyramid.lock().await;
+            let size = Vec2D::new(decoded_image.width(), decoded_image.height());
+            IMAGING_POOL
+                .run("tile_pyramid_update", || {
+                    tile_pyramid.update_area(&*fullsize_map_image, tot_offset, size);
+                })
+                .await;
             tot_offset
         };
+        self.featureless_map.write().await.update_from_image(tot_offset_u32, &decoded_image);
+        if self.defer_thumbnail(batt, tot_offset_u32, angle).await {
+            return Ok((pos, tot_offset_u32));
+        }
         self.update_thumbnail_area_from_fullsize(
             tot_offset_u32,
             u32::from(angle.get_square_side_length() / 2),
         )
-        .await;
+        .await?;
         Ok((pos, tot_offset_u32))
     }
 
@@ -213,7 +293,7 @@ impl CameraController {
         f_cont_locked: Arc<RwLock<FlightComputer>>,
         angle: CameraAngle,
         zoned_objective_map_image: Option<&mut OffsetZonedObjectiveImage>,
-    ) -> Result<Vec2D<I32F32>, Box<dyn std::error::Error + Send + Sync>> {
+    ) -> Result<Vec2D<I32F32>, ImagingError> {
         let (pos, offset, decoded_image) = self.get_image(f_cont_locked, angle).await?;
         let offset_u32 = offset.to_unsigned();
         if let Some(image) = zoned_objective_map_image {
@@ -223,14 +303,100 @@ impl CameraController {
         Ok(pos)
     }
 
+    /// Returns for every given position whether its map cell was imaged and classified as
+    /// featureless (e.g. uniform ocean).
+    ///
+    /// # Arguments
+    /// * `positions` - The map positions to check.
+    pub async fn featureless_flags(&self, positions: &[Vec2D<I32F32>]) -> Vec<bool> {
+        let featureless_map = self.featureless_map.read().await;
+        positions.iter().map(|pos| featureless_map.is_featureless(*pos)).collect()
+    }
+
+    /// Returns `true` while map captures are persistently failing.
+    pub async fn is_capture_degraded(&self) -> bool {
+        self.capture_health.lock().await.is_degraded()
+    }
+
+    /// Subscribes to changes of the map capture degradation state.
+    pub async fn capture_health_watch(&self) -> watch::Receiver<bool> {
+        self.capture_health.lock().await.subscribe()
+    }
+
+    /// Returns the times of all successful map captures within a time window.
+    ///
+    /// # Arguments
+    /// * `start` - The start of the window.
+    /// * `end` - The inclusive end of the window.
+    pub async fn captures_between(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Vec<DateTime<Utc>> {
+        self.capture_log.lock().await.between(start, end)
+    }
+
+    /// Defers the thumbnail update of a map capture if the battery is below the
+    /// `thumb_defer_batt` of the [`MissionConfig`].
+    ///
+    /// Once a capture is deferred, all further captures are deferred as well until
+    /// [`CameraController::flush_deferred_thumbnails`] is called, keeping their order.
+    ///
+    /// # Arguments
+    /// * `batt` - The battery level before the capture.
+    /// * `offset` - The offset of the capture in the map buffer.
+    /// * `angle` - The camera angle of the capture.
+    ///
+    /// # Returns
+    /// `true` if the thumbnail update was deferred.
+    async fn defer_thumbnail(&self, batt: I32F32, offset: Vec2D<u32>, angle: CameraAngle) -> bool {
+        let threshold = I32F32::from_num(MissionConfig::get().runtime.thumb_defer_batt);
+        self.deferred_thumbnails.lock().await.defer(batt, threshold, offset, angle)
+    }
+
+    /// Returns `true` while thumbnail updates are deferred.
+    pub async fn has_deferred_thumbnails(&self) -> bool {
+        self.deferred_thumbnails.lock().await.is_active()
+    }
+
+    /// Applies all deferred thumbnail updates in one batch.
+    ///
+    /// # Returns
+    /// The offsets and camera angles of all updated captures, e.g. to notify the console.
+    pub(crate) async fn flush_deferred_thumbnails(&self) -> Vec<(Vec2D<u32>, CameraAngle)> {
+        let mut deferral = self.deferred_thumbnails.lock().await;
+        let deferred = deferral.take();
+        if deferred.is_empty() {
+            return deferred;
+        }
+        info!("Applying {} deferred thumbnail updates.", deferred.len());
+        let mut updated = Vec::with_capacity(deferred.len());
+        for (offset, angle) in deferred {
+            let size = u32::from(angle.get_square_side_length() / 2);
+            match self.update_thumbnail_area_from_fullsize(offset, size).await {
+                Ok(()) => updated.push((offset, angle)),
+                Err(e) => error!("Failed to apply deferred thumbnail update: {e}"),
+            }
+        }
+        updated
+    }
+
     /// Updates the thumbnail area of the map based on the full-size map data.
     ///
     /// # Arguments
     ///
     /// * `offset` - Offset to update.
     /// * `size` - Size of the region to update.
+    ///
+    /// # Returns
+    ///
+    /// An error if the thumbnail buffer could not be updated.
     #[allow(clippy::cast_possible_wrap)]
-    async fn update_thumbnail_area_from_fullsize(&self, offset: Vec2D<u32>, size: u32) {
+    async fn update_thumbnail_area_from_fullsize(
+        &self,
+        offset: Vec2D<u32>,
+        size: u32,
+    ) -> Result<(), ImagingError> {
         let thumbnail_offset = Vec2D::new(
             offset.x() as i32 - ThumbnailMapImage::THUMBNAIL_SCALE_FACTOR as i32 * 2,
             offset.y() as i32 - ThumbnailMapImage::THUMBNAIL_SCALE_FACTOR as i32 * 2,
@@ -250,7 +416,7 @@ impl CameraController {
         self.thumbnail_map_image.write().await.update_area(
             thumbnail_offset / ThumbnailMapImage::THUMBNAIL_SCALE_FACTOR,
             &resized_image,
-        );
+        )
     }
 
     /// Fetches image data from the camera as a byte vector.
@@ -258,13 +424,15 @@ impl CameraController {
     /// # Returns
     ///
     /// The raw PNG data or an error.
-    async fn fetch_image_data(&self) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    async fn fetch_image_data(&self) -> Result<Vec<u8>, ImagingError> {
         let response_stream = ShootImageRequest {}.send_request(&self.request_client).await?;
 
         let mut collected_png: Vec<u8> = Vec::new();
         futures::pin_mut!(response_stream);
 
-        while let Some(Ok(chunk_result)) = response_stream.next().await {
+        while let Some(chunk) = response_stream.next().await {
+            let chunk_result = chunk.map_err(HTTPError::from)?;
+            BandwidthShaper::link().acquire(TrafficClass::Bulk, chunk_result.len()).await;
             collected_png.extend_from_slice(&chunk_result[..]);
         }
 
@@ -284,7 +452,7 @@ impl CameraController {
     fn decode_png_data(
         collected_png: &[u8],
         angle: CameraAngle,
-    ) -> Result<RgbImage, Box<dyn std::error::Error + Send + Sync>> {
+    ) -> Result<RgbImage, ImagingError> {
         let decoded_image =
             ImageReader::new(Cursor::new(collected_png)).with_guessed_format()?.decode()?.to_rgb8();
         let resized_unit_length = angle.get_square_side_length();
@@ -299,42 +467,208 @@ impl CameraController {
         Ok(resized_image)
     }
 
-    /// Exports a specific region of the map as a PNG and uploads it to the server associated with the given objective ID.
+    /// Analyzes the completeness of an objective mosaic before it is uploaded.
     ///
     /// # Arguments
+    /// * `offset` - The offset of the objective zone in the map.
+    /// * `size` - The dimensions of the objective zone.
+    /// * `zoned_objective_map_image` - The dedicated objective image, if one was taken.
     ///
-    /// * `objective_id` - The identifier of the objective to associate the exported PNG with.
+    /// # Returns
+    /// The [`MosaicCompleteness`] of the image that would be uploaded.
+    pub(crate) async fn objective_completeness(
+        &self,
+        offset: Vec2D<u32>,
+        size: Vec2D<u32>,
+        zoned_objective_map_image: Option<&OffsetZonedObjectiveImage>,
+    ) -> MosaicCompleteness {
+        if let Some(zo_image) = zoned_objective_map_image {
+            MosaicCompleteness::analyze(zo_image)
+        } else {
+            let map_image = self.fullsize_map_image.read().await;
+            MosaicCompleteness::analyze(&map_image.vec_view(offset, size))
+        }
+    }
+
+    /// Exports a specific region of the map with the objective codec and uploads it to the server
+    /// associated with the given objective ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `objective_id` - The identifier of the objective to associate the exported image with.
     /// * `offset` - The offset in the map to start the export.
-    /// * `size` - The dimensions of the region to export as a PNG.
+    /// * `size` - The dimensions of the region to export.
+    /// * `export_path` - The path of the uploaded file, its extension follows the codec.
+    /// * `zoned_objective_map_image` - The dedicated objective image, if one was taken.
+    /// * `retry_deadline` - The time until which a rejected upload is retried, if at all.
     ///
     /// # Returns
     ///
-    /// A result indicating the success or failure of the operation.
+    /// A result containing the response message of the DRS if the image was uploaded.
     #[allow(clippy::cast_sign_loss)]
-    pub(crate) async fn export_and_upload_objective_png(
+    pub(crate) async fn export_and_upload_objective_img(
         &self,
-        objective_id: usize,
+        objective_id: ImgObjectiveId,
         offset: Vec2D<u32>,
         size: Vec2D<u32>,
         export_path: Option<PathBuf>,
         zoned_objective_map_image: Option<&OffsetZonedObjectiveImage>,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+        retry_deadline: Option<DateTime<Utc>>,
+    ) -> Result<Option<String>, ImagingError> {
+        let codec = Self::objective_codec();
         let encoded_image = if let Some(zo_image) = zoned_objective_map_image {
-            zo_image.export_as_png()?
+            IMAGING_POOL.run("objective_encode", || zo_image.export_as(codec)).await?
         } else {
             let map_image = self.fullsize_map_image.read().await;
-            map_image.export_area_as_png(offset, size)?
+            IMAGING_POOL
+                .run("objective_encode", || map_image.export_area_as(offset, size, codec))
+                .await?
         };
-        if let Some(img_path) = export_path {
-            let mut img_file = File::create(&img_path).await?;
-            img_file.write_all(encoded_image.data.as_slice()).await?;
-            drop(img_file);
-            ObjectiveImageRequest::new(objective_id, img_path)
-                .send_request(&self.request_client)
+        if let Some(path) = export_path {
+            let protected = self.pending_upload_paths().await;
+            let img_path = self
+                .with_image_store("zo_img_store", |store| {
+                    let img_path = path.with_extension(codec.extension());
+                    store.store(objective_id, img_path, &encoded_image.data, &protected)
+                })
                 .await?;
+            let upload = ObjectiveImageRequest::new(objective_id, img_path.clone())
+                .send_request(&self.request_client)
+                .await;
+            return match (upload, retry_deadline) {
+                (Ok(response), _) => {
+                    log!("Successfully exported and uploaded objective {codec}.");
+                    self.upload_queue.lock().await.remove(objective_id);
+                    self.with_image_store("zo_img_release", |store| store.release(&img_path))
+                        .await;
+                    Ok(Some(response))
+                }
+                (Err(e), Some(deadline)) if deadline > Utc::now() => {
+                    warn!("Upload of objective {objective_id} rejected, retrying later: {e}");
+                    self.upload_queue.lock().await.push(objective_id, img_path.clone(), deadline);
+                    self.with_image_store("zo_img_queue", |store| store.finish_upload(&img_path))
+                        .await;
+                    Err(e.into())
+                }
+                (Err(e), _) => {
+                    self.with_image_store("zo_img_keep", |store| store.finish_upload(&img_path))
+                        .await;
+                    Err(e.into())
+                }
+            };
         }
-        log!("Successfully exported and uploaded objective png.");
-        Ok(())
+        log!("Successfully exported objective {codec}.");
+        Ok(None)
+    }
+
+    /// Returns `true` if a rejected upload of the objective waits for a retry.
+    ///
+    /// # Arguments
+    ///
+    /// * `objective_id` - The identifier of the objective.
+    pub(crate) async fn is_upload_pending(&self, objective_id: ImgObjectiveId) -> bool {
+        let queue = self.upload_queue.lock().await;
+        queue.pending().iter().any(|p| p.objective_id() == objective_id)
+    }
+
+    /// Retries the rejected objective image uploads whose objective did not end yet.
+    ///
+    /// # Returns
+    ///
+    /// The objectives whose image was uploaded with the DRS responses, and the objectives
+    /// whose upload was given up as their deadline passed.
+    pub(crate) async fn retry_objective_uploads(
+        &self,
+    ) -> (Vec<(ImgObjectiveId, String)>, Vec<ImgObjectiveId>) {
+        let mut queue = self.upload_queue.lock().await;
+        let taken = queue.take_expired(Utc::now());
+        let expired = taken.iter().map(PendingUpload::objective_id).collect();
+        self.with_image_store("zo_img_expire", |store| {
+            for upload in &taken {
+                store.release(upload.path());
+            }
+        })
+        .await;
+        let mut uploaded = Vec::new();
+        for upload in queue.pending().to_vec() {
+            let id = upload.objective_id();
+            let req = ObjectiveImageRequest::new(id, upload.path().to_path_buf());
+            match req.send_request(&self.request_client).await {
+                Ok(response) => {
+                    obj!("Re-uploaded objective {id} after {} attempts.", upload.attempts());
+                    queue.remove(id);
+                    self.with_image_store("zo_img_release", |store| store.release(upload.path()))
+                        .await;
+                    uploaded.push((id, response));
+                }
+                Err(e) => {
+                    warn!("Retry of objective {id} upload failed: {e}");
+                    queue.record_attempt(id);
+                }
+            }
+        }
+        (uploaded, expired)
+    }
+
+    /// Returns the paths of the objective images waiting for an upload retry.
+    async fn pending_upload_paths(&self) -> Vec<PathBuf> {
+        let queue = self.upload_queue.lock().await;
+        queue.pending().iter().map(|p| p.path().to_path_buf()).collect()
+    }
+
+    /// Returns the disk usage of the objective images and the free space of the file system
+    /// holding the map buffer.
+    pub(crate) async fn storage_report(&self) -> StorageReport {
+        let base_path = Path::new(&self.base_path);
+        self.with_image_store("storage_report", |store| StorageReport::new(store, base_path)).await
+    }
+
+    /// Deletes all objective images that are neither waiting for an upload retry nor being
+    /// uploaded.
+    ///
+    /// # Returns
+    ///
+    /// The number of freed bytes.
+    pub(crate) async fn reclaim_disk_space(&self) -> u64 {
+        let protected = self.pending_upload_paths().await;
+        self.with_image_store("zo_img_reclaim", |store| store.evict_to(0, &protected)).await
+    }
+
+    /// Runs a job on the [`ImageStore`] in the [`IMAGING_POOL`], as it blocks on file I/O.
+    ///
+    /// # Arguments
+    ///
+    /// * `job_name` - The name the job is timed under.
+    /// * `job` - The job accessing the store.
+    async fn with_image_store<R>(
+        &self,
+        job_name: &'static str,
+        job: impl FnOnce(&mut ImageStore) -> R,
+    ) -> R {
+        IMAGING_POOL.run(job_name, || job(&mut self.image_store.lock().unwrap())).await
+    }
+
+    /// Returns alert messages for rejected objective image uploads whose deadline approaches,
+    /// each only once.
+    pub(crate) async fn upload_deadline_alerts(&self) -> Vec<String> {
+        let now = Utc::now();
+        let mut queue = self.upload_queue.lock().await;
+        if queue.is_empty() {
+            return Vec::new();
+        }
+        queue
+            .take_alerts(now)
+            .iter()
+            .map(|p| {
+                let mins = (p.deadline() - now).num_minutes().max(0);
+                format!(
+                    "Objective {} image still not uploaded after {} attempts, \
+                    {mins} min left.",
+                    p.objective_id(),
+                    p.attempts()
+                )
+            })
+            .collect()
     }
 
     /// Helper method generating the export path for a given zoned objective id.
@@ -344,25 +678,73 @@ impl CameraController {
     ///
     /// # Returns
     /// The path to the zoned objective image file as a `PathBuf`
-    pub(crate) fn generate_zo_img_path(id: usize) -> PathBuf {
+    pub(crate) fn generate_zo_img_path(id: ImgObjectiveId) -> PathBuf {
         let dir = Path::new(Self::ZO_IMG_FOLDER);
-        let mut path = dir.join(format!("zo_{id}.png"));
+        let ext = Self::objective_codec().extension();
+        let mut path = dir.join(format!("zo_{id}.{ext}"));
         let mut counter = 0;
         while path.exists() {
-            path = dir.join(format!("zo_{id}_{counter}.png",));
+            path = dir.join(format!("zo_{id}_{counter}.{ext}"));
             counter += 1;
         }
         path
     }
 
+    /// Starts the upload of the full snapshot last exported by `export_full_snapshot`.
+    ///
+    /// # Returns
+    ///
+    /// The upload progress, or an error if the snapshot does not exist.
+    pub(crate) fn start_daily_map_upload(&self) -> Result<DailyMapUpload, std::io::Error> {
+        DailyMapUpload::new(Path::new(&self.base_path).join(SNAPSHOT_FULL_PATH))
+    }
+
     /// Uploads the daily map snapshot as a PNG to the server.
     ///
     /// # Returns
     ///
     /// A result indicating the success or failure of the operation.
-    #[allow(clippy::cast_sign_loss)]
-    pub(crate) async fn upload_daily_map_png(&self) -> Result<(), Box<dyn std::error::Error>> {
-        DailyMapRequest::new(SNAPSHOT_FULL_PATH)?.send_request(&self.request_client).await?;
+    pub(crate) async fn upload_daily_map_png(&self) -> Result<(), ImagingError> {
+        self.resume_daily_map_upload(&mut self.start_daily_map_upload()?).await
+    }
+
+    /// Uploads the daily map snapshot as a PNG to the server, resuming the given upload.
+    ///
+    /// If `daily_map_chunk_kib` of the [`MissionConfig`] is set, the snapshot is sent in
+    /// chunks paced as bulk traffic, and every confirmed chunk is recorded in `upload`.
+    /// Otherwise, the snapshot is sent in a single request.
+    ///
+    /// # Arguments
+    ///
+    /// * `upload` - The upload progress, restarted if the snapshot changed.
+    ///
+    /// # Returns
+    ///
+    /// A result indicating the success or failure of the operation.
+    #[allow(clippy::cast_possible_truncation)]
+    pub(crate) async fn resume_daily_map_upload(
+        &self,
+        upload: &mut DailyMapUpload,
+    ) -> Result<(), ImagingError> {
+        if upload.is_stale() {
+            log!("Daily Map snapshot changed, restarting upload.");
+            *upload = DailyMapUpload::new(upload.path())?;
+        }
+        let chunk_len = u64::from(MissionConfig::get().runtime.daily_map_chunk_kib) * 1024;
+        if chunk_len == 0 {
+            DailyMapRequest::new(upload.path())?.send_request(&self.request_client).await?;
+            upload.confirm(&(0..upload.total()));
+            return Ok(());
+        }
+        while let Some(range) = upload.next_chunk(chunk_len) {
+            let len = (range.end - range.start) as usize;
+            BandwidthShaper::link().acquire(TrafficClass::Bulk, len).await;
+            DailyMapRequest::chunk(upload.path(), range.clone(), upload.total())?
+                .send_request(&self.request_client)
+                .await?;
+            upload.confirm(&range);
+            log!("Uploaded Daily Map chunk, {:.0}% done.", upload.progress() * 100.0);
+        }
         Ok(())
     }
 
@@ -371,11 +753,14 @@ impl CameraController {
     /// # Returns
     ///
     /// A result indicating the success or failure of the operation.
-    pub(crate) async fn create_thumb_snapshot(&self) -> Result<(), Box<dyn std::error::Error>> {
-        self.thumbnail_map_image
-            .read()
+    pub(crate) async fn create_thumb_snapshot(&self) -> Result<(), ImagingError> {
+        let thumbnail = self.thumbnail_map_image.read().await;
+        let path = Path::new(&self.base_path).join(SNAPSHOT_THUMBNAIL_PATH);
+        IMAGING_POOL
+            .run("thumbnail_snapshot", || {
+                thumbnail.create_snapshot_as(path, Self::internal_codec())
+            })
             .await
-            .create_snapshot(Path::new(&self.base_path).join(SNAPSHOT_THUMBNAIL_PATH))
     }
 
     /// Creates and saves a full-size snapshot of the map.
@@ -383,12 +768,11 @@ impl CameraController {
     /// # Returns
     ///
     /// A result indicating the success or failure of the operation.
-    pub(crate) async fn export_full_snapshot(&self) -> Result<(), Box<dyn std::error::Error>> {
+    pub(crate) async fn export_full_snapshot(&self) -> Result<(), ImagingError> {
         let start_time = Utc::now();
-        self.fullsize_map_image
-            .read()
-            .await
-            .create_snapshot(Path::new(&self.base_path).join(SNAPSHOT_FULL_PATH))?;
+        let fullsize = self.fullsize_map_image.read().await;
+        let path = Path::new(&self.base_path).join(SNAPSHOT_FULL_PATH);
+        IMAGING_POOL.run("full_snapshot", || fullsize.create_snapshot(path)).await?;
         info!(
             "Exported Full-View PNG in {}s!",
             (Utc::now() - start_time).num_seconds()
@@ -396,7 +780,40 @@ impl CameraController {
         Ok(())
     }
 
-    /// Exports a part of the thumbnail map as a PNG.
+    /// Writes all pending changes of the memory-mapped fullsize map back to its file.
+    ///
+    /// # Returns
+    ///
+    /// A result indicating the success or failure of the operation.
+    pub(crate) async fn flush_map(&self) -> Result<(), ImagingError> {
+        self.fullsize_map_image.read().await.flush()
+    }
+
+    /// Verifies the next `max_tiles` tiles of the fullsize map against their checksums and
+    /// quarantines corrupted ones, see [`FullsizeMapImage::verify_integrity`].
+    ///
+    /// # Arguments
+    ///
+    /// * `max_tiles` - The maximum number of tiles to verify.
+    ///
+    /// # Returns
+    ///
+    /// The regions quarantined by this call and by the verification at startup.
+    pub(crate) async fn scrub_map(&self, max_tiles: usize) -> Vec<(Vec2D<u32>, Vec2D<u32>)> {
+        let mut regions = std::mem::take(&mut *self.quarantined.lock().await);
+        regions.extend(self.fullsize_map_image.write().await.verify_integrity(max_tiles));
+        regions
+    }
+
+    /// Returns the configured codec for images that stay within MELVIN and the console.
+    pub(crate) fn internal_codec() -> ImageCodec { MissionConfig::get().runtime.internal_img_codec }
+
+    /// Returns the configured codec for objective images uploaded to the DRS.
+    pub(crate) fn objective_codec() -> ImageCodec {
+        MissionConfig::get().runtime.objective_img_codec
+    }
+
+    /// Exports a part of the thumbnail map with the internal codec.
     ///
     /// # Arguments
     ///
@@ -405,29 +822,60 @@ impl CameraController {
     ///
     /// # Returns
     ///
-    /// A result containing the extracted PNG image data or an error.
-    pub(crate) async fn export_thumbnail_png(
+    /// A result containing the extracted image data or an error.
+    pub(crate) async fn export_thumbnail(
         &self,
         offset: Vec2D<u32>,
         angle: CameraAngle,
-    ) -> Result<EncodedImageExtract, Box<dyn std::error::Error>> {
+    ) -> Result<EncodedImageExtract, ImagingError> {
         let size =
             u32::from(angle.get_square_side_length()) / ThumbnailMapImage::THUMBNAIL_SCALE_FACTOR;
-        self.thumbnail_map_image.read().await.export_area_as_png(
-            offset / ThumbnailMapImage::THUMBNAIL_SCALE_FACTOR,
-            Vec2D::new(size, size),
-        )
+        let thumbnail = self.thumbnail_map_image.read().await;
+        IMAGING_POOL
+            .run("thumbnail_encode", || {
+                thumbnail.export_area_as(
+                    offset / ThumbnailMapImage::THUMBNAIL_SCALE_FACTOR,
+                    Vec2D::new(size, size),
+                    Self::internal_codec(),
+                )
+            })
+            .await
     }
 
-    /// Exports the entire map thumbnail as a PNG.
+    /// Exports a tile of the full-size map pyramid with the internal codec.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The zoom level, column and row of the tile.
     ///
     /// # Returns
     ///
-    /// A result containing the extracted PNG image data or an error.
-    pub(crate) async fn export_full_thumbnail_png(
+    /// A result containing the extracted tile with the tile grid of its zoom level, `None` if
+    /// the tile lies outside of the pyramid, or an error.
+    pub(crate) async fn export_map_tile(
         &self,
-    ) -> Result<EncodedImageExtract, Box<dyn std::error::Error>> {
-        self.thumbnail_map_image.read().await.export_as_png()
+        id: TileId,
+    ) -> Result<(Option<EncodedImageExtract>, Vec2D<u32>), ImagingError> {
+        let fullsize = self.fullsize_map_image.read().await;
+        let mut tile_pyramid = self.tile_pyramid.lock().await;
+        IMAGING_POOL
+            .run("map_tile", || {
+                let grid = tile_pyramid.grid(id.zoom);
+                tile_pyramid.tile(&*fullsize, id, Self::internal_codec()).map(|t| (t, grid))
+            })
+            .await
+    }
+
+    /// Exports the entire map thumbnail with the internal codec.
+    ///
+    /// # Returns
+    ///
+    /// A result containing the extracted image data or an error.
+    pub(crate) async fn export_full_thumbnail(
+        &self,
+    ) -> Result<EncodedImageExtract, ImagingError> {
+        let thumbnail = self.thumbnail_map_image.read().await;
+        IMAGING_POOL.run("thumbnail_encode", || thumbnail.export_as(Self::internal_codec())).await
     }
 
     /// Compares the thumbnail map with its saved snapshot.
@@ -437,24 +885,30 @@ impl CameraController {
     /// A result containing the difference as an encoded PNG image or an error.
     pub(crate) async fn diff_thumb_snapshot(
         &self,
-    ) -> Result<EncodedImageExtract, Box<dyn std::error::Error>> {
+    ) -> Result<EncodedImageExtract, ImagingError> {
         self.thumbnail_map_image
             .read()
             .await
-            .diff_with_snapshot(Path::new(&self.base_path).join(SNAPSHOT_THUMBNAIL_PATH))
+            .diff_with_snapshot(
+                Path::new(&self.base_path).join(SNAPSHOT_THUMBNAIL_PATH),
+                Self::internal_codec(),
+            )
             .await
     }
 
     /// Executes a series of image acquisitions, processes them, and updates the associated map buffers.
     ///
+    /// The lens is selected by the [`LensPolicy`] for the current velocity before the first image.
+    /// Transient capture failures are retried after the backoff of the capture health, while
+    /// local faults like a corrupted map buffer end the cycle.
+    ///
     /// # Arguments
     ///
     /// * `f_cont_lock` - Lock-protected flight computer controlling the acquisition cycle.
     /// * `console_messenger` - Used for sending notifications during processing.
     /// * `(end_time, last_img_kill)` - The end time for the cycle and a notify object to terminate the process prematurely.
-    /// * `image_max_dt` - Maximum allowed interval between consecutive images.
-    /// * `lens` - The camera angle and field of view.
-    /// * `start_index` - The starting index for tracking image acquisitions.
+    /// * `planner` - Provides the interval between consecutive images and the starting index
+    ///   for tracking image acquisitions.
     ///
     /// # Returns
     ///
@@ -465,33 +919,55 @@ impl CameraController {
         f_cont_lock: Arc<RwLock<FlightComputer>>,
         console_messenger: Arc<ConsoleMessenger>,
         (end_time, kill): (DateTime<Utc>, oneshot::Receiver<PeriodicImagingEndSignal>),
-        image_max_dt: I32F32,
-        start_index: usize,
+        planner: CoveragePlanner,
     ) -> Vec<(isize, isize)> {
         log!(
             "Starting acquisition cycle. Deadline: {}",
             end_time.format("%H:%M:%S")
         );
-        let lens = f_cont_lock.read().await.current_angle();
+        let lens = LensPolicy::for_mapping(f_cont_lock.read().await.current_vel());
+        FlightComputer::set_angle_wait(Arc::clone(&f_cont_lock), lens).await;
         let mut kill_box = Box::pin(kill);
         let mut last_image_flag = false;
 
         let pic_count_lock = Arc::new(Mutex::new(0));
-        let mut state = CycleState::init_cycle(image_max_dt, start_index as isize);
+        let mut state = CycleState::init_cycle(planner.img_max_dt(), planner.start_i() as isize);
 
         loop {
             let (img_t, offset) =
                 Self::exec_map_capture(self, &f_cont_lock, &pic_count_lock, lens).await;
 
-            let mut next_img_due = Self::get_next_map_img(image_max_dt, end_time);
-            if let Some(off) = offset {
-                console_messenger.send_thumbnail(off, lens);
-                state.update_success(img_t);
-            } else {
-                state.update_failed(img_t);
-                error!("Rescheduling failed picture immediately!");
-                next_img_due = Utc::now() + TimeDelta::seconds(1);
-            }
+            let img_dt = planner.next_img_dt(img_t);
+            let mut next_img_due = Self::get_next_map_img(img_dt, end_time);
+            let mut health = self.capture_health.lock().await;
+            let transition = match offset {
+                Ok(off) => {
+                    if !self.has_deferred_thumbnails().await {
+                        console_messenger.send_thumbnail(off, lens);
+                    }
+                    state.update_success(img_t);
+                    self.capture_log.lock().await.record(img_t);
+                    health.record_success(img_t)
+                }
+                Err(e) if e.is_transient() => {
+                    state.update_failed(img_t);
+                    let transition = health.record_failure(img_t);
+                    let backoff = health.backoff();
+                    error!("Rescheduling failed picture in {}s!", backoff.num_seconds());
+                    next_img_due = Utc::now() + backoff;
+                    transition
+                }
+                Err(e) => {
+                    // Local faults persist, retrying would only keep writing to a broken map
+                    state.update_failed(img_t);
+                    let msg = format!("Map capture failed permanently, ending cycle: {e}");
+                    error!("{msg}");
+                    console_messenger.send_alert(msg);
+                    return state.finish();
+                }
+            };
+            drop(health);
+            Self::handle_capture_transition(transition, &console_messenger);
 
             if last_image_flag {
                 return state.finish();
@@ -517,12 +993,42 @@ impl CameraController {
         }
     }
 
+    /// Alerts the operator console about a degradation or recovery of map captures.
+    ///
+    /// # Arguments
+    /// * `transition` - The result of recording the last capture attempt.
+    /// * `console_messenger` - Used for sending the alert.
+    fn handle_capture_transition(
+        transition: CaptureTransition,
+        console_messenger: &ConsoleMessenger,
+    ) {
+        match transition {
+            CaptureTransition::None => {}
+            CaptureTransition::Degraded => {
+                let msg = "Map captures keep failing. Imaging degraded, preferring Charge.";
+                error!("{msg}");
+                console_messenger.send_alert(msg.to_string());
+            }
+            CaptureTransition::Recovered(outage) => {
+                let msg = format!(
+                    "Map captures recovered after {}s and {} failures. Scheduling catch-up coverage.",
+                    (outage.end - outage.start).num_seconds(),
+                    outage.failures
+                );
+                info!("{msg}");
+                console_messenger.send_alert(msg);
+                outage.dump_json();
+            }
+        }
+    }
+
     /// Executes a series of image acquisitions, processes them, and updates an associated zoned objective buffer.
     ///
     /// # Arguments
     /// * `f_cont_lock` - Lock-protected flight computer controlling the acquisition cycle.
     /// * `deadline` - The end time for the cycle.
-    /// * `zoned_objective_image_buffer` - An optional mutable reference to an `OffsetZonedObjectiveImage`
+    /// * `zoned_objective_image_buffer` - An optional mutable reference to an `OffsetZonedObjectiveImage`,
+    ///   an existing buffer is kept so that further passes complete the same mosaic.
     /// * `offset` - The offset of the buffer in the global map buffer.
     /// * `dimensions` - The dimensions of the zoned objective.
     pub async fn execute_zo_target_cycle(
@@ -537,14 +1043,17 @@ impl CameraController {
             "Starting acquisition cycle for objective. Deadline {}!",
             deadline.format("%H:%M:%S")
         );
-        zoned_objective_image_buffer.replace(OffsetZonedObjectiveImage::new(offset, dimensions));
+        zoned_objective_image_buffer
+            .get_or_insert_with(|| OffsetZonedObjectiveImage::new(offset, dimensions));
         let lens = f_cont_lock.read().await.current_angle();
         let mut pics = 0;
         let deadline_cont = deadline - Utc::now() > TimeDelta::seconds(20);
         let step_print = if deadline_cont { 20 } else { 2 };
+        let mut next_img_due = Utc::now();
         loop {
-            let next_img_due = Utc::now() + Self::ZO_IMG_ACQ_DELAY;
             let img_init_timestamp = Utc::now();
+            // Keep the burst on its planned millisecond grid instead of drifting with each capture
+            next_img_due = (next_img_due + Self::ZO_IMG_ACQ_DELAY).max(img_init_timestamp);
             match self
                 .shoot_image_to_zo_buffer(
                     Arc::clone(&f_cont_lock),
@@ -573,6 +1082,8 @@ impl CameraController {
 
     /// Helper method returning the timestamp of the next image
     ///
+    /// The interval is clamped to the imaging cadence bounds of the [`MissionConfig`].
+    ///
     /// # Arguments
     /// * `img_max_dt`: An `I32F32` resembling the maximum number of seconds between consecutive images in mapping.
     /// * `end_time`: The deadline as a `DateTime<Utc>`
@@ -580,7 +1091,12 @@ impl CameraController {
     /// # Returns
     /// The next image timestamp as an `DateTime<Utc>`
     fn get_next_map_img(img_max_dt: I32F32, end_time: DateTime<Utc>) -> DateTime<Utc> {
-        let next_max_dt = Utc::now() + TimeDelta::seconds(img_max_dt.to_num::<i64>());
+        let config = MissionConfig::get();
+        let cadence = &config.runtime;
+        let img_dt = img_max_dt
+            .to_num::<i64>()
+            .clamp(cadence.img_min_dt_secs.into(), cadence.img_max_dt_secs.into());
+        let next_max_dt = Utc::now() + TimeDelta::seconds(img_dt);
         if next_max_dt > end_time { end_time - Self::LAST_IMG_END_DELAY } else { next_max_dt }
     }
 
@@ -594,13 +1110,13 @@ impl CameraController {
     /// # Returns
     /// A tuple containing:
     ///   - The UTC timestamp when the image was taken
-    ///   - The `Vec2D<i32>` offset in the global map image buffer
+    ///   - The `Vec2D<u32>` offset in the global map image buffer or the [`ImagingError`]
     async fn exec_map_capture(
         self: &Arc<Self>,
         f_cont: &Arc<RwLock<FlightComputer>>,
         p_c: &Arc<Mutex<i32>>,
         lens: CameraAngle,
-    ) -> (DateTime<Utc>, Option<Vec2D<u32>>) {
+    ) -> (DateTime<Utc>, Result<Vec2D<u32>, ImagingError>) {
         let f_cont_clone = Arc::clone(f_cont);
         let p_c_clone = Arc::clone(p_c);
         let self_clone = Arc::clone(self);
@@ -616,16 +1132,16 @@ impl CameraController {
                     };
                     let s = (Utc::now() - img_init_timestamp).num_seconds();
                     info!("Took {pic_num:02}. picture. Processed for {s}s. Position was {pos}");
-                    Some(offset)
+                    Ok(offset)
                 }
                 Err(e) => {
                     error!("Couldn't take picture: {e}");
-                    None
+                    Err(e)
                 }
             }
         });
 
-        let res = img_handle.await.ok().flatten();
+        let res = img_handle.await.unwrap_or_else(|e| Err(ImagingError::Aborted(e.to_string())));
         (img_init_timestamp, res)
     }
 }
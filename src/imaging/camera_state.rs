@@ -14,7 +14,10 @@ use rand::prelude::Rng;
 ///
 /// These angles are associated with a specific square side length
 /// for image processing purposes, available in a pre-computed lookup table.
-#[derive(Debug, Display, PartialEq, Eq, Clone, Copy, Hash, EnumIter)]
+#[derive(
+    Debug, Display, PartialEq, Eq, Clone, Copy, Hash, EnumIter, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
 pub enum CameraAngle {
     Narrow,
     Normal,
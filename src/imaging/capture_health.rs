@@ -0,0 +1,139 @@
+use crate::util::{EVENT_BUS, ImagingEvent, logger::JsonDump};
+use chrono::{DateTime, TimeDelta, Utc};
+use tokio::sync::watch;
+
+/// A period during which the backend persistently failed to deliver images.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CaptureOutage {
+    /// The time of the first failed capture.
+    pub start: DateTime<Utc>,
+    /// The time of the first successful capture after the outage.
+    pub end: DateTime<Utc>,
+    /// The number of failed captures.
+    pub failures: u32,
+}
+
+impl JsonDump for CaptureOutage {
+    /// Returns the file name for the JSON dump of the outage.
+    fn file_name(&self) -> String { format!("outage_{}", self.start.timestamp()) }
+
+    /// Returns the directory name for the outage JSON files.
+    fn dir_name(&self) -> &'static str { "capture_outages" }
+}
+
+/// The result of recording a capture attempt with [`CaptureHealth`].
+#[derive(Debug)]
+pub enum CaptureTransition {
+    /// Nothing notable changed.
+    None,
+    /// Captures have been failing for too long, imaging is now degraded.
+    Degraded,
+    /// Captures succeed again after a degradation.
+    Recovered(CaptureOutage),
+}
+
+/// Tracks consecutive failures of map captures to back off and degrade gracefully when the
+/// backend persistently fails to deliver images.
+///
+/// The degradation state is published through a watch channel, so that the mode control
+/// can prefer charging while degraded and re-plan once captures succeed again.
+pub struct CaptureHealth {
+    /// The number of consecutive failed captures.
+    failures: u32,
+    /// The time of the first failed capture in the current failure streak.
+    first_failure: Option<DateTime<Utc>>,
+    /// Watch sender publishing `true` while imaging is degraded.
+    degraded: watch::Sender<bool>,
+}
+
+impl CaptureHealth {
+    /// The delay before retrying after the first failure.
+    const BASE_BACKOFF: TimeDelta = TimeDelta::seconds(1);
+    /// The maximum delay between two retries.
+    const MAX_BACKOFF: TimeDelta = TimeDelta::seconds(60);
+    /// The duration of a failure streak after which imaging counts as degraded.
+    const DEGRADE_AFTER: TimeDelta = TimeDelta::minutes(3);
+
+    /// Creates a new, healthy [`CaptureHealth`].
+    pub fn new() -> Self {
+        Self { failures: 0, first_failure: None, degraded: watch::Sender::new(false) }
+    }
+
+    /// Returns `true` while imaging is degraded.
+    pub fn is_degraded(&self) -> bool { *self.degraded.borrow() }
+
+    /// Subscribes to changes of the degradation state.
+    pub fn subscribe(&self) -> watch::Receiver<bool> { self.degraded.subscribe() }
+
+    /// Returns the exponential backoff delay for the current failure streak.
+    pub fn backoff(&self) -> TimeDelta {
+        let exp = self.failures.saturating_sub(1).min(16);
+        (Self::BASE_BACKOFF * 2i32.pow(exp)).min(Self::MAX_BACKOFF)
+    }
+
+    /// Records a failed capture.
+    ///
+    /// # Arguments
+    /// * `t` – The time of the capture attempt.
+    ///
+    /// # Returns
+    /// * `CaptureTransition::Degraded` if this failure made imaging degraded.
+    pub fn record_failure(&mut self, t: DateTime<Utc>) -> CaptureTransition {
+        self.failures += 1;
+        let first = *self.first_failure.get_or_insert(t);
+        if t - first >= Self::DEGRADE_AFTER && !self.is_degraded() {
+            self.degraded.send_replace(true);
+            EVENT_BUS.publish(ImagingEvent::CaptureDegraded);
+            CaptureTransition::Degraded
+        } else {
+            CaptureTransition::None
+        }
+    }
+
+    /// Records a successful capture and ends the current failure streak.
+    ///
+    /// # Arguments
+    /// * `t` – The time of the capture.
+    ///
+    /// # Returns
+    /// * `CaptureTransition::Recovered` if imaging was degraded before.
+    pub fn record_success(&mut self, t: DateTime<Utc>) -> CaptureTransition {
+        let failures = std::mem::take(&mut self.failures);
+        let first = self.first_failure.take();
+        match first {
+            Some(start) if self.is_degraded() => {
+                self.degraded.send_replace(false);
+                EVENT_BUS.publish(ImagingEvent::CaptureRecovered);
+                CaptureTransition::Recovered(CaptureOutage { start, end: t, failures })
+            }
+            _ => CaptureTransition::None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_backoff_and_degradation() {
+        let mut health = CaptureHealth::new();
+        let start = Utc::now();
+        let mut t = start;
+        let mut delays = Vec::new();
+        while !matches!(health.record_failure(t), CaptureTransition::Degraded) {
+            delays.push(health.backoff().num_seconds());
+            t += health.backoff();
+        }
+        assert_eq!(delays[..4], [1, 2, 4, 8]);
+        assert_eq!(*delays.last().unwrap(), 60);
+        assert!(health.is_degraded() && t - start >= CaptureHealth::DEGRADE_AFTER);
+
+        let CaptureTransition::Recovered(outage) = health.record_success(t) else {
+            panic!("Expected recovery after degradation");
+        };
+        assert_eq!(outage.start, start);
+        assert!(!health.is_degraded() && health.backoff() == CaptureHealth::BASE_BACKOFF);
+        assert!(matches!(health.record_success(t), CaptureTransition::None));
+    }
+}
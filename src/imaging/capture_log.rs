@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc};
+use std::collections::VecDeque;
+
+/// A bounded log of the times of successful map captures.
+///
+/// The log outlives single acquisition cycles, so that the coverage of captures whose cycle
+/// never reported its imaged ranges can be reconstructed from the orbit model later on.
+#[derive(Debug, Default)]
+pub struct CaptureLog {
+    /// The times of the logged captures, oldest first.
+    captures: VecDeque<DateTime<Utc>>,
+}
+
+impl CaptureLog {
+    /// The maximum number of logged captures, roughly one orbit of continuous mapping.
+    const CAPACITY: usize = 4096;
+
+    /// Records a successful map capture.
+    ///
+    /// # Arguments
+    /// * `t` – The time of the capture.
+    pub fn record(&mut self, t: DateTime<Utc>) {
+        if self.captures.len() == Self::CAPACITY {
+            self.captures.pop_front();
+        }
+        self.captures.push_back(t);
+    }
+
+    /// Returns all logged captures within a time window.
+    ///
+    /// # Arguments
+    /// * `start` – The start of the window.
+    /// * `end` – The inclusive end of the window.
+    pub fn between(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+        self.captures.iter().filter(|t| (start..=end).contains(*t)).copied().collect()
+    }
+}
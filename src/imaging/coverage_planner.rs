@@ -0,0 +1,68 @@
+use crate::flight_control::orbit::ClosedOrbit;
+use crate::util::MissionConfig;
+use chrono::{DateTime, Utc};
+use fixed::types::I32F32;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Adapts the interval between two mapping images to the coverage of the upcoming ground track.
+///
+/// Over fresh terrain the nominal interval of the lens is used, which still yields a gapless
+/// strip. If the ground track ahead of the current footprint is already marked as done in the
+/// [`ClosedOrbit`], the next image is delayed until its footprint reaches the first uncovered
+/// orbit second, saving battery over already-mapped terrain.
+pub(crate) struct CoveragePlanner {
+    /// The lock-protected closed orbit holding the coverage bitmap.
+    c_orbit: Arc<RwLock<ClosedOrbit>>,
+    /// The orbit index at the start of the acquisition cycle.
+    start_i: usize,
+    /// The start time of the acquisition cycle.
+    start_t: DateTime<Utc>,
+    /// The nominal maximum interval between two images for the current lens.
+    img_max_dt: I32F32,
+}
+
+impl CoveragePlanner {
+    /// Creates a new [`CoveragePlanner`] for an acquisition cycle starting now.
+    ///
+    /// # Arguments
+    /// * `c_orbit` – The lock-protected closed orbit holding the coverage bitmap.
+    /// * `start_i` – The orbit index at the start of the cycle.
+    /// * `img_max_dt` – The nominal maximum interval between two images.
+    pub(crate) fn new(
+        c_orbit: Arc<RwLock<ClosedOrbit>>,
+        start_i: usize,
+        img_max_dt: I32F32,
+    ) -> Self {
+        Self { c_orbit, start_i, start_t: Utc::now(), img_max_dt }
+    }
+
+    /// Returns the orbit index at the start of the acquisition cycle.
+    pub(crate) fn start_i(&self) -> usize { self.start_i }
+
+    /// Returns the nominal maximum interval between two images.
+    pub(crate) fn img_max_dt(&self) -> I32F32 { self.img_max_dt }
+
+    /// Returns the interval in seconds until the next image after an image taken at `img_t`.
+    ///
+    /// The footprint of an image reaches half the nominal interval ahead. The interval is
+    /// lengthened by the number of already covered orbit seconds beyond that edge, up to
+    /// `img_covered_max_dt_secs` of the [`MissionConfig`], which disables adaptation if `0`.
+    ///
+    /// # Arguments
+    /// * `img_t` – The time the last image was taken.
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+    pub(crate) fn next_img_dt(&self, img_t: DateTime<Utc>) -> I32F32 {
+        let max_dt = MissionConfig::get().runtime.img_covered_max_dt_secs as usize;
+        let nominal = self.img_max_dt.to_num::<usize>();
+        if max_dt <= nominal {
+            return self.img_max_dt;
+        }
+        let elapsed = (img_t - self.start_t).num_seconds().max(0) as usize;
+        let edge = self.start_i + elapsed + nominal / 2 + 1;
+        // Never delay an image behind a writer of the orbit, fall back to the nominal interval
+        let Ok(c_orbit) = self.c_orbit.try_read() else { return self.img_max_dt };
+        let covered = c_orbit.done_ahead(edge, max_dt - nominal);
+        self.img_max_dt + I32F32::from_num(covered)
+    }
+}
@@ -0,0 +1,108 @@
+use std::{
+    io,
+    ops::Range,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+/// Tracks the progress of a daily map upload.
+///
+/// The snapshot is uploaded in chunks, each confirmed by the DRS before the next one is sent.
+/// The progress is kept across failed attempts, so an interrupted upload resumes with the
+/// first unconfirmed chunk instead of re-sending the whole snapshot. If the snapshot file
+/// changed in the meantime, the upload starts over.
+#[derive(Debug)]
+pub(crate) struct DailyMapUpload {
+    /// The path of the uploaded snapshot.
+    path: PathBuf,
+    /// The size of the snapshot in bytes.
+    total: u64,
+    /// The modification time of the snapshot when the upload was started.
+    modified: Option<SystemTime>,
+    /// The number of bytes confirmed by the DRS.
+    uploaded: u64,
+}
+
+impl DailyMapUpload {
+    /// Starts a new upload of the snapshot at `path`.
+    ///
+    /// # Errors
+    /// Returns an error if the metadata of the snapshot cannot be read.
+    pub(crate) fn new<P: AsRef<Path>>(path: P) -> Result<Self, io::Error> {
+        let meta = std::fs::metadata(path.as_ref())?;
+        Ok(Self {
+            path: path.as_ref().to_path_buf(),
+            total: meta.len(),
+            modified: meta.modified().ok(),
+            uploaded: 0,
+        })
+    }
+
+    /// Returns the path of the uploaded snapshot.
+    pub(crate) fn path(&self) -> &Path { &self.path }
+    /// Returns the size of the snapshot in bytes.
+    pub(crate) fn total(&self) -> u64 { self.total }
+    /// Returns `true` if the whole snapshot was confirmed.
+    pub(crate) fn is_done(&self) -> bool { self.uploaded >= self.total }
+
+    /// Returns the confirmed share of the snapshot between `0.0` and `1.0`.
+    #[allow(clippy::cast_precision_loss)]
+    pub(crate) fn progress(&self) -> f64 {
+        if self.total == 0 { 1.0 } else { self.uploaded as f64 / self.total as f64 }
+    }
+
+    /// Returns `true` if the snapshot file changed since the upload was started.
+    pub(crate) fn is_stale(&self) -> bool {
+        std::fs::metadata(&self.path)
+            .map_or(true, |meta| meta.len() != self.total || meta.modified().ok() != self.modified)
+    }
+
+    /// Returns the byte range of the next chunk to send.
+    ///
+    /// # Arguments
+    /// * `chunk_len` – The maximum chunk size in bytes.
+    ///
+    /// # Returns
+    /// The byte range, or `None` if the upload is done.
+    pub(crate) fn next_chunk(&self, chunk_len: u64) -> Option<Range<u64>> {
+        (!self.is_done() && chunk_len > 0)
+            .then(|| self.uploaded..self.uploaded.saturating_add(chunk_len).min(self.total))
+    }
+
+    /// Marks a chunk as confirmed by the DRS.
+    ///
+    /// # Arguments
+    /// * `range` – The byte range of the confirmed chunk.
+    pub(crate) fn confirm(&mut self, range: &Range<u64>) {
+        if range.start <= self.uploaded {
+            self.uploaded = self.uploaded.max(range.end.min(self.total));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resumed_chunks() {
+        let dir = std::env::temp_dir().join(format!("melvin_daily_map_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("snapshot.bin");
+        std::fs::write(&path, vec![0u8; 10]).unwrap();
+        let mut upload = DailyMapUpload::new(&path).unwrap();
+        let first = upload.next_chunk(4).unwrap();
+        assert_eq!(first, 0..4);
+        upload.confirm(&first);
+        // A failed chunk is not confirmed and thus sent again.
+        assert_eq!(upload.next_chunk(4), Some(4..8));
+        upload.confirm(&(4..8));
+        assert_eq!(upload.next_chunk(4), Some(8..10));
+        upload.confirm(&(8..10));
+        assert!(upload.is_done() && upload.next_chunk(4).is_none());
+        assert!(!upload.is_stale());
+        std::fs::write(&path, vec![0u8; 12]).unwrap();
+        assert!(upload.is_stale());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -79,6 +79,52 @@ impl FileBackedBuffer {
         }
         Ok(FileBackedBuffer { file, length, ptr: ptr.cast::<u8>() })
     }
+
+    /// Maps an existing file copy-on-write, so that the buffer can be analyzed without ever
+    /// modifying or extending the file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the existing file.
+    /// * `length` - The expected size of the file in bytes.
+    ///
+    /// # Returns
+    ///
+    /// The buffer, or an error if the file is missing, has a different size or could not be
+    /// mapped.
+    pub(crate) fn open_read_only<T: AsRef<Path>>(path: T, length: usize) -> std::io::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        if file.metadata()?.len() != length as u64 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("expected a file of {length} bytes"),
+            ));
+        }
+        let ptr = unsafe {
+            libc::mmap(
+                null_mut(),
+                length,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_FILE,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(FileBackedBuffer { file, length, ptr: ptr.cast::<u8>() })
+    }
+
+    /// Synchronously writes all modified pages of the mapped region back to the file.
+    ///
+    /// # Returns
+    ///
+    /// An error message if `msync` failed.
+    pub(crate) fn flush(&self) -> Result<(), &'static str> {
+        let res = unsafe { libc::msync(self.ptr.cast::<c_void>(), self.length, libc::MS_SYNC) };
+        if res == 0 { Ok(()) } else { Err("msync failed") }
+    }
 }
 
 impl Drop for FileBackedBuffer {
@@ -0,0 +1,290 @@
+use crate::util::MissionConfig;
+use image::{
+    DynamicImage, EncodableLayout, ExtendedColorType, ImageBuffer, ImageError, ImageFormat, ImageResult,
+    PixelWithColorType, RgbImage, RgbaImage,
+    codecs::{jpeg::JpegEncoder, png::PngEncoder, qoi::QoiEncoder, webp::WebPEncoder},
+    error::{DecodingError, EncodingError, ImageFormatHint},
+};
+use std::{io::Cursor, ops::Deref};
+use strum_macros::Display as StrumDisplay;
+
+/// Encodings for images stored by MELVIN, sent to the operator console or uploaded as
+/// objective images.
+///
+/// PNG spends most of its time in deflate and dominates the CPU load of the imaging path.
+/// Internal buffers, snapshots and console transfers may therefore use a faster codec, and
+/// objective images may use a more compact one for the constrained downlink. The daily map is
+/// always encoded as PNG. Decoding detects the codec from the data itself, so stored images
+/// remain readable after the configured codec changed.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, StrumDisplay, serde::Serialize, serde::Deserialize,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ImageCodec {
+    /// Deflate-compressed PNG, slow but compact and universally readable.
+    #[default]
+    Png,
+    /// The "Quite OK Image" format, lossless and several times faster than PNG.
+    Qoi,
+    /// Raw pixels compressed with LZ4, the fastest option at a moderate compression ratio.
+    Lz4,
+    /// Lossy JPEG with the configured quality, compact but without alpha channel.
+    Jpeg,
+    /// Lossless WebP, usually smaller than PNG at a similar encoding speed.
+    WebpLossless,
+    /// Lossy WebP with the configured quality, the most compact option.
+    WebpLossy,
+}
+
+impl ImageCodec {
+    /// Magic bytes preceding LZ4-compressed raw images.
+    const LZ4_MAGIC: &'static [u8; 4] = b"MLZ4";
+    /// Length of the LZ4 header: magic, width, height and channel count.
+    const LZ4_HEADER_LEN: usize = 13;
+    /// The first chunk of lossless WebP images, following the 12-byte RIFF header.
+    const WEBP_LOSSLESS_CHUNK: &'static [u8] = b"VP8L";
+
+    /// Returns the file extension of the codec.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Qoi => "qoi",
+            Self::Lz4 => "lz4",
+            Self::Jpeg => "jpg",
+            Self::WebpLossless | Self::WebpLossy => "webp",
+        }
+    }
+
+    /// Returns `true` if the codec discards image information.
+    pub fn is_lossy(self) -> bool { matches!(self, Self::Jpeg | Self::WebpLossy) }
+
+    /// Encodes an image buffer, lossy codecs use the quality configured in the
+    /// [`MissionConfig`].
+    ///
+    /// # Arguments
+    /// * `image` - The image to encode.
+    ///
+    /// # Returns
+    /// The encoded bytes, or an error if the codec does not support the pixel type.
+    pub fn encode<P, C>(self, image: &ImageBuffer<P, C>) -> ImageResult<Vec<u8>>
+    where
+        P: PixelWithColorType,
+        [P::Subpixel]: EncodableLayout,
+        C: Deref<Target = [P::Subpixel]>,
+    {
+        self.encode_with_quality(image, MissionConfig::get().runtime.img_lossy_quality)
+    }
+
+    /// Encodes an image buffer with an explicit quality for lossy codecs.
+    ///
+    /// # Arguments
+    /// * `image` - The image to encode.
+    /// * `quality` - The quality of lossy codecs from `1` to `100`, ignored by lossless ones.
+    ///
+    /// # Returns
+    /// The encoded bytes, or an error if the codec does not support the pixel type.
+    pub fn encode_with_quality<P, C>(
+        self,
+        image: &ImageBuffer<P, C>,
+        quality: u8,
+    ) -> ImageResult<Vec<u8>>
+    where
+        P: PixelWithColorType,
+        [P::Subpixel]: EncodableLayout,
+        C: Deref<Target = [P::Subpixel]>,
+    {
+        let mut writer = Cursor::new(Vec::<u8>::new());
+        match self {
+            Self::Png => image.write_with_encoder(PngEncoder::new(&mut writer))?,
+            Self::Qoi => image.write_with_encoder(QoiEncoder::new(&mut writer))?,
+            Self::Lz4 => {
+                let raw = image.as_raw().as_bytes();
+                let out = writer.get_mut();
+                out.extend_from_slice(Self::LZ4_MAGIC);
+                out.extend_from_slice(&image.width().to_le_bytes());
+                out.extend_from_slice(&image.height().to_le_bytes());
+                out.push(P::CHANNEL_COUNT);
+                out.extend_from_slice(&lz4_flex::compress_prepend_size(raw));
+            }
+            Self::Jpeg => image
+                .write_with_encoder(JpegEncoder::new_with_quality(&mut writer, quality.max(1)))?,
+            Self::WebpLossless => {
+                image.write_with_encoder(WebPEncoder::new_lossless(&mut writer))?;
+            }
+            Self::WebpLossy => {
+                let raw = image.as_raw().as_bytes();
+                let layout = match P::COLOR_TYPE {
+                    ExtendedColorType::Rgb8 => webp::PixelLayout::Rgb,
+                    ExtendedColorType::Rgba8 => webp::PixelLayout::Rgba,
+                    _ => return Err(Self::encoding_error("unsupported pixel type")),
+                };
+                let encoder = webp::Encoder::new(raw, layout, image.width(), image.height());
+                let data = encoder
+                    .encode_simple(false, f32::from(quality.clamp(1, 100)))
+                    .map_err(|e| Self::encoding_error(&format!("{e:?}")))?;
+                writer.get_mut().extend_from_slice(&data);
+            }
+        }
+        Ok(writer.into_inner())
+    }
+
+    /// Detects the codec of encoded image data from its magic bytes.
+    ///
+    /// # Arguments
+    /// * `data` - The encoded image.
+    ///
+    /// # Returns
+    /// The detected codec, or `None` for unknown data.
+    pub fn detect(data: &[u8]) -> Option<Self> {
+        if data.starts_with(Self::LZ4_MAGIC) {
+            return Some(Self::Lz4);
+        }
+        match image::guess_format(data).ok()? {
+            ImageFormat::Png => Some(Self::Png),
+            ImageFormat::Qoi => Some(Self::Qoi),
+            ImageFormat::Jpeg => Some(Self::Jpeg),
+            ImageFormat::WebP if data.get(12..16) == Some(Self::WEBP_LOSSLESS_CHUNK) => {
+                Some(Self::WebpLossless)
+            }
+            ImageFormat::WebP => Some(Self::WebpLossy),
+            _ => None,
+        }
+    }
+
+    /// Decodes image data of any supported codec.
+    ///
+    /// # Arguments
+    /// * `data` - The encoded image.
+    ///
+    /// # Returns
+    /// The decoded image.
+    pub fn decode(data: &[u8]) -> ImageResult<DynamicImage> {
+        match Self::detect(data) {
+            Some(Self::Lz4) => Self::decode_lz4(data),
+            Some(Self::Png) => image::load_from_memory_with_format(data, ImageFormat::Png),
+            Some(Self::Qoi) => image::load_from_memory_with_format(data, ImageFormat::Qoi),
+            Some(Self::Jpeg) => image::load_from_memory_with_format(data, ImageFormat::Jpeg),
+            Some(Self::WebpLossless | Self::WebpLossy) => {
+                image::load_from_memory_with_format(data, ImageFormat::WebP)
+            }
+            None => Err(Self::decoding_error("unknown image codec")),
+        }
+    }
+
+    /// Re-encodes image data of any supported codec.
+    ///
+    /// # Arguments
+    /// * `data` - The encoded image.
+    /// * `target` - The codec to convert to.
+    ///
+    /// # Returns
+    /// The image encoded with `target`, or the unchanged data if it already is.
+    pub fn convert(data: Vec<u8>, target: Self) -> ImageResult<Vec<u8>> {
+        if Self::detect(&data) == Some(target) {
+            return Ok(data);
+        }
+        match Self::decode(&data)? {
+            DynamicImage::ImageRgba8(img) => target.encode(&img),
+            other => target.encode(&other.to_rgb8()),
+        }
+    }
+
+    /// Decodes LZ4-compressed raw pixels.
+    ///
+    /// # Arguments
+    /// * `data` - The encoded image including its header.
+    fn decode_lz4(data: &[u8]) -> ImageResult<DynamicImage> {
+        if data.len() < Self::LZ4_HEADER_LEN {
+            return Err(Self::decoding_error("truncated LZ4 header"));
+        }
+        let width = u32::from_le_bytes(data[4..8].try_into().unwrap());
+        let height = u32::from_le_bytes(data[8..12].try_into().unwrap());
+        let raw = lz4_flex::decompress_size_prepended(&data[Self::LZ4_HEADER_LEN..])
+            .map_err(|e| Self::decoding_error(&e.to_string()))?;
+        let image = match data[12] {
+            3 => RgbImage::from_raw(width, height, raw).map(DynamicImage::ImageRgb8),
+            4 => RgbaImage::from_raw(width, height, raw).map(DynamicImage::ImageRgba8),
+            _ => None,
+        };
+        image.ok_or_else(|| Self::decoding_error("LZ4 payload does not match its header"))
+    }
+
+    /// Builds an [`ImageError`] for malformed data.
+    fn decoding_error(msg: &str) -> ImageError {
+        ImageError::Decoding(DecodingError::new(ImageFormatHint::Name("lz4".into()), msg))
+    }
+
+    /// Builds an [`ImageError`] for a failed lossy WebP encoding.
+    fn encoding_error(msg: &str) -> ImageError {
+        ImageError::Encoding(EncodingError::new(ImageFormatHint::Exact(ImageFormat::WebP), msg))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GenericImageView, Rgb};
+    use std::time::Instant;
+
+    /// Builds a map-like test image with smooth gradients and some noise.
+    fn test_image(size: u32) -> RgbImage {
+        let byte = |v: u32| u8::try_from(v % 256).unwrap();
+        RgbImage::from_fn(size, size, |x, y| {
+            let noise = byte((x * 7 + y * 13) % 5);
+            Rgb([byte(x / 4) + noise, byte(y / 4), byte((x + y) / 8)])
+        })
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let img = test_image(64);
+        let lossless = [ImageCodec::Png, ImageCodec::Qoi, ImageCodec::Lz4, ImageCodec::WebpLossless];
+        for codec in lossless {
+            let data = codec.encode(&img).unwrap();
+            assert_eq!(ImageCodec::detect(&data), Some(codec));
+            assert_eq!(ImageCodec::decode(&data).unwrap().to_rgb8(), img);
+            let png = ImageCodec::convert(data, ImageCodec::Png).unwrap();
+            assert_eq!(ImageCodec::decode(&png).unwrap().to_rgb8(), img);
+        }
+        for codec in [ImageCodec::Jpeg, ImageCodec::WebpLossy] {
+            let coarse = codec.encode_with_quality(&img, 20).unwrap();
+            let fine = codec.encode_with_quality(&img, 95).unwrap();
+            assert_eq!(ImageCodec::detect(&fine), Some(codec));
+            assert!(coarse.len() < fine.len());
+            assert_eq!(ImageCodec::decode(&fine).unwrap().dimensions(), img.dimensions());
+        }
+    }
+
+    /// Compares the codecs on a thumbnail-sized image.
+    ///
+    /// Run with `cargo test --release bench_codecs -- --ignored --nocapture`.
+    #[test]
+    #[ignore = "benchmark"]
+    fn bench_codecs() {
+        let img = test_image(864);
+        let rounds = 20;
+        let codecs = [
+            ImageCodec::Png,
+            ImageCodec::Qoi,
+            ImageCodec::Lz4,
+            ImageCodec::Jpeg,
+            ImageCodec::WebpLossless,
+            ImageCodec::WebpLossy,
+        ];
+        for codec in codecs {
+            let start = Instant::now();
+            let mut data = Vec::new();
+            for _ in 0..rounds {
+                data = codec.encode(&img).unwrap();
+            }
+            let enc = start.elapsed() / rounds;
+            let start = Instant::now();
+            for _ in 0..rounds {
+                ImageCodec::decode(&data).unwrap();
+            }
+            let dec = start.elapsed() / rounds;
+            println!("{codec}: {} bytes, encode {enc:?}, decode {dec:?}", data.len());
+        }
+    }
+}
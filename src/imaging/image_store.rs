@@ -0,0 +1,291 @@
+use crate::util::ImgObjectiveId;
+use crate::{info, warn};
+use std::{
+    ffi::CString,
+    fmt::{Display, Formatter},
+    hash::{DefaultHasher, Hash, Hasher},
+    io::ErrorKind,
+    os::unix::ffi::OsStrExt,
+    path::{Path, PathBuf},
+};
+
+/// An objective image kept in the [`ImageStore`].
+#[derive(Debug, Clone)]
+struct StoredImage {
+    /// The path of the image file.
+    path: PathBuf,
+    /// The objective the image belongs to.
+    objective_id: ImgObjectiveId,
+    /// The size of the image file in bytes.
+    size: u64,
+    /// The hash of the image content.
+    hash: u64,
+}
+
+/// Storage manager of the exported objective images.
+///
+/// Identical exports of an objective are stored once and images are deleted as soon as the
+/// DRS accepted their upload. If the images exceed the disk budget, the oldest ones are
+/// evicted, sparing the images still waiting for an upload retry or being uploaded.
+#[derive(Debug)]
+pub(crate) struct ImageStore {
+    /// The maximum number of bytes the images may occupy.
+    budget: u64,
+    /// The stored images, oldest first.
+    images: Vec<StoredImage>,
+    /// The images whose upload is in flight.
+    uploading: Vec<PathBuf>,
+}
+
+impl ImageStore {
+    /// Opens the store in `dir`, creating the directory and indexing the images already in it.
+    ///
+    /// # Arguments
+    /// * `dir` – The directory holding the objective images.
+    /// * `budget` – The maximum number of bytes the images may occupy.
+    ///
+    /// # Errors
+    /// An error if the directory can not be created or listed.
+    pub(crate) fn open<P: AsRef<Path>>(dir: P, budget: u64) -> std::io::Result<Self> {
+        std::fs::create_dir_all(&dir)?;
+        let mut found = Vec::new();
+        for entry in std::fs::read_dir(&dir)?.flatten() {
+            let path = entry.path();
+            let Some(objective_id) = Self::objective_of(&path) else { continue };
+            let Ok(data) = std::fs::read(&path) else { continue };
+            let modified = entry.metadata().and_then(|m| m.modified()).ok();
+            let (size, hash) = (data.len() as u64, Self::hash(&data));
+            found.push((modified, StoredImage { path, objective_id, size, hash }));
+        }
+        found.sort_by_key(|(modified, _)| *modified);
+        let images = found.into_iter().map(|(_, i)| i).collect();
+        let mut store = Self { budget, images, uploading: Vec::new() };
+        store.evict_to(budget, &[]);
+        Ok(store)
+    }
+
+    /// Parses the objective of an image file named `zo_<id>.<ext>` or `zo_<id>_<n>.<ext>`.
+    fn objective_of(path: &Path) -> Option<ImgObjectiveId> {
+        let stem = path.file_stem()?.to_str()?.strip_prefix("zo_")?;
+        stem.split('_').next()?.parse().ok().map(ImgObjectiveId::new)
+    }
+
+    /// Hashes the content of an image.
+    fn hash(data: &[u8]) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        data.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Returns the number of bytes occupied by the stored images.
+    pub(crate) fn used_bytes(&self) -> u64 { self.images.iter().map(|i| i.size).sum() }
+
+    /// Returns the maximum number of bytes the images may occupy.
+    pub(crate) fn budget(&self) -> u64 { self.budget }
+
+    /// Stores an encoded objective image, unless an identical image of the objective is
+    /// stored already. Afterwards, the oldest images are evicted until the budget is met.
+    ///
+    /// The returned image is about to be uploaded and spared from eviction until
+    /// [`Self::finish_upload`] or [`Self::release`] is called.
+    ///
+    /// # Arguments
+    /// * `objective_id` – The objective the image belongs to.
+    /// * `path` – The path to write a new image to.
+    /// * `data` – The encoded image.
+    /// * `protected` – Images that must not be evicted, e.g. pending uploads.
+    ///
+    /// # Returns
+    /// The path of the stored image, which is the path of the identical image if there is one.
+    ///
+    /// # Errors
+    /// An error if the image could not be written.
+    pub(crate) fn store(
+        &mut self,
+        objective_id: ImgObjectiveId,
+        path: PathBuf,
+        data: &[u8],
+        protected: &[PathBuf],
+    ) -> std::io::Result<PathBuf> {
+        let (size, hash) = (data.len() as u64, Self::hash(data));
+        let duplicate = self.images.iter().find(|i| {
+            i.objective_id == objective_id
+                && i.size == size
+                && i.hash == hash
+                && std::fs::read(&i.path).is_ok_and(|stored| stored == data)
+        });
+        if let Some(image) = duplicate {
+            info!("Objective {objective_id} image is identical to {}.", image.path.display());
+            let path = image.path.clone();
+            self.uploading.push(path.clone());
+            return Ok(path);
+        }
+        std::fs::write(&path, data)?;
+        self.images.push(StoredImage { path: path.clone(), objective_id, size, hash });
+        self.uploading.push(path.clone());
+        self.evict_to(self.budget, protected);
+        Ok(path)
+    }
+
+    /// Ends the upload of an image that is kept, so that it may be evicted again.
+    ///
+    /// # Arguments
+    /// * `path` – The path of the image.
+    pub(crate) fn finish_upload(&mut self, path: &Path) {
+        if let Some(pos) = self.uploading.iter().position(|p| p == path) {
+            self.uploading.remove(pos);
+        }
+    }
+
+    /// Deletes an image whose upload was accepted by the DRS.
+    ///
+    /// # Arguments
+    /// * `path` – The path of the image.
+    pub(crate) fn release(&mut self, path: &Path) {
+        self.finish_upload(path);
+        if let Some(pos) = self.images.iter().position(|i| i.path == path) {
+            Self::remove_file(&self.images.remove(pos).path);
+        }
+    }
+
+    /// Evicts the oldest images until at most `target` bytes are occupied.
+    ///
+    /// # Arguments
+    /// * `target` – The number of bytes the images may occupy afterwards.
+    /// * `protected` – Images that must not be evicted, in addition to the uploading ones.
+    ///
+    /// # Returns
+    /// The number of freed bytes.
+    pub(crate) fn evict_to(&mut self, target: u64, protected: &[PathBuf]) -> u64 {
+        let mut used = self.used_bytes();
+        let mut freed = 0;
+        let mut i = 0;
+        while used > target && i < self.images.len() {
+            let path = &self.images[i].path;
+            if protected.contains(path) || self.uploading.contains(path) {
+                i += 1;
+                continue;
+            }
+            let image = self.images.remove(i);
+            Self::remove_file(&image.path);
+            used -= image.size;
+            freed += image.size;
+        }
+        if freed > 0 {
+            info!("Evicted {freed} bytes of objective images, {used} bytes remain.");
+        }
+        freed
+    }
+
+    /// Deletes an image file, tolerating that it is already gone.
+    fn remove_file(path: &Path) {
+        match std::fs::remove_file(path) {
+            Err(e) if e.kind() != ErrorKind::NotFound => {
+                warn!("Failed to delete objective image {}: {e}.", path.display());
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The disk usage of the imaging pipeline.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct StorageReport {
+    /// The number of bytes occupied by objective images.
+    zo_img_bytes: u64,
+    /// The maximum number of bytes objective images may occupy.
+    zo_img_budget: u64,
+    /// The number of bytes available on the file system of the map buffer, if known.
+    free_bytes: Option<u64>,
+}
+
+impl StorageReport {
+    /// Creates a report of the objective images in `store` and the free space at `base_path`.
+    pub(crate) fn new(store: &ImageStore, base_path: &Path) -> Self {
+        let free_bytes = free_disk_space(base_path)
+            .inspect_err(|e| warn!("Failed to query free disk space: {e}."))
+            .ok();
+        Self { zo_img_bytes: store.used_bytes(), zo_img_budget: store.budget(), free_bytes }
+    }
+
+    /// Returns the number of bytes occupied by objective images.
+    pub(crate) fn zo_img_bytes(&self) -> u64 { self.zo_img_bytes }
+    /// Returns the number of bytes available on the file system, if known.
+    pub(crate) fn free_bytes(&self) -> Option<u64> { self.free_bytes }
+}
+
+impl Display for StorageReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        const MIB: u64 = 1 << 20;
+        write!(
+            f,
+            "objective images use {} of {} MiB, ",
+            self.zo_img_bytes / MIB,
+            self.zo_img_budget / MIB
+        )?;
+        match self.free_bytes {
+            Some(free) => write!(f, "{} MiB free on disk", free / MIB),
+            None => write!(f, "free disk space unknown"),
+        }
+    }
+}
+
+/// Returns the number of bytes available to unprivileged processes on the file system
+/// holding `path`.
+///
+/// # Errors
+/// An error if the file system could not be queried.
+#[allow(clippy::useless_conversion)]
+pub(crate) fn free_disk_space(path: &Path) -> std::io::Result<u64> {
+    let c_path = CString::new(path.as_os_str().as_bytes())?;
+    let mut raw = std::mem::MaybeUninit::<libc::statvfs>::uninit();
+    let res = unsafe { libc::statvfs(c_path.as_ptr(), raw.as_mut_ptr()) };
+    if res != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    let stat = unsafe { raw.assume_init() };
+    Ok(u64::from(stat.f_bavail) * u64::from(stat.f_frsize))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_store() {
+        let dir = std::env::temp_dir().join(format!("melvin_zo_img_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let (a, b) = (ImgObjectiveId::new(1), ImgObjectiveId::new(2));
+
+        let mut store = ImageStore::open(&dir, 10).unwrap();
+        let first = store.store(a, dir.join("zo_1.png"), &[1; 4], &[]).unwrap();
+        let dup = store.store(a, dir.join("zo_1_0.png"), &[1; 4], &[]).unwrap();
+        assert_eq!(dup, first);
+        assert!(!dir.join("zo_1_0.png").exists());
+        let other = store.store(b, dir.join("zo_2.png"), &[1; 4], &[]).unwrap();
+        assert_ne!(other, first);
+        assert_eq!(store.used_bytes(), 8);
+
+        // Images are spared while their upload is in flight
+        assert_eq!(store.evict_to(0, &[]), 0);
+        store.finish_upload(&first);
+        store.finish_upload(&first);
+        store.finish_upload(&other);
+
+        // Exceeding the budget evicts the oldest unprotected image
+        let protected = [first.clone()];
+        let newest = store.store(a, dir.join("zo_1_1.png"), &[2; 4], &protected).unwrap();
+        assert!(first.exists() && newest.exists() && !other.exists());
+        store.finish_upload(&newest);
+
+        // The index is restored from disk
+        let mut store = ImageStore::open(&dir, 10).unwrap();
+        assert_eq!(store.used_bytes(), 8);
+        store.release(&newest);
+        assert!(!newest.exists());
+        assert_eq!(store.evict_to(0, &[]), 4);
+        assert!(!first.exists());
+        assert!(free_disk_space(&dir).unwrap() > 0);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,81 @@
+use crate::http_handler::HTTPError;
+use image::ImageError;
+
+/// Represents possible errors of the camera and map image operations.
+///
+/// The variants separate transient failures, e.g. a dropped connection to the DRS or a
+/// truncated image download, from local faults like an unwritable disk or a corrupted
+/// memory-mapped map buffer, which do not go away by retrying.
+#[derive(Debug)]
+pub enum ImagingError {
+    /// A request to the DRS failed.
+    Http(HTTPError),
+    /// The received image data could not be decoded.
+    Decode(ImageError),
+    /// An image could not be encoded.
+    Encode(ImageError),
+    /// A snapshot or image file could not be read or written.
+    Io(std::io::Error),
+    /// The memory-mapped fullsize map buffer is inconsistent or could not be synchronized.
+    BufferCorruption(&'static str),
+    /// The imaging task was aborted before it finished.
+    Aborted(String),
+}
+
+impl ImagingError {
+    /// Returns `true` if the operation may succeed when retried, i.e. the error was not caused
+    /// by a local fault.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Self::Http(_) | Self::Decode(_) | Self::Aborted(_))
+    }
+}
+
+impl std::fmt::Display for ImagingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Http(e) => write!(f, "DRS request failed: {e}"),
+            Self::Decode(e) => write!(f, "image not decodable: {e}"),
+            Self::Encode(e) => write!(f, "image not encodable: {e}"),
+            Self::Io(e) => write!(f, "image file not accessible: {e}"),
+            Self::BufferCorruption(e) => write!(f, "map buffer corrupted: {e}"),
+            Self::Aborted(e) => write!(f, "imaging task aborted: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ImagingError {}
+
+impl From<HTTPError> for ImagingError {
+    fn from(e: HTTPError) -> Self { Self::Http(e) }
+}
+
+impl From<std::io::Error> for ImagingError {
+    fn from(e: std::io::Error) -> Self { Self::Io(e) }
+}
+
+impl From<ImageError> for ImagingError {
+    /// Maps [`ImageError`]s by their kind, keeping I/O failures apart from codec failures.
+    fn from(e: ImageError) -> Self {
+        match e {
+            ImageError::IoError(io) => Self::Io(io),
+            ImageError::Decoding(_) => Self::Decode(e),
+            _ => Self::Encode(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::error::{DecodingError, ImageFormatHint};
+
+    #[test]
+    fn test_transient_variants() {
+        let truncated = DecodingError::new(ImageFormatHint::Unknown, "truncated");
+        let decode = ImagingError::from(ImageError::Decoding(truncated));
+        let io = ImagingError::from(ImageError::IoError(std::io::Error::other("disk")));
+        assert!(matches!(decode, ImagingError::Decode(_)) && decode.is_transient());
+        assert!(matches!(io, ImagingError::Io(_)) && !io.is_transient());
+        assert!(!ImagingError::BufferCorruption("msync failed").is_transient());
+    }
+}
@@ -0,0 +1,50 @@
+use super::CameraAngle;
+use crate::util::Vec2D;
+use fixed::types::I32F32;
+
+/// Selects the [`CameraAngle`] of mapping tasks from the velocity.
+///
+/// Zoned objectives are always imaged with their required lens, see
+/// [`KnownImgObjective::optic_required`](crate::objective::KnownImgObjective::optic_required).
+///
+/// Narrower lenses image a smaller square at a higher resolution, but only up to a lower
+/// speed limit (see [`CameraAngle::get_max_speed`]).
+pub struct LensPolicy;
+
+impl LensPolicy {
+    /// All lenses, ordered from the highest to the lowest resolution.
+    const BY_RESOLUTION: [CameraAngle; 3] =
+        [CameraAngle::Narrow, CameraAngle::Normal, CameraAngle::Wide];
+
+    /// Returns `true` if images taken with `lens` at `vel` are valid.
+    fn permits(lens: CameraAngle, vel: Vec2D<I32F32>) -> bool { vel.abs() <= lens.get_max_speed() }
+
+    /// Selects the lens for mapping, i.e. the highest resolution lens valid at `vel`.
+    ///
+    /// # Arguments
+    /// * `vel` – The velocity during the acquisition cycle.
+    ///
+    /// # Returns
+    /// * The selected lens, [`CameraAngle::Wide`] if no narrower lens is valid.
+    pub fn for_mapping(vel: Vec2D<I32F32>) -> CameraAngle {
+        Self::BY_RESOLUTION
+            .into_iter()
+            .find(|lens| Self::permits(*lens, vel))
+            .unwrap_or(CameraAngle::Wide)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lens_policy() {
+        let slow = Vec2D::new(I32F32::lit("4.0"), I32F32::lit("7.0"));
+        let medium = Vec2D::new(I32F32::lit("20.0"), I32F32::lit("20.0"));
+        let fast = Vec2D::new(I32F32::lit("60.0"), I32F32::lit("10.0"));
+        assert_eq!(LensPolicy::for_mapping(slow), CameraAngle::Narrow);
+        assert_eq!(LensPolicy::for_mapping(medium), CameraAngle::Normal);
+        assert_eq!(LensPolicy::for_mapping(fast), CameraAngle::Wide);
+    }
+}
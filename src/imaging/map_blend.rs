@@ -0,0 +1,200 @@
+use super::file_based_buffer::FileBackedBuffer;
+use crate::util::Vec2D;
+use image::{GenericImageView, Pixel, Rgb};
+use std::path::Path;
+use strum_macros::Display as StrumDisplay;
+
+/// Strategies for merging a new image into map areas that were imaged before.
+///
+/// Every merged pixel is rated by the sharpness of its surrounding block in the new image. The
+/// rating is kept in a [`ConfidenceLayer`], so that repeated passes over the same area improve
+/// rather than churn the map.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, StrumDisplay, serde::Serialize, serde::Deserialize,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum MapBlendMode {
+    /// Every new image overwrites the map.
+    #[default]
+    LastWins,
+    /// New pixels only replace pixels taken from blurrier images.
+    KeepSharpest,
+    /// New pixels are averaged into the map, weighted by their confidence.
+    Average,
+}
+
+impl MapBlendMode {
+    /// Merges a new pixel into a map pixel.
+    ///
+    /// # Arguments
+    /// * `old` – The map pixel and its confidence, `0` if it was never imaged.
+    /// * `new` – The new pixel and its confidence, at least `1`.
+    ///
+    /// # Returns
+    /// The merged pixel and its confidence, or `None` if the map pixel is kept.
+    pub(crate) fn blend(self, old: (Rgb<u8>, u8), new: (Rgb<u8>, u8)) -> Option<(Rgb<u8>, u8)> {
+        let ((old_px, old_conf), (new_px, new_conf)) = (old, new);
+        match self {
+            _ if old_conf == 0 => Some(new),
+            Self::LastWins => Some(new),
+            Self::KeepSharpest => (new_conf >= old_conf).then_some(new),
+            Self::Average => {
+                let (w_old, w_new) = (u32::from(old_conf), u32::from(new_conf));
+                let w_sum = w_old + w_new;
+                let px = old_px.map2(&new_px, |o, n| {
+                    let sum = u32::from(o) * w_old + u32::from(n) * w_new;
+                    u8::try_from((sum + w_sum / 2) / w_sum).unwrap_or(u8::MAX)
+                });
+                Some((px, old_conf.saturating_add(new_conf)))
+            }
+        }
+    }
+}
+
+/// The sharpness of an image, rated per square block as confidence of its pixels.
+pub(crate) struct BlockSharpness {
+    /// The confidence of each block, row by row.
+    blocks: Vec<u8>,
+    /// The number of blocks per row.
+    blocks_per_row: u32,
+}
+
+impl BlockSharpness {
+    /// The side length of a block in px.
+    const BLOCK_SIZE: u32 = 16;
+    /// The factor scaling the mean absolute Laplacian of a block to its confidence.
+    const LAPLACE_SCALE: f64 = 4.0;
+
+    /// Rates the blocks of `image` by the mean absolute Laplacian of their luma.
+    ///
+    /// # Arguments
+    /// * `image` – The rated image.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    pub(crate) fn rate<I: GenericImageView<Pixel = Rgb<u8>>>(image: &I) -> Self {
+        let (width, height) = image.dimensions();
+        let blocks_per_row = width.div_ceil(Self::BLOCK_SIZE);
+        let rows = height.div_ceil(Self::BLOCK_SIZE);
+        let luma = |x: u32, y: u32| i32::from(image.get_pixel(x, y).to_luma().0[0]);
+        let mut sums = vec![(0u64, 0u64); (blocks_per_row * rows) as usize];
+        for y in 1..height.saturating_sub(1) {
+            for x in 1..width.saturating_sub(1) {
+                let laplace = 4 * luma(x, y)
+                    - luma(x - 1, y)
+                    - luma(x + 1, y)
+                    - luma(x, y - 1)
+                    - luma(x, y + 1);
+                let block = (y / Self::BLOCK_SIZE * blocks_per_row + x / Self::BLOCK_SIZE) as usize;
+                sums[block].0 += u64::from(laplace.unsigned_abs());
+                sums[block].1 += 1;
+            }
+        }
+        let blocks = sums
+            .into_iter()
+            .map(|(sum, n)| {
+                let mean = if n == 0 { 0.0 } else { sum as f64 / n as f64 };
+                1 + (mean * Self::LAPLACE_SCALE).round().min(254.0) as u8
+            })
+            .collect();
+        Self { blocks, blocks_per_row }
+    }
+
+    /// Returns the confidence of the pixel at `(x, y)` of the rated image.
+    pub(crate) fn at(&self, x: u32, y: u32) -> u8 {
+        let block = y / Self::BLOCK_SIZE * self.blocks_per_row + x / Self::BLOCK_SIZE;
+        self.blocks[block as usize]
+    }
+}
+
+/// The per-pixel confidence of the full-size map, backed by a memory-mapped file.
+///
+/// A confidence of `0` marks a pixel that was never imaged, higher values mark pixels taken
+/// from sharper or more images.
+pub(crate) struct ConfidenceLayer {
+    /// The confidence of each pixel, row by row.
+    buffer: FileBackedBuffer,
+    /// The width of the map in px.
+    width: u32,
+}
+
+impl ConfidenceLayer {
+    /// Opens or creates the confidence layer of a map of the given size.
+    ///
+    /// # Arguments
+    /// * `path` – The file backing the layer.
+    /// * `size` – The dimensions of the map.
+    pub(crate) fn open<P: AsRef<Path>>(path: P, size: Vec2D<u32>) -> Result<Self, &'static str> {
+        let len = size.x() as usize * size.y() as usize;
+        Ok(Self { buffer: FileBackedBuffer::open(path, len)?, width: size.x() })
+    }
+
+    /// Opens the existing confidence layer of a map of the given size without modifying it.
+    ///
+    /// # Arguments
+    /// * `path` – The file backing the layer.
+    /// * `size` – The dimensions of the map.
+    pub(crate) fn open_read_only<P: AsRef<Path>>(
+        path: P,
+        size: Vec2D<u32>,
+    ) -> std::io::Result<Self> {
+        let len = size.x() as usize * size.y() as usize;
+        Ok(Self { buffer: FileBackedBuffer::open_read_only(path, len)?, width: size.x() })
+    }
+
+    /// Returns the confidence of the map pixel at `(x, y)`.
+    pub(crate) fn get(&self, x: u32, y: u32) -> u8 {
+        self.buffer[y as usize * self.width as usize + x as usize]
+    }
+
+    /// Sets the confidence of the map pixel at `(x, y)`.
+    pub(crate) fn set(&mut self, x: u32, y: u32, confidence: u8) {
+        self.buffer[y as usize * self.width as usize + x as usize] = confidence;
+    }
+
+    /// Sets the confidence of all map pixels in a rectangle.
+    ///
+    /// # Arguments
+    /// * `offset` – The top-left corner of the rectangle.
+    /// * `size` – The dimensions of the rectangle, which must not cross the map edge.
+    /// * `confidence` – The new confidence of the pixels.
+    pub(crate) fn fill(&mut self, offset: Vec2D<u32>, size: Vec2D<u32>, confidence: u8) {
+        for y in offset.y()..offset.y() + size.y() {
+            let start = y as usize * self.width as usize + offset.x() as usize;
+            self.buffer[start..start + size.x() as usize].fill(confidence);
+        }
+    }
+
+    /// Writes all pending changes of the layer back to its file.
+    pub(crate) fn flush(&self) -> Result<(), &'static str> { self.buffer.flush() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::RgbImage;
+
+    #[test]
+    fn test_blend_modes() {
+        let (old, new) = ((Rgb([100, 0, 50]), 30), (Rgb([200, 90, 50]), 10));
+        assert_eq!(MapBlendMode::LastWins.blend(old, new), Some(new));
+        assert_eq!(MapBlendMode::KeepSharpest.blend(old, new), None);
+        assert_eq!(MapBlendMode::KeepSharpest.blend(new, old), Some(old));
+        assert_eq!(MapBlendMode::Average.blend(old, new), Some((Rgb([125, 23, 50]), 40)));
+        assert_eq!(MapBlendMode::Average.blend((Rgb([0, 0, 0]), 0), new), Some(new));
+        assert_eq!(MapBlendMode::Average.blend((old.0, 250), new).unwrap().1, u8::MAX);
+    }
+
+    #[test]
+    fn test_block_sharpness() {
+        let checker = RgbImage::from_fn(32, 16, |x, y| {
+            if x < 15 && (x + y) % 2 == 0 { Rgb([255, 255, 255]) } else { Rgb([0, 0, 0]) }
+        });
+        let sharpness = BlockSharpness::rate(&checker);
+        assert_eq!(sharpness.at(3, 3), u8::MAX);
+        assert_eq!(sharpness.at(20, 3), 1);
+    }
+}
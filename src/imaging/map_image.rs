@@ -1,13 +1,20 @@
-use super::{file_based_buffer::FileBackedBuffer, sub_buffer::SubBuffer};
-use crate::util::{MapSize, Vec2D};
+use super::{
+    ImagingError,
+    file_based_buffer::FileBackedBuffer,
+    image_codec::ImageCodec,
+    map_blend::{BlockSharpness, ConfidenceLayer, MapBlendMode},
+    map_integrity::MapIntegrity,
+    sub_buffer::{SubBuffer, split_at_seam},
+};
+use crate::util::{MapSize, MissionConfig, Vec2D};
 use image::{
-    DynamicImage, EncodableLayout, GenericImage, GenericImageView, ImageBuffer, Pixel,
+    EncodableLayout, GenericImage, GenericImageView, ImageBuffer, Pixel,
     PixelWithColorType, Rgb, RgbImage,
-    codecs::png::{CompressionType, FilterType, PngDecoder, PngEncoder},
+    codecs::png::{CompressionType, FilterType, PngEncoder},
     imageops,
 };
 use std::{
-    io::{BufReader, Cursor},
+    io::Cursor,
     ops::{Deref, DerefMut},
     path::Path,
 };
@@ -24,6 +31,8 @@ pub(crate) struct EncodedImageExtract {
     pub(crate) size: Vec2D<u32>,
     /// The encoded image data as a vector of bytes.
     pub(crate) data: Vec<u8>,
+    /// The codec of `data`.
+    pub(crate) codec: ImageCodec,
 }
 
 /// Trait representing operations for working with map images.
@@ -74,50 +83,71 @@ pub(crate) trait MapImage {
     /// A reference to the image buffer.
     fn buffer(&self) -> &ImageBuffer<Self::Pixel, Self::Container>;
 
-    /// Exports the entire image buffer as a PNG.
+    /// Exports the entire image buffer with the given codec.
+    ///
+    /// This method encodes the image and returns the encoded byte array along with metadata
+    /// about the image. The encoded data is stored in an `EncodedImageExtract` struct that
+    /// contains the image's offset, size, codec and encoded data.
     ///
-    /// This method encodes the image as a PNG and returns the encoded byte array along
-    /// with metadata about the image. The encoded data is stored in an `EncodedImageExtract`
-    /// struct that contains the image's offset, size, and encoded data.
+    /// # Arguments
+    /// * `codec` - The codec used to encode the image.
     ///
     /// # Returns
     /// An `EncodedImageExtract` containing the offset, size, and encoded image data.
     ///
     /// # Errors
-    /// Returns an error if the PNG encoding process fails.
-    fn export_as_png(&self) -> Result<EncodedImageExtract, Box<dyn std::error::Error>>
-    where [<Self::Pixel as Pixel>::Subpixel]: EncodableLayout {
-        let mut writer = Cursor::new(Vec::<u8>::new());
+    /// Returns an error if the encoding process fails.
+    fn export_as(
+        &self,
+        codec: ImageCodec,
+    ) -> Result<EncodedImageExtract, ImagingError>
+    where
+        [<Self::Pixel as Pixel>::Subpixel]: EncodableLayout,
+    {
         let buffer = self.buffer();
-        buffer.write_with_encoder(PngEncoder::new(&mut writer))?;
         Ok(EncodedImageExtract {
             offset: Vec2D::new(0, 0),
             size: Vec2D::new(buffer.width(), buffer.height()),
-            data: writer.into_inner(),
+            data: codec.encode(buffer)?,
+            codec,
         })
     }
 
-    /// Exports a specific sub-region of the image as a PNG.
+    /// Exports the entire image buffer as a PNG, as required by the DRS.
+    ///
+    /// # Returns
+    /// An `EncodedImageExtract` containing the offset, size, and encoded image data.
+    ///
+    /// # Errors
+    /// Returns an error if the PNG encoding process fails.
+    fn export_as_png(&self) -> Result<EncodedImageExtract, ImagingError>
+    where [<Self::Pixel as Pixel>::Subpixel]: EncodableLayout {
+        self.export_as(ImageCodec::Png)
+    }
+
+    /// Exports a specific sub-region of the image with the given codec.
     ///
-    /// This method extracts the specified sub-region of the image, encodes it as a PNG,
+    /// This method extracts the specified sub-region of the image, encodes it,
     /// and returns it as an `EncodedImageExtract`. The sub-region to be extracted is defined
     /// by the provided `offset` and `size`.
     ///
     /// # Arguments
     /// * `offset` - The top-left corner of the region to export.
     /// * `size` - The dimensions of the region to export, specified as a width and height.
+    /// * `codec` - The codec used to encode the region.
     ///
     /// # Returns
     /// An `EncodedImageExtract` containing the offset, size, and encoded image data of the sub-region.
     ///
     /// # Errors
-    /// Returns an error if the PNG encoding process fails.
+    /// Returns an error if the encoding process fails.
     #[allow(clippy::cast_sign_loss)]
-    fn export_area_as_png(
+    fn export_area_as(
         &self,
         offset: Vec2D<u32>,
         size: Vec2D<u32>,
-    ) -> Result<EncodedImageExtract, Box<dyn std::error::Error>>
+        codec: ImageCodec,
+    ) -> Result<EncodedImageExtract, ImagingError>
     where
         [<<Self::ViewSubBuffer as GenericImageView>::Pixel as Pixel>::Subpixel]: EncodableLayout,
     {
@@ -127,10 +157,32 @@ pub(crate) trait MapImage {
             <Self::ViewSubBuffer as GenericImageView>::Pixel,
             Vec<<<Self::ViewSubBuffer as GenericImageView>::Pixel as Pixel>::Subpixel>,
         >::new(size.x(), size.y());
-        area_image.copy_from(&area_view, 0, 0).unwrap();
-        let mut writer = Cursor::new(Vec::<u8>::new());
-        area_image.write_with_encoder(PngEncoder::new(&mut writer))?;
-        Ok(EncodedImageExtract { offset, size, data: writer.into_inner() })
+        area_image
+            .copy_from(&area_view, 0, 0)
+            .map_err(|_| ImagingError::BufferCorruption("exported area out of bounds"))?;
+        Ok(EncodedImageExtract { offset, size, data: codec.encode(&area_image)?, codec })
+    }
+
+    /// Exports a specific sub-region of the image as a PNG, as required by the DRS.
+    ///
+    /// # Arguments
+    /// * `offset` - The top-left corner of the region to export.
+    /// * `size` - The dimensions of the region to export, specified as a width and height.
+    ///
+    /// # Returns
+    /// An `EncodedImageExtract` containing the offset, size, and encoded image data of the sub-region.
+    ///
+    /// # Errors
+    /// Returns an error if the PNG encoding process fails.
+    fn export_area_as_png(
+        &self,
+        offset: Vec2D<u32>,
+        size: Vec2D<u32>,
+    ) -> Result<EncodedImageExtract, ImagingError>
+    where
+        [<<Self::ViewSubBuffer as GenericImageView>::Pixel as Pixel>::Subpixel]: EncodableLayout,
+    {
+        self.export_area_as(offset, size, ImageCodec::Png)
     }
 
     /// Saves the current image buffer as a snapshot in PNG format.
@@ -143,26 +195,95 @@ pub(crate) trait MapImage {
     /// # Returns
     /// Returns `Ok(())` if the save operation is successful.
     /// Returns an error if the save process fails.
-    fn create_snapshot<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn std::error::Error>>
+    fn create_snapshot<P: AsRef<Path>>(&self, path: P) -> Result<(), ImagingError>
     where [<Self::Pixel as Pixel>::Subpixel]: EncodableLayout {
         self.buffer().save(path)?;
         Ok(())
     }
 
+    /// Saves the current image buffer as a snapshot encoded with the given codec.
+    ///
+    /// # Arguments
+    /// * `path` - The file path where the snapshot should be saved.
+    /// * `codec` - The codec used to encode the snapshot.
+    ///
+    /// # Returns
+    /// Returns `Ok(())` if the save operation is successful.
+    /// Returns an error if the encoding or writing fails.
+    fn create_snapshot_as<P: AsRef<Path>>(
+        &self,
+        path: P,
+        codec: ImageCodec,
+    ) -> Result<(), ImagingError>
+    where
+        [<Self::Pixel as Pixel>::Subpixel]: EncodableLayout,
+    {
+        std::fs::write(path, codec.encode(self.buffer())?)?;
+        Ok(())
+    }
+
     /// Updates a specific sub-region of the image with the given data.
     ///
     /// This method copies the content of `image` into the corresponding sub-region of the current
-    /// image buffer, starting from the specified `offset`.
+    /// image buffer, starting from the specified `offset`. Regions crossing the x or y seam of
+    /// the buffer are split into up to four contiguous parts, which are merged separately.
     ///
     /// # Arguments
     /// * `offset` - The top-left corner of the target sub-region to update.
     /// * `image` - The new image data to copy into the target sub-region.
+    ///
+    /// # Errors
+    /// Returns [`ImagingError::BufferCorruption`] if a part does not fit into the buffer.
     fn update_area<I: GenericImageView<Pixel = Self::Pixel>>(
         &mut self,
         offset: Vec2D<u32>,
         image: &I,
-    ) {
-        self.mut_vec_view(offset).copy_from(image, 0, 0).unwrap();
+    ) -> Result<(), ImagingError> {
+        let (width, height) = self.buffer().dimensions();
+        let image_size = Vec2D::new(image.width(), image.height());
+        for rect in split_at_seam(offset, image_size, Vec2D::new(width, height)) {
+            let part = image.view(rect.src.x(), rect.src.y(), rect.size.x(), rect.size.y());
+            self.mut_vec_view(rect.dst)
+                .copy_from(&*part, 0, 0)
+                .map_err(|_| ImagingError::BufferCorruption("updated area out of bounds"))?;
+        }
+        Ok(())
+    }
+
+    /// Scores how well `image` matches the current content of the buffer at `offset`.
+    ///
+    /// Regions crossing the x or y seam of the buffer are split into up to four contiguous
+    /// parts, so that every pixel of `image` is compared to exactly one buffer pixel.
+    ///
+    /// # Arguments
+    /// * `offset` - The top-left corner of the compared sub-region.
+    /// * `image` - The image data to compare.
+    ///
+    /// # Returns
+    /// The negated number of differing pixels, i.e. `0` for a perfect match.
+    #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
+    fn match_score<I: GenericImageView<Pixel = Self::Pixel>>(
+        &self,
+        offset: Vec2D<u32>,
+        image: &I,
+    ) -> i32
+    where
+        Self::Pixel: PartialEq,
+    {
+        let buffer = self.buffer();
+        let image_size = Vec2D::new(image.width(), image.height());
+        let buffer_size = Vec2D::new(buffer.width(), buffer.height());
+        let mut differing = 0;
+        for rect in split_at_seam(offset, image_size, buffer_size) {
+            let existing = buffer.view(rect.dst.x(), rect.dst.y(), rect.size.x(), rect.size.y());
+            let new = image.view(rect.src.x(), rect.src.y(), rect.size.x(), rect.size.y());
+            differing += existing
+                .pixels()
+                .zip(new.pixels())
+                .filter(|((_, _, existing_px), (_, _, new_px))| existing_px != new_px)
+                .count();
+        }
+        -(differing as i32)
     }
 }
 
@@ -171,9 +292,13 @@ pub(crate) trait MapImage {
 /// This struct manages the full-sized map image which includes
 /// a coverage bitmap and an image buffer backed by a memory-mapped file.
 /// It provides functionality to open and handle the image buffer efficiently.
-pub(crate) struct FullsizeMapImage {
+pub struct FullsizeMapImage {
     /// The image buffer containing the pixel data, backed by a file.
     image_buffer: ImageBuffer<Rgb<u8>, FileBackedBuffer>,
+    /// The confidence of every map pixel, backed by a file next to the image buffer.
+    confidence: ConfidenceLayer,
+    /// The tile checksums of the image buffer, persisted next to it.
+    integrity: MapIntegrity,
 }
 
 pub(crate) struct OffsetZonedObjectiveImage {
@@ -217,13 +342,19 @@ impl OffsetZonedObjectiveImage {
         }
     }
 
-    fn export_as_png(&self) -> Result<EncodedImageExtract, Box<dyn std::error::Error>> {
-        let mut writer = Cursor::new(Vec::<u8>::new());
-        self.image_buffer.write_with_encoder(PngEncoder::new(&mut writer))?;
+    /// Exports the objective image with the given codec at its offset in the map.
+    ///
+    /// # Arguments
+    /// * `codec` - The codec used to encode the image.
+    pub(crate) fn export_as(
+        &self,
+        codec: ImageCodec,
+    ) -> Result<EncodedImageExtract, ImagingError> {
         Ok(EncodedImageExtract {
             offset: self.offset,
             size: Vec2D::new(self.image_buffer.width(), self.image_buffer.height()),
-            data: writer.into_inner(),
+            data: codec.encode(&self.image_buffer)?,
+            codec,
         })
     }
 }
@@ -269,6 +400,9 @@ impl FullsizeMapImage {
     /// # Arguments
     /// * `path` - The file path of the image to open.
     ///
+    /// The confidence layer is mapped from the same path with the extension `conf`, the tile
+    /// checksums are read from the extension `sum`.
+    ///
     /// # Returns
     /// An instance of `FullsizeMapImage` with the coverage bitmap initialized
     /// and the image buffer mapped to the file.
@@ -277,9 +411,11 @@ impl FullsizeMapImage {
     /// This function will panic if:
     /// * The `FileBackedBuffer` cannot be created.
     /// * The `ImageBuffer` cannot be created from the `FileBackedBuffer`.
-    pub(crate) fn open<P: AsRef<Path>>(path: P) -> Self {
+    pub fn open<P: AsRef<Path>>(path: P) -> Self {
         let fullsize_buffer_size: usize =
             (u32::map_size().x() as usize) * (u32::map_size().y() as usize) * 3;
+        let confidence_path = path.as_ref().with_extension("conf");
+        let integrity_path = path.as_ref().with_extension("sum");
         let file_based_buffer = FileBackedBuffer::open(path, fullsize_buffer_size).unwrap();
         Self {
             image_buffer: ImageBuffer::from_raw(
@@ -288,7 +424,131 @@ impl FullsizeMapImage {
                 file_based_buffer,
             )
             .unwrap(),
+            confidence: ConfidenceLayer::open(confidence_path, u32::map_size()).unwrap(),
+            integrity: MapIntegrity::open(integrity_path, u32::map_size()),
+        }
+    }
+
+    /// Opens an existing map buffer and its confidence layer for analysis without modifying
+    /// or creating any file. Changes to the opened map are never written back.
+    ///
+    /// # Arguments
+    /// * `path` - The path of the map buffer.
+    ///
+    /// # Errors
+    /// An [`ImagingError::Io`] if the map buffer or its confidence layer is missing or of
+    /// the wrong size.
+    pub(crate) fn open_read_only<P: AsRef<Path>>(path: P) -> Result<Self, ImagingError> {
+        let map = u32::map_size();
+        let fullsize_buffer_size = (map.x() as usize) * (map.y() as usize) * 3;
+        let confidence_path = path.as_ref().with_extension("conf");
+        let integrity_path = path.as_ref().with_extension("sum");
+        let buffer = FileBackedBuffer::open_read_only(path, fullsize_buffer_size)?;
+        Ok(Self {
+            image_buffer: ImageBuffer::from_raw(map.x(), map.y(), buffer).unwrap(),
+            confidence: ConfidenceLayer::open_read_only(confidence_path, map)?,
+            integrity: MapIntegrity::open(integrity_path, map),
+        })
+    }
+
+    /// Verifies up to `max_tiles` tiles of the map against their checksums, continuing
+    /// after the tile verified last.
+    ///
+    /// Corrupted tiles are quarantined: their pixels and confidence are reset to unimaged
+    /// and their checksums are recomputed, so that they are simply imaged again.
+    ///
+    /// # Arguments
+    /// * `max_tiles` - The maximum number of tiles to verify.
+    ///
+    /// # Returns
+    /// The top-left corner and the dimensions of every quarantined tile.
+    pub(crate) fn verify_integrity(&mut self, max_tiles: usize) -> Vec<(Vec2D<u32>, Vec2D<u32>)> {
+        let corrupted = self.integrity.verify(self.image_buffer.as_raw(), max_tiles);
+        if corrupted.is_empty() {
+            return Vec::new();
+        }
+        let rects: Vec<_> = corrupted.iter().map(|tile| self.integrity.tile_rect(*tile)).collect();
+        for (offset, size) in &rects {
+            for y in offset.y()..offset.y() + size.y() {
+                for x in offset.x()..offset.x() + size.x() {
+                    self.image_buffer.put_pixel(x, y, Rgb([0, 0, 0]));
+                    self.confidence.set(x, y, 0);
+                }
+            }
+        }
+        self.integrity.reseal(self.image_buffer.as_raw(), &corrupted);
+        rects
+    }
+
+    /// Returns whether the map pixel at `(x, y)` was ever imaged.
+    pub(crate) fn is_imaged(&self, x: u32, y: u32) -> bool { self.confidence.get(x, y) > 0 }
+
+    /// Returns the number of checksummed tiles of the map.
+    pub(crate) fn integrity_tiles(&self) -> usize { self.integrity.tile_count() }
+
+    /// Writes all pending changes of the memory-mapped image buffer and confidence layer back
+    /// to their files, then persists the tile checksums matching the written buffer.
+    ///
+    /// # Returns
+    /// [`ImagingError::BufferCorruption`] if the buffer could not be synchronized.
+    pub(crate) fn flush(&self) -> Result<(), ImagingError> {
+        self.image_buffer.as_raw().flush().map_err(ImagingError::BufferCorruption)?;
+        self.confidence.flush().map_err(ImagingError::BufferCorruption)?;
+        self.integrity.persist();
+        Ok(())
+    }
+
+    /// Merges `image` into the map at `offset` with the given [`MapBlendMode`].
+    ///
+    /// The pixels of `image` are rated by the sharpness of their block, and each is blended
+    /// with the map pixel and its confidence. [`MapBlendMode::LastWins`] skips the rating and
+    /// copies `image` over the map, marking its pixels as imaged with a confidence of `1`.
+    /// Regions crossing the x or y seam of the map are split as in [`MapImage::update_area`].
+    ///
+    /// # Arguments
+    /// * `offset` - The top-left corner of the target sub-region to update.
+    /// * `image` - The new image data to merge into the target sub-region.
+    /// * `mode` - The blending strategy for previously imaged pixels.
+    ///
+    /// # Errors
+    /// Returns [`ImagingError::BufferCorruption`] if a part does not fit into the buffer.
+    pub(crate) fn blend_area<I: GenericImageView<Pixel = Rgb<u8>>>(
+        &mut self,
+        offset: Vec2D<u32>,
+        image: &I,
+        mode: MapBlendMode,
+    ) -> Result<(), ImagingError> {
+        let (width, height) = self.image_buffer.dimensions();
+        let image_size = Vec2D::new(image.width(), image.height());
+        let rating = (mode != MapBlendMode::LastWins).then(|| BlockSharpness::rate(image));
+        for rect in split_at_seam(offset, image_size, Vec2D::new(width, height)) {
+            if rect.dst.x() + rect.size.x() > width || rect.dst.y() + rect.size.y() > height {
+                return Err(ImagingError::BufferCorruption("updated area out of bounds"));
+            }
+            if let Some(sharpness) = &rating {
+                for y in 0..rect.size.y() {
+                    for x in 0..rect.size.x() {
+                        let (src_x, src_y) = (rect.src.x() + x, rect.src.y() + y);
+                        let (dst_x, dst_y) = (rect.dst.x() + x, rect.dst.y() + y);
+                        let old_px = *self.image_buffer.get_pixel(dst_x, dst_y);
+                        let old = (old_px, self.confidence.get(dst_x, dst_y));
+                        let new = (image.get_pixel(src_x, src_y), sharpness.at(src_x, src_y));
+                        if let Some((px, conf)) = mode.blend(old, new) {
+                            self.image_buffer.put_pixel(dst_x, dst_y, px);
+                            self.confidence.set(dst_x, dst_y, conf);
+                        }
+                    }
+                }
+            } else {
+                let part = image.view(rect.src.x(), rect.src.y(), rect.size.x(), rect.size.y());
+                self.image_buffer
+                    .copy_from(&*part, rect.dst.x(), rect.dst.y())
+                    .map_err(|_| ImagingError::BufferCorruption("updated area out of bounds"))?;
+                self.confidence.fill(rect.dst, rect.size, 1);
+            }
+            self.integrity.record_area(self.image_buffer.as_raw(), rect.dst, rect.size);
         }
+        Ok(())
     }
 }
 
@@ -364,13 +624,23 @@ impl MapImage for FullsizeMapImage {
     /// # Returns
     /// A reference to the `ImageBuffer` containing the RGB pixel data.
     fn buffer(&self) -> &ImageBuffer<Self::Pixel, Self::Container> { &self.image_buffer }
+
+    /// Merges `image` into the map at `offset` with the `map_blend_mode` of the
+    /// [`MissionConfig`], see [`FullsizeMapImage::blend_area`].
+    fn update_area<I: GenericImageView<Pixel = Self::Pixel>>(
+        &mut self,
+        offset: Vec2D<u32>,
+        image: &I,
+    ) -> Result<(), ImagingError> {
+        self.blend_area(offset, image, MissionConfig::get().runtime.map_blend_mode)
+    }
 }
 
 /// Represents a thumbnail image generated from a full-size map image.
 ///
 /// This struct is designed to manage scaled-down versions of map images,
 /// which are useful for generating previews or comparing snapshots.
-pub(crate) struct ThumbnailMapImage {
+pub struct ThumbnailMapImage {
     /// The underlying image buffer storing the pixel data of the thumbnail.
     image_buffer: RgbImage,
 }
@@ -438,7 +708,10 @@ impl ThumbnailMapImage {
     ///
     /// # Returns
     /// A `Vec2D<u32>` representing the dimensions of the thumbnail.
-    pub(crate) fn thumbnail_size() -> Vec2D<u32> { u32::map_size() / Self::THUMBNAIL_SCALE_FACTOR }
+    pub fn thumbnail_size() -> Vec2D<u32> { u32::map_size() / Self::THUMBNAIL_SCALE_FACTOR }
+
+    /// Returns a read-only reference to the pixel data of the thumbnail.
+    pub fn image(&self) -> &RgbImage { &self.image_buffer }
 
     /// Generates a thumbnail from a given full-sized map image.
     ///
@@ -450,7 +723,7 @@ impl ThumbnailMapImage {
     ///
     /// # Returns
     /// A `ThumbnailMapImage` containing the scaled-down image.
-    pub(crate) fn from_fullsize(fullsize_map_image: &FullsizeMapImage) -> Self {
+    pub fn from_fullsize(fullsize_map_image: &FullsizeMapImage) -> Self {
         Self {
             image_buffer: imageops::thumbnail(
                 fullsize_map_image,
@@ -466,15 +739,13 @@ impl ThumbnailMapImage {
     /// If it does not exist, a blank image with the dimensions of the thumbnail is created.
     ///
     /// # Arguments
-    /// * `snapshot_path` - The file path to the snapshot PNG.
+    /// * `snapshot_path` - The file path to the snapshot in any [`ImageCodec`].
     ///
     /// # Returns
     /// A `ThumbnailMapImage` containing either the loaded thumbnail image or a blank thumbnail.
     pub(crate) fn from_snapshot<P: AsRef<Path>>(snapshot_path: P) -> Self {
-        let image_buffer = if let Ok(file) = std::fs::File::open(snapshot_path) {
-            DynamicImage::from_decoder(PngDecoder::new(&mut BufReader::new(file)).unwrap())
-                .unwrap()
-                .to_rgb8()
+        let image_buffer = if let Ok(data) = std::fs::read(snapshot_path) {
+            ImageCodec::decode(&data).unwrap().to_rgb8()
         } else {
             ImageBuffer::new(Self::thumbnail_size().x(), Self::thumbnail_size().y())
         };
@@ -487,27 +758,26 @@ impl ThumbnailMapImage {
     /// saved snapshot and creates a new image showing the differences. Pixels that
     /// are identical are marked as transparent, and differing pixels retain their values.
     ///
-    /// If the snapshot file does not exist, the current thumbnail is exported as a PNG.
+    /// If the snapshot file does not exist, the current thumbnail is exported as a whole.
     ///
     /// # Arguments
-    /// * `base_snapshot_path` - The file path to the base snapshot PNG.
+    /// * `base_snapshot_path` - The file path to the base snapshot in any [`ImageCodec`].
+    /// * `codec` - The codec used to encode the diff image.
     ///
     /// # Returns
-    /// An `EncodedImageExtract` containing the diff image as a PNG.
+    /// An `EncodedImageExtract` containing the encoded diff image.
     ///
     /// # Errors
-    /// Returns an error if the snapshot file cannot be read or the PNG encoding fails.
+    /// Returns an error if the snapshot file cannot be read or the encoding fails.
     pub(crate) async fn diff_with_snapshot<P: AsRef<Path>>(
         &self,
         base_snapshot_path: P,
-    ) -> Result<EncodedImageExtract, Box<dyn std::error::Error>> {
+        codec: ImageCodec,
+    ) -> Result<EncodedImageExtract, ImagingError> {
         if let Ok(mut file) = File::open(base_snapshot_path).await {
             let mut old_snapshot_encoded = Vec::<u8>::new();
             file.read_to_end(&mut old_snapshot_encoded).await?;
-            let old_snapshot = DynamicImage::from_decoder(PngDecoder::new(&mut Cursor::new(
-                old_snapshot_encoded,
-            ))?)?
-            .to_rgb8();
+            let old_snapshot = ImageCodec::decode(&old_snapshot_encoded)?.to_rgb8();
             let mut current_snapshot = self.image_buffer.clone();
 
             for (current_pixel, new_pixel) in
@@ -517,20 +787,25 @@ impl ThumbnailMapImage {
                     *new_pixel = Rgb([0, 0, 0]);
                 }
             }
-            let mut writer = Cursor::new(Vec::<u8>::new());
-            current_snapshot.write_with_encoder(PngEncoder::new_with_quality(
-                &mut writer,
-                CompressionType::Best,
-                FilterType::Adaptive,
-            ))?;
-            let diff_encoded = writer.into_inner();
+            let diff_encoded = if codec == ImageCodec::Png {
+                let mut writer = Cursor::new(Vec::<u8>::new());
+                current_snapshot.write_with_encoder(PngEncoder::new_with_quality(
+                    &mut writer,
+                    CompressionType::Best,
+                    FilterType::Adaptive,
+                ))?;
+                writer.into_inner()
+            } else {
+                codec.encode(&current_snapshot)?
+            };
             Ok(EncodedImageExtract {
                 offset: Vec2D::new(0, 0),
                 size: u32::map_size() / ThumbnailMapImage::THUMBNAIL_SCALE_FACTOR,
                 data: diff_encoded,
+                codec,
             })
         } else {
-            self.export_as_png()
+            self.export_as(codec)
         }
     }
 }
@@ -539,10 +814,24 @@ impl ThumbnailMapImage {
 mod tests {
     use super::*;
     use crate::imaging::CameraAngle;
+    use std::path::PathBuf;
+
+    /// Returns the path of a scratch map buffer in the temp directory.
+    fn scratch_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("melvin_{name}_{}.bin", std::process::id()))
+    }
+
+    /// Removes a scratch map buffer together with its confidence layer and checksums.
+    fn remove_scratch(path: &Path) {
+        for ext in ["bin", "conf", "sum"] {
+            std::fs::remove_file(path.with_extension(ext)).unwrap();
+        }
+    }
 
     #[test]
     fn test_overflow() {
-        let mut fullsize_image = FullsizeMapImage::open("tmp.bin");
+        let path = scratch_path("overflow");
+        let mut fullsize_image = FullsizeMapImage::open(&path);
 
         let angle = CameraAngle::Normal;
         let area_size = u32::from(angle.get_square_side_length());
@@ -561,7 +850,7 @@ mod tests {
                 ]);
             }
         }
-        fullsize_image.update_area(offset, &area_image);
+        fullsize_image.update_area(offset, &area_image).unwrap();
         let assert_area_edge = |fs_offset: Vec2D<u32>, area_offset: Vec2D<u32>, size: u32| {
             let fs_view = fullsize_image.vec_view(fs_offset, Vec2D::new(size, size));
             let mut fs_image: ImageBuffer<Rgb<u8>, Vec<u8>> = ImageBuffer::new(size, size);
@@ -583,5 +872,114 @@ mod tests {
             area_size / 2,
         );
         assert_area_edge(offset, Vec2D::new(0, 0), area_size);
+        fullsize_image.flush().unwrap();
+        drop(fullsize_image);
+        remove_scratch(&path);
+    }
+
+    #[test]
+    fn test_repeated_passes_keep_sharpest() {
+        let path = scratch_path("blend");
+        let mut map = FullsizeMapImage::open(&path);
+        let map_size = Vec2D::<u32>::map_size();
+        let offset = Vec2D::new(map_size.x() - 16, map_size.y() - 16);
+        let sharp =
+            RgbImage::from_fn(32, 32, |x, y| Rgb([u8::try_from((x + y) % 2 * 200).unwrap(), 0, 9]));
+        let blurry = RgbImage::from_fn(32, 32, |_, _| Rgb([100, 0, 9]));
+
+        map.blend_area(offset, &sharp, MapBlendMode::KeepSharpest).unwrap();
+        let conf = map.confidence.get(0, 0);
+        assert!(conf > 1);
+        map.blend_area(offset, &blurry, MapBlendMode::KeepSharpest).unwrap();
+        assert_eq!(map.get_pixel(0, 0), *sharp.get_pixel(16, 16));
+        assert_eq!(map.confidence.get(0, 0), conf);
+
+        map.blend_area(offset, &blurry, MapBlendMode::Average).unwrap();
+        assert_eq!(map.confidence.get(0, 0), conf.saturating_add(1));
+        assert_eq!(map.get_pixel(map_size.x() - 1, 0).0[2], 9);
+
+        map.blend_area(offset, &blurry, MapBlendMode::LastWins).unwrap();
+        assert_eq!(map.get_pixel(0, 0), Rgb([100, 0, 9]));
+        assert_eq!(map.confidence.get(0, 0), 1);
+        map.blend_area(offset, &sharp, MapBlendMode::LastWins).unwrap();
+        assert_eq!(map.get_pixel(map_size.x() - 1, 0), *sharp.get_pixel(15, 16));
+        assert_eq!(map.confidence.get(0, 0), 1);
+        // The checksums are only persisted once the buffer is flushed
+        assert!(!path.with_extension("sum").exists());
+        map.flush().unwrap();
+        assert!(path.with_extension("sum").exists());
+        drop(map);
+        remove_scratch(&path);
+    }
+
+    #[test]
+    fn test_open_read_only() {
+        let path = scratch_path("read_only");
+        assert!(FullsizeMapImage::open_read_only(&path).is_err());
+        assert!(!path.exists() && !path.with_extension("conf").exists());
+
+        let mut map = FullsizeMapImage::open(&path);
+        let area = RgbImage::from_fn(8, 8, |_, _| Rgb([50, 60, 70]));
+        map.blend_area(Vec2D::new(4, 4), &area, MapBlendMode::LastWins).unwrap();
+        map.flush().unwrap();
+        drop(map);
+
+        let mut map = FullsizeMapImage::open_read_only(&path).unwrap();
+        assert!(map.is_imaged(4, 4) && !map.is_imaged(0, 0));
+        assert_eq!(map.get_pixel(4, 4), Rgb([50, 60, 70]));
+        // Changes to the opened map never reach the files
+        map.confidence.set(0, 0, 5);
+        drop(map);
+        assert!(!FullsizeMapImage::open_read_only(&path).unwrap().is_imaged(0, 0));
+
+        std::fs::remove_file(path.with_extension("conf")).unwrap();
+        assert!(FullsizeMapImage::open_read_only(&path).is_err());
+        assert!(!path.with_extension("conf").exists());
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(path.with_extension("sum")).unwrap();
+    }
+
+    #[test]
+    fn test_seam_split_at_map_corners() {
+        let map = Vec2D::<u32>::map_size();
+        let size = Vec2D::new(4, 4);
+        assert_eq!(split_at_seam(Vec2D::new(0, 0), size, map).len(), 1);
+        assert_eq!(split_at_seam(Vec2D::new(map.x() - 4, map.y() - 4), size, map).len(), 1);
+        assert_eq!(split_at_seam(Vec2D::new(map.x() - 4, 0), Vec2D::new(5, 4), map).len(), 2);
+        let corner = split_at_seam(Vec2D::new(map.x() - 1, map.y() - 1), size, map);
+        let expected = [
+            ((0, 0), (map.x() - 1, map.y() - 1), (1, 1)),
+            ((0, 1), (map.x() - 1, 0), (1, 3)),
+            ((1, 0), (0, map.y() - 1), (3, 1)),
+            ((1, 1), (0, 0), (3, 3)),
+        ];
+        for (rect, (src, dst, rect_size)) in corner.iter().zip(expected) {
+            assert_eq!(rect.src, Vec2D::new(src.0, src.1));
+            assert_eq!(rect.dst, Vec2D::new(dst.0, dst.1));
+            assert_eq!(rect.size, Vec2D::new(rect_size.0, rect_size.1));
+        }
+        let covered: u32 = corner.iter().map(|r| r.size.x() * r.size.y()).sum();
+        assert_eq!(covered, size.x() * size.y());
+    }
+
+    #[test]
+    fn test_seam_update_and_score_at_corner() {
+        let size = ThumbnailMapImage::thumbnail_size();
+        let mut thumb = ThumbnailMapImage { image_buffer: RgbImage::new(size.x(), size.y()) };
+        let byte = |v: u32| u8::try_from(v).unwrap();
+        let capture = RgbImage::from_fn(6, 6, |x, y| Rgb([byte(x) + 1, byte(y) + 1, 7]));
+        let offset = Vec2D::new(size.x() - 2, size.y() - 3);
+
+        thumb.update_area(offset, &capture).unwrap();
+        for (x, y, px) in capture.enumerate_pixels() {
+            let map_x = (offset.x() + x) % size.x();
+            let map_y = (offset.y() + y) % size.y();
+            assert_eq!(thumb.image().get_pixel(map_x, map_y), px);
+        }
+        assert_eq!(thumb.image().get_pixel(0, 0), capture.get_pixel(2, 3));
+        assert_eq!(thumb.image().get_pixel(size.x() - 3, 0), &Rgb([0, 0, 0]));
+
+        assert_eq!(thumb.match_score(offset, &capture), 0);
+        assert_eq!(thumb.match_score(Vec2D::new(size.x() - 1, size.y() - 3), &capture), -36);
     }
 }
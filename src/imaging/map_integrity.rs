@@ -0,0 +1,245 @@
+use crate::util::{Vec2D, helpers::crc32_update};
+use crate::{error, warn};
+use std::path::{Path, PathBuf};
+
+/// The on-disk manifest of a [`MapIntegrity`].
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct IntegrityManifest {
+    /// Identifies the file as a map integrity manifest.
+    magic: [u8; 4],
+    /// The version of the manifest layout.
+    version: u16,
+    /// The width of the map in px.
+    width: u32,
+    /// The height of the map in px.
+    height: u32,
+    /// The side length of a tile in px.
+    tile_size: u32,
+    /// The checksum of every tile, row by row, `None` if it was never computed.
+    sums: Vec<Option<u32>>,
+}
+
+/// Per-tile CRC-32 checksums of a memory-mapped RGB map buffer.
+///
+/// The map is split into square tiles of [`MapIntegrity::TILE_SIZE`] px. The checksums of
+/// all tiles touched by a write batch are recomputed and persisted to a manifest next to
+/// the buffer once the buffer was flushed, so that a torn write or a truncated buffer file
+/// shows up as a mismatch when the tile is verified later. Tiles without a checksum, e.g. of
+/// maps written before the manifest existed, are adopted as they are on their first
+/// verification.
+#[derive(Debug)]
+pub(crate) struct MapIntegrity {
+    /// The file the manifest is persisted to.
+    file: PathBuf,
+    /// The dimensions of the map.
+    size: Vec2D<u32>,
+    /// The number of tiles per map row.
+    tiles_x: usize,
+    /// The checksum of every tile, row by row.
+    sums: Vec<Option<u32>>,
+    /// Whether the manifest did not match the map, so no tile can be trusted.
+    untrusted: bool,
+    /// The tile at which the next verification continues.
+    cursor: usize,
+}
+
+impl MapIntegrity {
+    /// The side length of a tile in px.
+    pub(crate) const TILE_SIZE: u32 = 256;
+    /// Identifies a map integrity manifest.
+    const MAGIC: [u8; 4] = *b"MMAP";
+    /// The current version of the manifest layout.
+    const VERSION: u16 = 1;
+
+    /// Opens the manifest of a map of the given size at `path`.
+    ///
+    /// A missing or unreadable manifest starts without checksums. A manifest written for
+    /// different dimensions marks every tile as corrupted on the next verification, since
+    /// the buffer layout cannot be trusted.
+    ///
+    /// # Arguments
+    /// * `path` – The file the manifest is persisted to.
+    /// * `size` – The dimensions of the map.
+    pub(crate) fn open<P: AsRef<Path>>(path: P, size: Vec2D<u32>) -> Self {
+        let file = path.as_ref().to_path_buf();
+        let tiles_x = size.x().div_ceil(Self::TILE_SIZE) as usize;
+        let tiles = tiles_x * size.y().div_ceil(Self::TILE_SIZE) as usize;
+        let mut integrity =
+            Self { file, size, tiles_x, sums: vec![None; tiles], untrusted: false, cursor: 0 };
+        let Ok(data) = std::fs::read(&integrity.file) else { return integrity };
+        let decoded = bincode::serde::decode_from_slice::<IntegrityManifest, _>(
+            &data,
+            bincode::config::standard(),
+        );
+        match decoded {
+            Ok((manifest, _)) if manifest.magic != Self::MAGIC => {
+                warn!("Ignoring map integrity manifest with unknown magic.");
+            }
+            Ok((manifest, _)) if manifest.version != Self::VERSION => {
+                warn!("Ignoring map integrity manifest of version {}.", manifest.version);
+            }
+            Ok((manifest, _))
+                if manifest.width != size.x()
+                    || manifest.height != size.y()
+                    || manifest.tile_size != Self::TILE_SIZE
+                    || manifest.sums.len() != tiles =>
+            {
+                warn!(
+                    "Map integrity manifest is for a {}x{} map with {}px tiles, distrusting map.",
+                    manifest.width, manifest.height, manifest.tile_size
+                );
+                integrity.untrusted = true;
+            }
+            Ok((manifest, _)) => integrity.sums = manifest.sums,
+            Err(e) => warn!("Ignoring invalid map integrity manifest: {e}."),
+        }
+        integrity
+    }
+
+    /// Returns the number of tiles of the map.
+    pub(crate) fn tile_count(&self) -> usize { self.sums.len() }
+
+    /// Returns the top-left corner and the dimensions of a tile.
+    ///
+    /// # Arguments
+    /// * `tile` – The index of the tile, row by row.
+    #[allow(clippy::cast_possible_truncation)]
+    pub(crate) fn tile_rect(&self, tile: usize) -> (Vec2D<u32>, Vec2D<u32>) {
+        let offset = Vec2D::new(
+            (tile % self.tiles_x) as u32 * Self::TILE_SIZE,
+            (tile / self.tiles_x) as u32 * Self::TILE_SIZE,
+        );
+        let size = Vec2D::new(
+            Self::TILE_SIZE.min(self.size.x() - offset.x()),
+            Self::TILE_SIZE.min(self.size.y() - offset.y()),
+        );
+        (offset, size)
+    }
+
+    /// Computes the checksum of a tile over its RGB rows in `data`.
+    fn checksum(&self, data: &[u8], tile: usize) -> u32 {
+        let (offset, size) = self.tile_rect(tile);
+        let row_len = size.x() as usize * 3;
+        (offset.y()..offset.y() + size.y()).fold(0, |crc, y| {
+            let start = (y as usize * self.size.x() as usize + offset.x() as usize) * 3;
+            crc32_update(crc, &data[start..start + row_len])
+        })
+    }
+
+    /// Recomputes the checksums of all tiles overlapping a written area. The area must not
+    /// cross the map seam, see [`MapIntegrity::persist`] to store the result.
+    ///
+    /// # Arguments
+    /// * `data` – The RGB buffer of the map.
+    /// * `offset` – The top-left corner of the written area.
+    /// * `size` – The dimensions of the written area.
+    pub(crate) fn record_area(&mut self, data: &[u8], offset: Vec2D<u32>, size: Vec2D<u32>) {
+        if size.x() == 0 || size.y() == 0 {
+            return;
+        }
+        let last_x = (offset.x() + size.x() - 1) / Self::TILE_SIZE;
+        let last_y = (offset.y() + size.y() - 1) / Self::TILE_SIZE;
+        for ty in offset.y() / Self::TILE_SIZE..=last_y {
+            for tx in offset.x() / Self::TILE_SIZE..=last_x {
+                let tile = ty as usize * self.tiles_x + tx as usize;
+                self.sums[tile] = Some(self.checksum(data, tile));
+            }
+        }
+    }
+
+    /// Recomputes the checksums of the given tiles, see [`MapIntegrity::persist`] to store the
+    /// result.
+    ///
+    /// # Arguments
+    /// * `data` – The RGB buffer of the map.
+    /// * `tiles` – The indices of the tiles to reseal.
+    pub(crate) fn reseal(&mut self, data: &[u8], tiles: &[usize]) {
+        for tile in tiles {
+            self.sums[*tile] = Some(self.checksum(data, *tile));
+        }
+    }
+
+    /// Verifies up to `max_tiles` tiles, continuing after the tile verified last.
+    ///
+    /// Tiles without a checksum are adopted with their current content. If the manifest did
+    /// not match the map, all tiles are reported on the first call.
+    ///
+    /// # Arguments
+    /// * `data` – The RGB buffer of the map.
+    /// * `max_tiles` – The maximum number of tiles to verify.
+    ///
+    /// # Returns
+    /// * The indices of all tiles whose content does not match their checksum.
+    pub(crate) fn verify(&mut self, data: &[u8], max_tiles: usize) -> Vec<usize> {
+        if self.untrusted {
+            self.untrusted = false;
+            return (0..self.tile_count()).collect();
+        }
+        let mut corrupted = Vec::new();
+        for _ in 0..max_tiles.min(self.tile_count()) {
+            let tile = self.cursor;
+            self.cursor = (self.cursor + 1) % self.tile_count();
+            let sum = self.checksum(data, tile);
+            match self.sums[tile] {
+                Some(expected) if expected != sum => corrupted.push(tile),
+                Some(_) => {}
+                None => self.sums[tile] = Some(sum),
+            }
+        }
+        corrupted
+    }
+
+    /// Writes the manifest to a temporary file and moves it over the previous one, so that
+    /// a crash never leaves a torn manifest behind.
+    ///
+    /// The buffer must be flushed before, otherwise a crash leaves a manifest describing
+    /// writes that never reached the disk, and the stale tiles fail their verification.
+    pub(crate) fn persist(&self) {
+        let manifest = IntegrityManifest {
+            magic: Self::MAGIC,
+            version: Self::VERSION,
+            width: self.size.x(),
+            height: self.size.y(),
+            tile_size: Self::TILE_SIZE,
+            sums: self.sums.clone(),
+        };
+        let tmp = self.file.with_extension("tmp");
+        let res = bincode::serde::encode_to_vec(&manifest, bincode::config::standard())
+            .map_err(|e| e.to_string())
+            .and_then(|data| std::fs::write(&tmp, data).map_err(|e| e.to_string()))
+            .and_then(|()| std::fs::rename(&tmp, &self.file).map_err(|e| e.to_string()));
+        if let Err(e) = res {
+            error!("Failed to persist map integrity manifest: {e}.");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_and_reseals_corruption() {
+        let name = format!("melvin_integrity_{}.sum", std::process::id());
+        let path = std::env::temp_dir().join(name);
+        let size = Vec2D::new(600, 300);
+        let mut data = vec![7u8; 600 * 300 * 3];
+        let mut integrity = MapIntegrity::open(&path, size);
+        assert_eq!(integrity.tile_count(), 6);
+        assert_eq!(integrity.tile_rect(5), (Vec2D::new(512, 256), Vec2D::new(88, 44)));
+        assert!(integrity.verify(&data, usize::MAX).is_empty());
+
+        data[(260 * 600 + 10) * 3] = 9;
+        integrity.record_area(&data, Vec2D::new(10, 260), Vec2D::new(1, 1));
+        integrity.persist();
+        data[(299 * 600 + 599) * 3 + 2] = 0;
+        let mut reopened = MapIntegrity::open(&path, size);
+        assert_eq!(reopened.verify(&data, usize::MAX), vec![5]);
+        reopened.reseal(&data, &[5]);
+        assert!(reopened.verify(&data, usize::MAX).is_empty());
+
+        let mut resized = MapIntegrity::open(&path, Vec2D::new(600, 600));
+        assert_eq!(resized.verify(&data, 1).len(), resized.tile_count());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
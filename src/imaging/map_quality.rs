@@ -0,0 +1,431 @@
+use super::ImagingError;
+use crate::util::{Vec2D, logger::JsonDump};
+use chrono::{DateTime, Utc};
+use image::{GenericImageView, Rgb, RgbImage, imageops};
+use std::path::PathBuf;
+
+/// The orientation of a [`MapSeam`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SeamAxis {
+    /// A vertical seam between two map columns.
+    Vertical,
+    /// A horizontal seam between two map rows.
+    Horizontal,
+}
+
+/// A straight discontinuity in a tile of the map, likely the border of two captures whose
+/// offsets disagree.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub(crate) struct MapSeam {
+    /// The top-left corner of the tile in map pixels.
+    tile: Vec2D<u32>,
+    /// The orientation of the seam.
+    axis: SeamAxis,
+    /// The map column or row right before the seam.
+    pos: u32,
+    /// The mean luminance step across the seam.
+    step: f64,
+}
+
+impl MapSeam {
+    /// Returns the top-left corner of the tile in map pixels.
+    pub(crate) fn tile(&self) -> Vec2D<u32> { self.tile }
+    /// Returns the orientation of the seam.
+    pub(crate) fn axis(&self) -> SeamAxis { self.axis }
+    /// Returns the map column or row right before the seam.
+    pub(crate) fn pos(&self) -> u32 { self.pos }
+}
+
+/// The analysis result of a single tile, kept for rendering the preview.
+#[derive(Debug, Clone, Copy, Default)]
+struct TileQuality {
+    /// Share of imaged pixels from `0.0` to `1.0`.
+    coverage: f64,
+    /// Whether the tile is imaged but suspiciously uniform.
+    uniform: bool,
+}
+
+/// Offline quality report of the stitched map, used to decide where to spend the remaining
+/// acquisition time.
+///
+/// The map is split into square tiles of [`MapQualityReport::TILE_SIZE`] pixels. Besides the
+/// share of imaged pixels, every tile is checked for seams and suspiciously uniform content:
+/// - Capture offsets are not persisted, but images are placed as axis-aligned rectangles. A
+///   misaligned capture thus shows as a column or row whose mean luminance step across the
+///   tile clearly exceeds that of the other columns or rows.
+/// - A fully imaged tile with (almost) no luminance variance is more likely a blank or
+///   saturated capture than actual terrain, as even open ocean shows some noise.
+#[derive(Debug, serde::Serialize)]
+pub(crate) struct MapQualityReport {
+    /// The time of the analysis.
+    created: DateTime<Utc>,
+    /// The number of tile columns and rows.
+    grid: Vec2D<u32>,
+    /// Share of imaged map pixels from `0.0` to `1.0`.
+    coverage: f64,
+    /// The number of fully imaged tiles.
+    full_tiles: usize,
+    /// The number of partially imaged tiles.
+    partial_tiles: usize,
+    /// The number of tiles without any imaged pixel.
+    empty_tiles: usize,
+    /// All detected seams, row by row.
+    seams: Vec<MapSeam>,
+    /// The top-left corners of all suspiciously uniform tiles, row by row.
+    uniform_tiles: Vec<Vec2D<u32>>,
+    /// The analysis result of every tile, row by row.
+    #[serde(skip)]
+    tiles: Vec<TileQuality>,
+}
+
+impl MapQualityReport {
+    /// The side length of an analyzed tile in pixels.
+    pub(crate) const TILE_SIZE: u32 = 100;
+    /// The factor the map is scaled down by for the annotated preview.
+    const PREVIEW_SCALE: u32 = 10;
+    /// The minimum mean luminance step across a seam.
+    const MIN_SEAM_STEP: f64 = 12.0;
+    /// The minimum ratio of the step across a seam to the median step within its tile.
+    const MIN_SEAM_RATIO: f64 = 4.0;
+    /// The maximum luminance variance of a suspiciously uniform tile.
+    const MAX_UNIFORM_VARIANCE: f64 = 1.0;
+    /// The color of unimaged preview pixels.
+    const UNIMAGED_COLOR: Rgb<u8> = Rgb([96, 0, 0]);
+    /// The color of seams in the preview.
+    const SEAM_COLOR: Rgb<u8> = Rgb([255, 220, 0]);
+    /// The color of the outline of uniform tiles in the preview.
+    const UNIFORM_COLOR: Rgb<u8> = Rgb([255, 0, 255]);
+
+    /// Analyzes the given map.
+    ///
+    /// # Arguments
+    /// * `map` – The stitched map.
+    /// * `imaged` – Returns whether the map pixel at `(x, y)` was ever imaged.
+    #[allow(clippy::cast_precision_loss)]
+    pub(crate) fn analyze<V, F>(map: &V, imaged: F) -> Self
+    where
+        V: GenericImageView<Pixel = Rgb<u8>>,
+        F: Fn(u32, u32) -> bool,
+    {
+        let (width, height) = map.dimensions();
+        let grid = Vec2D::new(
+            width.div_ceil(Self::TILE_SIZE),
+            height.div_ceil(Self::TILE_SIZE),
+        );
+        let mut report = Self {
+            created: Utc::now(),
+            grid,
+            coverage: 0.0,
+            full_tiles: 0,
+            partial_tiles: 0,
+            empty_tiles: 0,
+            seams: Vec::new(),
+            uniform_tiles: Vec::new(),
+            tiles: Vec::with_capacity(grid.x() as usize * grid.y() as usize),
+        };
+        let mut imaged_px = 0usize;
+        for ty in 0..grid.y() {
+            for tx in 0..grid.x() {
+                let offset = Vec2D::new(tx * Self::TILE_SIZE, ty * Self::TILE_SIZE);
+                let tile = TileSampler::new(map, &imaged, offset);
+                imaged_px += tile.imaged_px;
+                let quality = report.analyze_tile(&tile);
+                report.tiles.push(quality);
+            }
+        }
+        report.coverage = if width == 0 || height == 0 {
+            0.0
+        } else {
+            imaged_px as f64 / (f64::from(width) * f64::from(height))
+        };
+        report
+    }
+
+    /// Classifies a sampled tile and records its seams and uniformity.
+    fn analyze_tile(&mut self, tile: &TileSampler) -> TileQuality {
+        let coverage = tile.coverage();
+        if tile.imaged_px == 0 {
+            self.empty_tiles += 1;
+            return TileQuality::default();
+        }
+        if tile.imaged_px < tile.len() {
+            self.partial_tiles += 1;
+        } else {
+            self.full_tiles += 1;
+        }
+        for axis in [SeamAxis::Vertical, SeamAxis::Horizontal] {
+            if let Some((pos, step)) = tile.seam(axis) {
+                self.seams.push(MapSeam { tile: tile.offset, axis, pos, step });
+            }
+        }
+        let uniform = tile.imaged_px == tile.len() && tile.variance() <= Self::MAX_UNIFORM_VARIANCE;
+        if uniform {
+            self.uniform_tiles.push(tile.offset);
+        }
+        TileQuality { coverage, uniform }
+    }
+
+    /// Returns the share of imaged map pixels from `0.0` to `1.0`.
+    pub(crate) fn coverage(&self) -> f64 { self.coverage }
+    /// Returns all detected seams.
+    pub(crate) fn seams(&self) -> &[MapSeam] { &self.seams }
+    /// Returns the top-left corners of all suspiciously uniform tiles.
+    pub(crate) fn uniform_tiles(&self) -> &[Vec2D<u32>] { &self.uniform_tiles }
+
+    /// Renders a preview of the map scaled down by [`MapQualityReport::PREVIEW_SCALE`].
+    ///
+    /// Unimaged tiles are tinted red by their missing share, seams are drawn in yellow and
+    /// uniform tiles are outlined in magenta.
+    ///
+    /// # Arguments
+    /// * `map` – The analyzed map.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub(crate) fn render_preview<V: GenericImageView<Pixel = Rgb<u8>>>(&self, map: &V) -> RgbImage {
+        let (width, height) = map.dimensions();
+        let (pw, ph) = (
+            (width / Self::PREVIEW_SCALE).max(1),
+            (height / Self::PREVIEW_SCALE).max(1),
+        );
+        let mut preview = imageops::thumbnail(map, pw, ph);
+        let cell = Self::TILE_SIZE / Self::PREVIEW_SCALE;
+        for (i, tile) in self.tiles.iter().enumerate() {
+            let (tx, ty) = (i as u32 % self.grid.x(), i as u32 / self.grid.x());
+            let (x0, y0) = (tx * cell, ty * cell);
+            let (x1, y1) = (((tx + 1) * cell).min(pw), ((ty + 1) * cell).min(ph));
+            let tint = 1.0 - tile.coverage;
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    let px = preview.get_pixel_mut(x, y);
+                    for (c, u) in px.0.iter_mut().zip(Self::UNIMAGED_COLOR.0) {
+                        *c = (f64::from(*c) * (1.0 - tint) + f64::from(u) * tint).round() as u8;
+                    }
+                    let border = x == x0 || y == y0 || x + 1 == x1 || y + 1 == y1;
+                    if tile.uniform && border {
+                        *px = Self::UNIFORM_COLOR;
+                    }
+                }
+            }
+        }
+        for seam in &self.seams {
+            let (x0, y0) = (
+                seam.tile.x() / Self::PREVIEW_SCALE,
+                seam.tile.y() / Self::PREVIEW_SCALE,
+            );
+            let (x1, y1) = ((x0 + cell).min(pw), (y0 + cell).min(ph));
+            let at = (seam.pos / Self::PREVIEW_SCALE).min(match seam.axis {
+                SeamAxis::Vertical => pw - 1,
+                SeamAxis::Horizontal => ph - 1,
+            });
+            match seam.axis {
+                SeamAxis::Vertical => {
+                    (y0..y1).for_each(|y| preview.put_pixel(at, y, Self::SEAM_COLOR));
+                }
+                SeamAxis::Horizontal => {
+                    (x0..x1).for_each(|x| preview.put_pixel(x, at, Self::SEAM_COLOR));
+                }
+            }
+        }
+        preview
+    }
+
+    /// Saves the annotated preview as a PNG next to the dumped JSON report.
+    ///
+    /// # Arguments
+    /// * `map` – The analyzed map.
+    ///
+    /// # Returns
+    /// The path of the saved preview.
+    pub(crate) fn save_preview<V: GenericImageView<Pixel = Rgb<u8>>>(
+        &self,
+        map: &V,
+    ) -> Result<PathBuf, ImagingError> {
+        let dir = PathBuf::from(format!("./dumps/{}", self.dir_name()));
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join(format!("{}.png", self.file_name()));
+        self.render_preview(map).save(&path)?;
+        Ok(path)
+    }
+}
+
+impl JsonDump for MapQualityReport {
+    /// Returns a unique filename based on the time of the analysis.
+    fn file_name(&self) -> String {
+        format!("quality_{}", self.created.format("%Y-%m-%dT%H-%M-%S"))
+    }
+
+    /// Specifies the output directory for dumped map quality reports.
+    fn dir_name(&self) -> &'static str { "map_quality" }
+}
+
+/// The luminance of a tile and one extra column and row beyond it, `None` if unimaged.
+struct TileSampler {
+    /// The top-left corner of the tile in map pixels.
+    offset: Vec2D<u32>,
+    /// The dimensions of the tile, clipped at the map border.
+    size: Vec2D<u32>,
+    /// The dimensions of the sampled area, including the extra column and row if on the map.
+    sampled: Vec2D<u32>,
+    /// The luminance of every sampled pixel, row by row.
+    lum: Vec<Option<f64>>,
+    /// The number of imaged pixels within the tile.
+    imaged_px: usize,
+}
+
+impl TileSampler {
+    /// Samples the tile at `offset`.
+    fn new<V, F>(map: &V, imaged: &F, offset: Vec2D<u32>) -> Self
+    where
+        V: GenericImageView<Pixel = Rgb<u8>>,
+        F: Fn(u32, u32) -> bool,
+    {
+        let (width, height) = map.dimensions();
+        let tile = MapQualityReport::TILE_SIZE;
+        let size = Vec2D::new(tile.min(width - offset.x()), tile.min(height - offset.y()));
+        let sampled = Vec2D::new(
+            (size.x() + 1).min(width - offset.x()),
+            (size.y() + 1).min(height - offset.y()),
+        );
+        let mut lum = Vec::with_capacity(sampled.x() as usize * sampled.y() as usize);
+        let mut imaged_px = 0;
+        for y in 0..sampled.y() {
+            for x in 0..sampled.x() {
+                let (mx, my) = (offset.x() + x, offset.y() + y);
+                if !imaged(mx, my) {
+                    lum.push(None);
+                    continue;
+                }
+                let [r, g, b] = map.get_pixel(mx, my).0;
+                let l = 0.299 * f64::from(r) + 0.587 * f64::from(g) + 0.114 * f64::from(b);
+                lum.push(Some(l));
+                imaged_px += usize::from(x < size.x() && y < size.y());
+            }
+        }
+        Self { offset, size, sampled, lum, imaged_px }
+    }
+
+    /// Returns the number of pixels within the tile.
+    fn len(&self) -> usize { self.size.x() as usize * self.size.y() as usize }
+
+    /// Returns the share of imaged pixels within the tile.
+    #[allow(clippy::cast_precision_loss)]
+    fn coverage(&self) -> f64 { self.imaged_px as f64 / self.len() as f64 }
+
+    /// Returns the luminance at `(x, y)` relative to the tile.
+    fn at(&self, x: u32, y: u32) -> Option<f64> {
+        self.lum[y as usize * self.sampled.x() as usize + x as usize]
+    }
+
+    /// Returns the luminance variance of the imaged pixels within the tile.
+    #[allow(clippy::cast_precision_loss)]
+    fn variance(&self) -> f64 {
+        let (mut sum, mut sum_sq, mut n) = (0.0, 0.0, 0usize);
+        for y in 0..self.size.y() {
+            for l in (0..self.size.x()).filter_map(|x| self.at(x, y)) {
+                sum += l;
+                sum_sq += l * l;
+                n += 1;
+            }
+        }
+        if n == 0 {
+            return 0.0;
+        }
+        let mean = sum / n as f64;
+        sum_sq / n as f64 - mean * mean
+    }
+
+    /// Finds the strongest seam along `axis` that starts within the tile.
+    ///
+    /// The mean luminance step between every pair of neighboring columns (or rows) is taken
+    /// over all pixels imaged on both sides, which must span at least half the tile.
+    ///
+    /// # Returns
+    /// The map column (or row) before the seam and its mean step, or `None` if no step stands
+    /// out by [`MapQualityReport::MIN_SEAM_STEP`] and [`MapQualityReport::MIN_SEAM_RATIO`].
+    #[allow(clippy::cast_precision_loss)]
+    fn seam(&self, axis: SeamAxis) -> Option<(u32, f64)> {
+        let (steps, along) = match axis {
+            SeamAxis::Vertical => ((self.sampled.x() - 1).min(self.size.x()), self.size.y()),
+            SeamAxis::Horizontal => ((self.sampled.y() - 1).min(self.size.y()), self.size.x()),
+        };
+        let mut profile = Vec::with_capacity(steps as usize);
+        for i in 0..steps {
+            let (mut sum, mut n) = (0.0, 0u32);
+            for j in 0..along {
+                let pair = match axis {
+                    SeamAxis::Vertical => self.at(i, j).zip(self.at(i + 1, j)),
+                    SeamAxis::Horizontal => self.at(j, i).zip(self.at(j, i + 1)),
+                };
+                if let Some((a, b)) = pair {
+                    sum += (a - b).abs();
+                    n += 1;
+                }
+            }
+            if 2 * n >= along && n > 0 {
+                profile.push((i, sum / f64::from(n)));
+            }
+        }
+        if profile.len() < 3 {
+            return None;
+        }
+        let mut sorted: Vec<f64> = profile.iter().map(|(_, step)| *step).collect();
+        sorted.sort_by(f64::total_cmp);
+        let median = sorted[sorted.len() / 2];
+        let (i, step) = profile.into_iter().max_by(|a, b| a.1.total_cmp(&b.1))?;
+        let stands_out = step >= MapQualityReport::MIN_SEAM_STEP
+            && step >= MapQualityReport::MIN_SEAM_RATIO * median.max(1.0);
+        let start = match axis {
+            SeamAxis::Vertical => self.offset.x(),
+            SeamAxis::Horizontal => self.offset.y(),
+        };
+        stands_out.then_some((start + i, step))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_quality_report() {
+        // A noisy 400x200 map whose right half was captured with an offset
+        let terrain = |x: u32, y: u32| {
+            let v = u8::try_from((x * 7 + y * 13) % 11 + (x / 3 + y / 5) % 7).unwrap();
+            Rgb([60 + v, 90 + v, 40 + v])
+        };
+        let mut map = RgbImage::from_fn(400, 200, |x, y| {
+            if x < 150 { terrain(x, y) } else { Rgb(terrain(x + 40, y + 40).0.map(|c| c + 60)) }
+        });
+        // A blank capture in the bottom left tile
+        for y in 100..200 {
+            for x in 0..100 {
+                map.put_pixel(x, y, Rgb([70, 100, 50]));
+            }
+        }
+        // The top right tile was never imaged, the one left of it only halfway
+        let imaged =
+            |x: u32, y: u32| !(x >= 300 && y < 100 || (200..300).contains(&x) && y < 50);
+        let report = MapQualityReport::analyze(&map, imaged);
+        assert!((report.coverage() - 0.8125).abs() < 1e-9);
+        assert_eq!(
+            (report.full_tiles, report.partial_tiles, report.empty_tiles),
+            (6, 1, 1)
+        );
+
+        // The offset shows as a vertical seam before column 150 in both tiles it crosses
+        assert_eq!(report.seams().len(), 2);
+        for seam in report.seams() {
+            assert_eq!(
+                (seam.tile().x(), seam.axis(), seam.pos()),
+                (100, SeamAxis::Vertical, 149)
+            );
+        }
+        assert_eq!(report.uniform_tiles(), &[Vec2D::new(0, 100)]);
+
+        let preview = report.render_preview(&map);
+        assert_eq!(preview.dimensions(), (40, 20));
+        assert_eq!(*preview.get_pixel(35, 5), MapQualityReport::UNIMAGED_COLOR);
+        assert_eq!(*preview.get_pixel(14, 5), MapQualityReport::SEAM_COLOR);
+        assert_eq!(*preview.get_pixel(0, 10), MapQualityReport::UNIFORM_COLOR);
+    }
+}
@@ -3,10 +3,39 @@
 
 pub(super) mod cycle_state;
 mod file_based_buffer;
+mod image_codec;
 pub(crate) mod map_image;
 mod sub_buffer;
 mod camera_controller;
 mod camera_state;
+mod capture_health;
+mod capture_log;
+mod coverage_planner;
+mod daily_map_upload;
+mod image_store;
+mod imaging_error;
+mod lens_policy;
+mod map_blend;
+mod map_integrity;
+mod map_quality;
+mod mosaic_completeness;
+mod offset_estimator;
+mod resolution_estimator;
+mod thumbnail_deferral;
+mod tile_classifier;
+mod tile_pyramid;
+mod upload_queue;
 
 pub use camera_controller::CameraController;
-pub use camera_state::CameraAngle;
\ No newline at end of file
+pub(crate) use camera_controller::MAP_BUFFER_PATH;
+pub use camera_state::CameraAngle;
+pub(crate) use coverage_planner::CoveragePlanner;
+pub(crate) use daily_map_upload::DailyMapUpload;
+pub use image_codec::ImageCodec;
+pub use imaging_error::ImagingError;
+pub use lens_policy::LensPolicy;
+pub use map_blend::MapBlendMode;
+pub use map_image::{FullsizeMapImage, ThumbnailMapImage};
+pub(crate) use map_quality::MapQualityReport;
+pub(crate) use resolution_estimator::ResolutionEstimate;
+pub(crate) use tile_pyramid::TileId;
@@ -0,0 +1,118 @@
+use bitvec::{bitbox, order::Lsb0, prelude::BitBox};
+use image::{GenericImageView, Rgb};
+
+/// Completeness of a zoned objective mosaic, judged by its non-black pixels.
+///
+/// Pixels that were never written by an image remain black. Black regions that are enclosed by
+/// imaged pixels are counted as holes, while black regions touching the border of the zone are
+/// treated as a missing edge of the mosaic.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MosaicCompleteness {
+    /// Share of imaged (non-black) pixels in the zone from `0.0` to `1.0`.
+    coverage: f64,
+    /// Number of black regions enclosed by imaged pixels.
+    holes: usize,
+    /// Size of the largest black region in pixels, whether enclosed or not.
+    largest_gap_px: usize,
+}
+
+impl MosaicCompleteness {
+    /// Analyzes the given zone image.
+    ///
+    /// # Arguments
+    /// * `view` – The image of the zone, with unimaged pixels left black.
+    ///
+    /// # Returns
+    /// * The [`MosaicCompleteness`] of the zone, full coverage for an empty zone.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn analyze<V: GenericImageView<Pixel = Rgb<u8>>>(view: &V) -> Self {
+        let (w, h) = (view.width() as usize, view.height() as usize);
+        let len = w * h;
+        if len == 0 {
+            return Self { coverage: 1.0, holes: 0, largest_gap_px: 0 };
+        }
+        let mut black: BitBox<usize, Lsb0> = bitbox![usize, Lsb0; 0; len];
+        for (x, y, px) in view.pixels() {
+            if px.0 == [0, 0, 0] {
+                black.set(y as usize * w + x as usize, true);
+            }
+        }
+        let black_px = black.count_ones();
+
+        let mut holes = 0;
+        let mut largest_gap_px = 0;
+        let mut stack = Vec::new();
+        while let Some(start) = black.first_one() {
+            black.set(start, false);
+            stack.push(start);
+            let (mut size, mut touches_border) = (0, false);
+            while let Some(i) = stack.pop() {
+                size += 1;
+                let (x, y) = (i % w, i / w);
+                touches_border |= x == 0 || y == 0 || x == w - 1 || y == h - 1;
+                let neighbours = [
+                    (x > 0).then(|| i - 1),
+                    (x + 1 < w).then(|| i + 1),
+                    (y > 0).then(|| i - w),
+                    (y + 1 < h).then(|| i + w),
+                ];
+                for n in neighbours.into_iter().flatten() {
+                    if black[n] {
+                        black.set(n, false);
+                        stack.push(n);
+                    }
+                }
+            }
+            holes += usize::from(!touches_border);
+            largest_gap_px = largest_gap_px.max(size);
+        }
+        let coverage = (len - black_px) as f64 / len as f64;
+        Self { coverage, holes, largest_gap_px }
+    }
+
+    /// Returns the share of imaged pixels from `0.0` to `1.0`.
+    pub fn coverage(&self) -> f64 { self.coverage }
+
+    /// Returns the number of black regions enclosed by imaged pixels.
+    pub fn holes(&self) -> usize { self.holes }
+
+    /// Returns the size of the largest black region in pixels.
+    pub fn largest_gap_px(&self) -> usize { self.largest_gap_px }
+
+    /// Returns `true` if the zone is imaged well enough to be uploaded.
+    ///
+    /// # Arguments
+    /// * `min_coverage` – The minimum share of imaged pixels; `0.0` accepts any mosaic.
+    pub fn is_sufficient(&self, min_coverage: f64) -> bool { self.coverage >= min_coverage }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::RgbImage;
+
+    #[test]
+    fn test_mosaic_completeness() {
+        let mut img = RgbImage::from_pixel(10, 10, Rgb([120, 80, 40]));
+        let full = MosaicCompleteness::analyze(&img);
+        assert!((full.coverage() - 1.0).abs() < f64::EPSILON);
+        assert_eq!((full.holes(), full.largest_gap_px()), (0, 0));
+
+        // An enclosed 2x2 hole and a missing 10x1 edge
+        for (x, y) in [(4, 4), (4, 5), (5, 4), (5, 5)] {
+            img.put_pixel(x, y, Rgb([0, 0, 0]));
+        }
+        for x in 0..10 {
+            img.put_pixel(x, 9, Rgb([0, 0, 0]));
+        }
+        let partial = MosaicCompleteness::analyze(&img);
+        assert!((partial.coverage() - 0.86).abs() < 1e-9);
+        assert_eq!((partial.holes(), partial.largest_gap_px()), (1, 10));
+        assert!(partial.is_sufficient(0.85));
+        assert!(!partial.is_sufficient(0.9));
+
+        let empty = MosaicCompleteness::analyze(&RgbImage::new(4, 4));
+        assert!(empty.coverage().abs() < f64::EPSILON);
+        assert_eq!((empty.holes(), empty.largest_gap_px()), (0, 16));
+    }
+}
@@ -0,0 +1,192 @@
+use super::map_image::MapImage;
+use crate::util::Vec2D;
+use image::{Rgb, RgbImage};
+
+/// Coarse-to-fine estimator of image offsets beyond the small search window of the
+/// [`CameraController`](super::CameraController).
+///
+/// Both the captured image and the surrounding map region are downsampled into tiles of
+/// grayscale block means. All tile shifts within the search radius are compared by their mean
+/// absolute difference, ignoring tiles not imaged yet. The best coarse shift is then refined at
+/// full resolution with the exact pixel match score of the map.
+pub(crate) struct OffsetEstimator {
+    /// The maximum searched shift in pixels along each axis.
+    radius: i32,
+}
+
+impl OffsetEstimator {
+    /// The side length in pixels of a downsampled tile.
+    const TILE: u32 = 4;
+    /// The minimum share of imaged tiles a coarse shift must overlap to be considered.
+    const MIN_VALID_SHARE: f32 = 0.3;
+
+    /// Creates a new [`OffsetEstimator`].
+    ///
+    /// # Arguments
+    /// * `radius` - The maximum searched shift in pixels along each axis.
+    pub(crate) fn new(radius: u32) -> Self {
+        Self { radius: i32::try_from(radius).unwrap_or(i32::MAX) }
+    }
+
+    /// Estimates the additional offset at which `image` matches `base` best.
+    ///
+    /// # Arguments
+    /// * `image` - The captured image.
+    /// * `base` - The map the image is merged into.
+    /// * `offset` - The offset of the image in the map derived from MELVINs position.
+    ///
+    /// # Returns
+    /// The additional offset and its full resolution match score, or `None` if the searched
+    /// region is not imaged enough for a reliable estimate.
+    #[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+    pub(crate) fn estimate<M: MapImage<Pixel = Rgb<u8>>>(
+        &self,
+        image: &RgbImage,
+        base: &M,
+        offset: Vec2D<u32>,
+    ) -> Option<(Vec2D<i32>, i32)> {
+        let tile = Self::TILE as i32;
+        let coarse_r = self.radius / tile;
+        if coarse_r == 0 {
+            return None;
+        }
+        let (img_tiles, _) = Self::downsample(image.width(), image.height(), |x, y| {
+            Some(*image.get_pixel(x, y))
+        });
+        let (w, h) = (image.width() / Self::TILE, image.height() / Self::TILE);
+        let margin = coarse_r * tile;
+        let pad = 2 * margin as u32;
+        let region_size = Vec2D::new(image.width() + pad, image.height() + pad);
+        let region_start = Vec2D::new(offset.x() as i32 - margin, offset.y() as i32 - margin);
+        let buffer = base.buffer();
+        let (map_w, map_h) = (buffer.width() as i32, buffer.height() as i32);
+        let (base_tiles, base_w) =
+            Self::downsample(region_size.x(), region_size.y(), |x, y| {
+                let map_x = (region_start.x() + x as i32).rem_euclid(map_w) as u32;
+                let map_y = (region_start.y() + y as i32).rem_euclid(map_h) as u32;
+                let px = *buffer.get_pixel(map_x, map_y);
+                (px != Rgb([0, 0, 0])).then_some(px)
+            });
+
+        let mut best: Option<(f32, Vec2D<i32>)> = None;
+        for dy in -coarse_r..=coarse_r {
+            for dx in -coarse_r..=coarse_r {
+                let (sx, sy) = ((dx + coarse_r) as u32, (dy + coarse_r) as u32);
+                let (mut diff, mut valid) = (0.0, 0u32);
+                for y in 0..h {
+                    for x in 0..w {
+                        let base_tile = base_tiles[((y + sy) * base_w + x + sx) as usize];
+                        if let (Some(b), Some(i)) = (base_tile, img_tiles[(y * w + x) as usize]) {
+                            diff += (b - i).abs();
+                            valid += 1;
+                        }
+                    }
+                }
+                #[allow(clippy::cast_precision_loss)]
+                if (valid as f32) < Self::MIN_VALID_SHARE * (w * h) as f32 {
+                    continue;
+                }
+                #[allow(clippy::cast_precision_loss)]
+                let mean = diff / valid as f32;
+                if best.is_none_or(|(best_mean, _)| mean < best_mean) {
+                    best = Some((mean, Vec2D::new(dx * tile, dy * tile)));
+                }
+            }
+        }
+        let (_, coarse) = best?;
+        Some(Self::refine(image, base, offset, coarse))
+    }
+
+    /// Refines a coarse shift by searching the surrounding pixels at full resolution.
+    ///
+    /// # Arguments
+    /// * `image` - The captured image.
+    /// * `base` - The map the image is merged into.
+    /// * `offset` - The offset of the image in the map derived from MELVINs position.
+    /// * `coarse` - The coarse additional offset.
+    ///
+    /// # Returns
+    /// The refined additional offset and its full resolution match score.
+    #[allow(clippy::cast_possible_wrap)]
+    fn refine<M: MapImage<Pixel = Rgb<u8>>>(
+        image: &RgbImage,
+        base: &M,
+        offset: Vec2D<u32>,
+        coarse: Vec2D<i32>,
+    ) -> (Vec2D<i32>, i32) {
+        let fine_r = Self::TILE as i32 / 2;
+        let mut refined = (coarse, i32::MIN);
+        for dy in -fine_r..=fine_r {
+            for dx in -fine_r..=fine_r {
+                let shift = Vec2D::new(coarse.x() + dx, coarse.y() + dy);
+                let current = Vec2D::new(offset.x() as i32, offset.y() as i32) + shift;
+                let score = base.match_score(current.wrap_around_map().to_unsigned(), image);
+                if score > refined.1 {
+                    refined = (shift, score);
+                }
+            }
+        }
+        refined
+    }
+
+    /// Downsamples an image into tiles of grayscale block means.
+    ///
+    /// # Arguments
+    /// * `width` - The width of the image in pixels.
+    /// * `height` - The height of the image in pixels.
+    /// * `pixel` - Returns the pixel at the given position, or `None` if it is not imaged.
+    ///
+    /// # Returns
+    /// The tiles in row-major order, `None` if a tile contains unimaged pixels, and the number
+    /// of tiles per row.
+    fn downsample(
+        width: u32,
+        height: u32,
+        pixel: impl Fn(u32, u32) -> Option<Rgb<u8>>,
+    ) -> (Vec<Option<f32>>, u32) {
+        let (w, h) = (width / Self::TILE, height / Self::TILE);
+        let mut tiles = Vec::with_capacity((w * h) as usize);
+        for ty in 0..h {
+            for tx in 0..w {
+                let mut sum = Some(0.0);
+                for y in ty * Self::TILE..(ty + 1) * Self::TILE {
+                    for x in tx * Self::TILE..(tx + 1) * Self::TILE {
+                        sum = sum.zip(pixel(x, y)).map(|(s, Rgb([r, g, b]))| {
+                            s + 0.299 * f32::from(r) + 0.587 * f32::from(g) + 0.114 * f32::from(b)
+                        });
+                    }
+                }
+                #[allow(clippy::cast_precision_loss)]
+                tiles.push(sum.map(|s| s / (Self::TILE * Self::TILE) as f32));
+            }
+        }
+        (tiles, w)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::imaging::ThumbnailMapImage;
+
+    #[test]
+    fn test_wide_offset_estimate() {
+        // Without a snapshot, a blank thumbnail is created
+        let blank = || ThumbnailMapImage::from_snapshot("melvin_missing_snapshot.png");
+        let texture = |x: u32, y: u32| {
+            let h = (x / 3).wrapping_mul(73_856_093) ^ (y / 3).wrapping_mul(19_349_663);
+            Rgb([(h % 251) as u8 + 1, (h % 241) as u8 + 1, (h % 239) as u8 + 1])
+        };
+        let mut thumb = blank();
+        let region = RgbImage::from_fn(200, 200, &texture);
+        thumb.update_area(Vec2D::new(100, 100), &region).unwrap();
+        let capture = RgbImage::from_fn(64, 64, |x, y| texture(x + 59, y + 43));
+
+        // Captured at (159, 143) while the position predicted (150, 150)
+        let estimator = OffsetEstimator::new(16);
+        let (shift, score) = estimator.estimate(&capture, &thumb, Vec2D::new(150, 150)).unwrap();
+        assert_eq!(shift, Vec2D::new(9, -7));
+        assert_eq!(score, 0);
+        assert!(estimator.estimate(&capture, &blank(), Vec2D::new(150, 150)).is_none());
+    }
+}
@@ -0,0 +1,102 @@
+use super::CameraAngle;
+use crate::flight_control::FlightComputer;
+use crate::objective::KnownImgObjective;
+use crate::util::Vec2D;
+use fixed::types::I32F32;
+
+/// The estimated imaging resolution of a zoned objective flyover.
+///
+/// Every lens captures the same number of pixels, so the ground resolution is the footprint of
+/// the lens per pixel. Images are only valid below the speed limit of the lens, which the
+/// detumble towards the objective enforces by braking, bounded by
+/// [`FlightComputer::max_detumble_brake`].
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+pub(crate) struct ResolutionEstimate {
+    /// The lens used for the flyover.
+    lens: CameraAngle,
+    /// The speed over the zone after the detumble.
+    flyover_speed: f64,
+    /// The achieved ground resolution in map units per pixel.
+    ground_res: f64,
+    /// Whether the flyover speed is within the speed limit of the lens.
+    usable: bool,
+}
+
+impl ResolutionEstimate {
+    /// The side length of the captured images in pixels, independent of the lens.
+    const SENSOR_SIDE_PX: f64 = 1000.0;
+
+    /// Returns the ground resolution of `lens` in map units per pixel.
+    pub(crate) fn ground_resolution(lens: CameraAngle) -> f64 {
+        f64::from(lens.get_square_side_length()) / Self::SENSOR_SIDE_PX
+    }
+
+    /// Returns the speed reached over the zone after detumbling for `lens`.
+    ///
+    /// # Arguments
+    /// * `lens` – The lens used over the zone.
+    /// * `vel` – The velocity after the exit burn.
+    pub(crate) fn detumbled_speed(lens: CameraAngle, vel: Vec2D<I32F32>) -> I32F32 {
+        let (speed, max) = (vel.abs(), lens.get_max_speed());
+        if speed <= max { speed } else { (speed - FlightComputer::max_detumble_brake()).max(max) }
+    }
+
+    /// Estimates the resolution of the images taken with `lens`.
+    ///
+    /// # Arguments
+    /// * `lens` – The lens used over the zone.
+    /// * `vel` – The velocity after the exit burn.
+    pub(crate) fn estimate(lens: CameraAngle, vel: Vec2D<I32F32>) -> Self {
+        let flyover_speed = Self::detumbled_speed(lens, vel);
+        Self {
+            lens,
+            flyover_speed: flyover_speed.to_num::<f64>(),
+            ground_res: Self::ground_resolution(lens),
+            usable: flyover_speed <= lens.get_max_speed(),
+        }
+    }
+
+    /// Estimates the resolution of the images of a zoned objective, which are always taken
+    /// with the lens required by the objective.
+    ///
+    /// # Arguments
+    /// * `zo` – The targeted objective.
+    /// * `vel` – The planned velocity after the exit burn.
+    pub(crate) fn for_objective(zo: &KnownImgObjective, vel: Vec2D<I32F32>) -> Self {
+        Self::estimate(zo.optic_required(), vel)
+    }
+
+    /// Returns the lens used for the flyover.
+    pub(crate) fn lens(&self) -> CameraAngle { self.lens }
+    /// Returns the speed over the zone after the detumble.
+    pub(crate) fn flyover_speed(&self) -> f64 { self.flyover_speed }
+    /// Returns the achieved ground resolution in map units per pixel.
+    pub(crate) fn ground_res(&self) -> f64 { self.ground_res }
+    /// Returns `true` if the flyover speed is within the speed limit of the lens.
+    pub(crate) fn is_usable(&self) -> bool { self.usable }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolution_estimate() {
+        let vel = |x: f64| Vec2D::new(I32F32::from_num(x), I32F32::ZERO);
+        let estimate = ResolutionEstimate::estimate;
+        let slow = estimate(CameraAngle::Narrow, vel(8.0));
+        assert!(slow.is_usable());
+        assert!((slow.ground_res() - 0.6).abs() < 1e-9);
+        assert!((slow.flyover_speed() - 8.0).abs() < 1e-9);
+
+        // The detumble brakes down to the speed limit of the lens
+        let brake = FlightComputer::max_detumble_brake().to_num::<f64>();
+        let braked = estimate(CameraAngle::Normal, vel(50.0 + brake));
+        assert!(braked.is_usable());
+        assert!((braked.flyover_speed() - 50.0).abs() < 1e-9);
+
+        // Too fast to brake in time
+        let blurred = estimate(CameraAngle::Narrow, vel(11.0 + brake));
+        assert!(!blurred.is_usable());
+    }
+}
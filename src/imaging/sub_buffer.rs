@@ -92,3 +92,51 @@ where
         self.buffer.blend_pixel(x_loc, y_loc, pixel);
     }
 }
+
+/// A contiguous part of a wrapping region that does not cross the buffer seam.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct SeamRect {
+    /// The offset of this part within the wrapping region.
+    pub(crate) src: Vec2D<u32>,
+    /// The offset of this part within the overall buffer.
+    pub(crate) dst: Vec2D<u32>,
+    /// The dimensions of this part.
+    pub(crate) size: Vec2D<u32>,
+}
+
+/// Splits a wrapping region at the x and y seams of the buffer into up to four contiguous parts.
+///
+/// # Arguments
+/// * `offset` - The offset of the region within the buffer.
+/// * `size` - The dimensions of the region, at most `buffer_size` on each axis.
+/// * `buffer_size` - The size of the entire buffer in pixels.
+///
+/// # Returns
+/// The contiguous parts, starting with the one at `offset`.
+pub(crate) fn split_at_seam(
+    offset: Vec2D<u32>,
+    size: Vec2D<u32>,
+    buffer_size: Vec2D<u32>,
+) -> Vec<SeamRect> {
+    let split_axis = |off: u32, len: u32, total: u32| {
+        let wrapped_off = off % total;
+        let first = len.min(total - wrapped_off);
+        let mut parts = vec![(0, wrapped_off, first)];
+        if first < len {
+            parts.push((first, 0, len - first));
+        }
+        parts
+    };
+    let x_parts = split_axis(offset.x(), size.x(), buffer_size.x());
+    let y_parts = split_axis(offset.y(), size.y(), buffer_size.y());
+    x_parts
+        .iter()
+        .flat_map(|&(src_x, dst_x, w)| {
+            y_parts.iter().map(move |&(src_y, dst_y, h)| SeamRect {
+                src: Vec2D::new(src_x, src_y),
+                dst: Vec2D::new(dst_x, dst_y),
+                size: Vec2D::new(w, h),
+            })
+        })
+        .collect()
+}
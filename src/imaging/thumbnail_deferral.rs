@@ -0,0 +1,72 @@
+use super::CameraAngle;
+use crate::util::Vec2D;
+use fixed::types::I32F32;
+
+/// Collects the map captures whose thumbnail update was deferred on low battery.
+///
+/// Once a capture is deferred, all further captures are deferred as well until the
+/// collected captures are taken, so that thumbnail updates are applied in capture order.
+#[derive(Debug, Default)]
+pub(super) struct ThumbnailDeferral {
+    /// The offsets and camera angles of the deferred captures, in capture order.
+    pending: Vec<(Vec2D<u32>, CameraAngle)>,
+}
+
+impl ThumbnailDeferral {
+    /// Defers the thumbnail update of a capture if the battery is below `threshold` or
+    /// earlier captures are still deferred.
+    ///
+    /// # Arguments
+    /// * `batt` - The battery level before the capture.
+    /// * `threshold` - The battery level below which thumbnail updates are deferred.
+    /// * `offset` - The offset of the capture in the map buffer.
+    /// * `angle` - The camera angle of the capture.
+    ///
+    /// # Returns
+    /// `true` if the thumbnail update was deferred.
+    pub(super) fn defer(
+        &mut self,
+        batt: I32F32,
+        threshold: I32F32,
+        offset: Vec2D<u32>,
+        angle: CameraAngle,
+    ) -> bool {
+        if self.pending.is_empty() && batt >= threshold {
+            return false;
+        }
+        self.pending.push((offset, angle));
+        true
+    }
+
+    /// Returns `true` while thumbnail updates are deferred.
+    pub(super) fn is_active(&self) -> bool { !self.pending.is_empty() }
+
+    /// Takes all deferred captures in capture order and ends the deferral.
+    pub(super) fn take(&mut self) -> Vec<(Vec2D<u32>, CameraAngle)> {
+        std::mem::take(&mut self.pending)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deferral_keeps_order_until_flushed() {
+        let threshold = I32F32::from_num(15);
+        let mut deferral = ThumbnailDeferral::default();
+        let (a, b, c) = (Vec2D::new(1, 2), Vec2D::new(3, 4), Vec2D::new(5, 6));
+
+        assert!(!deferral.defer(I32F32::from_num(50), threshold, a, CameraAngle::Wide));
+        assert!(!deferral.is_active());
+        assert!(deferral.defer(I32F32::from_num(10), threshold, b, CameraAngle::Narrow));
+        // Charged again, but earlier captures are still pending.
+        assert!(deferral.defer(I32F32::from_num(80), threshold, c, CameraAngle::Normal));
+        assert!(deferral.is_active());
+
+        let flushed = deferral.take();
+        assert_eq!(flushed, vec![(b, CameraAngle::Narrow), (c, CameraAngle::Normal)]);
+        assert!(!deferral.is_active() && deferral.take().is_empty());
+        assert!(!deferral.defer(I32F32::from_num(80), threshold, a, CameraAngle::Wide));
+    }
+}
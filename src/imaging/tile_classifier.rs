@@ -0,0 +1,151 @@
+use crate::util::{MapSize, Vec2D};
+use bitvec::{bitbox, order::Lsb0, prelude::BitBox};
+use fixed::types::I32F32;
+use image::{GenericImageView, RgbImage};
+
+/// Content class of a captured map tile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TileContent {
+    /// Uniform tile (e.g. open ocean) where re-imaging adds no value.
+    Featureless,
+    /// Tile with visible structure (e.g. coastlines or terrain).
+    FeatureRich,
+}
+
+impl TileContent {
+    /// Maximum luminance variance of a featureless tile.
+    const MAX_FEATURELESS_VARIANCE: f64 = 40.0;
+    /// Maximum luminance histogram entropy (in bits) of a featureless tile.
+    const MAX_FEATURELESS_ENTROPY: f64 = 3.5;
+    /// Number of luminance histogram bins used for the entropy estimate.
+    const HIST_BINS: usize = 64;
+
+    /// Classifies an image region by its luminance variance and histogram entropy.
+    ///
+    /// # Arguments
+    /// * `view` – The image region to classify.
+    ///
+    /// # Returns
+    /// * `TileContent::Featureless` if both variance and entropy are below their thresholds.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn classify<V: GenericImageView<Pixel = image::Rgb<u8>>>(view: &V) -> Self {
+        let mut hist = [0usize; Self::HIST_BINS];
+        let (mut sum, mut sum_sq, mut n) = (0.0, 0.0, 0usize);
+        for (_, _, px) in view.pixels() {
+            let [r, g, b] = px.0;
+            let lum = (299 * u32::from(r) + 587 * u32::from(g) + 114 * u32::from(b)) / 1000;
+            hist[lum as usize * Self::HIST_BINS / 256] += 1;
+            let lum_f = f64::from(lum);
+            sum += lum_f;
+            sum_sq += lum_f * lum_f;
+            n += 1;
+        }
+        if n == 0 {
+            return Self::FeatureRich;
+        }
+        let n_f = n as f64;
+        let mean = sum / n_f;
+        let variance = sum_sq / n_f - mean * mean;
+        let entropy: f64 = hist
+            .iter()
+            .filter(|c| **c > 0)
+            .map(|c| {
+                let p = *c as f64 / n_f;
+                -p * p.log2()
+            })
+            .sum();
+        if variance <= Self::MAX_FEATURELESS_VARIANCE && entropy <= Self::MAX_FEATURELESS_ENTROPY {
+            Self::Featureless
+        } else {
+            Self::FeatureRich
+        }
+    }
+}
+
+/// Coarse grid over the map marking cells that were imaged and classified as featureless.
+pub struct FeaturelessMap {
+    /// One bit per grid cell, `true` if the cell was classified as featureless.
+    cells: BitBox<usize, Lsb0>,
+    /// The dimensions of the grid in cells.
+    grid_size: Vec2D<u32>,
+}
+
+impl FeaturelessMap {
+    /// Side length of a single grid cell in map pixels.
+    const CELL_SIZE: u32 = 200;
+
+    /// Creates a new [`FeaturelessMap`] with no featureless cells.
+    pub fn new() -> Self {
+        let map_size = Vec2D::<I32F32>::map_size();
+        let grid_size = Vec2D::new(
+            map_size.x().to_num::<u32>() / Self::CELL_SIZE,
+            map_size.y().to_num::<u32>() / Self::CELL_SIZE,
+        );
+        let len = (grid_size.x() * grid_size.y()) as usize;
+        Self { cells: bitbox![usize, Lsb0; 0; len], grid_size }
+    }
+
+    /// Classifies all grid cells fully covered by a newly captured image.
+    ///
+    /// # Arguments
+    /// * `offset` – The wrapped top-left map position of the image.
+    /// * `image` – The captured image.
+    pub fn update_from_image(&mut self, offset: Vec2D<u32>, image: &RgbImage) {
+        let first_cell = Vec2D::new(
+            offset.x().div_ceil(Self::CELL_SIZE),
+            offset.y().div_ceil(Self::CELL_SIZE),
+        );
+        let end_cell =
+            Vec2D::new(offset.x() + image.width(), offset.y() + image.height()) / Self::CELL_SIZE;
+        for cell_y in first_cell.y()..end_cell.y() {
+            for cell_x in first_cell.x()..end_cell.x() {
+                let local_x = cell_x * Self::CELL_SIZE - offset.x();
+                let local_y = cell_y * Self::CELL_SIZE - offset.y();
+                let view = image.view(local_x, local_y, Self::CELL_SIZE, Self::CELL_SIZE);
+                let is_featureless = TileContent::classify(&*view) == TileContent::Featureless;
+                let i = self.cell_index(cell_x, cell_y);
+                self.cells.set(i, is_featureless);
+            }
+        }
+    }
+
+    /// Returns `true` if the grid cell containing the given map position is featureless.
+    ///
+    /// # Arguments
+    /// * `pos` – A map position.
+    pub fn is_featureless(&self, pos: Vec2D<I32F32>) -> bool {
+        let wrapped = pos.wrap_around_map();
+        let cell_x = wrapped.x().to_num::<u32>() / Self::CELL_SIZE;
+        let cell_y = wrapped.y().to_num::<u32>() / Self::CELL_SIZE;
+        self.cells[self.cell_index(cell_x, cell_y)]
+    }
+
+    /// Returns the bit index of a (possibly wrapping) cell coordinate.
+    fn cell_index(&self, cell_x: u32, cell_y: u32) -> usize {
+        let x = cell_x % self.grid_size.x();
+        let y = cell_y % self.grid_size.y();
+        (y * self.grid_size.x() + x) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgb;
+
+    #[test]
+    fn test_classify_ocean_and_terrain() {
+        let ocean = RgbImage::from_fn(600, 600, |x, y| Rgb([10, 40, 100 + ((x + y) % 3) as u8]));
+        let terrain = RgbImage::from_fn(600, 600, |x, y| {
+            Rgb([(x * 7 % 255) as u8, (y * 13 % 255) as u8, ((x + y) % 255) as u8])
+        });
+        assert_eq!(TileContent::classify(&ocean), TileContent::Featureless);
+        assert_eq!(TileContent::classify(&terrain), TileContent::FeatureRich);
+
+        let mut map = FeaturelessMap::new();
+        map.update_from_image(Vec2D::new(21400, 0), &ocean);
+        assert!(map.is_featureless(Vec2D::new(I32F32::from_num(100), I32F32::from_num(100))));
+        map.update_from_image(Vec2D::new(21400, 0), &terrain);
+        assert!(!map.is_featureless(Vec2D::new(I32F32::from_num(100), I32F32::from_num(100))));
+    }
+}
@@ -0,0 +1,272 @@
+use super::{ImageCodec, ImagingError, map_image::EncodedImageExtract, sub_buffer::split_at_seam};
+use crate::util::Vec2D;
+use image::{GenericImage, GenericImageView, Rgb, RgbImage, imageops};
+use std::collections::HashMap;
+
+/// Identifies a tile of the [`TilePyramid`] by its zoom level, column and row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct TileId {
+    /// The zoom level, `0` being the coarsest.
+    pub(crate) zoom: u32,
+    /// The column of the tile.
+    pub(crate) x: u32,
+    /// The row of the tile.
+    pub(crate) y: u32,
+}
+
+/// An encoded tile kept in the cache of the [`TilePyramid`].
+struct CachedTile {
+    /// The codec of `data`.
+    codec: ImageCodec,
+    /// The dimensions of the tile in pixels.
+    size: Vec2D<u32>,
+    /// The encoded tile.
+    data: Vec<u8>,
+}
+
+/// A multi-resolution pyramid of square map tiles for panning and zooming in the console.
+///
+/// Every zoom level scales the map down by [`TilePyramid::ZOOM_FACTOR`] relative to the next
+/// finer one, the finest level shows the map at full resolution. The coarse levels are kept
+/// as downscaled images, which are updated incrementally as new images land on the map, while
+/// the fine levels are rendered from the fullsize map on request. Encoded tiles are cached
+/// until the map area they cover changes.
+pub(crate) struct TilePyramid {
+    /// The size of the map in pixels.
+    map_size: Vec2D<u32>,
+    /// The downscaled maps of the stored zoom levels, built on first use.
+    levels: Option<Vec<RgbImage>>,
+    /// The encoded tiles by their ID.
+    cache: HashMap<TileId, CachedTile>,
+}
+
+impl TilePyramid {
+    /// The side length of a tile in pixels.
+    pub(crate) const TILE_SIZE: u32 = 256;
+    /// The number of zoom levels.
+    pub(crate) const ZOOM_LEVELS: u32 = 4;
+    /// The scale factor between two consecutive zoom levels.
+    const ZOOM_FACTOR: u32 = 4;
+    /// The number of coarse zoom levels that are kept as downscaled images.
+    const STORED_LEVELS: u32 = 2;
+    /// The maximum number of cached encoded tiles.
+    const MAX_CACHED_TILES: usize = 256;
+
+    /// Creates an empty [`TilePyramid`].
+    ///
+    /// # Arguments
+    /// * `map_size` – The size of the map in pixels.
+    pub(crate) fn new(map_size: Vec2D<u32>) -> Self {
+        Self { map_size, levels: None, cache: HashMap::new() }
+    }
+
+    /// Returns the number of map pixels per tile pixel at a zoom level.
+    fn scale(zoom: u32) -> u32 { Self::ZOOM_FACTOR.pow(Self::ZOOM_LEVELS - 1 - zoom) }
+
+    /// Returns the size of the map in pixels at a zoom level.
+    fn level_size(&self, zoom: u32) -> Vec2D<u32> {
+        let scale = Self::scale(zoom);
+        Vec2D::new(self.map_size.x().div_ceil(scale), self.map_size.y().div_ceil(scale))
+    }
+
+    /// Returns the number of tile columns and rows at a zoom level, which is empty if the zoom
+    /// level does not exist.
+    ///
+    /// # Arguments
+    /// * `zoom` – The zoom level, `0` being the coarsest.
+    pub(crate) fn grid(&self, zoom: u32) -> Vec2D<u32> {
+        if zoom >= Self::ZOOM_LEVELS {
+            return Vec2D::new(0, 0);
+        }
+        let size = self.level_size(zoom);
+        Vec2D::new(size.x().div_ceil(Self::TILE_SIZE), size.y().div_ceil(Self::TILE_SIZE))
+    }
+
+    /// Returns `true` if the tile lies within the pyramid.
+    fn contains(&self, id: TileId) -> bool {
+        let grid = self.grid(id.zoom);
+        id.x < grid.x() && id.y < grid.y()
+    }
+
+    /// Downscales a contiguous area of the map to a zoom level.
+    ///
+    /// # Arguments
+    /// * `map` – The fullsize map.
+    /// * `offset` – The top-left corner of the area at the zoom level.
+    /// * `size` – The dimensions of the area at the zoom level.
+    /// * `zoom` – The zoom level.
+    fn render<I: GenericImageView<Pixel = Rgb<u8>>>(
+        &self,
+        map: &I,
+        offset: Vec2D<u32>,
+        size: Vec2D<u32>,
+        zoom: u32,
+    ) -> RgbImage {
+        let scale = Self::scale(zoom);
+        let src_offset = Vec2D::new(offset.x() * scale, offset.y() * scale);
+        let src_size = Vec2D::new(
+            (size.x() * scale).min(self.map_size.x() - src_offset.x()),
+            (size.y() * scale).min(self.map_size.y() - src_offset.y()),
+        );
+        let area = map.view(src_offset.x(), src_offset.y(), src_size.x(), src_size.y());
+        if scale == 1 {
+            RgbImage::from_fn(src_size.x(), src_size.y(), |x, y| area.get_pixel(x, y))
+        } else {
+            imageops::thumbnail(&*area, size.x(), size.y())
+        }
+    }
+
+    /// Builds the downscaled maps of the stored zoom levels, each from the next finer one.
+    ///
+    /// # Arguments
+    /// * `map` – The fullsize map.
+    fn build_levels<I: GenericImageView<Pixel = Rgb<u8>>>(&mut self, map: &I) {
+        let finest = Self::STORED_LEVELS - 1;
+        let mut levels = vec![self.render(map, Vec2D::new(0, 0), self.level_size(finest), finest)];
+        for zoom in (0..finest).rev() {
+            let size = self.level_size(zoom);
+            levels.insert(0, imageops::thumbnail(&levels[0], size.x(), size.y()));
+        }
+        self.levels = Some(levels);
+    }
+
+    /// Updates the pyramid after an area of the map changed.
+    ///
+    /// Cached tiles covering the area are dropped and the stored zoom levels are re-rendered
+    /// within the area, if they were already built.
+    ///
+    /// # Arguments
+    /// * `map` – The updated fullsize map.
+    /// * `offset` – The top-left corner of the changed area, which may wrap around the map.
+    /// * `size` – The dimensions of the changed area.
+    pub(crate) fn update_area<I: GenericImageView<Pixel = Rgb<u8>>>(
+        &mut self,
+        map: &I,
+        offset: Vec2D<u32>,
+        size: Vec2D<u32>,
+    ) {
+        for rect in split_at_seam(offset, size, self.map_size) {
+            for zoom in 0..Self::ZOOM_LEVELS {
+                let scale = Self::scale(zoom);
+                let start = rect.dst / scale;
+                let end = Vec2D::new(
+                    (rect.dst.x() + rect.size.x()).div_ceil(scale),
+                    (rect.dst.y() + rect.size.y()).div_ceil(scale),
+                );
+                let size = Vec2D::new(end.x() - start.x(), end.y() - start.y());
+                let first = start / Self::TILE_SIZE;
+                let last = Vec2D::new(end.x() - 1, end.y() - 1) / Self::TILE_SIZE;
+                self.cache.retain(|id, _| {
+                    id.zoom != zoom
+                        || !(first.x()..=last.x()).contains(&id.x)
+                        || !(first.y()..=last.y()).contains(&id.y)
+                });
+                if zoom >= Self::STORED_LEVELS || self.levels.is_none() {
+                    continue;
+                }
+                let rendered = self.render(map, start, size, zoom);
+                if let Some(level) = self.levels.as_mut().and_then(|l| l.get_mut(zoom as usize)) {
+                    level.copy_from(&rendered, start.x(), start.y()).ok();
+                }
+            }
+        }
+    }
+
+    /// Returns an encoded tile, rendering it if it is not cached.
+    ///
+    /// # Arguments
+    /// * `map` – The fullsize map.
+    /// * `id` – The requested tile.
+    /// * `codec` – The codec used to encode the tile.
+    ///
+    /// # Returns
+    /// * The encoded tile with its offset and size at its zoom level, or `None` if the tile
+    ///   lies outside of the pyramid.
+    ///
+    /// # Errors
+    /// * An [`ImagingError`] if the tile could not be encoded.
+    pub(crate) fn tile<I: GenericImageView<Pixel = Rgb<u8>>>(
+        &mut self,
+        map: &I,
+        id: TileId,
+        codec: ImageCodec,
+    ) -> Result<Option<EncodedImageExtract>, ImagingError> {
+        if !self.contains(id) {
+            return Ok(None);
+        }
+        let offset = Vec2D::new(id.x * Self::TILE_SIZE, id.y * Self::TILE_SIZE);
+        if let Some(tile) = self.cache.get(&id).filter(|tile| tile.codec == codec) {
+            let data = tile.data.clone();
+            return Ok(Some(EncodedImageExtract { offset, size: tile.size, data, codec }));
+        }
+        let level_size = self.level_size(id.zoom);
+        let size = Vec2D::new(
+            Self::TILE_SIZE.min(level_size.x() - offset.x()),
+            Self::TILE_SIZE.min(level_size.y() - offset.y()),
+        );
+        let image = if id.zoom < Self::STORED_LEVELS {
+            if self.levels.is_none() {
+                self.build_levels(map);
+            }
+            let level = &self.levels.as_ref().unwrap()[id.zoom as usize];
+            level.view(offset.x(), offset.y(), size.x(), size.y()).to_image()
+        } else {
+            self.render(map, offset, size, id.zoom)
+        };
+        let data = codec.encode(&image)?;
+        if self.cache.len() >= Self::MAX_CACHED_TILES {
+            self.cache.clear();
+        }
+        self.cache.insert(id, CachedTile { codec, size, data: data.clone() });
+        Ok(Some(EncodedImageExtract { offset, size, data, codec }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tile_pyramid() {
+        let map_size = Vec2D::new(2048, 1024);
+        let mut map = RgbImage::from_pixel(map_size.x(), map_size.y(), Rgb([10, 20, 30]));
+        let mut pyramid = TilePyramid::new(map_size);
+        assert_eq!(pyramid.grid(0), Vec2D::new(1, 1));
+        assert_eq!(pyramid.grid(1), Vec2D::new(1, 1));
+        assert_eq!(pyramid.grid(2), Vec2D::new(2, 1));
+        assert_eq!(pyramid.grid(3), Vec2D::new(8, 4));
+
+        let tile = |p: &mut TilePyramid, map: &RgbImage, zoom, x, y| {
+            let extract = p.tile(map, TileId { zoom, x, y }, ImageCodec::Png).unwrap().unwrap();
+            (extract.offset, extract.size, ImageCodec::decode(&extract.data).unwrap().to_rgb8())
+        };
+        let (offset, size, coarse) = tile(&mut pyramid, &map, 0, 0, 0);
+        assert_eq!((offset, size), (Vec2D::new(0, 0), Vec2D::new(32, 16)));
+        assert_eq!(*coarse.get_pixel(5, 5), Rgb([10, 20, 30]));
+        let (offset, size, fine) = tile(&mut pyramid, &map, 3, 7, 3);
+        assert_eq!((offset, size), (Vec2D::new(1792, 768), Vec2D::new(256, 256)));
+        assert_eq!(*fine.get_pixel(0, 0), Rgb([10, 20, 30]));
+        assert_eq!(pyramid.grid(4), Vec2D::new(0, 0));
+        for id in [TileId { zoom: 3, x: 8, y: 0 }, TileId { zoom: 4, x: 0, y: 0 }] {
+            assert!(pyramid.tile(&map, id, ImageCodec::Png).unwrap().is_none());
+        }
+
+        // An update across the seam reaches both map edges in all levels
+        let patch = RgbImage::from_pixel(128, 128, Rgb([200, 0, 0]));
+        let patch_offset = Vec2D::new(1984, 960);
+        for rect in split_at_seam(patch_offset, Vec2D::new(128, 128), map_size) {
+            let part = patch.view(rect.src.x(), rect.src.y(), rect.size.x(), rect.size.y());
+            map.copy_from(&*part, rect.dst.x(), rect.dst.y()).unwrap();
+        }
+        pyramid.update_area(&map, patch_offset, Vec2D::new(128, 128));
+        let (_, _, fine) = tile(&mut pyramid, &map, 3, 7, 3);
+        assert_eq!(*fine.get_pixel(255, 255), Rgb([200, 0, 0]));
+        assert_eq!(*fine.get_pixel(0, 0), Rgb([10, 20, 30]));
+        let (_, _, fine) = tile(&mut pyramid, &map, 3, 0, 0);
+        assert_eq!(*fine.get_pixel(0, 0), Rgb([200, 0, 0]));
+        let (_, _, coarse) = tile(&mut pyramid, &map, 0, 0, 0);
+        assert_eq!(*coarse.get_pixel(31, 15), Rgb([200, 0, 0]));
+        assert_eq!(*coarse.get_pixel(0, 0), Rgb([200, 0, 0]));
+        assert_eq!(*coarse.get_pixel(16, 8), Rgb([10, 20, 30]));
+    }
+}
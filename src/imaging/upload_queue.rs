@@ -0,0 +1,201 @@
+use crate::util::ImgObjectiveId;
+use crate::{error, warn};
+use chrono::{DateTime, TimeDelta, Utc};
+use std::path::{Path, PathBuf};
+
+/// An objective image whose upload failed and is retried later.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub(crate) struct PendingUpload {
+    /// The objective the image belongs to.
+    objective_id: ImgObjectiveId,
+    /// The path of the exported image.
+    path: PathBuf,
+    /// The end of the objective, after which the upload is given up.
+    deadline: DateTime<Utc>,
+    /// The number of failed upload attempts.
+    attempts: u32,
+    /// Whether the operator was already alerted about the approaching deadline.
+    alerted: bool,
+}
+
+impl PendingUpload {
+    /// Returns the objective the image belongs to.
+    pub(crate) fn objective_id(&self) -> ImgObjectiveId { self.objective_id }
+    /// Returns the path of the exported image.
+    pub(crate) fn path(&self) -> &Path { &self.path }
+    /// Returns the end of the objective.
+    pub(crate) fn deadline(&self) -> DateTime<Utc> { self.deadline }
+    /// Returns the number of failed upload attempts.
+    pub(crate) fn attempts(&self) -> u32 { self.attempts }
+}
+
+/// Queue of rejected objective image uploads, persisted as JSON so that pending uploads
+/// survive a restart.
+///
+/// Every modification is written to disk immediately. Uploads are retried on the next comms
+/// opportunity until they succeed or the objective ends.
+#[derive(Debug)]
+pub(crate) struct UploadQueue {
+    /// The file the queue is persisted to.
+    file: PathBuf,
+    /// The pending uploads, ordered by deadline.
+    pending: Vec<PendingUpload>,
+}
+
+impl UploadQueue {
+    /// The time before the deadline of a pending upload at which the operator is alerted.
+    pub(crate) const ALERT_LEAD: TimeDelta = TimeDelta::minutes(30);
+
+    /// Opens the queue persisted at `file`, starting empty if it does not exist or is invalid.
+    ///
+    /// # Arguments
+    /// * `path` – The file the queue is persisted to.
+    pub(crate) fn open<P: AsRef<Path>>(path: P) -> Self {
+        let file = path.as_ref().to_path_buf();
+        let pending = match std::fs::read(&file) {
+            Ok(data) => serde_json::from_slice(&data).unwrap_or_else(|e| {
+                warn!("Discarding invalid pending upload queue: {e}.");
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+        Self { file, pending }
+    }
+
+    /// Writes the queue to its file.
+    fn persist(&self) {
+        let res = serde_json::to_vec_pretty(&self.pending)
+            .map_err(std::io::Error::from)
+            .and_then(|data| std::fs::write(&self.file, data));
+        if let Err(e) = res {
+            error!("Failed to persist pending upload queue: {e}.");
+        }
+    }
+
+    /// Returns the pending uploads, ordered by deadline.
+    pub(crate) fn pending(&self) -> &[PendingUpload] { &self.pending }
+
+    /// Returns `true` if no uploads are pending.
+    pub(crate) fn is_empty(&self) -> bool { self.pending.is_empty() }
+
+    /// Adds a failed upload, replacing a pending upload of the same objective.
+    ///
+    /// # Arguments
+    /// * `objective_id` – The objective the image belongs to.
+    /// * `path` – The path of the exported image.
+    /// * `deadline` – The end of the objective.
+    pub(crate) fn push(
+        &mut self,
+        objective_id: ImgObjectiveId,
+        path: PathBuf,
+        deadline: DateTime<Utc>,
+    ) {
+        let attempts = self.remove_entry(objective_id).map_or(1, |p| p.attempts + 1);
+        let pos = self.pending.partition_point(|p| p.deadline <= deadline);
+        let upload = PendingUpload { objective_id, path, deadline, attempts, alerted: false };
+        self.pending.insert(pos, upload);
+        self.persist();
+    }
+
+    /// Removes the pending upload of an objective without persisting the queue.
+    fn remove_entry(&mut self, objective_id: ImgObjectiveId) -> Option<PendingUpload> {
+        let pos = self.pending.iter().position(|p| p.objective_id == objective_id)?;
+        Some(self.pending.remove(pos))
+    }
+
+    /// Removes the pending upload of an objective, e.g. after it succeeded.
+    ///
+    /// # Arguments
+    /// * `objective_id` – The objective whose upload is removed.
+    pub(crate) fn remove(&mut self, objective_id: ImgObjectiveId) {
+        if self.remove_entry(objective_id).is_some() {
+            self.persist();
+        }
+    }
+
+    /// Counts another failed attempt of a pending upload.
+    ///
+    /// # Arguments
+    /// * `objective_id` – The objective whose upload failed again.
+    pub(crate) fn record_attempt(&mut self, objective_id: ImgObjectiveId) {
+        if let Some(upload) = self.pending.iter_mut().find(|p| p.objective_id == objective_id) {
+            upload.attempts += 1;
+            self.persist();
+        }
+    }
+
+    /// Removes and returns the pending uploads whose deadline passed.
+    ///
+    /// # Arguments
+    /// * `now` – The current time.
+    pub(crate) fn take_expired(&mut self, now: DateTime<Utc>) -> Vec<PendingUpload> {
+        let n_expired = self.pending.partition_point(|p| p.deadline <= now);
+        let expired: Vec<_> = self.pending.drain(..n_expired).collect();
+        if !expired.is_empty() {
+            self.persist();
+        }
+        expired
+    }
+
+    /// Returns the pending uploads whose deadline is within [`UploadQueue::ALERT_LEAD`] and
+    /// that were not alerted yet, marking them as alerted.
+    ///
+    /// # Arguments
+    /// * `now` – The current time.
+    pub(crate) fn take_alerts(&mut self, now: DateTime<Utc>) -> Vec<PendingUpload> {
+        let mut alerts = Vec::new();
+        for upload in self.pending.iter_mut().filter(|p| !p.alerted) {
+            if upload.deadline - now <= Self::ALERT_LEAD {
+                upload.alerted = true;
+                alerts.push(upload.clone());
+            }
+        }
+        if !alerts.is_empty() {
+            self.persist();
+        }
+        alerts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upload_queue() {
+        let file = std::env::temp_dir().join(format!("melvin_uploads_{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&file);
+        let now = Utc::now();
+        let (a, b, c) = (ImgObjectiveId::new(1), ImgObjectiveId::new(2), ImgObjectiveId::new(3));
+
+        let mut queue = UploadQueue::open(&file);
+        assert!(queue.is_empty());
+        queue.push(a, PathBuf::from("zo_1.png"), now + TimeDelta::hours(2));
+        queue.push(b, PathBuf::from("zo_2.png"), now + TimeDelta::minutes(10));
+        queue.push(c, PathBuf::from("zo_3.png"), now - TimeDelta::minutes(1));
+        queue.push(a, PathBuf::from("zo_1_0.png"), now + TimeDelta::hours(2));
+        queue.record_attempt(b);
+
+        // The queue is restored from disk ordered by deadline
+        let mut queue = UploadQueue::open(&file);
+        let ids: Vec<_> = queue.pending().iter().map(PendingUpload::objective_id).collect();
+        assert_eq!(ids, [c, b, a]);
+        assert_eq!(queue.pending()[2].path(), Path::new("zo_1_0.png"));
+        assert_eq!(queue.pending()[2].attempts(), 2);
+        assert_eq!(queue.pending()[1].attempts(), 2);
+
+        let expired = queue.take_expired(now);
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].objective_id(), c);
+        let alerts = queue.take_alerts(now);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].objective_id(), b);
+        assert!(queue.take_alerts(now).is_empty());
+        assert!(UploadQueue::open(&file).take_alerts(now).is_empty());
+
+        queue.remove(b);
+        queue.remove(a);
+        assert!(UploadQueue::open(&file).is_empty());
+        std::fs::remove_file(&file).unwrap();
+    }
+}
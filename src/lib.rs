@@ -0,0 +1,457 @@
+#![allow(dead_code, clippy::similar_names)]
+#![allow(
+    clippy::must_use_candidate,
+    clippy::return_self_not_must_use,
+    clippy::missing_panics_doc,
+    clippy::missing_errors_doc
+)]
+#![warn(clippy::shadow_reuse, clippy::shadow_same, clippy::builtin_type_shadow)]
+//! Welcome to the onboard software for **Team 03 — "Cache us if you can"** competing in the **2024/2025 ESA Computer in a Room Challenge**. 
+//! This repository contains the embedded code running on the simulated MELVIN onboard computer, responsible 
+//! for command execution, event detection, task scheduling and DRS communication during the mission.
+//!
+//! The mission itself is started by the `melvin-ob` binary through [`run_configured_mission`],
+//! or offline against a simulated backend through [`run_dry_mission`]. Analysis tools
+//! and alternative frontends can reuse the core subsystems through the read-only facade in [`api`].
+
+pub mod api;
+mod console_communication;
+mod flight_control;
+mod http_handler;
+mod imaging;
+mod mode_control;
+mod objective;
+mod scheduling;
+mod util;
+
+use crate::console_communication::{TelemetryEndpoint, TelemetrySource};
+use crate::flight_control::{
+    EscalatingPolicy, FlightComputer, FlightState,
+    orbit::{
+        ClosedOrbit, IndexedOrbitPosition, OrbitBase, OrbitCharacteristics, OrbitUsabilityError,
+    },
+};
+use crate::http_handler::http_client::HTTPClient;
+use crate::imaging::{CameraAngle, FullsizeMapImage, MAP_BUFFER_PATH, MapQualityReport};
+use crate::mode_control::{
+    ModeContext, OpExitSignal, TransitionPhase, run_coverage_guard, run_coverage_reconciler, run_end_of_mission,
+    run_map_scrubber, run_orbit_replanner, shutdown, wait_for_shutdown,
+    mode::{GlobalMode, OrbitReturnMode},
+};
+use crate::objective::BeaconController;
+use crate::scheduling::{ScheduleSimulator, TaskController, VirtualClock};
+use crate::util::{
+    EVENT_BUS, Keychain, KeychainWithOrbit, MISSION_JOURNAL, MapSize, MissionConfig,
+    RuntimeReport, WorkerPool, logger::JsonDump, spawn_supervised,
+};
+use chrono::TimeDelta;
+use fixed::types::I32F32;
+use std::{path::Path, sync::Arc, time::Duration};
+use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
+
+/// Shared 0-length timedelta in chrono units
+const DT_0: TimeDelta = TimeDelta::seconds(0);
+/// Shared 0-length timedelta in std time units
+const DT_0_STD: Duration = Duration::from_secs(0);
+
+/// Static orbit velocity for closed orbit
+const STATIC_ORBIT_VEL: (I32F32, I32F32) = (I32F32::lit("6.40"), I32F32::lit("7.40"));
+/// Directory below `imaging.base_path` that active beacon objectives are persisted to
+const BEACON_STATE_DIR: &str = "beacon_state";
+
+/// Builds the multi-threaded tokio runtime for [`run_configured_mission`].
+///
+/// The number of worker threads and the blocking pool size are taken from the `threads`
+/// section of the mission configuration, detecting the available cores if unset.
+pub fn build_runtime() -> std::io::Result<tokio::runtime::Runtime> {
+    let config = MissionConfig::get();
+    let threads = &config.threads;
+    info!(
+        "Runtime sizing: {} workers, {} blocking, {} imaging and {} planning jobs.",
+        threads.resolved_workers(),
+        threads.max_blocking_threads,
+        threads.resolved_imaging_jobs(),
+        threads.resolved_planning_jobs()
+    );
+    WorkerPool::build_runtime()
+}
+
+/// Runs the full mission against the DRS backend given by `network.base_url` of the mission
+/// configuration: initializes all subsystems and then executes the global mode state machine
+/// until the process is asked to terminate.
+pub async fn run_configured_mission() {
+    let base_url = MissionConfig::get().network.base_url.clone();
+    run_mission_at(&base_url).await;
+}
+
+/// Runs the full mission against the DRS backend at `base_url`, recording all requests if
+/// `network.record_session` is set.
+///
+/// # Arguments
+/// * `base_url` – The base URL of the DRS backend.
+async fn run_mission_at(base_url: &str) {
+    let path = MissionConfig::get().network.record_session.clone();
+    let client = if path.is_empty() {
+        HTTPClient::new(base_url)
+    } else {
+        info!("Recording all DRS requests to {path}.");
+        HTTPClient::recording(base_url, Path::new(&path))
+            .unwrap_or_else(|e| fatal!("Failed to create DRS session {path}: {e}"))
+    };
+    run(client).await;
+}
+
+/// Runs the full mission against the DRS responses recorded in a session file, so that
+/// behavior observed during a live run can be reproduced offline.
+///
+/// The replay stops as soon as MELVIN sends a request that was not recorded, as the run then
+/// diverged from the recorded one.
+///
+/// # Arguments
+/// * `path` – The path of the session file recorded with `network.record_session`.
+///
+/// # Returns
+/// `false` if the session could not be loaded or the replay diverged.
+pub async fn run_replayed_mission(path: &str) -> bool {
+    warn!("Replay mode: all DRS requests are answered from the session {path}!");
+    match HTTPClient::replaying(Path::new(path)) {
+        Ok(client) => run(client).await,
+        Err(e) => {
+            error!("Failed to load DRS session {path}: {e}");
+            false
+        }
+    }
+}
+
+/// Runs the full mission offline against a deterministic simulated DRS backend, so scheduling
+/// and mode logic can be exercised end-to-end without a real server.
+pub async fn run_dry_mission() {
+    warn!("Dry-run mode: all DRS requests are answered by the simulated backend!");
+    run(HTTPClient::simulated()).await;
+}
+
+/// Initializes all subsystems on top of `client` and executes the global mode state machine
+/// until a `SIGTERM` or `SIGINT` triggers a graceful [`shutdown`].
+///
+/// # Arguments
+/// * `client` – The client used for all DRS requests.
+///
+/// # Returns
+/// `false` if the client replays a session and the run diverged from it.
+async fn run(client: HTTPClient) -> bool {
+    MissionConfig::init();
+    let diverged = client.session().map_or_else(CancellationToken::new, |s| s.diverged().clone());
+    let mut shutdown_signal = tokio::spawn(wait_for_shutdown());
+    let (context, start_mode) = tokio::select! {
+        res = init(client) => res,
+        signal = &mut shutdown_signal => {
+            warn!("Received {} during initialization, exiting!", signal.unwrap_or("signal"));
+            return true;
+        }
+        () = diverged.cancelled() => {
+            error!("Stopping the replay during initialization, the run diverged!");
+            return false;
+        }
+    };
+    tokio::spawn(run_end_of_mission(Arc::clone(&context)));
+    tokio::spawn(run_coverage_guard(Arc::clone(&context)));
+    tokio::spawn(run_coverage_reconciler(Arc::clone(&context)));
+    tokio::spawn(run_map_scrubber(Arc::clone(&context)));
+    tokio::spawn(run_orbit_replanner(Arc::clone(&context)));
+    let supervisor = Arc::clone(context.super_v());
+    tokio::spawn(async move { supervisor.run_watchdog(EscalatingPolicy::default()).await });
+    let supervisor = Arc::clone(context.super_v());
+    tokio::spawn(async move { supervisor.run_link_monitor().await });
+    let (supervisor, c_orbit) = (Arc::clone(context.super_v()), context.k().c_orbit());
+    tokio::spawn(async move { supervisor.run_orbit_monitor(c_orbit).await });
+    tokio::spawn(MISSION_JOURNAL.run_bus_recorder());
+    let checkpointer = Arc::clone(context.checkpointer());
+    tokio::spawn(async move { checkpointer.run().await });
+    context.k().con().spawn_orbit_heatmap(context.k().c_orbit());
+    context.k().con().spawn_coverage_forecast(
+        context.k().c_orbit(),
+        context.o_ch_watch(),
+        context.k().f_cont(),
+    );
+    let _telemetry = TelemetryEndpoint::start(Arc::clone(&context) as Arc<dyn TelemetrySource>);
+
+    tokio::select! {
+        () = run_modes(Arc::clone(&context), start_mode) => true,
+        signal = shutdown_signal => {
+            shutdown(&context, signal.unwrap_or("signal")).await;
+            true
+        }
+        () = diverged.cancelled() => {
+            error!("Stopping the replay, the run diverged from the recorded session!");
+            false
+        }
+    }
+}
+
+/// Executes the global mode state machine forever, starting with `start_mode`.
+///
+/// # Arguments
+/// * `context` – The shared mode context.
+/// * `start_mode` – The first global mode.
+async fn run_modes(context: Arc<ModeContext>, start_mode: Box<dyn GlobalMode>) {
+    let mut global_mode = start_mode;
+    loop {
+        let phase = context.o_ch().mode_switches();
+        info!("Starting phase {phase} in {}!", global_mode.type_name());
+        context.set_active_mode(global_mode.type_name());
+        context.super_v().set_abortable(global_mode.accepts_operator_abort());
+        context.persist_mode(global_mode.as_ref());
+        context.checkpointer().checkpoint("mode switch").await;
+        match global_mode.init_mode_guarded(Arc::clone(&context)).await {
+            OpExitSignal::ReInit(mode) => {
+                context.record_transition(TransitionPhase::Init, mode.type_name());
+                global_mode = mode;
+                continue;
+            }
+            OpExitSignal::Continue => (),
+        };
+        match global_mode.exec_task_queue(Arc::clone(&context)).await {
+            OpExitSignal::ReInit(mode) => {
+                context.record_transition(TransitionPhase::Exec, mode.type_name());
+                global_mode = mode;
+                continue;
+            }
+            OpExitSignal::Continue => {
+                global_mode = global_mode.exit_mode(Arc::clone(&context)).await;
+                context.record_transition(TransitionPhase::Exit, global_mode.type_name());
+                continue;
+            }
+        }
+    }
+}
+
+/// Plans an optimal orbit schedule for the exported orbit and fast-forwards it through the
+/// [`ScheduleSimulator`] without contacting the DRS. The report is dumped as JSON.
+///
+/// The orbit is imported like at mission start, so `orbit.try_import` must be set.
+///
+/// # Arguments
+/// * `batt` – The initial battery level, MELVIN starts in `Charge`.
+/// * `fuel` – The initial fuel level.
+///
+/// # Returns
+/// `true` if the schedule was simulated without violations.
+#[allow(clippy::cast_possible_wrap)]
+pub async fn simulate_schedule(batt: f64, fuel: f64) -> bool {
+    MissionConfig::init();
+    let Some(c_orbit) = ClosedOrbit::try_import_default() else {
+        error!("No orbit to simulate, export one and set MELVIN_ORBIT_TRY_IMPORT=true!");
+        return false;
+    };
+    let period = c_orbit.period().0.to_num::<usize>();
+    let start_i = IndexedOrbitPosition::new(0, period, *c_orbit.base_orbit_ref().fp());
+    let (init_batt, init_fuel) = (I32F32::from_num(batt), I32F32::from_num(fuel));
+    let t_cont = TaskController::new();
+    let clock = VirtualClock::new(start_i.t());
+    let st_batt = (init_batt, FlightState::Charge.to_dp_usize());
+    t_cont.sched_opt_orbit_from(&RwLock::new(c_orbit), start_i, None, st_batt, &clock).await;
+
+    let end = start_i.t() + TimeDelta::seconds(period as i64);
+    let sim = ScheduleSimulator::new(start_i.t(), FlightState::Charge, init_batt, init_fuel);
+    let report = sim.run(t_cont.sched_arc().read().await.iter(), end);
+    report.dump_json();
+    info!("Simulated schedule, lowest battery level was {}.", report.min_batt());
+    for violation in report.violations() {
+        warn!("Schedule violation: {violation:?}");
+    }
+    report.is_valid()
+}
+
+/// Analyzes the stitched map in `imaging.base_path` offline without contacting the DRS.
+///
+/// The map is opened read-only and a pixel counts as imaged if its confidence is set or if it
+/// lies in the footprint of an orbit second marked done in the exported orbit. The
+/// [`MapQualityReport`] on coverage, seams and uniform regions is dumped as JSON, next to an
+/// annotated downsampled preview of the map.
+///
+/// # Returns
+/// `true` if the map was analyzed and the preview could be saved.
+pub fn report_map_quality() -> bool {
+    MissionConfig::init();
+    let config = MissionConfig::get();
+    let path = Path::new(&config.imaging.base_path).join(MAP_BUFFER_PATH);
+    let map = match FullsizeMapImage::open_read_only(&path) {
+        Ok(map) => map,
+        Err(e) => {
+            error!("No map to analyze at {}: {e}!", path.display());
+            return false;
+        }
+    };
+    let orbit_footprint = match ClosedOrbit::import_default() {
+        Ok(c_orbit) => {
+            let vel = *c_orbit.base_orbit_ref().vel();
+            let lens = config.orbit.active_profile(vel).map_or(CameraAngle::Narrow, |p| p.lens);
+            Some(c_orbit.done_footprint(lens))
+        }
+        Err(e) => {
+            warn!("No orbit coverage to merge: {e}.");
+            None
+        }
+    };
+    let width = u32::map_size().x() as usize;
+    let imaged = |x: u32, y: u32| {
+        map.is_imaged(x, y)
+            || orbit_footprint.as_ref().is_some_and(|f| f[y as usize * width + x as usize])
+    };
+    let report = MapQualityReport::analyze(&map, imaged);
+    report.dump_json();
+    info!(
+        "Map is {:.2}% covered, found {} seams and {} uniform tiles.",
+        report.coverage() * 100.0,
+        report.seams().len(),
+        report.uniform_tiles().len()
+    );
+    match report.save_preview(&map) {
+        Ok(preview) => info!("Saved annotated map preview to {}.", preview.display()),
+        Err(e) => {
+            error!("Failed to save map preview: {e}.");
+            return false;
+        }
+    }
+    true
+}
+
+/// Creates a new closed orbit with the init orbit profile of the [`MissionConfig`].
+///
+/// The closure and overlap of the profile are checked before its velocity is reached.
+///
+/// # Arguments
+/// * `init_k` – The keychain holding the flight computer.
+async fn create_static_orbit(init_k: &Keychain) -> ClosedOrbit {
+    let config = MissionConfig::get();
+    let orbit_config = &config.orbit;
+    let profile = orbit_config.init_profile().unwrap_or_else(|| {
+        fatal!("Unknown init orbit profile {}", orbit_config.init_profile)
+    });
+    info!("Creating new Static Orbit with profile {}!", profile.name);
+    let pos = init_k.f_cont().read().await.current_pos();
+    if let Err(e) = profile.check(pos) {
+        fatal!("Orbit profile {} is not usable: {e}", profile.name);
+    }
+    if init_k.f_cont().read().await.current_battery() < I32F32::lit("50") {
+        FlightComputer::charge_full_wait(&init_k.f_cont()).await;
+    }
+    let f_cont_lock = init_k.f_cont();
+    FlightComputer::set_state_wait(init_k.f_cont(), FlightState::Acquisition).await;
+    if let Err(e) = FlightComputer::set_vel_wait(init_k.f_cont(), profile.vel(), false).await {
+        fatal!("Velocity of orbit profile {} was rejected: {e}", profile.name);
+    }
+    FlightComputer::set_angle_wait(init_k.f_cont(), CameraAngle::Narrow).await;
+    let f_cont = f_cont_lock.read().await;
+    profile.closed_orbit(OrbitBase::new(&f_cont)).unwrap_or_else(|e| match e {
+        OrbitUsabilityError::OrbitNotClosed => fatal!("Static orbit is not closed"),
+        OrbitUsabilityError::OrbitNotEnoughOverlap => {
+            fatal!("Static orbit is not overlapping enough")
+        }
+    })
+}
+
+#[allow(clippy::cast_precision_loss)]
+async fn init(client: HTTPClient) -> (Arc<ModeContext>, Box<dyn GlobalMode>) {
+    let (init_k, obj_rx, beac_rx) = Keychain::new(client).await;
+
+    let supervisor_clone = init_k.supervisor();
+    tokio::spawn(async move {
+        supervisor_clone.run_obs_obj_mon().await;
+    });
+
+    if MissionConfig::get().network.skip_reset {
+        warn!("Skipping reset!");
+        FlightComputer::avoid_transition(&init_k.f_cont()).await;
+    } else {
+        FlightComputer::reset(&init_k.f_cont()).await;
+    }
+
+    let (beac_cont, beac_state_rx) = {
+        let state_dir = Path::new(&MissionConfig::get().imaging.base_path).join(BEACON_STATE_DIR);
+        let res = BeaconController::new(beac_rx, init_k.score(), &state_dir);
+        (Arc::new(res.0), res.1)
+    };
+
+    let supervisor_clone = init_k.supervisor();
+    spawn_supervised("announcement_hub", move || {
+        let hub_supervisor = Arc::clone(&supervisor_clone);
+        async move { hub_supervisor.run_announcement_hub().await }
+    });
+    tokio::spawn(RuntimeReport::run_monitor());
+    tokio::spawn(EVENT_BUS.run_monitor());
+    if let Some(end) = MissionConfig::get().scheduling.mission_end {
+        let supervisor_clone = init_k.supervisor();
+        tokio::spawn(async move {
+            supervisor_clone.run_mission_clock(end).await;
+        });
+    }
+    let supervisor_clone = init_k.supervisor();
+    let init_k_c_cont = init_k.c_cont();
+    spawn_supervised("daily_map_uploader", move || {
+        let uploader_supervisor = Arc::clone(&supervisor_clone);
+        let uploader_c_cont = Arc::clone(&init_k_c_cont);
+        async move { uploader_supervisor.run_daily_map_uploader(uploader_c_cont).await }
+    });
+    let supervisor_clone = init_k.supervisor();
+    let init_k_c_cont = init_k.c_cont();
+    spawn_supervised("storage_monitor", move || {
+        let storage_supervisor = Arc::clone(&supervisor_clone);
+        let storage_c_cont = Arc::clone(&init_k_c_cont);
+        async move { storage_supervisor.run_storage_monitor(storage_c_cont).await }
+    });
+    let supervisor_clone = init_k.supervisor();
+    let init_k_c_cont = init_k.c_cont();
+    tokio::spawn(async move {
+        supervisor_clone.run_replay_recorder(init_k_c_cont).await;
+    });
+    init_k.con().spawn_beacon_heatmaps(Arc::clone(&beac_cont));
+    let beac_cont_clone = Arc::clone(&beac_cont);
+    let handler = Arc::clone(&init_k.client());
+    spawn_supervised("beacon_controller", move || {
+        Arc::clone(&beac_cont_clone).run(Arc::clone(&handler))
+    });
+
+    tokio::time::sleep(Duration::from_secs(5)).await;
+
+    if let Some(c_orbit) = ClosedOrbit::try_import_default() {
+        info!(
+            "Imported existing Orbit with {}% coverage!",
+            c_orbit.get_coverage() * 100
+        );
+        let orbit_char = OrbitCharacteristics::new(&c_orbit, &init_k.f_cont()).await;
+        let supervisor = init_k.supervisor();
+        let mode_context = ModeContext::new(
+            KeychainWithOrbit::new(init_k, c_orbit),
+            orbit_char,
+            obj_rx,
+            beac_state_rx,
+            supervisor,
+            beac_cont,
+        );
+        // A reset moves MELVIN away from any persisted plan
+        let resumed = if MissionConfig::get().network.skip_reset {
+            mode_context.resume_mode().await
+        } else {
+            None
+        };
+        let mode = resumed.unwrap_or_else(|| Box::new(OrbitReturnMode::new()));
+        return (mode_context, mode);
+    }
+
+    let c_orbit = create_static_orbit(&init_k).await;
+
+    let orbit_char = OrbitCharacteristics::new(&c_orbit, &init_k.f_cont()).await;
+    let supervisor = init_k.supervisor();
+    let mode_context = ModeContext::new(
+        KeychainWithOrbit::new(init_k, c_orbit),
+        orbit_char,
+        obj_rx,
+        beac_state_rx,
+        supervisor,
+        beac_cont,
+    );
+    let mode = OrbitReturnMode::get_next_mode(&mode_context).await;
+    (mode_context, mode)
+}
@@ -1,17 +1,5 @@
-#![allow(dead_code, clippy::similar_names)]
-#![warn(clippy::shadow_reuse, clippy::shadow_same, clippy::builtin_type_shadow)]
-//! Welcome to the onboard software for **Team 03 — "Cache us if you can"** competing in the **2024/2025 ESA Computer in a Room Challenge**. 
-//! This repository contains the embedded code running on the simulated MELVIN onboard computer, responsible 
-//! for command execution, event detection, task scheduling and DRS communication during the mission.
-
-mod console_communication;
-mod flight_control;
-mod http_handler;
-mod imaging;
-mod mode_control;
-mod objective;
-mod scheduling;
-mod util;
+//! Binary entry point of the MELVIN onboard software. The mission logic lives in the
+//! `melvin_ob` library crate.
 
 #[cfg(not(target_env = "msvc"))]
 use tikv_jemallocator::Jemalloc;
@@ -20,148 +8,40 @@ use tikv_jemallocator::Jemalloc;
 #[global_allocator]
 static GLOBAL: Jemalloc = Jemalloc;
 
-use crate::flight_control::{
-    FlightComputer, FlightState,
-    orbit::{ClosedOrbit, OrbitBase, OrbitCharacteristics, OrbitUsabilityError},
-};
-use crate::imaging::CameraAngle;
-use crate::mode_control::{
-    ModeContext, OpExitSignal,
-    mode::{GlobalMode, OrbitReturnMode},
-};
-use crate::objective::BeaconController;
-use crate::util::{Keychain, KeychainWithOrbit};
-use chrono::TimeDelta;
-use fixed::types::I32F32;
-use std::{env, sync::Arc, time::Duration};
-
-/// Shared 0-length timedelta in chrono units
-const DT_0: TimeDelta = TimeDelta::seconds(0);
-/// Shared 0-length timedelta in std time units
-const DT_0_STD: Duration = Duration::from_secs(0);
-
-/// Static orbit velocity for closed orbit
-const STATIC_ORBIT_VEL: (I32F32, I32F32) = (I32F32::lit("6.40"), I32F32::lit("7.40"));
-/// Environment variable holding the DRS url
-const ENV_BASE_URL: &str = "DRS_BASE_URL";
-/// Environment variable indicating whether to skip the initial reset or not
-const ENV_SKIP_RESET: &str = "SKIP_RESET";
-
-#[tokio::main(flavor = "multi_thread", worker_threads = 4)]
-async fn main() {
-    let base_url_var = env::var(ENV_BASE_URL);
-    let base_url = base_url_var.as_ref().map_or("http://localhost:33000", |v| v.as_str());
-    let (context, start_mode) = init(base_url).await;
-
-    let mut global_mode = start_mode;
-    loop {
-        let phase = context.o_ch_clone().await.mode_switches();
-        info!("Starting phase {phase} in {}!", global_mode.type_name());
-        match global_mode.init_mode(Arc::clone(&context)).await {
-            OpExitSignal::ReInit(mode) => {
-                global_mode = mode;
-                continue;
-            }
-            OpExitSignal::Continue => (),
-        };
-        match global_mode.exec_task_queue(Arc::clone(&context)).await {
-            OpExitSignal::ReInit(mode) => {
-                global_mode = mode;
-                continue;
-            }
-            OpExitSignal::Continue => {
-                global_mode = global_mode.exit_mode(Arc::clone(&context)).await;
-                continue;
-            }
-        }
+use std::{env, time::Duration};
+
+/// Environment variable enabling dry-run mode against the simulated DRS backend
+const ENV_DRY_RUN: &str = "DRY_RUN";
+/// CLI flag enabling dry-run mode against the simulated DRS backend.
+const FLAG_DRY_RUN: &str = "--dry-run";
+/// Environment variable holding the path of a recorded DRS session to replay
+const ENV_REPLAY_SESSION: &str = "DRS_REPLAY_SESSION";
+/// Dev subcommand simulating an orbit schedule: `simulate-schedule [battery] [fuel]`.
+const CMD_SIMULATE_SCHEDULE: &str = "simulate-schedule";
+/// Dev subcommand analyzing the quality of the stitched map: `map-report`.
+const CMD_MAP_REPORT: &str = "map-report";
+/// Time granted to background tasks after a graceful shutdown before the process exits.
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(5);
+
+fn main() {
+    let runtime = melvin_ob::build_runtime().expect("[FATAL] Failed to build tokio runtime!");
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.first().is_some_and(|cmd| cmd == CMD_SIMULATE_SCHEDULE) {
+        let level = |i: usize| args.get(i).and_then(|a| a.parse().ok()).unwrap_or(100.0);
+        let valid = runtime.block_on(melvin_ob::simulate_schedule(level(1), level(2)));
+        std::process::exit(i32::from(!valid));
     }
-    // drop(console_messenger);
-}
-
-#[allow(clippy::cast_precision_loss)]
-async fn init(url: &str) -> (Arc<ModeContext>, Box<dyn GlobalMode>) {
-    let (init_k, obj_rx, beac_rx) = Keychain::new(url).await;
-
-    let supervisor_clone = init_k.supervisor();
-    tokio::spawn(async move {
-        supervisor_clone.run_obs_obj_mon().await;
-    });
-
-    if env::var(ENV_SKIP_RESET).is_ok_and(|s| s == "1") {
-        warn!("Skipping reset!");
-        FlightComputer::avoid_transition(&init_k.f_cont()).await;
-    } else {
-        init_k.f_cont().write().await.reset().await;
+    if args.first().is_some_and(|cmd| cmd == CMD_MAP_REPORT) {
+        std::process::exit(i32::from(!melvin_ob::report_map_quality()));
     }
-
-    let (beac_cont, beac_state_rx) = {
-        let res = BeaconController::new(beac_rx);
-        (Arc::new(res.0), res.1)
-    };
-
-    let supervisor_clone = init_k.supervisor();
-    tokio::spawn(async move {
-        supervisor_clone.run_announcement_hub().await;
-    });
-    let supervisor_clone = init_k.supervisor();
-    let init_k_c_cont = init_k.c_cont();
-    tokio::spawn(async move {
-        supervisor_clone.run_daily_map_uploader(init_k_c_cont).await;
-    });
-    let beac_cont_clone = Arc::clone(&beac_cont);
-    let handler = Arc::clone(&init_k.client());
-    tokio::spawn(async move {
-        beac_cont_clone.run(handler).await;
-    });
-
-    tokio::time::sleep(Duration::from_secs(5)).await;
-
-    if let Some(c_orbit) = ClosedOrbit::try_from_env() {
-        info!(
-            "Imported existing Orbit with {}% coverage!",
-            c_orbit.get_coverage() * 100
-        );
-        let orbit_char = OrbitCharacteristics::new(&c_orbit, &init_k.f_cont()).await;
-        let supervisor = init_k.supervisor();
-        let mode_context = ModeContext::new(
-            KeychainWithOrbit::new(init_k, c_orbit),
-            orbit_char,
-            obj_rx,
-            beac_state_rx,
-            supervisor,
-            beac_cont,
-        );
-        return (mode_context, Box::new(OrbitReturnMode::new()));
+    if args.iter().any(|arg| arg == FLAG_DRY_RUN) || env::var(ENV_DRY_RUN).is_ok_and(|v| v == "1") {
+        runtime.block_on(melvin_ob::run_dry_mission());
+    } else if let Ok(path) = env::var(ENV_REPLAY_SESSION) {
+        let replayed = runtime.block_on(melvin_ob::run_replayed_mission(&path));
+        runtime.shutdown_timeout(SHUTDOWN_GRACE);
+        std::process::exit(i32::from(!replayed));
+    } else {
+        runtime.block_on(melvin_ob::run_configured_mission());
     }
-
-    let c_orbit: ClosedOrbit = {
-        info!("Creating new Static Orbit!");
-        if init_k.f_cont().read().await.current_battery() < I32F32::lit("50") {
-            FlightComputer::charge_full_wait(&init_k.f_cont()).await;
-        }
-        let f_cont_lock = init_k.f_cont();
-        FlightComputer::set_state_wait(init_k.f_cont(), FlightState::Acquisition).await;
-        FlightComputer::set_vel_wait(init_k.f_cont(), STATIC_ORBIT_VEL.into(), false).await;
-        FlightComputer::set_angle_wait(init_k.f_cont(), CameraAngle::Narrow).await;
-        let f_cont = f_cont_lock.read().await;
-        ClosedOrbit::new(OrbitBase::new(&f_cont), CameraAngle::Wide).unwrap_or_else(|e| match e {
-            OrbitUsabilityError::OrbitNotClosed => fatal!("Static orbit is not closed"),
-            OrbitUsabilityError::OrbitNotEnoughOverlap => {
-                fatal!("Static orbit is not overlapping enough")
-            }
-        })
-    };
-
-    let orbit_char = OrbitCharacteristics::new(&c_orbit, &init_k.f_cont()).await;
-    let supervisor = init_k.supervisor();
-    let mode_context = ModeContext::new(
-        KeychainWithOrbit::new(init_k, c_orbit),
-        orbit_char,
-        obj_rx,
-        beac_state_rx,
-        supervisor,
-        beac_cont,
-    );
-    let mode = OrbitReturnMode::get_next_mode(&mode_context).await;
-    (mode_context, mode)
+    runtime.shutdown_timeout(SHUTDOWN_GRACE);
 }
@@ -3,12 +3,20 @@ use super::signal::{
     PeriodicImagingEndSignal,
     TaskEndSignal::{self, Join, Timestamp},
 };
-use crate::flight_control::{FlightComputer, FlightState, orbit::IndexedOrbitPosition};
-use crate::imaging::CameraAngle;
-use crate::objective::BeaconControllerState;
-use crate::scheduling::{EndCondition, TaskController, task::SwitchStateTask};
-use crate::{DT_0_STD, error, fatal, info, log};
+use crate::flight_control::{
+    FlightComputer, FlightState,
+    orbit::{ClosedOrbit, IndexedOrbitPosition},
+};
+use crate::imaging::CoveragePlanner;
+use crate::objective::{BeaconControllerState, LifecycleStage, MeasConfidence, OBJECTIVE_TRACKER};
+use crate::scheduling::{
+    CommsBias, EndCondition, TaskController,
+    task::{ExternalEvent, ImageTask, SwitchStateTask},
+};
+use crate::util::{BeaconEvent, EVENT_BUS};
+use crate::{DT_0_STD, error, fatal, info, log, warn};
 use chrono::{DateTime, TimeDelta, Utc};
+use fixed::types::I32F32;
 use std::{future::Future, pin::Pin, sync::Arc};
 use strum_macros::Display;
 use tokio::{sync::oneshot, task::JoinHandle, time::Instant};
@@ -16,7 +24,9 @@ use tokio_util::sync::CancellationToken;
 
 /// Represents high-level operational modes of the onboard software when in orbit.
 /// Each variant encodes different scheduling logic and task handling behavior.
-#[derive(Display, Clone, Copy)]
+// The only `unsafe` stems from the pinning in `tokio::select!`, not from the variants.
+#[allow(clippy::unsafe_derive_deserialize)]
+#[derive(Display, Clone, Copy, serde::Serialize, serde::Deserialize)]
 pub(super) enum BaseMode {
     /// Regular mapping mode focused on maximizing imaging coverage.
     MappingMode,
@@ -25,13 +35,13 @@ pub(super) enum BaseMode {
 }
 
 impl BaseMode {
-    /// Default camera angle used during mapping operations.
-    const DEF_MAPPING_ANGLE: CameraAngle = CameraAngle::Narrow;
+    /// Minimum battery level to switch to Acquisition while map captures are degraded.
+    const DEGRADED_MIN_ACQ_BATT: I32F32 = I32F32::lit("80.0");
 
     /// Executes a full mapping acquisition cycle, listening until either a signal or cancellation occurs.
     ///
-    /// This function initializes an image acquisition cycle using the default mapping camera angle
-    /// and coordinates between the camera controller and various signal channels.
+    /// This function initializes an image acquisition cycle, whose lens is selected by the
+    /// `LensPolicy`, and coordinates between the camera controller and various signal channels.
     /// It finalizes by marking orbit coverage and exporting updated coverage data.
     ///
     /// # Arguments
@@ -46,14 +56,15 @@ impl BaseMode {
                 Join(_) => Utc::now() + TimeDelta::seconds(10000),
             }
         };
-        let o_ch_clone = context.o_ch_clone().await;
+        let o_ch_clone = context.o_ch();
+        let orbit_start = context.k().c_orbit().read().await.base_orbit_ref().start_timestamp();
         let acq_phase = {
             let f_cont_lock = Arc::clone(&context.k().f_cont());
             let (tx, rx) = oneshot::channel();
             let i_start = o_ch_clone.i_entry().new_from_pos(f_cont_lock.read().await.current_pos());
             let k_clone = Arc::clone(context.k());
-            let img_dt = o_ch_clone.img_dt();
-            FlightComputer::set_angle_wait(Arc::clone(&f_cont_lock), Self::DEF_MAPPING_ANGLE).await;
+            let planner =
+                CoveragePlanner::new(context.k().c_orbit(), i_start.index(), o_ch_clone.img_dt());
             let handle = tokio::spawn(async move {
                 k_clone
                     .c_cont()
@@ -61,8 +72,7 @@ impl BaseMode {
                         f_cont_lock,
                         k_clone.con(),
                         (end_t, rx),
-                        img_dt,
-                        i_start.index(),
+                        planner,
                     )
                     .await
             });
@@ -108,18 +118,57 @@ impl BaseMode {
             String::new()
         };
         log!("Marking done: {} - {}{and}", ranges[0].0, ranges[0].1);
+        Self::mark_imaged(&context, &fixed_ranges, orbit_start).await;
+    }
+
+    /// Marks the imaged orbit ranges as done, records their coverage and featureless flags and
+    /// exports the updated closed orbit.
+    ///
+    /// # Arguments
+    /// - `context`: A shared reference to a [`ModeContext`] object.
+    /// - `fixed_ranges`: The imaged ranges of orbit indices.
+    /// - `orbit_start`: The start of the closed orbit the acquisition cycle was planned on.
+    async fn mark_imaged(
+        context: &Arc<ModeContext>,
+        fixed_ranges: &[(usize, usize)],
+        orbit_start: DateTime<Utc>,
+    ) {
         let k_loc = Arc::clone(context.k());
         let c_orbit_lock = k_loc.c_orbit();
+        let imaged: Vec<_> = fixed_ranges.iter().filter(|(start, end)| start != end).collect();
+        let (indices, positions): (Vec<_>, Vec<_>) = {
+            let c_orbit = c_orbit_lock.read().await;
+            imaged
+                .iter()
+                .flat_map(|(start, end)| *start..=*end)
+                .map(|i| (i, c_orbit.pos_at(i)))
+                .unzip()
+        };
+        let featureless = k_loc.c_cont().featureless_flags(&positions).await;
         let mut c_orbit = c_orbit_lock.write().await;
-        for (start, end) in &fixed_ranges {
-            if start != end {
+        if c_orbit.base_orbit_ref().start_timestamp() != orbit_start {
+            log!("Closed orbit was replanned during acquisition, skipping coverage update.");
+            return;
+        }
+        {
+            let mut coverage = context.coverage().lock().await;
+            for (start, end) in imaged {
                 c_orbit.mark_done(*start, *end);
+                coverage.record(*start, *end, Utc::now());
             }
         }
+        for (i, is_featureless) in indices.into_iter().zip(featureless) {
+            c_orbit.set_featureless(i, is_featureless);
+        }
         log!(
             "Current discrete Orbit Coverage is {}%.",
             c_orbit.get_coverage() * 100
         );
+        k_loc.score().update_coverage(c_orbit.get_coverage().to_num::<f64>()).await;
+        if ClosedOrbit::skip_featureless() && c_orbit.is_fully_done() {
+            info!("Orbit fully imaged. Starting refinement pass over feature-rich terrain.");
+            c_orbit.clear_done();
+        }
         c_orbit.try_export_default();
     }
 
@@ -127,13 +176,21 @@ impl BaseMode {
     ///
     /// Uses an event-based listener to process incoming beacon messages.
     /// Automatically terminates based on task completion or shutdown signals.
+    /// Outside of `FlightState::Comms` this acts as a degraded listener, recording
+    /// whatever pings still arrive with `MeasConfidence::Degraded`.
     ///
     /// # Arguments
     /// - `context`: A shared reference to a `ModeContext` object.
     /// - `end`: A `TaskEndSignal`-enum type indicating how the task end condition should be defined.
     /// - `c_tok`: A `CancellationToken` that is able to cancel this task with proper cleanup.
-    async fn exec_comms(context: Arc<ModeContext>, end: TaskEndSignal, c_tok: CancellationToken) {
-        let mut event_rx = context.super_v().subscribe_event_hub();
+    /// - `confidence`: The confidence assigned to received measurements.
+    async fn exec_comms(
+        context: Arc<ModeContext>,
+        end: TaskEndSignal,
+        c_tok: CancellationToken,
+        confidence: MeasConfidence,
+    ) {
+        let mut event_rx = EVENT_BUS.subscribe::<BeaconEvent>();
 
         let mut fut: Pin<Box<dyn Future<Output = ()> + Send>> = match end {
             Timestamp(t) => {
@@ -144,17 +201,19 @@ impl BaseMode {
         };
 
         let start = Utc::now();
-        info!("Starting Comms Listener.");
+        let mut pings = 0;
+        info!("Starting Comms Listener ({confidence:?} confidence).");
         loop {
             tokio::select! {
                 // Wait for a message
-                Ok(msg) = event_rx.recv() => {
+                Some(BeaconEvent::Announcement(t, msg)) = event_rx.recv() => {
                     let f_cont = context.k().f_cont();
-                    context.beac_cont().handle_poss_bo_ping(msg, f_cont).await;
+                    context.beac_cont().handle_poss_bo_ping((t, msg), f_cont, confidence).await;
+                    pings += 1;
                 }
                 // If the timeout expires, exit
                 () = &mut fut => {
-                    log!("Comms Deadline reached after {}s. Stopping listener.",
+                    log!("Comms Deadline reached after {}s and {pings} messages. Stopping listener.",
                     (Utc::now() - start).num_seconds());
                     break;
                 },
@@ -179,8 +238,12 @@ impl BaseMode {
         context: Arc<ModeContext>,
     ) -> DateTime<Utc> {
         match self {
-            BaseMode::MappingMode => FlightComputer::escape_if_comms(context.k().f_cont()).await,
+            BaseMode::MappingMode => {
+                context.set_init_stage("leaving comms state");
+                FlightComputer::escape_if_comms(context.k().f_cont()).await
+            }
             BaseMode::BeaconObjectiveScanningMode => {
+                context.set_init_stage("getting to comms state");
                 FlightComputer::get_to_comms(context.k().f_cont()).await
             }
         }
@@ -210,8 +273,9 @@ impl BaseMode {
         comms_end: DateTime<Utc>,
         end: Option<EndCondition>,
     ) -> JoinHandle<()> {
+        context.set_init_stage("computing schedule");
         let k = Arc::clone(context.k());
-        let o_ch = context.o_ch_clone().await;
+        let o_ch = context.o_ch();
         let j_handle = match self {
             BaseMode::MappingMode => tokio::spawn(TaskController::sched_opt_orbit(
                 k.t_cont(),
@@ -223,6 +287,7 @@ impl BaseMode {
             BaseMode::BeaconObjectiveScanningMode => {
                 let last_obj_end =
                     context.beac_cont().last_active_beac_end().await.unwrap_or(Utc::now());
+                let bias = CommsBias::from_posteriors(&context.beac_cont().posteriors().await);
                 tokio::spawn(TaskController::sched_opt_orbit_w_comms(
                     k.t_cont(),
                     k.c_orbit(),
@@ -231,28 +296,32 @@ impl BaseMode {
                     last_obj_end,
                     comms_end,
                     end,
+                    bias,
+                    k.slots(),
                 ))
             }
         };
         let state = context.k().f_cont().read().await.state();
-        if state == FlightState::Acquisition {
-            tokio::spawn(BaseMode::exec_map(context, Join(j_handle), c_tok))
-        } else if state == FlightState::Comms {
-            match self {
-                BaseMode::MappingMode => fatal!("Illegal state ({state})!"),
-                BaseMode::BeaconObjectiveScanningMode => {
-                    tokio::spawn(BaseMode::exec_comms(context, Join(j_handle), c_tok))
-                }
+        match (state, self) {
+            (FlightState::Acquisition, _) => {
+                tokio::spawn(BaseMode::exec_map(context, Join(j_handle), c_tok))
             }
-        } else {
-            j_handle
+            (FlightState::Comms, BaseMode::MappingMode) => fatal!("Illegal state ({state})!"),
+            (FlightState::Comms, BaseMode::BeaconObjectiveScanningMode) => tokio::spawn(
+                BaseMode::exec_comms(context, Join(j_handle), c_tok, MeasConfidence::Full),
+            ),
+            (FlightState::Charge, BaseMode::BeaconObjectiveScanningMode) => tokio::spawn(
+                BaseMode::exec_comms(context, Join(j_handle), c_tok, MeasConfidence::Degraded),
+            ),
+            _ => j_handle,
         }
     }
 
     /// Spawns the corresponding primitive for the task wait time.
     ///
     /// The returned handle either:
-    /// - `FlightState::Charge`: Waits for the task timeout and exports a full image snapshot.
+    /// - `FlightState::Charge`: Waits for the task timeout. In `BeaconObjectiveScanningMode`
+    ///   a degraded beacon listener runs meanwhile, as the battery was too low for Comms.
     /// - `FlightState::Acquisition`: Executes a mapping task.
     /// - `FlightState::Comms`: Executes a beacon listening task.
     ///
@@ -283,14 +352,24 @@ impl BaseMode {
             tokio::time::timeout(sleep, c_tok_clone.cancelled()).await.ok().unwrap_or(());
         });
         let task_fut: Pin<Box<dyn Future<Output = _> + Send>> = match current_state {
-            FlightState::Charge => def,
+            FlightState::Charge => {
+                if let Self::BeaconObjectiveScanningMode = self {
+                    Box::pin(async move {
+                        let conf = MeasConfidence::Degraded;
+                        Self::exec_comms(context, Timestamp(due), c_tok, conf).await;
+                    })
+                } else {
+                    def
+                }
+            }
             FlightState::Acquisition => Box::pin(async move {
                 Self::exec_map(context, Timestamp(due), c_tok).await;
             }),
             FlightState::Comms => {
                 if let Self::BeaconObjectiveScanningMode = self {
                     Box::pin(async move {
-                        Self::exec_comms(context, Timestamp(due), c_tok).await;
+                        let conf = MeasConfidence::Full;
+                        Self::exec_comms(context, Timestamp(due), c_tok, conf).await;
                     })
                 } else {
                     error!("Not in Beacon Objective Scanning Mode. Waiting for Comms to end.");
@@ -304,9 +383,41 @@ impl BaseMode {
         tokio::spawn(task_fut)
     }
 
+    /// Executes an operator-pinned image task with the requested lens.
+    ///
+    /// The image is merged into the map like the images of the acquisition cycle. Outside of
+    /// [`FlightState::Acquisition`] or if the footprint of the lens would not cover the requested
+    /// position, e.g. after a replan changed the trajectory, the task is skipped.
+    ///
+    /// # Arguments
+    /// - `context`: A shared reference to a [`ModeContext`] object.
+    /// - `task`: The corresponding [`ImageTask`] object.
+    pub(super) async fn get_pinned_image(context: Arc<ModeContext>, task: ImageTask) {
+        let f_cont = context.k().f_cont();
+        let state = f_cont.read().await.state();
+        if state != FlightState::Acquisition {
+            warn!("Skipping pinned image task in state {state}.");
+            return;
+        }
+        FlightComputer::set_angle_wait(Arc::clone(&f_cont), task.lens).await;
+        let pos = f_cont.read().await.current_pos();
+        if !task.covers(pos) {
+            warn!("Skipping pinned image task for {} at {pos}.", task.planned_pos);
+            return;
+        }
+        match context.k().c_cont().shoot_image_to_map_buffer(f_cont, task.lens).await {
+            Ok((pos, _)) => info!("Took pinned image at {pos}."),
+            Err(e) => error!("Pinned image task failed: {e}"),
+        }
+    }
+
     /// Executes the corresponding primitive for task execution.
     ///
     /// In `GlobalMode` with a corresponding [`BaseMode`] this handles the logic for [`SwitchStateTask`].
+    /// While map captures are degraded, switches to Acquisition are skipped below
+    /// `DEGRADED_MIN_ACQ_BATT` in favor of charging. Switches to Charge apply the thumbnail
+    /// updates deferred on low battery in one batch. Switches to Charge and Comms retry the
+    /// rejected objective image uploads.
     ///
     /// # Arguments
     /// - `context`: A shared reference to a [`ModeContext`] object.
@@ -315,15 +426,24 @@ impl BaseMode {
         let f_cont = context.k().f_cont();
         match task.target_state() {
             FlightState::Acquisition => {
+                let batt = f_cont.read().await.current_battery();
+                let degraded = context.k().c_cont().is_capture_degraded().await;
+                if degraded && batt < Self::DEGRADED_MIN_ACQ_BATT {
+                    log!("Imaging degraded. Charging instead of switching to Acquisition.");
+                    return;
+                }
                 FlightComputer::set_state_wait(f_cont, FlightState::Acquisition).await;
             }
             FlightState::Charge => {
                 let task_handle = async {
                     FlightComputer::set_state_wait(f_cont, FlightState::Charge).await;
                 };
-                let k_clone = Arc::clone(context.k());
+                let context_clone = Arc::clone(&context);
                 let export_handle = tokio::spawn(async move {
-                    let c_cont = k_clone.c_cont();
+                    Self::retry_objective_uploads(&context_clone).await;
+                    let c_cont = context_clone.k().c_cont();
+                    let con = context_clone.k().con();
+                    con.send_thumbnail_batch(&c_cont.flush_deferred_thumbnails().await);
                     c_cont
                         .export_full_snapshot()
                         .await
@@ -346,12 +466,48 @@ impl BaseMode {
                 }
                 BaseMode::BeaconObjectiveScanningMode => {
                     FlightComputer::set_state_wait(f_cont, FlightState::Comms).await;
+                    let context_clone = Arc::clone(&context);
+                    tokio::spawn(async move {
+                        Self::retry_objective_uploads(&context_clone).await;
+                    });
                 }
             },
             _ => fatal!("Illegal target state!"),
         }
     }
 
+    /// Retries the rejected objective image uploads, scores the accepted ones and alerts the
+    /// console about uploads whose objective is about to end.
+    ///
+    /// Retried probes of secret objectives are evaluated like direct uploads, so they only
+    /// count once the DRS confirms the hidden zone.
+    ///
+    /// # Arguments
+    /// - `context`: The shared [`ModeContext`].
+    async fn retry_objective_uploads(context: &Arc<ModeContext>) {
+        let k = context.k();
+        let (uploaded, expired) = k.c_cont().retry_objective_uploads().await;
+        for (id, response) in uploaded {
+            OBJECTIVE_TRACKER.record(id, LifecycleStage::Uploaded);
+            let probe = context.secret_hunt().lock().await.take_queued_probe(id);
+            let accepted = match probe {
+                Some(zone) => context.eval_secret_probe(id, zone, Some(&response)).await,
+                None => !context.secret_hunt().lock().await.contains(id),
+            };
+            if accepted {
+                k.score().record_zo(id).await;
+                k.t_cont().events().fire(ExternalEvent::ObjectiveAccepted(id));
+            }
+        }
+        for id in expired {
+            warn!("Gave up uploading objective {id} after its end.");
+            OBJECTIVE_TRACKER.fail(id, "upload retries expired");
+        }
+        for alert in k.c_cont().upload_deadline_alerts().await {
+            k.con().send_alert(alert);
+        }
+    }
+
     /// Returns the relevant `BeaconControllerState` associated with this mode.
     ///
     /// Used to inform beacon-handling logic of the signal that would indicate switching.
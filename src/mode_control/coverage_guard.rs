@@ -0,0 +1,266 @@
+use super::ModeContext;
+use crate::flight_control::{Supervisor, orbit::IndexedOrbitPosition};
+use crate::imaging::CameraAngle;
+use crate::util::{
+    EVENT_BUS, ImagingEvent, JournalEvent, MISSION_JOURNAL, MissionConfig, SafetyEvent,
+    logger::JsonDump,
+};
+use crate::{DT_0_STD, error, info, warn};
+use chrono::{DateTime, TimeDelta, Utc};
+use std::{sync::Arc, time::Duration};
+
+/// Tracks when each stripe of the closed orbit was last imaged.
+///
+/// A stripe is a block of `STRIPE_LEN` consecutive orbit seconds. Stripes that were never
+/// imaged count from the time the guard was created, so that they only become stale once
+/// the mission had enough time to cover them.
+#[derive(Debug)]
+pub(crate) struct CoverageGuard {
+    /// The time the guard was created.
+    since: DateTime<Utc>,
+    /// The period of the closed orbit in seconds.
+    period: usize,
+    /// The last imaging time of every stripe.
+    stripe_t: Vec<Option<DateTime<Utc>>>,
+}
+
+/// The result of a coverage check before a daily map upload.
+#[derive(Debug, serde::Serialize)]
+pub(crate) struct CoverageCheck {
+    /// The time of the check.
+    t: DateTime<Utc>,
+    /// The share of stripes imaged at least once.
+    coverage: f64,
+    /// The required share of imaged stripes.
+    min_coverage: f64,
+    /// The age of the oldest stripe in hours.
+    oldest_age_h: i64,
+    /// The maximum tolerated stripe age in hours.
+    max_age_h: i64,
+    /// The orbit index ranges of all stripes that are stale or were never imaged.
+    stale: Vec<(usize, usize)>,
+    /// Whether all criteria were met.
+    passed: bool,
+}
+
+impl JsonDump for CoverageCheck {
+    /// Returns a unique filename based on the check time.
+    fn file_name(&self) -> String { format!("check_{}", self.t.format("%Y-%m-%d")) }
+
+    /// Specifies the output directory for dumped coverage checks.
+    fn dir_name(&self) -> &'static str { "coverage" }
+}
+
+impl CoverageGuard {
+    /// The number of orbit seconds per stripe.
+    const STRIPE_LEN: usize = 120;
+
+    /// Creates a new [`CoverageGuard`] for a closed orbit.
+    ///
+    /// # Arguments
+    /// * `period` – The period of the closed orbit in seconds.
+    pub(crate) fn new(period: usize) -> Self {
+        let stripes = period.div_ceil(Self::STRIPE_LEN).max(1);
+        Self { since: Utc::now(), period, stripe_t: vec![None; stripes] }
+    }
+
+    /// Records an imaged range of orbit indices.
+    ///
+    /// Only stripes fully contained in the range are refreshed, a stripe touched at its
+    /// edge still counts as stale.
+    ///
+    /// # Arguments
+    /// * `first_i` – The first imaged orbit index.
+    /// * `last_i` – The last imaged orbit index.
+    /// * `t` – The time of the imaging pass.
+    pub(crate) fn record(&mut self, first_i: usize, last_i: usize, t: DateTime<Utc>) {
+        let first_stripe = first_i.div_ceil(Self::STRIPE_LEN);
+        let end_stripe = (last_i + 1) / Self::STRIPE_LEN;
+        let last_full = if last_i + 1 >= self.period { self.stripe_t.len() } else { end_stripe };
+        for stripe in self.stripe_t.iter_mut().take(last_full).skip(first_stripe) {
+            *stripe = Some(t);
+        }
+    }
+
+    /// Checks the recorded coverage against the configured criteria.
+    ///
+    /// # Arguments
+    /// * `now` – The time of the check.
+    ///
+    /// # Returns
+    /// * The resulting [`CoverageCheck`].
+    #[allow(clippy::cast_precision_loss)]
+    fn check(&self, now: DateTime<Utc>) -> CoverageCheck {
+        let config = MissionConfig::get();
+        let runtime = &config.runtime;
+        let max_age = TimeDelta::hours(i64::from(runtime.coverage_max_age_h));
+        let imaged = self.stripe_t.iter().filter(|t| t.is_some()).count();
+        let coverage = imaged as f64 / self.stripe_t.len() as f64;
+        let ages: Vec<_> = self.stripe_t.iter().map(|t| now - t.unwrap_or(self.since)).collect();
+        let oldest_age = ages.iter().max().copied().unwrap_or(TimeDelta::zero());
+        let stale: Vec<_> = ages
+            .iter()
+            .enumerate()
+            .filter(|(i, age)| **age > max_age || self.stripe_t[*i].is_none())
+            .map(|(i, _)| {
+                let start = i * Self::STRIPE_LEN;
+                (start, (start + Self::STRIPE_LEN).min(self.period) - 1)
+            })
+            .collect();
+        CoverageCheck {
+            t: now,
+            coverage,
+            min_coverage: runtime.coverage_min,
+            oldest_age_h: oldest_age.num_hours(),
+            max_age_h: max_age.num_hours(),
+            passed: coverage >= runtime.coverage_min && oldest_age <= max_age,
+            stale,
+        }
+    }
+}
+
+/// Checks the orbit coverage ahead of every daily map upload and schedules a catch-up
+/// imaging block if the daily map would otherwise silently degrade.
+///
+/// The check runs `coverage_check_lead_h` hours before the upload. If the coverage is below
+/// `coverage_min` or any stripe is older than `coverage_max_age_h`, the stale stripes are
+/// reopened on the closed orbit and the current mode is asked to re-plan, so that the
+/// mapping schedule concentrates on them for the rest of the day.
+///
+/// # Arguments
+/// * `context` – The shared mode context.
+pub(crate) async fn run_coverage_guard(context: Arc<ModeContext>) {
+    loop {
+        let lead = TimeDelta::hours(i64::from(MissionConfig::get().runtime.coverage_check_lead_h));
+        let now = Utc::now();
+        let mut check_t = Supervisor::daily_map_upload_t(now) - lead;
+        if check_t <= now {
+            check_t += TimeDelta::days(1);
+        }
+        tokio::time::sleep((check_t - now).to_std().unwrap_or(DT_0_STD)).await;
+
+        let check = context.coverage().lock().await.check(Utc::now());
+        check.dump_json();
+        if check.passed {
+            info!(
+                "Coverage check passed: {:.1}% covered, oldest stripe {}h old.",
+                check.coverage * 100.0,
+                check.oldest_age_h
+            );
+            continue;
+        }
+        let msg = format!(
+            "Coverage check failed: {:.1}% covered (min {:.1}%), oldest stripe {}h old (max {}h). \
+             Scheduling catch-up for {} stripes.",
+            check.coverage * 100.0,
+            check.min_coverage * 100.0,
+            check.oldest_age_h,
+            check.max_age_h,
+            check.stale.len()
+        );
+        warn!("{msg}");
+        {
+            let c_orbit_lock = context.k().c_orbit();
+            let mut c_orbit = c_orbit_lock.write().await;
+            for (first_i, last_i) in &check.stale {
+                c_orbit.mark_undone(*first_i, *last_i);
+            }
+        }
+        context.k().con().send_alert(msg);
+        EVENT_BUS.publish(ImagingEvent::CoverageCatchUp);
+    }
+}
+
+/// Backfills the orbit coverage of map captures taken during observation link outages.
+///
+/// While the link is down, acquisition cycles may end without reporting their imaged ranges,
+/// although single captures still succeeded. Once the link is restored, every capture logged
+/// within the outage is mapped to its closed orbit index via the orbit entry and marked done
+/// together with half an imaging interval to either side.
+///
+/// # Arguments
+/// * `context` – The shared mode context.
+#[allow(clippy::cast_possible_wrap)]
+pub(crate) async fn run_coverage_reconciler(context: Arc<ModeContext>) {
+    let mut safety_rx = EVENT_BUS.subscribe::<SafetyEvent>();
+    while let Some(event) = safety_rx.recv().await {
+        let SafetyEvent::LinkRestored(start, end) = event else { continue };
+        let o_ch = context.o_ch();
+        let i_entry = o_ch.i_entry();
+        let captures = context.k().c_cont().captures_between(start.max(i_entry.t()), end).await;
+        if captures.is_empty() {
+            continue;
+        }
+        let half_dt = (o_ch.img_dt() / 2).to_num::<isize>();
+        let k_loc = Arc::clone(context.k());
+        let c_orbit_lock = k_loc.c_orbit();
+        let mut c_orbit = c_orbit_lock.write().await;
+        let mut coverage = context.coverage().lock().await;
+        let before = c_orbit.get_coverage();
+        for t in &captures {
+            let i = i_entry.index_then(*t) as isize;
+            let ranges = vec![(i - half_dt, i + half_dt)];
+            let period = i_entry.period() as isize;
+            for (first_i, last_i) in IndexedOrbitPosition::map_ranges(&ranges, period) {
+                c_orbit.mark_done(first_i, last_i);
+                coverage.record(first_i, last_i, *t);
+            }
+        }
+        let after = c_orbit.get_coverage();
+        info!(
+            "Reconciled {} captures from the {}s link outage, coverage {}% -> {}%.",
+            captures.len(),
+            (end - start).num_seconds(),
+            before * 100,
+            after * 100
+        );
+        k_loc.score().update_coverage(after.to_num::<f64>()).await;
+        c_orbit.try_export_default();
+    }
+}
+
+/// Continuously verifies the memory-mapped map buffer against its tile checksums and reopens
+/// the coverage of corrupted regions.
+///
+/// The first call picks up the regions quarantined by the full verification at startup,
+/// afterwards `SCRUB_TILES` tiles are verified every `SCRUB_INTERVAL`. Every orbit second
+/// imaging a quarantined region with the widest lens is marked undone, and the current mode
+/// is asked to re-plan. Every pass flushes the map buffer, which persists its checksums.
+///
+/// # Arguments
+/// * `context` – The shared mode context.
+pub(crate) async fn run_map_scrubber(context: Arc<ModeContext>) {
+    /// The interval between two scrub passes.
+    const SCRUB_INTERVAL: Duration = Duration::from_secs(60);
+    /// The number of tiles verified per scrub pass.
+    const SCRUB_TILES: usize = 64;
+
+    let margin = u32::from(CameraAngle::Wide.get_square_side_length()) / 2;
+    loop {
+        let regions = context.k().c_cont().scrub_map(SCRUB_TILES).await;
+        if !regions.is_empty() {
+            let reopened = {
+                let c_orbit_lock = context.k().c_orbit();
+                let mut c_orbit = c_orbit_lock.write().await;
+                let reopened: usize = regions
+                    .iter()
+                    .map(|(offset, size)| c_orbit.mark_region_undone(*offset, *size, margin))
+                    .sum();
+                c_orbit.try_export_default();
+                reopened
+            };
+            let msg = format!(
+                "Map buffer corrupted: quarantined {} tiles and reopened {reopened}s of the orbit.",
+                regions.len()
+            );
+            error!("{msg}");
+            context.k().con().send_alert(msg);
+            MISSION_JOURNAL.record(JournalEvent::MapQuarantined { tiles: regions.len(), reopened });
+            EVENT_BUS.publish(ImagingEvent::CoverageCatchUp);
+        }
+        if let Err(e) = context.k().c_cont().flush_map().await {
+            error!("Failed to flush the map buffer: {e}");
+        }
+        tokio::time::sleep(SCRUB_INTERVAL).await;
+    }
+}
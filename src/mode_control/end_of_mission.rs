@@ -0,0 +1,198 @@
+use super::ModeContext;
+use crate::imaging::CameraController;
+use crate::objective::{KnownImgObjective, LifecycleStage, OBJECTIVE_TRACKER};
+use crate::util::logger::JsonDump;
+use crate::{error, info, obj, warn};
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+
+/// A single verified item of the [`MissionChecklist`].
+#[derive(Debug, serde::Serialize)]
+struct ChecklistItem {
+    /// A short name of the verified item.
+    item: &'static str,
+    /// Whether the item was completed successfully.
+    passed: bool,
+    /// Additional details, e.g. counts or error messages.
+    detail: String,
+}
+
+/// Final metrics of the mission, dumped alongside the checklist.
+#[derive(Debug, Default, serde::Serialize)]
+struct MissionMetrics {
+    /// The final map coverage of the closed orbit in `[0, 1]`.
+    coverage: f64,
+    /// The cumulative expected score.
+    expected_score: f64,
+    /// The number of booked score entries.
+    score_entries: usize,
+    /// The number of beacon objectives submitted by the end-of-mission routine.
+    final_beacon_submissions: usize,
+    /// The number of zoned objectives submitted by the end-of-mission routine.
+    final_zo_submissions: usize,
+}
+
+/// Verification report of the end-of-mission routine.
+///
+/// Every finalization step is recorded as a checklist item, so that the operator can verify
+/// that nothing scoreable was left unsubmitted when the mission window closed.
+#[derive(Debug, serde::Serialize)]
+pub(crate) struct MissionChecklist {
+    /// The time the routine was started.
+    started: DateTime<Utc>,
+    /// The time the routine finished.
+    finished: Option<DateTime<Utc>>,
+    /// The verified items in execution order.
+    items: Vec<ChecklistItem>,
+    /// The final mission metrics.
+    metrics: MissionMetrics,
+}
+
+impl JsonDump for MissionChecklist {
+    /// Returns the file name for the JSON dump of the checklist.
+    fn file_name(&self) -> String { "checklist".to_string() }
+
+    /// Returns the directory name for the end-of-mission JSON files.
+    fn dir_name(&self) -> &'static str { "end_of_mission" }
+}
+
+impl MissionChecklist {
+    /// Creates a new, empty [`MissionChecklist`].
+    fn new() -> Self {
+        Self {
+            started: Utc::now(),
+            finished: None,
+            items: Vec::new(),
+            metrics: MissionMetrics::default(),
+        }
+    }
+
+    /// Records a checklist item and logs its outcome.
+    ///
+    /// # Arguments
+    /// * `item` – A short name of the verified item.
+    /// * `passed` – Whether the item was completed successfully.
+    /// * `detail` – Additional details.
+    fn check(&mut self, item: &'static str, passed: bool, detail: String) {
+        if passed {
+            info!("End of mission: {item} done ({detail}).");
+        } else {
+            warn!("End of mission: {item} failed ({detail}).");
+        }
+        self.items.push(ChecklistItem { item, passed, detail });
+    }
+
+    /// Returns a one-line summary of the checklist.
+    fn summary(&self) -> String {
+        let failed: Vec<_> = self.items.iter().filter(|i| !i.passed).map(|i| i.item).collect();
+        if failed.is_empty() {
+            format!("End of mission: all {} checks passed.", self.items.len())
+        } else {
+            format!("End of mission: {} checks failed: {}.", failed.len(), failed.join(", "))
+        }
+    }
+}
+
+/// Waits for the end-of-mission request of the [`Supervisor`](crate::flight_control::Supervisor)
+/// and then runs [`finalize_mission`] exactly once.
+///
+/// # Arguments
+/// * `context` – The shared mode context.
+pub(crate) async fn run_end_of_mission(context: Arc<ModeContext>) {
+    let mut eom_mon = context.super_v().eom_mon();
+    if eom_mon.wait_for(|requested| *requested).await.is_err() {
+        return;
+    }
+    finalize_mission(&context).await;
+}
+
+/// Finalizes the mission dataset.
+///
+/// Force-submits all pending beacon estimates and buffered zoned objectives, exports the full
+/// map snapshot, uploads the last daily map, completes all journals and dumps the final
+/// metrics together with a verification [`MissionChecklist`].
+///
+/// # Arguments
+/// * `context` – The shared mode context.
+pub(crate) async fn finalize_mission(context: &Arc<ModeContext>) {
+    obj!("Starting end-of-mission routine!");
+    let mut checklist = MissionChecklist::new();
+    let k = context.k();
+
+    let (beacons, bo_failed) = context.beac_cont().submit_all(&k.client()).await;
+    checklist.check(
+        "beacon submissions",
+        bo_failed == 0,
+        format!("{beacons} submitted, {bo_failed} failed"),
+    );
+    checklist.metrics.final_beacon_submissions = beacons;
+
+    let buffered: Vec<KnownImgObjective> = context.k_buffer().lock().await.drain().collect();
+    let (mut zo_ok, mut zo_failed) = (0, 0);
+    for zo in buffered.iter().filter(|zo| zo.end() > Utc::now()) {
+        if k.score().has_zo(zo.id()).await {
+            continue;
+        }
+        let offset = zo.zone().offset().to_unsigned();
+        let dim = zo.zone().size().to_unsigned();
+        let uploaded = k
+            .c_cont()
+            .export_and_upload_objective_img(
+                zo.id(),
+                offset,
+                dim,
+                Some(CameraController::generate_zo_img_path(zo.id())),
+                None,
+                None,
+            )
+            .await
+            .map_err(|e| error!("Error submitting ZO {} from map: {e}", zo.id()))
+            .is_ok();
+        if uploaded {
+            OBJECTIVE_TRACKER.record(zo.id(), LifecycleStage::Uploaded);
+            k.score().record_zo(zo.id()).await;
+            zo_ok += 1;
+        } else {
+            OBJECTIVE_TRACKER.fail(zo.id(), "end-of-mission upload failed");
+            zo_failed += 1;
+        }
+    }
+    checklist.check(
+        "zoned objective submissions",
+        zo_failed == 0,
+        format!("{zo_ok} submitted, {zo_failed} failed"),
+    );
+    checklist.metrics.final_zo_submissions = zo_ok;
+
+    let c_cont = k.c_cont();
+    let snapshot = c_cont.export_full_snapshot().await.map_err(|e| e.to_string());
+    checklist.check("full map snapshot", snapshot.is_ok(), snapshot.err().unwrap_or_default());
+    let daily_map = c_cont.upload_daily_map_png().await.map_err(|e| e.to_string());
+    checklist.check("daily map upload", daily_map.is_ok(), daily_map.err().unwrap_or_default());
+
+    let journals = [
+        ("score", k.score().dump().await),
+        ("task timing", k.t_cont().dump_timing_report().await),
+        ("reconciliation", k.f_cont().read().await.dump_reconciliation_log()),
+    ];
+    let failed: Vec<_> = journals
+        .iter()
+        .filter_map(|(name, res)| res.as_ref().err().map(|e| format!("{name}: {e}")))
+        .collect();
+    let detail = if failed.is_empty() {
+        journals.map(|(name, _)| name).join(", ")
+    } else {
+        failed.join(", ")
+    };
+    checklist.check("journals", failed.is_empty(), detail);
+
+    checklist.metrics.coverage = k.c_orbit().read().await.get_coverage().to_num();
+    checklist.metrics.expected_score = k.score().total().await;
+    checklist.metrics.score_entries = k.score().entries().await.len();
+    checklist.finished = Some(Utc::now());
+    checklist.dump_json();
+
+    let summary = checklist.summary();
+    obj!("{summary}");
+    k.con().send_alert(summary);
+}
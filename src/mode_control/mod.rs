@@ -3,10 +3,22 @@
 //! various operational modes in the implemented nested state machine.
 
 mod base_mode;
+mod coverage_guard;
+mod end_of_mission;
 pub(crate) mod mode;
 mod mode_context;
+mod mode_graph;
+mod mode_state_store;
+mod mosaic_passes;
+mod orbit_replanner;
+mod shutdown;
 mod signal;
 
+pub(crate) use coverage_guard::{run_coverage_guard, run_coverage_reconciler, run_map_scrubber};
+pub(crate) use end_of_mission::run_end_of_mission;
+pub(crate) use mode_graph::{MODE_GRAPH, TransitionPhase};
+pub(crate) use orbit_replanner::run_orbit_replanner;
+pub(crate) use shutdown::{shutdown, wait_for_shutdown};
 pub(crate) use signal::OpExitSignal;
 pub(crate) use signal::PeriodicImagingEndSignal;
 pub(crate) use crate::mode_control::mode_context::ModeContext;
\ No newline at end of file
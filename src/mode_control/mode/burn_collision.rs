@@ -0,0 +1,119 @@
+use crate::flight_control::{FlightState, orbit::BurnSequence};
+use crate::scheduling::EndCondition;
+use crate::util::{ImgObjectiveId, logger::JsonDump};
+use crate::warn;
+use chrono::{DateTime, TimeDelta, Utc};
+use fixed::types::I32F32;
+use num::Zero;
+
+/// The decision taken when a safe mode event collides with a scheduled exit burn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub(super) enum CollisionAction {
+    /// The burn was recomputed and shifted to a start time reachable after the recovery.
+    Shift,
+    /// No feasible burn remained after the recovery, the objective was stashed.
+    Cancel,
+    /// The burn was interrupted mid-sequence, MELVIN returns to its closed orbit.
+    Abort,
+    /// The burn had already completed, MELVIN continues to the objective.
+    Continue,
+}
+
+/// Record of a safe mode event that struck shortly before or during a scheduled exit burn.
+///
+/// Without special handling, the burn would be attempted with the wrong flight state or an
+/// insufficient battery level after the safe mode recovery. A [`BurnCollision`] captures the
+/// decision taken instead and is dumped for later analysis.
+#[derive(Debug, Clone, serde::Serialize)]
+pub(super) struct BurnCollision {
+    /// The ID of the zoned objective the burn was aiming for.
+    zo_id: ImgObjectiveId,
+    /// The time the safe mode event was detected.
+    safe_t: DateTime<Utc>,
+    /// The originally planned burn start.
+    planned_burn_start: DateTime<Utc>,
+    /// The time the collision was resolved, i.e. after the safe mode recovery.
+    resolved_t: Option<DateTime<Utc>>,
+    /// The decision taken.
+    action: Option<CollisionAction>,
+    /// The start of the shifted burn, if any.
+    new_burn_start: Option<DateTime<Utc>>,
+}
+
+impl BurnCollision {
+    /// Safe mode events closer than this to the planned burn start count as a collision.
+    const COLLISION_WINDOW: TimeDelta = TimeDelta::minutes(15);
+
+    /// Checks whether a safe mode event collides with a scheduled burn.
+    ///
+    /// # Arguments
+    /// * `zo_id` – The ID of the targeted zoned objective.
+    /// * `planned_burn_start` – The scheduled start of the exit burn.
+    /// * `safe_t` – The time the safe mode event was detected.
+    ///
+    /// # Returns
+    /// * `Some(BurnCollision)` if the burn starts within `COLLISION_WINDOW` or has already started.
+    pub(super) fn detect(
+        zo_id: ImgObjectiveId,
+        planned_burn_start: DateTime<Utc>,
+        safe_t: DateTime<Utc>,
+    ) -> Option<Self> {
+        (planned_burn_start - safe_t <= Self::COLLISION_WINDOW).then_some(Self {
+            zo_id,
+            safe_t,
+            planned_burn_start,
+            resolved_t: None,
+            action: None,
+            new_burn_start: None,
+        })
+    }
+
+    /// Checks whether a burn can still be prepared in time from the current battery level.
+    ///
+    /// # Arguments
+    /// * `burn` – The candidate burn sequence.
+    /// * `now` – The current time.
+    /// * `batt` – The current battery level.
+    ///
+    /// # Returns
+    /// * `true` if charging to the burns minimum charge and switching to acquisition fits
+    ///   before the burn start.
+    pub(super) fn is_feasible(burn: &BurnSequence, now: DateTime<Utc>, batt: I32F32) -> bool {
+        let end = EndCondition::from_burn(burn);
+        let charge_secs = FlightState::Charge.charge_dt(batt, end.charge().max(batt));
+        if charge_secs == I32F32::MAX {
+            return false;
+        }
+        let charge_dt = TimeDelta::seconds(charge_secs.max(I32F32::zero()).ceil().to_num::<i64>());
+        let t_time = FlightState::Acquisition.td_dt_to(FlightState::Charge)
+            + FlightState::Charge.td_dt_to(FlightState::Acquisition);
+        now + t_time + charge_dt <= end.time()
+    }
+
+    /// Records the decision taken for this collision, logs it and dumps it.
+    ///
+    /// # Arguments
+    /// * `action` – The decision taken.
+    /// * `new_burn_start` – The start of the shifted burn, if the burn was shifted.
+    pub(super) fn resolve(mut self, action: CollisionAction, new_burn_start: Option<DateTime<Utc>>) {
+        self.resolved_t = Some(Utc::now());
+        self.action = Some(action);
+        self.new_burn_start = new_burn_start;
+        let planned = self.planned_burn_start.format("%H:%M:%S");
+        let dt = (self.planned_burn_start - self.safe_t).num_seconds();
+        warn!(
+            "Safe mode collided with burn for ZO {} planned at {planned} ({dt}s after safe). \
+             Decision: {action:?}.",
+            self.zo_id
+        );
+        self.dump_json();
+    }
+}
+
+impl JsonDump for BurnCollision {
+    /// Returns the file name for the JSON dump of the burn collision.
+    fn file_name(&self) -> String { format!("collision_{}_{}", self.zo_id, self.safe_t.timestamp()) }
+
+    /// Returns the directory name for the burn collision JSON files.
+    fn dir_name(&self) -> &'static str { "burn_collisions" }
+}
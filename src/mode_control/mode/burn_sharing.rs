@@ -0,0 +1,120 @@
+use crate::flight_control::orbit::ExitBurnResult;
+use crate::objective::KnownImgObjective;
+use crate::scheduling::{TaskController, ZoCandidate, ZoLeg};
+use crate::util::{ImgObjectiveId, Vec2D, logger::JsonDump};
+use fixed::types::I32F32;
+
+/// Further zoned objectives that are retrieved with the exit burn planned for another one.
+///
+/// Several objectives with overlapping time windows may lie close to the impact point of the
+/// same exit burn. Instead of a full exit/return cycle for each of them, the partners inherit
+/// the burn of the primary objective: after the primary images are taken, MELVIN turns
+/// towards the partner zones one after another, sequenced by their deadlines, and images
+/// them during the same off-orbit phase. All turns share the fuel left after the exit burn.
+#[derive(Debug, Clone, serde::Serialize)]
+pub(super) struct SharedBurn {
+    /// The ID of the objective the burn was planned for.
+    primary_id: ImgObjectiveId,
+    /// The objectives sharing the burn, in retrieval order.
+    #[serde(skip)]
+    partners: Vec<KnownImgObjective>,
+    /// The retrieval legs towards the partners, in retrieval order.
+    legs: Vec<ZoLeg>,
+    /// The fuel available for all turns after the exit burn.
+    fuel_budget: I32F32,
+    /// The estimated fuel saved compared to separate exit/return cycles.
+    fuel_saved: I32F32,
+}
+
+impl JsonDump for SharedBurn {
+    /// Returns a unique filename based on the primary objective ID.
+    fn file_name(&self) -> String { format!("shared_burn_{}", self.primary_id) }
+
+    /// Specifies the output directory for dumped zoned objective results.
+    fn dir_name(&self) -> &'static str { "zoned_objectives" }
+}
+
+impl SharedBurn {
+    /// Searches the buffered objectives for ones that can share the given exit burn.
+    ///
+    /// Only single-image objectives requiring the same optic as the primary objective are
+    /// considered, and only if the primary objective is a single-image objective itself, as
+    /// the second-target turn is otherwise already in use.
+    ///
+    /// # Arguments
+    /// * `primary` – The objective the burn was planned for.
+    /// * `exit_burn` – The planned exit burn.
+    /// * `candidates` – The buffered objectives.
+    /// * `fuel_left` – The fuel left before the exit burn.
+    ///
+    /// # Returns
+    /// * `Some(SharedBurn)` if at least one partner is feasible, `None` if there is none.
+    pub(super) fn find(
+        primary: &KnownImgObjective,
+        exit_burn: &ExitBurnResult,
+        candidates: &[KnownImgObjective],
+        fuel_left: I32F32,
+    ) -> Option<Self> {
+        if exit_burn.add_target().is_some() {
+            return None;
+        }
+        let eligible: Vec<&KnownImgObjective> = candidates
+            .iter()
+            .filter(|c| {
+                c.id() != primary.id()
+                    && c.min_images() == 1
+                    && c.optic_required() == primary.optic_required()
+            })
+            .collect();
+        let zo_candidates: Vec<ZoCandidate> = eligible
+            .iter()
+            .map(|c| ZoCandidate {
+                id: c.id(),
+                pos: c.get_single_image_point(),
+                start: c.start(),
+                end: c.end(),
+            })
+            .collect();
+        let fuel_budget = fuel_left - exit_burn.sequence().min_fuel();
+        let legs = TaskController::sequence_zo_legs(exit_burn, &zo_candidates, fuel_budget);
+        if legs.is_empty() {
+            return None;
+        }
+        let partners = legs
+            .iter()
+            .filter_map(|leg| eligible.iter().find(|c| c.id() == leg.id()).map(|c| (*c).clone()))
+            .collect();
+        let fuel_saved = legs.iter().map(ZoLeg::fuel_saved).sum();
+        Some(Self { primary_id: primary.id(), partners, legs, fuel_budget, fuel_saved })
+    }
+
+    /// Returns the objectives sharing the burn, in retrieval order.
+    pub(super) fn partners(&self) -> &[KnownImgObjective] { &self.partners }
+
+    /// Checks whether an objective shares the burn.
+    ///
+    /// # Arguments
+    /// * `id` – The ID of the objective.
+    pub(super) fn contains(&self, id: ImgObjectiveId) -> bool {
+        self.legs.iter().any(|leg| leg.id() == id)
+    }
+
+    /// Returns the partner objectives with their imaging positions, in retrieval order.
+    pub(super) fn retrievals(&self) -> Vec<(KnownImgObjective, Vec2D<I32F32>)> {
+        self.partners.iter().cloned().zip(self.legs.iter().map(ZoLeg::pos)).collect()
+    }
+
+    /// Returns the estimated fuel saved compared to separate exit/return cycles.
+    pub(super) fn fuel_saved(&self) -> I32F32 { self.fuel_saved }
+
+    /// Returns the estimated fuel needed for all turns towards the partners.
+    pub(super) fn turn_fuel(&self) -> I32F32 { self.legs.iter().map(ZoLeg::turn_fuel).sum() }
+
+    /// Returns the IDs of the objectives sharing a burn, in retrieval order.
+    ///
+    /// # Arguments
+    /// * `shared` – The shared burn, if any.
+    pub(super) fn partner_ids(shared: Option<&Self>) -> Vec<ImgObjectiveId> {
+        shared.map_or_else(Vec::new, |s| s.legs.iter().map(ZoLeg::id).collect())
+    }
+}
@@ -0,0 +1,370 @@
+use super::{
+    burn_collision::BurnCollision, global_mode::GlobalMode, in_orbit_mode::InOrbitMode,
+    orbit_return_mode::OrbitReturnMode, zo_retrieval_mode::ZORetrievalMode,
+};
+use crate::flight_control::{FlightComputer, orbit::ExitBurnResult};
+use crate::imaging::ResolutionEstimate;
+use crate::mode_control::{
+    base_mode::BaseMode,
+    mode_context::ModeContext,
+    signal::{ExecExitSignal, OpExitSignal, OptOpExitSignal, WaitExitSignal},
+};
+use crate::objective::{KnownImgObjective, LifecycleStage, OBJECTIVE_TRACKER, ObjectivePriority};
+use crate::scheduling::{
+    ScheduleSimulator, TaskController,
+    task::{BaseTask, Task},
+};
+use crate::util::{ImgObjectiveId, MissionConfig, ObjectiveEvent, TimeBudget, logger::JsonDump};
+use crate::{DT_0_STD, fatal, log, log_burn, obj, warn};
+use async_trait::async_trait;
+use chrono::{DateTime, TimeDelta, Utc};
+use fixed::types::I32F32;
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicBool, Ordering},
+};
+use tokio_util::sync::CancellationToken;
+
+/// [`EmergencyRetrievalMode`] prepares the exit burn towards a valuable zoned objective that
+/// ends soon, when the battery can not be charged to the nominal burn reserve in time.
+///
+/// The battery reserve of [`TaskController::min_battery_threshold()`] is lowered to the
+/// `emergency_batt_floor` of the [`MissionConfig`]. The schedule is replaced by a minimal
+/// charge-burn plan, which is only accepted if a [`ScheduleSimulator`] run through the
+/// subsequent imaging phase stays above that floor, and optional console traffic is dropped
+/// until the burn was executed. The imaging itself is left to a [`ZORetrievalMode`].
+pub(super) struct EmergencyRetrievalMode {
+    /// The targeted zoned objective.
+    target: KnownImgObjective,
+    /// The exit burn with the lowered battery reserve.
+    exit_burn: ExitBurnResult,
+    /// The charge-burn plan verified by the simulation, taken when it is scheduled.
+    plan: Mutex<Vec<Task>>,
+    /// Indicates whether the execution of the exit burn has started.
+    burn_started: AtomicBool,
+    /// Indicates whether the satellite has already left its orbit.
+    left_orbit: AtomicBool,
+}
+
+impl EmergencyRetrievalMode {
+    /// Internal name used for logging and identification.
+    const MODE_NAME: &'static str = "EmergencyRetrievalMode";
+    /// Share of the zoned objective points an objective must at least be valued at.
+    const MIN_VALUE_SHARE: f64 = 0.5;
+
+    /// Constructs an [`EmergencyRetrievalMode`] if the exit burn of a zoned objective can not
+    /// be prepared nominally, but within the relaxed battery reserve.
+    ///
+    /// # Arguments
+    /// * `context` – Shared mode context.
+    /// * `zo` – The target zoned objective.
+    /// * `exit_burn` – The exit burn planned by [`ModeContext::plan_exit_burn`].
+    ///
+    /// # Returns
+    /// * `Some(EmergencyRetrievalMode)` if the objective is valuable and ends soon, the nominal
+    ///   preparation is infeasible and the emergency plan passed the simulation.
+    /// * `None` otherwise, leaving the objective to a nominal
+    ///   [`ZOPrepMode`](super::zo_prep_mode::ZOPrepMode).
+    pub(super) async fn try_new(
+        context: &Arc<ModeContext>,
+        zo: &KnownImgObjective,
+        exit_burn: &ExitBurnResult,
+    ) -> Option<Self> {
+        let config = MissionConfig::get();
+        let lead = TimeDelta::minutes(i64::from(config.runtime.emergency_lead_min));
+        let now = Utc::now();
+        let value = ObjectivePriority::new(zo, Some(exit_burn.sequence().min_fuel())).value();
+        if zo.end() - now > lead || value < config.scoring.zo_points * Self::MIN_VALUE_SHARE {
+            return None;
+        }
+        if !ModeContext::meets_resolution(zo, exit_burn) {
+            return None;
+        }
+        let (state, batt, fuel) = {
+            let f_cont = context.k().f_cont();
+            let f_cont_lock = f_cont.read().await;
+            (f_cont_lock.state(), f_cont_lock.current_battery(), f_cont_lock.fuel_left())
+        };
+        if BurnCollision::is_feasible(exit_burn.sequence(), now, batt) {
+            return None;
+        }
+        let floor = I32F32::from_num(config.runtime.emergency_batt_floor);
+        let relaxed = exit_burn.with_charge_floor(floor);
+        let burn = relaxed.sequence();
+        let plan = TaskController::emergency_burn_plan(now, state, burn)?;
+        let (imaging, sim_end) = Self::imaging_phase_plan(zo, &relaxed);
+        let sim = ScheduleSimulator::new(now, state, batt, fuel);
+        let report = sim.run(plan.iter().chain(&imaging), sim_end);
+        report.dump_json();
+        if !report.is_valid() || report.min_batt() < floor {
+            obj!(
+                "No emergency plan for Zoned Objective {}: battery {batt:.1} drops to {:.1} \
+                 with {} violations.",
+                zo.id(),
+                report.min_batt(),
+                report.violations().len()
+            );
+            return None;
+        }
+        if let Err(e) = context.k().fuel().reserve(zo.id(), burn.min_fuel(), fuel) {
+            log!("Rejecting emergency burn for Zoned Objective {}: {e}.", zo.id());
+            return None;
+        }
+        warn!(
+            "Preparing emergency exit burn for Zoned Objective {} at battery {batt:.1}, \
+             lowest simulated level {:.1}.",
+            zo.id(),
+            report.min_batt()
+        );
+        OBJECTIVE_TRACKER.record(zo.id(), LifecycleStage::Scheduled);
+        Some(Self {
+            target: zo.clone(),
+            exit_burn: relaxed,
+            plan: Mutex::new(plan),
+            burn_started: AtomicBool::new(false),
+            left_orbit: AtomicBool::new(false),
+        })
+    }
+
+    /// Builds the imaging phase following the exit burn, as scheduled by the
+    /// [`ZORetrievalMode`] after detumbling towards the objective.
+    ///
+    /// # Arguments
+    /// * `zo` – The target zoned objective.
+    /// * `exit_burn` – The exit burn with the lowered battery reserve.
+    ///
+    /// # Returns
+    /// * The tasks of the imaging phase and the end of the acquisition cycle.
+    fn imaging_phase_plan(
+        zo: &KnownImgObjective,
+        exit_burn: &ExitBurnResult,
+    ) -> (Vec<Task>, DateTime<Utc>) {
+        let burn = exit_burn.sequence();
+        let burn_end = burn.start_i().t() + TimeBudget::secs(burn.acc_dt());
+        // The first image is never due before the burn is done
+        let detumble_dt = TimeBudget::secs(burn.detumble_dt()).to_delta();
+        let target_t = burn_end + detumble_dt.max(TaskController::ZO_IMAGE_FIRST_DEL);
+        let vel = *burn.sequence_vel().last().unwrap();
+        let lens = ResolutionEstimate::for_objective(zo, vel).lens();
+        let pos = exit_burn.target_pos().wrap_around_map();
+        let plan = TaskController::retrieval_phase_plan(target_t, target_t, pos, lens);
+        let t_first = target_t - TaskController::ZO_IMAGE_FIRST_DEL;
+        let acq_dt = ZORetrievalMode::acquisition_dt(pos, exit_burn.add_target(), vel);
+        (plan, t_first + acq_dt)
+    }
+
+    /// Releases the reserved fuel and restores the optional console traffic.
+    ///
+    /// # Arguments
+    /// * `context` – Shared mode context.
+    fn release(&self, context: &Arc<ModeContext>) {
+        context.k().con().set_power_saving(false);
+        if let Some(fuel) = context.k().fuel().release(self.target.id()) {
+            log!("Released {fuel:.1} fuel reserved for Zoned Objective {}.", self.target.id());
+        }
+    }
+
+    /// Returns the target to the objective buffer, unless it expired.
+    ///
+    /// # Arguments
+    /// * `context` – Shared mode context.
+    async fn stash_target(&self, context: &Arc<ModeContext>) {
+        if Utc::now() < self.target.end() {
+            context.k_buffer().lock().await.push(self.target.clone());
+        } else {
+            OBJECTIVE_TRACKER.fail(self.target.id(), "emergency burn not executed");
+        }
+    }
+}
+
+#[async_trait]
+impl GlobalMode for EmergencyRetrievalMode {
+    /// Returns the internal name of this mode.
+    fn type_name(&self) -> &'static str { Self::MODE_NAME }
+
+    /// Drops the optional console traffic and replaces the schedule with the emergency plan.
+    ///
+    /// # Arguments
+    /// * `context` – Shared mode context.
+    ///
+    /// # Returns
+    /// * `OpExitSignal::Continue` – Always continues with the plan.
+    async fn init_mode(&self, context: Arc<ModeContext>) -> OpExitSignal {
+        context.k().con().set_power_saving(true);
+        let plan = std::mem::take(&mut *self.plan.lock().unwrap());
+        context.k().t_cont().schedule_emergency_plan(plan).await;
+        OBJECTIVE_TRACKER.record(self.target.id(), LifecycleStage::BurnPlanned);
+        OpExitSignal::Continue
+    }
+
+    /// Waits until the due time of the next task or exits early on a Safe Mode event or the
+    /// deletion of a Zoned Objective. New objectives stay queued until the burn is done.
+    ///
+    /// # Arguments
+    /// * `context` – Mode context.
+    /// * `due` – Scheduled execution time.
+    ///
+    /// # Returns
+    /// * `WaitExitSignal` – Indicates continuation or interruption.
+    async fn exec_task_wait(
+        &self,
+        context: Arc<ModeContext>,
+        due: DateTime<Utc>,
+    ) -> WaitExitSignal {
+        let mut safe_mon = context.safe_mon();
+        let mut zo_rem_mon = context.zo_rem_mon().write().await;
+        let dt = (due - Utc::now()).to_std().unwrap_or(DT_0_STD);
+        tokio::select! {
+            () = FlightComputer::wait_for_duration(dt, false) => WaitExitSignal::Continue,
+            () = ModeContext::wait_for_safe(&mut safe_mon) => WaitExitSignal::SafeEvent,
+            Some(ObjectiveEvent::ZoRemoved(id)) = zo_rem_mon.recv() => {
+                WaitExitSignal::ZORemovedEvent(id)
+            }
+        }
+    }
+
+    /// Executes a task of the emergency plan. State switches skip the map exports of the
+    /// base modes, image tasks are not part of the plan. Operator-pinned image tasks are taken
+    /// like in the orbital modes.
+    ///
+    /// # Arguments
+    /// * `context` – Shared mode context.
+    /// * `task` – The task to execute.
+    ///
+    /// # Returns
+    /// * `ExecExitSignal` – Indicates result of execution.
+    async fn exec_task(&self, context: Arc<ModeContext>, task: Task) -> ExecExitSignal {
+        let f_cont = context.k().f_cont();
+        match task.task_type() {
+            BaseTask::SwitchState(switch) => {
+                FlightComputer::set_state_wait(f_cont, switch.target_state()).await;
+            }
+            BaseTask::ChangeVelocity(vel_change) => {
+                let batt = f_cont.read().await.current_battery();
+                log_burn!("Emergency burn started at battery {batt:.1}.");
+                self.burn_started.store(true, Ordering::Release);
+                let never_abort = CancellationToken::new();
+                let burn = FlightComputer::execute_burn(f_cont, vel_change.burn(), &never_abort);
+                let mut safe_mon = context.safe_mon();
+                tokio::select! {
+                    res = burn => {
+                        if let Err(aborted) = res {
+                            return ExecExitSignal::BurnAborted(aborted);
+                        }
+                        self.left_orbit.store(true, Ordering::Release);
+                    }
+                    () = ModeContext::wait_for_safe(&mut safe_mon) => {
+                        log_burn!("Emergency burn interrupted by safe mode event!");
+                        return ExecExitSignal::SafeEvent;
+                    }
+                }
+            }
+            BaseTask::TakeImage(image) if task.is_pinned() => {
+                BaseMode::get_pinned_image(context, *image).await;
+            }
+            BaseTask::TakeImage(_) => fatal!(
+                "Illegal task type {} for state {}!",
+                task.task_type(),
+                Self::MODE_NAME
+            ),
+        }
+        ExecExitSignal::Continue
+    }
+
+    /// Escapes the safe mode and gives up the emergency plan, as the battery is drained.
+    ///
+    /// # Arguments
+    /// * `context` – Shared mode context.
+    ///
+    /// # Returns
+    /// * `OpExitSignal::ReInit` – With an [`OrbitReturnMode`] if the burn already started,
+    ///   otherwise with an [`InOrbitMode`].
+    async fn safe_handler(&self, context: Arc<ModeContext>) -> OpExitSignal {
+        FlightComputer::escape_safe(context.k().f_cont(), false).await;
+        context.finish_phase(self.safe_mode_rationale()).await;
+        context.k().t_cont().clear_schedule().await;
+        self.release(&context);
+        self.stash_target(&context).await;
+        if self.burn_started.load(Ordering::Acquire) {
+            OpExitSignal::ReInit(Box::new(OrbitReturnMode::new()))
+        } else {
+            OpExitSignal::ReInit(Box::new(InOrbitMode::new(BaseMode::MappingMode)))
+        }
+    }
+
+    /// Stashes a newly received zoned objective, as the emergency plan is not re-planned.
+    async fn zo_handler(&self, c: &Arc<ModeContext>, obj: KnownImgObjective) -> OptOpExitSignal {
+        obj!("Objective {} received during emergency retrieval. Stashing!", obj.id());
+        c.k_buffer().lock().await.push(obj);
+        None
+    }
+
+    /// Beacon Objective events are ignored until the emergency burn is done.
+    async fn bo_event_handler(&self, _: &Arc<ModeContext>) -> OptOpExitSignal { None }
+
+    /// Handles the deletion of a Zoned Objective by the backend.
+    ///
+    /// If the target was deleted before the burn started, the emergency plan is cancelled and
+    /// the next mode is selected from the remaining objectives.
+    ///
+    /// # Arguments
+    /// * `context` – Shared mode context.
+    /// * `id` – The ID of the deleted objective.
+    ///
+    /// # Returns
+    /// * `Some(OpExitSignal::ReInit)` if the plan was cancelled.
+    async fn zo_removed_handler(
+        &self,
+        context: &Arc<ModeContext>,
+        id: ImgObjectiveId,
+    ) -> OptOpExitSignal {
+        context.remove_zo(id).await;
+        if id != self.target.id() || self.burn_started.load(Ordering::Acquire) {
+            return None;
+        }
+        obj!("Cancelled emergency burn for deleted Zoned Objective {id}.");
+        context.k().t_cont().clear_schedule().await;
+        self.release(context);
+        context.finish_phase(self.zo_removed_rationale()).await;
+        Some(OpExitSignal::ReInit(OrbitReturnMode::get_next_mode(context).await))
+    }
+
+    /// Handles a timed out initialization by giving up the emergency plan.
+    ///
+    /// # Arguments
+    /// * `context` – Shared mode context.
+    ///
+    /// # Returns
+    /// * `OpExitSignal::ReInit` – With an [`InOrbitMode`].
+    async fn init_timeout_handler(&self, context: Arc<ModeContext>) -> OpExitSignal {
+        context.k().t_cont().clear_schedule().await;
+        self.release(&context);
+        self.stash_target(&context).await;
+        OpExitSignal::ReInit(Box::new(InOrbitMode::new(BaseMode::MappingMode)))
+    }
+
+    /// Finalizes the mode and transitions into a [`ZORetrievalMode`] if the satellite has
+    /// left its orbit.
+    ///
+    /// # Arguments
+    /// * `context` – Shared mode context.
+    ///
+    /// # Returns
+    /// * `Box<dyn GlobalMode>` – The next mode (retrieval or fallback).
+    async fn exit_mode(&self, context: Arc<ModeContext>) -> Box<dyn GlobalMode> {
+        context.finish_phase(self.tasks_done_exit_rationale()).await;
+        self.release(&context);
+        if self.left_orbit.load(Ordering::Acquire) {
+            Box::new(ZORetrievalMode::new(
+                self.target.clone(),
+                self.exit_burn.add_target(),
+                *self.exit_burn.unwrapped_target(),
+                Vec::new(),
+            ))
+        } else {
+            warn!("Emergency plan finished without exit burn.");
+            self.stash_target(&context).await;
+            Box::new(InOrbitMode::new(BaseMode::MappingMode))
+        }
+    }
+}
@@ -1,16 +1,26 @@
 use crate::objective::{BeaconControllerState, KnownImgObjective};
-use crate::scheduling::task::Task;
+use crate::scheduling::task::{Task, TimeResolution};
 use crate::mode_control::{
     base_mode::BaseMode,
     mode_context::ModeContext,
+    mode_graph::TransitionTrigger,
     signal::{ExecExitSignal, OpExitSignal, WaitExitSignal, OptOpExitSignal},
 };
+use super::{
+    in_orbit_mode::InOrbitMode, init_timeout::InitTimeout, mode_snapshot::ModeSnapshot,
+    orbit_return_mode::OrbitReturnMode,
+};
+use crate::flight_control::BurnAborted;
+use crate::util::{
+    EVENT_BUS, ImagingEvent, ImgObjectiveId, MissionConfig, ObjectiveEvent, SafetyEvent,
+    SchedulingEvent, Subscription,
+};
 use crate::{DT_0_STD, fatal, info, log, warn};
 use async_trait::async_trait;
 use chrono::{DateTime, TimeDelta, Utc};
 use std::mem::discriminant;
-use std::{future::Future, pin::Pin, sync::Arc};
-use tokio::{sync::{RwLock, watch::Receiver}, task::JoinError};
+use std::{future::Future, pin::Pin, sync::Arc, time::Duration};
+use tokio::{sync::watch::Receiver, task::JoinError};
 use tokio_util::sync::CancellationToken;
 
 /// Trait representing a high-level operational mode within the onboard Finite-State-Machine (FSM) architecture.
@@ -22,6 +32,8 @@ pub trait GlobalMode: Sync + Send {
     fn safe_mode_rationale(&self) -> &'static str { "SAFE mode Event!" }
     /// Returns the rationale string for finishing the current phase due to a new Zoned Objective.
     fn new_zo_rationale(&self) -> &'static str { "newly discovered ZO!" }
+    /// Returns the rationale string for finishing the current phase due to a deleted Zoned Objective.
+    fn zo_removed_rationale(&self) -> &'static str { "ZO deleted by backend!" }
     /// Returns the rationale string for finishing the current phase due to a new Beacon Objective.
     fn new_bo_rationale(&self) -> &'static str { "newly discovered BO!" }
     /// Returns the rationale string used when the task queue has completed.
@@ -32,12 +44,30 @@ pub trait GlobalMode: Sync + Send {
     }
     /// Returns the rationale for finishing the current phase due to being outside of orbit without a valid reason.
     fn out_of_orbit_rationale(&self) -> &'static str { "out of orbit without purpose!" }
+    /// Returns the rationale for re-planning the current phase after map captures recovered.
+    fn imaging_recovered_rationale(&self) -> &'static str { "map captures recovered!" }
+    /// Returns the rationale for re-planning the current phase after a failed coverage check.
+    fn coverage_catch_up_rationale(&self) -> &'static str { "coverage check failed!" }
+    /// Returns the rationale for finishing the current phase after an operator abort.
+    fn operator_abort_rationale(&self) -> &'static str { "aborted by operator!" }
+    /// Returns the rationale for finishing the current phase after an aborted burn.
+    fn burn_aborted_rationale(&self) -> &'static str { "burn aborted!" }
     /// Returns the rationale used for finishing the current phase when a beacon objective has been completed or expired.
     fn bo_done_rationale(&self) -> &'static str { "BO done or expired!" }
 
     /// Returns the string representation of the current mode.
     fn type_name(&self) -> &'static str;
 
+    /// Returns `true` if the mode handles an operator abort, `false` by default.
+    fn accepts_operator_abort(&self) -> bool { false }
+
+    /// Returns the plan of the mode to persist for resuming it after a restart.
+    ///
+    /// # Returns
+    /// * `Some(ModeSnapshot)` if the plan of the mode would be lost on a restart.
+    /// * `None` by default.
+    fn snapshot(&self) -> Option<ModeSnapshot> { None }
+
     /// Initializes the mode with the provided context.
     ///
     /// # Arguments
@@ -47,6 +77,70 @@ pub trait GlobalMode: Sync + Send {
     /// [`OpExitSignal`] - Signal indicating what action to take after initialization.
     async fn init_mode(&self, context: Arc<ModeContext>) -> OpExitSignal;
 
+    /// Initializes the mode like [`GlobalMode::init_mode`], but takes the fallback of
+    /// [`GlobalMode::init_timeout_handler`] if the initialization does not finish within
+    /// the configured `runtime.init_timeout_secs`.
+    ///
+    /// # Arguments
+    /// * `context` - Shared reference to the current mode context.
+    ///
+    /// # Returns
+    /// [`OpExitSignal`] - Signal indicating what action to take after initialization.
+    async fn init_mode_guarded(&self, context: Arc<ModeContext>) -> OpExitSignal {
+        let timeout = u64::from(MissionConfig::get().runtime.init_timeout_secs);
+        let start = Utc::now();
+        context.set_init_stage("initializing");
+        let init = self.init_mode(Arc::clone(&context));
+        if let Ok(sig) = tokio::time::timeout(Duration::from_secs(timeout), init).await {
+            return sig;
+        }
+        let event = InitTimeout::new(self.type_name(), start, context.init_stage());
+        context.note_transition_trigger(TransitionTrigger::InitTimeout);
+        let fallback = self.init_timeout_handler(context).await;
+        event.resolve(match &fallback {
+            OpExitSignal::ReInit(mode) => mode.type_name(),
+            OpExitSignal::Continue => self.type_name(),
+        });
+        fallback
+    }
+
+    /// Handles a timed out initialization.
+    ///
+    /// By default, the schedule is dropped and MELVIN reverts to an [`InOrbitMode`] in
+    /// mapping mode, whose schedule has the least constraints.
+    ///
+    /// # Arguments
+    /// * `context` - Shared reference to the current mode context.
+    ///
+    /// # Returns
+    /// * `OpExitSignal` - Signal to reinitialize with the fallback mode.
+    async fn init_timeout_handler(&self, context: Arc<ModeContext>) -> OpExitSignal {
+        context.k().t_cont().clear_schedule().await;
+        OpExitSignal::ReInit(Box::new(InOrbitMode::new(BaseMode::MappingMode)))
+    }
+
+    /// Handles a burn sequence that was aborted and rolled back during [`GlobalMode::exec_task`].
+    ///
+    /// By default, the remaining schedule is dropped, as it assumed the completed burn, and
+    /// MELVIN returns to its closed orbit.
+    ///
+    /// # Arguments
+    /// * `context` - Shared reference to the current mode context.
+    /// * `aborted` - The record of the aborted burn.
+    ///
+    /// # Returns
+    /// * `OpExitSignal` - Signal to reinitialize with an [`OrbitReturnMode`].
+    async fn burn_aborted_handler(
+        &self,
+        context: Arc<ModeContext>,
+        aborted: BurnAborted,
+    ) -> OpExitSignal {
+        log!("Returning to the closed orbit from the aborted burn at {}.", aborted.pos());
+        context.k().t_cont().clear_schedule().await;
+        context.finish_phase(self.burn_aborted_rationale()).await;
+        OpExitSignal::ReInit(Box::new(OrbitReturnMode::new()))
+    }
+
     /// Executes all tasks in the current task queue in sequence.
     /// Waits for each task’s scheduled time and handles early exit signals such as safe transitions or new objectives.
     ///
@@ -55,11 +149,11 @@ pub trait GlobalMode: Sync + Send {
     ///
     /// # Returns
     /// * [`OpExitSignal`] - Signal indicating whether to continue or exit the mode.
-    #[allow(clippy::cast_sign_loss, clippy::cast_precision_loss)]
+    #[allow(clippy::cast_sign_loss, clippy::cast_precision_loss, clippy::too_many_lines)]
     async fn exec_task_queue(&self, context: Arc<ModeContext>) -> OpExitSignal {
         let context_local = Arc::clone(&context);
         let mut tasks = 0;
-        while let Some(task) = {
+        'tasks: while let Some(task) = {
             let sched_arc = context_local.k().t_cont().sched_arc();
             let mut sched_lock = sched_arc.write().await;
             let t = sched_lock.pop_front();
@@ -69,38 +163,111 @@ pub trait GlobalMode: Sync + Send {
             let due_time = task.t() - Utc::now();
             let task_type = task.task_type();
             info!("TASK {tasks}: {task_type} in  {}s!", due_time.num_seconds());
-            while task.t() > Utc::now() + TimeDelta::seconds(2) {
+            loop {
+                let fire_t = task.t() - context.command_lead(task_type);
+                if fire_t <= Utc::now() + TimeDelta::seconds(2) {
+                    break;
+                }
                 let context_clone = Arc::clone(&context);
-                match self.exec_task_wait(context_clone, task.t()).await {
+                match self.exec_task_wait(context_clone, fire_t).await {
                     WaitExitSignal::Continue => {}
                     WaitExitSignal::SafeEvent => {
+                        context.note_transition_trigger(TransitionTrigger::SafeEvent);
                         return self.safe_handler(context_local).await;
                     }
                     WaitExitSignal::NewZOEvent(obj) => {
+                        let id = obj.id();
                         if let Some(opt) = self.zo_handler(&context, obj).await {
+                            context.note_transition_trigger(TransitionTrigger::NewZo(id));
                             return opt;
                         };
                     }
+                    WaitExitSignal::ZORemovedEvent(id) => {
+                        if let Some(opt) = self.zo_removed_handler(&context, id).await {
+                            context.note_transition_trigger(TransitionTrigger::ZoRemoved(id));
+                            return opt;
+                        }
+                    }
                     WaitExitSignal::BOEvent => {
                         if let Some(opt) = self.bo_event_handler(&context).await {
+                            context.note_transition_trigger(TransitionTrigger::BoEvent);
                             return opt;
                         };
                     }
+                    WaitExitSignal::ImagingRecovered => {
+                        if let Some(opt) = self.imaging_recovered_handler(&context).await {
+                            context.note_transition_trigger(TransitionTrigger::ImagingRecovered);
+                            return opt;
+                        }
+                    }
+                    WaitExitSignal::CoverageCatchUp => {
+                        if let Some(opt) = self.coverage_catch_up_handler(&context).await {
+                            context.note_transition_trigger(TransitionTrigger::CoverageCatchUp);
+                            return opt;
+                        }
+                    }
+                    WaitExitSignal::OperatorAbort => {
+                        if let Some(opt) = self.operator_abort_handler(&context).await {
+                            context.note_transition_trigger(TransitionTrigger::OperatorAbort);
+                            return opt;
+                        }
+                    }
+                    WaitExitSignal::Preempted => {
+                        log!("TASK {tasks} preempted by an urgent task.");
+                        context.k().t_cont().requeue_preempted(task).await;
+                        continue 'tasks;
+                    }
                 };
             }
-            let task_delay = (task.t() - Utc::now()).num_milliseconds() as f32 / 1000.0;
+            if let Some(dep) = task.dependency() {
+                let t_cont = context.k().t_cont();
+                let mut safe_mon = context.safe_mon();
+                tokio::select! {
+                    exec = t_cont.await_dependency(dep, task.t()) => if !exec {
+                        continue;
+                    },
+                    () = ModeContext::wait_for_safe(&mut safe_mon) => {
+                        context.note_transition_trigger(TransitionTrigger::SafeEvent);
+                        return self.safe_handler(context_local).await;
+                    }
+                }
+            }
+            // Commands are fired ahead by their latency, which needs sub-second precision
+            let lead = context.command_lead(task_type);
+            let fire_t = task.t() - lead;
+            if task_type.resolution() == TimeResolution::Millis || !lead.is_zero() {
+                let mut safe_mon = context.safe_mon();
+                let precise_dt = (fire_t - Utc::now()).to_std().unwrap_or(DT_0_STD);
+                tokio::select! {
+                    () = tokio::time::sleep(precise_dt) => {},
+                    () = ModeContext::wait_for_safe(&mut safe_mon) => {
+                        context.note_transition_trigger(TransitionTrigger::SafeEvent);
+                        return self.safe_handler(context_local).await;
+                    }
+                }
+            }
+            let task_delay = (fire_t - Utc::now()).num_milliseconds() as f32 / 1000.0;
             if task_delay.abs() > 2.0 {
                 log!("Task {tasks} delayed by {task_delay}s!");
             }
+            let (task_name, planned, started) = (task_type.to_string(), task.t(), Utc::now());
             let context_clone = Arc::clone(&context);
-            match self.exec_task(context_clone, task).await {
+            let exec_sig = self.exec_task(context_clone, task).await;
+            let t_cont = context.k().t_cont();
+            t_cont.record_task_timing(&task_name, planned, started, Utc::now(), lead).await;
+            match exec_sig {
                 ExecExitSignal::Continue => {}
                 ExecExitSignal::SafeEvent => {
+                    context.note_transition_trigger(TransitionTrigger::SafeEvent);
                     return self.safe_handler(context_local).await;
                 }
                 ExecExitSignal::NewZOEvent(_) => {
                     fatal!("Unexpected task exit signal!");
                 }
+                ExecExitSignal::BurnAborted(aborted) => {
+                    context.note_transition_trigger(TransitionTrigger::BurnAborted);
+                    return self.burn_aborted_handler(context_local, aborted).await;
+                }
             };
             tasks += 1;
         }
@@ -151,6 +318,22 @@ pub trait GlobalMode: Sync + Send {
         obj: KnownImgObjective,
     ) -> OptOpExitSignal;
     
+    /// Handles the deletion of a Zoned Objective by the backend.
+    ///
+    /// By default, the objective is only dropped from the objective buffer, as no plan of
+    /// the mode depends on it.
+    ///
+    /// # Arguments
+    /// * `context` - Shared reference to the mode context.
+    /// * `id` - The ID of the deleted objective.
+    ///
+    /// # Returns
+    /// * `OptOpExitSignal` - Optional signal indicating a mode switch or continuation.
+    async fn zo_removed_handler(&self, context: &Arc<ModeContext>, id: ImgObjectiveId) -> OptOpExitSignal {
+        context.remove_zo(id).await;
+        None
+    }
+
     /// Handles beacon-related events (e.g., mode changes or new signals).
     ///
     /// # Arguments
@@ -160,6 +343,44 @@ pub trait GlobalMode: Sync + Send {
     /// * `OptOpExitSignal` - Optional signal indicating a mode switch or continuation.
     async fn bo_event_handler(&self, context: &Arc<ModeContext>) -> OptOpExitSignal;
 
+    /// Handles the recovery of map captures after a persistent backend failure.
+    ///
+    /// # Arguments
+    /// * `context` - Shared reference to the mode context.
+    ///
+    /// # Returns
+    /// * `OptOpExitSignal` - Optional signal to re-plan a catch-up schedule. Defaults to `None`.
+    async fn imaging_recovered_handler(&self, _context: &Arc<ModeContext>) -> OptOpExitSignal {
+        None
+    }
+
+    /// Handles a failed coverage check that reopened stale orbit stripes for imaging.
+    ///
+    /// # Arguments
+    /// * `context` - Shared reference to the mode context.
+    ///
+    /// # Returns
+    /// * `OptOpExitSignal` - Optional signal to re-plan a catch-up schedule. Defaults to `None`.
+    async fn coverage_catch_up_handler(&self, _context: &Arc<ModeContext>) -> OptOpExitSignal {
+        None
+    }
+
+    /// Handles an abort of the current mode issued by an operator from the console.
+    ///
+    /// By default, the schedule is dropped and MELVIN reverts to an [`InOrbitMode`] in
+    /// mapping mode. Only modes waiting on the closed orbit monitor operator aborts.
+    ///
+    /// # Arguments
+    /// * `context` - Shared reference to the mode context.
+    ///
+    /// # Returns
+    /// * `OptOpExitSignal` - Optional signal to reinitialize with the fallback mode.
+    async fn operator_abort_handler(&self, context: &Arc<ModeContext>) -> OptOpExitSignal {
+        context.k().t_cont().clear_schedule().await;
+        context.finish_phase(self.operator_abort_rationale()).await;
+        Some(OpExitSignal::ReInit(Box::new(InOrbitMode::new(BaseMode::MappingMode))))
+    }
+
     /// Handles cleanup and transition logic when exiting a mode.
     ///
     /// # Arguments
@@ -200,7 +421,9 @@ pub(super) trait OrbitalMode: GlobalMode {
     /// While waiting, this method concurrently monitors for:
     /// - Safe mode triggers
     /// - New zoned objectives (ZO)
+    /// - Zoned objectives deleted by the backend
     /// - Beacon state changes (BO)
+    /// - Recovery of degraded map captures
     ///
     /// It also supports short or long sleep strategies depending on how far the task lies in the future.
     ///
@@ -215,9 +438,10 @@ pub(super) trait OrbitalMode: GlobalMode {
         context: Arc<ModeContext>,
         due: DateTime<Utc>,
     ) -> WaitExitSignal {
-        let safe_mon = context.super_v().safe_mon();
+        let mut safe_mon = context.safe_mon();
         let mut zo_mon = context.zo_mon().write().await;
-        let bo_mon = context.bo_mon();
+        let mut zo_rem_mon = context.zo_rem_mon().write().await;
+        let bo_mon = context.bo_watch();
         let cancel_task = CancellationToken::new();
 
         let fut: Pin<Box<dyn Future<Output = Result<_, JoinError>> + Send>> =
@@ -232,13 +456,17 @@ pub(super) trait OrbitalMode: GlobalMode {
                 })
             };
         let bo_change_signal = self.base().get_rel_bo_event();
+        let capture_mon = context.k().c_cont().capture_health_watch().await;
+        let imaging_sub = EVENT_BUS.subscribe::<ImagingEvent>();
+        let safety_sub = EVENT_BUS.subscribe::<SafetyEvent>();
+        let sched_sub = EVENT_BUS.subscribe::<SchedulingEvent>();
         tokio::pin!(fut);
         tokio::select! {
             exit_sig = &mut fut => {
                 exit_sig.unwrap_or_else(|_|fatal!("Task wait hung up!"));
                 WaitExitSignal::Continue
             },
-            () = safe_mon.notified() => {
+            () = ModeContext::wait_for_safe(&mut safe_mon) => {
                 cancel_task.cancel();
                 fut.await.ok();
                 WaitExitSignal::SafeEvent
@@ -249,11 +477,36 @@ pub(super) trait OrbitalMode: GlobalMode {
                 fut.await.ok();
                 WaitExitSignal::NewZOEvent(img_obj)
             }
+            Some(ObjectiveEvent::ZoRemoved(id)) = zo_rem_mon.recv() => {
+                cancel_task.cancel();
+                fut.await.ok();
+                WaitExitSignal::ZORemovedEvent(id)
+            }
             () = Self::monitor_bo_mon_change(bo_change_signal, bo_mon) => {
                 cancel_task.cancel();
                 fut.await.ok();
                 WaitExitSignal::BOEvent
             }
+            () = Self::monitor_capture_recovery(capture_mon) => {
+                cancel_task.cancel();
+                fut.await.ok();
+                WaitExitSignal::ImagingRecovered
+            }
+            () = Self::monitor_coverage_catch_up(imaging_sub) => {
+                cancel_task.cancel();
+                fut.await.ok();
+                WaitExitSignal::CoverageCatchUp
+            }
+            () = Self::monitor_operator_abort(safety_sub) => {
+                cancel_task.cancel();
+                fut.await.ok();
+                WaitExitSignal::OperatorAbort
+            }
+            () = Self::monitor_preemption(sched_sub, due) => {
+                cancel_task.cancel();
+                fut.await.ok();
+                WaitExitSignal::Preempted
+            }
 
         }
     }
@@ -268,12 +521,11 @@ pub(super) trait OrbitalMode: GlobalMode {
     /// * `bo_mon` – A watch receiver providing asynchronous access to beacon state changes.
     async fn monitor_bo_mon_change(
         sig: BeaconControllerState,
-        bo_mon: &RwLock<Receiver<BeaconControllerState>>,
+        mut bo_mon: Receiver<BeaconControllerState>,
     ) {
-        let mut bo_mon_lock = bo_mon.write().await;
         loop {
-            if let Ok(()) = bo_mon_lock.changed().await {
-                let sent_sig = bo_mon_lock.borrow_and_update();
+            if let Ok(()) = bo_mon.changed().await {
+                let sent_sig = bo_mon.borrow_and_update();
                 let sent_sig_ref = &*sent_sig;
                 if discriminant(&sig) == discriminant(sent_sig_ref) {
                     return;
@@ -282,6 +534,59 @@ pub(super) trait OrbitalMode: GlobalMode {
         }
     }
 
+    /// Waits until map captures recover from a degradation.
+    ///
+    /// # Arguments
+    /// * `capture_mon` – A watch receiver publishing `true` while map captures are degraded.
+    async fn monitor_capture_recovery(mut capture_mon: Receiver<bool>) {
+        while capture_mon.changed().await.is_ok() {
+            if !*capture_mon.borrow_and_update() {
+                return;
+            }
+        }
+        std::future::pending::<()>().await;
+    }
+
+    /// Waits until a failed coverage check requests catch-up imaging.
+    ///
+    /// # Arguments
+    /// * `imaging_sub` – A subscription to the [`ImagingEvent`] topic.
+    async fn monitor_coverage_catch_up(mut imaging_sub: Subscription<ImagingEvent>) {
+        while let Some(event) = imaging_sub.recv().await {
+            if matches!(event, ImagingEvent::CoverageCatchUp) {
+                return;
+            }
+        }
+        std::future::pending::<()>().await;
+    }
+
+    /// Waits until an operator aborts the current mode.
+    ///
+    /// # Arguments
+    /// * `safety_sub` – A subscription to the [`SafetyEvent`] topic.
+    async fn monitor_operator_abort(mut safety_sub: Subscription<SafetyEvent>) {
+        while let Some(event) = safety_sub.recv().await {
+            if matches!(event, SafetyEvent::OperatorAbort) {
+                return;
+            }
+        }
+        std::future::pending::<()>().await;
+    }
+
+    /// Waits until an urgent task due before `due` is spliced into the schedule.
+    ///
+    /// # Arguments
+    /// * `sched_sub` – A subscription to the [`SchedulingEvent`] topic.
+    /// * `due` – The due time of the awaited task.
+    async fn monitor_preemption(mut sched_sub: Subscription<SchedulingEvent>, due: DateTime<Utc>) {
+        while let Some(event) = sched_sub.recv().await {
+            if matches!(event, SchedulingEvent::UrgentSpliced(t) if t < due) {
+                return;
+            }
+        }
+        std::future::pending::<()>().await;
+    }
+
     /// Logs a beacon-related event and finalizes the orbit at the current satellite position.
     ///
     /// This is used to capture the reason for switching out of the current [`BaseMode`],
@@ -292,14 +597,8 @@ pub(super) trait OrbitalMode: GlobalMode {
     /// * `base` – The [`BaseMode`] that determines the rationale for finishing the orbit segment.
     async fn log_bo_event(&self, context: &Arc<ModeContext>, base: BaseMode) {
         match base {
-            BaseMode::BeaconObjectiveScanningMode => context.o_ch_lock().write().await.finish(
-                context.k().f_cont().read().await.current_pos(),
-                self.new_bo_rationale(),
-            ),
-            BaseMode::MappingMode => context.o_ch_lock().write().await.finish(
-                context.k().f_cont().read().await.current_pos(),
-                self.bo_done_rationale(),
-            ),
+            BaseMode::BeaconObjectiveScanningMode => context.finish_phase(self.new_bo_rationale()).await,
+            BaseMode::MappingMode => context.finish_phase(self.bo_done_rationale()).await,
         }
     }
 }
@@ -1,7 +1,12 @@
 use crate::scheduling::task::{BaseTask, Task};
 use crate::objective::KnownImgObjective;
 use crate::flight_control::FlightComputer;
-use super::{zo_prep_mode::ZOPrepMode, global_mode::{GlobalMode, OrbitalMode}};
+use super::{
+    emergency_retrieval_mode::EmergencyRetrievalMode,
+    global_mode::{GlobalMode, OrbitalMode},
+    secret_objective_mode::SecretObjectiveMode,
+    zo_prep_mode::ZOPrepMode,
+};
 use crate::mode_control::{
     base_mode::BaseMode,
     mode_context::ModeContext,
@@ -28,7 +33,7 @@ pub struct InOrbitMode {
 
 impl InOrbitMode {
     /// The internal name of the mode used for logging and identification.
-    const MODE_NAME: &'static str = "InOrbitMode";
+    pub(crate) const MODE_NAME: &'static str = "InOrbitMode";
 
     /// Constructs a new [`InOrbitMode`] instance using the given [`BaseMode`].
     ///
@@ -50,6 +55,9 @@ impl GlobalMode for InOrbitMode {
     /// Returns the static name of this mode.
     fn type_name(&self) -> &'static str { Self::MODE_NAME }
 
+    /// Returns `true`, as the mode drops its schedule on an operator abort.
+    fn accepts_operator_abort(&self) -> bool { true }
+
     /// Initializes the mode by running scheduling logic and listening for early exit signals.
     ///
     /// Reacts to Safe Mode signals and reinitializes if needed. Otherwise, continues
@@ -68,12 +76,13 @@ impl GlobalMode for InOrbitMode {
             self.base.get_schedule_handle(Arc::clone(&context), cancel_clone, comms_end, None).await
         };
         tokio::pin!(sched_handle);
-        let safe_mon = context.super_v().safe_mon();
+        let mut safe_mon = context.safe_mon();
         tokio::select!(
             _ = &mut sched_handle => {
                 context.k().con().send_tasklist().await;
+                context.refresh_timeline().await;
             },
-            () = safe_mon.notified() => {
+            () = ModeContext::wait_for_safe(&mut safe_mon) => {
                 cancel_task.cancel();
                 sched_handle.await.ok();
 
@@ -98,7 +107,8 @@ impl GlobalMode for InOrbitMode {
 
     /// Executes a single scheduled task.
     ///
-    /// Only state-switching tasks are valid in this mode. Other task types will cause a fatal error.
+    /// Only state-switching tasks and operator-pinned image tasks are valid in this mode. Other
+    /// task types will cause a fatal error.
     ///
     /// # Arguments
     /// * `context` – Mode context for task execution.
@@ -109,6 +119,9 @@ impl GlobalMode for InOrbitMode {
     async fn exec_task(&self, context: Arc<ModeContext>, task: Task) -> ExecExitSignal {
         match task.task_type() {
             BaseTask::SwitchState(switch) => self.base.get_task(context, *switch).await,
+            BaseTask::TakeImage(image) if task.is_pinned() => {
+                BaseMode::get_pinned_image(context, *image).await;
+            }
             _ => {
                 fatal!(
                     "Illegal task type {} for state {}!",
@@ -130,16 +143,15 @@ impl GlobalMode for InOrbitMode {
     /// * `OpExitSignal::ReInit` – Always reinitializes the current mode.
     async fn safe_handler(&self, context: Arc<ModeContext>) -> OpExitSignal {
         FlightComputer::escape_safe(context.k().f_cont(), false).await;
-        context.o_ch_lock().write().await.finish(
-            context.k().f_cont().read().await.current_pos(),
-            self.safe_mode_rationale(),
-        );
+        context.finish_phase(self.safe_mode_rationale()).await;
         OpExitSignal::ReInit(Box::new(self.clone()))
     }
 
     /// Handles the detection of a new Zoned Objective.
     ///
-    /// Attempts to switch to a `ZOPrepMode`. If the objective is unreachable, logs a warning and continues.
+    /// Attempts to switch to a `ZOPrepMode`, or to an `EmergencyRetrievalMode` if the battery
+    /// can not be charged for the exit burn in time. If the objective is unreachable, logs a
+    /// warning and continues.
     ///
     /// # Arguments
     /// * `c` – Shared context.
@@ -152,11 +164,16 @@ impl GlobalMode for InOrbitMode {
         let id = obj.id();
         obj!("Found new Zoned Objective {id}!");
 
-        if let Some(zo_mode) = ZOPrepMode::from_obj(c, obj, self.base).await {
-            c.o_ch_lock().write().await.finish(
-                c.k().f_cont().read().await.current_pos(),
-                self.new_zo_rationale(),
-            );
+        let Some(exit_burn) = c.plan_exit_burn(&obj).await else {
+            warn!("Skipping Objective, burn not feasible.");
+            return None;
+        };
+        if let Some(emergency) = EmergencyRetrievalMode::try_new(c, &obj, &exit_burn).await {
+            c.finish_phase(self.new_zo_rationale()).await;
+            return Some(OpExitSignal::ReInit(Box::new(emergency)));
+        }
+        if let Some(zo_mode) = ZOPrepMode::from_burn(c, obj, exit_burn, self.base).await {
+            c.finish_phase(self.new_zo_rationale()).await;
             Some(OpExitSignal::ReInit(Box::new(zo_mode)))
         } else {
             warn!("Skipping Objective, burn not feasible.");
@@ -178,18 +195,45 @@ impl GlobalMode for InOrbitMode {
         Some(OpExitSignal::ReInit(Box::new(Self { base })))
     }
 
+    /// Re-plans the current orbit after map captures recovered, so that the coverage missed
+    /// during the outage is caught up.
+    ///
+    /// # Arguments
+    /// * `context` – Shared context.
+    ///
+    /// # Returns
+    /// * `Some(OpExitSignal::ReInit)` – Always re-plans with the same base mode.
+    async fn imaging_recovered_handler(&self, context: &Arc<ModeContext>) -> OptOpExitSignal {
+        context.finish_phase(self.imaging_recovered_rationale()).await;
+        Some(OpExitSignal::ReInit(Box::new(self.clone())))
+    }
+
+    /// Re-plans the current orbit after a failed coverage check, so that the reopened stale
+    /// stripes are imaged before the daily map upload.
+    ///
+    /// # Arguments
+    /// * `context` – Shared context.
+    ///
+    /// # Returns
+    /// * `Some(OpExitSignal::ReInit)` – Always re-plans with the same base mode.
+    async fn coverage_catch_up_handler(&self, context: &Arc<ModeContext>) -> OptOpExitSignal {
+        context.finish_phase(self.coverage_catch_up_rationale()).await;
+        Some(OpExitSignal::ReInit(Box::new(self.clone())))
+    }
+
     /// Performs final cleanup when exiting the mode and marks the phase as finished.
     ///
     /// # Arguments
     /// * `context` – Shared context.
     ///
     /// # Returns
-    /// * `Box<dyn GlobalMode>` – A boxed copy of the current mode.
+    /// * `Box<dyn GlobalMode>` – A [`SecretObjectiveMode`] if secret objectives are hunted,
+    ///   otherwise a boxed copy of the current mode.
     async fn exit_mode(&self, context: Arc<ModeContext>) -> Box<dyn GlobalMode> {
-        context.o_ch_lock().write().await.finish(
-            context.k().f_cont().read().await.current_pos(),
-            self.tasks_done_rationale(),
-        );
+        context.finish_phase(self.tasks_done_rationale()).await;
+        if let Some(secret_mode) = SecretObjectiveMode::try_new(&context, self.base).await {
+            return Box::new(secret_mode);
+        }
         Box::new(self.clone())
     }
 }
@@ -0,0 +1,55 @@
+use crate::util::logger::JsonDump;
+use crate::warn;
+use chrono::{DateTime, Utc};
+
+/// Record of a mode initialization that did not finish within the configured timeout.
+///
+/// Initializations may block indefinitely, e.g. when awaiting a charge level that is never
+/// reached due to a miscomputed target. Such an event captures what the initialization was
+/// waiting on and which fallback was taken instead, and is dumped for later analysis.
+#[derive(Debug, Clone, serde::Serialize)]
+pub(super) struct InitTimeout {
+    /// The name of the mode whose initialization timed out.
+    mode: &'static str,
+    /// The time the initialization was started.
+    start: DateTime<Utc>,
+    /// The time the timeout elapsed.
+    timeout_t: DateTime<Utc>,
+    /// What the initialization was waiting on when the timeout elapsed.
+    waiting_on: &'static str,
+    /// The name of the fallback mode.
+    fallback: Option<&'static str>,
+}
+
+impl JsonDump for InitTimeout {
+    /// Returns a unique filename based on the mode name and the timeout time.
+    fn file_name(&self) -> String { format!("{}_{}", self.mode, self.timeout_t.timestamp()) }
+
+    /// Specifies the output directory for dumped initialization timeouts.
+    fn dir_name(&self) -> &'static str { "init_timeouts" }
+}
+
+impl InitTimeout {
+    /// Creates a new [`InitTimeout`] record and logs it.
+    ///
+    /// # Arguments
+    /// * `mode` – The name of the mode whose initialization timed out.
+    /// * `start` – The time the initialization was started.
+    /// * `waiting_on` – What the initialization was waiting on.
+    pub(super) fn new(mode: &'static str, start: DateTime<Utc>, waiting_on: &'static str) -> Self {
+        let timeout_t = Utc::now();
+        let secs = (timeout_t - start).num_seconds();
+        warn!("Initialization of {mode} timed out after {secs}s while {waiting_on}!");
+        Self { mode, start, timeout_t, waiting_on, fallback: None }
+    }
+
+    /// Records the fallback mode and dumps the record.
+    ///
+    /// # Arguments
+    /// * `fallback` – The name of the fallback mode.
+    pub(super) fn resolve(mut self, fallback: &'static str) {
+        warn!("Falling back to {fallback} after {} init timeout.", self.mode);
+        self.fallback = Some(fallback);
+        self.dump_json();
+    }
+}
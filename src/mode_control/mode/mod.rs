@@ -1,12 +1,22 @@
 //! This module organizes and exposes various operational modes for the system, 
-//! including the abstract global mode trait, in orbit mode, and zoned objective preparation/
-//! retrieval modes. Each mode is implemented in its respective submodule.
+//! including the abstract global mode trait, in orbit mode, zoned objective
+//! preparation/retrieval modes, the emergency retrieval and the secret objective hunt. Each mode is implemented in its respective submodule.
 
+mod burn_collision;
+mod burn_sharing;
+mod emergency_retrieval_mode;
 mod global_mode;
 mod in_orbit_mode;
+mod init_timeout;
+mod mode_snapshot;
 mod orbit_return_mode;
+mod secret_objective_mode;
 mod zo_prep_mode;
 mod zo_retrieval_mode;
 
+pub(super) use in_orbit_mode::InOrbitMode;
 pub(crate) use orbit_return_mode::OrbitReturnMode;
-pub(crate) use global_mode::GlobalMode;
\ No newline at end of file
+pub(crate) use global_mode::GlobalMode;
+pub(crate) use mode_snapshot::ModeSnapshot;
+#[cfg(test)]
+pub(crate) use zo_prep_mode::ZOPrepSnapshot;
\ No newline at end of file
@@ -0,0 +1,36 @@
+use super::{global_mode::GlobalMode, zo_prep_mode::{ZOPrepMode, ZOPrepSnapshot}};
+use crate::mode_control::mode_context::ModeContext;
+use std::sync::Arc;
+
+/// The persisted plan of a global mode that can be resumed after a restart.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub(crate) enum ModeSnapshot {
+    /// A [`ZOPrepMode`] whose exit burn did not start yet.
+    ZOPrep(ZOPrepSnapshot),
+}
+
+impl ModeSnapshot {
+    /// Returns the name of the persisted mode.
+    pub(crate) fn mode_name(&self) -> &'static str {
+        match self {
+            Self::ZOPrep(_) => ZOPrepMode::MODE_NAME,
+        }
+    }
+
+    /// Rebuilds the persisted mode if its plan is still feasible.
+    ///
+    /// # Arguments
+    /// * `context` – Shared mode context.
+    ///
+    /// # Returns
+    /// * `Some(Box<dyn GlobalMode>)` – The resumed mode.
+    /// * `None` if the plan can no longer be executed.
+    pub(crate) async fn resume(self, context: &Arc<ModeContext>) -> Option<Box<dyn GlobalMode>> {
+        match self {
+            Self::ZOPrep(snapshot) => {
+                let mode = ZOPrepMode::resume(context, snapshot).await?;
+                Some(Box::new(mode))
+            }
+        }
+    }
+}
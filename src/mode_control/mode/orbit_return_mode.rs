@@ -1,16 +1,22 @@
-use crate::flight_control::FlightComputer;
+use crate::flight_control::{FlightComputer, orbit::OrbitBase};
 use crate::objective::{BeaconControllerState, KnownImgObjective};
 use crate::scheduling::{TaskController, task::Task};
-use super::{global_mode::GlobalMode, in_orbit_mode::InOrbitMode, zo_prep_mode::ZOPrepMode};
+use crate::util::MissionConfig;
+use super::{
+    emergency_retrieval_mode::EmergencyRetrievalMode, global_mode::GlobalMode,
+    in_orbit_mode::InOrbitMode,
+    secret_objective_mode::SecretObjectiveMode, zo_prep_mode::ZOPrepMode,
+};
 use crate::mode_control::{
     base_mode::BaseMode,
     mode_context::ModeContext,
+    orbit_replanner::replace_orbit,
     signal::{ExecExitSignal, OpExitSignal, WaitExitSignal, OptOpExitSignal},
 };
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use std::sync::Arc;
-use crate::{log, obj};
+use crate::{log, obj, warn};
 
 /// [`OrbitReturnMode`] is a transitional mode used after executing an out-of-orbit maneuver to
 /// complete a zoned objective. It ensures the satellite returns to a valid
@@ -37,8 +43,9 @@ impl OrbitReturnMode {
     /// Selects and returns the appropriate next mode after orbit reentry.
     ///
     /// This function inspects the beacon controller and objective buffer to decide
-    /// whether to transition into a [`ZOPrepMode`] (if valid objectives exist) or fallback
-    /// to [`InOrbitMode`] using the appropriate [`BaseMode`].
+    /// whether to transition into a [`ZOPrepMode`] (if valid objectives exist), a
+    /// [`SecretObjectiveMode`] (if secret objectives are hunted) or fallback to [`InOrbitMode`]
+    /// using the appropriate [`BaseMode`].
     ///
     /// # Arguments
     /// * `context` – Shared mode context containing state and signal access.
@@ -46,30 +53,99 @@ impl OrbitReturnMode {
     /// # Returns
     /// * `Box<dyn GlobalMode>` – The next mode to enter after completing return procedures.
     pub(crate) async fn get_next_mode(context: &Arc<ModeContext>) -> Box<dyn GlobalMode> {
-        let next_base_mode = Self::get_next_base_mode(context).await;
-        let mut obj_mon = context.zo_mon().write().await;
-        let mut k_buffer = context.k_buffer().lock().await;
-        while let Ok(obj) = obj_mon.try_recv() {
-            obj!("Found Zoned Objective, ID: {} in mode {}. Stashing!", obj.id(), Self::MODE_NAME);
-            k_buffer.push(obj);
-        };        
-        k_buffer.retain(|obj| {
-            if Utc::now() > obj.end() {
-                obj!("Zoned Objective, ID: {} is expired", obj.id());
-                return false;
+        let next_base_mode = Self::get_next_base_mode(context);
+        let candidates: Vec<_> = {
+            let mut obj_mon = context.zo_mon().write().await;
+            let mut k_buffer = context.k_buffer().lock().await;
+            while let Ok(obj) = obj_mon.try_recv() {
+                let id = obj.id();
+                obj!("Found Zoned Objective, ID: {id} in mode {}. Stashing!", Self::MODE_NAME);
+                k_buffer.push(obj);
+            }
+            k_buffer.retain(|obj| {
+                if Utc::now() > obj.end() {
+                    obj!("Zoned Objective, ID: {} is expired", obj.id());
+                    return false;
+                }
+                true
+            });
+            k_buffer.drain().collect()
+        };
+        let mut valid = Vec::with_capacity(candidates.len());
+        for obj in candidates {
+            if context.is_zo_removed(obj.id()).await {
+                obj!("Zoned Objective, ID: {} was deleted by the backend", obj.id());
+            } else {
+                valid.push(obj);
+            }
+        }
+        let ranked = context.select_objectives(valid).await;
+        // Lower ranked objectives stay buffered, so that they may share the selected exit burn
+        context.k_buffer().lock().await.extend(ranked.iter().map(|(obj, _)| obj.clone()));
+        for (obj, exit_burn) in ranked {
+            context.k_buffer().lock().await.retain(|buffered| buffered.id() != obj.id());
+            if let Some(emergency) = EmergencyRetrievalMode::try_new(context, &obj, &exit_burn).await
+            {
+                return Box::new(emergency);
             }
-            true
-        });
-        while let Some(obj) = k_buffer.pop() {
-            let res = ZOPrepMode::from_obj(context, obj, next_base_mode).await;
+            let res = ZOPrepMode::from_burn(context, obj, exit_burn, next_base_mode).await;
             if let Some(prep_mode) = res {
                 return Box::new(prep_mode);
             }
         }
+        if let Some(secret_mode) = SecretObjectiveMode::try_new(context, next_base_mode).await {
+            log!("No Zoned Objective left. Hunting Secret Objectives!");
+            return Box::new(secret_mode);
+        }
         log!("No Zoned Objective left. Starting InOrbitMode!");
         Box::new(InOrbitMode::new(next_base_mode))
     }
 
+    /// Switches to the return orbit profile of the [`MissionConfig`], if it differs from the
+    /// current closed orbit.
+    ///
+    /// The profile is checked for closure and overlap before its velocity is reached. The closed
+    /// orbit is then replanned from the reached state, so that no return maneuver is needed.
+    ///
+    /// # Arguments
+    /// * `context` – Shared mode context.
+    ///
+    /// # Returns
+    /// * `true` if MELVIN is on a replanned closed orbit of the return profile.
+    async fn switch_to_return_profile(context: &Arc<ModeContext>) -> bool {
+        let Some(profile) = MissionConfig::get().orbit.return_profile().cloned() else {
+            return false;
+        };
+        let orbit_vel = *context.k().c_orbit().read().await.base_orbit_ref().vel();
+        if profile.matches(orbit_vel) {
+            return false;
+        }
+        let f_cont_lock = context.k().f_cont();
+        let pos = f_cont_lock.read().await.current_pos();
+        if let Err(e) = profile.check(pos) {
+            warn!("Return orbit profile {} is not usable: {e}. Keeping the orbit.", profile.name);
+            return false;
+        }
+        context.set_init_stage("reaching return profile velocity");
+        FlightComputer::get_to_orbit_vel(&f_cont_lock, profile.vel()).await;
+        let (base, pos) = {
+            let f_cont = f_cont_lock.read().await;
+            (OrbitBase::new(&f_cont), f_cont.current_pos())
+        };
+        match profile.closed_orbit(base) {
+            Ok(new_orbit) => {
+                log!("Switching to return orbit profile {}.", profile.name);
+                replace_orbit(context, new_orbit, pos).await;
+                true
+            }
+            Err(e) => {
+                let name = &profile.name;
+                warn!("Return orbit profile {name} is not usable: {e}. Keeping the orbit.");
+                false
+            }
+        }
+    }
+
     /// Selects the appropriate [`BaseMode`] to use after orbit return.
     ///
     /// This is determined based on the state of the beacon controller.
@@ -79,12 +155,8 @@ impl OrbitReturnMode {
     ///
     /// # Returns
     /// * `BaseMode` – Either `MappingMode` or `BeaconObjectiveScanningMode`.
-    async fn get_next_base_mode(context: &Arc<ModeContext>) -> BaseMode {
-        let beacon_cont_state = {
-            let mut bo_mon = context.bo_mon().write().await;
-            *bo_mon.borrow_and_update()
-        };
-        match beacon_cont_state {
+    fn get_next_base_mode(context: &Arc<ModeContext>) -> BaseMode {
+        match context.bo_state() {
             BeaconControllerState::ActiveBeacons => BaseMode::BeaconObjectiveScanningMode,
             BeaconControllerState::NoActiveBeacons => BaseMode::MappingMode,
         }
@@ -98,6 +170,7 @@ impl GlobalMode for OrbitReturnMode {
 
     /// Initializes the orbit return procedure, performing reentry maneuvers and
     /// charging if needed. Handles safe mode interruptions and stores orbit state.
+    /// A configured return orbit profile replaces the closed orbit instead.
     ///
     /// # Arguments
     /// * `context` – Shared mode context.
@@ -105,24 +178,33 @@ impl GlobalMode for OrbitReturnMode {
     /// # Returns
     /// * `OpExitSignal` – Indicates continuation or reinitialization.
     async fn init_mode(&self, context: Arc<ModeContext>) -> OpExitSignal {
-        let safe_mon = context.super_v().safe_mon();
+        let mut safe_mon = context.safe_mon();
         let f_cont_clone = context.k().f_cont().clone();
         let fut = async {
-            FlightComputer::get_to_static_orbit_vel(&f_cont_clone).await;
+            if Self::switch_to_return_profile(&context).await {
+                return None;
+            }
+            context.set_init_stage("reaching orbit velocity");
+            let orbit_vel = *context.k().c_orbit().read().await.base_orbit_ref().vel();
+            FlightComputer::get_to_orbit_vel(&f_cont_clone, orbit_vel).await;
             let max_maneuver_batt = FlightComputer::max_or_maneuver_charge();
             let batt = f_cont_clone.read().await.current_battery();
             if batt < max_maneuver_batt {
+                context.set_init_stage("charging for orbit return maneuver");
                 FlightComputer::charge_to_wait(&f_cont_clone, max_maneuver_batt).await;
             }
+            context.set_init_stage("orbit return maneuver");
             FlightComputer::or_maneuver(context.k().f_cont(), context.k().c_orbit()).await
         };
         tokio::select! {
-        new_i = fut => {
-                let pos = context.k().f_cont().read().await.current_pos();
-                context.o_ch_lock().write().await.finish_entry(pos, new_i);
+        entry_i = fut => {
+                if let Some(new_i) = entry_i {
+                    let pos = context.k().f_cont().read().await.current_pos();
+                    context.o_ch_modify(|o_ch| o_ch.finish_entry(pos, new_i));
+                }
                 OpExitSignal::ReInit(self.exit_mode(context).await)
             },
-        () = safe_mon.notified() => self.safe_handler(context).await
+        () = ModeContext::wait_for_safe(&mut safe_mon) => self.safe_handler(context).await
         }
     }
 
@@ -148,6 +230,15 @@ impl GlobalMode for OrbitReturnMode {
         OpExitSignal::ReInit(Box::new(OrbitReturnMode::new()))
     }
 
+    /// Handles a timed out initialization by retrying the orbit return, as no other mode is
+    /// applicable outside the closed orbit.
+    ///
+    /// # Returns
+    /// * `OpExitSignal::ReInit` – Always restarts orbit return procedures.
+    async fn init_timeout_handler(&self, _: Arc<ModeContext>) -> OpExitSignal {
+        OpExitSignal::ReInit(Box::new(OrbitReturnMode::new()))
+    }
+
     /// Handles discovery of a new Zoned Objective during return. Stashes it into the buffer.
     ///
     /// # Arguments
@@ -175,8 +266,8 @@ impl GlobalMode for OrbitReturnMode {
     /// # Returns
     /// * `Box<dyn GlobalMode>` – The next mode to run.
     async fn exit_mode(&self, c: Arc<ModeContext>) -> Box<dyn GlobalMode> {
-        if c.k().f_cont().read().await.current_battery() < TaskController::MIN_BATTERY_THRESHOLD {
-            FlightComputer::charge_to_wait(&c.k().f_cont(), TaskController::MIN_BATTERY_THRESHOLD)
+        if c.k().f_cont().read().await.current_battery() < TaskController::min_battery_threshold() {
+            FlightComputer::charge_to_wait(&c.k().f_cont(), TaskController::min_battery_threshold())
                 .await;
         }
         Self::get_next_mode(&c).await
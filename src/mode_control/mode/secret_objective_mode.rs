@@ -0,0 +1,154 @@
+use crate::flight_control::FlightComputer;
+use crate::objective::KnownImgObjective;
+use crate::scheduling::task::Task;
+use crate::util::{MissionConfig, Vec2D};
+use super::{global_mode::GlobalMode, in_orbit_mode::InOrbitMode, zo_prep_mode::ZOPrepMode};
+use crate::mode_control::{
+    base_mode::BaseMode,
+    mode_context::ModeContext,
+    signal::{ExecExitSignal, OpExitSignal, WaitExitSignal, OptOpExitSignal},
+};
+use crate::{log, obj};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use fixed::types::I32F32;
+use std::sync::Arc;
+
+/// [`SecretObjectiveMode`] is a transitional mode hunting the unknown zones of secret
+/// objectives.
+///
+/// Each initialization picks the nearest unprobed candidate region of the secret objective
+/// ending first, centered on a point ahead on the ground track, and hands it to a [`ZOPrepMode`]
+/// like a regular zoned objective. The exit burn, imaging and orbit return are thereby planned
+/// by the existing zoned objective modes, while the upload response is evaluated in the
+/// `ZORetrievalMode`. Unreachable candidates are skipped, and the mode falls back to
+/// [`InOrbitMode`] if no candidate can be reached.
+///
+/// While in this mode, tasks are not executed from the main scheduler.
+#[derive(Clone)]
+pub(super) struct SecretObjectiveMode {
+    /// The base operational context (e.g. Mapping or Beacon Objective Scanning).
+    base: BaseMode,
+}
+
+impl SecretObjectiveMode {
+    /// Static name for the mode, used for logging and diagnostics.
+    const MODE_NAME: &'static str = "SecretObjectiveMode";
+    /// The number of candidate regions tried per initialization before giving up.
+    const MAX_ATTEMPTS: usize = 4;
+    /// The time in seconds the sweep is centered ahead of MELVINs current position.
+    const ANCHOR_LEAD_S: i32 = 900;
+
+    /// Constructs a new [`SecretObjectiveMode`] if secret objectives are pending and the hunt
+    /// is enabled by `secret_hunt_max_probes` of the [`MissionConfig`].
+    ///
+    /// # Arguments
+    /// * `context` – Shared mode context.
+    /// * `base` – The [`BaseMode`] to fall back to.
+    ///
+    /// # Returns
+    /// * `Option<Self>` – The mode, or `None` if there is nothing to hunt.
+    pub(super) async fn try_new(context: &Arc<ModeContext>, base: BaseMode) -> Option<Self> {
+        let max_probes = MissionConfig::get().runtime.secret_hunt_max_probes as usize;
+        if max_probes == 0 {
+            return None;
+        }
+        context.sync_secret_hunt().await;
+        let anchor = Self::anchor(context).await;
+        let hunt = context.secret_hunt().lock().await;
+        hunt.next_probe(anchor, max_probes, Utc::now()).map(|_| Self { base })
+    }
+
+    /// Returns the map position ahead on the ground track the sweep is centered on.
+    ///
+    /// # Arguments
+    /// * `context` – Shared mode context.
+    async fn anchor(context: &Arc<ModeContext>) -> Vec2D<I32F32> {
+        let f_cont_lock = context.k().f_cont();
+        let f_cont = f_cont_lock.read().await;
+        let lead = f_cont.current_vel() * I32F32::from_num(Self::ANCHOR_LEAD_S);
+        (f_cont.current_pos() + lead).wrap_around_map()
+    }
+}
+
+#[async_trait]
+impl GlobalMode for SecretObjectiveMode {
+    /// Returns the static string name of the mode.
+    fn type_name(&self) -> &'static str { Self::MODE_NAME }
+
+    /// Selects the next candidate region and tries to plan a [`ZOPrepMode`] for it.
+    ///
+    /// # Arguments
+    /// * `context` – Shared mode context.
+    ///
+    /// # Returns
+    /// * `OpExitSignal::ReInit` – The [`ZOPrepMode`] probing a candidate, or [`InOrbitMode`].
+    async fn init_mode(&self, context: Arc<ModeContext>) -> OpExitSignal {
+        let max_probes = MissionConfig::get().runtime.secret_hunt_max_probes as usize;
+        context.set_init_stage("planning secret objective probe");
+        for _ in 0..Self::MAX_ATTEMPTS {
+            let anchor = Self::anchor(&context).await;
+            let next = context.secret_hunt().lock().await.next_probe(
+                anchor,
+                max_probes,
+                Utc::now(),
+            );
+            let Some(probe) = next else { break };
+            let (id, zone) = (probe.id(), probe.zone());
+            if let Some(prep_mode) = ZOPrepMode::from_obj(&context, probe, self.base).await {
+                obj!("Probing zone {zone:?} for Secret Objective {id}.");
+                return OpExitSignal::ReInit(Box::new(prep_mode));
+            }
+            obj!("Zone {zone:?} of Secret Objective {id} is unreachable. Skipping!");
+            context.secret_hunt().lock().await.record_probe(id, zone, None);
+        }
+        log!("No reachable secret objective probe left. Starting InOrbitMode!");
+        OpExitSignal::ReInit(self.exit_mode(context).await)
+    }
+
+    /// Not implemented. This mode does not wait for scheduled tasks.
+    async fn exec_task_wait(&self, _: Arc<ModeContext>, _: DateTime<Utc>) -> WaitExitSignal {
+        unimplemented!()
+    }
+
+    /// Not implemented. This mode does not execute scheduled tasks.
+    async fn exec_task(&self, _: Arc<ModeContext>, _: Task) -> ExecExitSignal { unimplemented!() }
+
+    /// Handles Safe Mode by escaping it and restarting the probe selection.
+    ///
+    /// # Arguments
+    /// * `context` – Shared mode context.
+    ///
+    /// # Returns
+    /// * `OpExitSignal::ReInit` – Always restarts the probe selection.
+    async fn safe_handler(&self, context: Arc<ModeContext>) -> OpExitSignal {
+        FlightComputer::escape_safe(context.k().f_cont(), false).await;
+        OpExitSignal::ReInit(Box::new(self.clone()))
+    }
+
+    /// Handles discovery of a new Zoned Objective. Stashes it into the buffer.
+    ///
+    /// # Arguments
+    /// * `c` – Shared mode context.
+    /// * `obj` – The newly discovered objective.
+    ///
+    /// # Returns
+    /// * `None` – The mode does not act immediately but stashes the objective.
+    async fn zo_handler(&self, c: &Arc<ModeContext>, obj: KnownImgObjective) -> OptOpExitSignal {
+        let id = obj.id();
+        obj!("Found new Zoned Objective with ID: {id} in mode {}. Stashing!", Self::MODE_NAME);
+        c.k_buffer().lock().await.push(obj);
+        None
+    }
+
+    /// Not implemented. Beacon state changes do not affect this mode.
+    async fn bo_event_handler(&self, _: &Arc<ModeContext>) -> OptOpExitSignal { unimplemented!() }
+
+    /// Falls back to [`InOrbitMode`] with the stored [`BaseMode`].
+    ///
+    /// # Returns
+    /// * `Box<dyn GlobalMode>` – The next mode to run.
+    async fn exit_mode(&self, _: Arc<ModeContext>) -> Box<dyn GlobalMode> {
+        Box::new(InOrbitMode::new(self.base))
+    }
+}
@@ -1,26 +1,34 @@
 use super::{
+    burn_collision::{BurnCollision, CollisionAction},
+    burn_sharing::SharedBurn,
     global_mode::{GlobalMode, OrbitalMode},
     in_orbit_mode::InOrbitMode,
+    mode_snapshot::ModeSnapshot,
+    orbit_return_mode::OrbitReturnMode,
     zo_retrieval_mode::ZORetrievalMode,
 };
 use crate::flight_control::{
-    FlightComputer, FlightState,
+    BurnAborted, FlightComputer, FuelBudgetError,
     orbit::{BurnSequence, ExitBurnResult},
 };
-use crate::objective::KnownImgObjective;
+use crate::imaging::ResolutionEstimate;
+use crate::objective::{
+    KnownImgObjective, LifecycleStage, OBJECTIVE_TRACKER, ObjectivePriority,
+};
 use crate::scheduling::{
-    EndCondition, TaskController,
+    BlendedPlan, EndCondition,
     task::{BaseTask, Task},
 };
-use crate::util::logger::JsonDump;
+use crate::util::{ImgObjectiveId, Vec2D, logger::JsonDump};
 use crate::mode_control::{
     base_mode::BaseMode,
     mode_context::ModeContext,
     signal::{ExecExitSignal, OpExitSignal, OptOpExitSignal, WaitExitSignal},
 };
-use crate::{error, fatal, info, log, log_burn, obj};
+use crate::{error, fatal, info, log, log_burn, obj, warn};
 use async_trait::async_trait;
 use chrono::{DateTime, TimeDelta, Utc};
+use fixed::types::I32F32;
 use std::{
     mem::discriminant,
     sync::{
@@ -45,6 +53,33 @@ pub(super) struct ZOPrepMode {
     target: KnownImgObjective,
     /// Indicates whether the satellite has already left its orbit.
     left_orbit: AtomicBool,
+    /// Indicates whether the execution of the exit burn has started.
+    burn_started: AtomicBool,
+    /// Further zoned objectives retrieved with the same exit burn, if any.
+    shared: Option<SharedBurn>,
+}
+
+/// The plan of a [`ZOPrepMode`] persisted for resuming it after a restart.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub(crate) struct ZOPrepSnapshot {
+    /// Underlying pre-exit behavior context (Mapping or Beacon Scanning).
+    base: BaseMode,
+    /// The planned exit burn.
+    exit_burn: ExitBurnResult,
+    /// The targeted zoned objective.
+    target: KnownImgObjective,
+}
+
+#[cfg(test)]
+impl ZOPrepSnapshot {
+    /// Constructs a [`ZOPrepSnapshot`] without a running [`ZOPrepMode`].
+    pub(in crate::mode_control) fn new(
+        base: BaseMode,
+        exit_burn: ExitBurnResult,
+        target: KnownImgObjective,
+    ) -> Self {
+        Self { base, exit_burn, target }
+    }
 }
 
 impl Clone for ZOPrepMode {
@@ -54,13 +89,15 @@ impl Clone for ZOPrepMode {
             exit_burn: self.exit_burn.clone(),
             target: self.target.clone(),
             left_orbit: AtomicBool::new(self.left_orbit.load(Ordering::Acquire)),
+            burn_started: AtomicBool::new(self.burn_started.load(Ordering::Acquire)),
+            shared: self.shared.clone(),
         }
     }
 }
 
 impl ZOPrepMode {
     /// Internal name used for logging and identification.
-    const MODE_NAME: &'static str = "ZOPrepMode";
+    pub(super) const MODE_NAME: &'static str = "ZOPrepMode";
     /// Minimum time before scheduled burn start during which re-planning is allowed.
     const MIN_REPLANNING_DT: TimeDelta = TimeDelta::seconds(500);
 
@@ -74,53 +111,284 @@ impl ZOPrepMode {
     /// # Returns
     /// * `Some(ZOPrepMode)` if a valid burn sequence can be computed.
     /// * `None` if the objective is unreachable.
-    #[allow(clippy::cast_possible_wrap)]
     pub(super) async fn from_obj(
         context: &Arc<ModeContext>,
         zo: KnownImgObjective,
         curr_base: BaseMode,
     ) -> Option<Self> {
-        log!("Trying ZOPrepMode for Zoned Objective: {}", zo.id());
-        let due = zo.end();
-        let (current_vel, fuel_left) = {
-            let f_cont_lock = context.k().f_cont();
+        let exit_burn = context.plan_exit_burn(&zo).await?;
+        Self::from_burn(context, zo, exit_burn, curr_base).await
+    }
+
+    /// Constructs a [`ZOPrepMode`] from a known zoned objective and its planned exit burn.
+    ///
+    /// # Arguments
+    /// * `context` – Shared mode context.
+    /// * `zo` – The target zoned objective.
+    /// * `exit_burn` – The exit burn planned by [`ModeContext::plan_exit_burn`].
+    /// * `curr_base` – The current base mode (Mapping or Beacon).
+    ///
+    /// # Returns
+    /// * `Some(ZOPrepMode)` if the fuel of the exit burn could be reserved.
+    /// * `None` if the flyover would not image the objective with its required optic, see
+    ///   [`ResolutionEstimate`], in which case the objective is buffered again, or the fuel
+    ///   could not be reserved.
+    pub(super) async fn from_burn(
+        context: &Arc<ModeContext>,
+        zo: KnownImgObjective,
+        exit_burn: ExitBurnResult,
+        curr_base: BaseMode,
+    ) -> Option<Self> {
+        let (fuel_avail, fuel_left) = {
+            let fuel_left = context.k().f_cont().read().await.fuel_left();
+            (context.k().fuel().available_for(zo.id(), fuel_left), fuel_left)
+        };
+        let resolution = Self::estimate_resolution(&exit_burn, &zo);
+        Self::log_burn(&exit_burn, &zo, &resolution);
+        if !ModeContext::meets_resolution(&zo, &exit_burn) {
+            // Keep the objective, a later exit burn may fly over it slow enough
+            context.k_buffer().lock().await.push(zo);
+            return None;
+        }
+        let base = Self::overthink_base(context, curr_base, exit_burn.sequence(), zo.id()).await;
+        exit_burn.dump_json();
+        if let Err(e) = Self::reserve_fuel(context, zo.id(), &exit_burn, None, fuel_left) {
+            log!("Rejecting exit burn for Zoned Objective {}: {e}.", zo.id());
+            return None;
+        }
+        let claimed = Self::claim_shared_burn(context, &zo, &exit_burn, fuel_avail).await;
+        let shared =
+            Self::reserve_shared_fuel(context, zo.id(), &exit_burn, claimed, fuel_left).await;
+        OBJECTIVE_TRACKER.record(zo.id(), LifecycleStage::Scheduled);
+        Some(ZOPrepMode {
+            base,
+            exit_burn,
+            target: zo,
+            left_orbit: AtomicBool::new(false),
+            burn_started: AtomicBool::new(false),
+            shared,
+        })
+    }
+
+    /// Constructs a [`ZOPrepMode`] from the plan persisted before a restart.
+    ///
+    /// The plan is only resumed if the objective did not end, the burn can still be prepared
+    /// in time and MELVIN still drifts towards the planned burn entry, i.e. the deviation of
+    /// the entry reached with the current velocity stays within the impact margin left by
+    /// the burn dispersion. Objectives that shared the burn are claimed anew.
+    ///
+    /// # Arguments
+    /// * `context` – Shared mode context.
+    /// * `snapshot` – The persisted plan.
+    ///
+    /// # Returns
+    /// * `Some(ZOPrepMode)` if the plan is still feasible.
+    /// * `None` otherwise.
+    pub(super) async fn resume(
+        context: &Arc<ModeContext>,
+        snapshot: ZOPrepSnapshot,
+    ) -> Option<Self> {
+        let f_cont_lock = context.k().f_cont();
+        let (pos, vel, batt) = {
             let f_cont = f_cont_lock.read().await;
-            (f_cont.current_vel(), f_cont.fuel_left())
+            (f_cont.estimated_pos_now(), f_cont.current_vel(), f_cont.current_battery())
         };
-        let start = zo.start();
-        if start > Utc::now() {
+        if !Self::is_resumable(&snapshot, Utc::now(), pos, vel, batt) {
+            return None;
+        }
+        let ZOPrepSnapshot { base, exit_burn, target } = snapshot;
+        Self::from_burn(context, target, exit_burn, base).await
+    }
+
+    /// Checks whether a persisted plan can still be resumed, see [`ZOPrepMode::resume`].
+    ///
+    /// # Arguments
+    /// * `snapshot` – The persisted plan.
+    /// * `now` – The current time.
+    /// * `pos` – The estimated current position of MELVIN.
+    /// * `vel` – The current velocity of MELVIN.
+    /// * `batt` – The current battery level.
+    ///
+    /// # Returns
+    /// * `true` if the objective did not end, the burn can be prepared in time and the burn
+    ///   entry did not drift beyond the margin.
+    fn is_resumable(
+        snapshot: &ZOPrepSnapshot,
+        now: DateTime<Utc>,
+        pos: Vec2D<I32F32>,
+        vel: Vec2D<I32F32>,
+        batt: I32F32,
+    ) -> bool {
+        let ZOPrepSnapshot { exit_burn, target, .. } = snapshot;
+        let burn = exit_burn.sequence();
+        if target.end() < now || !BurnCollision::is_feasible(burn, now, batt) {
+            log!("Exit burn for Zoned Objective {} can not be prepared in time.", target.id());
+            return false;
+        }
+        let dt = I32F32::from_num((burn.start_i().t() - now).num_seconds());
+        let entry = (pos + vel * dt).wrap_around_map();
+        let drift = entry.unwrapped_to(&burn.sequence_pos()[0]).abs();
+        let margin = target.impact_margin() - exit_burn.sensitivity().dispersion();
+        if drift > margin {
             log!(
-                "Objective {} will be calculated as a short objective.",
-                zo.id()
+                "Burn entry for Zoned Objective {} drifted by {drift:.1} (margin {margin:.1}).",
+                target.id()
             );
+            return false;
         }
-        let exit_burn = if zo.min_images() == 1 {
-            let target = zo.get_single_image_point();
-            TaskController::calculate_single_target_burn_sequence(
-                context.o_ch_clone().await.i_entry(),
-                current_vel,
-                target,
-                start,
-                due,
-                fuel_left,
-                zo.id(),
-            )
-        } else {
-            let entries = zo.get_corners();
-            TaskController::calculate_multi_target_burn_sequence(
-                context.o_ch_clone().await.i_entry(),
-                current_vel,
-                entries,
-                start,
-                due,
-                fuel_left,
-                zo.id(),
-            )
-        }?;
-        Self::log_burn(&exit_burn, &zo);
-        let base = Self::overthink_base(context, curr_base, exit_burn.sequence()).await;
-        exit_burn.dump_json();
-        Some(ZOPrepMode { base, exit_burn, target: zo, left_orbit: AtomicBool::new(false) })
+        true
+    }
+
+    /// Checks whether an objective announced during the exit burn is worth aborting it.
+    ///
+    /// Aborting wastes the fuel burned so far, so the objective has to end before the target
+    /// and to be valued higher by its [`ObjectivePriority`].
+    ///
+    /// # Arguments
+    /// * `target` – The objective the exit burn is executed for.
+    /// * `obj` – The newly announced objective.
+    fn preempts_burn(target: &KnownImgObjective, obj: &KnownImgObjective) -> bool {
+        let value = |zo| ObjectivePriority::new(zo, None).value();
+        obj.end() < target.end() && value(obj) > value(target)
+    }
+
+    /// Reserves the fuel of the exit burn and of the turns of a shared burn.
+    ///
+    /// # Arguments
+    /// * `context` – Shared mode context.
+    /// * `zo_id` – The ID of the objective the burn was planned for.
+    /// * `exit_burn` – The planned exit burn.
+    /// * `shared` – The objectives sharing the burn, if any.
+    /// * `fuel_left` – The fuel currently left in the tank.
+    ///
+    /// # Errors
+    /// A [`FuelBudgetError`] if the maneuvers would cut into the fuel safety margin.
+    fn reserve_fuel(
+        context: &Arc<ModeContext>,
+        zo_id: ImgObjectiveId,
+        exit_burn: &ExitBurnResult,
+        shared: Option<&SharedBurn>,
+        fuel_left: I32F32,
+    ) -> Result<(), FuelBudgetError> {
+        let turn_fuel = shared.map_or(I32F32::ZERO, SharedBurn::turn_fuel);
+        let amount = exit_burn.sequence().min_fuel() + turn_fuel;
+        context.k().fuel().reserve(zo_id, amount, fuel_left)
+    }
+
+    /// Reserves the fuel of the exit burn together with the turns of a shared burn.
+    ///
+    /// If the fuel of the turns can not be reserved, the partner objectives are returned to the
+    /// objective buffer and only the exit burn stays reserved.
+    ///
+    /// # Arguments
+    /// * `context` – Shared mode context.
+    /// * `zo_id` – The ID of the objective the burn was planned for.
+    /// * `exit_burn` – The planned exit burn.
+    /// * `shared` – The objectives claimed to share the burn, if any.
+    /// * `fuel_left` – The fuel currently left in the tank.
+    ///
+    /// # Returns
+    /// * The shared burn if the fuel of its turns could be reserved.
+    async fn reserve_shared_fuel(
+        context: &Arc<ModeContext>,
+        zo_id: ImgObjectiveId,
+        exit_burn: &ExitBurnResult,
+        shared: Option<SharedBurn>,
+        fuel_left: I32F32,
+    ) -> Option<SharedBurn> {
+        let res = Self::reserve_fuel(context, zo_id, exit_burn, shared.as_ref(), fuel_left);
+        let (Some(burn), Err(e)) = (&shared, res) else { return shared };
+        warn!("Releasing the partners of the shared burn for Zoned Objective {zo_id}: {e}.");
+        context.k_buffer().lock().await.extend(burn.partners().iter().cloned());
+        Self::reserve_fuel(context, zo_id, exit_burn, None, fuel_left).ok();
+        None
+    }
+
+    /// Releases the fuel reserved for the exit burn.
+    ///
+    /// # Arguments
+    /// * `context` – Shared mode context.
+    fn release_fuel(&self, context: &Arc<ModeContext>) {
+        if let Some(fuel) = context.k().fuel().release(self.target.id()) {
+            log!("Released {fuel:.1} fuel reserved for Zoned Objective {}.", self.target.id());
+        }
+    }
+
+    /// Searches the buffered objectives for ones that can share the exit burn and removes
+    /// them from the buffer, so that they are not planned with separate exit/return cycles.
+    ///
+    /// # Arguments
+    /// * `context` – Shared mode context.
+    /// * `zo` – The objective the burn was planned for.
+    /// * `exit_burn` – The planned exit burn.
+    /// * `fuel_left` – The fuel left before the exit burn.
+    ///
+    /// # Returns
+    /// * `Some(SharedBurn)` if at least one partner objective was claimed.
+    async fn claim_shared_burn(
+        context: &Arc<ModeContext>,
+        zo: &KnownImgObjective,
+        exit_burn: &ExitBurnResult,
+        fuel_left: I32F32,
+    ) -> Option<SharedBurn> {
+        let mut k_buffer = context.k_buffer().lock().await;
+        let candidates: Vec<_> = k_buffer.iter().cloned().collect();
+        let shared = SharedBurn::find(zo, exit_burn, &candidates, fuel_left)?;
+        k_buffer.retain(|obj| !shared.contains(obj.id()));
+        let partner_ids = SharedBurn::partner_ids(Some(&shared));
+        obj!(
+            "Zoned Objective(s) {partner_ids:?} share the exit burn of {}, saving ~{:.1} fuel.",
+            zo.id(),
+            shared.fuel_saved()
+        );
+        shared.dump_json();
+        Some(shared)
+    }
+
+    /// Returns the partner objectives of a shared burn to the objective buffer.
+    ///
+    /// # Arguments
+    /// * `context` – Shared mode context.
+    async fn release_partners(&self, context: &Arc<ModeContext>) {
+        let Some(shared) = &self.shared else { return };
+        for partner in shared.partners() {
+            if context.is_zo_removed(partner.id()).await {
+                continue;
+            }
+            obj!("Releasing Zoned Objective {} from shared burn.", partner.id());
+            context.k_buffer().lock().await.push(partner.clone());
+        }
+    }
+
+    /// Releases the current partner objectives and claims the objectives sharing the exit
+    /// burn anew from the objective buffer.
+    ///
+    /// # Arguments
+    /// * `context` – Shared mode context.
+    ///
+    /// # Returns
+    /// * `Some(SharedBurn)` if at least one partner objective was claimed.
+    async fn replan_shared_burn(&self, context: &Arc<ModeContext>) -> Option<SharedBurn> {
+        self.release_partners(context).await;
+        let fuel_left = context.k().f_cont().read().await.fuel_left();
+        let fuel_avail = context.k().fuel().available_for(self.target.id(), fuel_left);
+        let claimed =
+            Self::claim_shared_burn(context, &self.target, &self.exit_burn, fuel_avail).await;
+        let id = self.target.id();
+        Self::reserve_shared_fuel(context, id, &self.exit_burn, claimed, fuel_left).await
+    }
+
+    /// Estimates the resolution of the images taken with the exit velocity of the burn.
+    ///
+    /// # Arguments
+    /// * `exit_burn` – The planned exit burn.
+    /// * `target` – The objective the burn aims to reach.
+    fn estimate_resolution(
+        exit_burn: &ExitBurnResult,
+        target: &KnownImgObjective,
+    ) -> ResolutionEstimate {
+        let vel = *exit_burn.sequence().sequence_vel().last().unwrap();
+        ResolutionEstimate::for_objective(target, vel)
     }
 
     /// Logs key information about the generated burn sequence.
@@ -128,7 +396,12 @@ impl ZOPrepMode {
     /// # Arguments
     /// * `exit_burn` – The calculated burn data.
     /// * `target` – The objective the burn aims to reach.
-    fn log_burn(exit_burn: &ExitBurnResult, target: &KnownImgObjective) {
+    /// * `resolution` – The estimated resolution of the images over the objective.
+    fn log_burn(
+        exit_burn: &ExitBurnResult,
+        target: &KnownImgObjective,
+        resolution: &ResolutionEstimate,
+    ) {
         let exit_burn_seq = exit_burn.sequence();
         let entry_pos = exit_burn_seq.sequence_pos().first().unwrap();
         let exit_pos = exit_burn_seq.sequence_pos().last().unwrap();
@@ -151,6 +424,20 @@ impl ZOPrepMode {
         if let Some(tar2) = add_tar {
             log_burn!("Additional Target will be {tar2}");
         }
+        let sens = exit_burn.sensitivity();
+        log_burn!(
+            "Impact dispersion is {:.1} (quantization {:.1}, timing {:.1}) within margin {:.1}.",
+            sens.dispersion(),
+            sens.quant_dispersion(),
+            sens.timing_dispersion(),
+            target.impact_margin()
+        );
+        log_burn!(
+            "Imaging with {} at {:.1} yields {:.2}/px.",
+            resolution.lens(),
+            resolution.flyover_speed(),
+            resolution.ground_res()
+        );
     }
 
     /// Clones the current `ZOPrepMode` but with an updated base mode.
@@ -166,38 +453,61 @@ impl ZOPrepMode {
             exit_burn: self.exit_burn.clone(),
             target: self.target.clone(),
             left_orbit: AtomicBool::new(self.left_orbit.load(Ordering::Acquire)),
+            burn_started: AtomicBool::new(self.burn_started.load(Ordering::Acquire)),
+            shared: self.shared.clone(),
         }
     }
 
     /// Determines whether the current base mode should change based on the burn timing
-    /// and worst-case beacon communication schedules.
+    /// and the comms windows that can be blended into the waiting period before the burn.
     ///
     /// # Arguments
     /// * `c` – Shared context.
     /// * `base` – Proposed base mode.
     /// * `burn` – Calculated burn sequence.
+    /// * `zo_id` – The ID of the targeted zoned objective.
     ///
     /// # Returns
     /// * `BaseMode` – The chosen base mode to continue with.
-    #[allow(clippy::cast_possible_wrap)]
-    async fn overthink_base(c: &Arc<ModeContext>, base: BaseMode, burn: &BurnSequence) -> BaseMode {
+    async fn overthink_base(
+        c: &Arc<ModeContext>,
+        base: BaseMode,
+        burn: &BurnSequence,
+        zo_id: ImgObjectiveId,
+    ) -> BaseMode {
         if matches!(base, BaseMode::MappingMode) {
             return BaseMode::MappingMode;
         }
-        let burn_start = burn.start_i().t();
-        let worst_case_first_comms_end = {
-            let to_dt = FlightComputer::get_to_comms_t_est(c.k().f_cont()).await;
-            let state_change = FlightState::Comms.td_dt_to(FlightState::Acquisition);
-            to_dt + TimeDelta::seconds(TaskController::IN_COMMS_SCHED_SECS as i64) + state_change
-        };
-        if worst_case_first_comms_end + TimeDelta::seconds(5) > burn_start {
-            let t = worst_case_first_comms_end.format("%d %H:%M:%S").to_string();
-            log!("Requested BOScanningMode not feasible, first comms end is {t}.");
-            BaseMode::MappingMode
-        } else {
+        if Self::blended_plan(c, burn, zo_id).await.is_some() {
             BaseMode::BeaconObjectiveScanningMode
+        } else {
+            let t = burn.start_i().t().format("%d %H:%M:%S").to_string();
+            log!("Requested BOScanningMode not feasible, no comms window fits before burn at {t}.");
+            BaseMode::MappingMode
         }
     }
+
+    /// Plans the comms windows that can be interleaved into the waiting period before the
+    /// exit burn without sacrificing burn readiness.
+    ///
+    /// # Arguments
+    /// * `c` – Shared context.
+    /// * `burn` – Calculated burn sequence.
+    /// * `zo_id` – The ID of the targeted zoned objective.
+    ///
+    /// # Returns
+    /// * `Some(BlendedPlan)` if at least one comms window fits before the burn.
+    /// * `None` if no beacon objective is active or the slack is too short.
+    async fn blended_plan(
+        c: &Arc<ModeContext>,
+        burn: &BurnSequence,
+        zo_id: ImgObjectiveId,
+    ) -> Option<BlendedPlan> {
+        let last_bo_end = c.beac_cont().last_active_beac_end().await?;
+        let first_comms_start = FlightComputer::get_to_comms_t_est(c.k().f_cont()).await;
+        let end = EndCondition::from_burn(burn);
+        BlendedPlan::new(zo_id, first_comms_start, &end, last_bo_end)
+    }
 }
 
 impl OrbitalMode for ZOPrepMode {
@@ -205,11 +515,55 @@ impl OrbitalMode for ZOPrepMode {
     fn base(&self) -> &BaseMode { &self.base }
 }
 
+impl ZOPrepMode {
+    /// Returns the decision for a safe mode event that is fixed by the progress of the exit
+    /// burn alone.
+    ///
+    /// # Returns
+    /// * `Some(CollisionAction::Continue)` if MELVIN already left its orbit.
+    /// * `Some(CollisionAction::Abort)` if the burn was interrupted mid-sequence.
+    /// * `None` if the burn did not start, i.e. it may still be shifted or cancelled.
+    fn burn_progress_action(&self) -> Option<CollisionAction> {
+        if self.left_orbit.load(Ordering::Acquire) {
+            Some(CollisionAction::Continue)
+        } else if self.burn_started.load(Ordering::Acquire) {
+            Some(CollisionAction::Abort)
+        } else {
+            None
+        }
+    }
+
+    /// Constructs the [`ZORetrievalMode`] following the completed exit burn.
+    fn retrieval_mode(&self) -> ZORetrievalMode {
+        ZORetrievalMode::new(
+            self.target.clone(),
+            self.exit_burn.add_target(),
+            *self.exit_burn.unwrapped_target(),
+            self.shared.as_ref().map_or_else(Vec::new, SharedBurn::retrievals),
+        )
+    }
+}
+
 #[async_trait]
 impl GlobalMode for ZOPrepMode {
     /// Returns the internal name of this mode.
     fn type_name(&self) -> &'static str { Self::MODE_NAME }
 
+    /// Returns `true`, as the mode drops its schedule on an operator abort.
+    fn accepts_operator_abort(&self) -> bool { true }
+
+    /// Returns the planned exit burn and its target as long as the burn did not start.
+    fn snapshot(&self) -> Option<ModeSnapshot> {
+        if self.burn_started.load(Ordering::Acquire) {
+            return None;
+        }
+        Some(ModeSnapshot::ZOPrep(ZOPrepSnapshot {
+            base: self.base,
+            exit_burn: self.exit_burn.clone(),
+            target: self.target.clone(),
+        }))
+    }
+
     /// Initializes scheduling and preparatory logic for the exit burn.
     ///
     /// If a base mode change is required due to beacon conflicts, the mode reinitializes.
@@ -222,11 +576,23 @@ impl GlobalMode for ZOPrepMode {
     /// * `OpExitSignal` – Indicates whether to continue or reinitialize.
     async fn init_mode(&self, context: Arc<ModeContext>) -> OpExitSignal {
         let cancel_task = CancellationToken::new();
-        let new_base = Self::overthink_base(&context, self.base, self.exit_burn.sequence()).await;
+        let burn = self.exit_burn.sequence();
+        let new_base = Self::overthink_base(&context, self.base, burn, self.target.id()).await;
         if discriminant(&self.base) != discriminant(&new_base) {
             return OpExitSignal::ReInit(Box::new(self.new_base(new_base)));
         }
-        let comms_end = self.base.handle_sched_preconditions(Arc::clone(&context)).await;
+        let mut comms_end = self.base.handle_sched_preconditions(Arc::clone(&context)).await;
+        if matches!(self.base, BaseMode::BeaconObjectiveScanningMode) {
+            if let Some(plan) = Self::blended_plan(&context, burn, self.target.id()).await {
+                comms_end = comms_end.min(plan.comms_deadline());
+                info!(
+                    "Blending {} comms window(s) with {}s total into burn preparation.",
+                    plan.n_windows(),
+                    plan.total_comms_time().num_seconds()
+                );
+                plan.dump_json();
+            }
+        }
         let end = EndCondition::from_burn(self.exit_burn.sequence());
         let sched_handle = {
             let cancel_clone = cancel_task.clone();
@@ -235,14 +601,16 @@ impl GlobalMode for ZOPrepMode {
                 .await
         };
         tokio::pin!(sched_handle);
-        let safe_mon = context.super_v().safe_mon();
+        let mut safe_mon = context.safe_mon();
         tokio::select!(
             _ = &mut sched_handle => {
                 info!("Additionally scheduling Orbit Escape Burn Sequence!");
                 context.k().t_cont().schedule_vel_change(self.exit_burn.sequence().clone()).await;
+                OBJECTIVE_TRACKER.record(self.target.id(), LifecycleStage::BurnPlanned);
                 context.k().con().send_tasklist().await;
+                context.refresh_timeline().await;
             },
-            () = safe_mon.notified() => {
+            () = ModeContext::wait_for_safe(&mut safe_mon) => {
                 cancel_task.cancel();
                 sched_handle.await.ok();
                 return self.safe_handler(context).await;
@@ -257,6 +625,7 @@ impl GlobalMode for ZOPrepMode {
     }
 
     /// Executes a scheduled task (only [`SwitchState`] or [`VelocityChange`] tasks are allowed).
+    /// Operator-pinned image tasks are taken like in the orbital modes.
     ///
     /// # Arguments
     /// * `context` – Shared mode context.
@@ -268,13 +637,48 @@ impl GlobalMode for ZOPrepMode {
         match task.task_type() {
             BaseTask::SwitchState(switch) => self.base.get_task(context, *switch).await,
             BaseTask::ChangeVelocity(vel_change) => {
-                let pos = context.k().f_cont().read().await.current_pos();
+                let f_cont = context.k().f_cont();
+                let pos = f_cont.read().await.current_pos();
                 log_burn!(
                     "Burn started at Pos {pos}. Expected Position was: {}.",
                     vel_change.burn().sequence_pos()[0]
                 );
-                FlightComputer::execute_burn(context.k().f_cont(), vel_change.burn()).await;
-                self.left_orbit.store(true, Ordering::Release);
+                self.burn_started.store(true, Ordering::Release);
+                context.persist_mode(self);
+                let abort = CancellationToken::new();
+                let burn = FlightComputer::execute_burn(f_cont, vel_change.burn(), &abort);
+                tokio::pin!(burn);
+                let mut safe_mon = context.safe_mon();
+                let mut zo_mon = context.zo_mon().write().await;
+                loop {
+                    tokio::select! {
+                        res = &mut burn => {
+                            if let Err(aborted) = res {
+                                return ExecExitSignal::BurnAborted(aborted);
+                            }
+                            self.left_orbit.store(true, Ordering::Release);
+                            self.release_fuel(&context);
+                            break;
+                        }
+                        () = ModeContext::wait_for_safe(&mut safe_mon) => {
+                            log_burn!("Burn interrupted by safe mode event!");
+                            return ExecExitSignal::SafeEvent;
+                        }
+                        Some(obj) = zo_mon.recv() => {
+                            let id = obj.id();
+                            if Self::preempts_burn(&self.target, &obj) && !abort.is_cancelled() {
+                                obj!("Objective {id} is prioritized. Aborting the exit burn!");
+                                abort.cancel();
+                            } else {
+                                obj!("Found Zoned Objective {id} during exit burn. Stashing!");
+                            }
+                            context.k_buffer().lock().await.push(obj);
+                        }
+                    }
+                }
+            }
+            BaseTask::TakeImage(image) if task.is_pinned() => {
+                BaseMode::get_pinned_image(context, *image).await;
             }
             BaseTask::TakeImage(_) => fatal!(
                 "Illegal task type {} for state {}!",
@@ -286,48 +690,109 @@ impl GlobalMode for ZOPrepMode {
     }
 
     /// Responds to a safe mode interrupt by escaping and attempting to reinitiate the mode.
+    ///
+    /// If the exit burn already completed, MELVIN continues to the objective in a
+    /// [`ZORetrievalMode`]. Otherwise, if the safe mode event collides with the scheduled exit
+    /// burn, the burn is aborted, shifted or cancelled depending on its progress and on whether
+    /// a recomputed burn can still be prepared after the recovery. The decision is recorded as
+    /// a [`BurnCollision`].
     async fn safe_handler(&self, context: Arc<ModeContext>) -> OpExitSignal {
+        let planned_start = self.exit_burn.sequence().start_i().t();
+        let opt_collision = BurnCollision::detect(self.target.id(), planned_start, Utc::now());
         FlightComputer::escape_safe(context.k().f_cont(), false).await;
-        context.o_ch_lock().write().await.finish(
-            context.k().f_cont().read().await.current_pos(),
-            self.safe_mode_rationale(),
-        );
-        let new = Self::from_obj(&context, self.target.clone(), self.base).await;
-        OpExitSignal::ReInit(new.map_or(Box::new(InOrbitMode::new(self.base)), |b| Box::new(b)))
+        context.finish_phase(self.safe_mode_rationale()).await;
+        let progress_action = self.burn_progress_action();
+        if progress_action == Some(CollisionAction::Continue) {
+            // The partners are retrieved in the same retrieval mode and stay claimed
+            if let Some(collision) = opt_collision {
+                collision.resolve(CollisionAction::Continue, None);
+            }
+            return OpExitSignal::ReInit(Box::new(self.retrieval_mode()));
+        }
+        self.release_partners(&context).await;
+        let Some(collision) = opt_collision else {
+            let new = Self::from_obj(&context, self.target.clone(), self.base).await;
+            if new.is_none() {
+                self.release_fuel(&context);
+            }
+            return OpExitSignal::ReInit(
+                new.map_or(Box::new(InOrbitMode::new(self.base)), |b| Box::new(b)),
+            );
+        };
+        if progress_action == Some(CollisionAction::Abort) {
+            self.release_fuel(&context);
+            collision.resolve(CollisionAction::Abort, None);
+            context.k_buffer().lock().await.push(self.target.clone());
+            return OpExitSignal::ReInit(Box::new(OrbitReturnMode::new()));
+        }
+        let batt = context.k().f_cont().read().await.current_battery();
+        let new = Self::from_obj(&context, self.target.clone(), self.base)
+            .await
+            .filter(|m| BurnCollision::is_feasible(m.exit_burn.sequence(), Utc::now(), batt));
+        if let Some(prep_mode) = new {
+            let new_start = prep_mode.exit_burn.sequence().start_i().t();
+            collision.resolve(CollisionAction::Shift, Some(new_start));
+            OpExitSignal::ReInit(Box::new(prep_mode))
+        } else {
+            self.release_fuel(&context);
+            collision.resolve(CollisionAction::Cancel, None);
+            context.k_buffer().lock().await.push(self.target.clone());
+            OpExitSignal::ReInit(Box::new(InOrbitMode::new(self.base)))
+        }
     }
 
     /// Handles a newly received zoned objective.
     /// Replaces the current target if the new one ends earlier and sufficient time remains.
+    /// The current target itself is ignored, as it is announced again after a restart.
+    /// Otherwise, the objective is stashed and the objectives sharing the exit burn are
+    /// re-planned, so that the new one may be retrieved with the same burn.
     ///
     /// # Arguments
     /// * `c` – Shared context.
     /// * `obj` – The new zoned objective.
     ///
     /// # Returns
-    /// * `Some(OpExitSignal::ReInit)` if reprioritization occurs.
+    /// * `Some(OpExitSignal::ReInit)` if reprioritization occurs or the shared burn changed.
     /// * `None` otherwise.
     async fn zo_handler(&self, c: &Arc<ModeContext>, obj: KnownImgObjective) -> OptOpExitSignal {
+        if obj.id() == self.target.id() {
+            // Announced again after the mode was resumed from a restart
+            obj!("Objective {} is already targeted.", obj.id());
+            return None;
+        }
         let burn_dt_cond =
             self.exit_burn.sequence().start_i().t() - Utc::now() > Self::MIN_REPLANNING_DT;
         if obj.end() < self.target.end() && burn_dt_cond {
             let new_obj_mode = Self::from_obj(c, obj.clone(), self.base).await;
             if let Some(prep_mode) = new_obj_mode {
-                c.o_ch_lock().write().await.finish(
-                    c.k().f_cont().read().await.current_pos(),
-                    self.new_zo_rationale(),
-                );
+                c.finish_phase(self.new_zo_rationale()).await;
                 obj!(
                     "Objective {} is prioritized. Stashing current ZO {}!",
                     obj.id(),
                     self.target.id()
                 );
                 c.k_buffer().lock().await.push(self.target.clone());
+                self.release_fuel(c);
+                self.release_partners(c).await;
                 return Some(OpExitSignal::ReInit(Box::new(prep_mode)));
             }
         }
         obj!("Objective {} is not prioritized. Stashing!", obj.id());
+        let new_id = obj.id();
         c.k_buffer().lock().await.push(obj);
-        None
+        if !burn_dt_cond || self.exit_burn.add_target().is_some() {
+            return None;
+        }
+        let shared = self.replan_shared_burn(c).await;
+        let partner_ids = SharedBurn::partner_ids(shared.as_ref());
+        if partner_ids == SharedBurn::partner_ids(self.shared.as_ref()) {
+            return None;
+        }
+        if partner_ids.contains(&new_id) {
+            obj!("Objective {new_id} joins the exit burn of {}.", self.target.id());
+        }
+        c.finish_phase(self.new_zo_rationale()).await;
+        Some(OpExitSignal::ReInit(Box::new(Self { shared, ..self.new_base(self.base) })))
     }
 
     /// Reacts to a Beacon Objective state change by potentially switching the base mode.
@@ -340,8 +805,8 @@ impl GlobalMode for ZOPrepMode {
     /// * `None` if the current base mode is still valid.
     async fn bo_event_handler(&self, context: &Arc<ModeContext>) -> OptOpExitSignal {
         let prop_new_base = self.base.bo_event();
-        let new_base =
-            Self::overthink_base(context, prop_new_base, self.exit_burn.sequence()).await;
+        let burn = self.exit_burn.sequence();
+        let new_base = Self::overthink_base(context, prop_new_base, burn, self.target.id()).await;
         if discriminant(&self.base) == discriminant(&new_base) {
             None
         } else {
@@ -355,6 +820,137 @@ impl GlobalMode for ZOPrepMode {
         }
     }
 
+    /// Re-plans the preparation schedule after map captures recovered, so that the coverage
+    /// missed during the outage is caught up before the burn.
+    ///
+    /// # Arguments
+    /// * `context` – Shared mode context.
+    ///
+    /// # Returns
+    /// * `Some(OpExitSignal::ReInit)` if enough time is left before the burn to re-plan.
+    async fn imaging_recovered_handler(&self, context: &Arc<ModeContext>) -> OptOpExitSignal {
+        let burn_start = self.exit_burn.sequence().start_i().t();
+        if burn_start - Utc::now() <= Self::MIN_REPLANNING_DT {
+            return None;
+        }
+        context.finish_phase(self.imaging_recovered_rationale()).await;
+        Some(OpExitSignal::ReInit(Box::new(self.new_base(self.base))))
+    }
+
+    /// Handles the deletion of a Zoned Objective by the backend.
+    ///
+    /// If the target was deleted before the exit burn started, the burn and the preparation
+    /// schedule are cancelled, which frees the fuel and battery kept for the burn, and the
+    /// next mode is selected from the remaining objectives. If the partner of a shared burn
+    /// was deleted, the burn is kept and the preparation is re-planned without it.
+    ///
+    /// # Arguments
+    /// * `context` – Shared mode context.
+    /// * `id` – The ID of the deleted objective.
+    ///
+    /// # Returns
+    /// * `Some(OpExitSignal::ReInit)` if the plan depended on the deleted objective.
+    async fn zo_removed_handler(&self, context: &Arc<ModeContext>, id: ImgObjectiveId) -> OptOpExitSignal {
+        context.remove_zo(id).await;
+        if self.burn_started.load(Ordering::Acquire) {
+            return None;
+        }
+        if id == self.target.id() {
+            context.k().t_cont().clear_schedule().await;
+            self.release_fuel(context);
+            obj!(
+                "Cancelled exit burn for deleted Zoned Objective {id}, freeing ~{:.1} fuel.",
+                self.exit_burn.sequence().min_fuel()
+            );
+            context.finish_phase(self.zo_removed_rationale()).await;
+            self.release_partners(context).await;
+            return Some(OpExitSignal::ReInit(OrbitReturnMode::get_next_mode(context).await));
+        }
+        if self.shared.as_ref().is_some_and(|s| s.contains(id)) {
+            obj!("Re-planning exit burn of {} without deleted partner {id}.", self.target.id());
+            context.finish_phase(self.zo_removed_rationale()).await;
+            let shared = self.replan_shared_burn(context).await;
+            return Some(OpExitSignal::ReInit(Box::new(Self { shared, ..self.new_base(self.base) })));
+        }
+        None
+    }
+
+    /// Handles an abort issued by an operator.
+    ///
+    /// Before the exit burn started, the schedule is dropped, the reserved fuel released and
+    /// the target and partners are stashed for a later attempt. Once the burn started, the
+    /// abort is ignored, as MELVIN is already on its way to the objective.
+    ///
+    /// # Arguments
+    /// * `context` – Shared mode context.
+    ///
+    /// # Returns
+    /// * `Some(OpExitSignal::ReInit)` – With an [`InOrbitMode`] if the burn did not start yet.
+    async fn operator_abort_handler(&self, context: &Arc<ModeContext>) -> OptOpExitSignal {
+        if self.burn_started.load(Ordering::Acquire) {
+            return None;
+        }
+        context.k().t_cont().clear_schedule().await;
+        context.finish_phase(self.operator_abort_rationale()).await;
+        self.release_fuel(context);
+        self.release_partners(context).await;
+        context.k_buffer().lock().await.push(self.target.clone());
+        Some(OpExitSignal::ReInit(Box::new(InOrbitMode::new(BaseMode::MappingMode))))
+    }
+
+    /// Handles a timed out initialization.
+    ///
+    /// Retries without blended comms windows if enough time is left before the burn.
+    /// Otherwise, the objective is stashed and MELVIN reverts to an [`InOrbitMode`].
+    ///
+    /// # Arguments
+    /// * `context` – Shared mode context.
+    ///
+    /// # Returns
+    /// * `OpExitSignal::ReInit` – With the relaxed or the fallback mode.
+    async fn init_timeout_handler(&self, context: Arc<ModeContext>) -> OpExitSignal {
+        context.k().t_cont().clear_schedule().await;
+        let burn_start = self.exit_burn.sequence().start_i().t();
+        let relaxable = matches!(self.base, BaseMode::BeaconObjectiveScanningMode);
+        if relaxable && burn_start - Utc::now() > Self::MIN_REPLANNING_DT {
+            return OpExitSignal::ReInit(Box::new(self.new_base(BaseMode::MappingMode)));
+        }
+        context.k_buffer().lock().await.push(self.target.clone());
+        self.release_fuel(&context);
+        self.release_partners(&context).await;
+        OpExitSignal::ReInit(Box::new(InOrbitMode::new(BaseMode::MappingMode)))
+    }
+
+    /// Handles an exit burn that was aborted for a prioritized objective.
+    ///
+    /// The schedule is dropped, the reserved fuel released and the target and partners are
+    /// stashed, so that they compete with the new objective after the orbit return.
+    ///
+    /// # Arguments
+    /// * `context` – Shared mode context.
+    /// * `aborted` – The record of the aborted burn.
+    ///
+    /// # Returns
+    /// * `OpExitSignal::ReInit` – With an [`OrbitReturnMode`].
+    async fn burn_aborted_handler(
+        &self,
+        context: Arc<ModeContext>,
+        aborted: BurnAborted,
+    ) -> OpExitSignal {
+        log_burn!(
+            "Exit burn for ZO {} aborted after {}/{} steps.",
+            self.target.id(),
+            aborted.steps_done(),
+            aborted.steps_total()
+        );
+        context.k().t_cont().clear_schedule().await;
+        context.finish_phase(self.burn_aborted_rationale()).await;
+        self.release_fuel(&context);
+        self.release_partners(&context).await;
+        context.k_buffer().lock().await.push(self.target.clone());
+        OpExitSignal::ReInit(Box::new(OrbitReturnMode::new()))
+    }
+
     /// Finalizes the mode and transitions into a `ZORetrievalMode` if the satellite has left orbit.
     ///
     /// # Arguments
@@ -363,19 +959,101 @@ impl GlobalMode for ZOPrepMode {
     /// # Returns
     /// * `Box<dyn GlobalMode>` – The next mode (retrieval or fallback).
     async fn exit_mode(&self, context: Arc<ModeContext>) -> Box<dyn GlobalMode> {
-        context.o_ch_lock().write().await.finish(
-            context.k().f_cont().read().await.current_pos(),
-            self.tasks_done_exit_rationale(),
-        );
+        context.finish_phase(self.tasks_done_exit_rationale()).await;
         if self.left_orbit.load(Ordering::Acquire) {
-            Box::new(ZORetrievalMode::new(
-                self.target.clone(),
-                self.exit_burn.add_target(),
-                *self.exit_burn.unwrapped_target(),
-            ))
+            Box::new(self.retrieval_mode())
         } else {
             error!("ZOPrepMode::exit_mode called without left_orbit flag set!");
+            self.release_fuel(&context);
+            self.release_partners(&context).await;
             Box::new(InOrbitMode::new(self.base))
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flight_control::orbit::IndexedOrbitPosition;
+    use crate::imaging::CameraAngle;
+    use crate::util::ZoneRect;
+
+    #[test]
+    fn test_resume_rejects_drift_and_missed_burn() {
+        let pos = |x: i32, y: i32| Vec2D::new(I32F32::from_num(x), I32F32::from_num(y));
+        let vel = pos(10, 0);
+        let start_i = IndexedOrbitPosition::new(0, 1000, pos(1000, 1000));
+        let burn_t = start_i.t();
+        let id = ImgObjectiveId::new(3);
+        let burn = ExitBurnResult::straight_fixture(start_i, I32F32::ONE, id);
+        let zone = ZoneRect::new(4900, 900, 5100, 1100);
+        let end = burn_t + TimeDelta::hours(3);
+        let lens = CameraAngle::Narrow;
+        let zo = KnownImgObjective::new(id, "zo".into(), burn_t, end, zone, lens, 1.0);
+        let snapshot = ZOPrepSnapshot::new(BaseMode::MappingMode, burn, zo);
+        let batt = I32F32::lit("100");
+
+        // An hour before the burn, MELVIN drifts exactly towards the burn entry
+        let now = burn_t - TimeDelta::hours(1);
+        let on_track = pos(1000 - 36_000, 1000).wrap_around_map();
+        assert!(ZOPrepMode::is_resumable(&snapshot, now, on_track, vel, batt));
+        // The entry drifted beyond the impact margin
+        let drifted = pos(1000 - 36_000, 1500).wrap_around_map();
+        assert!(!ZOPrepMode::is_resumable(&snapshot, now, drifted, vel, batt));
+        // The burn can not be prepared in time anymore
+        let late = burn_t - TimeDelta::seconds(10);
+        let late_pos = pos(1000 - 100, 1000);
+        assert!(!ZOPrepMode::is_resumable(&snapshot, late, late_pos, vel, batt));
+        // The objective ended
+        let after = end + TimeDelta::seconds(1);
+        assert!(!ZOPrepMode::is_resumable(&snapshot, after, on_track, vel, batt));
+    }
+
+    #[test]
+    fn test_burn_preemption() {
+        let start = Utc::now();
+        let zo = |raw_id: usize, end_h: i64, side: i32| {
+            let (id, end) = (ImgObjectiveId::new(raw_id), start + TimeDelta::hours(end_h));
+            let zone = ZoneRect::new(0, 0, side, side);
+            KnownImgObjective::new(id, "zo".into(), start, end, zone, CameraAngle::Normal, 1.0)
+        };
+        let target = zo(1, 3, 1500);
+        // Earlier objectives only preempt the burn if they are worth more than the target
+        assert!(ZOPrepMode::preempts_burn(&target, &zo(2, 2, 500)));
+        assert!(!ZOPrepMode::preempts_burn(&target, &zo(3, 2, 2000)));
+        assert!(!ZOPrepMode::preempts_burn(&target, &zo(4, 2, 1500)));
+        // Later objectives never do
+        assert!(!ZOPrepMode::preempts_burn(&target, &zo(5, 4, 500)));
+    }
+
+    #[test]
+    fn test_safe_after_completed_burn_continues() {
+        let pos = Vec2D::new(I32F32::from_num(1000), I32F32::from_num(1000));
+        let start_i = IndexedOrbitPosition::new(0, 1000, pos);
+        let id = ImgObjectiveId::new(7);
+        let burn_t = start_i.t();
+        let zone = ZoneRect::new(4900, 900, 5100, 1100);
+        let end = burn_t + TimeDelta::hours(3);
+        let lens = CameraAngle::Narrow;
+        let target = KnownImgObjective::new(id, "zo".into(), burn_t, end, zone, lens, 1.0);
+        let mode = ZOPrepMode {
+            base: BaseMode::MappingMode,
+            exit_burn: ExitBurnResult::straight_fixture(start_i, I32F32::ONE, id),
+            target,
+            left_orbit: AtomicBool::new(false),
+            burn_started: AtomicBool::new(false),
+            shared: None,
+        };
+        // A safe mode event long after the burn still collides with it
+        let safe_t = burn_t + TimeDelta::minutes(30);
+        assert!(BurnCollision::detect(id, burn_t, safe_t).is_some());
+
+        assert_eq!(mode.burn_progress_action(), None);
+        mode.burn_started.store(true, Ordering::Release);
+        assert_eq!(mode.burn_progress_action(), Some(CollisionAction::Abort));
+        // A completed burn is never aborted, even though it also started
+        mode.left_orbit.store(true, Ordering::Release);
+        assert_eq!(mode.burn_progress_action(), Some(CollisionAction::Continue));
+        assert_eq!(mode.retrieval_mode().type_name(), "ZORetrievalMode");
+    }
+}
@@ -1,14 +1,16 @@
 use super::{global_mode::GlobalMode, orbit_return_mode::OrbitReturnMode};
 use crate::flight_control::{FlightComputer, FlightState};
-use crate::imaging::CameraController;
+use crate::imaging::{CameraController, map_image::OffsetZonedObjectiveImage};
 use crate::mode_control::{
+    base_mode::BaseMode,
     mode_context::ModeContext,
+    mosaic_passes::MosaicPasses,
     signal::{ExecExitSignal, OpExitSignal, OptOpExitSignal, WaitExitSignal},
 };
-use crate::objective::KnownImgObjective;
-use crate::scheduling::task::{BaseTask, Task};
-use crate::util::Vec2D;
-use crate::{DT_0_STD, error, fatal, log, warn};
+use crate::objective::{KnownImgObjective, LifecycleStage, OBJECTIVE_TRACKER};
+use crate::scheduling::task::{BaseTask, ExternalEvent, Task};
+use crate::util::{ImgObjectiveId, MissionConfig, ObjectiveEvent, Vec2D};
+use crate::{DT_0_STD, error, fatal, log, obj, warn};
 use async_trait::async_trait;
 use chrono::{DateTime, TimeDelta, Utc};
 use fixed::types::I32F32;
@@ -22,7 +24,7 @@ use tokio_util::sync::CancellationToken;
 ///
 /// The mode is considered time-sensitive, interruptible (e.g., safe mode), and does not allow
 /// velocity change tasks. It can optionally perform a secondary targeting maneuver if a
-/// secondary objective is provided.
+/// secondary objective is provided, and retrieve further objectives sharing the exit burn.
 #[derive(Clone)]
 pub(super) struct ZORetrievalMode {
     /// The primary zoned objective this mode attempts to complete.
@@ -31,6 +33,8 @@ pub(super) struct ZORetrievalMode {
     add_target: Option<Vec2D<I32F32>>,
     /// Unwrapped position of the target objective on the map (absolute), perspective from the burn exit point
     unwrapped_pos: Arc<Mutex<Vec2D<I32F32>>>,
+    /// Further objectives sharing the exit burn with their imaging positions, in retrieval order.
+    shared: Vec<(KnownImgObjective, Vec2D<I32F32>)>,
 }
 
 impl ZORetrievalMode {
@@ -45,6 +49,7 @@ impl ZORetrievalMode {
     /// * `target` – The objective to fulfill.
     /// * `add_target` – Optional second target position for dual-acquisition.
     /// * `unwrapped_pos` – Global position of the target on the map, perspective from the burn exit point.
+    /// * `shared` – Objectives sharing the exit burn with their imaging positions.
     ///
     /// # Returns
     /// * `ZORetrievalMode` – An initialized mode for retrieval.
@@ -52,9 +57,29 @@ impl ZORetrievalMode {
         target: KnownImgObjective,
         add_target: Option<Vec2D<I32F32>>,
         unwrapped_pos: Vec2D<I32F32>,
+        shared: Vec<(KnownImgObjective, Vec2D<I32F32>)>,
     ) -> Self {
         let unwrapped_lock = Arc::new(Mutex::new(unwrapped_pos));
-        Self { target, add_target, unwrapped_pos: unwrapped_lock }
+        Self { target, add_target, unwrapped_pos: unwrapped_lock, shared }
+    }
+
+    /// Returns the duration of an acquisition cycle, including the turn to a second target.
+    ///
+    /// # Arguments
+    /// * `pos` – Position at the start of the acquisition.
+    /// * `second_target` – Optional second target coordinates.
+    /// * `vel` – Velocity during the acquisition.
+    pub(super) fn acquisition_dt(
+        pos: Vec2D<I32F32>,
+        second_target: Option<Vec2D<I32F32>>,
+        vel: Vec2D<I32F32>,
+    ) -> TimeDelta {
+        let Some(add_target) = second_target else {
+            return Self::SINGLE_TARGET_ACQ_DT;
+        };
+        let to_target = pos.wrap_around_map().unwrapped_to(&add_target);
+        let traversal_ms = to_target.abs() / vel.abs() * I32F32::from_num(1000);
+        Self::SINGLE_TARGET_ACQ_DT * 2 + TimeDelta::milliseconds(traversal_ms.to_num::<i64>())
     }
 
     /// Prepares the async future for imaging, including timing and potential
@@ -77,13 +102,8 @@ impl ZORetrievalMode {
     ) {
         if let Some(add_target) = second_target {
             let current_vel = context.k().f_cont().read().await.current_vel();
-            let to_target = {
-                let wrapped = unwrapped_pos.wrap_around_map();
-                wrapped.unwrapped_to(&add_target)
-            };
-            let target_traversal_dt =
-                TimeDelta::seconds((to_target.abs() / current_vel.abs()).to_num::<i64>());
-            let t_end = Utc::now() + Self::SINGLE_TARGET_ACQ_DT * 2 + target_traversal_dt;
+            let acq_dt = Self::acquisition_dt(unwrapped_pos, second_target, current_vel);
+            let t_end = Utc::now() + acq_dt;
             let fut = FlightComputer::turn_for_2nd_target(context.k().f_cont(), add_target, t_end);
             (t_end, Box::pin(fut))
         } else {
@@ -97,27 +117,69 @@ impl ZORetrievalMode {
 
     /// Executes the full retrieval task including imaging and export/upload.
     ///
+    /// If further objectives share the exit burn, MELVIN turns towards them one after
+    /// another afterward and retrieves them in the same way.
+    ///
     /// # Arguments
     /// * `target` – The zoned objective to complete.
     /// * `unwrapped_target` – Absolute coordinates for targeting.
     /// * `second_target` – Optional second target for multi-point objectives.
+    /// * `shared` – Objectives sharing the exit burn, in retrieval order.
     /// * `context` – Shared context.
     /// * `c_tok` – Cancellation token for task coordination.
     async fn exec_img_task(
         target: KnownImgObjective,
         unwrapped_target: Vec2D<I32F32>,
         second_target: Option<Vec2D<I32F32>>,
+        shared: Vec<(KnownImgObjective, Vec2D<I32F32>)>,
         context: Arc<ModeContext>,
         c_tok: CancellationToken,
     ) {
-        let offset = Vec2D::new(target.zone()[0], target.zone()[1]).to_unsigned();
-        let dim = Vec2D::new(target.width(), target.height()).to_unsigned();
-
-        let c_cont = context.k().c_cont();
         let (deadline, add_fut) =
             Self::get_img_fut(second_target, unwrapped_target, &context).await;
+        Self::acquire_and_upload(&target, deadline, add_fut, &context, &c_tok).await;
+        for (partner, partner_pos) in shared {
+            if c_tok.is_cancelled() {
+                return;
+            }
+            if context.is_zo_removed(partner.id()).await {
+                continue;
+            }
+            log!("Turning towards Zoned Objective {} sharing the exit burn.", partner.id());
+            let pos = context.k().f_cont().read().await.current_pos();
+            let (partner_deadline, turn_fut) =
+                Self::get_img_fut(Some(partner_pos), pos, &context).await;
+            Self::acquire_and_upload(&partner, partner_deadline, turn_fut, &context, &c_tok).await;
+        }
+    }
+
+    /// Acquires images of a zoned objective until the deadline and uploads the result.
+    ///
+    /// The acquisition extends the mosaic held back from earlier flyovers, if any. An incomplete
+    /// mosaic is held back for the next flyover if another pass is allowed and possible before
+    /// the objective ends, otherwise the best mosaic imaged so far is uploaded.
+    ///
+    /// # Arguments
+    /// * `target` – The zoned objective to complete.
+    /// * `deadline` – The end of the acquisition cycle.
+    /// * `add_fut` – Future running alongside the acquisition, e.g. a turn.
+    /// * `context` – Shared context.
+    /// * `c_tok` – Cancellation token for task coordination.
+    async fn acquire_and_upload(
+        target: &KnownImgObjective,
+        deadline: DateTime<Utc>,
+        add_fut: Pin<Box<dyn Future<Output = ()> + Send + Sync>>,
+        context: &Arc<ModeContext>,
+        c_tok: &CancellationToken,
+    ) {
+        let offset = target.zone().offset().to_unsigned();
+        let dim = target.zone().size().to_unsigned();
+
+        let c_cont = context.k().c_cont();
         let f_cont = context.k().f_cont();
-        let mut zoned_objective_image_buffer = None;
+        let (mut zoned_objective_image_buffer, prev_passes) =
+            context.mosaic_passes().lock().await.take(target.id());
+        OBJECTIVE_TRACKER.record(target.id(), LifecycleStage::Imaging);
         let img_fut = c_cont.execute_zo_target_cycle(
             f_cont,
             deadline,
@@ -134,21 +196,136 @@ impl ZORetrievalMode {
                 FlightComputer::stop_ongoing_burn(context.k().f_cont()).await;
             }
         }
+        let passes = prev_passes + 1;
+        let buffer_ref = zoned_objective_image_buffer.as_ref();
+        if Self::await_next_flyover(target, buffer_ref, passes, context).await {
+            Self::release_incomplete(target, zoned_objective_image_buffer, passes, context).await;
+            return;
+        }
         let c_cont = context.k().c_cont();
         let id = target.id();
         let img_path = Some(CameraController::generate_zo_img_path(id));
-        c_cont
-            .export_and_upload_objective_png(
+        let upload = c_cont
+            .export_and_upload_objective_img(
                 id,
                 offset,
                 dim,
                 img_path,
                 zoned_objective_image_buffer.as_ref(),
+                Some(target.end()),
             )
             .await
-            .unwrap_or_else(|e| {
-                error!("Error exporting and uploading objective image: {e}");
-            });
+            .map_err(|e| error!("Error exporting and uploading objective image: {e}"));
+        let accepted = if let Ok(response) = upload {
+            OBJECTIVE_TRACKER.record(id, LifecycleStage::Uploaded);
+            context.eval_secret_probe(id, target.zone(), response.as_deref()).await
+        } else {
+            if c_cont.is_upload_pending(id).await {
+                context.secret_hunt().lock().await.queue_probe(id, target.zone());
+            } else {
+                OBJECTIVE_TRACKER.fail(id, "upload failed");
+            }
+            false
+        };
+        if accepted {
+            context.k().score().record_zo(id).await;
+            context.k().t_cont().events().fire(ExternalEvent::ObjectiveAccepted(id));
+        }
+    }
+
+    /// Checks whether the mosaic of a zoned objective reaches the `zo_min_coverage` of the
+    /// [`MissionConfig`] and otherwise whether a further imaging pass is worth waiting for.
+    ///
+    /// The zone has already been passed after an acquisition, so a further pass is only
+    /// possible on the next flyover, one closed orbit period later at the earliest. If the
+    /// `zo_max_extra_passes` are used up or the objective ends before that, the incomplete
+    /// mosaic is the best one available.
+    ///
+    /// # Arguments
+    /// * `target` – The zoned objective being imaged.
+    /// * `zo_image` – The mosaic of the objective.
+    /// * `passes` – The number of imaging passes taken, including the current one.
+    /// * `context` – Shared context.
+    ///
+    /// # Returns
+    /// `true` if the mosaic should be held back for the next flyover instead of being uploaded.
+    async fn await_next_flyover(
+        target: &KnownImgObjective,
+        zo_image: Option<&OffsetZonedObjectiveImage>,
+        passes: u32,
+        context: &Arc<ModeContext>,
+    ) -> bool {
+        let config = MissionConfig::get();
+        let runtime = &config.runtime;
+        let offset = target.zone().offset().to_unsigned();
+        let dim = target.zone().size().to_unsigned();
+        let c_cont = context.k().c_cont();
+        let completeness = c_cont.objective_completeness(offset, dim, zo_image).await;
+        if completeness.is_sufficient(runtime.zo_min_coverage) {
+            return false;
+        }
+        obj!(
+            "Zoned Objective {} mosaic covers {:.1}% with {} holes, largest gap {} px after {} \
+             passes.",
+            target.id(),
+            completeness.coverage() * 100.0,
+            completeness.holes(),
+            completeness.largest_gap_px(),
+            passes
+        );
+        let period = i64::try_from(context.o_ch().orbit_full_period()).unwrap_or(i64::MAX);
+        let next_flyover = Utc::now() + TimeDelta::seconds(period.min(i64::MAX / 1000));
+        let retry = MosaicPasses::retry_on_next_flyover(
+            passes,
+            runtime.zo_max_extra_passes,
+            next_flyover,
+            target.end(),
+        );
+        if !retry {
+            let id = target.id();
+            obj!("No further pass of Zoned Objective {id} possible, uploading the best mosaic.");
+        }
+        retry
+    }
+
+    /// Holds back an incomplete mosaic and returns its zoned objective to the objective buffer,
+    /// so that it is imaged again on its next flyover, unless it expired or was deleted.
+    ///
+    /// # Arguments
+    /// * `target` – The zoned objective that was not uploaded.
+    /// * `zo_image` – The mosaic imaged so far.
+    /// * `passes` – The number of imaging passes taken.
+    /// * `context` – Shared context.
+    async fn release_incomplete(
+        target: &KnownImgObjective,
+        zo_image: Option<OffsetZonedObjectiveImage>,
+        passes: u32,
+        context: &Arc<ModeContext>,
+    ) {
+        let id = target.id();
+        if Utc::now() > target.end() || context.is_zo_removed(id).await {
+            warn!("Zoned Objective {id} mosaic is incomplete and it can not be retrieved again.");
+            OBJECTIVE_TRACKER.fail(id, "incomplete mosaic");
+        } else {
+            obj!("Zoned Objective {id} mosaic is incomplete. Imaging it on its next flyover.");
+            context.mosaic_passes().lock().await.hold(id, zo_image, passes, target.end());
+            context.k_buffer().lock().await.push(target.clone());
+        }
+    }
+
+    /// Returns the partner objectives of a shared exit burn to the objective buffer,
+    /// unless they were already submitted.
+    ///
+    /// # Arguments
+    /// * `context` – Shared context.
+    async fn release_partners(&self, context: &Arc<ModeContext>) {
+        for (partner, _) in &self.shared {
+            let id = partner.id();
+            if !context.k().score().has_zo(id).await && !context.is_zo_removed(id).await {
+                obj!("Zoned Objective {id} sharing the exit burn was not retrieved.");
+                context.k_buffer().lock().await.push(partner.clone());
+            }
+        }
     }
 }
 
@@ -159,6 +336,8 @@ impl GlobalMode for ZORetrievalMode {
 
     /// Initializes the mode by performing detumbling, scheduling, and target alignment.
     ///
+    /// The images are taken with the lens required by the objective.
+    ///
     /// # Arguments
     /// * `context` – Shared context for access to controllers and state.
     ///
@@ -166,12 +345,10 @@ impl GlobalMode for ZORetrievalMode {
     /// * `OpExitSignal` – Whether to continue or reinitialize the mode.
     async fn init_mode(&self, context: Arc<ModeContext>) -> OpExitSignal {
         let mut unwrapped_pos = self.unwrapped_pos.lock().await;
-        let fut = FlightComputer::detumble_to(
-            context.k().f_cont(),
-            *unwrapped_pos,
-            self.target.optic_required(),
-        );
-        let safe_mon = context.super_v().safe_mon();
+        let lens = self.target.optic_required();
+        context.set_init_stage("detumbling to target");
+        let fut = FlightComputer::detumble_to(context.k().f_cont(), *unwrapped_pos, lens);
+        let mut safe_mon = context.safe_mon();
         let target_t;
         let wrapped_target;
         let mut handle = tokio::spawn(fut);
@@ -181,7 +358,7 @@ impl GlobalMode for ZORetrievalMode {
                 wrapped_target =  res.1;
                 target_t = res.0;
             },
-            () = safe_mon.notified() => {
+            () = ModeContext::wait_for_safe(&mut safe_mon) => {
                 handle.abort();
                 return self.safe_handler(context).await;
             }
@@ -191,17 +368,15 @@ impl GlobalMode for ZORetrievalMode {
         let t_cont = context.k().t_cont();
         t_cont.clear_schedule().await; // Just to be sure
         t_cont
-            .schedule_retrieval_phase(
-                target_t,
-                wrapped_target.wrap_around_map(),
-                self.target.optic_required(),
-            )
+            .schedule_retrieval_phase(target_t, wrapped_target.wrap_around_map(), lens)
             .await;
         context.k().con().send_tasklist().await;
+        context.refresh_timeline().await;
         OpExitSignal::Continue
     }
 
-    /// Waits until the due time of the next task or exits early on a Safe Mode event.
+    /// Waits until the due time of the next task or exits early on a Safe Mode event or the
+    /// deletion of a Zoned Objective.
     ///
     /// # Arguments
     /// * `context` – Mode context.
@@ -214,20 +389,25 @@ impl GlobalMode for ZORetrievalMode {
         context: Arc<ModeContext>,
         due: DateTime<Utc>,
     ) -> WaitExitSignal {
-        let safe_mon = context.super_v().safe_mon();
+        let mut safe_mon = context.safe_mon();
+        let mut zo_rem_mon = context.zo_rem_mon().write().await;
         let dt = (due - Utc::now()).to_std().unwrap_or(DT_0_STD);
         tokio::select! {
             () = FlightComputer::wait_for_duration(dt, false) => {
                 WaitExitSignal::Continue
             },
-            () = safe_mon.notified() => {
+            () = ModeContext::wait_for_safe(&mut safe_mon) => {
                 WaitExitSignal::SafeEvent
             }
+            Some(ObjectiveEvent::ZoRemoved(id)) = zo_rem_mon.recv() => {
+                WaitExitSignal::ZORemovedEvent(id)
+            }
         }
     }
 
     /// Executes a given task: imaging or state transition.
-    /// Velocity change tasks are not allowed and result in a logged error.
+    /// Velocity change tasks are not allowed and result in a logged error. Operator-pinned image
+    /// tasks are taken like in the orbital modes instead of retrieving the objective.
     ///
     /// # Arguments
     /// * `context` – Shared context.
@@ -237,19 +417,24 @@ impl GlobalMode for ZORetrievalMode {
     /// * `ExecExitSignal` – Indicates result of execution.
     async fn exec_task(&self, context: Arc<ModeContext>, task: Task) -> ExecExitSignal {
         match task.task_type() {
+            BaseTask::TakeImage(image) if task.is_pinned() => {
+                BaseMode::get_pinned_image(context, *image).await;
+            }
             BaseTask::TakeImage(_) => {
-                let safe_mon = context.super_v().safe_mon();
+                let mut safe_mon = context.safe_mon();
                 let c_tok = CancellationToken::new();
                 let c_tok_clone = c_tok.clone();
                 let context_clone = Arc::clone(&context);
                 let second_target = self.add_target;
                 let unwrapped_target = *self.unwrapped_pos.lock().await;
                 let target = self.target.clone();
+                let shared = self.shared.clone();
                 let img_handle = tokio::spawn(async move {
                     Self::exec_img_task(
                         target,
                         unwrapped_target,
                         second_target,
+                        shared,
                         context_clone,
                         c_tok_clone,
                     )
@@ -258,7 +443,7 @@ impl GlobalMode for ZORetrievalMode {
                 tokio::pin!(img_handle);
                 tokio::select! {
                     _ = &mut img_handle => { },
-                    () = safe_mon.notified() => {
+                    () = ModeContext::wait_for_safe(&mut safe_mon) => {
                         c_tok.cancel();
                         img_handle.await.unwrap_or_else(|e| {
                             error!("Error joining zo image task: {e}");
@@ -321,10 +506,8 @@ impl GlobalMode for ZORetrievalMode {
             }
         }
         warn!("Objective not reachable after safe event, exiting ZORetrievalMode");
-        context.o_ch_lock().write().await.finish(
-            context.k().f_cont().read().await.current_pos(),
-            self.out_of_orbit_rationale(),
-        );
+        context.finish_phase(self.out_of_orbit_rationale()).await;
+        self.release_partners(&context).await;
         OpExitSignal::ReInit(Box::new(OrbitReturnMode::new()))
     }
 
@@ -336,6 +519,42 @@ impl GlobalMode for ZORetrievalMode {
     /// Not implemented – Beacon Objective events are ignored in this mode.
     async fn bo_event_handler(&self, _: &Arc<ModeContext>) -> OptOpExitSignal { unimplemented!() }
 
+    /// Handles the deletion of a Zoned Objective by the backend.
+    ///
+    /// If the target was deleted, the retrieval is cancelled and MELVIN returns to the
+    /// closed orbit. A deleted partner of a shared burn is skipped during imaging.
+    ///
+    /// # Arguments
+    /// * `context` – Shared context.
+    /// * `id` – The ID of the deleted objective.
+    ///
+    /// # Returns
+    /// * `Some(OpExitSignal::ReInit)` with an `OrbitReturnMode` if the target was deleted.
+    async fn zo_removed_handler(&self, context: &Arc<ModeContext>, id: ImgObjectiveId) -> OptOpExitSignal {
+        context.remove_zo(id).await;
+        if id != self.target.id() {
+            return None;
+        }
+        obj!("Cancelling retrieval of deleted Zoned Objective {id}.");
+        context.k().t_cont().clear_schedule().await;
+        context.finish_phase(self.zo_removed_rationale()).await;
+        self.release_partners(context).await;
+        Some(OpExitSignal::ReInit(Box::new(OrbitReturnMode::new())))
+    }
+
+    /// Handles a timed out initialization by returning to the closed orbit.
+    ///
+    /// # Arguments
+    /// * `context` – Shared context.
+    ///
+    /// # Returns
+    /// * `OpExitSignal::ReInit` – With an `OrbitReturnMode`.
+    async fn init_timeout_handler(&self, context: Arc<ModeContext>) -> OpExitSignal {
+        context.k().t_cont().clear_schedule().await;
+        self.release_partners(&context).await;
+        OpExitSignal::ReInit(Box::new(OrbitReturnMode::new()))
+    }
+
     /// Finalizes the retrieval mode and transitions to `OrbitReturnMode`.
     ///
     /// # Arguments
@@ -344,10 +563,8 @@ impl GlobalMode for ZORetrievalMode {
     /// # Returns
     /// * `Box<dyn GlobalMode>` – Next mode to execute.
     async fn exit_mode(&self, context: Arc<ModeContext>) -> Box<dyn GlobalMode> {
-        context.o_ch_lock().write().await.finish(
-            context.k().f_cont().read().await.current_pos(),
-            self.tasks_done_rationale(),
-        );
+        context.finish_phase(self.tasks_done_rationale()).await;
+        self.release_partners(&context).await;
         Box::new(OrbitReturnMode::new())
     }
 }
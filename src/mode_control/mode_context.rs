@@ -1,10 +1,29 @@
+use crate::console_communication::{TelemetrySnapshot, TelemetrySource};
 use crate::flight_control::{
-    orbit::OrbitCharacteristics,
-    Supervisor,
+    orbit::{ExitBurnResult, OrbitCharacteristics, OrbitCheckpointer},
+    FlightTelemetry, Supervisor,
+};
+use crate::imaging::ResolutionEstimate;
+use super::coverage_guard::CoverageGuard;
+use super::mode_graph::{MODE_GRAPH, TransitionPhase, TransitionTrigger};
+use super::{mode::GlobalMode, mode_state_store::ModeStateStore, mosaic_passes::MosaicPasses};
+use crate::objective::{
+    BeaconController, BeaconControllerState, KnownImgObjective, OBJECTIVE_TRACKER, ObjectivePriority,
+    SecretHunt,
+};
+use crate::scheduling::{MissionTimeline, TaskController, task::BaseTask};
+use crate::util::{
+    EVENT_BUS, ImgObjectiveId, JournalEvent, KeychainWithOrbit, MISSION_JOURNAL, MISSION_METRICS,
+    MissionConfig, ObjectiveEvent, PLANNING_POOL, Subscription, ZoneRect, logger::JsonDump,
+};
+use crate::{info, log, obj};
+use async_trait::async_trait;
+use chrono::{TimeDelta, Utc};
+use std::{
+    collections::{BinaryHeap, HashSet},
+    path::Path,
+    sync::Arc,
 };
-use crate::objective::{BeaconController, BeaconControllerState, KnownImgObjective};
-use crate::util::KeychainWithOrbit;
-use std::{collections::BinaryHeap, sync::Arc};
 use tokio::sync::{Mutex, RwLock, mpsc::Receiver, watch};
 
 /// [`ModeContext`] is a central context container used by `GlobalMode` in the onboard software.
@@ -18,20 +37,49 @@ pub(crate) struct ModeContext {
     /// Shared keychain containing the various controllers and the orbit configuration.
     k: Arc<KeychainWithOrbit>,
     /// Orbit characteristics, updated during operation (e.g., after burns).
-    o_ch: Arc<RwLock<OrbitCharacteristics>>,
+    o_ch: watch::Sender<OrbitCharacteristics>,
     /// Supervisor instance providing new observation data, objective data, etc.
     super_v: Arc<Supervisor>,
     /// Receiver for new Known Image Objectives (Zoned Objectives).
     zo_mon: RwLock<Receiver<KnownImgObjective>>,
+    /// Receiver for the IDs of Zoned Objectives deleted by the backend.
+    zo_rem_mon: RwLock<Subscription<ObjectiveEvent>>,
+    /// The IDs of all Zoned Objectives deleted by the backend.
+    removed_zos: Mutex<HashSet<ImgObjectiveId>>,
     /// Watch receiver for the current state of the Beacon Controller.
-    bo_mon: RwLock<watch::Receiver<BeaconControllerState>>,
+    bo_mon: watch::Receiver<BeaconControllerState>,
     /// Priority buffer for scheduled image objectives, used by internal planners.
     k_buffer: Mutex<BinaryHeap<KnownImgObjective>>,
     /// Shared access to the Beacon Controller for retrieval logic and updates.
     beac_cont: Arc<BeaconController>,
+    /// Imaging age of the closed orbit stripes, checked before every daily map upload.
+    coverage: Mutex<CoverageGuard>,
+    /// Description of what the currently running mode initialization is waiting on.
+    init_stage: std::sync::Mutex<&'static str>,
+    /// Name of the currently active global mode.
+    active_mode: std::sync::Mutex<&'static str>,
+    /// The event that caused the active mode to request a transition, if any.
+    transition_trigger: std::sync::Mutex<Option<TransitionTrigger>>,
+    /// Periodic persistence of the closed orbit coverage.
+    checkpointer: Arc<OrbitCheckpointer>,
+    /// Search state for the zones of pending secret objectives.
+    secret_hunt: Mutex<SecretHunt>,
+    /// Incomplete Zoned Objective mosaics held back for their next flyover.
+    mosaic_passes: Mutex<MosaicPasses>,
+    /// Persisted plan of the active mode, resumed after a restart.
+    mode_store: ModeStateStore,
 }
 
 impl ModeContext {
+    /// The name of the active mode before the first mode is started.
+    const IDLE_MODE: &'static str = "idle";
+    /// Directory below `imaging.base_path` that the plan of the active mode is persisted to.
+    const MODE_STATE_DIR: &'static str = "mode_state";
+    /// The minimum share of the highest candidate value for an infeasible Zoned Objective to
+    /// be retried later.
+    const RETRY_MIN_VALUE_SHARE: f64 = 0.75;
+    /// The minimum remaining window for an infeasible Zoned Objective to be retried later.
+    const RETRY_MIN_WINDOW: TimeDelta = TimeDelta::hours(2);
 
     /// Constructs a new [`ModeContext`], initializing all internal references.
     ///
@@ -51,34 +99,401 @@ impl ModeContext {
         beac_cont: Arc<BeaconController>,
     ) -> Arc<Self> {
         let k = Arc::new(key);
-        let o_ch = Arc::new(RwLock::new(o_char));
+        let (o_ch, _) = watch::channel(o_char);
         let zo_mon = RwLock::new(zo_mon_un);
-        let bo_mon = RwLock::new(bo_mon_un);
+        let zo_rem_mon = RwLock::new(EVENT_BUS.subscribe());
+        let coverage = Mutex::new(CoverageGuard::new(o_char.i_entry().period()));
+        let checkpointer = Arc::new(OrbitCheckpointer::new(k.c_orbit()));
+        let base_path = MissionConfig::get().imaging.base_path.clone();
+        let state_dir = Path::new(&base_path).join(Self::MODE_STATE_DIR);
         Arc::new(Self {
             k,
             o_ch,
             super_v,
             zo_mon,
-            bo_mon,
+            zo_rem_mon,
+            removed_zos: Mutex::new(HashSet::new()),
+            bo_mon: bo_mon_un,
             k_buffer: Mutex::new(BinaryHeap::new()),
             beac_cont,
+            coverage,
+            init_stage: std::sync::Mutex::new("idle"),
+            active_mode: std::sync::Mutex::new(Self::IDLE_MODE),
+            transition_trigger: std::sync::Mutex::new(None),
+            checkpointer,
+            secret_hunt: Mutex::new(SecretHunt::default()),
+            mosaic_passes: Mutex::new(MosaicPasses::default()),
+            mode_store: ModeStateStore::open(state_dir),
         })
     }
 
     /// Provides a reference to the [`KeychainWithOrbit`].
-    pub(super) fn k(&self) -> &Arc<KeychainWithOrbit> { &self.k }
-    /// Provides a copy of the current [`OrbitCharacteristics`]. 
-    pub(crate) async fn o_ch_clone(&self) -> OrbitCharacteristics { *self.o_ch.read().await }
-    /// Provides a reference to the shared and locked [`OrbitCharacteristics`].
-    pub(super) fn o_ch_lock(&self) -> &RwLock<OrbitCharacteristics> { &self.o_ch }
+    pub(crate) fn k(&self) -> &Arc<KeychainWithOrbit> { &self.k }
+    /// Provides a copy of the latest [`OrbitCharacteristics`].
+    pub(crate) fn o_ch(&self) -> OrbitCharacteristics { *self.o_ch.borrow() }
+    /// Subscribes to changes of the [`OrbitCharacteristics`].
+    pub(crate) fn o_ch_watch(&self) -> watch::Receiver<OrbitCharacteristics> {
+        self.o_ch.subscribe()
+    }
+    /// Modifies the [`OrbitCharacteristics`] in place and notifies all watchers.
+    pub(super) fn o_ch_modify(&self, f: impl FnOnce(&mut OrbitCharacteristics)) {
+        self.o_ch.send_modify(f);
+    }
+    /// Finishes the current orbit phase at MELVINs current position.
+    ///
+    /// # Arguments
+    /// - `rationale`: The reason for finishing the phase, used for logging.
+    pub(super) async fn finish_phase(&self, rationale: &str) {
+        let pos = self.k.f_cont().read().await.current_pos();
+        self.o_ch_modify(|o_ch| o_ch.finish(pos, rationale));
+    }
     /// Provides a reference to the locked Zoned Objective Event Receiver.
     pub(super) fn zo_mon(&self) -> &RwLock<Receiver<KnownImgObjective>> { &self.zo_mon }
-    /// Provides a reference to the watch resembling the current state of the Beacon controller.
-    pub(super) fn bo_mon(&self) -> &RwLock<watch::Receiver<BeaconControllerState>> { &self.bo_mon }
+    /// Provides a reference to the locked receiver for deleted Zoned Objective IDs.
+    pub(super) fn zo_rem_mon(&self) -> &RwLock<Subscription<ObjectiveEvent>> {
+        &self.zo_rem_mon
+    }
+    /// Marks a Zoned Objective as deleted and drops it from the objective buffer.
+    ///
+    /// # Arguments
+    /// - `id`: The ID of the deleted objective.
+    ///
+    /// # Returns
+    /// - `true` if the objective was buffered.
+    pub(super) async fn remove_zo(&self, id: ImgObjectiveId) -> bool {
+        self.removed_zos.lock().await.insert(id);
+        let mut k_buffer = self.k_buffer.lock().await;
+        let len = k_buffer.len();
+        k_buffer.retain(|obj| obj.id() != id);
+        let buffered = k_buffer.len() < len;
+        if buffered {
+            obj!("Dropped deleted Zoned Objective {id} from buffer.");
+        }
+        buffered
+    }
+    /// Returns `true` if the Zoned Objective was deleted by the backend.
+    pub(super) async fn is_zo_removed(&self, id: ImgObjectiveId) -> bool {
+        self.removed_zos.lock().await.contains(&id)
+    }
+    /// Plans the exit burn towards a Zoned Objective with the fuel available to it.
+    ///
+    /// A burn whose exit velocity is too fast for usable images with the required lens of the
+    /// objective counts as unreachable, so that the objective is deferred like any other.
+    ///
+    /// # Arguments
+    /// - `zo`: The Zoned Objective.
+    ///
+    /// # Returns
+    /// - The best [`ExitBurnResult`], or `None` if the objective is unreachable.
+    pub(super) async fn plan_exit_burn(&self, zo: &KnownImgObjective) -> Option<ExitBurnResult> {
+        let exit_burn = self.plan_unchecked_exit_burn(zo).await?;
+        Self::meets_resolution(zo, &exit_burn).then_some(exit_burn)
+    }
+
+    /// Returns `true` if the images taken with the exit velocity of a burn are usable for a
+    /// Zoned Objective, logging the rejection otherwise.
+    ///
+    /// # Arguments
+    /// - `zo`: The Zoned Objective.
+    /// - `exit_burn`: The exit burn towards it.
+    pub(super) fn meets_resolution(zo: &KnownImgObjective, exit_burn: &ExitBurnResult) -> bool {
+        let vel = *exit_burn.sequence().sequence_vel().last().unwrap();
+        let resolution = ResolutionEstimate::for_objective(zo, vel);
+        if !resolution.is_usable() {
+            log!(
+                "Rejecting exit burn for Zoned Objective {}: {} images at {:.1} are unusable.",
+                zo.id(),
+                resolution.lens(),
+                resolution.flyover_speed()
+            );
+        }
+        resolution.is_usable()
+    }
+
+    /// Plans the fuel-optimal exit burn towards a Zoned Objective, regardless of the resolution
+    /// of the images taken with its exit velocity. The burn is planned in the [`PLANNING_POOL`].
+    ///
+    /// # Arguments
+    /// - `zo`: The Zoned Objective.
+    async fn plan_unchecked_exit_burn(&self, zo: &KnownImgObjective) -> Option<ExitBurnResult> {
+        log!("Planning exit burn for Zoned Objective: {}", zo.id());
+        let (current_vel, fuel_left) = {
+            let f_cont_lock = self.k.f_cont();
+            let f_cont = f_cont_lock.read().await;
+            (f_cont.current_vel(), f_cont.fuel_left())
+        };
+        let fuel_avail = self.k.fuel().available_for(zo.id(), fuel_left);
+        let (start, due) = (zo.start(), zo.end());
+        if start > Utc::now() {
+            log!("Objective {} will be calculated as a short objective.", zo.id());
+        }
+        let i_entry = self.o_ch().i_entry();
+        PLANNING_POOL
+            .run("exit_burn", || {
+                if zo.min_images() == 1 {
+                    TaskController::calculate_single_target_burn_sequence(
+                        i_entry,
+                        current_vel,
+                        zo.get_single_image_point(),
+                        start,
+                        due,
+                        fuel_avail,
+                        zo.id(),
+                        zo.impact_margin(),
+                    )
+                } else {
+                    TaskController::calculate_multi_target_burn_sequence(
+                        i_entry,
+                        current_vel,
+                        zo.get_corners(),
+                        start,
+                        due,
+                        fuel_avail,
+                        zo.id(),
+                        zo.impact_margin(),
+                    )
+                }
+            })
+            .await
+    }
+    /// Ranks concurrent Zoned Objectives by their [`ObjectivePriority`].
+    ///
+    /// The exit burn of every candidate is planned. Infeasible candidates are dropped early if
+    /// their value is low compared to the most valuable candidate or their window closes soon.
+    /// The remaining infeasible ones are returned to the objective buffer to be retried later.
+    ///
+    /// # Arguments
+    /// - `candidates`: The Zoned Objectives to rank.
+    ///
+    /// # Returns
+    /// - The feasible candidates with their exit burns by descending score.
+    pub(super) async fn select_objectives(
+        &self,
+        candidates: Vec<KnownImgObjective>,
+    ) -> Vec<(KnownImgObjective, ExitBurnResult)> {
+        let mut planned = Vec::with_capacity(candidates.len());
+        for zo in candidates {
+            let exit_burn = self.plan_exit_burn(&zo).await;
+            let fuel = exit_burn.as_ref().map(|burn| burn.sequence().min_fuel());
+            let priority = ObjectivePriority::new(&zo, fuel);
+            planned.push((zo, exit_burn, priority));
+        }
+        let max_value = planned.iter().map(|(_, _, p)| p.value()).fold(0.0, f64::max);
+        let retry_until = Utc::now() + Self::RETRY_MIN_WINDOW;
+        let mut feasible = Vec::new();
+        for (zo, exit_burn, priority) in planned {
+            if let Some(burn) = exit_burn {
+                obj!("Ranking Zoned Objective {}: {priority}.", zo.id());
+                feasible.push((zo, burn, priority));
+            } else if priority.value() < max_value * Self::RETRY_MIN_VALUE_SHARE {
+                obj!("Dropping Zoned Objective {}: {priority}, outranked.", zo.id());
+                OBJECTIVE_TRACKER.fail(zo.id(), "outranked");
+            } else if zo.end() < retry_until {
+                obj!("Dropping Zoned Objective {}: {priority}, closing soon.", zo.id());
+                OBJECTIVE_TRACKER.fail(zo.id(), "unreachable before its end");
+            } else {
+                obj!("Deferring Zoned Objective {}: {priority}.", zo.id());
+                self.k_buffer.lock().await.push(zo);
+            }
+        }
+        let score = |p: &ObjectivePriority| p.score().unwrap_or_default();
+        feasible.sort_by(|(_, _, a), (_, _, b)| score(b).total_cmp(&score(a)));
+        feasible.into_iter().map(|(zo, burn, _)| (zo, burn)).collect()
+    }
+    /// Returns the latest state of the Beacon Controller.
+    pub(super) fn bo_state(&self) -> BeaconControllerState { *self.bo_mon.borrow() }
+    /// Provides a watch receiver that is notified on every future Beacon Controller state change.
+    pub(super) fn bo_watch(&self) -> watch::Receiver<BeaconControllerState> {
+        let mut rx = self.bo_mon.clone();
+        rx.mark_unchanged();
+        rx
+    }
+    /// Provides a watch receiver for the safe-mode flag, which is `true` while MELVIN is in
+    /// an unplanned safe mode.
+    pub(super) fn safe_mon(&self) -> watch::Receiver<bool> { self.super_v.safe_mon() }
+
+    /// Waits until the safe-mode flag of the given receiver is set.
+    ///
+    /// # Arguments
+    /// - `safe_mon`: A receiver obtained from `safe_mon()`.
+    pub(super) async fn wait_for_safe(safe_mon: &mut watch::Receiver<bool>) {
+        if safe_mon.wait_for(|safe| *safe).await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    }
     /// Provides a shared reference to the [`Supervisor`].
-    pub(super) fn super_v(&self) -> &Arc<Supervisor> { &self.super_v }
+    pub(crate) fn super_v(&self) -> &Arc<Supervisor> { &self.super_v }
     /// Provides a reference to the locked Zoned Objective Buffer implemented as a [`BinaryHeap`].
     pub(super) fn k_buffer(&self) -> &Mutex<BinaryHeap<KnownImgObjective>> { &self.k_buffer }
     /// Provides a shared reference to the [`BeaconController`].
     pub(super) fn beac_cont(&self) -> &Arc<BeaconController> { &self.beac_cont }
+    /// Provides a reference to the locked [`CoverageGuard`].
+    pub(super) fn coverage(&self) -> &Mutex<CoverageGuard> { &self.coverage }
+    /// Provides a reference to the locked [`SecretHunt`].
+    pub(super) fn secret_hunt(&self) -> &Mutex<SecretHunt> { &self.secret_hunt }
+    /// Provides a reference to the locked [`MosaicPasses`].
+    pub(super) fn mosaic_passes(&self) -> &Mutex<MosaicPasses> { &self.mosaic_passes }
+    /// Synchronizes the [`SecretHunt`] with the secret objectives pending at the supervisor.
+    pub(super) async fn sync_secret_hunt(&self) {
+        let pending = self.super_v.pending_secret_objectives().await;
+        self.secret_hunt.lock().await.sync(&pending, chrono::Utc::now());
+    }
+    /// Evaluates the upload of a zoned objective that probes a candidate region of a
+    /// secret objective. Regular zoned objectives are always accepted.
+    ///
+    /// # Arguments
+    /// * `id` – The ID of the uploaded objective.
+    /// * `zone` – The imaged zone of the uploaded objective.
+    /// * `response` – The DRS response to the upload.
+    ///
+    /// # Returns
+    /// `true` if the upload completed the objective.
+    pub(super) async fn eval_secret_probe(
+        &self,
+        id: ImgObjectiveId,
+        zone: ZoneRect,
+        response: Option<&str>,
+    ) -> bool {
+        let mut hunt = self.secret_hunt.lock().await;
+        if !hunt.contains(id) {
+            return true;
+        }
+        if hunt.record_probe(id, zone, response) {
+            drop(hunt);
+            obj!("Secret Objective {id} found in zone {zone:?}!");
+            self.super_v.resolve_secret_objective(id).await;
+            true
+        } else {
+            obj!("Secret Objective {id} not in zone {zone:?}: {}", response.unwrap_or("-"));
+            false
+        }
+    }
+    /// Regenerates the [`MissionTimeline`] from the current schedule, the buffered Zoned
+    /// Objectives and the active Beacon Objectives, dumps it and hands it to the console.
+    pub(super) async fn refresh_timeline(&self) {
+        let state = self.k.f_cont().read().await.state();
+        let mut timeline = {
+            let sched = self.k.t_cont().sched_arc();
+            let sched_lock = sched.read().await;
+            MissionTimeline::from_schedule(Utc::now(), state, sched_lock.iter())
+        };
+        for zo in self.k_buffer.lock().await.iter() {
+            timeline.add_objective(format!("ZO {}-'{}'", zo.id(), zo.name()), zo.start(), zo.end());
+        }
+        for (label, start, end) in self.beac_cont.active_windows().await {
+            timeline.add_objective(label, start, end);
+        }
+        timeline.dump_json();
+        self.k.con().update_timeline(timeline).await;
+    }
+    /// Records what the currently running mode initialization is waiting on.
+    pub(super) fn set_init_stage(&self, stage: &'static str) {
+        *self.init_stage.lock().unwrap() = stage;
+    }
+    /// Returns what the currently running mode initialization is waiting on.
+    pub(super) fn init_stage(&self) -> &'static str { *self.init_stage.lock().unwrap() }
+    /// Provides a shared reference to the [`OrbitCheckpointer`].
+    pub(crate) fn checkpointer(&self) -> &Arc<OrbitCheckpointer> { &self.checkpointer }
+    /// Returns the time a task is fired ahead of its due time to compensate the measured
+    /// command latency.
+    ///
+    /// # Arguments
+    /// * `task` – The task about to be executed.
+    pub(crate) fn command_lead(&self, task: &BaseTask) -> TimeDelta {
+        if task.is_latency_compensated() {
+            self.k.client().latency().lead()
+        } else {
+            TimeDelta::zero()
+        }
+    }
+    /// Persists the plan of a mode that is about to start, or deletes the persisted plan if
+    /// the mode has none.
+    ///
+    /// # Arguments
+    /// * `mode` – The mode about to start.
+    pub(crate) fn persist_mode(&self, mode: &dyn GlobalMode) {
+        match mode.snapshot() {
+            Some(snapshot) => self.mode_store.save(&snapshot),
+            None => self.mode_store.clear(),
+        }
+    }
+    /// Restores the mode that was active before a restart, if its plan is still feasible.
+    /// An infeasible plan is deleted.
+    ///
+    /// # Returns
+    /// * `Some(Box<dyn GlobalMode>)` – The resumed mode.
+    /// * `None` if no mode was persisted or its plan can no longer be executed.
+    pub(crate) async fn resume_mode(self: &Arc<Self>) -> Option<Box<dyn GlobalMode>> {
+        let snapshot = self.mode_store.restore()?;
+        let name = snapshot.mode_name();
+        let resumed = snapshot.resume(self).await;
+        if resumed.is_some() {
+            info!("Resuming {name} interrupted by the restart.");
+        } else {
+            log!("Interrupted {name} is no longer feasible, discarding its plan.");
+            self.mode_store.clear();
+        }
+        resumed
+    }
+    /// Returns the name of the currently active global mode.
+    pub(crate) fn active_mode(&self) -> &'static str { *self.active_mode.lock().unwrap() }
+    /// Records the name of the currently active global mode and journals the switch.
+    pub(crate) fn set_active_mode(&self, mode: &'static str) {
+        let prev = std::mem::replace(&mut *self.active_mode.lock().unwrap(), mode);
+        let from = (prev != Self::IDLE_MODE).then_some(prev);
+        if from.is_some() {
+            MISSION_METRICS.record_mode_switch();
+        }
+        MISSION_JOURNAL.record(JournalEvent::ModeSwitch { from, to: mode });
+    }
+    /// Notes the event that causes the active mode to request a transition.
+    pub(super) fn note_transition_trigger(&self, trigger: TransitionTrigger) {
+        *self.transition_trigger.lock().unwrap() = Some(trigger);
+    }
+    /// Records a transition of the active mode in the [`MODE_GRAPH`], with the noted trigger
+    /// or [`TransitionTrigger::Mode`] if none was noted.
+    ///
+    /// # Arguments
+    /// * `phase` – The step in which the active mode was left.
+    /// * `to` – The name of the next mode.
+    pub(crate) fn record_transition(&self, phase: TransitionPhase, to: &'static str) {
+        let trigger = match phase {
+            TransitionPhase::Exit => TransitionTrigger::TasksDone,
+            TransitionPhase::Init | TransitionPhase::Exec => {
+                self.transition_trigger.lock().unwrap().take().unwrap_or(TransitionTrigger::Mode)
+            }
+        };
+        MODE_GRAPH.record(self.active_mode(), to, phase, trigger);
+    }
+}
+
+#[async_trait]
+impl TelemetrySource for ModeContext {
+    async fn snapshot(&self) -> TelemetrySnapshot {
+        let f_cont_lock = self.k.f_cont();
+        let (observed_at, pos, vel, state, battery, fuel) = {
+            let f_cont = f_cont_lock.read().await;
+            (
+                f_cont.observed_at(),
+                f_cont.pos(),
+                f_cont.vel(),
+                f_cont.flight_state(),
+                f_cont.battery(),
+                f_cont.fuel(),
+            )
+        };
+        let task_queue_len = self.k.t_cont().sched_arc().read().await.len();
+        let coverage = self.k.c_orbit().read().await.get_coverage();
+        TelemetrySnapshot {
+            observed_at,
+            pos: [pos.x().to_num(), pos.y().to_num()],
+            vel: [vel.x().to_num(), vel.y().to_num()],
+            state,
+            battery: battery.to_num(),
+            fuel: fuel.to_num(),
+            mode: *self.active_mode.lock().unwrap(),
+            task_queue_len,
+            orbit_coverage: (coverage * 100).to_num(),
+        }
+    }
 }
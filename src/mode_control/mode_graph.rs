@@ -0,0 +1,216 @@
+use crate::util::ImgObjectiveId;
+use chrono::{DateTime, Utc};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt::Write,
+    sync::{LazyLock, Mutex},
+};
+use strum_macros::Display;
+
+/// The process-wide record of global mode transitions.
+pub(crate) static MODE_GRAPH: LazyLock<ModeGraph> = LazyLock::new(ModeGraph::new);
+
+/// The step of the mode state machine in which a global mode was left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Display, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub(crate) enum TransitionPhase {
+    /// The mode initialization returned [`OpExitSignal::ReInit`](super::OpExitSignal).
+    Init,
+    /// The task queue execution returned [`OpExitSignal::ReInit`](super::OpExitSignal).
+    Exec,
+    /// The task queue was completed with [`OpExitSignal::Continue`](super::OpExitSignal) and
+    /// the exit of the mode chose the next one.
+    Exit,
+}
+
+/// The event that caused a global mode transition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "kind", content = "id", rename_all = "snake_case")]
+pub(crate) enum TransitionTrigger {
+    /// The mode decided on its own, e.g. after an infeasible plan.
+    Mode,
+    /// The mode initialization timed out.
+    InitTimeout,
+    /// An unplanned safe mode was entered.
+    SafeEvent,
+    /// A new zoned objective arrived.
+    NewZo(ImgObjectiveId),
+    /// A zoned objective was deleted by the backend.
+    ZoRemoved(ImgObjectiveId),
+    /// The beacon objective state changed.
+    BoEvent,
+    /// Map captures recovered from a degradation.
+    ImagingRecovered,
+    /// A failed coverage check requested catch-up imaging.
+    CoverageCatchUp,
+    /// An operator aborted the mode from the console.
+    OperatorAbort,
+    /// A burn sequence was aborted and rolled back.
+    BurnAborted,
+    /// The task queue was completed.
+    TasksDone,
+}
+
+impl TransitionTrigger {
+    /// Returns the name of the trigger without its objective ID.
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            Self::Mode => "mode",
+            Self::InitTimeout => "init_timeout",
+            Self::SafeEvent => "safe_event",
+            Self::NewZo(_) => "new_zo",
+            Self::ZoRemoved(_) => "zo_removed",
+            Self::BoEvent => "bo_event",
+            Self::ImagingRecovered => "imaging_recovered",
+            Self::CoverageCatchUp => "coverage_catch_up",
+            Self::OperatorAbort => "operator_abort",
+            Self::BurnAborted => "burn_aborted",
+            Self::TasksDone => "tasks_done",
+        }
+    }
+}
+
+/// A single recorded global mode transition.
+#[derive(Debug, Clone, serde::Serialize)]
+pub(crate) struct ModeTransition {
+    /// The time of the transition.
+    t: DateTime<Utc>,
+    /// The mode that was left.
+    from: &'static str,
+    /// The mode that was entered.
+    to: &'static str,
+    /// The step in which `from` was left.
+    phase: TransitionPhase,
+    /// The event that caused the transition.
+    trigger: TransitionTrigger,
+}
+
+/// The aggregated transitions between two modes with the same phase and trigger.
+#[derive(Debug, Clone, serde::Serialize)]
+struct ModeEdge {
+    /// The mode that was left.
+    from: &'static str,
+    /// The mode that was entered.
+    to: &'static str,
+    /// The step in which `from` was left.
+    phase: TransitionPhase,
+    /// The name of the trigger.
+    trigger: &'static str,
+    /// The number of recorded transitions.
+    count: usize,
+    /// The time of the most recent transition.
+    last: DateTime<Utc>,
+}
+
+/// The key of a [`ModeEdge`]: source, target, phase and trigger name.
+type EdgeKey = (&'static str, &'static str, TransitionPhase, &'static str);
+
+/// The mutable state of the [`ModeGraph`].
+#[derive(Default)]
+struct GraphState {
+    /// The aggregated transitions since mission start.
+    edges: BTreeMap<EdgeKey, ModeEdge>,
+    /// The most recent transitions, oldest first.
+    recent: VecDeque<ModeTransition>,
+}
+
+/// Graph of the transitions of the global mode state machine.
+///
+/// Every transition is recorded with the step in which the previous mode was left and the
+/// triggering event. Transitions are aggregated into edges between modes, so that oscillations
+/// show up as heavy cycles when the graph is exported as DOT or JSON to the operator console.
+pub(crate) struct ModeGraph {
+    /// The lock-protected graph state.
+    state: Mutex<GraphState>,
+}
+
+impl ModeGraph {
+    /// The number of individual transitions kept in memory.
+    const RECENT_CAPACITY: usize = 256;
+
+    /// Creates a new, empty [`ModeGraph`].
+    fn new() -> Self { Self { state: Mutex::new(GraphState::default()) } }
+
+    /// Records a mode transition.
+    ///
+    /// # Arguments
+    /// * `from` – The mode that was left.
+    /// * `to` – The mode that is entered.
+    /// * `phase` – The step in which `from` was left.
+    /// * `trigger` – The event that caused the transition.
+    pub(crate) fn record(
+        &self,
+        from: &'static str,
+        to: &'static str,
+        phase: TransitionPhase,
+        trigger: TransitionTrigger,
+    ) {
+        let t = Utc::now();
+        let mut state = self.state.lock().unwrap();
+        let label = trigger.label();
+        state
+            .edges
+            .entry((from, to, phase, label))
+            .and_modify(|e| {
+                e.count += 1;
+                e.last = t;
+            })
+            .or_insert(ModeEdge { from, to, phase, trigger: label, count: 1, last: t });
+        if state.recent.len() == Self::RECENT_CAPACITY {
+            state.recent.pop_front();
+        }
+        state.recent.push_back(ModeTransition { t, from, to, phase, trigger });
+    }
+
+    /// Exports the aggregated graph in the DOT language, one edge per phase and trigger
+    /// labelled with its count.
+    pub(crate) fn to_dot(&self) -> String {
+        let state = self.state.lock().unwrap();
+        let mut out = String::from("digraph modes {\n    rankdir=LR;\n");
+        for e in state.edges.values() {
+            let _ = writeln!(
+                out,
+                "    \"{}\" -> \"{}\" [label=\"{}: {} ({})\"];",
+                e.from, e.to, e.phase, e.trigger, e.count
+            );
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Exports the aggregated edges and the most recent transitions as a JSON object.
+    pub(crate) fn to_json(&self) -> String {
+        let state = self.state.lock().unwrap();
+        let edges: Vec<_> = state.edges.values().collect();
+        serde_json::json!({ "edges": edges, "recent": state.recent }).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mode_graph_export() {
+        let graph = ModeGraph::new();
+        let zo = TransitionTrigger::NewZo(ImgObjectiveId::new(7));
+        graph.record("InOrbitMode", "ZOPrepMode", TransitionPhase::Exec, zo);
+        graph.record("ZOPrepMode", "InOrbitMode", TransitionPhase::Init, TransitionTrigger::Mode);
+        graph.record("InOrbitMode", "ZOPrepMode", TransitionPhase::Exec, zo);
+        graph.record("InOrbitMode", "InOrbitMode", TransitionPhase::Exit, TransitionTrigger::TasksDone);
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph modes {"));
+        assert!(dot.contains("\"InOrbitMode\" -> \"ZOPrepMode\" [label=\"exec: new_zo (2)\"];"));
+        assert!(dot.contains("\"ZOPrepMode\" -> \"InOrbitMode\" [label=\"init: mode (1)\"];"));
+        assert_eq!(dot.matches("->").count(), 3);
+
+        let json: serde_json::Value = serde_json::from_str(&graph.to_json()).unwrap();
+        assert_eq!(json["edges"].as_array().unwrap().len(), 3);
+        let recent = json["recent"].as_array().unwrap();
+        assert_eq!(recent.len(), 4);
+        assert_eq!(recent[0]["trigger"], serde_json::json!({ "kind": "new_zo", "id": 7 }));
+        assert_eq!(recent[3]["phase"], "exit");
+    }
+}
@@ -0,0 +1,135 @@
+use super::mode::ModeSnapshot;
+use crate::{error, warn};
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+/// Persistent store of the plan of the active global mode.
+///
+/// Modes whose plan would be lost on a restart, like a [`ModeSnapshot::ZOPrep`] with an exit
+/// burn that did not start yet, are written to `mode.bin` whenever they are entered. Any
+/// other mode deletes the file, so that only the plan of the last active mode is resumed.
+/// Plans are only resumed if `network.skip_reset` is set, as a reset invalidates them.
+#[derive(Debug)]
+pub(crate) struct ModeStateStore {
+    /// The file holding the persisted mode.
+    path: PathBuf,
+}
+
+impl ModeStateStore {
+    /// The name of the file holding the persisted mode.
+    const FILE_NAME: &'static str = "mode.bin";
+
+    /// Opens the store in the directory at `path`, creating the directory if necessary.
+    ///
+    /// # Arguments
+    /// * `path` – The directory holding the persisted mode.
+    pub(crate) fn open<P: AsRef<Path>>(path: P) -> Self {
+        let dir = path.as_ref();
+        if let Err(e) = std::fs::create_dir_all(dir) {
+            warn!("Failed to create mode state directory {}: {e}.", dir.display());
+        }
+        Self { path: dir.join(Self::FILE_NAME) }
+    }
+
+    /// Restores the persisted mode, if any. An invalid file is deleted.
+    pub(crate) fn restore(&self) -> Option<ModeSnapshot> {
+        let data = match std::fs::read(&self.path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == ErrorKind::NotFound => return None,
+            Err(e) => {
+                warn!("Failed to read mode state {}: {e}.", self.path.display());
+                return None;
+            }
+        };
+        match bincode::serde::decode_from_slice(&data, bincode::config::standard()) {
+            Ok((snapshot, _)) => Some(snapshot),
+            Err(e) => {
+                warn!("Discarding invalid mode state {}: {e}.", self.path.display());
+                self.clear();
+                None
+            }
+        }
+    }
+
+    /// Writes the plan of a mode to a temporary file and renames it to the file, so that a
+    /// crash mid-write keeps the previous plan.
+    ///
+    /// # Arguments
+    /// * `snapshot` – The plan to persist.
+    pub(crate) fn save(&self, snapshot: &ModeSnapshot) {
+        let tmp = self.path.with_extension("tmp");
+        let res = bincode::serde::encode_to_vec(snapshot, bincode::config::standard())
+            .map_err(|e| e.to_string())
+            .and_then(|data| std::fs::write(&tmp, data).map_err(|e| e.to_string()))
+            .and_then(|()| std::fs::rename(&tmp, &self.path).map_err(|e| e.to_string()));
+        if let Err(e) = res {
+            error!("Failed to persist state of {}: {e}.", snapshot.mode_name());
+        }
+    }
+
+    /// Deletes the persisted mode, tolerating that it is already gone.
+    pub(crate) fn clear(&self) {
+        match std::fs::remove_file(&self.path) {
+            Err(e) if e.kind() != ErrorKind::NotFound => {
+                warn!("Failed to delete mode state {}: {e}.", self.path.display());
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flight_control::orbit::{ExitBurnResult, IndexedOrbitPosition};
+    use crate::imaging::CameraAngle;
+    use crate::mode_control::{base_mode::BaseMode, mode::ZOPrepSnapshot};
+    use crate::objective::KnownImgObjective;
+    use crate::util::{ImgObjectiveId, Vec2D, ZoneRect};
+    use chrono::{TimeDelta, Utc};
+    use fixed::types::I32F32;
+
+    fn zo_prep_snapshot() -> ModeSnapshot {
+        let pos = |x: i32, y: i32| Vec2D::new(I32F32::from_num(x), I32F32::from_num(y));
+        let start_i = IndexedOrbitPosition::new(0, 1000, pos(1000, 1000));
+        let id = ImgObjectiveId::new(3);
+        let burn = ExitBurnResult::straight_fixture(start_i, I32F32::ONE, id);
+        let end = Utc::now() + TimeDelta::hours(3);
+        let zone = ZoneRect::new(4900, 900, 5100, 1100);
+        let lens = CameraAngle::Narrow;
+        let zo = KnownImgObjective::new(id, "zo".into(), Utc::now(), end, zone, lens, 1.0);
+        ModeSnapshot::ZOPrep(ZOPrepSnapshot::new(BaseMode::MappingMode, burn, zo))
+    }
+
+    fn encode(snapshot: &ModeSnapshot) -> Vec<u8> {
+        bincode::serde::encode_to_vec(snapshot, bincode::config::standard()).unwrap()
+    }
+
+    #[test]
+    fn test_mode_state_restore() {
+        let dir = std::env::temp_dir().join(format!("melvin_mode_state_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let store = ModeStateStore::open(&dir);
+        assert!(store.restore().is_none());
+
+        let snapshot = zo_prep_snapshot();
+        store.save(&snapshot);
+        let restored = store.restore().unwrap();
+        assert_eq!(restored.mode_name(), "ZOPrepMode");
+        assert_eq!(encode(&restored), encode(&snapshot));
+        assert!(!dir.join("mode.tmp").exists());
+
+        // Modes without a plan clear the store
+        store.clear();
+        assert!(store.restore().is_none());
+        store.clear();
+
+        // Invalid data is discarded
+        std::fs::write(dir.join(ModeStateStore::FILE_NAME), [0xff; 4]).unwrap();
+        assert!(store.restore().is_none());
+        assert!(!dir.join(ModeStateStore::FILE_NAME).exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,106 @@
+use crate::imaging::map_image::OffsetZonedObjectiveImage;
+use crate::util::ImgObjectiveId;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+/// The partial mosaic of a zoned objective held back for a further imaging pass.
+struct HeldMosaic {
+    /// The mosaic imaged so far, if a dedicated objective image was taken.
+    mosaic: Option<OffsetZonedObjectiveImage>,
+    /// The number of imaging passes already taken.
+    passes: u32,
+    /// The end of the objective, after which the mosaic is useless.
+    end: DateTime<Utc>,
+}
+
+/// Holds the incomplete mosaics of zoned objectives between their flyovers, so that every
+/// further imaging pass extends the best mosaic imaged so far.
+#[derive(Default)]
+pub(super) struct MosaicPasses {
+    /// The held mosaics by objective.
+    held: HashMap<ImgObjectiveId, HeldMosaic>,
+}
+
+impl MosaicPasses {
+    /// Takes the held mosaic of an objective.
+    ///
+    /// # Arguments
+    /// * `id` – The ID of the objective.
+    ///
+    /// # Returns
+    /// The mosaic imaged so far and the number of passes taken, `(None, 0)` on the first pass.
+    pub(super) fn take(
+        &mut self,
+        id: ImgObjectiveId,
+    ) -> (Option<OffsetZonedObjectiveImage>, u32) {
+        self.held.remove(&id).map_or((None, 0), |held| (held.mosaic, held.passes))
+    }
+
+    /// Holds the mosaic of an objective for its next flyover and drops all held mosaics of
+    /// objectives that already ended.
+    ///
+    /// # Arguments
+    /// * `id` – The ID of the objective.
+    /// * `mosaic` – The mosaic imaged so far.
+    /// * `passes` – The number of passes taken, including the current one.
+    /// * `end` – The end of the objective.
+    pub(super) fn hold(
+        &mut self,
+        id: ImgObjectiveId,
+        mosaic: Option<OffsetZonedObjectiveImage>,
+        passes: u32,
+        end: DateTime<Utc>,
+    ) {
+        let now = Utc::now();
+        self.held.retain(|_, held| held.end > now);
+        self.held.insert(id, HeldMosaic { mosaic, passes, end });
+    }
+
+    /// Returns `true` if a further pass of an incomplete mosaic is worth waiting for.
+    ///
+    /// # Arguments
+    /// * `passes` – The number of passes taken, including the current one.
+    /// * `max_extra_passes` – The maximum number of passes after the first one.
+    /// * `next_flyover` – The earliest time of the next flyover over the zone.
+    /// * `end` – The end of the objective.
+    pub(super) fn retry_on_next_flyover(
+        passes: u32,
+        max_extra_passes: u32,
+        next_flyover: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> bool {
+        passes <= max_extra_passes && next_flyover < end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::Vec2D;
+    use chrono::TimeDelta;
+
+    #[test]
+    fn test_mosaic_passes() {
+        let now = Utc::now();
+        let (ended, held, other) =
+            (ImgObjectiveId::new(3), ImgObjectiveId::new(7), ImgObjectiveId::new(9));
+        let mut passes = MosaicPasses::default();
+        assert!(matches!(passes.take(held), (None, 0)));
+
+        let mosaic = OffsetZonedObjectiveImage::new(Vec2D::new(0, 0), Vec2D::new(4, 4));
+        passes.hold(ended, None, 2, now - TimeDelta::minutes(1));
+        passes.hold(held, Some(mosaic), 1, now + TimeDelta::hours(1));
+        // Held mosaics of ended objectives are dropped on the next hold.
+        passes.hold(other, None, 1, now + TimeDelta::hours(1));
+        assert!(matches!(passes.take(ended), (None, 0)));
+        assert!(matches!(passes.take(held), (Some(_), 1)));
+        assert!(matches!(passes.take(held), (None, 0)));
+
+        let end = now + TimeDelta::hours(3);
+        assert!(MosaicPasses::retry_on_next_flyover(1, 2, now + TimeDelta::hours(2), end));
+        assert!(MosaicPasses::retry_on_next_flyover(2, 2, now + TimeDelta::hours(2), end));
+        assert!(!MosaicPasses::retry_on_next_flyover(3, 2, now + TimeDelta::hours(2), end));
+        assert!(!MosaicPasses::retry_on_next_flyover(1, 2, now + TimeDelta::hours(4), end));
+        assert!(!MosaicPasses::retry_on_next_flyover(1, 0, now + TimeDelta::hours(2), end));
+    }
+}
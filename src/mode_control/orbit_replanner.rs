@@ -0,0 +1,91 @@
+use super::{ModeContext, coverage_guard::CoverageGuard, mode::InOrbitMode};
+use crate::flight_control::{
+    RecoveryAction,
+    orbit::{ClosedOrbit, OrbitBase},
+};
+use crate::imaging::CameraAngle;
+use crate::util::{EVENT_BUS, JournalEvent, MISSION_JOURNAL, MissionConfig, SafetyEvent, Vec2D};
+use crate::{info, log, warn};
+use fixed::types::I32F32;
+use std::sync::Arc;
+
+/// The maximum distance in pixels between matching positions of the old and the replanned
+/// orbit for the coverage to be carried over.
+const CARRY_MAX_DIST_PX: I32F32 = I32F32::lit("150");
+
+/// Replans the closed orbit whenever the supervisor reports a persistent deviation from it.
+///
+/// A new [`ClosedOrbit`] is computed from MELVINs current state with the lens of the active
+/// orbit profile and swapped in, carrying the coverage of the old orbit over where the ground
+/// tracks still match. Afterward, the orbit characteristics and the [`CoverageGuard`] are reset
+/// and the active [`InOrbitMode`] is reinitialized, so that the schedule is planned on the new
+/// orbit. Deviations reported while another mode is active, e.g. during objective retrieval, or
+/// while a watchdog recovery is pending are ignored.
+///
+/// # Arguments
+/// * `context` – The shared [`ModeContext`].
+pub(crate) async fn run_orbit_replanner(context: Arc<ModeContext>) {
+    let mut safety_rx = EVENT_BUS.subscribe::<SafetyEvent>();
+    while let Some(event) = safety_rx.recv().await {
+        let SafetyEvent::OrbitDeviation(dev) = event else { continue };
+        let mode = context.active_mode();
+        if mode != InOrbitMode::MODE_NAME {
+            log!("Ignoring orbit deviation of {dev}px during {mode}.");
+            continue;
+        }
+        let f_cont_lock = context.k().f_cont();
+        let (base, pos) = {
+            let f_cont = f_cont_lock.read().await;
+            if f_cont.recovery_pending() {
+                log!("Ignoring orbit deviation of {dev}px during a pending recovery.");
+                continue;
+            }
+            (OrbitBase::new(&f_cont), f_cont.current_pos())
+        };
+        let orbit_vel = *context.k().c_orbit().read().await.base_orbit_ref().vel();
+        let config = MissionConfig::get();
+        let lens = config.orbit.active_profile(orbit_vel).map_or(CameraAngle::Wide, |p| p.lens);
+        let new_orbit = match ClosedOrbit::new(base, lens) {
+            Ok(orbit) => orbit,
+            Err(e) => {
+                warn!("Orbit not replanned after {dev}px deviation: {e:?}");
+                continue;
+            }
+        };
+        replace_orbit(&context, new_orbit, pos).await;
+        let mut f_cont = f_cont_lock.write().await;
+        // A recovery requested meanwhile re-initializes the mode as well, keep its action.
+        if !f_cont.recovery_pending() {
+            f_cont.request_recovery(RecoveryAction::ReInit);
+        }
+    }
+}
+
+/// Swaps a new closed orbit starting at the current position in.
+///
+/// The coverage of the old orbit is carried over where the ground tracks still match, and the
+/// orbit characteristics and the [`CoverageGuard`] are reset to the new orbit.
+///
+/// # Arguments
+/// * `context` – The shared [`ModeContext`].
+/// * `new_orbit` – The new closed orbit.
+/// * `pos` – The current position, the first position of the new orbit.
+pub(crate) async fn replace_orbit(
+    context: &ModeContext,
+    new_orbit: ClosedOrbit,
+    pos: Vec2D<I32F32>,
+) {
+    let c_orbit_lock = context.k().c_orbit();
+    let (period, carried) = {
+        let mut c_orbit = c_orbit_lock.write().await;
+        let mut replanned = new_orbit;
+        let carried = replanned.carry_coverage_from(&c_orbit, CARRY_MAX_DIST_PX);
+        *c_orbit = replanned;
+        c_orbit.try_export_default();
+        context.o_ch_modify(|o_ch| o_ch.replan(&c_orbit, pos));
+        (c_orbit.period().0.to_num::<usize>(), carried)
+    };
+    *context.coverage().lock().await = CoverageGuard::new(period);
+    info!("Replanned closed orbit with period {period}s, carried {carried}s of coverage.");
+    MISSION_JOURNAL.record(JournalEvent::OrbitReplanned { period, carried });
+}
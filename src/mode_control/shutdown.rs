@@ -0,0 +1,53 @@
+use super::ModeContext;
+use crate::util::{JournalEvent, MISSION_JOURNAL};
+use crate::{error, info, warn};
+use std::sync::Arc;
+use tokio::signal::unix::{SignalKind, signal};
+
+/// Waits until the process receives `SIGTERM` or `SIGINT`.
+///
+/// The signal handlers are installed on the first poll, replacing the default handlers that
+/// terminate the process immediately, so this should be spawned as early as possible.
+///
+/// # Returns
+/// The name of the received signal.
+pub(crate) async fn wait_for_shutdown() -> &'static str {
+    let (Ok(mut term), Ok(mut int)) =
+        (signal(SignalKind::terminate()), signal(SignalKind::interrupt()))
+    else {
+        error!("Failed installing shutdown signal handlers, shutdown will not be graceful!");
+        return std::future::pending().await;
+    };
+    tokio::select! {
+        _ = term.recv() => "SIGTERM",
+        _ = int.recv() => "SIGINT",
+    }
+}
+
+/// Persists all mission state that would otherwise be lost or corrupted by a restart.
+///
+/// Must only be called after the main mode loop was stopped, so that no new tasks are
+/// executed. Flushes the memory-mapped fullsize map, exports the coverage of the closed orbit
+/// and dumps the pending task schedule, the active beacon objectives and the score ledger.
+///
+/// # Arguments
+/// * `context` – The shared mode context.
+/// * `signal` – The name of the signal that triggered the shutdown.
+pub(crate) async fn shutdown(context: &Arc<ModeContext>, signal: &'static str) {
+    warn!("Received {signal}, shutting down gracefully!");
+    MISSION_JOURNAL.record(JournalEvent::Shutdown { signal });
+    let k = context.k();
+    match k.c_cont().flush_map().await {
+        Ok(()) => info!("Shutdown: fullsize map flushed."),
+        Err(e) => error!("Shutdown: failed flushing fullsize map: {e}"),
+    }
+    if context.checkpointer().flush().await {
+        info!("Shutdown: orbit coverage exported.");
+    }
+    let tasks = k.t_cont().dump_schedule().await;
+    let beacons = context.beac_cont().dump_active().await;
+    if let Err(e) = k.score().dump().await {
+        error!("Shutdown: failed dumping score ledger: {e}");
+    }
+    info!("Shutdown: persisted {tasks} pending tasks and {beacons} active beacon objectives.");
+}
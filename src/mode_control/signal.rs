@@ -1,6 +1,8 @@
 use chrono::{DateTime, Utc};
 use tokio::task::JoinHandle;
+use crate::flight_control::BurnAborted;
 use crate::objective::KnownImgObjective;
+use crate::util::ImgObjectiveId;
 use super::mode::GlobalMode;
 
 pub(super) enum TaskEndSignal {
@@ -23,13 +25,19 @@ pub(crate) enum ExecExitSignal {
     Continue,
     SafeEvent,
     NewZOEvent(KnownImgObjective),
+    BurnAborted(BurnAborted),
 }
 
 pub(crate) enum WaitExitSignal {
     Continue,
     SafeEvent,
     NewZOEvent(KnownImgObjective),
+    ZORemovedEvent(ImgObjectiveId),
     BOEvent,
+    ImagingRecovered,
+    CoverageCatchUp,
+    OperatorAbort,
+    Preempted,
 }
 
 pub(super) type OptOpExitSignal = Option<OpExitSignal>;
@@ -1,14 +1,15 @@
-use crate::util::Vec2D;
-use super::BeaconMeas;
+use crate::util::{MapSize, Vec2D};
+use super::{BeaconMeas, DistanceModel};
+use bitvec::{bitbox, order::Lsb0, prelude::BitBox};
 use fixed::types::I32F32;
 use kiddo::{ImmutableKdTree, SquaredEuclidean};
 use num::traits::FloatConst;
 use std::collections::{HashMap, HashSet};
 use std::num::NonZero;
-use crate::fatal;
+use crate::{info, warn};
 
 /// A structure representing a square-shaped slice of a 2D map.
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct SquareSlice {
     /// The offset of the square slice on the map.
     offset: Vec2D<I32F32>,
@@ -157,12 +158,68 @@ impl SquareSlice {
     }
 }
 
+/// Summary statistics of the posterior distribution of a beacon position.
+///
+/// All coordinates of the credible region are equally likely, so the statistics describe the
+/// shape of the region, with coordinates unwrapped across the map seam.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PosteriorStats {
+    /// The wrapped center of mass of the credible region.
+    centroid: Vec2D<I32F32>,
+    /// The covariance matrix `[[xx, xy], [xy, yy]]` of the credible region in px².
+    covariance: [[f64; 2]; 2],
+    /// Twice the standard deviation along the major axis of the region in px.
+    error_radius: f64,
+    /// The number of coordinates in the credible region.
+    region_size: usize,
+    /// The best guess cells as `(center, share of the region covered)`, in descending order.
+    candidates: Vec<(Vec2D<I32F32>, f64)>,
+}
+
+impl PosteriorStats {
+    /// Returns the wrapped center of mass of the credible region.
+    pub fn centroid(&self) -> Vec2D<I32F32> { self.centroid }
+    /// Returns the covariance matrix of the credible region.
+    pub fn covariance(&self) -> [[f64; 2]; 2] { self.covariance }
+    /// Returns twice the standard deviation along the major axis of the region.
+    pub fn error_radius(&self) -> f64 { self.error_radius }
+    /// Returns the number of coordinates in the credible region.
+    pub fn region_size(&self) -> usize { self.region_size }
+    /// Returns the best guess cells together with the share of the region they cover.
+    pub fn candidates(&self) -> &[(Vec2D<I32F32>, f64)] { &self.candidates }
+}
+
+/// The persisted state of a [`BayesianSet`], including its coordinate grid.
+///
+/// The coordinates are stored as a bitmap over their bounding box, unwrapped relative to the
+/// slice offset, which keeps the state compact even while the set is still a wide ring.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub(crate) struct BayesianSetSnapshot {
+    /// The square slice of the map containing the set.
+    curr_slice: SquareSlice,
+    /// The width of the coordinate bitmap.
+    grid_width: u32,
+    /// The coordinate bitmap, row by row.
+    grid: BitBox<u64, Lsb0>,
+    /// The accepted beacon measurements.
+    measurements: Vec<BeaconMeas>,
+    /// The beacon measurements rejected as outliers.
+    rejected: Vec<BeaconMeas>,
+    /// The distance model applied to all measurements.
+    model: DistanceModel,
+}
+
 #[derive(Debug, Clone, serde::Serialize)]
 /// Represents a discrete binary Bayesian set used for probabilistic mapping and spatial estimation.
 ///
 /// Maintains a collection of coordinates (`set`) within a certain region (`curr_slice`) 
 /// that satisfy constraints derived from beacon measurements. Utilizes these measurements to 
 /// estimate positions and optimize spatial packing of regions.
+///
+/// Measurements contradicting all coordinates of the set are rejected as outliers. Every
+/// rejected measurement seeds a competing RANSAC-style hypothesis, which replaces the set once
+/// it is consistent with more measurements, so that an outlier accepted early can not poison
+/// the set for good.
 pub struct BayesianSet {
     /// The current set of coordinates that satisfy constraints.
     #[serde(skip)]
@@ -171,19 +228,23 @@ pub struct BayesianSet {
     curr_slice: SquareSlice,
     /// A collection of beacon measurements contributing to the set's constraints.
     measurements: Vec<BeaconMeas>,
+    /// The beacon measurements rejected as outliers.
+    rejected: Vec<BeaconMeas>,
+    /// The distance model applied to all measurements of this set.
+    model: DistanceModel,
 }
 
 impl BayesianSet {
     /// Maximum scale factor for noise calculations.
-    const K_FAC_MAX: I32F32 = I32F32::lit("0.9");
+    pub const K_FAC_MAX: I32F32 = I32F32::lit("0.9");
     /// Minimum scale factor for noise calculations.
-    const K_FAC_MIN: I32F32 = I32F32::lit("1.1");
+    pub const K_FAC_MIN: I32F32 = I32F32::lit("1.1");
     /// Safety buffer for standard deviation in distance calculations.
     pub const STD_DIST_SAFETY: I32F32 = I32F32::lit("5");
     /// Maximum allowable distance for measurements.
     pub const MAX_DIST: I32F32 = I32F32::lit("2000");
     /// Minimum allowable distance for measurements.
-    const MIN_DIST: I32F32 = I32F32::lit("0");
+    pub const MIN_DIST: I32F32 = I32F32::lit("0");
     /// Constant noise offset.
     pub const K_ADD: I32F32 = I32F32::lit("225.1");
     /// Maximum resolution for uncertainty radius, used in hexagonal packing.
@@ -191,54 +252,218 @@ impl BayesianSet {
     /// Maximum number of items to retrieve during a nearest neighbor search.
     const MAX_ITEMS: NonZero<usize> = unsafe { NonZero::new_unchecked(6) };
 
-    /// Computes the minimum and maximum distances for a given noisy distance value.
+    /// Creates a new [`BayesianSet`] from an initial beacon measurement using the a-priori
+    /// [`DistanceModel`].
     ///
     /// # Arguments
-    /// * `d_noisy` - The noisy distance value (`I32F32`).
+    /// * `meas` - The initial beacon measurement.
     ///
     /// # Returns
-    /// A tuple containing the minimum and maximum distances.
-    fn get_dists(d_noisy: I32F32) -> (I32F32, I32F32) {
-        let min_dist = ((d_noisy - Self::K_ADD) / Self::K_FAC_MIN) - Self::STD_DIST_SAFETY;
-        let max_dist = ((d_noisy + Self::K_ADD) / Self::K_FAC_MAX) + Self::STD_DIST_SAFETY;
-        (
-            min_dist.max(Self::MIN_DIST).floor(),
-            max_dist.min(Self::MAX_DIST).ceil(),
-        )
-    }
+    /// A new `BayesianSet` instance.
+    pub fn new(meas: BeaconMeas) -> Self { Self::with_model(meas, DistanceModel::default()) }
 
     /// Creates a new [`BayesianSet`] from an initial beacon measurement.
     ///
     /// # Arguments
     /// * `meas` - The initial beacon measurement.
+    /// * `model` - The distance model applied to all measurements of this set.
     ///
     /// # Returns
     /// A new `BayesianSet` instance.
-    pub fn new(meas: BeaconMeas) -> Self {
-        let (min_dist, max_dist) = Self::get_dists(I32F32::from_num(meas.rssi()));
+    pub fn with_model(meas: BeaconMeas, model: DistanceModel) -> Self {
+        let (min_dist, max_dist) = model.meas_dists(&meas);
         let side_len = I32F32::from_num(max_dist);
         let pos = meas.corr_pos();
         let slice = SquareSlice::new(pos, Vec2D::new(side_len, side_len));
         let set = slice.get_coord_set(pos, min_dist, max_dist);
-        Self { set, curr_slice: slice, measurements: vec![meas] }
+        Self { set, curr_slice: slice, measurements: vec![meas], rejected: vec![], model }
     }
 
     /// Updates the current Bayesian set based on a new beacon measurement.
     ///
+    /// A measurement inconsistent with every coordinate of the set is rejected as outlier,
+    /// after which it seeds a competing consensus hypothesis.
+    ///
     /// # Arguments
     /// * `meas` - The new beacon measurement to incorporate.
     pub fn update(&mut self, meas: &BeaconMeas) {
-        let (min_dist, max_dist) = Self::get_dists(I32F32::from_num(meas.rssi()));
+        if self.constrain(meas) {
+            return;
+        }
+        warn!("Rejecting beacon {} measurement at {} as outlier.", meas.id(), meas.pos());
+        self.rejected.push(meas.clone());
+        self.try_consensus(meas);
+    }
+
+    /// Narrows the set to the coordinates consistent with a measurement.
+    ///
+    /// # Arguments
+    /// * `meas` - The beacon measurement to incorporate.
+    ///
+    /// # Returns
+    /// `true` if the measurement was incorporated, `false` if no coordinate of the set is
+    /// consistent with it, in which case the set is left unchanged.
+    fn constrain(&mut self, meas: &BeaconMeas) -> bool {
+        let (min_dist, max_dist) = self.model.meas_dists(meas);
         let pos = meas.corr_pos();
-        let slice = self
-            .curr_slice
-            .intersect(&SquareSlice::new(pos, Vec2D::new(max_dist, max_dist)))
-            .unwrap_or_else(|| fatal!("No possible intersection found!"));
-        let new_set = slice.get_coord_set(pos, min_dist, max_dist);
-        self.set = self.set.intersection(&new_set).copied().collect();
+        let Some(slice) =
+            self.curr_slice.intersect(&SquareSlice::new(pos, Vec2D::new(max_dist, max_dist)))
+        else {
+            return false;
+        };
+        let map = i32::map_size();
+        let (x, y) = (pos.x().to_num::<f64>(), pos.y().to_num::<f64>());
+        let min_sq = min_dist.to_num::<f64>().powi(2);
+        let max_sq = max_dist.to_num::<f64>().powi(2);
+        let new_set: HashSet<_> = self
+            .set
+            .iter()
+            .filter(|p| {
+                let dx = Self::wrapped_delta(f64::from(p.x()) - x, f64::from(map.x()));
+                let dy = Self::wrapped_delta(f64::from(p.y()) - y, f64::from(map.y()));
+                (min_sq..=max_sq).contains(&(dx * dx + dy * dy))
+            })
+            .copied()
+            .collect();
+        if new_set.is_empty() {
+            return false;
+        }
+        self.set = new_set;
         self.curr_slice = slice;
+        self.measurements.push(meas.clone());
+        true
+    }
+
+    /// Returns the shortest signed distance equivalent to `delta` on a wrapping axis.
+    ///
+    /// # Arguments
+    /// * `delta` - The unwrapped distance.
+    /// * `len` - The length of the axis.
+    fn wrapped_delta(delta: f64, len: f64) -> f64 {
+        let wrapped = delta.rem_euclid(len);
+        if wrapped > len / 2.0 { wrapped - len } else { wrapped }
+    }
+
+    /// Replaces the set by the consensus hypothesis seeded with a rejected measurement if it
+    /// is consistent with more measurements than the set.
+    ///
+    /// The hypothesis greedily incorporates the other rejected measurements, most recent first,
+    /// followed by the accepted ones. It is only built if enough measurements have a distance
+    /// ring intersecting the one of the seed for it to possibly outnumber the set.
+    ///
+    /// # Arguments
+    /// * `seed` - The rejected measurement seeding the hypothesis.
+    fn try_consensus(&mut self, seed: &BeaconMeas) {
+        let (rejected, accepted) = (&self.rejected[..self.rejected.len() - 1], &self.measurements);
+        let candidates =
+            rejected.iter().chain(accepted).filter(|m| self.rings_intersect(seed, m)).count();
+        if candidates < self.measurements.len() {
+            return;
+        }
+        let mut hypothesis = Self::with_model(seed.clone(), self.model);
+        for meas in rejected.iter().rev().chain(accepted) {
+            if !hypothesis.constrain(meas) {
+                hypothesis.rejected.push(meas.clone());
+            }
+        }
+        if hypothesis.measurements.len() > self.measurements.len() {
+            info!(
+                "Rebuilt beacon {} set from {} consistent measurements, {} rejected.",
+                seed.id(),
+                hypothesis.measurements.len(),
+                hypothesis.rejected.len()
+            );
+            *self = hypothesis;
+        }
+    }
+
+    /// Returns `true` if the distance rings of two measurements intersect, which is necessary
+    /// for both to be consistent with a common coordinate.
+    ///
+    /// # Arguments
+    /// * `a` - The first beacon measurement.
+    /// * `b` - The second beacon measurement.
+    fn rings_intersect(&self, a: &BeaconMeas, b: &BeaconMeas) -> bool {
+        let to_f64 = |(min, max): (I32F32, I32F32)| (min.to_num::<f64>(), max.to_num::<f64>());
+        let ((min_a, max_a), (min_b, max_b)) =
+            (to_f64(self.model.meas_dists(a)), to_f64(self.model.meas_dists(b)));
+        let (pos_a, pos_b) = (a.corr_pos(), b.corr_pos());
+        let map = i32::map_size();
+        let dx = Self::wrapped_delta((pos_a.x() - pos_b.x()).to_num::<f64>(), f64::from(map.x()));
+        let dy = Self::wrapped_delta((pos_a.y() - pos_b.y()).to_num::<f64>(), f64::from(map.y()));
+        // One pixel of slack, as the set only holds whole coordinates
+        let dist = dx.hypot(dy);
+        dist <= max_a + max_b + 1.0 && dist + 1.0 >= min_a - max_b && dist + 1.0 >= min_b - max_a
+    }
+
+    /// Captures the complete state of the set, including its coordinates.
+    #[allow(clippy::cast_sign_loss)]
+    pub(crate) fn snapshot(&self) -> BayesianSetSnapshot {
+        let rel: Vec<_> = self.set.iter().map(|p| self.rel_to_slice(*p)).collect();
+        let grid_width = rel.iter().map(|p| p.x() + 1).max().unwrap_or(0);
+        let grid_height = rel.iter().map(|p| p.y() + 1).max().unwrap_or(0);
+        let mut grid = bitbox![u64, Lsb0; 0; (grid_width * grid_height) as usize];
+        for p in rel {
+            grid.set((p.y() * grid_width + p.x()) as usize, true);
+        }
+        BayesianSetSnapshot {
+            curr_slice: self.curr_slice.clone(),
+            grid_width: grid_width as u32,
+            grid,
+            measurements: self.measurements.clone(),
+            rejected: self.rejected.clone(),
+            model: self.model,
+        }
+    }
+
+    /// Restores a set from a snapshot taken by [`BayesianSet::snapshot`].
+    ///
+    /// # Arguments
+    /// * `snapshot` - The persisted state of the set.
+    ///
+    /// # Returns
+    /// The restored set, or `None` if the snapshot holds no measurement.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    pub(crate) fn from_snapshot(snapshot: BayesianSetSnapshot) -> Option<Self> {
+        if snapshot.measurements.is_empty() {
+            return None;
+        }
+        let origin = snapshot.curr_slice.offset.round().to_num::<i32>();
+        let width = snapshot.grid_width.max(1) as usize;
+        let set = snapshot
+            .grid
+            .iter_ones()
+            .map(|i| {
+                let (x, y) = ((i % width) as i32, (i / width) as i32);
+                Vec2D::new(origin.x() + x, origin.y() + y).wrap_around_map()
+            })
+            .collect();
+        Some(Self {
+            set,
+            curr_slice: snapshot.curr_slice,
+            measurements: snapshot.measurements,
+            rejected: snapshot.rejected,
+            model: snapshot.model,
+        })
     }
 
+    /// Returns the offset of a coordinate of the set from the slice offset, unwrapped across
+    /// the map seam.
+    fn rel_to_slice(&self, p: Vec2D<i32>) -> Vec2D<i32> {
+        let map = i32::map_size();
+        let origin = self.curr_slice.offset.round().to_num::<i32>();
+        Vec2D::new(
+            Vec2D::wrap_coordinate(p.x() - origin.x(), map.x()),
+            Vec2D::wrap_coordinate(p.y() - origin.y(), map.y()),
+        )
+    }
+
+    /// Returns all beacon measurements contributing to the set's constraints.
+    pub fn measurements(&self) -> &[BeaconMeas] { &self.measurements }
+
+    /// Returns the beacon measurements rejected as outliers.
+    pub fn rejected(&self) -> &[BeaconMeas] { &self.rejected }
+
     /// Checks if a given position is part of the current set.
     ///
     /// # Arguments
@@ -248,6 +473,36 @@ impl BayesianSet {
     /// `true` if the position is in the set, otherwise `false`.
     pub fn is_in_set(&self, pos: Vec2D<i32>) -> bool { self.set.contains(&pos) }
 
+    /// Returns the number of coordinates in the current set.
+    pub fn len(&self) -> usize { self.set.len() }
+
+    /// Returns `true` if no coordinate satisfies all constraints.
+    pub fn is_empty(&self) -> bool { self.set.is_empty() }
+
+    /// Returns the offset and the side length of the slice containing the set.
+    pub fn bounds(&self) -> (Vec2D<I32F32>, Vec2D<I32F32>) {
+        (self.curr_slice.offset, self.curr_slice.side_length)
+    }
+
+    /// Counts the measurements whose distance range contains a given position.
+    ///
+    /// # Arguments
+    /// * `pos` - The position to check.
+    ///
+    /// # Returns
+    /// The number of consistent measurements.
+    pub fn consistent_meas(&self, pos: Vec2D<i32>) -> usize {
+        let fix_pos = Vec2D::from_real(&pos);
+        self.measurements
+            .iter()
+            .filter(|meas| {
+                let (min_dist, max_dist) = self.model.meas_dists(meas);
+                let dist = meas.corr_pos().unwrapped_to(&fix_pos).abs();
+                min_dist <= dist && dist <= max_dist
+            })
+            .count()
+    }
+
     /// Estimates the number of 75px guesses required to cover the current coordinate set.
     ///
     /// # Returns
@@ -259,6 +514,101 @@ impl BayesianSet {
         (len as f32 / max_one_guess_area).ceil() as usize
     }
 
+    /// Returns the centroid of the credible region, i.e. the member closest to its center of
+    /// mass.
+    ///
+    /// Coordinates are unwrapped relative to the slice offset first, so that regions crossing
+    /// the map seam are averaged correctly. Snapping to the closest member keeps the guess
+    /// inside the region even if it is ring-shaped.
+    ///
+    /// # Returns
+    /// The wrapped centroid, or `None` if the set is empty.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    pub fn credible_centroid(&self) -> Option<Vec2D<I32F32>> {
+        let map = i32::map_size();
+        let origin = self.curr_slice.offset.round().to_num::<i32>();
+        let rel = |p: &Vec2D<i32>| {
+            Vec2D::new(
+                i64::from(Vec2D::wrap_coordinate(p.x() - origin.x(), map.x())),
+                i64::from(Vec2D::wrap_coordinate(p.y() - origin.y(), map.y())),
+            )
+        };
+        let n = i64::try_from(self.set.len()).ok().filter(|n| *n > 0)?;
+        let sum = self.set.iter().map(rel).fold(Vec2D::new(0, 0), |acc, p| acc + p);
+        let mean = Vec2D::new(sum.x() / n, sum.y() / n);
+        let closest = self.set.iter().min_by_key(|p| {
+            let (dx, dy) = (rel(p).x() - mean.x(), rel(p).y() - mean.y());
+            dx * dx + dy * dy
+        })?;
+        Some(Vec2D::from_real(closest))
+    }
+
+    /// Computes the [`PosteriorStats`] of the credible region.
+    ///
+    /// The error radius is twice the standard deviation along the major axis of the
+    /// covariance, which equals the radius for a disc-shaped region. Candidates are the cells
+    /// of the guess packing grid ranked by the share of the region within their guess radius.
+    ///
+    /// # Arguments
+    /// * `top_k` - The maximum number of candidate cells, `0` skips the candidate ranking.
+    ///
+    /// # Returns
+    /// The posterior statistics, or `None` if the set is empty.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn posterior(&self, top_k: usize) -> Option<PosteriorStats> {
+        let n = self.set.len();
+        if n == 0 {
+            return None;
+        }
+        let origin = self.curr_slice.offset;
+        let rel: Vec<(f64, f64)> = self
+            .set
+            .iter()
+            .map(|p| {
+                let d = origin.unwrapped_to(&Vec2D::from_real(p));
+                (d.x().to_num::<f64>(), d.y().to_num::<f64>())
+            })
+            .collect();
+        let n_f = n as f64;
+        let (sx, sy) = rel.iter().fold((0.0, 0.0), |(ax, ay), (x, y)| (ax + x, ay + y));
+        let (mx, my) = (sx / n_f, sy / n_f);
+        let (mut sxx, mut sxy, mut syy) = (0.0, 0.0, 0.0);
+        for (x, y) in &rel {
+            let (dx, dy) = (x - mx, y - my);
+            sxx += dx * dx;
+            sxy += dx * dy;
+            syy += dy * dy;
+        }
+        let (xx, xy, yy) = (sxx / n_f, sxy / n_f, syy / n_f);
+        let lambda_max = f64::midpoint(xx, yy) + (((xx - yy) / 2.0).powi(2) + xy * xy).sqrt();
+        let centroid = (origin + Vec2D::new(I32F32::from_num(mx), I32F32::from_num(my)))
+            .wrap_around_map()
+            .round();
+
+        let mut candidates = vec![];
+        if top_k > 0 {
+            let (h_c_tree, h_c) = self.curr_slice.generate_hex_grid();
+            let mut cells: Vec<_> =
+                self.assign_points_to_hexes(&h_c_tree, &h_c).into_iter().collect();
+            cells.sort_by_key(|(c, covered)| (std::cmp::Reverse(covered.len()), c.x(), c.y()));
+            candidates = cells
+                .into_iter()
+                .take(top_k)
+                .map(|(c, covered)| {
+                    let center = (origin + Vec2D::from_real(&c)).wrap_around_map().round();
+                    (center, covered.len() as f64 / n_f)
+                })
+                .collect();
+        }
+        Some(PosteriorStats {
+            centroid,
+            covariance: [[xx, xy], [xy, yy]],
+            error_radius: 2.0 * lambda_max.max(0.0).sqrt(),
+            region_size: n,
+            candidates,
+        })
+    }
+
     /// Packs the set's coordinates into circular regions with minimal overlap.
     ///
     /// # Returns
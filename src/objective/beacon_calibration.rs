@@ -0,0 +1,185 @@
+use super::{BayesianSet, BeaconMeas, MeasConfidence};
+use crate::obj;
+use crate::util::{BeaconObjectiveId, Vec2D, logger::JsonDump};
+use fixed::types::I32F32;
+
+/// The model mapping a noisy ping distance to the range of possible true distances.
+///
+/// A noisy distance `d_noisy` constrains the true distance `d` to
+/// `(d_noisy - add) / fac_min <= d <= (d_noisy + add) / fac_max`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct DistanceModel {
+    /// Constant noise offset.
+    add: I32F32,
+    /// Scale factor applied for the minimum distance.
+    fac_min: I32F32,
+    /// Scale factor applied for the maximum distance.
+    fac_max: I32F32,
+}
+
+impl Default for DistanceModel {
+    /// Returns the a-priori model derived from the backends documented noise.
+    fn default() -> Self {
+        Self {
+            add: BayesianSet::K_ADD,
+            fac_min: BayesianSet::K_FAC_MIN,
+            fac_max: BayesianSet::K_FAC_MAX,
+        }
+    }
+}
+
+impl DistanceModel {
+    /// Additional safety buffer for measurements with degraded confidence.
+    const DEGRADED_DIST_SAFETY: I32F32 = I32F32::lit("60");
+
+    /// Returns the constant noise offset of the model.
+    pub fn k_add(&self) -> I32F32 { self.add }
+
+    /// Computes the minimum and maximum true distances for a given noisy distance value.
+    ///
+    /// # Arguments
+    /// * `d_noisy` - The noisy distance value (`I32F32`).
+    ///
+    /// # Returns
+    /// A tuple containing the minimum and maximum distances.
+    pub fn dists(&self, d_noisy: I32F32) -> (I32F32, I32F32) {
+        let min_dist = ((d_noisy - self.add) / self.fac_min) - BayesianSet::STD_DIST_SAFETY;
+        let max_dist = ((d_noisy + self.add) / self.fac_max) + BayesianSet::STD_DIST_SAFETY;
+        (
+            min_dist.max(BayesianSet::MIN_DIST).floor(),
+            max_dist.min(BayesianSet::MAX_DIST).ceil(),
+        )
+    }
+
+    /// Computes the minimum and maximum true distances for a beacon measurement.
+    ///
+    /// Measurements with [`MeasConfidence::Degraded`] get their range widened by
+    /// `DEGRADED_DIST_SAFETY`, so that they only loosely constrain the estimate.
+    ///
+    /// # Arguments
+    /// * `meas` - The beacon measurement.
+    ///
+    /// # Returns
+    /// A tuple containing the minimum and maximum distances.
+    pub fn meas_dists(&self, meas: &BeaconMeas) -> (I32F32, I32F32) {
+        let (min_dist, max_dist) = self.dists(I32F32::from_num(meas.rssi()));
+        match meas.confidence() {
+            MeasConfidence::Full => (min_dist, max_dist),
+            MeasConfidence::Degraded => (
+                (min_dist - Self::DEGRADED_DIST_SAFETY).max(BayesianSet::MIN_DIST),
+                (max_dist + Self::DEGRADED_DIST_SAFETY).min(BayesianSet::MAX_DIST),
+            ),
+        }
+    }
+
+    /// Computes the smallest noise offset for which a noisy distance is consistent with any
+    /// true distance within `d_uncertainty` of `d_true`.
+    ///
+    /// # Arguments
+    /// * `d_noisy` - The noisy distance value.
+    /// * `d_true` - The (approximate) true distance.
+    /// * `d_uncertainty` - The maximum error of `d_true`.
+    fn required_k_add(&self, d_noisy: I32F32, d_true: I32F32, d_uncertainty: I32F32) -> I32F32 {
+        let d_min = (d_true - d_uncertainty).max(I32F32::ZERO);
+        let d_max = d_true + d_uncertainty;
+        (d_noisy - self.fac_min * d_min).max(self.fac_max * d_max - d_noisy)
+    }
+}
+
+/// A calibration sample: a noisy distance and the distance to a known beacon position.
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+struct CalibrationSample {
+    /// The objective ID the sample belongs to.
+    id: BeaconObjectiveId,
+    /// The noisy distance reported by the ping.
+    d_noisy: I32F32,
+    /// The distance between the corrected measurement position and the known beacon position.
+    d_true: I32F32,
+}
+
+/// Back-fits the [`DistanceModel`] from beacon objectives whose true position became known.
+///
+/// Each found beacon reveals its position up to the guess radius. The recorded measurements
+/// of such a beacon are compared to this checkpoint, and once enough samples are collected,
+/// the noise offset is refitted to the observed envelope plus a safety margin. The refined
+/// model is applied to all beacons that are announced afterward.
+#[derive(Debug, serde::Serialize)]
+pub struct BeaconCalibration {
+    /// All collected calibration samples.
+    samples: Vec<CalibrationSample>,
+    /// The currently applied distance model.
+    model: DistanceModel,
+}
+
+impl JsonDump for BeaconCalibration {
+    /// Returns the file name for the JSON dump of the calibration.
+    fn file_name(&self) -> String { "beacon_calibration".to_string() }
+
+    /// Returns the directory name for the calibration JSON file.
+    fn dir_name(&self) -> &'static str { "beacon_objectives" }
+}
+
+impl BeaconCalibration {
+    /// Minimum number of samples needed before the model is refitted.
+    const MIN_SAMPLES: usize = 5;
+    /// Relative safety margin applied to the fitted noise offset.
+    const MARGIN: I32F32 = I32F32::lit("1.1");
+    /// Lower bound for the fitted noise offset.
+    const MIN_K_ADD: I32F32 = I32F32::lit("25");
+    /// Upper bound for the fitted noise offset.
+    const MAX_K_ADD: I32F32 = I32F32::lit("450");
+
+    /// Creates a new [`BeaconCalibration`] using the a-priori [`DistanceModel`].
+    pub fn new() -> Self { Self { samples: Vec::new(), model: DistanceModel::default() } }
+
+    /// Returns the currently applied distance model.
+    pub fn model(&self) -> DistanceModel { self.model }
+
+    /// Adds the measurements of a beacon with a known position as calibration checkpoint
+    /// and refits the model.
+    ///
+    /// # Arguments
+    /// * `id` - The ID of the beacon objective.
+    /// * `meas` - The recorded measurements of the beacon.
+    /// * `known_pos` - The known beacon position.
+    /// * `pos_uncertainty` - The maximum error of `known_pos`.
+    pub fn add_checkpoint(
+        &mut self,
+        id: BeaconObjectiveId,
+        meas: &[BeaconMeas],
+        known_pos: Vec2D<I32F32>,
+        pos_uncertainty: I32F32,
+    ) {
+        for m in meas {
+            let d_true = m.corr_pos().unwrapped_to(&known_pos).abs();
+            let d_noisy = I32F32::from_num(m.rssi());
+            self.samples.push(CalibrationSample { id, d_noisy, d_true });
+        }
+        self.refit(pos_uncertainty);
+        self.dump_json();
+    }
+
+    /// Refits the noise offset of the model to the collected samples.
+    ///
+    /// # Arguments
+    /// * `pos_uncertainty` - The maximum error of the known beacon positions.
+    fn refit(&mut self, pos_uncertainty: I32F32) {
+        if self.samples.len() < Self::MIN_SAMPLES {
+            return;
+        }
+        let prior = DistanceModel::default();
+        let envelope = self
+            .samples
+            .iter()
+            .map(|s| prior.required_k_add(s.d_noisy, s.d_true, pos_uncertainty))
+            .max()
+            .unwrap_or(prior.add);
+        let k_add = (envelope * Self::MARGIN).clamp(Self::MIN_K_ADD, Self::MAX_K_ADD);
+        obj!(
+            "Refitted beacon distance model from {} samples: K_ADD {} -> {k_add:.1}.",
+            self.samples.len(),
+            self.model.add
+        );
+        self.model = DistanceModel { add: k_add, ..prior };
+    }
+}
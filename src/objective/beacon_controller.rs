@@ -1,11 +1,18 @@
-use super::{BeaconObjective, BeaconMeas, beacon_objective_done::BeaconObjectiveDone};
+use super::{
+    BayesianSet, BeaconCalibration, BeaconHeatmap, BeaconObjective, BeaconMeas, LifecycleStage,
+    MeasConfidence, OBJECTIVE_TRACKER, PosteriorStats, ScoreLedger,
+    beacon_objective_done::BeaconObjectiveDone, beacon_state_store::BeaconStateStore,
+};
 use crate::flight_control::FlightComputer;
-use crate::http_handler::http_client::HTTPClient;
-use crate::util::logger::JsonDump;
+use crate::http_handler::{
+    http_client::HTTPClient, http_request::beacon_position_put::BeaconPositionRequest,
+};
+use crate::util::{BeaconObjectiveId, MissionConfig, Vec2D, logger::JsonDump};
 use crate::{event, obj, warn};
 use chrono::{DateTime, TimeDelta, Utc};
+use fixed::types::I32F32;
 use regex::Regex;
-use std::{collections::HashMap, sync::{Arc, LazyLock}, time::Duration};
+use std::{collections::HashMap, path::Path, sync::{Arc, LazyLock}, time::Duration};
 use tokio::{time::interval, sync::{mpsc::Receiver, Mutex, RwLock, watch}};
 
 /// The [`BeaconController`] manages active and completed Beacon Objectives,
@@ -18,15 +25,22 @@ use tokio::{time::interval, sync::{mpsc::Receiver, Mutex, RwLock, watch}};
 /// - Handling and filtering incoming ping messages
 /// - Estimating distances from noisy measurements
 /// - Submitting completed objectives through the endpoint
+/// - Persisting active objectives so that a restart resumes their localization
 pub struct BeaconController {
     /// Map of active beacon objectives indexed by ID.
-    active_bo: RwLock<HashMap<usize, BeaconObjective>>,
+    active_bo: RwLock<HashMap<BeaconObjectiveId, BeaconObjective>>,
     /// Map of completed beacon objectives that were already submitted.
-    done_bo: RwLock<HashMap<usize, BeaconObjectiveDone>>,
+    done_bo: RwLock<HashMap<BeaconObjectiveId, BeaconObjectiveDone>>,
     /// Receiver channel for newly announced beacon objectives.
     beacon_rx: Mutex<Receiver<BeaconObjective>>,
     /// State broadcast channel for notifying listeners when beacon activity changes.
     state_rx: watch::Sender<BeaconControllerState>,
+    /// Ledger receiving the estimated score of found beacons.
+    score: Arc<ScoreLedger>,
+    /// Calibration of the ping distance model from found beacons.
+    calibration: RwLock<BeaconCalibration>,
+    /// Persistent store of the active beacon objectives.
+    state_store: BeaconStateStore,
 }
 
 /// Enum representing whether any active beacon objectives are currently available.
@@ -47,26 +61,47 @@ static BO_REGEX: LazyLock<Regex> = LazyLock::new(|| {
 impl BeaconController {
     /// Interval between automatic passive checks for near-expiring objectives.
     const TIME_TO_NEXT_PASSIVE_CHECK: Duration = Duration::from_secs(30);
-    /// Maximum number of guesses allowed before beacon is considered resolved.
-    const MAX_ESTIMATE_GUESSES: usize = 5;
+    /// Number of candidate guess cells reported per beacon posterior.
+    pub const TOP_CANDIDATES: usize = 5;
 
     /// Creates a new [`BeaconController`] and associated state receiver.
     ///
+    /// Beacon objectives persisted before a restart are restored as active objectives,
+    /// together with their measurements, unless they have ended in the meantime.
+    ///
     /// # Arguments
     /// * `rx_beac` – A receiver channel to receive newly active beacon objectives.
+    /// * `score` – The ledger receiving the estimated score of found beacons.
+    /// * `state_dir` – The directory active beacon objectives are persisted to.
     ///
     /// # Returns
     /// A tuple `(BeaconController, watch::Receiver<BeaconControllerState>)`
     pub fn new(
         rx_beac: Receiver<BeaconObjective>,
+        score: Arc<ScoreLedger>,
+        state_dir: &Path,
     ) -> (Self, watch::Receiver<BeaconControllerState>) {
-        let (tx, rx) = watch::channel(BeaconControllerState::NoActiveBeacons);
+        let state_store = BeaconStateStore::open(state_dir);
+        let restored: HashMap<_, _> =
+            state_store.restore(Utc::now()).into_iter().map(|b| (b.id(), b)).collect();
+        for id in restored.keys() {
+            OBJECTIVE_TRACKER.record(*id, LifecycleStage::Scheduled);
+        }
+        let state = if restored.is_empty() {
+            BeaconControllerState::NoActiveBeacons
+        } else {
+            BeaconControllerState::ActiveBeacons
+        };
+        let (tx, rx) = watch::channel(state);
         (
             Self {
-                active_bo: RwLock::new(HashMap::new()),
+                active_bo: RwLock::new(restored),
                 done_bo: RwLock::new(HashMap::new()),
                 beacon_rx: Mutex::new(rx_beac),
                 state_rx: tx,
+                score,
+                calibration: RwLock::new(BeaconCalibration::new()),
+                state_store,
             },
             rx,
         )
@@ -103,6 +138,15 @@ impl BeaconController {
         self.active_bo.read().await.values().map(BeaconObjective::end).max()
     }
 
+    /// Returns a label, the start and the end of every currently active beacon objective.
+    pub async fn active_windows(&self) -> Vec<(String, DateTime<Utc>, DateTime<Utc>)> {
+        let active_lock = self.active_bo.read().await;
+        active_lock
+            .values()
+            .map(|b| (format!("Beacon {}-'{}'", b.id(), b.name()), b.start(), b.end()))
+            .collect()
+    }
+
     /// Attempts to extract a beacon ID and noisy distance from a telemetry message.
     ///
     /// # Arguments
@@ -110,14 +154,14 @@ impl BeaconController {
     ///
     /// # Returns
     /// * `Some((id, distance))` if parsing succeeds, `None` otherwise.
-    fn extract_id_and_d(input: &str) -> Option<(usize, f64)> {
+    fn extract_id_and_d(input: &str) -> Option<(BeaconObjectiveId, f64)> {
         // Match the input string
         if let Some(captures) = BO_REGEX.captures(input) {
             // Extract beacon_id and d_noisy
             if let (Some(beacon_id), Some(d_noisy)) = (captures.get(1), captures.get(2)) {
                 let id: usize = beacon_id.as_str().parse().unwrap();
                 let d_n: f64 = d_noisy.as_str().parse().unwrap();
-                return Some((id, d_n));
+                return Some((BeaconObjectiveId::new(id), d_n));
             }
         }
         None // Return None if values cannot be extracted
@@ -130,23 +174,45 @@ impl BeaconController {
     /// # Arguments
     /// * `msg` – Tuple of timestamp and message string.
     /// * `f_cont` – Lock to the flight computer for obtaining position.
+    /// * `confidence` – The confidence assigned to the measurement.
     pub async fn handle_poss_bo_ping(
         &self,
         msg: (DateTime<Utc>, String),
         f_cont: Arc<RwLock<FlightComputer>>,
+        confidence: MeasConfidence,
+    ) {
+        let pos = f_cont.read().await.current_pos();
+        self.handle_poss_bo_ping_at(msg, pos, confidence).await;
+    }
+
+    /// Processes a received ping message that was received at a known position of MELVIN.
+    ///
+    /// # Arguments
+    /// * `msg` – Tuple of timestamp and message string.
+    /// * `pos` – Position of MELVIN when the message was received.
+    /// * `confidence` – The confidence assigned to the measurement.
+    pub(super) async fn handle_poss_bo_ping_at(
+        &self,
+        msg: (DateTime<Utc>, String),
+        pos: Vec2D<I32F32>,
+        confidence: MeasConfidence,
     ) {
         let (t, val) = msg;
         if let Some((id, d_noisy)) = Self::extract_id_and_d(val.as_str()) {
-            let f_cont_lock = f_cont.read().await;
-            let pos = f_cont_lock.current_pos();
-
             let msg_delay = Utc::now() - t;
-            let meas = BeaconMeas::new(id, pos, d_noisy, msg_delay);
-            obj!("Received BO measurement at {pos} for ID {id} with distance {d_noisy}.");
-            let mut active_lock = self.active_bo.write().await;
-            if let Some(obj) = active_lock.get_mut(&id) {
+            let meas = BeaconMeas::new(id, pos, d_noisy, msg_delay).with_confidence(confidence);
+            obj!(
+                "Received BO measurement at {pos} for ID {id} with distance {d_noisy} \
+                 ({confidence:?} confidence)."
+            );
+            let model = self.calibration.read().await.model();
+            let snapshot = self.active_bo.write().await.get_mut(&id).map(|obj| {
                 obj!("Updating BO {id} measurement list!");
-                obj.append_measurement(meas);
+                obj.append_measurement(meas, model);
+                obj.snapshot()
+            });
+            if let Some(snap) = snapshot {
+                self.state_store.save(snap).await;
             } else {
                 warn!("Unknown BO ID {id}. Ignoring!");
             }
@@ -155,13 +221,65 @@ impl BeaconController {
         }
     }
 
+    /// Returns the current guess estimate of an active beacon.
+    ///
+    /// # Arguments
+    /// * `id` – The ID of the active beacon objective.
+    pub(super) async fn active_guess_estimate(&self, id: BeaconObjectiveId) -> Option<usize> {
+        let active_lock = self.active_bo.read().await;
+        Some(active_lock.get(&id)?.measurements()?.guess_estimate())
+    }
+
+    /// Returns the packed guess centers of an active beacon.
+    ///
+    /// # Arguments
+    /// * `id` – The ID of the active beacon objective.
+    pub(super) async fn active_guess_centers(&self, id: BeaconObjectiveId) -> Option<Vec<Vec2D<I32F32>>> {
+        let active_lock = self.active_bo.read().await;
+        Some(active_lock.get(&id)?.measurements()?.pack_perfect_circles())
+    }
+
+    /// Returns the posterior statistics of all active beacons with measurements.
+    pub async fn posteriors(&self) -> Vec<(BeaconObjectiveId, PosteriorStats)> {
+        let active_lock = self.active_bo.read().await;
+        active_lock
+            .values()
+            .filter_map(|b| Some((b.id(), b.measurements()?.posterior(Self::TOP_CANDIDATES)?)))
+            .collect()
+    }
+
+    /// Returns whether a beacon estimate is tight enough to be submitted, i.e. whether its
+    /// error radius is below `max_radius`.
+    ///
+    /// # Arguments
+    /// * `set` – The current estimate of the beacon position.
+    /// * `max_radius` – The `beacon_submit_radius` of the [`MissionConfig`].
+    fn is_resolved(set: &BayesianSet, max_radius: f64) -> bool {
+        set.posterior(0).is_some_and(|stats| stats.error_radius() < max_radius)
+    }
+
+    /// Renders the credible-region heatmaps of all active beacons with measurements.
+    pub async fn heatmaps(&self) -> Vec<BeaconHeatmap> {
+        let active_lock = self.active_bo.read().await;
+        active_lock
+            .values()
+            .filter_map(|b| b.measurements().map(|set| BeaconHeatmap::render(b.id(), set)))
+            .collect()
+    }
+
     /// Registers a newly received beacon objective into the active tracking list.
     ///
-    /// Notifies downstream listeners if this is the first active beacon.
+    /// Notifies downstream listeners if this is the first active beacon. An objective restored
+    /// after a restart is announced again by the backend, in which case the restored state
+    /// is kept.
     ///
     /// # Arguments
     /// * `obj` – The received `BeaconObjective`.
-    async fn add_beacon(&self, obj: BeaconObjective) {
+    pub(super) async fn add_beacon(&self, obj: BeaconObjective) {
+        if self.active_bo.read().await.contains_key(&obj.id()) {
+            obj!("Resuming the hunt for Beacon {}-'{}'.", obj.id(), obj.name());
+            return;
+        }
         obj!(
             "The Beacon {}-'{}' is lit! Gondor calls for Aid! Available Timeframe {} - {}.",
             obj.id(),
@@ -170,22 +288,35 @@ impl BeaconController {
             obj.end().format("%d %H:%M:%S").to_string()
         );
         let empty = self.active_bo.read().await.is_empty();
+        OBJECTIVE_TRACKER.record(obj.id(), LifecycleStage::Scheduled);
+        self.state_store.save(obj.snapshot()).await;
         self.active_bo.write().await.insert(obj.id(), obj);
         if empty {
             self.state_rx.send(BeaconControllerState::ActiveBeacons).expect("Failed to send state");
         }
     }
 
+    /// Dumps the state of all active beacon objectives, including their measurements, as JSON.
+    ///
+    /// # Returns
+    /// The number of dumped objectives.
+    pub async fn dump_active(&self) -> usize {
+        let active_bo = self.active_bo.read().await;
+        active_bo.values().for_each(JsonDump::dump_json);
+        active_bo.len()
+    }
+
     /// Moves finished objectives from `active_bo` to `done_bo`.
     ///
     /// Also logs and stores submission results.
     ///
     /// # Arguments
     /// * `finished` – Map of completed objectives to move.
-    async fn move_to_done(&self, finished: HashMap<usize, BeaconObjective>) {
+    async fn move_to_done(&self, finished: HashMap<BeaconObjectiveId, BeaconObjective>) {
         let mut done_bo = self.done_bo.write().await;
         for (id, beacon) in finished {
             beacon.dump_json();
+            self.state_store.remove(id);
             let done_beacon = BeaconObjectiveDone::from(beacon);
             let guesses = done_beacon.guesses().len();
             obj!("Finished Beacon objective: ID {id} with {guesses} guesses.");
@@ -195,27 +326,32 @@ impl BeaconController {
 
     /// Checks for objectives that are:
     /// - About to end within `TIME_TO_NEXT_PASSIVE_CHECK`
-    /// - Have enough guesses already
+    /// - Have an estimate with an error radius below the configured submit radius
     ///
     /// Submits them and updates internal state.
     ///
     /// # Arguments
     /// * `handler` – Shared HTTP client for submission.
     async fn check_approaching_end(&self, handler: &Arc<HTTPClient>) {
+        self.guard_deadlines(handler).await;
         let mut finished = HashMap::new();
         let deadline = Utc::now() + Self::TIME_TO_NEXT_PASSIVE_CHECK + TimeDelta::seconds(10);
+        let max_radius = MissionConfig::get().runtime.beacon_submit_radius;
         let no_more_beacons = {
             let mut active_beacon_tasks = self.active_bo.write().await;
             active_beacon_tasks.retain(|id, beacon: &mut BeaconObjective| {
-                let finished_cond = beacon
-                    .measurements()
-                    .is_some_and(|b| b.guess_estimate() < Self::MAX_ESTIMATE_GUESSES);
+                let finished_cond =
+                    beacon.measurements().is_some_and(|set| Self::is_resolved(set, max_radius));
                 let deadline_cond = beacon.end() < deadline;
-                if deadline_cond || finished_cond {
+                if finished_cond {
+                    obj!("Active BO {id} estimate is below the submit radius. Submitting now!");
+                } else if deadline_cond {
                     obj!(
                         "Active BO end is less than {} s away: ID {id}. Submitting this now!",
                         Self::TIME_TO_NEXT_PASSIVE_CHECK.as_secs(),
                     );
+                }
+                if deadline_cond || finished_cond {
                     finished.insert(*id, beacon.clone());
                     false
                 } else {
@@ -233,23 +369,147 @@ impl BeaconController {
         self.handle_beacon_submission(handler).await;
     }
 
+    /// Submits a last-chance guess for every beacon objective that approaches its end while
+    /// its posterior is still too wide to be resolved by the regular guesses.
+    ///
+    /// The guess is the centroid of the credible region, which maximizes the chance of a
+    /// partial score if the remaining packed guesses are never reached. A hit or a final miss
+    /// completes the objective immediately, otherwise the guess is remembered and counted
+    /// when the objective is submitted at its deadline.
+    ///
+    /// # Arguments
+    /// * `handler` – Shared HTTP client for submission.
+    #[allow(clippy::cast_possible_truncation)]
+    async fn guard_deadlines(&self, handler: &Arc<HTTPClient>) {
+        let config = MissionConfig::get();
+        let (lead_s, max_radius) =
+            (config.runtime.beacon_fallback_lead_s, config.runtime.beacon_submit_radius);
+        if lead_s == 0 {
+            return;
+        }
+        let guard_t = Utc::now() + TimeDelta::seconds(i64::from(lead_s));
+        let due: Vec<_> = self
+            .active_bo
+            .read()
+            .await
+            .values()
+            .filter(|b| b.fallback_guess().is_none() && b.end() < guard_t)
+            .filter_map(|b| {
+                let set = b.measurements()?;
+                if Self::is_resolved(set, max_radius) {
+                    return None;
+                }
+                set.credible_centroid().map(|c| (b.id(), c))
+            })
+            .collect();
+        for (id, centroid) in due {
+            obj!("BO {id} ends within {lead_s}s with a wide estimate. Guessing {centroid}.");
+            let req = BeaconPositionRequest {
+                beacon_id: id.raw() as u16,
+                width: centroid.x().abs().to_num::<u32>(),
+                height: centroid.y().abs().to_num::<u32>(),
+            };
+            let client = Arc::clone(handler);
+            let res = BeaconObjectiveDone::submit_guess(req, client, &centroid, 0).await;
+            let Some(mut beacon) = self.active_bo.write().await.remove(&id) else { continue };
+            beacon.set_fallback_guess(centroid);
+            if let Ok(None) = res {
+                self.state_store.save(beacon.snapshot()).await;
+                self.active_bo.write().await.insert(id, beacon);
+                continue;
+            }
+            beacon.dump_json();
+            self.state_store.remove(id);
+            let mut done_beacon = BeaconObjectiveDone::from(beacon);
+            done_beacon.set_submitted();
+            OBJECTIVE_TRACKER.record(id, LifecycleStage::Uploaded);
+            if res.is_ok() {
+                self.score.record_beacon(id, 1).await;
+                self.add_calibration_checkpoint(&done_beacon, 1).await;
+            } else {
+                OBJECTIVE_TRACKER.fail(id, "fallback guess missed");
+            }
+            self.done_bo.write().await.insert(id, done_beacon);
+        }
+    }
+
+    /// Submits all beacon objectives immediately, regardless of their remaining time.
+    ///
+    /// Used by the end-of-mission routine so that no estimate is left unsubmitted.
+    ///
+    /// # Arguments
+    /// * `handler` – Shared HTTP client for submission.
+    ///
+    /// # Returns
+    /// * The number of beacon objectives submitted by this call and the number of them whose
+    ///   guesses could not be sent.
+    pub async fn submit_all(&self, handler: &Arc<HTTPClient>) -> (usize, usize) {
+        let active: HashMap<_, _> = self.active_bo.write().await.drain().collect();
+        if !active.is_empty() {
+            obj!("Force-submitting {} active BOs at end of mission.", active.len());
+        }
+        self.move_to_done(active).await;
+        self.state_rx.send_replace(BeaconControllerState::NoActiveBeacons);
+        let pending = self.done_bo.read().await.values().filter(|b| !b.submitted()).count();
+        let failed = self.handle_beacon_submission(handler).await;
+        (pending, failed)
+    }
+
+    /// Uses a found beacon as calibration checkpoint for the ping distance model.
+    ///
+    /// The successful guess reveals the beacon position up to the guess radius.
+    ///
+    /// # Arguments
+    /// * `beacon` – The found beacon objective.
+    /// * `guesses` – The number of guesses needed to find the beacon.
+    async fn add_calibration_checkpoint(&self, beacon: &BeaconObjectiveDone, guesses: usize) {
+        let Some(known_pos) = beacon.guesses().get(guesses - 1) else { return };
+        if beacon.measurements().is_empty() {
+            return;
+        }
+        let uncertainty = I32F32::from_num(BayesianSet::MAX_RES_UNCERTAINTY_RAD);
+        self.calibration.write().await.add_checkpoint(
+            beacon.id(),
+            beacon.measurements(),
+            *known_pos,
+            uncertainty,
+        );
+    }
+
     /// Handles submission of all completed (done) beacon objectives.
     ///
     /// Applies random guesses or estimates based on measurement data.
     ///
     /// # Arguments
     /// * `handler` – Shared HTTP client used to send results.
-    async fn handle_beacon_submission(&self, handler: &Arc<HTTPClient>) {
+    ///
+    /// # Returns
+    /// * The number of beacon objectives whose guesses could not be sent.
+    async fn handle_beacon_submission(&self, handler: &Arc<HTTPClient>) -> usize {
         let mut done_beacons = self.done_bo.write().await;
+        let mut failed = 0;
         for beacon in done_beacons.values_mut() {
             if !beacon.submitted() {
                 beacon.set_submitted();
-                if beacon.guesses().is_empty() {
-                    beacon.randomize_no_meas_guesses(Arc::clone(handler)).await;
+                let found_after = if beacon.guesses().is_empty() {
+                    beacon.randomize_no_meas_guesses(Arc::clone(handler)).await
                 } else {
-                    beacon.guess_max(Arc::clone(handler)).await;
+                    beacon.guess_max(Arc::clone(handler)).await
+                };
+                OBJECTIVE_TRACKER.record(beacon.id(), LifecycleStage::Uploaded);
+                match found_after {
+                    Ok(Some(guesses)) => {
+                        self.score.record_beacon(beacon.id(), guesses).await;
+                        self.add_calibration_checkpoint(beacon, guesses).await;
+                    }
+                    Ok(None) => OBJECTIVE_TRACKER.fail(beacon.id(), "no guess hit"),
+                    Err(_) => {
+                        OBJECTIVE_TRACKER.fail(beacon.id(), "guess submission failed");
+                        failed += 1;
+                    }
                 }
             }
         }
+        failed
     }
 }
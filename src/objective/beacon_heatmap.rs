@@ -0,0 +1,136 @@
+use super::BayesianSet;
+use crate::util::{BeaconObjectiveId, Vec2D};
+use fixed::types::I32F32;
+use image::{Rgba, RgbaImage, codecs::png::PngEncoder};
+use std::io::Cursor;
+
+/// A small heatmap tile visualizing the position estimate of a single beacon.
+///
+/// Every cell is colored by the share of measurements consistent with it, from transparent
+/// (none) over blue to red (all but one). The credible region, i.e. the cells consistent
+/// with all measurements, is drawn in yellow with a white contour, so that operators can see
+/// at a glance how tight an estimate is.
+#[derive(Debug)]
+pub struct BeaconHeatmap {
+    /// The ID of the beacon objective.
+    id: BeaconObjectiveId,
+    /// The wrapped map offset of the tile.
+    offset: Vec2D<u32>,
+    /// The size of the tile in map coordinates.
+    size: Vec2D<u32>,
+    /// The number of coordinates in the credible region.
+    region_size: usize,
+    /// The estimated number of guesses needed to cover the credible region.
+    guess_estimate: usize,
+    /// The rendered tile.
+    image: RgbaImage,
+}
+
+impl BeaconHeatmap {
+    /// Maximum side length of the rendered tile in pixels.
+    const MAX_SIDE: u32 = 128;
+    /// Number of samples per cell and axis used to check the credible region.
+    const CELL_SAMPLES: u32 = 4;
+    /// Color of the credible region.
+    const REGION_COLOR: Rgba<u8> = Rgba([255, 220, 0, 220]);
+    /// Color of the credible region contour.
+    const CONTOUR_COLOR: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+    /// Renders the heatmap tile of a beacon estimate.
+    ///
+    /// # Arguments
+    /// * `id` - The ID of the beacon objective.
+    /// * `set` - The current estimate of the beacon position.
+    ///
+    /// # Returns
+    /// The rendered [`BeaconHeatmap`].
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_possible_wrap)]
+    pub fn render(id: BeaconObjectiveId, set: &BayesianSet) -> Self {
+        let (offset, side) = set.bounds();
+        let size = Vec2D::new(side.x().ceil().to_num::<u32>(), side.y().ceil().to_num::<u32>());
+        let scale = size.x().max(size.y()).div_ceil(Self::MAX_SIDE).max(1);
+        let (width, height) = (size.x().div_ceil(scale).max(1), size.y().div_ceil(scale).max(1));
+        let origin = Vec2D::new(offset.x().to_num::<i32>(), offset.y().to_num::<i32>());
+        let n_meas = set.measurements().len();
+
+        let cell_pos = |cx: u32, cy: u32, sx: u32, sy: u32| {
+            let step = scale.div_ceil(Self::CELL_SAMPLES);
+            let x = origin.x() + (cx * scale + sx * step) as i32;
+            let y = origin.y() + (cy * scale + sy * step) as i32;
+            Vec2D::new(x, y).wrap_around_map()
+        };
+        let samples = Self::CELL_SAMPLES.min(scale);
+        let mut in_region = vec![false; (width * height) as usize];
+        let mut image = RgbaImage::new(width, height);
+        for (cx, cy, px) in image.enumerate_pixels_mut() {
+            let region = (0..samples)
+                .flat_map(|sx| (0..samples).map(move |sy| (sx, sy)))
+                .any(|(sx, sy)| set.is_in_set(cell_pos(cx, cy, sx, sy)));
+            in_region[(cy * width + cx) as usize] = region;
+            *px = if region {
+                Self::REGION_COLOR
+            } else {
+                let center = cell_pos(cx, cy, samples / 2, samples / 2);
+                Self::heat_color(set.consistent_meas(center), n_meas)
+            };
+        }
+        let is_region = |x: i64, y: i64| {
+            x >= 0
+                && y >= 0
+                && x < i64::from(width)
+                && y < i64::from(height)
+                && in_region[(y as u32 * width + x as u32) as usize]
+        };
+        for y in 0..i64::from(height) {
+            for x in 0..i64::from(width) {
+                let edge = [(1, 0), (-1, 0), (0, 1), (0, -1)]
+                    .iter()
+                    .any(|(dx, dy)| !is_region(x + dx, y + dy));
+                if is_region(x, y) && edge {
+                    image.put_pixel(x as u32, y as u32, Self::CONTOUR_COLOR);
+                }
+            }
+        }
+        let wrapped = Vec2D::<I32F32>::new(offset.x(), offset.y()).wrap_around_map();
+        Self {
+            id,
+            offset: Vec2D::new(wrapped.x().to_num(), wrapped.y().to_num()),
+            size,
+            region_size: set.len(),
+            guess_estimate: set.guess_estimate(),
+            image,
+        }
+    }
+
+    /// Maps the share of consistent measurements to a heat color.
+    ///
+    /// # Arguments
+    /// * `consistent` - The number of consistent measurements.
+    /// * `total` - The total number of measurements.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn heat_color(consistent: usize, total: usize) -> Rgba<u8> {
+        if consistent == 0 || total == 0 {
+            return Rgba([0, 0, 0, 0]);
+        }
+        let share = u8::try_from(consistent * 255 / total).unwrap_or(u8::MAX);
+        Rgba([share, 0, 255 - share, 64 + share / 2])
+    }
+
+    /// Encodes the tile as a PNG.
+    pub fn encode_png(&self) -> Result<Vec<u8>, image::ImageError> {
+        let mut writer = Cursor::new(Vec::<u8>::new());
+        self.image.write_with_encoder(PngEncoder::new(&mut writer))?;
+        Ok(writer.into_inner())
+    }
+
+    /// Returns the ID of the beacon objective.
+    pub fn id(&self) -> BeaconObjectiveId { self.id }
+    /// Returns the wrapped map offset of the tile.
+    pub fn offset(&self) -> Vec2D<u32> { self.offset }
+    /// Returns the size of the tile in map coordinates.
+    pub fn size(&self) -> Vec2D<u32> { self.size }
+    /// Returns the number of coordinates in the credible region.
+    pub fn region_size(&self) -> usize { self.region_size }
+    /// Returns the estimated number of guesses needed to cover the credible region.
+    pub fn guess_estimate(&self) -> usize { self.guess_estimate }
+}
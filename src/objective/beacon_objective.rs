@@ -1,21 +1,33 @@
 use crate::STATIC_ORBIT_VEL;
-use crate::util::{Vec2D, logger::JsonDump};
-use super::BayesianSet;
+use crate::util::{BeaconObjectiveId, Vec2D, logger::JsonDump};
+use super::{BayesianSet, DistanceModel, bayesian_set::BayesianSetSnapshot};
 use chrono::{DateTime, TimeDelta, Utc};
 use fixed::types::I32F32;
 use std::cmp::Ordering;
 
+/// The confidence of a beacon measurement, depending on the state it was received in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum MeasConfidence {
+    /// The ping was received during a regular Comms window.
+    Full,
+    /// The ping was received outside Comms, e.g. by the degraded listener while charging.
+    /// Timing and position of such pings are less reliable.
+    Degraded,
+}
+
 /// Represents a beacon measurement with associated properties.
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct BeaconMeas {
     /// Unique identifier of the beacon.
-    id: usize,
+    id: BeaconObjectiveId,
     /// Position of MELVIN when the beacon measurement was received.
     pos: Vec2D<I32F32>,
     /// Received Signal Strength Indicator (RSSI) or `d_noisy` of the beacon.
     rssi: f64,
     /// Time delay associated with the beacon measurement and the observed position.
     delay: TimeDelta,
+    /// The confidence of the measurement.
+    confidence: MeasConfidence,
 }
 
 impl BeaconMeas {
@@ -26,12 +38,21 @@ impl BeaconMeas {
     /// * `pos` - Position of MELVIN as a 2D vector.
     /// * `rssi` - RSSI (`d_noisy`) value of the beacon.
     /// * `delay` - Time delay for the beacon.
-    pub fn new(id: usize, pos: Vec2D<I32F32>, rssi: f64, delay: TimeDelta) -> Self {
-        Self { id, pos, rssi, delay }
+    pub fn new(id: BeaconObjectiveId, pos: Vec2D<I32F32>, rssi: f64, delay: TimeDelta) -> Self {
+        Self { id, pos, rssi, delay, confidence: MeasConfidence::Full }
+    }
+
+    /// Sets the confidence of the measurement.
+    ///
+    /// # Arguments
+    /// * `confidence` - The confidence of the measurement.
+    pub fn with_confidence(mut self, confidence: MeasConfidence) -> Self {
+        self.confidence = confidence;
+        self
     }
 
     /// Returns the unique identifier of the beacon.
-    pub fn id(&self) -> usize { self.id }
+    pub fn id(&self) -> BeaconObjectiveId { self.id }
     /// Returns a reference to the position of the beacon.
     pub fn pos(&self) -> &Vec2D<I32F32> { &self.pos }
     /// Returns the RSSI of the beacon.
@@ -49,13 +70,15 @@ impl BeaconMeas {
 
     /// Returns the time delay associated with the beacon measurement.
     pub fn delay(&self) -> TimeDelta { self.delay }
+    /// Returns the confidence of the measurement.
+    pub fn confidence(&self) -> MeasConfidence { self.confidence }
 }
 
 /// Represents a beacon objective with associated metadata and measurements.
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct BeaconObjective {
     /// Unique identifier of the beacon objective.
-    id: usize,
+    id: BeaconObjectiveId,
     /// Name of the beacon objective.
     name: String,
     /// Start time of the beacon objective.
@@ -64,6 +87,46 @@ pub struct BeaconObjective {
     end: DateTime<Utc>,
     /// Optional set of measurements associated with the beacon objective.
     measurements: Option<BayesianSet>,
+    /// The last-chance guess already submitted by the deadline guard, if any.
+    fallback_guess: Option<Vec2D<I32F32>>,
+}
+
+/// The persisted state of a [`BeaconObjective`], restored after a restart.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub(crate) struct BeaconObjectiveSnapshot {
+    /// Unique identifier of the beacon objective.
+    id: BeaconObjectiveId,
+    /// Name of the beacon objective.
+    name: String,
+    /// Start time of the beacon objective.
+    start: DateTime<Utc>,
+    /// End time of the beacon objective.
+    end: DateTime<Utc>,
+    /// The persisted measurement set, if any measurement was received.
+    measurements: Option<BayesianSetSnapshot>,
+    /// The last-chance guess already submitted by the deadline guard, if any.
+    fallback_guess: Option<Vec2D<I32F32>>,
+}
+
+impl BeaconObjectiveSnapshot {
+    /// Returns the unique identifier of the beacon objective.
+    pub(crate) fn id(&self) -> BeaconObjectiveId { self.id }
+    /// Returns the end time of the beacon objective.
+    pub(crate) fn end(&self) -> DateTime<Utc> { self.end }
+}
+
+impl From<BeaconObjectiveSnapshot> for BeaconObjective {
+    /// Restores a beacon objective together with its measurement set.
+    fn from(snapshot: BeaconObjectiveSnapshot) -> Self {
+        Self {
+            id: snapshot.id,
+            name: snapshot.name,
+            start: snapshot.start,
+            end: snapshot.end,
+            measurements: snapshot.measurements.and_then(BayesianSet::from_snapshot),
+            fallback_guess: snapshot.fallback_guess,
+        }
+    }
 }
 
 impl JsonDump for BeaconObjective {
@@ -82,12 +145,12 @@ impl BeaconObjective {
     /// * `name` - Name of the beacon objective.
     /// * `start` - Start time of the beacon objective.
     /// * `end` - End time of the beacon objective.
-    pub fn new(id: usize, name: String, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
-        Self { id, name, start, end, measurements: None }
+    pub fn new(id: BeaconObjectiveId, name: String, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        Self { id, name, start, end, measurements: None, fallback_guess: None }
     }
 
     /// Returns the unique identifier of the beacon objective.
-    pub fn id(&self) -> usize { self.id }
+    pub fn id(&self) -> BeaconObjectiveId { self.id }
     /// Returns the name of the beacon objective.
     pub fn name(&self) -> &str { &self.name }
     /// Returns the start time of the beacon objective.
@@ -96,18 +159,37 @@ impl BeaconObjective {
     pub fn end(&self) -> DateTime<Utc> { self.end }
     /// Returns an optional reference to the set of beacon measurements.
     pub fn measurements(&self) -> Option<&BayesianSet> { self.measurements.as_ref() }
+    /// Returns the last-chance guess already submitted by the deadline guard, if any.
+    pub fn fallback_guess(&self) -> Option<Vec2D<I32F32>> { self.fallback_guess }
+    /// Records the last-chance guess submitted by the deadline guard.
+    pub fn set_fallback_guess(&mut self, guess: Vec2D<I32F32>) {
+        self.fallback_guess = Some(guess);
+    }
+
+    /// Captures the state of the objective and its measurement set for persistence.
+    pub(crate) fn snapshot(&self) -> BeaconObjectiveSnapshot {
+        BeaconObjectiveSnapshot {
+            id: self.id,
+            name: self.name.clone(),
+            start: self.start,
+            end: self.end,
+            measurements: self.measurements.as_ref().map(BayesianSet::snapshot),
+            fallback_guess: self.fallback_guess,
+        }
+    }
 
     /// Appends a beacon measurement to the objective's measurement set.
     ///
-    /// If the measurement set does not exist, it creates a new one.
+    /// If the measurement set does not exist, it creates a new one using the given model.
     ///
     /// # Arguments
     /// * `meas` - The `BeaconMeas` to be added.
-    pub fn append_measurement(&mut self, meas: BeaconMeas) {
+    /// * `model` - The distance model used if a new measurement set is created.
+    pub fn append_measurement(&mut self, meas: BeaconMeas, model: DistanceModel) {
         if let Some(meas_set) = &mut self.measurements {
             meas_set.update(&meas);
         } else {
-            self.measurements = Some(BayesianSet::new(meas));
+            self.measurements = Some(BayesianSet::with_model(meas, model));
         }
     }
 }
@@ -141,6 +223,7 @@ impl From<crate::http_handler::BeaconObjective> for BeaconObjective {
             start: obj.start(),
             end: obj.end(),
             measurements: None,
+            fallback_guess: None,
         }
     }
 }
@@ -1,5 +1,5 @@
-use super::BeaconObjective;
-use crate::util::Vec2D;
+use super::{BeaconMeas, BeaconObjective};
+use crate::util::{BeaconObjectiveId, MapSize, Vec2D};
 use crate::http_handler::{
     http_client::HTTPClient,
     http_request::{
@@ -20,7 +20,7 @@ use std::sync::Arc;
 #[derive(Clone)]
 pub struct BeaconObjectiveDone {
     /// The unique identifier of the objective.
-    id: usize,
+    id: BeaconObjectiveId,
     /// The name of the objective.
     name: String,
     /// The start time of the objective.
@@ -29,20 +29,20 @@ pub struct BeaconObjectiveDone {
     end: DateTime<Utc>,
     /// A collection of guesses made for the beacon position.
     guesses: Vec<Vec2D<I32F32>>,
+    /// The measurements the guesses were derived from.
+    measurements: Vec<BeaconMeas>,
+    /// The number of leading guesses that were already submitted before completion.
+    pre_submitted: usize,
     /// Status indicating whether the guesses have been submitted.
     submitted: bool,
 }
 
 impl BeaconObjectiveDone {
-    /// The allowed range for map width values.
-    const MAP_WIDTH_RANGE: std::ops::Range<u32> = 0..21600;
-    /// The allowed range for map height values.
-    const MAP_HEIGHT_RANGE: std::ops::Range<u32> = 0..10800;
     /// The minimum allowable distance between random guesses.
     const MIN_DISTANCE_RAND_GUESSES: f32 = 75.0;
 
     /// Returns the ID of the beacon objective.
-    pub fn id(&self) -> usize { self.id }
+    pub fn id(&self) -> BeaconObjectiveId { self.id }
     /// Returns the name of the beacon objective.
     pub fn name(&self) -> &str { &self.name }
     /// Returns the start time of the beacon objective.
@@ -51,34 +51,48 @@ impl BeaconObjectiveDone {
     pub fn end(&self) -> DateTime<Utc> { self.end }
     /// Returns a reference to the guesses for the beacon objective.
     pub fn guesses(&self) -> &Vec<Vec2D<I32F32>> { &self.guesses }
+    /// Returns the measurements the guesses were derived from.
+    pub fn measurements(&self) -> &[BeaconMeas] { &self.measurements }
     /// Returns whether the guesses have been submitted.
     pub fn submitted(&self) -> bool { self.submitted }
     /// Sets the submission status of the guesses to true.
     pub fn set_submitted(&mut self) { self.submitted = true }
 
-    /// Sends all guesses for the beacon to the DRS.
+    /// Sends all guesses for the beacon to the DRS until one succeeds.
+    ///
+    /// Guesses already submitted by the deadline guard are skipped but still counted.
     ///
     /// # Arguments
     ///
     /// * `client` - HTTP client used to send requests.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(n))` with the number of guesses needed if the beacon was found.
+    /// * `Ok(None)` if no guess hit the beacon.
+    /// * `Err` if a guess could not be sent to the DRS.
     #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
-    pub async fn guess_max(&self, client: Arc<HTTPClient>) {
+    pub async fn guess_max(&self, client: Arc<HTTPClient>) -> Result<Option<usize>, Error> {
         obj!(
             "Guessing max for {}: {} guesses...",
             self.id,
             self.guesses.len()
         );
-        let id_u16 = self.id() as u16;
+        let id_u16 = self.id().raw() as u16;
         let guess_cloned = self.guesses().clone();
-        for (i, guess) in guess_cloned.iter().enumerate() {
+        for (i, guess) in guess_cloned.iter().enumerate().skip(self.pre_submitted) {
             let width = guess.x().abs().to_num::<u32>();
             let height = guess.y().abs().to_num::<u32>();
             let req = BeaconPositionRequest { beacon_id: id_u16, width, height };
             obj!("Sending request for beacon {id_u16} with width {width} and height {height}...");
-            if self.submit_guess(req, client.clone(), guess, i).await.is_err() {
-                return;
-            };
+            match Self::submit_guess(req, client.clone(), guess, i).await {
+                Ok(Some(())) => return Ok(Some(i + 1)),
+                Ok(None) => {}
+                Err(e) if e.kind() == ErrorKind::NotConnected => return Err(e),
+                Err(_) => return Ok(None),
+            }
         }
+        Ok(None)
     }
 
     /// Randomizes guesses for the beacon if none are provided and submits them.
@@ -86,8 +100,17 @@ impl BeaconObjectiveDone {
     /// # Arguments
     ///
     /// * `client` - HTTP client used to send requests.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(n))` with the number of guesses needed if the beacon was found.
+    /// * `Ok(None)` if no guess hit the beacon.
+    /// * `Err` if a guess could not be sent to the DRS.
     #[allow(clippy::cast_possible_truncation)]
-    pub async fn randomize_no_meas_guesses(&self, client: Arc<HTTPClient>) {
+    pub async fn randomize_no_meas_guesses(
+        &self,
+        client: Arc<HTTPClient>,
+    ) -> Result<Option<usize>, Error> {
         if !self.guesses.is_empty() {
             obj!("Guesses are provided already, skipping randomization.");
             return self.guess_max(client).await;
@@ -97,18 +120,19 @@ impl BeaconObjectiveDone {
         let random_guesses = Self::generate_random_guesses();
         for (i, guess) in random_guesses.iter().enumerate() {
             let guess_req = BeaconPositionRequest {
-                beacon_id: self.id as u16,
+                beacon_id: self.id.raw() as u16,
                 width: guess.x().abs().to_num::<u32>(),
                 height: guess.y().abs().to_num::<u32>(),
             };
-            let res = self.submit_guess(guess_req, Arc::clone(&client), guess, i).await;
+            let res = Self::submit_guess(guess_req, Arc::clone(&client), guess, i).await;
             match res {
-                Ok(done) => {
-                    if done.is_some() { return; };
-                }
-                Err(_) => return,
+                Ok(Some(())) => return Ok(Some(i + 1)),
+                Ok(None) => {}
+                Err(e) if e.kind() == ErrorKind::NotConnected => return Err(e),
+                Err(_) => return Ok(None),
             }
         }
+        Ok(None)
     }
 
     /// Submits a single guess to the server.
@@ -124,9 +148,9 @@ impl BeaconObjectiveDone {
     ///
     /// * `Ok(Some(()))` if the guess is successful.
     /// * `Ok(None)` if the guess fails but the process is not over.
-    /// * `Err` if an error occurs or all attempts are exhausted.
-    async fn submit_guess(
-        &self,
+    /// * `Err` with [`ErrorKind::NotConnected`] if the request could not be sent.
+    /// * Another `Err` if the beacon is unknown or all attempts are exhausted.
+    pub(super) async fn submit_guess(
         req: BeaconPositionRequest,
         client: Arc<HTTPClient>,
         guess: &Vec2D<I32F32>,
@@ -162,7 +186,7 @@ impl BeaconObjectiveDone {
             return Err(Error::new(ErrorKind::Other, "Unknown Message!"));
         }
         error!("Unnoticed HTTP Error in submit_guess()");
-        Err(Error::new(ErrorKind::Other, "HTTP Error!"))
+        Err(Error::new(ErrorKind::NotConnected, "HTTP Error!"))
     }
 
     /// Generates a vector of random guesses, ensuring each guess
@@ -174,9 +198,10 @@ impl BeaconObjectiveDone {
     fn generate_random_guesses() -> Vec<Vec2D<I32F32>> {
         let mut rng = rand::rng();
         let mut random_guesses = Vec::new();
+        let map = u32::map_size();
         while random_guesses.len() <= 10 {
-            let random_width = rng.random_range(Self::MAP_WIDTH_RANGE);
-            let random_height = rng.random_range(Self::MAP_HEIGHT_RANGE);
+            let random_width = rng.random_range(0..map.x());
+            let random_height = rng.random_range(0..map.y());
             let rand_guess = Vec2D::new(
                 I32F32::from_num(random_width),
                 I32F32::from_num(random_height),
@@ -204,14 +229,23 @@ impl From<BeaconObjective> for BeaconObjectiveDone {
     ///
     /// * `obj` - The original objective to be converted.
     fn from(obj: BeaconObjective) -> Self {
-        let guesses =
-            if let Some(meas) = obj.measurements() { meas.pack_perfect_circles() } else { vec![] };
+        let (mut guesses, measurements) = if let Some(meas) = obj.measurements() {
+            (meas.pack_perfect_circles(), meas.measurements().to_vec())
+        } else {
+            (vec![], vec![])
+        };
+        let pre_submitted = usize::from(obj.fallback_guess().is_some());
+        if let Some(fallback) = obj.fallback_guess() {
+            guesses.insert(0, fallback);
+        }
         Self {
             id: obj.id(),
             name: String::from(obj.name()),
             start: obj.start(),
             end: obj.end(),
             guesses,
+            measurements,
+            pre_submitted,
             submitted: false,
         }
     }
@@ -0,0 +1,143 @@
+use super::{BeaconObjective, beacon_objective::BeaconObjectiveSnapshot};
+use crate::util::BeaconObjectiveId;
+use crate::{error, info, warn};
+use chrono::{DateTime, Utc};
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+use tokio::sync::Mutex;
+
+/// Persistent store of the active beacon objectives and their measurement sets.
+///
+/// Every objective is written to its own `bo_<id>.bin` file whenever it receives a
+/// measurement, so that a restart mid-hunt resumes the localization instead of starting over.
+/// Files are replaced atomically and deleted once their objective is done.
+#[derive(Debug)]
+pub(crate) struct BeaconStateStore {
+    /// The directory holding the persisted objectives.
+    dir: PathBuf,
+    /// Serializes the writes, so that an older state never replaces a newer one.
+    write_lock: Mutex<()>,
+}
+
+impl BeaconStateStore {
+    /// Opens the store in the directory at `path`, creating the directory if necessary.
+    ///
+    /// # Arguments
+    /// * `path` – The directory holding the persisted objectives.
+    pub(crate) fn open<P: AsRef<Path>>(path: P) -> Self {
+        let dir = path.as_ref().to_path_buf();
+        if let Err(e) = std::fs::create_dir_all(&dir) {
+            warn!("Failed to create beacon state directory {}: {e}.", dir.display());
+        }
+        Self { dir, write_lock: Mutex::new(()) }
+    }
+
+    /// Returns the file an objective is persisted to.
+    fn path(&self, id: BeaconObjectiveId) -> PathBuf { self.dir.join(format!("bo_{id}.bin")) }
+
+    /// Restores all persisted objectives that have not ended yet. Files of ended or invalid
+    /// objectives are deleted.
+    ///
+    /// # Arguments
+    /// * `now` – The current time.
+    pub(crate) fn restore(&self, now: DateTime<Utc>) -> Vec<BeaconObjective> {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else { return Vec::new() };
+        let mut restored = Vec::new();
+        for path in entries.flatten().map(|e| e.path()) {
+            if path.extension().is_none_or(|ext| ext != "bin") {
+                continue;
+            }
+            let loaded = std::fs::read(&path).map_err(|e| e.to_string()).and_then(|data| {
+                bincode::serde::decode_from_slice::<BeaconObjectiveSnapshot, _>(
+                    &data,
+                    bincode::config::standard(),
+                )
+                .map(|(decoded, _)| decoded)
+                .map_err(|e| e.to_string())
+            });
+            match loaded {
+                Ok(snapshot) if snapshot.end() > now => {
+                    info!("Restored state of beacon objective {}.", snapshot.id());
+                    restored.push(BeaconObjective::from(snapshot));
+                }
+                Ok(_) => Self::remove_file(&path),
+                Err(e) => {
+                    warn!("Discarding invalid beacon state {}: {e}.", path.display());
+                    Self::remove_file(&path);
+                }
+            }
+        }
+        restored
+    }
+
+    /// Writes the state of an objective to a temporary file on the blocking pool and renames
+    /// it to the objective's file, so that a crash mid-write keeps the previous state.
+    ///
+    /// # Arguments
+    /// * `snapshot` – The state of the objective to persist, see [`BeaconObjective::snapshot`].
+    pub(crate) async fn save(&self, snapshot: BeaconObjectiveSnapshot) {
+        let id = snapshot.id();
+        let path = self.path(id);
+        let _write_guard = self.write_lock.lock().await;
+        let res = tokio::task::spawn_blocking(move || {
+            let tmp = path.with_extension("tmp");
+            bincode::serde::encode_to_vec(snapshot, bincode::config::standard())
+                .map_err(|e| e.to_string())
+                .and_then(|data| std::fs::write(&tmp, data).map_err(|e| e.to_string()))
+                .and_then(|()| std::fs::rename(&tmp, &path).map_err(|e| e.to_string()))
+        })
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|res| res);
+        if let Err(e) = res {
+            error!("Failed to persist state of beacon objective {id}: {e}.");
+        }
+    }
+
+    /// Deletes the persisted state of an objective that is done.
+    ///
+    /// # Arguments
+    /// * `id` – The ID of the objective.
+    pub(crate) fn remove(&self, id: BeaconObjectiveId) { Self::remove_file(&self.path(id)); }
+
+    /// Deletes a state file, tolerating that it is already gone.
+    fn remove_file(path: &Path) {
+        match std::fs::remove_file(path) {
+            Err(e) if e.kind() != ErrorKind::NotFound => {
+                warn!("Failed to delete beacon state {}: {e}.", path.display());
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+
+    #[tokio::test]
+    async fn test_save_and_restore() {
+        let dir = std::env::temp_dir().join(format!("melvin_bo_state_{}", std::process::id()));
+        let store = BeaconStateStore::open(&dir);
+        let now = Utc::now();
+        let (active, ended) = (BeaconObjectiveId::new(1), BeaconObjectiveId::new(2));
+        let beacon = BeaconObjective::new(active, "a".into(), now, now + TimeDelta::hours(1));
+        store.save(beacon.snapshot()).await;
+        // A newer state replaces the file without leaving the temporary file behind
+        store.save(beacon.snapshot()).await;
+        let past = now - TimeDelta::hours(1);
+        store.save(BeaconObjective::new(ended, "b".into(), past, past).snapshot()).await;
+        assert!(!dir.join("bo_1.tmp").exists());
+
+        let restored = store.restore(now);
+        assert_eq!(restored.len(), 1);
+        assert_eq!(restored[0].id(), active);
+        assert!(!store.path(ended).exists());
+        store.remove(active);
+        assert!(store.restore(now).is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
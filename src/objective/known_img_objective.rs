@@ -1,5 +1,5 @@
 use crate::imaging::CameraAngle;
-use crate::util::Vec2D;
+use crate::util::{ImgObjectiveId, Vec2D, ZoneRect};
 use crate::http_handler::{ImageObjective, ZoneType};
 use chrono::{DateTime, Utc};
 use fixed::types::I32F32;
@@ -9,18 +9,18 @@ use std::cmp::Ordering;
 /// Represents a known image objective that specifies a region of interest on the map.
 ///
 /// This objective includes details like the time frame, required camera angle, and coverage percentage.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct KnownImgObjective {
     /// Unique identifier for the objective.
-    id: usize,
+    id: ImgObjectiveId,
     /// Human-readable name for the objective.
     name: String,
     /// Start time of the objective in UTC.
     start: DateTime<Utc>,
     /// End time of the objective in UTC.
     end: DateTime<Utc>,
-    /// Coordinates of the objective zone as `[x_min, y_min, x_max, y_max]`.
-    zone: [i32; 4],
+    /// The objective zone.
+    zone: ZoneRect,
     /// Required camera angle for the objective.
     optic_required: CameraAngle,
     /// Coverage percentage required for the objective.
@@ -30,11 +30,11 @@ pub struct KnownImgObjective {
 impl KnownImgObjective {
     /// Constructs a new [`KnownImgObjective`] from the provided parameters.
     pub fn new(
-        id: usize,
+        id: ImgObjectiveId,
         name: String,
         start: DateTime<Utc>,
         end: DateTime<Utc>,
-        zone: [i32; 4],
+        zone: ZoneRect,
         optic_required: CameraAngle,
         coverage_required: f64,
     ) -> KnownImgObjective {
@@ -42,38 +42,48 @@ impl KnownImgObjective {
     }
 
     /// Returns the unique identifier of the objective.
-    pub fn id(&self) -> usize { self.id }
+    pub fn id(&self) -> ImgObjectiveId { self.id }
     /// Returns the start time of the objective.
     pub fn start(&self) -> DateTime<Utc> { self.start }
     /// Returns the end time of the objective.
     pub fn end(&self) -> DateTime<Utc> { self.end }
     /// Returns the human-readable name of the objective.
     pub fn name(&self) -> &str { &self.name }
-    /// Returns the zone coordinates of the objective.
-    pub fn zone(&self) -> [i32; 4] { self.zone }
+    /// Returns the zone of the objective.
+    pub fn zone(&self) -> ZoneRect { self.zone }
     /// Returns the required camera angle for the objective.
     pub fn optic_required(&self) -> CameraAngle { self.optic_required }
     /// Returns the coverage percentage required for the objective.
     pub fn coverage_required(&self) -> f64 { self.coverage_required }
     /// Returns the width of the zone.
-    pub fn width(&self) -> i32 { self.zone[2] - self.zone[0] }
+    pub fn width(&self) -> i32 { self.zone.width() }
     /// Returns the height of the zone.
-    pub fn height(&self) -> i32 { self.zone[3] - self.zone[1] }
+    pub fn height(&self) -> i32 { self.zone.height() }
 
     /// Calculates the central point of the image zone and wraps it around the map if necessary.
-    pub fn get_single_image_point(&self) -> Vec2D<I32F32> {
-        let x_size = self.zone[2] - self.zone[0];
-        let y_size = self.zone[3] - self.zone[1];
-        let pos = Vec2D::new(self.zone[0] + x_size / 2, self.zone[1] + y_size / 2);
-        Vec2D::new(I32F32::from(pos.x()), I32F32::from(pos.y())).wrap_around_map()
+    pub fn get_single_image_point(&self) -> Vec2D<I32F32> { self.zone.center() }
+
+    /// Calculates the maximum deviation of the burn impact point from the planned imaging
+    /// position at which the zone is still imaged with the required coverage.
+    ///
+    /// For single-image objectives this is the slack of the lens footprint around the zone
+    /// plus the shrink of the zone that still satisfies the coverage requirement. Multi-image
+    /// objectives start at a zone corner, which stays within half a footprint.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn impact_margin(&self) -> I32F32 {
+        let lens_side = f64::from(self.optic_required.get_square_side_length());
+        if self.min_images() > 1 {
+            return I32F32::from_num(lens_side / 2.0);
+        }
+        let (width, height) = (f64::from(self.width()), f64::from(self.height()));
+        let slack = (lens_side - width.max(height)).max(0.0) / 2.0;
+        let shrink = (1.0 - self.coverage_required.sqrt()) * width.min(height);
+        I32F32::from_num(slack + shrink)
     }
 
     /// Returns the corners of the zone as pairs of points with their opposite corners.
     pub fn get_corners(&self) -> [(Vec2D<I32F32>, Vec2D<I32F32>); 4] {
-        let first = Vec2D::new(I32F32::from(self.zone[0]), I32F32::from(self.zone[1]));
-        let second = Vec2D::new(I32F32::from(self.zone[0]), I32F32::from(self.zone[3]));
-        let third = Vec2D::new(I32F32::from(self.zone[2]), I32F32::from(self.zone[1]));
-        let fourth = Vec2D::new(I32F32::from(self.zone[2]), I32F32::from(self.zone[3]));
+        let [first, second, third, fourth] = self.zone.corners();
         [
             (first, first.unwrapped_to(&fourth)),
             (second, second.unwrapped_to(&third)),
@@ -89,10 +99,7 @@ impl KnownImgObjective {
     #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
     pub fn min_images(&self) -> i32 {
         let lens_square_side_length = u32::from(self.optic_required().get_square_side_length());
-        let zone_width = self.zone[2] - self.zone[0];
-        let zone_height = self.zone[3] - self.zone[1];
-
-        let total_zone_area_size = f64::from(zone_width * zone_height);
+        let total_zone_area_size = f64::from(self.zone.area());
         let lens_area_size = f64::from(lens_square_side_length.pow(2));
         let min_area_required = total_zone_area_size * self.coverage_required;
 
@@ -127,14 +134,14 @@ impl TryFrom<ImageObjective> for KnownImgObjective {
     }
 }
 
-impl TryFrom<(ImageObjective, [i32; 4])> for KnownImgObjective {
+impl TryFrom<(ImageObjective, ZoneRect)> for KnownImgObjective {
     type Error = std::io::Error;
 
     /// Attempts to convert a tuple of `(ImageObjective, zone)` into a [`KnownImgObjective`].
     ///
     /// # Errors
     /// Returns an error if the `ImageObjective` is of type `KnownZone`.
-    fn try_from(obj_with_zone: (ImageObjective, [i32; 4])) -> Result<Self, Self::Error> {
+    fn try_from(obj_with_zone: (ImageObjective, ZoneRect)) -> Result<Self, Self::Error> {
         let obj = obj_with_zone.0;
         match obj.zone_type() {
             ZoneType::SecretZone(_) => Ok(Self {
@@ -7,15 +7,30 @@ mod beacon_objective_done;
 mod known_img_objective;
 mod secret_img_objective;
 mod bayesian_set;
+mod beacon_calibration;
 mod beacon_controller;
+mod beacon_heatmap;
+mod beacon_state_store;
+mod objective_priority;
+mod objective_tracker;
+mod score_ledger;
 
 use bayesian_set::BayesianSet;
+pub use bayesian_set::PosteriorStats;
 use beacon_objective::BeaconMeas;
+use beacon_calibration::{BeaconCalibration, DistanceModel};
 
 pub use beacon_objective::BeaconObjective;
+pub use beacon_objective::MeasConfidence;
 pub use known_img_objective::KnownImgObjective;
+pub use secret_img_objective::SecretHunt;
 pub use beacon_controller::BeaconController;
 pub use beacon_controller::BeaconControllerState;
+pub use beacon_heatmap::BeaconHeatmap;
+pub use objective_priority::ObjectivePriority;
+pub(crate) use objective_tracker::{LifecycleStage, OBJECTIVE_TRACKER};
+pub use score_ledger::ScoreLedger;
+pub use score_ledger::ScoreSource;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,80 @@
+use super::KnownImgObjective;
+use crate::flight_control::FlightComputer;
+use crate::util::{ImgObjectiveId, MissionConfig};
+use fixed::types::I32F32;
+use std::fmt::{Display, Formatter};
+
+/// Value and feasibility estimate used to rank concurrent zoned objectives.
+///
+/// The value of an objective are its expected points, discounted by the number of images
+/// needed to cover its zone, as every additional image is another chance to miss the required
+/// coverage. The score further discounts the value by the share of the usable fuel that the
+/// best exit burn found by the
+/// [`BurnSequenceEvaluator`](crate::flight_control::orbit::BurnSequenceEvaluator) consumes.
+/// The usable fuel is the full tank less the `runtime.fuel_safety_margin` and the
+/// `runtime.envelope_fuel_floor`. Objectives without a feasible exit burn have no score.
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+pub struct ObjectivePriority {
+    /// The ID of the ranked objective.
+    id: ImgObjectiveId,
+    /// The expected points of the objective.
+    points: f64,
+    /// The minimum number of images needed to cover the zone.
+    images: i32,
+    /// The fuel needed by the exit burn, `None` if no feasible burn was found.
+    fuel: Option<f64>,
+    /// The points discounted by the size of the zone.
+    value: f64,
+    /// The value discounted by the fuel of the exit burn.
+    score: Option<f64>,
+}
+
+impl ObjectivePriority {
+    /// Estimates the priority of a zoned objective.
+    ///
+    /// # Arguments
+    /// * `obj` – The zoned objective.
+    /// * `burn_fuel` – The fuel needed by the exit burn, `None` if no feasible burn was found.
+    ///
+    /// # Returns
+    /// The estimated [`ObjectivePriority`].
+    pub fn new(obj: &KnownImgObjective, burn_fuel: Option<I32F32>) -> Self {
+        let config = MissionConfig::get();
+        let points = config.scoring.zo_points;
+        let images = obj.min_images().max(1);
+        let value = points / f64::from(images).sqrt();
+        let fuel = burn_fuel.map(I32F32::to_num::<f64>);
+        let usable = Self::usable_fuel(&config);
+        let score = fuel.map(|f| value * (1.0 - f / usable).clamp(0.0, 1.0));
+        Self { id: obj.id(), points, images, fuel, value, score }
+    }
+
+    /// Returns the fuel of a full tank that may be burned by maneuvers at all.
+    ///
+    /// # Arguments
+    /// * `config` – The mission configuration.
+    pub fn usable_fuel(config: &MissionConfig) -> f64 {
+        let max = FlightComputer::MAX_100.to_num::<f64>();
+        let reserved = config.runtime.fuel_safety_margin + config.runtime.envelope_fuel_floor;
+        (max - reserved).max(f64::EPSILON)
+    }
+
+    /// Returns the ID of the ranked objective.
+    pub fn id(&self) -> ImgObjectiveId { self.id }
+    /// Returns the points discounted by the size of the zone.
+    pub fn value(&self) -> f64 { self.value }
+    /// Returns the value discounted by the exit burn fuel, `None` if the objective is
+    /// infeasible.
+    pub fn score(&self) -> Option<f64> { self.score }
+}
+
+impl Display for ObjectivePriority {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (points, images, value) = (self.points, self.images, self.value);
+        write!(f, "{points:.0} points over {images} image(s), value {value:.1}")?;
+        match (self.fuel, self.score) {
+            (Some(fuel), Some(score)) => write!(f, ", {fuel:.1} fuel, score {score:.1}"),
+            _ => write!(f, ", no feasible exit burn"),
+        }
+    }
+}
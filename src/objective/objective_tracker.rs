@@ -0,0 +1,267 @@
+use crate::obj;
+use crate::util::{BeaconObjectiveId, ImgObjectiveId, logger::JsonDump};
+use chrono::{DateTime, Utc};
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Formatter},
+    sync::{LazyLock, Mutex},
+};
+use strum_macros::Display;
+
+/// The process-wide record of objective lifecycles.
+pub(crate) static OBJECTIVE_TRACKER: LazyLock<ObjectiveTracker> =
+    LazyLock::new(ObjectiveTracker::new);
+
+/// An objective whose lifecycle is tracked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize)]
+#[serde(tag = "kind", content = "id", rename_all = "snake_case")]
+pub(crate) enum TrackedObjective {
+    /// A zoned objective, including secret ones.
+    Zoned(ImgObjectiveId),
+    /// A beacon objective.
+    Beacon(BeaconObjectiveId),
+}
+
+impl TrackedObjective {
+    /// Returns the short name of the objective kind.
+    pub(crate) fn kind(self) -> &'static str {
+        match self {
+            Self::Zoned(_) => "zo",
+            Self::Beacon(_) => "bo",
+        }
+    }
+
+    /// Returns the raw DRS identifier of the objective.
+    pub(crate) fn raw_id(self) -> usize {
+        match self {
+            Self::Zoned(id) => id.raw(),
+            Self::Beacon(id) => id.raw(),
+        }
+    }
+}
+
+impl From<ImgObjectiveId> for TrackedObjective {
+    fn from(id: ImgObjectiveId) -> Self { Self::Zoned(id) }
+}
+
+impl From<BeaconObjectiveId> for TrackedObjective {
+    fn from(id: BeaconObjectiveId) -> Self { Self::Beacon(id) }
+}
+
+impl Display for TrackedObjective {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Zoned(id) => write!(f, "Zoned Objective {id}"),
+            Self::Beacon(id) => write!(f, "Beacon {id}"),
+        }
+    }
+}
+
+/// A stage in the lifecycle of an objective.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub(crate) enum LifecycleStage {
+    /// The objective was received from the DRS.
+    Discovered,
+    /// A mode committed to the objective, or the beacon is actively scanned.
+    Scheduled,
+    /// The exit burn towards the objective was added to the task schedule.
+    BurnPlanned,
+    /// Images of the objective zone are acquired.
+    Imaging,
+    /// The objective image or the beacon guesses were submitted.
+    Uploaded,
+    /// Points were earned for the objective.
+    Completed,
+    /// The objective can not be completed anymore.
+    Failed,
+}
+
+impl LifecycleStage {
+    /// Returns `true` if no further stages follow.
+    pub(crate) fn is_terminal(self) -> bool { matches!(self, Self::Completed | Self::Failed) }
+}
+
+/// A single recorded stage transition of an objective.
+#[derive(Debug, Clone, serde::Serialize)]
+pub(crate) struct LifecycleTransition {
+    /// The time of the transition.
+    t: DateTime<Utc>,
+    /// The stage that was entered.
+    stage: LifecycleStage,
+    /// The reason of a failure.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+}
+
+impl LifecycleTransition {
+    /// Returns the time of the transition.
+    pub(crate) fn t(&self) -> DateTime<Utc> { self.t }
+
+    /// Returns the stage that was entered.
+    pub(crate) fn stage(&self) -> LifecycleStage { self.stage }
+
+    /// Returns the reason of a failure.
+    pub(crate) fn reason(&self) -> Option<&str> { self.reason.as_deref() }
+}
+
+/// The recorded stage transitions of a single objective.
+#[derive(Debug, Clone, serde::Serialize)]
+pub(crate) struct ObjectiveLifecycle {
+    /// The tracked objective.
+    objective: TrackedObjective,
+    /// The stage transitions, oldest first.
+    transitions: Vec<LifecycleTransition>,
+}
+
+impl ObjectiveLifecycle {
+    /// Returns the tracked objective.
+    pub(crate) fn objective(&self) -> TrackedObjective { self.objective }
+
+    /// Returns the current stage of the objective.
+    pub(crate) fn stage(&self) -> Option<LifecycleStage> {
+        self.transitions.last().map(LifecycleTransition::stage)
+    }
+
+    /// Returns the stage transitions, oldest first.
+    pub(crate) fn transitions(&self) -> &[LifecycleTransition] { &self.transitions }
+}
+
+/// Serializable snapshot of all lifecycles for the JSON dump.
+#[derive(serde::Serialize)]
+struct LifecycleDump<'a> {
+    /// All tracked lifecycles ordered by objective.
+    lifecycles: Vec<&'a ObjectiveLifecycle>,
+}
+
+impl JsonDump for LifecycleDump<'_> {
+    /// Returns the file name for the JSON dump of the objective lifecycles.
+    fn file_name(&self) -> String { "lifecycles".to_string() }
+
+    /// Returns the directory name for the objective lifecycle JSON files.
+    fn dir_name(&self) -> &'static str { "objectives" }
+}
+
+/// Tracks the lifecycle of every objective from its discovery to its completion or failure.
+///
+/// Objectives pass through the objective supervisor, the mode selection, the exit burn and
+/// the imaging until they are uploaded and scored. Every stage transition is logged and the
+/// lifecycles are dumped as JSON on each change, so that lost objectives can be traced.
+/// Stages may repeat when an objective is deferred and retried, but nothing is recorded after
+/// a terminal stage.
+pub(crate) struct ObjectiveTracker {
+    /// The lifecycles by objective.
+    lifecycles: Mutex<BTreeMap<TrackedObjective, ObjectiveLifecycle>>,
+}
+
+impl ObjectiveTracker {
+    /// Creates a new, empty [`ObjectiveTracker`].
+    fn new() -> Self { Self { lifecycles: Mutex::new(BTreeMap::new()) } }
+
+    /// Records that an objective entered a stage.
+    ///
+    /// # Arguments
+    /// * `objective` – The objective, or its ID.
+    /// * `stage` – The stage that was entered.
+    pub(crate) fn record(&self, objective: impl Into<TrackedObjective>, stage: LifecycleStage) {
+        self.advance(objective.into(), stage, None);
+    }
+
+    /// Records that an objective can not be completed anymore.
+    ///
+    /// # Arguments
+    /// * `objective` – The objective, or its ID.
+    /// * `reason` – Why the objective failed.
+    pub(crate) fn fail(&self, objective: impl Into<TrackedObjective>, reason: &str) {
+        self.advance(objective.into(), LifecycleStage::Failed, Some(reason));
+    }
+
+    /// Appends a stage transition unless the objective already is in that or a terminal stage.
+    ///
+    /// # Arguments
+    /// * `objective` – The objective.
+    /// * `stage` – The stage that was entered.
+    /// * `reason` – The reason of a failure.
+    fn advance(&self, objective: TrackedObjective, stage: LifecycleStage, reason: Option<&str>) {
+        let mut lifecycles = self.lifecycles.lock().unwrap();
+        let lifecycle = lifecycles
+            .entry(objective)
+            .or_insert_with(|| ObjectiveLifecycle { objective, transitions: Vec::new() });
+        if lifecycle.stage().is_some_and(|last| last == stage || last.is_terminal()) {
+            return;
+        }
+        lifecycle.transitions.push(LifecycleTransition {
+            t: Utc::now(),
+            stage,
+            reason: reason.map(str::to_string),
+        });
+        match reason {
+            Some(why) => obj!("{objective} lifecycle: {stage} ({why})."),
+            None => obj!("{objective} lifecycle: {stage}."),
+        }
+        LifecycleDump { lifecycles: lifecycles.values().collect() }.dump_json();
+    }
+
+    /// Returns the current stage of an objective.
+    ///
+    /// # Arguments
+    /// * `objective` – The objective, or its ID.
+    pub(crate) fn stage(&self, objective: impl Into<TrackedObjective>) -> Option<LifecycleStage> {
+        self.lifecycles.lock().unwrap().get(&objective.into()).and_then(ObjectiveLifecycle::stage)
+    }
+
+    /// Returns a copy of all lifecycles ordered by objective.
+    pub(crate) fn lifecycles(&self) -> Vec<ObjectiveLifecycle> {
+        self.lifecycles.lock().unwrap().values().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_objective_lifecycle() {
+        let tracker = ObjectiveTracker::new();
+        let zo = ImgObjectiveId::new(3);
+        let bo = BeaconObjectiveId::new(3);
+        tracker.record(zo, LifecycleStage::Discovered);
+        tracker.record(zo, LifecycleStage::Scheduled);
+        tracker.record(zo, LifecycleStage::Scheduled);
+        tracker.record(bo, LifecycleStage::Discovered);
+        tracker.record(zo, LifecycleStage::BurnPlanned);
+        tracker.record(zo, LifecycleStage::Imaging);
+        tracker.record(zo, LifecycleStage::Uploaded);
+        tracker.record(zo, LifecycleStage::Completed);
+        tracker.fail(zo, "deleted by the backend");
+        tracker.fail(bo, "expired");
+        tracker.record(bo, LifecycleStage::Scheduled);
+
+        assert_eq!(tracker.stage(zo), Some(LifecycleStage::Completed));
+        assert_eq!(tracker.stage(bo), Some(LifecycleStage::Failed));
+        assert_eq!(tracker.stage(ImgObjectiveId::new(4)), None);
+
+        let lifecycles = tracker.lifecycles();
+        assert_eq!(lifecycles.len(), 2);
+        assert_eq!(lifecycles[0].objective(), TrackedObjective::Zoned(zo));
+        let transitions = lifecycles[0].transitions().iter();
+        let stages: Vec<_> = transitions.map(LifecycleTransition::stage).collect();
+        assert_eq!(stages, [
+            LifecycleStage::Discovered,
+            LifecycleStage::Scheduled,
+            LifecycleStage::BurnPlanned,
+            LifecycleStage::Imaging,
+            LifecycleStage::Uploaded,
+            LifecycleStage::Completed,
+        ]);
+        assert!(lifecycles[0].transitions().windows(2).all(|w| w[0].t() <= w[1].t()));
+        let failed = &lifecycles[1].transitions()[1];
+        assert_eq!(failed.reason(), Some("expired"));
+
+        let json = serde_json::to_value(&lifecycles[1]).unwrap();
+        assert_eq!(json["objective"], serde_json::json!({ "kind": "beacon", "id": 3 }));
+        assert_eq!(json["transitions"][0]["stage"], "discovered");
+        assert!(json["transitions"][0].get("reason").is_none());
+    }
+}
@@ -0,0 +1,177 @@
+use crate::util::{
+    BeaconObjectiveId, ImgObjectiveId, JournalEvent, MISSION_JOURNAL, MissionConfig,
+    logger::JsonDump,
+};
+use super::{LifecycleStage, OBJECTIVE_TRACKER};
+use crate::obj;
+use chrono::{DateTime, Utc};
+use std::fmt::{Display, Formatter};
+use tokio::sync::{RwLock, watch};
+
+/// The source of an estimated score entry.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub enum ScoreSource {
+    /// A successfully submitted zoned objective image.
+    ZonedObjective { id: ImgObjectiveId },
+    /// A beacon found after the given number of guesses.
+    Beacon { id: BeaconObjectiveId, guesses: usize },
+    /// A reached orbit coverage milestone.
+    CoverageMilestone { coverage: f64 },
+}
+
+impl Display for ScoreSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ZonedObjective { id } => write!(f, "Zoned Objective {id}"),
+            Self::Beacon { id, guesses } => write!(f, "Beacon {id} ({guesses} guesses)"),
+            Self::CoverageMilestone { coverage } => {
+                write!(f, "Coverage {:.0}%", coverage * 100.0)
+            }
+        }
+    }
+}
+
+/// A single estimated score entry.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ScoreEntry {
+    /// The time at which the points were earned.
+    pub t: DateTime<Utc>,
+    /// What the points were earned for.
+    pub source: ScoreSource,
+    /// The estimated points.
+    pub points: f64,
+}
+
+/// Internal state of the [`ScoreLedger`].
+#[derive(Debug, Default, serde::Serialize)]
+struct ScoreLedgerState {
+    /// All earned score entries in chronological order.
+    entries: Vec<ScoreEntry>,
+    /// The number of coverage milestones already reached.
+    milestones_reached: usize,
+    /// The cumulative expected score.
+    total: f64,
+}
+
+impl ScoreLedgerState {
+    /// Returns `true` if points were already booked for the given source id.
+    fn contains(&self, source: ScoreSource) -> bool {
+        self.entries.iter().any(|e| match (e.source, source) {
+            (ScoreSource::ZonedObjective { id: a }, ScoreSource::ZonedObjective { id: b }) => a == b,
+            (ScoreSource::Beacon { id: a, .. }, ScoreSource::Beacon { id: b, .. }) => a == b,
+            _ => false,
+        })
+    }
+}
+
+impl JsonDump for ScoreLedgerState {
+    /// Returns the file name for the JSON dump of the score ledger.
+    fn file_name(&self) -> String { "score_ledger".to_string() }
+
+    /// Returns the directory name for the score ledger JSON files.
+    fn dir_name(&self) -> &'static str { "score" }
+}
+
+/// Estimates the points earned per completed objective and coverage milestone
+/// according to the scoring rules in the [`MissionConfig`].
+///
+/// Every change of the cumulative score is published through a watch channel,
+/// so that e.g. the console can show a running score dashboard.
+pub struct ScoreLedger {
+    /// The locked ledger state.
+    state: RwLock<ScoreLedgerState>,
+    /// Watch sender publishing the cumulative expected score.
+    total_tx: watch::Sender<f64>,
+}
+
+impl ScoreLedger {
+    /// Creates a new, empty [`ScoreLedger`].
+    pub fn new() -> Self {
+        Self { state: RwLock::new(ScoreLedgerState::default()), total_tx: watch::Sender::new(0.0) }
+    }
+
+    /// Subscribes to changes of the cumulative expected score.
+    pub fn subscribe(&self) -> watch::Receiver<f64> { self.total_tx.subscribe() }
+
+    /// Returns the cumulative expected score.
+    pub async fn total(&self) -> f64 { self.state.read().await.total }
+
+    /// Returns a copy of all score entries.
+    pub async fn entries(&self) -> Vec<ScoreEntry> { self.state.read().await.entries.clone() }
+
+    /// Returns `true` if points were already booked for the given zoned objective.
+    ///
+    /// # Arguments
+    /// * `id` – The objective id.
+    pub async fn has_zo(&self, id: ImgObjectiveId) -> bool {
+        self.state.read().await.contains(ScoreSource::ZonedObjective { id })
+    }
+
+    /// Dumps the current ledger state.
+    pub async fn dump(&self) -> std::io::Result<()> { self.state.read().await.try_dump_json() }
+
+    /// Books the points for a successfully submitted zoned objective.
+    ///
+    /// # Arguments
+    /// * `id` – The objective id. Repeated submissions are only counted once.
+    pub async fn record_zo(&self, id: ImgObjectiveId) {
+        let points = MissionConfig::get().scoring.zo_points;
+        self.book(ScoreSource::ZonedObjective { id }, points).await;
+    }
+
+    /// Books the points for a found beacon.
+    ///
+    /// # Arguments
+    /// * `id` – The beacon objective id.
+    /// * `guesses` – The number of guesses needed, including the successful one.
+    pub async fn record_beacon(&self, id: BeaconObjectiveId, guesses: usize) {
+        let points = MissionConfig::get().scoring.beacon_points_for(guesses);
+        self.book(ScoreSource::Beacon { id, guesses }, points).await;
+    }
+
+    /// Books the points for all newly reached coverage milestones.
+    ///
+    /// # Arguments
+    /// * `coverage` – The current coverage fraction in `[0, 1]`.
+    pub async fn update_coverage(&self, coverage: f64) {
+        let milestones = &MissionConfig::get().scoring.coverage_milestones;
+        let reached = milestones.iter().take_while(|(c, _)| *c <= coverage).count();
+        let new = {
+            let state = self.state.read().await;
+            milestones.get(state.milestones_reached..reached).map(<[_]>::to_vec)
+        };
+        for (milestone, points) in new.unwrap_or_default() {
+            self.book(ScoreSource::CoverageMilestone { coverage: milestone }, points).await;
+        }
+    }
+
+    /// Adds a score entry, publishes the new total, journals it and dumps the ledger.
+    ///
+    /// Booked objectives are marked as completed in the [`OBJECTIVE_TRACKER`].
+    ///
+    /// # Arguments
+    /// * `source` – What the points were earned for.
+    /// * `points` – The estimated points.
+    async fn book(&self, source: ScoreSource, points: f64) {
+        let mut state = self.state.write().await;
+        if state.contains(source) {
+            return;
+        }
+        if matches!(source, ScoreSource::CoverageMilestone { .. }) {
+            state.milestones_reached += 1;
+        }
+        state.entries.push(ScoreEntry { t: Utc::now(), source, points });
+        state.total += points;
+        obj!("Earned {points:.0} points for {source}. Expected total: {:.0}", state.total);
+        MISSION_JOURNAL.record(JournalEvent::ObjectiveCompleted { source, points });
+        match source {
+            ScoreSource::ZonedObjective { id } => {
+                OBJECTIVE_TRACKER.record(id, LifecycleStage::Completed);
+            }
+            ScoreSource::Beacon { id, .. } => OBJECTIVE_TRACKER.record(id, LifecycleStage::Completed),
+            ScoreSource::CoverageMilestone { .. } => {}
+        }
+        state.dump_json();
+        self.total_tx.send_replace(state.total);
+    }
+}
@@ -1,18 +1,50 @@
+use super::KnownImgObjective;
+use crate::http_handler::{ImageObjective, ZoneType};
 use crate::imaging::CameraAngle;
+use crate::util::{ImgObjectiveId, MapSize, Vec2D, ZoneRect};
 use chrono::{DateTime, Utc};
+use fixed::types::I32F32;
+use regex::Regex;
+use std::{collections::HashMap, sync::LazyLock};
+
+/// Matches DRS upload responses reporting that the hidden zone was hit.
+static ZONE_FOUND_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b(found|detected|identified|matched|completed)\b").unwrap());
+/// Matches DRS upload responses that negate or reject a hit.
+static ZONE_MISSED_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b(not|no|outside|wrong|failed|insufficient)\b").unwrap());
 
 /// Represents an objective focused on capturing a secret image.
+///
+/// The zone of a secret objective is unknown. It is hunted by probing candidate regions of the
+/// size of the required lens footprint, each of which is imaged and uploaded like a zoned
+/// objective until the DRS confirms the hidden zone.
 #[derive(Debug, Clone)]
 pub struct SecretImgObjective {
-    id: usize,
+    /// The unique identifier of the objective.
+    id: ImgObjectiveId,
+    /// The name of the objective.
     name: String,
+    /// The start time of the objective.
     start: DateTime<Utc>,
+    /// The end time of the objective.
     end: DateTime<Utc>,
+    /// The camera angle required for the images.
     optic_required: CameraAngle,
-    coverage_required: f32,
+    /// The fraction of the hidden zone that must be covered.
+    coverage_required: f64,
+    /// The candidate regions that were already probed or found unreachable.
+    probed: Vec<ZoneRect>,
+    /// The candidate region confirmed by the DRS, if any.
+    found: Option<ZoneRect>,
+    /// The candidate region whose image upload is queued for a retry, if any.
+    queued: Option<ZoneRect>,
 }
 
 impl SecretImgObjective {
+    /// The maximum distance of a candidate region from the anchor, in footprints.
+    const SEARCH_RADIUS: i32 = 10;
+
     /// Creates a new [`SecretImgObjective`].
     ///
     /// # Arguments
@@ -26,13 +58,211 @@ impl SecretImgObjective {
     /// # Returns
     /// A new [`SecretImgObjective`] instance.
     pub fn new(
-        id: usize,
+        id: ImgObjectiveId,
         name: String,
         start: DateTime<Utc>,
         end: DateTime<Utc>,
         optic_required: CameraAngle,
-        coverage_required: f32,
+        coverage_required: f64,
     ) -> Self {
-        Self { id, name, start, end, optic_required, coverage_required }
+        let (probed, found, queued) = (vec![], None, None);
+        Self { id, name, start, end, optic_required, coverage_required, probed, found, queued }
+    }
+
+    /// Returns the unique identifier of the objective.
+    pub fn id(&self) -> ImgObjectiveId { self.id }
+    /// Returns the end time of the objective.
+    pub fn end(&self) -> DateTime<Utc> { self.end }
+    /// Returns the required camera angle for the objective.
+    pub fn optic_required(&self) -> CameraAngle { self.optic_required }
+    /// Returns the number of candidate regions probed so far.
+    pub fn probes(&self) -> usize { self.probed.len() }
+    /// Returns the candidate region confirmed by the DRS, if any.
+    pub fn found(&self) -> Option<ZoneRect> { self.found }
+
+    /// Returns `true` if the objective accepts images at `now`.
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool { self.start <= now && now < self.end }
+
+    /// Returns the nearest candidate region that was not probed yet.
+    ///
+    /// Candidates are the cells of a grid of lens footprints aligned to the map origin. The
+    /// cell containing the anchor, usually a point ahead on the ground track, is probed first,
+    /// followed by its neighbors in increasing distance.
+    ///
+    /// # Arguments
+    /// - `anchor`: The map position the sweep is centered on.
+    ///
+    /// # Returns
+    /// The candidate region, or `None` if all cells within the search radius were probed.
+    pub fn next_candidate(&self, anchor: Vec2D<I32F32>) -> Option<ZoneRect> {
+        let side = i32::from(self.optic_required.get_square_side_length());
+        let map = i32::map_size();
+        let wrapped = anchor.wrap_around_map();
+        let (ax, ay) = (wrapped.x().to_num::<i32>() / side, wrapped.y().to_num::<i32>() / side);
+        let (cells_x, cells_y) = ((map.x() + side - 1) / side, (map.y() + side - 1) / side);
+        let r = Self::SEARCH_RADIUS;
+        (-r..=r)
+            .flat_map(|dx| (-r..=r).map(move |dy| (dx, dy)))
+            .filter(|(dx, dy)| dx.abs() < cells_x && dy.abs() < cells_y)
+            .map(|(dx, dy)| {
+                let (cx, cy) = ((ax + dx).rem_euclid(cells_x), (ay + dy).rem_euclid(cells_y));
+                let offset = Vec2D::new(cx * side, cy * side);
+                (dx * dx + dy * dy, ZoneRect::from_offset(offset, Vec2D::new(side, side)))
+            })
+            .filter(|(_, zone)| !self.probed.contains(zone) && self.queued != Some(*zone))
+            .min_by_key(|(dist, _)| *dist)
+            .map(|(_, zone)| zone)
+    }
+
+    /// Creates the zoned objective imaging a candidate region for this objective.
+    ///
+    /// # Arguments
+    /// - `zone`: The candidate region.
+    pub fn probe(&self, zone: ZoneRect) -> KnownImgObjective {
+        KnownImgObjective::new(
+            self.id,
+            self.name.clone(),
+            self.start,
+            self.end,
+            zone,
+            self.optic_required,
+            self.coverage_required,
+        )
+    }
+
+    /// Records the outcome of probing a candidate region.
+    ///
+    /// # Arguments
+    /// - `zone`: The probed candidate region.
+    /// - `response`: The DRS response to the uploaded image, `None` if nothing was uploaded.
+    ///
+    /// # Returns
+    /// `true` if the DRS confirmed the hidden zone.
+    pub fn record_probe(&mut self, zone: ZoneRect, response: Option<&str>) -> bool {
+        self.probed.push(zone);
+        if response.is_some_and(Self::zone_found) {
+            self.found = Some(zone);
+        }
+        self.found.is_some()
+    }
+
+    /// Evaluates whether a DRS upload response confirms that the hidden zone was covered.
+    ///
+    /// The DRS answers every upload with a free text message, so a hit is only assumed if the
+    /// message reports the zone as found without negating it.
+    ///
+    /// # Arguments
+    /// - `response`: The response message of the image upload.
+    pub fn zone_found(response: &str) -> bool {
+        ZONE_FOUND_REGEX.is_match(response) && !ZONE_MISSED_REGEX.is_match(response)
+    }
+}
+
+impl TryFrom<&ImageObjective> for SecretImgObjective {
+    type Error = std::io::Error;
+
+    /// Attempts to convert an [`ImageObjective`] into a [`SecretImgObjective`].
+    ///
+    /// # Errors
+    /// Returns an error if the provided [`ImageObjective`] is of type `KnownZone`.
+    fn try_from(obj: &ImageObjective) -> Result<Self, Self::Error> {
+        match obj.zone_type() {
+            ZoneType::SecretZone(_) => Ok(Self::new(
+                obj.id(),
+                String::from(obj.name()),
+                obj.start(),
+                obj.end(),
+                CameraAngle::from(obj.optic_required()),
+                obj.coverage_required(),
+            )),
+            ZoneType::KnownZone(_) => Err(std::io::Error::other("Wrong objective conversion!")),
+        }
+    }
+}
+
+/// The state of the autonomous search for the zones of all pending secret objectives.
+#[derive(Debug, Default)]
+pub struct SecretHunt {
+    /// The hunted secret objectives by ID.
+    hunts: HashMap<ImgObjectiveId, SecretImgObjective>,
+}
+
+impl SecretHunt {
+    /// Synchronizes the hunted objectives with the secret objectives pending at the supervisor.
+    ///
+    /// New objectives are added, while objectives that expired, were found or were assigned a
+    /// zone by the operator are dropped.
+    ///
+    /// # Arguments
+    /// - `pending`: The secret objectives that still await a zone.
+    /// - `now`: The current time.
+    pub(crate) fn sync(&mut self, pending: &[ImageObjective], now: DateTime<Utc>) {
+        self.hunts.retain(|id, hunt| {
+            hunt.end > now && hunt.found.is_none() && pending.iter().any(|obj| obj.id() == *id)
+        });
+        for obj in pending.iter().filter(|obj| obj.end() > now) {
+            if let (false, Ok(hunt)) = (self.hunts.contains_key(&obj.id()), obj.try_into()) {
+                self.hunts.insert(obj.id(), hunt);
+            }
+        }
+    }
+
+    /// Returns `true` if the objective is hunted.
+    pub fn contains(&self, id: ImgObjectiveId) -> bool { self.hunts.contains_key(&id) }
+
+    /// Returns the next probe of the active objective ending first that has probes left.
+    ///
+    /// # Arguments
+    /// - `anchor`: The map position the sweep is centered on.
+    /// - `max_probes`: The maximum number of probes per objective.
+    /// - `now`: The current time.
+    pub fn next_probe(
+        &self,
+        anchor: Vec2D<I32F32>,
+        max_probes: usize,
+        now: DateTime<Utc>,
+    ) -> Option<KnownImgObjective> {
+        let mut active: Vec<_> = self
+            .hunts
+            .values()
+            .filter(|hunt| hunt.is_active(now) && hunt.probes() < max_probes)
+            .collect();
+        active.sort_by_key(|hunt| hunt.end());
+        active
+            .into_iter()
+            .find_map(|hunt| hunt.next_candidate(anchor).map(|zone| hunt.probe(zone)))
+    }
+
+    /// Records the outcome of a probe, see [`SecretImgObjective::record_probe`].
+    ///
+    /// # Returns
+    /// `true` if the DRS confirmed the hidden zone of the objective.
+    pub fn record_probe(
+        &mut self,
+        id: ImgObjectiveId,
+        zone: ZoneRect,
+        response: Option<&str>,
+    ) -> bool {
+        self.hunts.get_mut(&id).is_some_and(|hunt| hunt.record_probe(zone, response))
+    }
+
+    /// Remembers the candidate region of a probe whose image upload was queued for a retry,
+    /// so that the retried upload can still be evaluated as a probe.
+    ///
+    /// # Arguments
+    /// - `id`: The ID of the secret objective.
+    /// - `zone`: The probed candidate region.
+    pub fn queue_probe(&mut self, id: ImgObjectiveId, zone: ZoneRect) {
+        if let Some(hunt) = self.hunts.get_mut(&id) {
+            hunt.queued = Some(zone);
+        }
+    }
+
+    /// Takes the candidate region of a probe whose image upload was queued for a retry.
+    ///
+    /// # Arguments
+    /// - `id`: The ID of the secret objective.
+    pub fn take_queued_probe(&mut self, id: ImgObjectiveId) -> Option<ZoneRect> {
+        self.hunts.get_mut(&id).and_then(|hunt| hunt.queued.take())
     }
 }
@@ -1,14 +1,35 @@
-use super::{bayesian_set::BayesianSet, BeaconMeas};
-use crate::util::{Vec2D, MapSize};
+use super::{
+    bayesian_set::BayesianSet, BeaconCalibration, BeaconController, BeaconControllerState,
+    BeaconMeas, BeaconObjective, KnownImgObjective, MeasConfidence, ObjectivePriority, ScoreLedger,
+    secret_img_objective::{SecretHunt, SecretImgObjective},
+};
+use crate::http_handler::ImageObjective;
+use crate::imaging::CameraAngle;
+use crate::util::{BeaconObjectiveId, ImgObjectiveId, MapSize, MissionConfig, Vec2D, ZoneRect};
 use crate::STATIC_ORBIT_VEL;
-use chrono::TimeDelta;
+use std::{
+    path::PathBuf,
+    sync::{Arc, atomic::{AtomicUsize, Ordering}},
+};
+use chrono::{TimeDelta, Utc};
 use fixed::types::I32F32;
 use num::traits::FloatConst;
-use rand::{Rng, rng};
+use rand::{Rng, SeedableRng, rng, rngs::StdRng};
+use tokio::sync::mpsc;
 
 const MAX_MEASURE_POINTS: usize = 6;
 const MEASURE_PERIOD: I32F32 = I32F32::lit("60");
 const MELVIN_SIM_STEP: (I32F32, I32F32) = STATIC_ORBIT_VEL;
+const CONVERGED_GUESSES: usize = 30;
+
+/// Returns a fresh directory for the persisted beacon state of a test controller.
+fn beacon_state_dir() -> PathBuf {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    let n = NEXT.fetch_add(1, Ordering::Relaxed);
+    let dir = std::env::temp_dir().join(format!("melvin_bo_state_{}_{n}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
 
 fn get_d_noisy(d_true: f32) -> f32 {
     let rand_k = rng().random_range(-1.0..=1.0);
@@ -56,7 +77,7 @@ fn test_bayesian_filter() {
     println!("STEP 0: {pos}\n\t Distance: {d_true}");
     let noisy = get_d_noisy(d_true);
     println!("\t Distance Noisy: {noisy}");
-    let meas = BeaconMeas::new(0, pos, f64::from(noisy), TimeDelta::zero());
+    let meas = BeaconMeas::new(BeaconObjectiveId::new(0), pos, f64::from(noisy), TimeDelta::zero());
     let mut bayesian_set = BayesianSet::new(meas);
     let min_guesses = bayesian_set.guess_estimate();
     println!("\t Minimum Guesses: {min_guesses}");
@@ -73,7 +94,7 @@ fn test_bayesian_filter() {
         };
         let noisy = get_d_noisy(d_true);
         println!("\t Distance Noisy: {noisy}");
-        let b_meas = BeaconMeas::new(0, *pos, f64::from(noisy), TimeDelta::zero());
+        let b_meas = BeaconMeas::new(BeaconObjectiveId::new(0), *pos, f64::from(noisy), TimeDelta::zero());
         bayesian_set.update(&b_meas);
         let min_guesses = bayesian_set.guess_estimate();
         println!("\t Minimum Guesses: {min_guesses}");
@@ -95,3 +116,392 @@ fn test_bayesian_filter() {
         }
     }
 }
+
+/// Noise model applied to the true beacon distance of a synthetic ping.
+#[derive(Clone, Copy)]
+enum PingNoise {
+    /// The exact distance is reported.
+    Exact,
+    /// Uniform noise as applied by the backend, bounded by `K_ADD + 0.1 * (d + 1)`.
+    Backend,
+}
+
+/// A synthetic beacon scenario: a hidden beacon and MELVIN passing by on a static orbit,
+/// receiving a ping every `ping_period` seconds.
+struct BeaconScenario {
+    /// Objective ID used in the generated announcements.
+    id: BeaconObjectiveId,
+    /// The true (hidden) beacon position.
+    beacon_pos: Vec2D<I32F32>,
+    /// MELVINs position at the first ping.
+    start_pos: Vec2D<I32F32>,
+    /// Seconds between two consecutive pings.
+    ping_period: I32F32,
+    /// Number of pings that are attempted.
+    pings: usize,
+    /// Applied noise model.
+    noise: PingNoise,
+}
+
+impl BeaconScenario {
+    /// Creates a scenario with a beacon placed at a random offset from MELVINs path.
+    fn random(id: usize, noise: PingNoise, rng: &mut StdRng) -> Self {
+        let start_pos = Vec2D::new(
+            I32F32::from_num(rng.random_range(0..21600)),
+            I32F32::from_num(rng.random_range(0..10800)),
+        );
+        let along = Vec2D::from(MELVIN_SIM_STEP) * MEASURE_PERIOD * I32F32::from_num(2);
+        let side = Vec2D::new(
+            I32F32::from_num(rng.random_range(-800..=800)),
+            I32F32::from_num(rng.random_range(-800..=800)),
+        );
+        let beacon_pos = (start_pos + along + side).round().wrap_around_map();
+        Self { id: BeaconObjectiveId::new(id), beacon_pos, start_pos, ping_period: MEASURE_PERIOD, pings: 6, noise }
+    }
+
+    /// Generates the positions of MELVIN and the announcement messages of all pings
+    /// that are within the beacon range.
+    fn generate(&self, rng: &mut StdRng) -> Vec<(Vec2D<I32F32>, String)> {
+        self.pings(rng)
+            .into_iter()
+            .map(|(pos, d)| (pos, format!("GALILEO_MSG_EB,ID_{},DISTANCE_{d:.1}", self.id)))
+            .collect()
+    }
+
+    /// Generates the positions of MELVIN and the noisy distances of all pings that are
+    /// within the beacon range.
+    fn pings(&self, rng: &mut StdRng) -> Vec<(Vec2D<I32F32>, f32)> {
+        (0..self.pings)
+            .map(|i| {
+                let step = Vec2D::from(MELVIN_SIM_STEP) * self.ping_period * I32F32::from_num(i);
+                (self.start_pos + step).wrap_around_map().floor()
+            })
+            .filter_map(|pos| {
+                let d_true = pos.unwrapped_to(&self.beacon_pos).abs().to_num::<f32>();
+                if d_true > BayesianSet::MAX_DIST.to_num::<f32>() {
+                    return None;
+                }
+                let d_noisy = match self.noise {
+                    PingNoise::Exact => d_true,
+                    PingNoise::Backend => {
+                        let rand_k = rng.random_range(-1.0..=1.0);
+                        let k_add = BayesianSet::K_ADD.to_num::<f32>();
+                        let noise = rand_k * (k_add + 0.1 * (d_true + 1.0));
+                        (d_true + noise).max(0.0)
+                    }
+                };
+                Some((pos, d_noisy))
+            })
+            .collect()
+    }
+}
+
+/// Drives a [`BeaconController`] through the announcement pathway with a synthetic scenario.
+/// Asserts that the guess estimate never grows with additional pings.
+///
+/// # Returns
+/// The number of pings until the estimate converged (if it did) and the final guess error.
+async fn run_beacon_scenario(
+    scenario: &BeaconScenario,
+    rng: &mut StdRng,
+) -> (Option<usize>, f32) {
+    let (_tx, rx) = mpsc::channel(1);
+    let state_dir = beacon_state_dir();
+    let (controller, _state_rx) =
+        BeaconController::new(rx, Arc::new(ScoreLedger::new()), &state_dir);
+    let bo = BeaconObjective::new(
+        scenario.id,
+        String::from("synthetic"),
+        Utc::now(),
+        Utc::now() + TimeDelta::hours(1),
+    );
+    controller.add_beacon(bo).await;
+
+    let mut converged_after = None;
+    let mut last_guesses = usize::MAX;
+    for (i, (pos, msg)) in scenario.generate(rng).into_iter().enumerate() {
+        controller.handle_poss_bo_ping_at((Utc::now(), msg), pos, MeasConfidence::Full).await;
+        let guesses = controller.active_guess_estimate(scenario.id).await.unwrap();
+        assert!(guesses <= last_guesses);
+        last_guesses = guesses;
+        if converged_after.is_none() && guesses <= CONVERGED_GUESSES {
+            converged_after = Some(i + 1);
+        }
+    }
+    let centers = controller.active_guess_centers(scenario.id).await.unwrap();
+    let err = centers
+        .iter()
+        .map(|c| c.unwrapped_to(&scenario.beacon_pos).abs().to_num::<f32>())
+        .fold(f32::MAX, f32::min);
+    std::fs::remove_dir_all(&state_dir).unwrap();
+    (converged_after, err)
+}
+
+#[tokio::test]
+async fn test_beacon_controller_exact_pings() {
+    let mut rng = StdRng::seed_from_u64(17);
+    for id in 0..2 {
+        let scenario = BeaconScenario::random(id, PingNoise::Exact, &mut rng);
+        let (converged_after, err) = run_beacon_scenario(&scenario, &mut rng).await;
+        println!("Scenario {id}: converged after {converged_after:?} pings, error {err}");
+        assert!(converged_after.is_some_and(|n| n <= 4));
+        assert!(err < BayesianSet::MAX_RES_UNCERTAINTY_RAD);
+    }
+}
+
+#[tokio::test]
+async fn test_beacon_controller_backend_noise() {
+    let mut rng = StdRng::seed_from_u64(42);
+    for id in 0..2 {
+        let scenario = BeaconScenario::random(id, PingNoise::Backend, &mut rng);
+        let (converged_after, err) = run_beacon_scenario(&scenario, &mut rng).await;
+        println!("Scenario {id}: converged after {converged_after:?} pings, error {err}");
+        // The noisy distances may take one ping more than exact ones
+        assert!(converged_after.is_some_and(|n| n <= 5));
+        assert!(err < BayesianSet::MAX_RES_UNCERTAINTY_RAD);
+    }
+}
+
+#[tokio::test]
+async fn test_beacon_state_restore() {
+    let mut rng = StdRng::seed_from_u64(11);
+    let scenario = BeaconScenario::random(7, PingNoise::Backend, &mut rng);
+    let state_dir = beacon_state_dir();
+    let announce = || {
+        let end = Utc::now() + TimeDelta::hours(1);
+        BeaconObjective::new(scenario.id, String::from("synthetic"), Utc::now(), end)
+    };
+    let (_tx, rx) = mpsc::channel(1);
+    let (controller, _state_rx) =
+        BeaconController::new(rx, Arc::new(ScoreLedger::new()), &state_dir);
+    controller.add_beacon(announce()).await;
+    for (pos, msg) in scenario.generate(&mut rng) {
+        controller.handle_poss_bo_ping_at((Utc::now(), msg), pos, MeasConfidence::Full).await;
+    }
+    let sorted_centers = |mut centers: Vec<Vec2D<I32F32>>| {
+        centers.sort_by_key(|c| (c.x(), c.y()));
+        centers
+    };
+    let estimate = controller.active_guess_estimate(scenario.id).await;
+    let centers = controller.active_guess_centers(scenario.id).await.map(sorted_centers);
+    assert!(estimate.is_some());
+
+    // A restarted controller resumes the hunt, even once the backend announces it again
+    let (_tx, rx) = mpsc::channel(1);
+    let (restored, state_rx) =
+        BeaconController::new(rx, Arc::new(ScoreLedger::new()), &state_dir);
+    assert!(matches!(*state_rx.borrow(), BeaconControllerState::ActiveBeacons));
+    restored.add_beacon(announce()).await;
+    assert_eq!(restored.active_guess_estimate(scenario.id).await, estimate);
+    let restored_centers = restored.active_guess_centers(scenario.id).await;
+    assert_eq!(restored_centers.map(sorted_centers), centers);
+    std::fs::remove_dir_all(&state_dir).unwrap();
+}
+
+#[test]
+fn test_bayesian_set_snapshot_at_seam() {
+    let pos = Vec2D::new(I32F32::from_num(5), I32F32::from_num(5));
+    let mut set =
+        BayesianSet::new(BeaconMeas::new(BeaconObjectiveId::new(0), pos, 500.0, TimeDelta::zero()));
+    let pos_2 = Vec2D::new(I32F32::from_num(21500), I32F32::from_num(40));
+    set.update(&BeaconMeas::new(BeaconObjectiveId::new(0), pos_2, 450.0, TimeDelta::zero()));
+    let restored = BayesianSet::from_snapshot(set.snapshot()).unwrap();
+    assert_eq!(restored.len(), set.len());
+    assert_eq!(restored.measurements().len(), 2);
+    assert_eq!(restored.credible_centroid(), set.credible_centroid());
+    assert_eq!(restored.bounds(), set.bounds());
+}
+
+#[test]
+fn test_beacon_calibration_refit() {
+    let mut rng = StdRng::seed_from_u64(7);
+    let mut calibration = BeaconCalibration::new();
+    let true_k_add = 40.0;
+    let uncertainty = I32F32::from_num(BayesianSet::MAX_RES_UNCERTAINTY_RAD);
+    let mut checks = Vec::new();
+    for id in 0..3 {
+        let scenario = BeaconScenario::random(id, PingNoise::Exact, &mut rng);
+        let meas: Vec<BeaconMeas> = scenario
+            .generate(&mut rng)
+            .into_iter()
+            .map(|(pos, _)| {
+                let d_true = pos.unwrapped_to(&scenario.beacon_pos).abs().to_num::<f32>();
+                let noise = rng.random_range(-1.0..=1.0) * (true_k_add + 0.05 * d_true);
+                let d_noisy = f64::from((d_true + noise).max(0.0));
+                BeaconMeas::new(scenario.id, pos, d_noisy, TimeDelta::zero())
+            })
+            .collect();
+        let offset = Vec2D::new(I32F32::from_num(40), I32F32::from_num(-30));
+        let found_guess = (scenario.beacon_pos + offset).wrap_around_map();
+        calibration.add_checkpoint(scenario.id, &meas, found_guess, uncertainty);
+        checks.push((meas, scenario.beacon_pos));
+    }
+    let model = calibration.model();
+    assert!(model.k_add() < BayesianSet::K_ADD);
+    for (meas, beacon_pos) in checks {
+        for m in meas {
+            let d_true = m.pos().unwrapped_to(&beacon_pos).abs();
+            let (min_dist, max_dist) = model.dists(I32F32::from_num(m.rssi()));
+            assert!(min_dist <= d_true && d_true <= max_dist);
+        }
+    }
+}
+
+#[test]
+fn test_bayesian_filter_rejects_outliers() {
+    let mut rng = StdRng::seed_from_u64(23);
+    let ping_period = I32F32::from_num(20);
+    let mid = Vec2D::from(MELVIN_SIM_STEP) * ping_period * I32F32::from_num(4.5);
+    let sides = [(300, -200), (-500, 400), (0, 600), (700, 0)];
+    for (id, (outlier, side_px)) in [0, 1, 4, 9].into_iter().zip(sides).enumerate() {
+        let start_pos = Vec2D::new(I32F32::from_num(3000 * (id + 1)), I32F32::from_num(5000));
+        let side = Vec2D::new(I32F32::from_num(side_px.0), I32F32::from_num(side_px.1));
+        let scenario = BeaconScenario {
+            id: BeaconObjectiveId::new(id),
+            beacon_pos: (start_pos + mid + side).round().wrap_around_map(),
+            start_pos,
+            ping_period,
+            pings: 10,
+            noise: PingNoise::Backend,
+        };
+        let mut pings = scenario.pings(&mut rng);
+        assert_eq!(pings.len(), 10);
+        // A gross outlier at least 1000px off the true distance
+        let d_outlier = (pings[outlier].1 + 1000.0) % BayesianSet::MAX_DIST.to_num::<f32>();
+        pings[outlier].1 = d_outlier;
+        let mut meas = pings.into_iter().map(|(pos, d)| {
+            BeaconMeas::new(scenario.id, pos, f64::from(d), TimeDelta::zero())
+        });
+        let mut set = BayesianSet::new(meas.next().unwrap());
+        meas.for_each(|m| set.update(&m));
+
+        let beacon_pos = Vec2D::new(
+            scenario.beacon_pos.x().to_num::<i32>(),
+            scenario.beacon_pos.y().to_num::<i32>(),
+        );
+        let is_outlier = |m: &BeaconMeas| (m.rssi() - f64::from(d_outlier)).abs() < 0.1;
+        assert!(set.is_in_set(beacon_pos));
+        assert!(!set.measurements().iter().any(is_outlier));
+        assert!(set.rejected().iter().any(is_outlier));
+    }
+}
+
+#[test]
+fn test_bayesian_filter_outlier_free() {
+    let mut rng = StdRng::seed_from_u64(5);
+    let scenario = BeaconScenario::random(0, PingNoise::Backend, &mut rng);
+    let mut meas = scenario
+        .pings(&mut rng)
+        .into_iter()
+        .map(|(pos, d)| BeaconMeas::new(scenario.id, pos, f64::from(d), TimeDelta::zero()));
+    let mut set = BayesianSet::new(meas.next().unwrap());
+    meas.for_each(|m| set.update(&m));
+    assert!(set.rejected().is_empty());
+}
+
+#[test]
+fn test_credible_centroid_at_seam() {
+    let pos = Vec2D::new(I32F32::from_num(5), I32F32::from_num(5));
+    let set = BayesianSet::new(BeaconMeas::new(BeaconObjectiveId::new(0), pos, 0.0, TimeDelta::zero()));
+    let centroid = set.credible_centroid().unwrap();
+    let centroid_i32 = Vec2D::new(centroid.x().to_num::<i32>(), centroid.y().to_num::<i32>());
+    assert!(set.is_in_set(centroid_i32));
+    assert!(centroid.unwrapped_to(&pos).abs() < I32F32::from_num(5));
+}
+
+#[test]
+#[allow(clippy::cast_precision_loss)]
+fn test_posterior_stats_of_disc() {
+    let pos = Vec2D::new(I32F32::from_num(5), I32F32::from_num(5));
+    let set = BayesianSet::new(BeaconMeas::new(BeaconObjectiveId::new(0), pos, 0.0, TimeDelta::zero()));
+    let stats = set.posterior(3).unwrap();
+    assert!(stats.centroid().unwrapped_to(&pos).abs() < I32F32::from_num(2));
+    let [[xx, xy], [_, yy]] = stats.covariance();
+    assert!((xx / yy - 1.0).abs() < 0.05 && xy.abs() < 0.05 * xx);
+    let disc_radius = (set.len() as f64 / f64::PI()).sqrt();
+    assert!((stats.error_radius() / disc_radius - 1.0).abs() < 0.05);
+    let shares: Vec<f64> = stats.candidates().iter().map(|(_, share)| *share).collect();
+    assert_eq!(shares.len(), 3);
+    assert!(shares.windows(2).all(|w| w[0] >= w[1]) && shares[0] <= 1.0);
+}
+
+#[test]
+fn test_secret_objective_sweep() {
+    let now = Utc::now();
+    let mut secret = SecretImgObjective::new(
+        ImgObjectiveId::new(7),
+        String::from("secret"),
+        now,
+        now + TimeDelta::hours(1),
+        CameraAngle::Narrow,
+        1.0,
+    );
+    let side = i32::from(CameraAngle::Narrow.get_square_side_length());
+    let anchor = Vec2D::new(I32F32::from_num(side * 3 + 1), I32F32::from_num(side * 5 + 1));
+    let first = secret.next_candidate(anchor).unwrap();
+    assert_eq!(first, ZoneRect::new(side * 3, side * 5, side * 4, side * 6));
+    assert!(!secret.record_probe(first, Some("The zone was not found.")));
+    let second = secret.next_candidate(anchor).unwrap();
+    assert_ne!(second, first);
+    let (a, b) = (first.offset(), second.offset());
+    assert_eq!((a.x() - b.x()).abs() + (a.y() - b.y()).abs(), side);
+    assert!(secret.record_probe(second, Some("Objective zone found!")));
+    assert_eq!(secret.found(), Some(second));
+    assert_eq!(secret.probes(), 2);
+}
+
+#[test]
+fn test_secret_hunt_keeps_queued_probe() {
+    let now = Utc::now();
+    let obj: ImageObjective = serde_json::from_value(serde_json::json!({
+        "id": 9, "name": "secret", "start": now, "end": now + TimeDelta::hours(1),
+        "decrease_rate": 0.0, "zone": "unknown", "optic_required": "narrow",
+        "coverage_required": 1.0, "sprite": null, "secret": true,
+    }))
+    .unwrap();
+    let id = obj.id();
+    let mut hunt = SecretHunt::default();
+    hunt.sync(&[obj], now);
+    let anchor = Vec2D::new(I32F32::from_num(1000), I32F32::from_num(1000));
+    let first = hunt.next_probe(anchor, 5, now).unwrap().zone();
+    hunt.queue_probe(id, first);
+    // A probe waiting for its upload retry is not imaged again.
+    assert_ne!(hunt.next_probe(anchor, 5, now).unwrap().zone(), first);
+    assert_eq!(hunt.take_queued_probe(id), Some(first));
+    assert_eq!(hunt.take_queued_probe(id), None);
+    assert!(hunt.record_probe(id, first, Some("Objective zone found!")));
+}
+
+#[test]
+fn test_objective_priority_ranking() {
+    let now = Utc::now();
+    let zo = |id, zone| {
+        let end = now + TimeDelta::hours(3);
+        let (name, angle) = (String::from("zo"), CameraAngle::Narrow);
+        KnownImgObjective::new(ImgObjectiveId::new(id), name, now, end, zone, angle, 1.0)
+    };
+    let small = zo(1, ZoneRect::new(0, 0, 500, 500));
+    let large = zo(2, ZoneRect::new(0, 0, 2000, 2000));
+    let config = MissionConfig::get();
+    let points = config.scoring.zo_points;
+    let usable = ObjectivePriority::usable_fuel(&config);
+    let reserved = config.runtime.fuel_safety_margin + config.runtime.envelope_fuel_floor;
+    assert!((usable - (100.0 - reserved)).abs() < 1e-9);
+    // The value is discounted by the square root of the minimum number of images
+    let infeasible = ObjectivePriority::new(&small, None);
+    assert!((infeasible.value() - points).abs() < 1e-9);
+    let large_value = ObjectivePriority::new(&large, None).value();
+    assert!((large_value - points / f64::from(large.min_images()).sqrt()).abs() < 1e-9);
+    assert!(infeasible.score().is_none());
+    // The score falls linearly with the share of the usable fuel the exit burn consumes
+    let score = |fuel: f64| {
+        let priority = ObjectivePriority::new(&small, Some(I32F32::from_num(fuel)));
+        assert!((priority.value() - points).abs() < 1e-9);
+        priority.score().unwrap()
+    };
+    assert!((score(0.0) - points).abs() < 1e-6);
+    assert!((score(usable / 4.0) - points * 0.75).abs() < 1e-6);
+    assert!((score(usable / 2.0) - points * 0.5).abs() < 1e-6);
+    assert!(score(usable).abs() < 1e-6);
+    assert!(score(usable + 10.0).abs() < f64::EPSILON);
+}
@@ -7,10 +7,14 @@ pub enum AtomicDecision {
     StayInCharge,
     /// Decision to stay in the acquisition state.
     StayInAcquisition,
+    /// Decision to stay in the comms state.
+    StayInComms,
     /// Decision to switch to the charge state.
     SwitchToCharge,
     /// Decision to switch to the acquisition state.
     SwitchToAcquisition,
+    /// Decision to switch to the comms state.
+    SwitchToComms,
 }
 
 impl AtomicDecision {
@@ -20,19 +24,19 @@ impl AtomicDecision {
     /// - `state`: The current state as a `usize`.
     ///   - `0` indicates the charge state.
     ///   - `1` indicates the acquisition state.
+    ///   - `2` indicates the comms state.
     ///
     /// # Returns
     /// - An [`AtomicDecision`] variant corresponding to staying in the current state.
     ///
     /// # Panics
-    /// - If `state` is not `0` or `1`.
+    /// - If `state` is not `0`, `1` or `2`.
     pub fn stay(state: usize) -> Self {
-        if state == 0 {
-            AtomicDecision::StayInCharge
-        } else if state == 1 {
-            AtomicDecision::StayInAcquisition
-        } else {
-            fatal!("Invalid state for stay decision")
+        match state {
+            0 => AtomicDecision::StayInCharge,
+            1 => AtomicDecision::StayInAcquisition,
+            2 => AtomicDecision::StayInComms,
+            _ => fatal!("Invalid state for stay decision"),
         }
     }
 
@@ -42,19 +46,28 @@ impl AtomicDecision {
     /// - `to_state`: The target state as a `usize`.
     ///   - `0` indicates the charge state.
     ///   - `1` indicates the acquisition state.
+    ///   - `2` indicates the comms state.
     ///
     /// # Returns
     /// - An `AtomicDecision` variant corresponding to switching to the target state.
     ///
     /// # Panics
-    /// - If `to_state` is not `0` or `1`.
+    /// - If `to_state` is not `0`, `1` or `2`.
     pub fn switch(to_state: usize) -> Self {
-        if to_state == 0 {
-            AtomicDecision::SwitchToCharge
-        } else if to_state == 1 {
-            AtomicDecision::SwitchToAcquisition
-        } else {
-            fatal!("Invalid state for stay decision")
+        match to_state {
+            0 => AtomicDecision::SwitchToCharge,
+            1 => AtomicDecision::SwitchToAcquisition,
+            2 => AtomicDecision::SwitchToComms,
+            _ => fatal!("Invalid state for switch decision"),
+        }
+    }
+
+    /// Returns the DP state index MELVIN is in after the decision.
+    pub fn state(self) -> usize {
+        match self {
+            AtomicDecision::StayInCharge | AtomicDecision::SwitchToCharge => 0,
+            AtomicDecision::StayInAcquisition | AtomicDecision::SwitchToAcquisition => 1,
+            AtomicDecision::StayInComms | AtomicDecision::SwitchToComms => 2,
         }
     }
 }
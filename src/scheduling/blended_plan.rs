@@ -0,0 +1,87 @@
+use super::{EndCondition, TaskController};
+use crate::flight_control::FlightState;
+use crate::util::{ImgObjectiveId, logger::JsonDump};
+use chrono::{DateTime, TimeDelta, Utc};
+
+/// Combined plan for the waiting period before an orbit exit burn.
+///
+/// A [`BlendedPlan`] bounds the comms windows for active beacon objectives in the time before
+/// a zoned objective exit burn by a deadline, reserving enough time to leave comms and charge
+/// up to the burns [`EndCondition`]. The comms-aware orbit scheduler places the windows before
+/// that deadline, the plan only checks that at least one window fits into the slack.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct BlendedPlan {
+    /// The ID of the zoned objective the burn is aiming for.
+    zo_id: ImgObjectiveId,
+    /// The start time of the exit burn.
+    burn_start: DateTime<Utc>,
+    /// The latest time at which comms may end without jeopardizing burn readiness.
+    comms_deadline: DateTime<Utc>,
+    /// The number of comms windows fitting before `comms_deadline`.
+    n_windows: usize,
+    /// The total time of the comms windows fitting before `comms_deadline`.
+    comms_time: TimeDelta,
+}
+
+impl BlendedPlan {
+    /// Minimum length of a comms window to be worth planning.
+    const MIN_COMMS_WINDOW: TimeDelta = TimeDelta::seconds(300);
+
+    /// Tries to create a [`BlendedPlan`] for the waiting period before a burn.
+    ///
+    /// # Arguments
+    /// * `zo_id` – The ID of the targeted zoned objective.
+    /// * `first_comms_start` – The earliest time at which MELVIN can be in comms.
+    /// * `end` – The burn end condition that must still be met.
+    /// * `last_bo_end` – The end of the last active beacon objective.
+    ///
+    /// # Returns
+    /// * `Some(BlendedPlan)` if at least one comms window fits into the slack.
+    /// * `None` otherwise.
+    pub fn new(
+        zo_id: ImgObjectiveId,
+        first_comms_start: DateTime<Utc>,
+        end: &EndCondition,
+        last_bo_end: DateTime<Utc>,
+    ) -> Option<Self> {
+        let t_time = FlightState::Charge.td_dt_to(FlightState::Comms);
+        let comms_deadline = end.time() - end.abs_charge_dt() - t_time * 2;
+        let in_comms = TaskController::IN_COMMS_SCHED_DT.to_delta();
+        let window_limit = comms_deadline.min(last_bo_end);
+
+        let (mut n_windows, mut comms_time) = (0, TimeDelta::zero());
+        let mut start = first_comms_start;
+        while start + Self::MIN_COMMS_WINDOW <= window_limit {
+            let end_t = (start + in_comms).min(window_limit);
+            n_windows += 1;
+            comms_time += end_t - start;
+            start = end_t + t_time * 2 + TaskController::comms_usable_time();
+        }
+        (n_windows > 0).then_some(Self {
+            zo_id,
+            burn_start: end.time(),
+            comms_deadline,
+            n_windows,
+            comms_time,
+        })
+    }
+
+    /// Returns the latest time at which comms may end without jeopardizing burn readiness.
+    pub fn comms_deadline(&self) -> DateTime<Utc> { self.comms_deadline }
+
+    /// Returns the number of comms windows fitting before the deadline.
+    pub fn n_windows(&self) -> usize { self.n_windows }
+
+    /// Returns the total time of the comms windows fitting before the deadline.
+    pub fn total_comms_time(&self) -> TimeDelta { self.comms_time }
+}
+
+impl JsonDump for BlendedPlan {
+    /// Returns the file name for the JSON dump of the blended plan.
+    fn file_name(&self) -> String {
+        format!("blended_{}_{}", self.zo_id, self.burn_start.timestamp())
+    }
+
+    /// Returns the directory name for the blended plan JSON files.
+    fn dir_name(&self) -> &'static str { "blended_plans" }
+}
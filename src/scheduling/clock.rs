@@ -0,0 +1,57 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use std::sync::Mutex;
+
+/// A source of the current time used by the planners and executors.
+///
+/// Planning and execution run against separate clocks: during a mission both follow the
+/// wall clock, while the schedule simulator fast-forwards a [`VirtualClock`], so computed
+/// schedules can be executed without real-time waits.
+pub trait Clock: Send + Sync {
+    /// Returns the current time of the clock.
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The real time, as used during a mission.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct WallClock;
+
+impl Clock for WallClock {
+    fn now(&self) -> DateTime<Utc> { Utc::now() }
+}
+
+/// A manually advanced clock that only moves forward.
+#[derive(Debug)]
+pub struct VirtualClock {
+    /// The current virtual time.
+    now: Mutex<DateTime<Utc>>,
+}
+
+impl VirtualClock {
+    /// Creates a new [`VirtualClock`] standing at `start`.
+    ///
+    /// # Arguments
+    /// - `start`: The initial virtual time.
+    pub fn new(start: DateTime<Utc>) -> Self { Self { now: Mutex::new(start) } }
+
+    /// Moves the clock forward to `t`. Times in the past are ignored.
+    ///
+    /// # Arguments
+    /// - `t`: The new virtual time.
+    pub fn advance_to(&self, t: DateTime<Utc>) {
+        let mut now = self.now.lock().unwrap();
+        *now = (*now).max(t);
+    }
+
+    /// Moves the clock forward by `dt`.
+    ///
+    /// # Arguments
+    /// - `dt`: The time to skip, negative values are ignored.
+    pub fn advance(&self, dt: TimeDelta) {
+        let mut now = self.now.lock().unwrap();
+        *now += dt.max(TimeDelta::zero());
+    }
+}
+
+impl Clock for VirtualClock {
+    fn now(&self) -> DateTime<Utc> { *self.now.lock().unwrap() }
+}
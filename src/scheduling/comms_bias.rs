@@ -0,0 +1,135 @@
+use crate::flight_control::orbit::ClosedOrbit;
+use crate::objective::PosteriorStats;
+use crate::util::{BeaconObjectiveId, MapSize, MissionConfig, Vec2D};
+use fixed::types::I32F32;
+
+/// Weighting of beacon scanning along the orbit by the distance to candidate beacon locations.
+///
+/// Pings received closer to a beacon are less noisy, so seconds in `Comms` on orbit segments
+/// passing near the candidate regions of active beacons are scored higher by the orbit
+/// scheduling DP. Without candidates every second is weighted equally.
+#[derive(Debug, Clone, Default)]
+pub struct CommsBias {
+    /// The candidate beacon locations with their weight, summing up to `1.0`.
+    targets: Vec<(Vec2D<f64>, f64)>,
+    /// The additional weight of a second directly above a candidate location.
+    strength: f64,
+}
+
+impl CommsBias {
+    /// The distance in px beyond which beacons can not be pinged.
+    const PING_RANGE: f64 = 2000.0;
+
+    /// Creates a [`CommsBias`] weighting every second equally.
+    pub fn uniform() -> Self { Self::default() }
+
+    /// Creates a [`CommsBias`] from weighted candidate beacon locations.
+    ///
+    /// # Arguments
+    /// * `cands` – The candidate locations with their non-negative weight.
+    /// * `strength` – The additional weight of a second directly above a candidate location.
+    pub fn new(cands: &[(Vec2D<I32F32>, f64)], strength: f64) -> Self {
+        let total: f64 = cands.iter().map(|(_, w)| *w).sum();
+        if total <= 0.0 || strength <= 0.0 {
+            return Self::uniform();
+        }
+        let targets = cands
+            .iter()
+            .filter(|(_, w)| *w > 0.0)
+            .map(|(pos, w)| (Vec2D::new(pos.x().to_num(), pos.y().to_num()), w / total))
+            .collect();
+        Self { targets, strength }
+    }
+
+    /// Creates a [`CommsBias`] from the posteriors of the active beacons, scaled by the
+    /// `comms_geometry_bias` of the [`MissionConfig`].
+    ///
+    /// Every beacon is weighted equally, split among its candidates by the share of the
+    /// credible region they cover.
+    ///
+    /// # Arguments
+    /// * `posteriors` – The posterior statistics of the active beacons.
+    pub fn from_posteriors(posteriors: &[(BeaconObjectiveId, PosteriorStats)]) -> Self {
+        let mut targets = Vec::new();
+        for (_, stats) in posteriors {
+            let cands = stats.candidates();
+            let total: f64 = cands.iter().map(|(_, share)| *share).sum();
+            if total > 0.0 {
+                targets.extend(cands.iter().map(|(pos, share)| (*pos, share / total)));
+            } else {
+                targets.push((stats.centroid(), 1.0));
+            }
+        }
+        Self::new(&targets, MissionConfig::get().runtime.comms_geometry_bias)
+    }
+
+    /// Returns `true` if every second is weighted equally.
+    pub fn is_uniform(&self) -> bool { self.targets.is_empty() }
+
+    /// Returns the wrapped distance in px from `pos` to the nearest candidate location.
+    fn nearest_dist(&self, pos: Vec2D<f64>) -> f64 {
+        self.targets.iter().map(|(t, _)| Self::wrapped_dist(pos, *t)).fold(f64::INFINITY, f64::min)
+    }
+
+    /// Returns the weighted proximity of `pos` to the candidate locations from `0.0` to `1.0`,
+    /// decreasing linearly to `0.0` at the ping range.
+    fn proximity(&self, pos: Vec2D<f64>) -> f64 {
+        self.targets
+            .iter()
+            .map(|(t, w)| w * (1.0 - Self::wrapped_dist(pos, *t) / Self::PING_RANGE).max(0.0))
+            .sum()
+    }
+
+    /// Returns the shortest distance between two positions across the map seams.
+    fn wrapped_dist(a: Vec2D<f64>, b: Vec2D<f64>) -> f64 {
+        let size = Vec2D::<f64>::map_size();
+        let dx = (a.x() - b.x()).rem_euclid(size.x());
+        let dy = (a.y() - b.y()).rem_euclid(size.y());
+        dx.min(size.x() - dx).hypot(dy.min(size.y() - dy))
+    }
+
+    /// Returns the orbit position `dt` seconds after the orbit index `start_i`.
+    fn pos_after(orbit: &ClosedOrbit, start_i: usize, dt: usize) -> Vec2D<f64> {
+        let pos = orbit.pos_at(start_i + dt);
+        Vec2D::new(pos.x().to_num(), pos.y().to_num())
+    }
+
+    /// Returns the DP scores of the seconds in `Comms` along the orbit.
+    ///
+    /// # Arguments
+    /// * `orbit` – The closed orbit that is scheduled.
+    /// * `start_i` – The orbit index of the first second.
+    /// * `len` – The number of seconds to score.
+    /// * `base` – The score of a second out of ping range of all candidate locations.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn scores(&self, orbit: &ClosedOrbit, start_i: usize, len: usize, base: f64) -> Vec<i32> {
+        if self.is_uniform() {
+            return vec![base.round() as i32; len];
+        }
+        (0..len)
+            .map(|dt| {
+                let prox = self.proximity(Self::pos_after(orbit, start_i, dt));
+                (base * (1.0 + self.strength * prox)).round() as i32
+            })
+            .collect()
+    }
+
+    /// Finds the closest pass of the orbit to any candidate location within one orbit period.
+    ///
+    /// # Arguments
+    /// * `orbit` – The closed orbit that is scheduled.
+    /// * `start_i` – The orbit index from which the phase offset is counted.
+    ///
+    /// # Returns
+    /// * The phase offset of the pass in seconds after `start_i` and its distance in px, or
+    ///   `None` without candidates.
+    pub fn nearest_pass(&self, orbit: &ClosedOrbit, start_i: usize) -> Option<(usize, f64)> {
+        if self.is_uniform() {
+            return None;
+        }
+        let period = orbit.period().0.to_num::<usize>();
+        (0..period)
+            .map(|dt| (dt, self.nearest_dist(Self::pos_after(orbit, start_i, dt))))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+}
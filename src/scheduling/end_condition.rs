@@ -1,6 +1,7 @@
 use crate::flight_control::{FlightState, orbit::BurnSequence};
 use chrono::{DateTime, TimeDelta, Utc};
 use fixed::types::I32F32;
+use num::Zero;
 
 /// Represents a scheduling boundary condition for dynamic orbit planning and task execution.
 ///
@@ -10,6 +11,7 @@ use fixed::types::I32F32;
 /// resources are met.
 ///
 /// Typically passed to the scheduler to guide the final phase of planning.
+#[derive(Debug, Clone, Copy)]
 pub struct EndCondition {
     /// The desired scheduling terminal charge
     charge: I32F32,
@@ -20,6 +22,16 @@ pub struct EndCondition {
 }
 
 impl EndCondition {
+    /// Creates a new [`EndCondition`].
+    ///
+    /// # Arguments
+    /// - `time`: The desired end of scheduling.
+    /// - `charge`: The minimum battery level at `time`.
+    /// - `state`: The flight state at `time`.
+    pub fn new(time: DateTime<Utc>, charge: I32F32, state: FlightState) -> Self {
+        Self { charge, state, time }
+    }
+
     /// Creates an [`EndCondition`] from a given burn sequence.
     ///
     /// The resulting condition requires being in `Acquisition` mode with
@@ -47,12 +59,12 @@ impl EndCondition {
 
     /// Computes the absolute `TimeDelta` required to charge from 0 to the required `charge()` level.
     ///
-    /// This is based on the charge rate defined for `FlightState::Charge`.
+    /// This is based on the charge curve configured for `FlightState::Charge`.
     ///
     /// # Returns
     /// - A `TimeDelta` representing the time needed to reach the required charge level.
     pub fn abs_charge_dt(&self) -> TimeDelta {
-        let secs = (self.charge() / FlightState::Charge.get_charge_rate()).round().to_num::<i64>();
-        TimeDelta::seconds(secs)
+        let secs = FlightState::Charge.charge_dt(I32F32::zero(), self.charge()).round();
+        TimeDelta::seconds(secs.to_num::<i64>())
     }
 }
@@ -0,0 +1,46 @@
+use super::task::ExternalEvent;
+use crate::util::{EVENT_BUS, SchedulingEvent};
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+use tokio::sync::watch;
+
+/// Registry of [`ExternalEvent`]s that have fired, used to release tasks with
+/// "no-earlier-than" dependencies.
+#[derive(Debug)]
+pub struct EventRegistry {
+    /// Watch sender holding the set of fired events.
+    fired: watch::Sender<HashSet<ExternalEvent>>,
+}
+
+impl EventRegistry {
+    /// Creates a new, empty [`EventRegistry`].
+    pub fn new() -> Self { Self { fired: watch::Sender::new(HashSet::new()) } }
+
+    /// Marks an event as fired and releases all tasks waiting for it.
+    ///
+    /// # Arguments
+    /// - `event`: The fired event.
+    pub fn fire(&self, event: ExternalEvent) {
+        if self.fired.send_if_modified(|fired| fired.insert(event)) {
+            EVENT_BUS.publish(SchedulingEvent::ExternalFired(event));
+        }
+    }
+
+    /// Returns `true` if the given event has already fired.
+    pub fn has_fired(&self, event: ExternalEvent) -> bool { self.fired.borrow().contains(&event) }
+
+    /// Waits until an event fires or a deadline passes.
+    ///
+    /// # Arguments
+    /// - `event`: The awaited event.
+    /// - `deadline`: The latest time to wait for.
+    ///
+    /// # Returns
+    /// - `true` if the event fired, `false` if the deadline passed first.
+    pub async fn wait_for(&self, event: ExternalEvent, deadline: DateTime<Utc>) -> bool {
+        let mut rx = self.fired.subscribe();
+        let timeout = (deadline - Utc::now()).to_std().unwrap_or_default();
+        let fired = rx.wait_for(|fired| fired.contains(&event));
+        matches!(tokio::time::timeout(timeout, fired).await, Ok(Ok(_)))
+    }
+}
@@ -46,6 +46,15 @@ impl<T> LinkedBox<T> {
     /// An `Option` containing a reference to the last element, or `None` if the list is empty.
     pub fn back(&self) -> Option<&T> { self.list.back() }
 
+    /// Returns a reference to the element at position `i`, counted from the front.
+    ///
+    /// # Arguments
+    /// * `i` - The position of the element, `0` being the most recently pushed one.
+    ///
+    /// # Returns
+    /// An `Option` containing a reference to the element, or `None` if `i` is out of bounds.
+    pub fn get(&self, i: usize) -> Option<&T> { self.list.get(i) }
+
     /// Returns the current number of elements in the list.
     ///
     /// # Returns
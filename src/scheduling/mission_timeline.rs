@@ -0,0 +1,280 @@
+use super::task::{BaseTask, Task};
+use crate::flight_control::FlightState;
+use crate::util::logger::JsonDump;
+use chrono::{DateTime, TimeDelta, Utc};
+use std::fmt::Write;
+
+/// The lane of the timeline a [`TimelineBar`] is drawn in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum TimelineLane {
+    /// The planned flight states.
+    State,
+    /// The planned `Comms` windows.
+    Comms,
+    /// Blocks of consecutive image captures.
+    Imaging,
+    /// Burn sequences.
+    Burn,
+    /// The availability window of an objective, ending at its deadline.
+    Objective,
+}
+
+/// A single bar of the [`MissionTimeline`].
+#[derive(Debug, Clone, serde::Serialize)]
+pub(crate) struct TimelineBar {
+    /// The lane of the bar.
+    lane: TimelineLane,
+    /// The description of the bar.
+    label: String,
+    /// The start of the bar.
+    start: DateTime<Utc>,
+    /// The end of the bar.
+    end: DateTime<Utc>,
+}
+
+/// A Gantt-style timeline of the planned tasks, `Comms` windows, burns and objective
+/// deadlines, exported as JSON and rendered as an SVG chart for the operator console.
+#[derive(Debug, Clone, serde::Serialize)]
+pub(crate) struct MissionTimeline {
+    /// The time the timeline was generated.
+    generated: DateTime<Utc>,
+    /// The end of the planned schedule.
+    horizon: DateTime<Utc>,
+    /// All bars, ordered by lane and start.
+    bars: Vec<TimelineBar>,
+}
+
+impl JsonDump for MissionTimeline {
+    /// Returns the file name for the JSON dump of the timeline.
+    fn file_name(&self) -> String { format!("timeline_{}", self.generated.format("%d_%H_%M_%S")) }
+
+    /// Returns the directory name for the timeline JSON files.
+    fn dir_name(&self) -> &'static str { "timeline" }
+}
+
+impl MissionTimeline {
+    /// The time the schedule horizon extends beyond the last task.
+    const TAIL: TimeDelta = TimeDelta::minutes(10);
+    /// The maximum gap between two image captures of the same imaging block.
+    const MAX_IMG_GAP: TimeDelta = TimeDelta::seconds(60);
+    /// The width of the rendered chart in px.
+    const SVG_WIDTH: i64 = 1200;
+    /// The width of the lane label column in px.
+    const SVG_LABEL_W: i64 = 220;
+    /// The height of a lane in px.
+    const SVG_ROW_H: i64 = 24;
+    /// The height of the time axis in px.
+    const SVG_AXIS_H: i64 = 30;
+    /// The candidate intervals between two time axis ticks in minutes.
+    const TICK_STEPS_MIN: [i64; 10] = [1, 5, 10, 15, 30, 60, 120, 240, 480, 720];
+
+    /// Builds the timeline of a task schedule.
+    ///
+    /// # Arguments
+    /// * `now` – The time of generation.
+    /// * `state` – The current flight state, held until the first state switch.
+    /// * `tasks` – The pending tasks in execution order.
+    pub(crate) fn from_schedule<'a>(
+        now: DateTime<Utc>,
+        state: FlightState,
+        tasks: impl IntoIterator<Item = &'a Task>,
+    ) -> Self {
+        let mut bars = Vec::new();
+        let mut state_bar = (state, now);
+        let mut img_block: Option<(DateTime<Utc>, DateTime<Utc>, usize)> = None;
+        let mut last_t = now;
+        for task in tasks {
+            let t = task.t();
+            last_t = last_t.max(t);
+            match task.task_type() {
+                BaseTask::SwitchState(switch) => {
+                    bars.push(Self::state_bar(state_bar, t));
+                    state_bar = (switch.target_state(), t);
+                }
+                BaseTask::TakeImage(_) => {
+                    img_block = match img_block {
+                        Some((start, end, n)) if t - end <= Self::MAX_IMG_GAP => {
+                            Some((start, t, n + 1))
+                        }
+                        block => {
+                            bars.extend(block.map(Self::imaging_bar));
+                            Some((t, t, 1))
+                        }
+                    };
+                }
+                BaseTask::ChangeVelocity(vel_change) => {
+                    let burn = vel_change.burn();
+                    let secs = i64::try_from(burn.acc_dt() + burn.detumble_dt()).unwrap_or(0);
+                    let dt = TimeDelta::seconds(secs);
+                    let label = format!("burn ({}s)", dt.num_seconds());
+                    let end = t + dt;
+                    bars.push(TimelineBar { lane: TimelineLane::Burn, label, start: t, end });
+                    last_t = last_t.max(t + dt);
+                }
+            }
+        }
+        let horizon = last_t + Self::TAIL;
+        bars.extend(img_block.map(Self::imaging_bar));
+        bars.push(Self::state_bar(state_bar, horizon));
+        let comms: Vec<_> = bars
+            .iter()
+            .filter(|b| b.lane == TimelineLane::State && b.label == FlightState::Comms.to_string())
+            .map(|b| TimelineBar {
+                lane: TimelineLane::Comms,
+                label: "comms".to_string(),
+                start: b.start,
+                end: b.end,
+            })
+            .collect();
+        bars.extend(comms);
+        bars.retain(|b| b.end > b.start || b.lane != TimelineLane::State);
+        let mut timeline = Self { generated: now, horizon, bars };
+        timeline.sort();
+        timeline
+    }
+
+    /// Creates the bar of a flight state held from `start` until `end`.
+    fn state_bar((state, start): (FlightState, DateTime<Utc>), end: DateTime<Utc>) -> TimelineBar {
+        TimelineBar { lane: TimelineLane::State, label: state.to_string(), start, end }
+    }
+
+    /// Creates the bar of an imaging block of `n` captures from `start` until `end`.
+    fn imaging_bar((start, end, n): (DateTime<Utc>, DateTime<Utc>, usize)) -> TimelineBar {
+        let label = format!("{n} images");
+        TimelineBar { lane: TimelineLane::Imaging, label, start, end: end + TimeDelta::seconds(1) }
+    }
+
+    /// Orders the bars by lane and start.
+    fn sort(&mut self) { self.bars.sort_by_key(|b| (b.lane, b.start)); }
+
+    /// Adds the availability window of an objective.
+    ///
+    /// # Arguments
+    /// * `label` – The description of the objective.
+    /// * `start` – The start of the objective.
+    /// * `end` – The deadline of the objective.
+    pub(crate) fn add_objective(
+        &mut self,
+        label: String,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) {
+        self.bars.push(TimelineBar { lane: TimelineLane::Objective, label, start, end });
+        self.sort();
+    }
+
+    /// Returns the time the timeline was generated.
+    pub(crate) fn generated(&self) -> DateTime<Utc> { self.generated }
+
+    /// Serializes the timeline as JSON.
+    pub(crate) fn to_json(&self) -> String { serde_json::to_string(self).unwrap_or_default() }
+
+    /// Renders the timeline as an SVG Gantt chart.
+    ///
+    /// The time axis spans from the generation to the end of the schedule. Every objective
+    /// gets its own row, bars reaching beyond the axis are cut at its end.
+    #[allow(clippy::cast_possible_wrap)]
+    pub(crate) fn to_svg(&self) -> String {
+        let fixed_lanes = [
+            (TimelineLane::State, "flight state"),
+            (TimelineLane::Comms, "comms windows"),
+            (TimelineLane::Imaging, "imaging"),
+            (TimelineLane::Burn, "burns"),
+        ];
+        let objectives: Vec<_> =
+            self.bars.iter().filter(|b| b.lane == TimelineLane::Objective).collect();
+        let rows = (fixed_lanes.len() + objectives.len()) as i64;
+        let height = Self::SVG_AXIS_H + rows * Self::SVG_ROW_H + 10;
+        let span_ms = (self.horizon - self.generated).num_milliseconds().max(1);
+        let plot_w = Self::SVG_WIDTH - Self::SVG_LABEL_W - 10;
+        let x = |t: DateTime<Utc>| {
+            let ms = (t - self.generated).num_milliseconds().clamp(0, span_ms);
+            Self::SVG_LABEL_W + ms * plot_w / span_ms
+        };
+        let row_y = |row: i64| Self::SVG_AXIS_H + row * Self::SVG_ROW_H;
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{height}\" \
+             font-family=\"monospace\" font-size=\"11\">\n\
+             <rect width=\"100%\" height=\"100%\" fill=\"#ffffff\"/>\n",
+            Self::SVG_WIDTH
+        );
+        let step_min = Self::TICK_STEPS_MIN
+            .into_iter()
+            .find(|step| span_ms / (step * 60_000) <= 12)
+            .unwrap_or(1440);
+        let step = TimeDelta::minutes(step_min);
+        let mut tick = self.generated;
+        while tick <= self.horizon {
+            let tx = x(tick);
+            let _ = writeln!(
+                svg,
+                "<line x1=\"{tx}\" y1=\"{}\" x2=\"{tx}\" y2=\"{}\" stroke=\"#dddddd\"/>\
+                 <text x=\"{tx}\" y=\"18\">{}</text>",
+                Self::SVG_AXIS_H - 6,
+                height - 10,
+                tick.format("%H:%M")
+            );
+            tick += step;
+        }
+        let mut bar = |row: i64, b: &TimelineBar| {
+            let (x0, y) = (x(b.start), row_y(row) + 3);
+            let w = (x(b.end) - x0).max(2);
+            let _ = writeln!(
+                svg,
+                "<rect x=\"{x0}\" y=\"{y}\" width=\"{w}\" height=\"{}\" fill=\"{}\">\
+                 <title>{} {} - {}</title></rect>",
+                Self::SVG_ROW_H - 6,
+                Self::color(b),
+                Self::escape(&b.label),
+                b.start.format("%d %H:%M:%S"),
+                b.end.format("%d %H:%M:%S")
+            );
+        };
+        for (row, (lane, _)) in fixed_lanes.iter().enumerate() {
+            self.bars.iter().filter(|b| b.lane == *lane).for_each(|b| bar(row as i64, b));
+        }
+        for (i, b) in objectives.iter().enumerate() {
+            bar((fixed_lanes.len() + i) as i64, b);
+        }
+        let labels = fixed_lanes
+            .iter()
+            .map(|(_, name)| (*name).to_string())
+            .chain(
+                objectives.iter().map(|b| format!("{} (due {})", b.label, b.end.format("%H:%M"))),
+            );
+        for (row, label) in labels.enumerate() {
+            let _ = writeln!(
+                svg,
+                "<text x=\"4\" y=\"{}\">{}</text>",
+                row_y(row as i64) + Self::SVG_ROW_H / 2 + 4,
+                Self::escape(&label)
+            );
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Returns the fill color of a bar.
+    fn color(bar: &TimelineBar) -> &'static str {
+        match bar.lane {
+            TimelineLane::State => match bar.label.as_str() {
+                "Acquisition" => "#4caf50",
+                "Charge" => "#ffc107",
+                "Comms" => "#2196f3",
+                "Safe" => "#f44336",
+                _ => "#9e9e9e",
+            },
+            TimelineLane::Comms => "#2196f3",
+            TimelineLane::Imaging => "#8bc34a",
+            TimelineLane::Burn => "#ff5722",
+            TimelineLane::Objective => "#9c27b0",
+        }
+    }
+
+    /// Escapes the XML special characters of a text.
+    fn escape(text: &str) -> String {
+        text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+    }
+}
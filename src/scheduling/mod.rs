@@ -1,18 +1,38 @@
 //! This module provides the core components for managing tasks and decisions.
 
 mod atomic_decision;
+mod blended_plan;
 mod atomic_decision_cube;
+mod clock;
+mod comms_bias;
 pub mod task;
 mod end_condition;
+mod event_registry;
+mod mission_timeline;
+mod schedule_sim;
+mod slot_manager;
 mod score_grid;
 mod task_controller;
+mod task_queue;
+mod task_timing;
 mod linked_box;
+mod zo_leg;
 
 #[cfg(test)]
 mod tests;
 
 pub use task_controller::TaskController;
+pub use task_queue::TaskQueue;
 pub use end_condition::EndCondition;
+pub use event_registry::EventRegistry;
+pub(crate) use mission_timeline::MissionTimeline;
+pub use blended_plan::BlendedPlan;
+pub use task_timing::TaskTimingReport;
+pub use clock::{Clock, VirtualClock, WallClock};
+pub use comms_bias::CommsBias;
+pub use slot_manager::SlotManager;
+pub use zo_leg::{ZoCandidate, ZoLeg};
+pub use schedule_sim::{ScheduleSimReport, ScheduleSimulator, SimSample, SimViolation};
 use atomic_decision_cube::AtomicDecisionCube;
 use atomic_decision::AtomicDecision;
 use score_grid::ScoreGrid;
@@ -0,0 +1,285 @@
+use super::{
+    Clock, VirtualClock,
+    task::{BaseTask, Task},
+};
+use crate::flight_control::FlightState;
+use crate::util::{TimeBudget, logger::JsonDump};
+use chrono::{DateTime, TimeDelta, Utc};
+use fixed::types::I32F32;
+
+/// A violation of the execution rules found while simulating a schedule.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub enum SimViolation {
+    /// The task is due before its predecessor in the schedule.
+    OutOfOrder { task: usize, t: DateTime<Utc> },
+    /// The task is due while a state transition or burn is still running.
+    Busy { task: usize, t: DateTime<Utc>, busy_until: DateTime<Utc> },
+    /// The task requires another flight state than the one MELVIN is in.
+    WrongState { task: usize, t: DateTime<Utc>, required: FlightState, actual: FlightState },
+    /// The state switch targets the current flight state.
+    RedundantSwitch { task: usize, t: DateTime<Utc>, state: FlightState },
+    /// The state switch targets a state that can not be reached from the current one.
+    IllegalTransition { task: usize, t: DateTime<Utc>, from: FlightState, to: FlightState },
+    /// The battery fell below the threshold of the simulator.
+    BelowThreshold { t: DateTime<Utc>, batt: I32F32, threshold: I32F32 },
+    /// The battery ran empty, which triggers a safe mode transition.
+    BatteryDepleted { t: DateTime<Utc> },
+    /// The burn starts with less charge than it needs.
+    InsufficientCharge { task: usize, t: DateTime<Utc>, needed: I32F32, available: I32F32 },
+    /// The burn needs more fuel than is left.
+    InsufficientFuel { task: usize, t: DateTime<Utc>, needed: I32F32, available: I32F32 },
+}
+
+/// A point of the simulated battery and fuel trajectory.
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+pub struct SimSample {
+    /// The virtual time of the sample.
+    pub t: DateTime<Utc>,
+    /// The flight state at `t`.
+    pub state: FlightState,
+    /// The battery level at `t`.
+    pub batt: I32F32,
+    /// The fuel level at `t`.
+    pub fuel: I32F32,
+}
+
+/// The result of a schedule simulation.
+#[derive(Debug, serde::Serialize)]
+pub struct ScheduleSimReport {
+    /// The virtual start of the simulation.
+    start: DateTime<Utc>,
+    /// The number of simulated tasks.
+    n_tasks: usize,
+    /// The lowest simulated battery level.
+    min_batt: I32F32,
+    /// The battery and fuel levels after the start, every task and the end of the simulation.
+    trajectory: Vec<SimSample>,
+    /// All violations in the order of their occurrence.
+    violations: Vec<SimViolation>,
+}
+
+impl ScheduleSimReport {
+    /// Returns the lowest simulated battery level.
+    pub fn min_batt(&self) -> I32F32 { self.min_batt }
+    /// Returns the simulated battery and fuel trajectory.
+    pub fn trajectory(&self) -> &[SimSample] { &self.trajectory }
+    /// Returns all violations in the order of their occurrence.
+    pub fn violations(&self) -> &[SimViolation] { &self.violations }
+    /// Returns whether the schedule executed without any violation.
+    pub fn is_valid(&self) -> bool { self.violations.is_empty() }
+}
+
+impl JsonDump for ScheduleSimReport {
+    /// Returns a unique filename based on the simulation start.
+    fn file_name(&self) -> String { format!("sim_{}", self.start.format("%Y-%m-%dT%H-%M-%S")) }
+
+    /// Specifies the output directory for dumped simulation reports.
+    fn dir_name(&self) -> &'static str { "schedule_sim" }
+}
+
+/// Executes a task schedule against a model of MELVIN with an accelerated [`VirtualClock`].
+///
+/// Battery levels follow the configured [`FlightState`] charge curves, state switches take the
+/// nominal transition times and burns consume their planned fuel and charge. The simulator
+/// skips straight from task to task, so a full orbit schedule is verified within milliseconds.
+#[derive(Debug)]
+pub struct ScheduleSimulator {
+    /// The execution clock of the simulation.
+    clock: VirtualClock,
+    /// The current flight state.
+    state: FlightState,
+    /// The target and the end of a running state transition.
+    transition: Option<(FlightState, DateTime<Utc>)>,
+    /// The end of the currently running task.
+    busy_until: DateTime<Utc>,
+    /// The current battery level.
+    batt: I32F32,
+    /// The current fuel level.
+    fuel: I32F32,
+    /// The battery level below which a [`SimViolation::BelowThreshold`] is recorded, if any.
+    threshold: Option<I32F32>,
+    /// The report collected during the simulation.
+    report: ScheduleSimReport,
+}
+
+impl ScheduleSimulator {
+    /// The maximum battery level.
+    const MAX_BATT: I32F32 = I32F32::lit("100.0");
+
+    /// Creates a new [`ScheduleSimulator`].
+    ///
+    /// # Arguments
+    /// - `start`: The virtual start time.
+    /// - `state`: The initial flight state.
+    /// - `batt`: The initial battery level.
+    /// - `fuel`: The initial fuel level.
+    pub fn new(start: DateTime<Utc>, state: FlightState, batt: I32F32, fuel: I32F32) -> Self {
+        let report = ScheduleSimReport {
+            start,
+            n_tasks: 0,
+            min_batt: batt,
+            trajectory: vec![SimSample { t: start, state, batt, fuel }],
+            violations: Vec::new(),
+        };
+        Self {
+            clock: VirtualClock::new(start),
+            state,
+            transition: None,
+            busy_until: start,
+            batt,
+            fuel,
+            threshold: None,
+            report,
+        }
+    }
+
+    /// Records a [`SimViolation::BelowThreshold`] whenever the battery falls below `threshold`.
+    ///
+    /// The charge rates are constant within a state, so the battery is checked at the end of
+    /// every simulated interval.
+    ///
+    /// # Arguments
+    /// - `threshold`: The lowest acceptable battery level.
+    pub fn with_threshold(mut self, threshold: I32F32) -> Self {
+        self.threshold = Some(threshold);
+        self
+    }
+
+    /// Executes all tasks in order and continues the simulation until `end`.
+    ///
+    /// # Arguments
+    /// - `tasks`: The schedule to execute.
+    /// - `end`: The virtual time at which the simulation ends.
+    ///
+    /// # Returns
+    /// - The [`ScheduleSimReport`] of the simulation.
+    pub fn run<'a>(
+        mut self,
+        tasks: impl IntoIterator<Item = &'a Task>,
+        end: DateTime<Utc>,
+    ) -> ScheduleSimReport {
+        let mut last_t = None;
+        for (i, task) in tasks.into_iter().enumerate() {
+            let t = task.t();
+            if last_t.is_some_and(|last| t < last) {
+                self.report.violations.push(SimViolation::OutOfOrder { task: i, t });
+            }
+            last_t = Some(t);
+            if t < self.busy_until {
+                let busy_until = self.busy_until;
+                self.report.violations.push(SimViolation::Busy { task: i, t, busy_until });
+            }
+            // Like the executor, a task is never started before its predecessor finished
+            self.advance_to(t.max(self.busy_until));
+            self.exec(i, task);
+            self.report.n_tasks += 1;
+            self.sample();
+        }
+        self.advance_to(end);
+        self.sample();
+        self.report
+    }
+
+    /// Executes a single task at the current virtual time.
+    ///
+    /// # Arguments
+    /// - `i`: The index of the task in the schedule.
+    /// - `task`: The task to execute.
+    fn exec(&mut self, i: usize, task: &Task) {
+        let t = self.clock.now();
+        match task.task_type() {
+            BaseTask::SwitchState(switch) => {
+                let target = switch.target_state();
+                if target == self.state {
+                    let violation = SimViolation::RedundantSwitch { task: i, t, state: target };
+                    self.report.violations.push(violation);
+                    return;
+                }
+                let Some(dt) = self.state.try_dt_to(target) else {
+                    let (from, to) = (self.state, target);
+                    let violation = SimViolation::IllegalTransition { task: i, t, from, to };
+                    self.report.violations.push(violation);
+                    return;
+                };
+                let end = t + TimeDelta::from_std(dt).unwrap_or(TimeDelta::zero());
+                self.transition = Some((target, end));
+                self.state = FlightState::Transition;
+                self.busy_until = end;
+            }
+            BaseTask::TakeImage(_) => self.require_state(i, FlightState::Acquisition),
+            BaseTask::ChangeVelocity(vel_change) => {
+                self.require_state(i, FlightState::Acquisition);
+                let burn = vel_change.burn();
+                if self.batt < burn.min_charge() {
+                    self.report.violations.push(SimViolation::InsufficientCharge {
+                        task: i,
+                        t,
+                        needed: burn.min_charge(),
+                        available: self.batt,
+                    });
+                }
+                if self.fuel < burn.min_fuel() {
+                    self.report.violations.push(SimViolation::InsufficientFuel {
+                        task: i,
+                        t,
+                        needed: burn.min_fuel(),
+                        available: self.fuel,
+                    });
+                }
+                self.advance_to(t + TimeBudget::secs(burn.acc_dt()));
+                let acc_drain = FlightState::ACQ_ACC_ADDITION * I32F32::from_num(burn.acc_dt());
+                self.set_batt(self.batt + acc_drain);
+                self.fuel = (self.fuel - burn.min_fuel()).max(I32F32::ZERO);
+                self.busy_until = self.clock.now();
+            }
+        }
+    }
+
+    /// Records a violation if MELVIN is not in the `required` state.
+    fn require_state(&mut self, i: usize, required: FlightState) {
+        if self.state != required {
+            let (t, actual) = (self.clock.now(), self.state);
+            self.report.violations.push(SimViolation::WrongState { task: i, t, required, actual });
+        }
+    }
+
+    /// Advances the virtual clock to `t`, completing a running transition on the way.
+    fn advance_to(&mut self, t: DateTime<Utc>) {
+        if let Some((target, end)) = self.transition.filter(|(_, end)| *end <= t) {
+            self.integrate(end);
+            self.state = target;
+            self.transition = None;
+        }
+        self.integrate(t);
+    }
+
+    /// Evolves the battery in the current state until `t` and advances the virtual clock.
+    fn integrate(&mut self, t: DateTime<Utc>) {
+        let now = self.clock.now();
+        if t <= now {
+            return;
+        }
+        self.clock.advance_to(t);
+        self.set_batt(self.state.batt_in_dt_from(self.batt, t - now));
+    }
+
+    /// Sets the battery level, recording a depletion, a threshold crossing and the minimum level.
+    fn set_batt(&mut self, batt: I32F32) {
+        let (t, clamped) = (self.clock.now(), batt.clamp(I32F32::ZERO, Self::MAX_BATT));
+        if let Some(threshold) = self.threshold.filter(|th| batt < *th && self.batt >= *th) {
+            let violation = SimViolation::BelowThreshold { t, batt: clamped, threshold };
+            self.report.violations.push(violation);
+        }
+        if batt <= I32F32::ZERO && self.batt > I32F32::ZERO {
+            self.report.violations.push(SimViolation::BatteryDepleted { t });
+        }
+        self.batt = clamped;
+        self.report.min_batt = self.report.min_batt.min(self.batt);
+    }
+
+    /// Appends the current levels to the trajectory.
+    fn sample(&mut self) {
+        let (t, state, batt, fuel) = (self.clock.now(), self.state, self.batt, self.fuel);
+        self.report.trajectory.push(SimSample { t, state, batt, fuel });
+    }
+}
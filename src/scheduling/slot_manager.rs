@@ -0,0 +1,171 @@
+use crate::http_handler::{
+    CommunicationSlot,
+    http_client::HTTPClient,
+    http_request::{
+        available_slots_get::AvailableSlotsRequest, modify_slot_put::ModifySlotRequest,
+        request_common::NoBodyHTTPRequestType,
+    },
+};
+use super::{EventRegistry, task::ExternalEvent};
+use crate::{info, log, warn};
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Books the communication slots announced by the DRS according to the planned comms windows.
+///
+/// The slots are fetched before every comms-aware scheduling run. Booked slots are handed to
+/// the orbit scheduling DP as hard constraints, and after scheduling, slots are booked or
+/// released so that they match the `Comms` windows of the new schedule. Slots that already
+/// opened are never released. Every confirmed booking fires an [`ExternalEvent::SlotBooked`],
+/// which releases the switch into the comms window planned over the slot.
+pub struct SlotManager {
+    /// The HTTP client for the slot requests.
+    client: Arc<HTTPClient>,
+    /// The lock-protected slots that did not close yet, as last reported by the DRS.
+    slots: RwLock<Vec<CommunicationSlot>>,
+}
+
+impl SlotManager {
+    /// Creates a new [`SlotManager`] without known slots.
+    ///
+    /// # Arguments
+    /// - `client`: The HTTP client for the slot requests.
+    pub(crate) fn new(client: Arc<HTTPClient>) -> Self { Self { client, slots: RwLock::new(Vec::new()) } }
+
+    /// Fetches the available slots from the DRS, keeping the known ones if the request fails.
+    pub async fn refresh(&self) {
+        let req = AvailableSlotsRequest {};
+        match req.send_request(&self.client).await {
+            Ok(resp) => {
+                let used = resp.slots_used();
+                let now = Utc::now();
+                let mut slots = resp.into_slots();
+                slots.retain(|s| s.end() > now);
+                log!("Fetched {} open communication slots, {used} used.", slots.len());
+                *self.slots.write().await = slots;
+            }
+            Err(e) => warn!("Failed to fetch communication slots: {e}"),
+        }
+    }
+
+    /// Returns the start and end times of the booked slots that did not close yet.
+    pub async fn booked_windows(&self) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+        let now = Utc::now();
+        self.slots
+            .read()
+            .await
+            .iter()
+            .filter(|s| s.is_enabled() && s.end() > now)
+            .map(|s| (s.start(), s.end()))
+            .collect()
+    }
+
+    /// Books the slots overlapping a planned comms window and releases the remaining booked
+    /// slots that did not open yet.
+    ///
+    /// # Arguments
+    /// - `windows`: The start and end times of the planned comms windows.
+    /// - `events`: The registry in which the confirmed bookings are fired.
+    pub async fn sync(&self, windows: &[(DateTime<Utc>, DateTime<Utc>)], events: &EventRegistry) {
+        let changes = Self::plan_changes(&self.slots.read().await, windows, Utc::now());
+        if !changes.is_empty() {
+            let mut booked = 0;
+            let mut released = 0;
+            for (slot_id, enabled) in changes {
+                let req = ModifySlotRequest { slot_id, enabled };
+                match req.send_request(&self.client).await {
+                    Ok(resp) => {
+                        let mut slots = self.slots.write().await;
+                        if let Some(slot) = slots.iter_mut().find(|s| s.id() == resp.id()) {
+                            slot.set_enabled(resp.enabled());
+                        }
+                        if resp.enabled() { booked += 1 } else { released += 1 }
+                    }
+                    Err(e) => warn!("Failed to modify communication slot {slot_id}: {e}"),
+                }
+            }
+            info!("Booked {booked} and released {released} communication slots.");
+        }
+        for slot in self.slots.read().await.iter().filter(|s| s.is_enabled()) {
+            events.fire(ExternalEvent::SlotBooked(slot.id()));
+        }
+    }
+
+    /// Returns the slot each planned comms window relies on, i.e. the first known slot
+    /// overlapping it.
+    ///
+    /// # Arguments
+    /// - `windows`: The start and end times of the planned comms windows.
+    ///
+    /// # Returns
+    /// - The start times of the windows overlapped by a slot, together with the slot ID.
+    pub async fn window_slots(
+        &self,
+        windows: &[(DateTime<Utc>, DateTime<Utc>)],
+    ) -> Vec<(DateTime<Utc>, usize)> {
+        let slots = self.slots.read().await;
+        windows
+            .iter()
+            .filter_map(|(start, end)| {
+                let slot = slots.iter().find(|s| s.overlaps(*start, *end))?;
+                Some((*start, slot.id()))
+            })
+            .collect()
+    }
+
+    /// Determines the slot modifications needed to match the planned comms windows.
+    ///
+    /// # Arguments
+    /// - `slots`: The known slots.
+    /// - `windows`: The start and end times of the planned comms windows.
+    /// - `now`: The current time.
+    ///
+    /// # Returns
+    /// - The IDs of the slots to modify with their new booking status.
+    fn plan_changes(
+        slots: &[CommunicationSlot],
+        windows: &[(DateTime<Utc>, DateTime<Utc>)],
+        now: DateTime<Utc>,
+    ) -> Vec<(usize, bool)> {
+        slots
+            .iter()
+            .filter(|s| s.end() > now)
+            .filter_map(|s| {
+                let wanted = windows.iter().any(|(start, end)| s.overlaps(*start, *end));
+                let book = wanted && !s.is_enabled();
+                let release = !wanted && s.is_enabled() && s.start() > now;
+                (book || release).then_some((s.id(), wanted))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+
+    #[test]
+    fn test_slot_plan_changes() {
+        let now = Utc::now();
+        let at = |mins| now + TimeDelta::minutes(mins);
+        let slot = |id, start, end, enabled| -> CommunicationSlot {
+            serde_json::from_value(serde_json::json!({
+                "id": id, "start": at(start), "end": at(end), "enabled": enabled,
+            }))
+            .unwrap()
+        };
+        let slots = [
+            slot(1, -20, -10, true),
+            slot(2, -5, 5, true),
+            slot(3, 10, 20, false),
+            slot(4, 30, 40, true),
+            slot(5, 50, 60, true),
+            slot(6, 70, 80, true),
+        ];
+        let windows = [(at(15), at(35)), (at(75), at(90))];
+        let changes = SlotManager::plan_changes(&slots, &windows, now);
+        assert_eq!(changes, [(3, true), (5, false)]);
+    }
+}
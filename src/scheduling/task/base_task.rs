@@ -1,24 +1,30 @@
 use super::{
     image_task::ImageTask,
     switch_state_task::SwitchStateTask,
+    task_dependency::NotEarlierThan,
     vel_change_task::VelocityChangeTask,
 };
 use crate::fatal;
 use crate::imaging::CameraAngle;
 use crate::util::Vec2D;
 use crate::flight_control::{FlightState, orbit::BurnSequence};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, SubsecRound, TimeDelta, Utc};
 use std::fmt::{Display, Formatter};
 use strum_macros::Display;
 
 /// Represents a task with a specific type and associated time delay.
 /// Tasks can include image capture, state switching, or velocity changes.
 #[derive(Debug)]
+#[allow(clippy::struct_field_names)]
 pub struct Task {
     /// The specific type of the task.
     task_type: BaseTask,
     /// The pinned time delay associated with the task's execution.
     t: DateTime<Utc>,
+    /// An optional external event the task must not be executed before.
+    dependency: Option<NotEarlierThan>,
+    /// Whether the task was pinned by an operator and must survive rescheduling.
+    pinned: bool,
 }
 
 /// An enumeration representing different types of tasks.
@@ -35,6 +41,69 @@ pub enum BaseTask {
     ChangeVelocity(VelocityChangeTask),
 }
 
+/// The resolution with which the executor honors the due time of a task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeResolution {
+    /// The task may be executed up to a few seconds early.
+    Seconds,
+    /// The task is executed at its due time with millisecond precision.
+    Millis,
+}
+
+/// The class of a task, used to query a [`TaskQueue`](crate::scheduling::TaskQueue) for the
+/// next task of a kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TaskClass {
+    /// Image capture tasks.
+    Image,
+    /// Flight state switches.
+    Switch,
+    /// Velocity changes.
+    Burn,
+}
+
+/// The priority of a task when it competes with other tasks for the same time.
+///
+/// Critical tasks precede routine tasks due at the same time and may displace conflicting
+/// routine tasks when spliced into a [`TaskQueue`](crate::scheduling::TaskQueue).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TaskPriority {
+    /// State switches planned by the orbit scheduler, which can be replanned.
+    Routine,
+    /// Burns, images and pinned operator tasks, which serve an objective.
+    Critical,
+}
+
+impl BaseTask {
+    /// Returns the [`TaskClass`] of this task type.
+    pub fn class(&self) -> TaskClass {
+        match self {
+            BaseTask::TakeImage(_) => TaskClass::Image,
+            BaseTask::SwitchState(_) => TaskClass::Switch,
+            BaseTask::ChangeVelocity(_) => TaskClass::Burn,
+        }
+    }
+
+    /// Returns the resolution with which the executor honors the due time of this task type.
+    ///
+    /// Image tasks need millisecond precision to hit narrow zones mid-footprint, state
+    /// switches and burns keep second resolution.
+    pub fn resolution(&self) -> TimeResolution {
+        match self {
+            BaseTask::TakeImage(_) => TimeResolution::Millis,
+            BaseTask::SwitchState(_) | BaseTask::ChangeVelocity(_) => TimeResolution::Seconds,
+        }
+    }
+
+    /// Returns whether this task type is fired ahead of its due time by the command latency.
+    ///
+    /// State switches and burns take effect once the backend received their command, image
+    /// tasks are timed by the captured observation instead.
+    pub fn is_latency_compensated(&self) -> bool {
+        matches!(self, BaseTask::SwitchState(_) | BaseTask::ChangeVelocity(_))
+    }
+}
+
 impl Display for Task {
     /// Formats the task for display purposes.
     ///
@@ -54,7 +123,10 @@ impl Display for Task {
                 )
             }
         };
-        let end = self.t.format("%d %H:%M:%S").to_string();
+        let end = match self.task_type.resolution() {
+            TimeResolution::Seconds => self.t.format("%d %H:%M:%S").to_string(),
+            TimeResolution::Millis => self.t.format("%d %H:%M:%S%.3f").to_string(),
+        };
         write!(f, "Due: {end}, Task: {task_type_str}")
     }
 }
@@ -64,7 +136,7 @@ impl Task {
     ///
     /// # Arguments
     /// - `target_state`: The desired flight state to switch to.
-    /// - `t`: The time delay associated with the task's execution.
+    /// - `t`: The time delay associated with the task's execution, truncated to whole seconds.
     ///
     /// # Returns
     /// - A new [`Task`] instance representing the state switch task.
@@ -77,7 +149,9 @@ impl Task {
                 SwitchStateTask::new(target_state)
                     .unwrap_or_else(|| fatal!("Tried to schedule invalid state switch")),
             ),
-            t,
+            t: t.trunc_subsecs(0),
+            dependency: None,
+            pinned: false,
         }
     }
 
@@ -86,12 +160,17 @@ impl Task {
     /// # Arguments
     /// - `planned_pos`: The target position for capturing the image.
     /// - `lens`: The camera lens configuration.
-    /// - `t`: The time delay associated with the task's execution.
+    /// - `t`: The time delay associated with the task's execution, kept in milliseconds.
     ///
     /// # Returns
     /// - A new `Task` instance representing the image capture task.
     pub fn image_task(planned_pos: Vec2D<u32>, lens: CameraAngle, t: DateTime<Utc>) -> Self {
-        Self { task_type: BaseTask::TakeImage(ImageTask::new(planned_pos, lens)), t }
+        Self {
+            task_type: BaseTask::TakeImage(ImageTask::new(planned_pos, lens)),
+            t: t.trunc_subsecs(3),
+            dependency: None,
+            pinned: false,
+        }
     }
 
     /// Creates a new task for velocity change.
@@ -106,17 +185,64 @@ impl Task {
         burn: BurnSequence,
         t: DateTime<Utc>,
     ) -> Self {
-        Self { task_type: BaseTask::ChangeVelocity(VelocityChangeTask::new(burn)), t }
+        Self {
+            task_type: BaseTask::ChangeVelocity(VelocityChangeTask::new(burn)),
+            t,
+            dependency: None,
+            pinned: false,
+        }
+    }
+
+    /// Adds a "no-earlier-than" dependency on an external event to the task.
+    ///
+    /// # Arguments
+    /// - `dependency`: The event dependency and its timeout policy.
+    ///
+    /// # Returns
+    /// - The task holding the dependency.
+    pub fn with_dependency(mut self, dependency: NotEarlierThan) -> Self {
+        self.dependency = Some(dependency);
+        self
     }
+
+    /// Pins the task, so that rescheduling keeps it and plans around it.
+    ///
+    /// # Returns
+    /// - The pinned task.
+    pub fn pin(mut self) -> Self {
+        self.pinned = true;
+        self
+    }
+
     /// Returns an immutable reference to the task's time delay.
     ///
     /// # Returns
     /// - An `DateTime<Utc>` representing the tasks due time.
     pub fn t(&self) -> DateTime<Utc> { self.t }
 
+    /// Moves the task's due time earlier by the given lead time.
+    ///
+    /// # Arguments
+    /// - `lead`: The `TimeDelta` by which the task should be executed earlier.
+    pub fn advance(&mut self, lead: TimeDelta) { self.t -= lead; }
+
     /// Returns an immutable reference to the task's type.
     ///
     /// # Returns
     /// - An immutable reference to the `BaseTask`.
     pub fn task_type(&self) -> &BaseTask { &self.task_type }
+
+    /// Returns the task's external event dependency, if any.
+    pub fn dependency(&self) -> Option<&NotEarlierThan> { self.dependency.as_ref() }
+
+    /// Returns `true` if the task was pinned by an operator.
+    pub fn is_pinned(&self) -> bool { self.pinned }
+
+    /// Returns the [`TaskPriority`] of the task. Pinned tasks are always critical.
+    pub fn priority(&self) -> TaskPriority {
+        match self.task_type.class() {
+            TaskClass::Switch if !self.pinned => TaskPriority::Routine,
+            _ => TaskPriority::Critical,
+        }
+    }
 }
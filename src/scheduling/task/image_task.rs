@@ -1,6 +1,6 @@
 use crate::imaging::CameraAngle;
 use crate::util::Vec2D;
-use fixed::types::I64F64;
+use fixed::types::{I32F32, I64F64};
 
 /// Represents the status of an image capture task.
 #[derive(Debug, Copy, Clone)]
@@ -59,4 +59,18 @@ impl ImageTask {
         let new_status = ImageTaskStatus::Done { actual_pos, px_dev_rel };
         self.image_status = new_status;
     }
+
+    /// Checks whether an image taken at the given position covers the planned position.
+    ///
+    /// # Arguments
+    /// - `pos`: The position of MELVIN.
+    ///
+    /// # Returns
+    /// - `true` if the planned position lies within the footprint of the lens.
+    pub fn covers(&self, pos: Vec2D<I32F32>) -> bool {
+        let planned = Vec2D::<I32F32>::from_real(&self.planned_pos);
+        let dev = pos.wrap_around_map().unwrapped_to(&planned);
+        let half_side = I32F32::from_num(self.lens.get_square_side_length() / 2);
+        dev.x().abs() <= half_side && dev.y().abs() <= half_side
+    }
 }
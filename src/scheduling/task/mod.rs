@@ -4,9 +4,13 @@
 mod base_task;
 mod image_task;
 mod switch_state_task;
+mod task_dependency;
 mod vel_change_task;
 
 pub use switch_state_task::SwitchStateTask;
 pub use base_task::Task;
 pub use base_task::BaseTask;
-pub use image_task::ImageTaskStatus;
+pub use base_task::{TaskClass, TaskPriority};
+pub use base_task::TimeResolution;
+pub use image_task::{ImageTask, ImageTaskStatus};
+pub use task_dependency::{ExternalEvent, NotEarlierThan, TimeoutPolicy};
@@ -0,0 +1,54 @@
+use crate::util::ImgObjectiveId;
+use chrono::TimeDelta;
+use strum_macros::Display;
+
+/// Typed external events a [`Task`](super::Task) can depend on.
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExternalEvent {
+    /// The DRS backend accepted an image submitted for the objective with the given ID.
+    ObjectiveAccepted(ImgObjectiveId),
+    /// The DRS backend confirmed the booking of the communication slot with the given ID.
+    SlotBooked(usize),
+}
+
+/// What to do with a task whose dependency did not fire in time.
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq)]
+pub enum TimeoutPolicy {
+    /// Execute the task anyway.
+    Execute,
+    /// Drop the task without executing it.
+    Skip,
+}
+
+/// A "no-earlier-than" dependency of a task on an [`ExternalEvent`].
+///
+/// The executor holds the task beyond its due time until the event fires or the
+/// timeout elapses, in which case the [`TimeoutPolicy`] decides on the task.
+#[derive(Debug, Clone, Copy)]
+pub struct NotEarlierThan {
+    /// The event the task waits for.
+    event: ExternalEvent,
+    /// The maximum time the task is held after its due time.
+    timeout: TimeDelta,
+    /// The policy applied when the timeout elapses.
+    policy: TimeoutPolicy,
+}
+
+impl NotEarlierThan {
+    /// Creates a new [`NotEarlierThan`] dependency.
+    ///
+    /// # Arguments
+    /// - `event`: The event the task waits for.
+    /// - `timeout`: The maximum time the task is held after its due time.
+    /// - `policy`: The policy applied when the timeout elapses.
+    pub fn new(event: ExternalEvent, timeout: TimeDelta, policy: TimeoutPolicy) -> Self {
+        Self { event, timeout, policy }
+    }
+
+    /// Returns the event the task waits for.
+    pub fn event(&self) -> ExternalEvent { self.event }
+    /// Returns the maximum time the task is held after its due time.
+    pub fn timeout(&self) -> TimeDelta { self.timeout }
+    /// Returns the policy applied when the timeout elapses.
+    pub fn policy(&self) -> TimeoutPolicy { self.policy }
+}
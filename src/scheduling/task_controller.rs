@@ -1,25 +1,50 @@
-use super::{AtomicDecision, AtomicDecisionCube, EndCondition, LinkedBox, ScoreGrid, task::Task};
+use super::{
+    AtomicDecision, AtomicDecisionCube, Clock, CommsBias, EndCondition, EventRegistry, LinkedBox, ScoreGrid,
+    SlotManager, TaskQueue, TaskTimingReport, WallClock, ZoCandidate, ZoLeg,
+    task::{BaseTask, ExternalEvent, NotEarlierThan, Task, TaskClass, TimeoutPolicy},
+};
 use crate::imaging::CameraAngle;
 use crate::flight_control::{FlightComputer, FlightState,
     orbit::{
-        BurnSequence, BurnSequenceEvaluator, ClosedOrbit, ExitBurnResult, IndexedOrbitPosition,
+        BurnSequence, BurnSequenceEvaluator, ClosedOrbit, CoverageForecast, ExitBurnResult,
+        IndexedOrbitPosition, OrbitPositionTable,
     },
 };
-use crate::util::Vec2D;
-use crate::{error, info, log};
+use crate::util::{
+    EVENT_BUS, ImgObjectiveId, MissionConfig, MissionTime, PLANNING_POOL, SchedulingEvent,
+    TimeBudget, Vec2D, logger::JsonDump,
+};
+use crate::{error, info, log, warn};
 use bitvec::prelude::BitRef;
 use chrono::{DateTime, TimeDelta, Utc};
-use fixed::types::{I32F32, I96F32};
-use num::Zero;
-use std::{collections::VecDeque, fmt::Debug, sync::Arc};
+use fixed::types::I32F32;
+use std::{
+    collections::BTreeMap,
+    fmt::Debug,
+    sync::{Arc, LazyLock},
+};
 use tokio::sync::RwLock;
 
+/// The minimum and maximum battery level of all scheduling operations.
+///
+/// They are read once from the `scheduling` section of the [`MissionConfig`], as every planned
+/// schedule and battery mapping of the dynamic programs depends on them.
+static BATTERY_THRESHOLDS: LazyLock<(I32F32, I32F32)> = LazyLock::new(|| {
+    let config = MissionConfig::get();
+    let sched = &config.scheduling;
+    (I32F32::from_num(sched.min_battery), I32F32::from_num(sched.max_battery))
+});
+
 /// [`TaskController`] manages and schedules tasks for MELVIN.
 /// It leverages a thread-safe task queue and powerful scheduling algorithms.
 #[derive(Debug)]
 pub struct TaskController {
     /// Schedule for the next task, e.g. state switches, burn sequences, ...
-    task_schedule: Arc<RwLock<VecDeque<Task>>>,
+    task_schedule: Arc<RwLock<TaskQueue>>,
+    /// Planned vs. actual execution timing of already executed tasks.
+    timing_report: RwLock<TaskTimingReport>,
+    /// External events that tasks with "no-earlier-than" dependencies wait for.
+    events: EventRegistry,
 }
 
 /// Helper Struct holding the result of the optimal orbit dynamic program
@@ -30,45 +55,140 @@ struct OptimalOrbitResult {
     pub coverage_slice: LinkedBox<ScoreGrid>,
 }
 
+/// The change of the DP battery index in a single [`FlightState`].
+///
+/// Rates below the battery resolution are applied as a single step every `period` seconds
+/// instead of being rounded to zero.
+#[derive(Debug, Clone, Copy)]
+struct EnergyStep {
+    /// The battery index change applied once per period.
+    de: isize,
+    /// The number of seconds between two applied changes.
+    period: usize,
+}
+
+impl EnergyStep {
+    /// Returns the battery index change at the DP time step `t`.
+    fn at(self, t: usize) -> isize { if t % self.period == 0 { self.de } else { 0 } }
+}
+
+/// A constraint of the orbit scheduling DP imposed by an operator-pinned task.
+#[derive(Debug, Clone, Copy)]
+enum PinConstraint {
+    /// MELVIN switches to the given DP state at the pinned time step.
+    Switch(usize),
+    /// MELVIN is in the given DP state at the pinned time step, e.g. to take an image.
+    Hold(usize),
+}
+
+/// A snapshot of the pending task schedule, persisted e.g. on shutdown.
+#[derive(Debug, serde::Serialize)]
+struct ScheduleSnapshot {
+    /// The time the snapshot was taken.
+    t: DateTime<Utc>,
+    /// The due time and description of every pending task in execution order.
+    tasks: Vec<(DateTime<Utc>, String)>,
+}
+
+impl JsonDump for ScheduleSnapshot {
+    /// Returns the file name for the JSON dump of the schedule.
+    fn file_name(&self) -> String { format!("schedule_{}", self.t.format("%d_%H_%M_%S")) }
+
+    /// Returns the directory name for the schedule JSON files.
+    fn dir_name(&self) -> &'static str { "schedule" }
+}
+
+impl Default for TaskController {
+    fn default() -> Self { Self::new() }
+}
+
 impl TaskController {
     /// The maximum number of seconds for orbit prediction calculations.
-    const MAX_ORBIT_PREDICTION_SECS: u32 = 80000;
+    const MAX_ORBIT_PREDICTION_SECS: usize = 80000;
     /// The resolution for battery levels used in calculations, expressed in fixed-point format.
     const BATTERY_RESOLUTION: I32F32 = I32F32::lit("0.1");
-    /// The minimum batter threshold for all scheduling operations
-    pub const MIN_BATTERY_THRESHOLD: I32F32 = I32F32::lit("10.00");
-    /// The maximum battery treshold for all scheduling operations
-    pub const MAX_BATTERY_THRESHOLD: I32F32 = I32F32::lit("90.00");
     /// The resolution for time duration calculations, expressed in fixed-point format.
     const TIME_RESOLUTION: I32F32 = I32F32::lit("1.0");
     /// The minimum delta time for scheduling objectives, in seconds.
     const OBJECTIVE_SCHEDULE_MIN_DT: usize = 1000;
     /// The minimum tolerance for retrieving scheduled objectives.
-    const OBJECTIVE_MIN_RETRIEVAL_TOL: usize = 100;
+    const OBJECTIVE_MIN_RETRIEVAL_TOL: TimeBudget = TimeBudget::secs(100);
+    /// The maximum time window considered for retrieving an objective.
+    const OBJECTIVE_MAX_RETRIEVAL_WINDOW: TimeBudget = TimeBudget::secs(8 * 3600);
+    /// The maximum number of objectives retrieved after the one an exit burn aims for.
+    const MAX_ZO_LEGS: usize = 3;
+    /// The maximum distance between two consecutive imaging positions of a combined retrieval.
+    const MAX_ZO_LEG_DIST: I32F32 = I32F32::lit("2000");
+    /// The maximum turn angle in degrees between two legs of a combined retrieval.
+    const MAX_ZO_LEG_TURN_ANGLE: I32F32 = I32F32::lit("45");
+    /// The time spent imaging a single zone before turning towards the next one.
+    const ZO_LEG_ACQ_DT: TimeDelta = TimeDelta::seconds(20);
     /// The initial battery threshold for performing a maneuver.
     const MANEUVER_INIT_BATT_TOL: I32F32 = I32F32::lit("10.0");
     /// The minimum delta time required for detumble maneuvers, in seconds.
     pub(crate) const MANEUVER_MIN_DETUMBLE_DT: usize = 20;
     /// The Delay for imaging objectives when the first image should be shot
     pub const ZO_IMAGE_FIRST_DEL: TimeDelta = TimeDelta::seconds(5);
-    /// The number of seconds that are planned per acquisition cycle
-    pub const IN_COMMS_SCHED_SECS: usize = 1100;
+    /// The time that is planned per acquisition cycle
+    pub const IN_COMMS_SCHED_DT: TimeBudget = TimeBudget::secs(1100);
     /// The period (number of seconds) after which another comms sequence should be scheduled.
     const COMMS_SCHED_PERIOD: usize = 800;
-    /// The usable `TimeDelta` between communication state switches
+    /// The nominal usable `TimeDelta` between communication state switches
     #[allow(clippy::cast_possible_wrap)]
     const COMMS_SCHED_USABLE_TIME: TimeDelta =
         TimeDelta::seconds((Self::COMMS_SCHED_PERIOD - 2 * 180) as i64);
-    /// The charge usage per strictly timed communication cycle
-    pub const COMMS_CHARGE_USAGE: I32F32 = I32F32::lit("9.00");
     /// The minimum charge needed to enter communication state
     pub const MIN_COMMS_START_CHARGE: I32F32 = I32F32::lit("20.0");
+    /// The states of the orbit scheduling DP, indexed by their DP index.
+    const DP_STATES: [FlightState; 3] =
+        [FlightState::Charge, FlightState::Acquisition, FlightState::Comms];
+    /// The DP score of a newly covered second in `Acquisition`.
+    const ACQ_SCORE: i32 = 8;
+    /// The tolerance within which a planned state switch is covered by a pinned one.
+    const PIN_MATCH_TOL: TimeDelta = TimeDelta::seconds(1);
+    /// The nominal DP score of a second in `Comms` while beacon objectives are active.
+    const COMMS_SCORE: f64 = 2.0;
+    /// The maximum time a switch into a comms window is held for the booking of its slot.
+    const SLOT_BOOKING_TIMEOUT: TimeDelta = TimeDelta::seconds(10);
+
+    /// Returns the minimum battery threshold for all scheduling operations, see
+    /// [`BATTERY_THRESHOLDS`].
+    pub fn min_battery_threshold() -> I32F32 { BATTERY_THRESHOLDS.0 }
+
+    /// Returns the maximum battery threshold for all scheduling operations, see
+    /// [`BATTERY_THRESHOLDS`].
+    pub fn max_battery_threshold() -> I32F32 { BATTERY_THRESHOLDS.1 }
+
+    /// Returns the usable `TimeDelta` between communication state switches, shortened or
+    /// lengthened by the comms aggressiveness of the [`MissionConfig`].
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    pub(super) fn comms_usable_time() -> TimeDelta {
+        let aggressiveness = MissionConfig::get().runtime.comms_aggressiveness;
+        let secs = Self::COMMS_SCHED_USABLE_TIME.num_seconds() as f64 / aggressiveness;
+        TimeDelta::seconds(secs.round() as i64)
+    }
+
+    /// Returns the maximum prediction duration of the orbit scheduling DP, limited by the orbit
+    /// period.
+    ///
+    /// # Arguments
+    /// * `orbit` - The closed orbit that is scheduled.
+    fn max_prediction_dt(orbit: &ClosedOrbit) -> TimeBudget {
+        let period = orbit.period().0.to_num::<usize>();
+        TimeBudget::secs(Self::MAX_ORBIT_PREDICTION_SECS.min(period))
+    }
 
     /// Creates a new instance of the [`TaskController`] struct.
     ///
     /// # Returns
     /// - A new [`TaskController`] with an empty task schedule.
-    pub fn new() -> Self { Self { task_schedule: Arc::new(RwLock::new(VecDeque::new())) } }
+    pub fn new() -> Self {
+        Self {
+            task_schedule: Arc::new(RwLock::new(TaskQueue::new())),
+            timing_report: RwLock::new(TaskTimingReport::default()),
+            events: EventRegistry::new(),
+        }
+    }
 
     /// Initializes the optimal orbit schedule calculation.
     ///
@@ -78,8 +198,13 @@ impl TaskController {
     /// # Arguments
     /// * `orbit` - Reference to the [`ClosedOrbit`] structure representing the current orbit configuration.
     /// * `p_t_shift` - The starting index used to shift and reorder the bitvector of the orbit.
-    /// * `dt` - Optional maximum prediction duration in seconds. If `None`, defaults to the orbit period or the maximum prediction length.
-    /// * `end_status` - Optional tuple containing the end flight state ([`FlightState`]) and battery level (`I32F32`) constraints.
+    /// * `dt` - Optional maximum prediction duration. If `None`, defaults to the orbit period or the maximum prediction length.
+    /// * `end_state` - Optional terminal [`FlightState`] constraint.
+    /// * `end_batt` - Optional terminal minimum battery level constraint.
+    /// * `comms` - Optional duration in which beacon scanning is scored, together with the
+    ///   [`CommsBias`] weighting its seconds. If given, `Comms` is modeled as a third state next
+    ///   to `Charge` and `Acquisition`.
+    /// * `pins` - The constraints of the pinned tasks by their DP time step.
     ///
     /// # Returns
     /// * `OptimalOrbitResult` - The final result containing calculated decisions and coverage slice used in the optimization.
@@ -87,25 +212,20 @@ impl TaskController {
     fn init_sched_dp(
         orbit: &ClosedOrbit,
         p_t_shift: usize,
-        dt: Option<usize>,
+        dt: Option<TimeBudget>,
         end_state: Option<FlightState>,
         end_batt: Option<I32F32>,
+        comms: Option<(TimeBudget, &CommsBias)>,
+        pins: &BTreeMap<usize, PinConstraint>,
     ) -> OptimalOrbitResult {
-        // List of potential states during the orbit scheduling process.
-        let states = [FlightState::Charge, FlightState::Acquisition];
+        // Number of potential states during the orbit scheduling process.
+        let s_len = if comms.is_some() { Self::DP_STATES.len() } else { 2 };
         // Calculate the usable battery range based on the fixed thresholds.
-        let usable_batt_range = Self::MAX_BATTERY_THRESHOLD - Self::MIN_BATTERY_THRESHOLD;
+        let usable_batt_range = Self::max_battery_threshold() - Self::min_battery_threshold();
         // Determine the maximum number of battery levels that can be represented.
         let max_battery = (usable_batt_range / Self::BATTERY_RESOLUTION).round().to_num::<usize>();
         // Determine the prediction duration in seconds, constrained by the orbit period or `dt` if provided.
-        let prediction_secs = {
-            if let Some(pred_secs) = dt {
-                // Ensure the prediction duration does not exceed the maximum prediction length or the provided duration.
-                pred_secs
-            } else {
-                Self::MAX_ORBIT_PREDICTION_SECS.min(orbit.period().0.to_num::<u32>()) as usize
-            }
-        };
+        let prediction_secs = dt.unwrap_or_else(|| Self::max_prediction_dt(orbit)).as_secs();
 
         // Retrieve a reordered iterator over the orbit's completion bitvector to optimize scheduling.
         let p_t_iter = orbit.get_p_t_reordered(
@@ -113,19 +233,24 @@ impl TaskController {
             orbit.period().0.to_num::<usize>() - prediction_secs,
         );
         // Create a blank decision buffer and score grid for the orbit schedule calculation.
-        let decision_buffer =
-            AtomicDecisionCube::new(prediction_secs, max_battery + 1, states.len());
-        let cov_dt_temp = ScoreGrid::new(max_battery + 1, states.len());
+        let decision_buffer = AtomicDecisionCube::new(prediction_secs, max_battery + 1, s_len);
+        let cov_dt_temp = ScoreGrid::new(max_battery + 1, s_len);
         // Initialize the first coverage grid based on the end status or use a default grid.
         let cov_dt_first = {
             let batt = end_batt.map_or(max_battery + 1, Self::map_e_to_dp);
-            let state = end_state.map(|o| o as usize);
+            let state = end_state.map(FlightState::to_dp_usize);
             let end_cast = (state, batt);
-            ScoreGrid::new_from_condition(max_battery + 1, states.len(), end_cast)
+            ScoreGrid::new_from_condition(max_battery + 1, s_len, end_cast)
         };
-        // Initialize a linked list of score cubes with a fixed size and push the initial coverage grid.
-        let mut score_cube = LinkedBox::new(180);
+        // Initialize a linked list of score cubes spanning the longest transition and push the initial coverage grid.
+        let max_trans_dt = Self::dp_transition_dts(s_len).into_iter().flatten().max().unwrap_or(1);
+        let mut score_cube = LinkedBox::new(max_trans_dt);
         score_cube.push(cov_dt_first);
+        // Score the seconds in `Comms` by their distance to the candidate beacon locations.
+        let comms_scores = comms.map_or_else(Vec::new, |(comms_dt, bias)| {
+            let comms_secs = comms_dt.as_secs().min(prediction_secs);
+            bias.scores(orbit, p_t_shift, comms_secs, Self::comms_score())
+        });
         // Perform the calculation for the optimal orbit schedule using the prepared variables.
         Self::calculate_optimal_orbit_schedule(
             prediction_secs,
@@ -133,6 +258,8 @@ impl TaskController {
             score_cube,
             &cov_dt_temp,
             decision_buffer,
+            &comms_scores,
+            pins,
         )
     }
 
@@ -140,7 +267,11 @@ impl TaskController {
     ///
     /// This function iterates backward over a prediction window (`pred_dt`) to compute the best decisions
     /// and score grid values for optimizing orbit transitions. It uses battery levels, state transitions,
-    /// and the orbits `done`-`BitBox`.
+    /// and the orbits `done`-`BitBox`. Newly covered seconds in `Acquisition` and, within the first
+    /// `comms_scores.len()` seconds, every second in `Comms` are scored.
+    ///
+    /// At the time steps of pinned tasks the decision is forced, and transitions spanning a
+    /// pinned time step are ruled out, so that the schedule is planned around the pins.
     ///
     /// # Arguments
     /// - `pred_dt`: The number of prediction time steps.
@@ -148,6 +279,8 @@ impl TaskController {
     /// - `score_cube`: A linked list holding previous and current score grids for dynamic programming.
     /// - `score_grid_default`: A grid initialized with default scores used during calculations.
     /// - `dec_cube`: A decision cube to store the selected actions at each time step.
+    /// - `comms_scores`: The score of each second in `Comms` while beacon scanning is scored.
+    /// - `pins`: The constraints of the pinned tasks by their DP time step.
     ///
     /// # Returns
     /// - `OptimalOrbitResult`: Contains the final decision cube and the score grid linked box.
@@ -158,44 +291,80 @@ impl TaskController {
         mut score_cube: LinkedBox<ScoreGrid>,
         score_grid_default: &ScoreGrid,
         mut dec_cube: AtomicDecisionCube,
+        comms_scores: &[i32],
+        pins: &BTreeMap<usize, PinConstraint>,
     ) -> OptimalOrbitResult {
         let max_battery = score_grid_default.e_len() - 1;
+        let s_len = score_grid_default.s_len();
+        let e_steps = Self::dp_energy_steps(max_battery);
+        let trans_dts = Self::dp_transition_dts(s_len);
+        let comms_min_e = Self::map_e_to_dp(Self::MIN_COMMS_START_CHARGE);
+        let mut next_pin = usize::MAX;
         for t in (0..pred_dt).rev() {
             let mut cov_dt = score_grid_default.clone();
             let p_dt = i32::from(!*p_t_it.next().unwrap());
-            for e in 0..=max_battery {
-                for s in 0..=1 {
-                    let de = if s == 0 { 1 } else { -1 };
-                    let new_e = (e as isize + de) as usize;
+            let comms_score = comms_scores.get(t).copied();
+            let comms_open = comms_score.is_some();
+            let pin = pins.get(&t).copied();
+            let next = score_cube.front().unwrap();
+            for (e, e_step) in e_steps.iter().enumerate() {
+                for s in 0..s_len {
+                    let new_e = e as isize + e_step[s].at(t);
                     // Compute score for the decision to stay in the current state.
-                    let stay = if s == 0 {
-                        // If in charge state, calculate score for staying.
-                        score_cube.front().unwrap().get(new_e.min(max_battery), s)
-                    } else if e > 0 {
-                        // If in acquisition state, consider score and state.
-                        score_cube.front().unwrap().get(new_e, s) + p_dt
+                    let stay = if new_e >= 0 {
+                        let reward = match s {
+                            1 => p_dt * Self::ACQ_SCORE,
+                            2 => comms_score.unwrap_or(0),
+                            _ => 0,
+                        };
+                        next.get((new_e as usize).min(max_battery), s) + reward
                     } else {
                         // If battery is depleted, staying is not possible.
                         i32::MIN
                     };
-
-                    let switch = if score_cube.len() < score_cube.size() {
-                        // We do not swap here as the time after the maximum prediction time is not predictable
-                        ScoreGrid::MIN_SCORE - 1
-                    } else {
-                        // Compute score for the decision to switch to the other state.
-                        score_cube.back().unwrap().get(e, s ^ 1)
+                    // We do not swap if the time after the transition is not predictable or a
+                    // pinned task falls into the transition
+                    let switch_score = |to: usize| {
+                        if next_pin < t + trans_dts[s][to] {
+                            return ScoreGrid::MIN_SCORE - 1;
+                        }
+                        score_cube
+                            .get(trans_dts[s][to] - 1)
+                            .map_or(ScoreGrid::MIN_SCORE - 1, |grid| grid.get(e, to))
                     };
-                    // Choose the better decision and record it.
-                    if stay >= switch {
-                        dec_cube.set(t, e, s, AtomicDecision::stay(s));
-                        cov_dt.set(e, s, stay);
-                    } else {
-                        dec_cube.set(t, e, s, AtomicDecision::switch(s ^ 1));
-                        cov_dt.set(e, s, switch);
-                    }
+                    let best = match pin {
+                        Some(PinConstraint::Switch(to) | PinConstraint::Hold(to)) if to == s => {
+                            (stay, AtomicDecision::stay(s))
+                        }
+                        Some(PinConstraint::Switch(to)) => {
+                            (switch_score(to), AtomicDecision::switch(to))
+                        }
+                        Some(PinConstraint::Hold(_)) => {
+                            (ScoreGrid::MIN_SCORE, AtomicDecision::stay(s))
+                        }
+                        None => {
+                            // Compute score for the decisions to switch to one of the other states.
+                            let mut best = (stay, AtomicDecision::stay(s));
+                            for to in (0..s_len).filter(|to| *to != s) {
+                                if to == 2 && (!comms_open || e < comms_min_e) {
+                                    continue;
+                                }
+                                let switch = switch_score(to);
+                                if switch > best.0 {
+                                    best = (switch, AtomicDecision::switch(to));
+                                }
+                            }
+                            best
+                        }
+                    };
+                    // Record the best decision.
+                    dec_cube.set(t, e, s, best.1);
+                    cov_dt.set(e, s, best.0);
                 }
             }
+            if pin.is_some() {
+                next_pin = t;
+            }
             // Push the updated score grid for the current time step into the linked box.
             score_cube.push(cov_dt);
         }
@@ -203,13 +372,38 @@ impl TaskController {
         OptimalOrbitResult { decisions: dec_cube, coverage_slice: score_cube }
     }
 
+    /// Returns the nominal DP score of a second spent in `Comms`, scaled by the comms
+    /// aggressiveness of the [`MissionConfig`].
+    fn comms_score() -> f64 {
+        let aggressiveness = MissionConfig::get().runtime.comms_aggressiveness;
+        Self::COMMS_SCORE * aggressiveness
+    }
+
+    /// Returns the transition delays in seconds between the first `s_len` DP states.
+    ///
+    /// # Arguments
+    /// - `s_len`: The number of modeled DP states.
+    #[allow(clippy::cast_possible_truncation)]
+    fn dp_transition_dts(s_len: usize) -> Vec<Vec<usize>> {
+        let states = &Self::DP_STATES[..s_len];
+        states
+            .iter()
+            .map(|from| {
+                states
+                    .iter()
+                    .map(|to| if from == to { 0 } else { from.dt_to(*to).as_secs() as usize })
+                    .collect()
+            })
+            .collect()
+    }
+
     /// Finds the last possible time offset (`dt`) at which a burn can still start to reach a target.
     ///
     /// The method simulates forward motion and calculates how long a burn can be delayed while
     /// still ensuring that MELVIN can traverse the remaining distance in time.
     ///
     /// # Arguments
-    /// - `i`: The current orbit index position.
+    /// - `track`: The precomputed positions along the current trajectory.
     /// - `vel`: Current velocity vector.
     /// - `targets`: Target positions and additional target direction vector.
     /// - `max_dt`: Upper bound for time offset.
@@ -217,7 +411,7 @@ impl TaskController {
     /// # Returns
     /// - `dt`: The latest viable starting offset in seconds.
     fn find_last_possible_dt(
-        i: &IndexedOrbitPosition,
+        track: &OrbitPositionTable,
         vel: &Vec2D<I32F32>,
         targets: &[(Vec2D<I32F32>, Vec2D<I32F32>)],
         max_dt: usize,
@@ -225,9 +419,7 @@ impl TaskController {
         let orbit_vel_abs = vel.abs();
 
         for dt in (Self::OBJECTIVE_SCHEDULE_MIN_DT..max_dt).rev() {
-            let pos_i96: Vec2D<I96F32> =
-                i.pos().to_num::<I96F32>() + (*vel).to_num::<I96F32>() * I96F32::from_num(dt);
-            let pos = pos_i96.to_num::<I32F32>().wrap_around_map();
+            let pos = track.pos_at(dt);
             let mut min_dt = usize::MAX;
 
             for target_pos in targets {
@@ -262,6 +454,7 @@ impl TaskController {
     /// * `f_cont_lock` - A shared lock on the `FlightComputer` for velocity and control access.
     /// * `target_pos` - The target position as a `Vec2D<I32F32>`.
     /// * `target_end_time` - The deadline by which the target must be reached.
+    /// * `zone_margin` - The maximum tolerated impact-point dispersion.
     ///
     /// # Returns
     /// * `(BurnSequence, I32F32)` - A tuple containing:
@@ -270,6 +463,7 @@ impl TaskController {
     ///
     /// # Panics
     /// Panics if no valid burn sequence is found or the target is unreachable.
+    #[allow(clippy::too_many_arguments)]
     pub fn calculate_single_target_burn_sequence(
         curr_i: IndexedOrbitPosition,
         curr_vel: Vec2D<I32F32>,
@@ -277,22 +471,18 @@ impl TaskController {
         target_start_time: DateTime<Utc>,
         target_end_time: DateTime<Utc>,
         fuel_left: I32F32,
-        target_id: usize,
+        target_id: ImgObjectiveId,
+        zone_margin: I32F32,
     ) -> Option<ExitBurnResult> {
         info!("Starting to calculate single-target burn towards {target_pos}");
         let target = [(target_pos, Vec2D::zero())];
-        let (min_dt, max_dt) = Self::get_min_max_dt(target_start_time, target_end_time, curr_i.t());
+        let (start_t, end_t) = (target_start_time.into(), target_end_time.into());
+        let (min_dt, max_dt) = Self::get_min_max_dt(start_t, end_t, curr_i.t().into());
         let max_off_orbit_dt = max_dt - Self::OBJECTIVE_SCHEDULE_MIN_DT;
 
-        // Spawn a task to compute possible turns asynchronously
-        let turns = FlightComputer::compute_possible_turns(curr_vel);
-
-        let last_possible_dt = Self::find_last_possible_dt(&curr_i, &curr_vel, &target, max_dt);
-
-        // Define range for evaluation and initialize best burn sequence tracker
-        let remaining_range = Self::OBJECTIVE_SCHEDULE_MIN_DT..=last_possible_dt;
+        // Reuse the possible turns of the last planning call if the velocity did not change
+        let turns = FlightComputer::possible_turns(curr_vel);
 
-        // Await the result of possible turn computations
         let mut evaluator = BurnSequenceEvaluator::new(
             curr_i,
             curr_vel,
@@ -303,10 +493,16 @@ impl TaskController {
             turns,
             fuel_left,
             target_id,
-        );
+        )
+        .with_max_dispersion(zone_margin);
+
+        let last_possible_dt =
+            Self::find_last_possible_dt(evaluator.track(), &curr_vel, &target, max_dt);
+        // Define range for evaluation
+        let remaining_range = Self::OBJECTIVE_SCHEDULE_MIN_DT..=last_possible_dt;
 
         for dt in remaining_range.rev() {
-            evaluator.process_dt(dt, Self::MAX_BATTERY_THRESHOLD);
+            evaluator.process_dt(dt, Self::max_battery_threshold());
         }
         // Return the best burn sequence, panicking if none was found
         evaluator.get_best_burn()
@@ -322,9 +518,11 @@ impl TaskController {
     /// - `target_end_time`: Deadline to acquire.
     /// - `fuel_left`: Remaining propellant budget.
     /// - `target_id`: ID of the image objective.
+    /// - `zone_margin`: Maximum tolerated impact-point dispersion.
     ///
     /// # Returns
     /// `Some(ExitBurnResult)` on success, or `None` if no valid burn sequence was found.
+    #[allow(clippy::too_many_arguments)]
     pub fn calculate_multi_target_burn_sequence(
         curr_i: IndexedOrbitPosition,
         curr_vel: Vec2D<I32F32>,
@@ -332,21 +530,17 @@ impl TaskController {
         target_start_time: DateTime<Utc>,
         target_end_time: DateTime<Utc>,
         fuel_left: I32F32,
-        target_id: usize,
+        target_id: ImgObjectiveId,
+        zone_margin: I32F32,
     ) -> Option<ExitBurnResult> {
         info!("Starting to calculate multi-target burn sequence!");
-        let (min_dt, max_dt) = Self::get_min_max_dt(target_start_time, target_end_time, curr_i.t());
+        let (start_t, end_t) = (target_start_time.into(), target_end_time.into());
+        let (min_dt, max_dt) = Self::get_min_max_dt(start_t, end_t, curr_i.t().into());
         let max_off_orbit_dt = max_dt - Self::OBJECTIVE_SCHEDULE_MIN_DT;
 
-        // Spawn a task to compute possible turns asynchronously
-        let turns = FlightComputer::compute_possible_turns(curr_vel);
+        // Reuse the possible turns of the last planning call if the velocity did not change
+        let turns = FlightComputer::possible_turns(curr_vel);
 
-        let last_possible_dt = Self::find_last_possible_dt(&curr_i, &curr_vel, &entries, max_dt);
-
-        // Define range for evaluation and initialize best burn sequence tracker
-        let remaining_range = Self::OBJECTIVE_SCHEDULE_MIN_DT..=last_possible_dt;
-
-        // Await the result of possible turn computations
         let mut evaluator = BurnSequenceEvaluator::new(
             curr_i,
             curr_vel,
@@ -357,118 +551,147 @@ impl TaskController {
             turns,
             fuel_left,
             target_id,
-        );
+        )
+        .with_max_dispersion(zone_margin);
+
+        let last_possible_dt =
+            Self::find_last_possible_dt(evaluator.track(), &curr_vel, &entries, max_dt);
+        // Define range for evaluation
+        let remaining_range = Self::OBJECTIVE_SCHEDULE_MIN_DT..=last_possible_dt;
 
         for dt in remaining_range.rev() {
-            evaluator.process_dt(dt, Self::MAX_BATTERY_THRESHOLD);
+            evaluator.process_dt(dt, Self::max_battery_threshold());
         }
         // Return the best burn sequence, panicking if none was found
         evaluator.get_best_burn()
     }
 
+    /// Sequences zoned objectives that can be retrieved during the off-orbit phase of an
+    /// exit burn, after the objective the burn aims for.
+    ///
+    /// Starting at the impact point of the burn, the candidate with the earliest deadline
+    /// that can be reached by a cheap turn within its time window is appended repeatedly, as
+    /// long as the accumulated turn fuel stays within the shared fuel budget.
+    ///
+    /// # Arguments
+    /// - `exit_burn`: The planned exit burn.
+    /// - `candidates`: The objectives that may be appended.
+    /// - `fuel_budget`: The fuel available for all turns after the exit burn.
+    ///
+    /// # Returns
+    /// The retrieval legs in flight order, empty if no candidate can be appended.
+    pub fn sequence_zo_legs(
+        exit_burn: &ExitBurnResult,
+        candidates: &[ZoCandidate],
+        fuel_budget: I32F32,
+    ) -> Vec<ZoLeg> {
+        let seq = exit_burn.sequence();
+        let arrival_dt = i64::try_from(seq.acc_dt() + seq.detumble_dt()).unwrap_or(i64::MAX);
+        let Some(mut vel) = seq.sequence_vel().last().copied() else { return Vec::new() };
+        let speed = vel.abs();
+        if speed == I32F32::ZERO || arrival_dt == i64::MAX {
+            return Vec::new();
+        }
+        let mut pool: Vec<&ZoCandidate> = candidates.iter().collect();
+        pool.sort_by_key(|c| c.end);
+        let mut pos = *exit_burn.target_pos();
+        let mut t = seq.start_i().t() + TimeDelta::seconds(arrival_dt);
+        let mut budget = fuel_budget;
+        let mut legs = Vec::new();
+        while legs.len() < Self::MAX_ZO_LEGS {
+            let next = pool.iter().enumerate().find_map(|(i, c)| {
+                Self::evaluate_zo_leg(pos, vel, t, c, seq.min_fuel())
+                    .filter(|leg| leg.turn_fuel() <= budget)
+                    .map(|leg| (i, leg))
+            });
+            let Some((i, leg)) = next else { break };
+            pool.remove(i);
+            budget -= leg.turn_fuel();
+            vel = pos.unwrapped_to(&leg.pos()).normalize() * speed;
+            pos = leg.pos();
+            t = leg.t();
+            legs.push(leg);
+        }
+        legs
+    }
+
+    /// Evaluates the turn from one imaging position towards a candidate objective.
+    ///
+    /// # Arguments
+    /// - `pos`: The current imaging position.
+    /// - `vel`: The velocity at the current imaging position.
+    /// - `t`: The estimated time the current imaging position is reached.
+    /// - `candidate`: The candidate objective.
+    /// - `exit_fuel`: The fuel of a separate exit burn, which the leg has to undercut.
+    ///
+    /// # Returns
+    /// `Some(ZoLeg)` if the candidate is close, the turn is cheap and the candidate zone is
+    /// reached within its time window.
+    fn evaluate_zo_leg(
+        pos: Vec2D<I32F32>,
+        vel: Vec2D<I32F32>,
+        t: DateTime<Utc>,
+        candidate: &ZoCandidate,
+        exit_fuel: I32F32,
+    ) -> Option<ZoLeg> {
+        let to_candidate = pos.unwrapped_to(&candidate.pos);
+        let dist = to_candidate.abs();
+        let speed = vel.abs();
+        if dist > Self::MAX_ZO_LEG_DIST
+            || vel.angle_to(&to_candidate).abs() > Self::MAX_ZO_LEG_TURN_ANGLE
+        {
+            return None;
+        }
+        let turn_dv = (to_candidate.normalize() * speed).euclid_distance(&vel);
+        let turn_dt = turn_dv / FlightComputer::ACC_CONST;
+        let turn_fuel = turn_dt * FlightComputer::FUEL_CONST;
+        let fuel_saved = exit_fuel - turn_fuel;
+        let travel_s = (dist / speed + turn_dt).ceil().to_num::<i64>();
+        let leg_t = t + Self::ZO_LEG_ACQ_DT + TimeDelta::seconds(travel_s);
+        let in_window = candidate.start <= leg_t && leg_t + Self::ZO_LEG_ACQ_DT < candidate.end;
+        (in_window && fuel_saved > I32F32::ZERO)
+            .then(|| ZoLeg::new(candidate, dist, leg_t, turn_fuel, fuel_saved))
+    }
+
     /// Determines the earliest and latest time offsets (in seconds) for a given target interval.
     ///
     /// # Arguments
-    /// - `start_time`: UTC time when the target becomes valid.
-    /// - `end_time`: UTC time by which the target must be acquired.
-    /// - `curr`: The current UTC time.
+    /// - `start_time`: The time when the target becomes valid.
+    /// - `end_time`: The time by which the target must be acquired.
+    /// - `curr`: The current time.
     ///
     /// # Returns
     /// A tuple of `(min_dt, max_dt)`:
     /// - `min_dt`: The earliest time offset from `curr` to consider.
     /// - `max_dt`: The latest time offset from `curr` before the target deadline.
     fn get_min_max_dt(
-        start_time: DateTime<Utc>,
-        end_time: DateTime<Utc>,
-        curr: DateTime<Utc>,
+        start_time: MissionTime,
+        end_time: MissionTime,
+        curr: MissionTime,
     ) -> (usize, usize) {
         // Calculate maximum allowed time delta for the maneuver, clamp to a maximum of 8 hours
-        let time_left = (end_time - curr).clamp(TimeDelta::zero(), TimeDelta::hours(8));
-        let max_dt = {
-            let max = usize::try_from(time_left.num_seconds()).unwrap_or(0);
-            max - Self::OBJECTIVE_MIN_RETRIEVAL_TOL
-        };
-
-        let time_to_start = (start_time - curr).max(TimeDelta::zero());
-        let min_dt = {
-            if time_to_start.num_seconds() > 0 {
-                let min = usize::try_from(time_to_start.num_seconds()).unwrap_or(0);
-                min + Self::OBJECTIVE_MIN_RETRIEVAL_TOL
-            } else {
-                0
-            }
-        };
-        (min_dt, max_dt)
-    }
+        let time_left = curr.until(end_time).min(Self::OBJECTIVE_MAX_RETRIEVAL_WINDOW);
+        let max_dt = time_left.saturating_sub(Self::OBJECTIVE_MIN_RETRIEVAL_TOL);
 
-    /// Schedules a single communication cycle within an orbit plan.
-    ///
-    /// This function is responsible for planning a charge-acquire-comm cycle based on
-    /// the given start time and constraints. It ensures sufficient battery and time are
-    /// available to enter communication mode, and either schedules the cycle or short-circuits
-    /// if the window is too small.
-    ///
-    /// # Arguments
-    /// - `c_end`: A tuple of the form `(DateTime<Utc>, I32F32)` representing the end time of the
-    ///   previous communication cycle and the remaining battery charge.
-    /// - `sched_start`: A tuple `(DateTime<Utc>, usize)` indicating the start time and the
-    ///   orbit index for scheduling.
-    /// - `orbit`: A reference to the [`ClosedOrbit`] used for orbit-based scheduling decisions.
-    /// - `strict_end`: A tuple `(DateTime<Utc>, usize)` specifying the hard cutoff for scheduling.
-    ///
-    /// # Returns
-    /// - `Some((DateTime<Utc>, I32F32))` with the projected end time and battery after the
-    ///   next comms cycle, if another cycle can be scheduled.
-    /// - `None` if the scheduling window is too short and no comms cycle can be inserted.
-    ///
-    /// # Notes
-    /// - This method ensures each comms cycle starts with sufficient charge.
-    /// - Uses `COMMS_SCHED_USABLE_TIME` and `COMMS_CHARGE_USAGE` constants to
-    ///   define time and battery requirements.
-    #[allow(clippy::cast_possible_wrap)]
-    async fn sched_single_comms_cycle(
-        &self,
-        c_end: (DateTime<Utc>, I32F32),
-        sched_start: (DateTime<Utc>, usize),
-        orbit: &ClosedOrbit,
-        strict_end: (DateTime<Utc>, usize),
-    ) -> Option<(DateTime<Utc>, I32F32)> {
-        let t_time = FlightState::Charge.dt_to(FlightState::Comms);
-        let sched_end = sched_start.0 + Self::COMMS_SCHED_USABLE_TIME;
-        let t_ch = Self::MIN_COMMS_START_CHARGE;
-
-        if sched_end + t_time > strict_end.0 {
-            let dt = usize::try_from((strict_end.0 - sched_start.0).num_seconds()).unwrap_or(0);
-            let result = Self::init_sched_dp(orbit, sched_start.1, Some(dt), None, None);
-            let target = {
-                let st =
-                    result.coverage_slice.front().unwrap().get_max_s(Self::map_e_to_dp(c_end.1));
-                (c_end.1, st)
-            };
-            self.schedule_switch(FlightState::from_dp_usize(target.1), c_end.0).await;
-            self.sched_opt_orbit_res(sched_start.0, result, 0, false, target).await;
-            None
+        let time_to_start = curr.until(start_time);
+        let min_dt = if time_to_start.is_zero() {
+            TimeBudget::ZERO
         } else {
-            let dt = usize::try_from((sched_end - sched_start.0).num_seconds()).unwrap_or(0);
-            let result = Self::init_sched_dp(orbit, sched_start.1, Some(dt), None, Some(t_ch));
-            let target = {
-                let st =
-                    result.coverage_slice.front().unwrap().get_max_s(Self::map_e_to_dp(c_end.1));
-                (c_end.1, st)
-            };
-            self.schedule_switch(FlightState::from_dp_usize(target.1), c_end.0).await;
-            let (_, batt) = self.sched_opt_orbit_res(sched_start.0, result, 0, false, target).await;
-            self.schedule_switch(FlightState::Comms, sched_end).await;
-            let next_c_end =
-                sched_end + t_time + TimeDelta::seconds(Self::IN_COMMS_SCHED_SECS as i64);
-            Some((next_c_end, batt - Self::COMMS_CHARGE_USAGE))
-        }
+            time_to_start + Self::OBJECTIVE_MIN_RETRIEVAL_TOL
+        };
+        (min_dt.as_secs(), max_dt.as_secs())
     }
 
     /// Computes and schedules tasks that balance imaging and communication passes.
     ///
-    /// This scheduling method handles alternating communication slots interleaved with optimized orbit
-    /// operation schedules. It tries to maximize productivity while periodically entering comms mode.
+    /// `Comms` is modeled as a third state of the orbit scheduling DP, scored per second until
+    /// the last beacon objective ends. Comms windows are thus placed where imaging gains the
+    /// least, instead of being interleaved at fixed periods. The `bias` additionally favors
+    /// orbit segments passing near candidate beacon locations.
+    ///
+    /// The communication slots booked via the `slots` manager are scheduled in `Comms` as hard
+    /// constraints. Afterwards, the slots are re-booked to match the planned comms windows, and
+    /// the switch into each window is held until the booking of its slot was confirmed.
     ///
     /// # Arguments
     /// - `self`: Shared reference to this `TaskController`.
@@ -478,7 +701,9 @@ impl TaskController {
     /// - `last_bo_end_t`: Deadline after which comms mode must stop.
     /// - `first_comms_end`: Initial estimate of when the first comms cycle ends.
     /// - `end_cond`: Optional condition that defines the final desired state and battery level.
-    #[allow(clippy::cast_possible_wrap, clippy::cast_precision_loss)]
+    /// - `bias`: The [`CommsBias`] derived from the current beacon estimates.
+    /// - `slots`: The [`SlotManager`] booking the announced communication slots.
+    #[allow(clippy::cast_precision_loss, clippy::too_many_arguments)]
     pub async fn sched_opt_orbit_w_comms(
         self: Arc<TaskController>,
         orbit_lock: Arc<RwLock<ClosedOrbit>>,
@@ -487,70 +712,39 @@ impl TaskController {
         last_bo_end_t: DateTime<Utc>,
         first_comms_end: DateTime<Utc>,
         end_cond: Option<EndCondition>,
+        bias: CommsBias,
+        slots: Arc<SlotManager>,
     ) {
         log!("Calculating/Scheduling optimal orbit with passive beacon scanning.");
         let computation_start = Utc::now();
-        self.clear_schedule().await;
-        let t_time = FlightState::Charge.td_dt_to(FlightState::Comms);
-        let strict_end = (last_bo_end_t, scheduling_start_i.index_then(last_bo_end_t));
-
-        let is_next_possible: Box<dyn Fn(DateTime<Utc>) -> bool + Send> =
-            if let Some(end) = &end_cond {
-                let dt = end.abs_charge_dt() + t_time * 2;
-                Box::new(move |comms_end: DateTime<Utc>| -> bool {
-                    let n_end = comms_end
-                        + TaskController::COMMS_SCHED_USABLE_TIME
-                        + t_time * 2
-                        + TimeDelta::seconds(TaskController::IN_COMMS_SCHED_SECS as i64);
-                    n_end + dt <= end.time()
-                })
-            } else {
-                Box::new(|_| -> bool { true })
+        slots.refresh().await;
+        let booked = slots.booked_windows().await;
+        let start = (first_comms_end, scheduling_start_i.index_then(first_comms_end));
+        let st_batt = {
+            let f_cont = f_cont_lock.read().await;
+            let state = match f_cont.state() {
+                st @ (FlightState::Charge | FlightState::Acquisition) => st,
+                _ => FlightState::Comms,
             };
-
-        let mut curr_comms_end = {
-            let dt = first_comms_end - Utc::now();
-            let batt = f_cont_lock.read().await.batt_in_dt(dt);
-            Some((first_comms_end, batt))
+            (f_cont.batt_in_dt(first_comms_end - Utc::now()), state.to_dp_usize())
         };
-
-        let mut next_start = (Utc::now(), scheduling_start_i.index());
-        let mut next_start_e = I32F32::zero();
-
-        let orbit = orbit_lock.read().await;
-        while let Some(end) = curr_comms_end {
-            (next_start, next_start_e) = {
-                let t = end.0 + t_time;
-                let i = scheduling_start_i.index_then(t);
-                ((t, i), end.1)
-            };
-            if is_next_possible(next_start.0) {
-                curr_comms_end =
-                    self.sched_single_comms_cycle(end, next_start, &orbit, strict_end).await;
-            } else {
-                break;
-            }
-        }
-
-        if let Some(e) = &end_cond {
-            let (left_dt, ch, s) = {
-                let dt = usize::try_from((e.time() - next_start.0).num_seconds()).unwrap_or(0);
-                (Some(dt), Some(e.charge()), Some(e.state()))
-            };
-            let result = Self::init_sched_dp(&orbit, next_start.1, left_dt, s, ch);
-            let target = {
-                let st = result
-                    .coverage_slice
-                    .front()
-                    .unwrap()
-                    .get_max_s(Self::map_e_to_dp(next_start_e));
-                (next_start_e, st)
-            };
-            self.schedule_switch(FlightState::from_dp_usize(target.1), next_start.0 - t_time).await;
-            self.sched_opt_orbit_res(next_start.0, result, 0, false, target).await;
-        }
-
-        let n_tasks = self.task_schedule.read().await.len();
+        let end_t = end_cond.as_ref().map(EndCondition::time);
+        let n_tasks = self
+            .sched_opt_orbit_w_comms_from(
+                &orbit_lock,
+                start,
+                last_bo_end_t,
+                end_cond,
+                st_batt,
+                &bias,
+                &booked,
+            )
+            .await;
+        let in_comms = st_batt.1 == FlightState::Comms.to_dp_usize();
+        let windows = self.comms_windows(in_comms.then_some(Utc::now()), last_bo_end_t).await;
+        slots.sync(&windows, &self.events).await;
+        self.gate_comms_switches(&slots.window_slots(&windows).await).await;
+        self.log_coverage_forecast(&orbit_lock, scheduling_start_i, st_batt.1, end_t).await;
         let dt_tot = (Utc::now() - computation_start).num_milliseconds() as f32 / 1000.0;
         info!(
             "Number of tasks after scheduling: {n_tasks}. \
@@ -558,6 +752,67 @@ impl TaskController {
         );
     }
 
+    /// Calculates and schedules the optimal orbit trajectory with `Comms` as a third state,
+    /// starting from a given time, battery level and state.
+    ///
+    /// Without an end condition the schedule ends with the last beacon objective, limited to
+    /// the orbit period.
+    ///
+    /// # Arguments
+    /// - `orbit_lock`: The shared closed orbit data.
+    /// - `(start_t, start_i)`: The start time of the schedule and its orbit index.
+    /// - `last_bo_end_t`: The time after which comms is no longer scored.
+    /// - `end_cond`: Optional condition that defines the final desired state and battery level.
+    /// - `(batt, st)`: The initial battery level and flight state as a DP index.
+    /// - `bias`: The [`CommsBias`] weighting the seconds in `Comms`.
+    /// - `booked`: The start and end times of the booked communication slots.
+    ///
+    /// # Returns
+    /// - The number of tasks in the schedule.
+    #[allow(clippy::cast_possible_truncation, clippy::too_many_arguments)]
+    pub async fn sched_opt_orbit_w_comms_from(
+        &self,
+        orbit_lock: &RwLock<ClosedOrbit>,
+        (start_t, start_i): (DateTime<Utc>, usize),
+        last_bo_end_t: DateTime<Utc>,
+        end_cond: Option<EndCondition>,
+        (batt, st): (I32F32, usize),
+        bias: &CommsBias,
+        booked: &[(DateTime<Utc>, DateTime<Utc>)],
+    ) -> usize {
+        self.clear_schedule().await;
+        let mut pins = self.pin_constraints(start_t, Self::DP_STATES.len()).await;
+        let comms_dt = TimeBudget::between(start_t, last_bo_end_t);
+        Self::slot_constraints(&mut pins, start_t, booked, comms_dt);
+        let result = {
+            let orbit = orbit_lock.read().await;
+            let (dt, end_state, end_batt) = if let Some(e) = &end_cond {
+                (TimeBudget::between(start_t, e.time()), Some(e.state()), Some(e.charge()))
+            } else {
+                (comms_dt.min(Self::max_prediction_dt(&orbit)), None, None)
+            };
+            PLANNING_POOL
+                .run("comms_orbit_dp", || {
+                    if let Some((offset, dist)) = bias.nearest_pass(&orbit, start_i) {
+                        info!("Closest pass to a beacon candidate in {offset}s at {dist:.0}px.");
+                    }
+                    Self::init_sched_dp(
+                        &orbit,
+                        start_i,
+                        Some(dt),
+                        end_state,
+                        end_batt,
+                        Some((comms_dt, bias)),
+                        &pins,
+                    )
+                })
+                .await
+        };
+        let (n_tasks, _) =
+            self.sched_opt_orbit_res(start_t, result, TimeBudget::ZERO, false, (batt, st)).await;
+        n_tasks
+    }
+
     /// Calculates and schedules the optimal orbit trajectory based on the current position and state.
     ///
     /// # Arguments
@@ -566,51 +821,81 @@ impl TaskController {
     /// - `f_cont_lock`: An `Arc<RwLock<FlightComputer>>` containing the flight control state.
     /// - `scheduling_start_i`: The starting orbital position as an `IndexedOrbitPosition`.
     /// - `end`: An optional `EndCondition` indicating the desired final status of MELVIN
+    pub async fn sched_opt_orbit(
+        self: Arc<TaskController>,
+        orbit_lock: Arc<RwLock<ClosedOrbit>>,
+        f_cont_lock: Arc<RwLock<FlightComputer>>,
+        scheduling_start_i: IndexedOrbitPosition,
+        end: Option<EndCondition>,
+    ) {
+        let st_batt = Self::get_batt_and_state(&f_cont_lock).await;
+        let end_t = end.as_ref().map(EndCondition::time);
+        self.sched_opt_orbit_from(&orbit_lock, scheduling_start_i, end, st_batt, &WallClock).await;
+        self.log_coverage_forecast(&orbit_lock, scheduling_start_i, st_batt.1, end_t).await;
+    }
+
+    /// Calculates and schedules the optimal orbit trajectory from a given battery level and state.
+    ///
+    /// The planning `clock` determines the time already spent on the calculation, by which the
+    /// start of the schedule is shifted. A [`VirtualClock`](super::VirtualClock) that is not
+    /// advanced during planning yields a schedule starting right at `scheduling_start_i`.
+    ///
+    /// # Arguments
+    /// - `orbit_lock`: The shared closed orbit data.
+    /// - `scheduling_start_i`: The starting orbital position as an `IndexedOrbitPosition`.
+    /// - `end`: An optional `EndCondition` indicating the desired final status of MELVIN
+    /// - `(batt, st)`: The initial battery level and flight state as a DP index.
+    /// - `clock`: The planning [`Clock`].
+    ///
+    /// # Returns
+    /// - The number of tasks in the schedule.
     #[allow(
         clippy::cast_precision_loss,
         clippy::cast_possible_wrap,
         clippy::cast_possible_truncation,
         clippy::cast_sign_loss
     )]
-    pub async fn sched_opt_orbit(
-        self: Arc<TaskController>,
-        orbit_lock: Arc<RwLock<ClosedOrbit>>,
-        f_cont_lock: Arc<RwLock<FlightComputer>>,
+    pub async fn sched_opt_orbit_from(
+        &self,
+        orbit_lock: &RwLock<ClosedOrbit>,
         scheduling_start_i: IndexedOrbitPosition,
         end: Option<EndCondition>,
-    ) {
+        (batt, st): (I32F32, usize),
+        clock: &impl Clock,
+    ) -> usize {
         log!("Calculating/Scheduling optimal orbit.");
         self.clear_schedule().await;
         let p_t_shift = scheduling_start_i.index();
         let comp_start = scheduling_start_i.t();
-        let (dt, batt, state) = if let Some(end_c) = end {
-            let end_t = (end_c.time() - Utc::now()).num_seconds().max(0) as usize;
+        let pins = self.pin_constraints(comp_start, 2).await;
+        let (dt, end_batt, end_state) = if let Some(end_c) = end {
+            let end_t = TimeBudget::between(clock.now(), end_c.time());
             (Some(end_t), Some(end_c.charge()), Some(end_c.state()))
         } else {
             (None, None, None)
         };
         let result = {
             let orbit = orbit_lock.read().await;
-            Self::init_sched_dp(&orbit, p_t_shift, dt, state, batt)
+            PLANNING_POOL
+                .run("orbit_dp", || {
+                    Self::init_sched_dp(&orbit, p_t_shift, dt, end_state, end_batt, None, &pins)
+                })
+                .await
         };
-        let dt_calc = (Utc::now() - comp_start).num_milliseconds() as f32 / 1000.0;
-        let dt_shift = dt_calc.ceil() as usize;
-
-        let (st_batt, dt_sh) = {
-            let (batt, st) = Self::get_batt_and_state(&f_cont_lock).await;
-            if st == 2 {
-                let best_st =
-                    result.coverage_slice.back().unwrap().get_max_s(Self::map_e_to_dp(batt));
-                self.schedule_switch(FlightState::from_dp_usize(best_st), comp_start).await;
-                ((batt, best_st), dt_shift + 180)
-            } else {
-                ((batt, st), dt_shift)
-            }
+        let dt_shift = TimeBudget::from_delta_ceil(clock.now() - comp_start);
+
+        let (st_batt, dt_sh) = if st == 2 {
+            let best_st = result.coverage_slice.back().unwrap().get_max_s(Self::map_e_to_dp(batt));
+            self.schedule_switch(FlightState::from_dp_usize(best_st), comp_start).await;
+            ((batt, best_st), dt_shift + TimeBudget::secs(180))
+        } else {
+            ((batt, st), dt_shift)
         };
         let (n_tasks, _) =
             self.sched_opt_orbit_res(comp_start, result, dt_sh, false, st_batt).await;
-        let dt_tot = (Utc::now() - comp_start).num_milliseconds() as f32 / 1000.0;
+        let dt_tot = (clock.now() - comp_start).num_milliseconds() as f32 / 1000.0;
         info!("Tasks after scheduling: {n_tasks}. Calculation and processing took {dt_tot:.2}s.");
+        n_tasks
     }
 
     /// Retrieves the current battery level and flight state index from the [`FlightComputer`].
@@ -629,6 +914,38 @@ impl TaskController {
         (batt, f_cont.state().to_dp_usize())
     }
 
+    /// Computes the DP battery index change for every battery index and state.
+    ///
+    /// The changes follow the configured [`FlightState`] charge curves. Rates below the battery
+    /// resolution are applied as one step every few seconds, rounding the period down, so
+    /// discharging is never underestimated.
+    ///
+    /// # Arguments
+    /// - `max_battery`: The highest DP battery index.
+    ///
+    /// # Returns
+    /// - A vector indexed by battery index holding the change for `Charge`, `Acquisition` and
+    ///   `Comms`.
+    fn dp_energy_steps(max_battery: usize) -> Vec<[EnergyStep; 3]> {
+        let curves = Self::DP_STATES.map(FlightState::charge_curve);
+        (0..=max_battery)
+            .map(|e| {
+                let batt = Self::map_dp_to_e(e);
+                curves.each_ref().map(|curve| {
+                    let rate = curve.rate_at(batt);
+                    let steps = (rate / Self::BATTERY_RESOLUTION).round();
+                    if steps.is_zero() && !rate.is_zero() {
+                        let secs = (Self::BATTERY_RESOLUTION / rate.abs()).floor();
+                        let period = secs.to_num::<usize>().max(1);
+                        EnergyStep { de: rate.signum().to_num::<isize>(), period }
+                    } else {
+                        EnergyStep { de: steps.to_num::<isize>(), period: 1 }
+                    }
+                })
+            })
+            .collect()
+    }
+
     /// Maps a battery level (`I32F32`) to a discrete DP index for scheduling purposes.
     ///
     /// # Arguments
@@ -637,9 +954,9 @@ impl TaskController {
     /// # Returns
     /// - `usize`: The index used in dynamic programming grids to represent energy.
     fn map_e_to_dp(e: I32F32) -> usize {
-        let e_clamp = e.clamp(Self::MIN_BATTERY_THRESHOLD, Self::MAX_BATTERY_THRESHOLD);
+        let e_clamp = e.clamp(Self::min_battery_threshold(), Self::max_battery_threshold());
 
-        ((e_clamp - Self::MIN_BATTERY_THRESHOLD) / Self::BATTERY_RESOLUTION)
+        ((e_clamp - Self::min_battery_threshold()) / Self::BATTERY_RESOLUTION)
             .round()
             .to_num::<usize>()
     }
@@ -652,8 +969,8 @@ impl TaskController {
     /// # Returns
     /// - `I32F32`: The real-valued battery charge corresponding to the DP index.
     fn map_dp_to_e(dp: usize) -> I32F32 {
-        (Self::MIN_BATTERY_THRESHOLD + (I32F32::from_num(dp) * Self::BATTERY_RESOLUTION))
-            .min(Self::MAX_BATTERY_THRESHOLD)
+        (Self::min_battery_threshold() + (I32F32::from_num(dp) * Self::BATTERY_RESOLUTION))
+            .min(Self::max_battery_threshold())
     }
 
     #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation, clippy::cast_sign_loss)]
@@ -672,7 +989,7 @@ impl TaskController {
         &self,
         base_t: DateTime<Utc>,
         res: OptimalOrbitResult,
-        dt_sh: usize,
+        dt_sh: TimeBudget,
         trunc: bool,
         (batt_f32, mut state): (I32F32, usize),
     ) -> (usize, I32F32) {
@@ -681,11 +998,12 @@ impl TaskController {
             self.clear_schedule().await;
         }
 
-        let mut dt = dt_sh;
-        let max_mapped = Self::map_e_to_dp(Self::MAX_BATTERY_THRESHOLD);
+        let mut dt = dt_sh.as_secs();
+        let max_mapped = Self::map_e_to_dp(Self::max_battery_threshold());
 
         // Map the current battery level into a discrete range.
         let mut batt = Self::map_e_to_dp(batt_f32);
+        let e_steps = Self::dp_energy_steps(max_mapped);
         let pred_secs = res.decisions.dt_len();
         let decisions = &res.decisions;
 
@@ -694,36 +1012,31 @@ impl TaskController {
             let decision = decisions.get(dt, batt, state);
 
             match decision {
-                AtomicDecision::StayInCharge => {
-                    // Stay in the charge state, increment battery level.
-                    state = 0;
-                    batt = (batt + 1).min(max_mapped);
-                    dt += 1;
-                }
-                AtomicDecision::StayInAcquisition => {
-                    // Stay in the acquisition state, decrement battery level.
-                    state = 1;
-                    if batt == 0 {
+                AtomicDecision::StayInCharge
+                | AtomicDecision::StayInAcquisition
+                | AtomicDecision::StayInComms => {
+                    // Stay in the current state, apply the battery level change.
+                    state = decision.state();
+                    let new_batt = batt as isize + e_steps[batt][state].at(dt);
+                    if new_batt < 0 {
                         error!("Battery level is already at 0!");
                         error!("current: {dt} max: {pred_secs} init_batt: {batt_f32}");
                     } else {
-                        batt -= 1;
+                        batt = (new_batt as usize).min(max_mapped);
                     }
                     dt += 1;
                 }
-                AtomicDecision::SwitchToCharge => {
-                    // Schedule a state change to "Charge" with an appropriate time delay.
-                    let sched_t = base_t + TimeDelta::seconds(dt as i64);
-                    self.schedule_switch(FlightState::Charge, sched_t).await;
-                    state = 0;
-                    dt = (dt + 180).min(pred_secs); // Add a delay for the transition.
-                }
-                AtomicDecision::SwitchToAcquisition => {
-                    // Schedule a state change to "Acquisition" with an appropriate time delay.
-                    let sched_t = base_t + TimeDelta::seconds(dt as i64);
-                    self.schedule_switch(FlightState::Acquisition, sched_t).await;
-                    state = 1;
-                    dt = (dt + 180).min(pred_secs); // Add a delay for the transition.
+                AtomicDecision::SwitchToCharge
+                | AtomicDecision::SwitchToAcquisition
+                | AtomicDecision::SwitchToComms => {
+                    // Schedule a state change with the appropriate transition delay.
+                    let target = FlightState::from_dp_usize(decision.state());
+                    let sched_t = base_t + TimeBudget::secs(dt);
+                    self.schedule_switch(target, sched_t).await;
+                    let from = FlightState::from_dp_usize(state);
+                    let trans_dt = TimeBudget::from(from.dt_to(target));
+                    state = decision.state();
+                    dt = (dt + trans_dt.as_secs()).min(pred_secs);
                 }
             }
         }
@@ -737,8 +1050,98 @@ impl TaskController {
     /// Provides a reference to the image task schedule.
     ///
     /// # Returns
-    /// - An `Arc` pointing to the `LockedTaskQueue`.
-    pub fn sched_arc(&self) -> Arc<RwLock<VecDeque<Task>>> { Arc::clone(&self.task_schedule) }
+    /// - An `Arc` pointing to the locked [`TaskQueue`].
+    pub fn sched_arc(&self) -> Arc<RwLock<TaskQueue>> { Arc::clone(&self.task_schedule) }
+
+    /// Returns the due time of the next scheduled task of the given class, if any.
+    ///
+    /// # Arguments
+    /// - `class`: The [`TaskClass`] to look for.
+    pub async fn next_due(&self, class: TaskClass) -> Option<DateTime<Utc>> {
+        self.task_schedule.read().await.next_of(class).map(Task::t)
+    }
+
+    /// Atomically splices an urgent task into the schedule, see [`TaskQueue::splice_urgent`].
+    ///
+    /// A running task wait is preempted if the urgent task is due before the awaited task.
+    ///
+    /// # Arguments
+    /// - `task`: The urgent task.
+    ///
+    /// # Returns
+    /// - The number of displaced tasks.
+    pub async fn splice_urgent(&self, task: Task) -> usize {
+        let due = task.t();
+        let displaced = self.task_schedule.write().await.splice_urgent(task);
+        for task in &displaced {
+            log!("Displaced task for an urgent task due {}. {task}", due.format("%d %H:%M:%S"));
+        }
+        EVENT_BUS.publish(SchedulingEvent::UrgentSpliced(due));
+        displaced.len()
+    }
+
+    /// Returns a task preempted by an urgent task to the schedule, see [`TaskQueue::requeue`].
+    ///
+    /// # Arguments
+    /// - `task`: The preempted task.
+    pub async fn requeue_preempted(&self, task: Task) {
+        for task in self.task_schedule.write().await.requeue(task) {
+            log!("Displaced preempted task for an urgent task. {task}");
+        }
+    }
+
+    /// Provides a reference to the registry of fired external events.
+    pub fn events(&self) -> &EventRegistry { &self.events }
+
+    /// Holds a task until its external event dependency fires or its timeout elapses.
+    ///
+    /// # Arguments
+    /// - `dep`: The dependency of the task.
+    /// - `due`: The due time of the task.
+    ///
+    /// # Returns
+    /// - `true` if the task should be executed, `false` if it should be skipped.
+    pub async fn await_dependency(&self, dep: &NotEarlierThan, due: DateTime<Utc>) -> bool {
+        if self.events.has_fired(dep.event()) {
+            return true;
+        }
+        log!("Holding task until {} (timeout {}s).", dep.event(), dep.timeout().num_seconds());
+        if self.events.wait_for(dep.event(), due + dep.timeout()).await {
+            return true;
+        }
+        log!("{} did not fire in time. Applying policy {}.", dep.event(), dep.policy());
+        dep.policy() == TimeoutPolicy::Execute
+    }
+
+    /// Makes the scheduled switches into comms windows depend on the booking of their slots.
+    ///
+    /// A switch is held until [`ExternalEvent::SlotBooked`] fired for its slot, for at most
+    /// `SLOT_BOOKING_TIMEOUT`, after which it is executed anyway, as the following switches
+    /// were planned on top of it.
+    ///
+    /// # Arguments
+    /// - `window_slots`: The start times of the comms windows together with their slot ID.
+    pub async fn gate_comms_switches(&self, window_slots: &[(DateTime<Utc>, usize)]) {
+        let mut sched = self.task_schedule.write().await;
+        let mut gated = TaskQueue::new();
+        while let Some(task) = sched.pop_front() {
+            let is_comms = matches!(
+                task.task_type(),
+                BaseTask::SwitchState(switch) if switch.target_state() == FlightState::Comms
+            );
+            let slot = window_slots.iter().find(|(start, _)| is_comms && *start == task.t());
+            gated.push(match slot {
+                Some((_, slot_id)) if task.dependency().is_none() => {
+                    let booked = ExternalEvent::SlotBooked(*slot_id);
+                    let policy = TimeoutPolicy::Execute;
+                    let dep = NotEarlierThan::new(booked, Self::SLOT_BOOKING_TIMEOUT, policy);
+                    task.with_dependency(dep)
+                }
+                _ => task,
+            });
+        }
+        *sched = gated;
+    }
 
     /// Schedules a task to switch the flight state at a specific time.
     ///
@@ -746,21 +1149,66 @@ impl TaskController {
     /// - `target`: The target flight state to switch to.
     /// - `sched_t`: The scheduled time for the state change as a `DateTime`.
     async fn schedule_switch(&self, target: FlightState, sched_t: DateTime<Utc>) {
-        self.enqueue_task(Task::switch_target(target, sched_t)).await;
+        let pinned = self.task_schedule.read().await.iter().any(|task| {
+            task.is_pinned()
+                && (task.t() - sched_t).abs() <= Self::PIN_MATCH_TOL
+                && matches!(
+                    task.task_type(),
+                    BaseTask::SwitchState(switch) if switch.target_state() == target
+                )
+        });
+        if pinned {
+            return;
+        }
+        let mut task = Task::switch_target(target, sched_t);
+        let lead = self.timing_report.read().await.lead_time(&task.task_type().to_string());
+        task.advance(lead);
+        self.enqueue_task(task).await;
     }
 
-    /// Schedules a task to capture an image at a specific time and position using the given camera lens.
+    /// Records the planned vs. actual execution timing of an executed task.
     ///
-    /// This method creates and enqueues a `Task::TakeImage` operation, wrapping the target
-    /// position to `u32` dimensions and assigning the provided lens type.
+    /// Periodically logs and dumps a drift report. Systematic lateness of state switches
+    /// measured here is fed back into `schedule_switch`.
     ///
     /// # Arguments
-    /// - `t`: The scheduled time to capture the image.
-    /// - `pos`: The unwrapped 2D map position of the target.
-    /// - `lens`: The [`CameraAngle`] specifying which lens to use.
-    async fn schedule_zo_image(&self, t: DateTime<Utc>, pos: Vec2D<I32F32>, lens: CameraAngle) {
-        let pos_u32 = Vec2D::new(pos.x().to_num::<u32>(), pos.y().to_num::<u32>());
-        self.enqueue_task(Task::image_task(pos_u32, lens, t)).await;
+    /// - `task_type`: The name of the executed task type.
+    /// - `planned`: The planned start time of the task.
+    /// - `started`: The actual start time of the task.
+    /// - `finished`: The actual end time of the task.
+    /// - `lead`: The command latency the task was fired ahead of its planned start by.
+    pub async fn record_task_timing(
+        &self,
+        task_type: &str,
+        planned: DateTime<Utc>,
+        started: DateTime<Utc>,
+        finished: DateTime<Utc>,
+        lead: TimeDelta,
+    ) {
+        let mut report = self.timing_report.write().await;
+        if report.record(task_type, planned, started, finished, lead) {
+            log!("{report}");
+            report.dump_json();
+        }
+    }
+
+    /// Dumps all pending tasks of the schedule as JSON.
+    ///
+    /// # Returns
+    /// - The number of persisted tasks.
+    pub async fn dump_schedule(&self) -> usize {
+        let tasks: Vec<_> =
+            self.task_schedule.read().await.iter().map(|t| (t.t(), t.to_string())).collect();
+        let count = tasks.len();
+        ScheduleSnapshot { t: Utc::now(), tasks }.dump_json();
+        count
+    }
+
+    /// Logs and dumps the task timing report collected so far.
+    pub async fn dump_timing_report(&self) -> std::io::Result<()> {
+        let report = self.timing_report.read().await;
+        log!("{report}");
+        report.try_dump_json()
     }
 
     /// Prepares and schedules the full sequence for capturing a Zoned Objective (ZO) image.
@@ -772,25 +1220,107 @@ impl TaskController {
     /// # Arguments
     /// - `t`: The nominal time at which the image should be taken.
     /// - `pos`: The target position on the map for the ZO image.
-    /// - `lens`: The lens configuration to use for capturing the image.
+    /// - `lens`: The lens configuration to use for capturing the image, i.e. the lens required
+    ///   by the objective.
     pub async fn schedule_retrieval_phase(
         &self,
         t: DateTime<Utc>,
         pos: Vec2D<I32F32>,
         lens: CameraAngle,
     ) {
+        for task in Self::retrieval_phase_plan(Utc::now(), t, pos, lens) {
+            match task.task_type() {
+                BaseTask::SwitchState(switch) => {
+                    self.schedule_switch(switch.target_state(), task.t()).await;
+                }
+                _ => {
+                    self.splice_urgent(task).await;
+                }
+            }
+        }
+    }
+
+    /// Builds the plan of the imaging phase of a Zoned Objective (ZO) retrieval, as scheduled by
+    /// [`TaskController::schedule_retrieval_phase`].
+    ///
+    /// # Arguments
+    /// - `now`: The start of the plan, with MELVIN in [`FlightState::Acquisition`].
+    /// - `t`: The nominal time at which the image should be taken.
+    /// - `pos`: The target position on the map for the ZO image.
+    /// - `lens`: The lens configuration to use for capturing the image.
+    ///
+    /// # Returns
+    /// - The optional charge phase and the first image task.
+    pub fn retrieval_phase_plan(
+        now: DateTime<Utc>,
+        t: DateTime<Utc>,
+        pos: Vec2D<I32F32>,
+        lens: CameraAngle,
+    ) -> Vec<Task> {
         let t_first = t - Self::ZO_IMAGE_FIRST_DEL;
         let trans_time = FlightState::Acquisition.td_dt_to(FlightState::Charge);
-        if Utc::now() + trans_time * 2 < t_first {
-            self.schedule_switch(FlightState::Charge, Utc::now()).await;
-            let last_charge_leave = t_first - trans_time;
-            self.schedule_switch(FlightState::Acquisition, last_charge_leave).await;
+        let mut plan = Vec::with_capacity(3);
+        if now + trans_time * 2 < t_first {
+            plan.push(Task::switch_target(FlightState::Charge, now));
+            plan.push(Task::switch_target(FlightState::Acquisition, t_first - trans_time));
         }
-        self.schedule_zo_image(t_first, pos, lens).await;
+        let pos_u32 = Vec2D::new(pos.x().to_num::<u32>(), pos.y().to_num::<u32>());
+        plan.push(Task::image_task(pos_u32, lens, t_first));
+        plan
+    }
+
+    /// Builds the minimal plan of an emergency exit burn: MELVIN charges for as long as the
+    /// switch to `Acquisition` still completes before the burn, then executes the burn.
+    ///
+    /// # Arguments
+    /// - `now`: The start of the plan.
+    /// - `state`: The current flight state.
+    /// - `burn`: The exit burn.
+    ///
+    /// # Returns
+    /// - The tasks of the plan, or `None` if MELVIN can not reach `Acquisition` from `state`.
+    pub fn emergency_burn_plan(
+        now: DateTime<Utc>,
+        state: FlightState,
+        burn: &BurnSequence,
+    ) -> Option<Vec<Task>> {
+        let burn_t = burn.start_i().t();
+        let charge_until = burn_t - FlightState::Charge.td_dt_to(FlightState::Acquisition);
+        let to_charge =
+            state.try_dt_to(FlightState::Charge).and_then(|dt| TimeDelta::from_std(dt).ok());
+        let mut plan = Vec::with_capacity(3);
+        if state == FlightState::Charge || to_charge.is_some_and(|dt| now + dt <= charge_until) {
+            if state != FlightState::Charge {
+                plan.push(Task::switch_target(FlightState::Charge, now));
+            }
+            plan.push(Task::switch_target(FlightState::Acquisition, charge_until));
+        } else if state != FlightState::Acquisition {
+            state.try_dt_to(FlightState::Acquisition)?;
+            plan.push(Task::switch_target(FlightState::Acquisition, now));
+        }
+        plan.push(Task::vel_change_task(burn.clone(), burn_t));
+        Some(plan)
+    }
+
+    /// Replaces the schedule, including pinned operator tasks, with an emergency plan.
+    ///
+    /// # Arguments
+    /// - `plan`: The tasks of the plan, e.g. from [`TaskController::emergency_burn_plan`].
+    pub async fn schedule_emergency_plan(&self, plan: Vec<Task>) {
+        let mut schedule = self.task_schedule.write().await;
+        let dropped_pins = schedule.iter().filter(|task| task.is_pinned()).count();
+        if dropped_pins > 0 {
+            warn!("Dropping {dropped_pins} pinned tasks for the emergency plan.");
+        }
+        schedule.clear();
+        schedule.extend(plan);
     }
 
     /// Schedules a velocity change task for a given burn sequence.
     ///
+    /// The burn is spliced in as an urgent task, displacing routine state switches that would
+    /// keep MELVIN out of `Acquisition` during the burn.
+    ///
     /// # Arguments
     /// - `burn`: The `BurnSequence` containing the velocity change details.
     ///
@@ -798,7 +1328,7 @@ impl TaskController {
     /// - The total number of tasks in the schedule after adding the velocity change task.
     pub async fn schedule_vel_change(self: Arc<TaskController>, burn: BurnSequence) -> usize {
         let due = burn.start_i().t();
-        self.enqueue_task(Task::vel_change_task(burn, due)).await;
+        self.splice_urgent(Task::vel_change_task(burn, due)).await;
         self.task_schedule.read().await.len()
     }
 
@@ -811,28 +1341,232 @@ impl TaskController {
         if !schedule_lock.read().await.is_empty() {
             return;
         }
-        let mut schedule = schedule_lock.write().await;
-        let schedule_len = schedule.len();
-        let mut first_remove = 0;
-        for i in 0..schedule_len {
-            if schedule[i].t() > dt {
-                first_remove = i;
-                break;
+        schedule_lock.write().await.retain(|task| task.t() <= dt || task.is_pinned());
+    }
+
+    /// Pins an operator task into the schedule.
+    ///
+    /// Pinned tasks survive rescheduling and constrain the orbit scheduling DP, which plans the
+    /// remaining state switches around them from the next (re-)scheduling on.
+    ///
+    /// # Arguments
+    /// - `task`: The `Task` to be pinned.
+    ///
+    /// # Returns
+    /// - The total number of tasks in the schedule after adding the pinned task.
+    pub async fn schedule_pinned(&self, task: Task) -> usize {
+        log!("Pinning operator task. {task}");
+        self.enqueue_task(task.pin()).await;
+        self.task_schedule.read().await.len()
+    }
+
+    /// Removes all pinned tasks from the schedule.
+    ///
+    /// # Returns
+    /// - The number of removed tasks.
+    pub async fn cancel_pinned(&self) -> usize {
+        let mut schedule = self.task_schedule.write().await;
+        let len = schedule.len();
+        schedule.retain(|task| !task.is_pinned());
+        len - schedule.len()
+    }
+
+    /// Collects the constraints the pinned tasks impose on an orbit scheduling DP.
+    ///
+    /// Pinned tasks before the start of the DP, burns and switches to states not modeled by
+    /// the DP impose no constraints.
+    ///
+    /// # Arguments
+    /// - `start`: The time of the first DP time step.
+    /// - `s_len`: The number of modeled DP states.
+    ///
+    /// # Returns
+    /// - The constraints by their DP time step.
+    async fn pin_constraints(
+        &self,
+        start: DateTime<Utc>,
+        s_len: usize,
+    ) -> BTreeMap<usize, PinConstraint> {
+        let mut pins = BTreeMap::new();
+        for task in self.task_schedule.read().await.iter().filter(|task| task.is_pinned()) {
+            let Some(dt) = TimeBudget::try_between(start, task.t()).map(TimeBudget::as_secs) else {
+                continue;
+            };
+            let pin = match task.task_type() {
+                BaseTask::SwitchState(switch) => {
+                    PinConstraint::Switch(switch.target_state().to_dp_usize())
+                }
+                BaseTask::TakeImage(_) => {
+                    PinConstraint::Hold(FlightState::Acquisition.to_dp_usize())
+                }
+                BaseTask::ChangeVelocity(_) => continue,
+            };
+            if let PinConstraint::Switch(st) | PinConstraint::Hold(st) = pin
+                && st >= s_len
+            {
+                warn!("Pinned task not modeled by the scheduler. {task}");
+                continue;
+            }
+            pins.insert(dt, pin);
+        }
+        pins
+    }
+
+    /// Adds the constraints of booked communication slots to the pinned task constraints.
+    ///
+    /// MELVIN has to be in `Comms` during every second of a booked slot, except for the
+    /// seconds needed to switch to `Comms` from the start of the DP and after `comms_dt`, when
+    /// `Comms` is no longer modeled. Pinned tasks take precedence over slots.
+    ///
+    /// # Arguments
+    /// - `pins`: The constraints of the pinned tasks by their DP time step.
+    /// - `start`: The time of the first DP time step.
+    /// - `booked`: The start and end times of the booked slots.
+    /// - `comms_dt`: The duration in which `Comms` is modeled.
+    fn slot_constraints(
+        pins: &mut BTreeMap<usize, PinConstraint>,
+        start: DateTime<Utc>,
+        booked: &[(DateTime<Utc>, DateTime<Utc>)],
+        comms_dt: TimeBudget,
+    ) {
+        let comms = FlightState::Comms.to_dp_usize();
+        let trans_dts = Self::dp_transition_dts(Self::DP_STATES.len());
+        let min_dt = trans_dts.iter().map(|dts| dts[comms]).max().unwrap_or(0);
+        let secs_after = |t| TimeBudget::try_between(start, t).map_or(0, TimeBudget::as_secs);
+        for (slot_start, slot_end) in booked {
+            let end = secs_after(*slot_end).min(comms_dt.as_secs());
+            for dt in secs_after(*slot_start).max(min_dt)..end {
+                pins.entry(dt).or_insert(PinConstraint::Hold(comms));
+            }
+        }
+    }
+
+    /// Returns the planned `Comms` windows of the task schedule.
+    ///
+    /// # Arguments
+    /// - `in_comms_since`: The start of the current `Comms` window, if MELVIN is in `Comms`
+    ///   when the schedule starts.
+    /// - `until`: The end of a `Comms` window that is not left within the schedule.
+    ///
+    /// # Returns
+    /// - The start and end times of the windows, from the switch to `Comms` until the next
+    ///   switch to another state.
+    pub async fn comms_windows(
+        &self,
+        in_comms_since: Option<DateTime<Utc>>,
+        until: DateTime<Utc>,
+    ) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+        self.state_windows(FlightState::Comms, in_comms_since, until).await
+    }
+
+    /// Returns the planned windows of the task schedule in which MELVIN stays in `state`.
+    ///
+    /// # Arguments
+    /// - `state`: The flight state of the windows.
+    /// - `in_state_since`: The start of the current window, if MELVIN is in `state` when the
+    ///   schedule starts.
+    /// - `until`: The end of a window that is not left within the schedule.
+    ///
+    /// # Returns
+    /// - The start and end times of the windows, from the switch to `state` until the next
+    ///   switch to another state.
+    async fn state_windows(
+        &self,
+        state: FlightState,
+        in_state_since: Option<DateTime<Utc>>,
+        until: DateTime<Utc>,
+    ) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+        let mut windows = Vec::new();
+        let mut open = in_state_since;
+        for task in self.task_schedule.read().await.iter() {
+            let BaseTask::SwitchState(switch) = task.task_type() else { continue };
+            match (open, switch.target_state() == state) {
+                (None, true) => open = Some(task.t()),
+                (Some(start), false) => {
+                    windows.push((start, task.t()));
+                    open = None;
+                }
+                _ => {}
             }
         }
-        schedule.drain(first_remove..schedule_len);
+        if let Some(start) = open.filter(|start| *start < until) {
+            windows.push((start, until));
+        }
+        windows
+    }
+
+    /// Forecasts the completion of the closed orbit from the acquisition windows of the task
+    /// schedule.
+    ///
+    /// # Arguments
+    /// - `orbit`: The closed orbit.
+    /// - `i_entry`: The indexed position the schedule was calculated from.
+    /// - `state`: The current flight state.
+    /// - `end`: The end of the schedule, if it was calculated towards an end condition.
+    pub async fn coverage_forecast(
+        &self,
+        orbit: &ClosedOrbit,
+        i_entry: IndexedOrbitPosition,
+        state: FlightState,
+        end: Option<DateTime<Utc>>,
+    ) -> CoverageForecast {
+        let now = Utc::now();
+        let until = end.unwrap_or(i_entry.t() + Self::max_prediction_dt(orbit));
+        let in_acq_since = (state == FlightState::Acquisition).then_some(now);
+        let windows = self.state_windows(FlightState::Acquisition, in_acq_since, until).await;
+        orbit.forecast_coverage(i_entry, &windows, now)
     }
 
-    /// Adds a task to the task schedule.
+    /// Logs the coverage forecast of a newly calculated schedule.
+    ///
+    /// # Arguments
+    /// - `orbit_lock`: The shared closed orbit data.
+    /// - `i_entry`: The indexed position the schedule was calculated from.
+    /// - `st`: The flight state at the start of the schedule as a DP index.
+    /// - `end`: The end of the schedule, if it was calculated towards an end condition.
+    async fn log_coverage_forecast(
+        &self,
+        orbit_lock: &RwLock<ClosedOrbit>,
+        i_entry: IndexedOrbitPosition,
+        st: usize,
+        end: Option<DateTime<Utc>>,
+    ) {
+        let state = FlightState::from_dp_usize(st);
+        let orbit = orbit_lock.read().await;
+        let forecast = self.coverage_forecast(&orbit, i_entry, state, end).await;
+        info!("Coverage forecast: {forecast}.");
+        for (i, w) in forecast.windows().iter().enumerate() {
+            log!(
+                "Acquisition window {i} from {} to {} adds {}s ({:.2}% of the orbit).",
+                w.start().format("%d %H:%M:%S"),
+                w.end().format("%d %H:%M:%S"),
+                w.new_secs(),
+                w.gain() * 100.0
+            );
+        }
+    }
+
+    /// Adds a task to the task schedule, keeping the schedule ordered by due time.
     ///
     /// # Arguments
     /// - `task`: The `Task` to be added to the task schedule.
-    async fn enqueue_task(&self, task: Task) { self.task_schedule.write().await.push_back(task); }
+    async fn enqueue_task(&self, task: Task) { self.task_schedule.write().await.push(task); }
 
-    /// Clears all pending tasks in the schedule.
+    /// Clears all pending tasks in the schedule, except for the pinned ones that are not due yet.
+    ///
+    /// Pinned tasks that are already due expire, since the following replan can no longer
+    /// execute them on time. Pinned image tasks are revalidated against the actual position
+    /// when they are executed.
     pub async fn clear_schedule(&self) {
         let schedule = &*self.task_schedule;
         log!("Clearing task schedule...");
-        schedule.write().await.clear();
+        let now = Utc::now();
+        let mut schedule_lock = schedule.write().await;
+        let pinned = schedule_lock.iter().filter(|task| task.is_pinned()).count();
+        schedule_lock.retain(|task| task.is_pinned() && task.t() > now);
+        let expired = pinned - schedule_lock.len();
+        if expired > 0 {
+            warn!("Dropping {expired} expired pinned tasks.");
+        }
     }
 }
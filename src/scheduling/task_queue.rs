@@ -0,0 +1,176 @@
+use super::task::{BaseTask, Task, TaskClass, TaskPriority};
+use crate::flight_control::FlightState;
+use crate::util::TimeBudget;
+use std::collections::{VecDeque, vec_deque};
+
+/// The task schedule of MELVIN, ordered by due time.
+///
+/// Tasks due at the same time are ordered by their [`TaskPriority`], critical tasks first,
+/// and otherwise keep their insertion order. The orbit scheduler relies on this order, as
+/// well as on consecutive state switches targeting different states.
+///
+/// Critical tasks can be spliced in with [`TaskQueue::splice_urgent`], which displaces the
+/// routine state switches that would keep MELVIN out of `Acquisition` when the task is due.
+#[derive(Debug, Default)]
+pub struct TaskQueue {
+    /// The queued tasks, ordered by due time and priority.
+    tasks: VecDeque<Task>,
+}
+
+impl TaskQueue {
+    /// Creates an empty [`TaskQueue`].
+    pub fn new() -> Self { Self::default() }
+
+    /// Returns the number of queued tasks.
+    pub fn len(&self) -> usize { self.tasks.len() }
+
+    /// Returns `true` if no task is queued.
+    pub fn is_empty(&self) -> bool { self.tasks.is_empty() }
+
+    /// Returns an iterator over the queued tasks in execution order.
+    pub fn iter(&self) -> vec_deque::Iter<'_, Task> { self.tasks.iter() }
+
+    /// Returns the next task to execute, if any.
+    pub fn front(&self) -> Option<&Task> { self.tasks.front() }
+
+    /// Removes and returns the next task to execute, if any.
+    pub fn pop_front(&mut self) -> Option<Task> { self.tasks.pop_front() }
+
+    /// Returns the next queued task of the given class, if any.
+    ///
+    /// # Arguments
+    /// - `class`: The [`TaskClass`] to look for.
+    pub fn next_of(&self, class: TaskClass) -> Option<&Task> {
+        self.tasks.iter().find(|task| task.task_type().class() == class)
+    }
+
+    /// Inserts a task behind all tasks due before it and all tasks of at least its priority
+    /// due at the same time.
+    ///
+    /// # Arguments
+    /// - `task`: The task to insert.
+    pub fn push(&mut self, task: Task) {
+        let (t, priority) = (task.t(), task.priority());
+        let pos = self.tasks.partition_point(|queued| {
+            queued.t() < t || (queued.t() == t && queued.priority() >= priority)
+        });
+        self.tasks.insert(pos, task);
+    }
+
+    /// Keeps only the tasks for which `keep` returns `true`.
+    pub fn retain(&mut self, keep: impl FnMut(&Task) -> bool) { self.tasks.retain(keep); }
+
+    /// Removes all tasks.
+    pub fn clear(&mut self) { self.tasks.clear(); }
+
+    /// Inserts a critical task, displacing the routine tasks in its way.
+    ///
+    /// Images and burns require `Acquisition`. Unpinned state switches to another state are
+    /// thus displaced if they are due within the protected window of the task. Unpinned
+    /// switches made redundant by the displacement, e.g. the return to `Acquisition` after a
+    /// displaced switch to `Charge`, are displaced as well.
+    ///
+    /// Routine tasks are simply inserted without displacing anything.
+    ///
+    /// # Arguments
+    /// - `task`: The urgent task.
+    ///
+    /// # Returns
+    /// - The displaced tasks in their former order.
+    pub fn splice_urgent(&mut self, task: Task) -> Vec<Task> {
+        if task.priority() == TaskPriority::Routine {
+            self.push(task);
+            return Vec::new();
+        }
+        let (mut displaced, kept): (Vec<Task>, Vec<Task>) =
+            self.tasks.drain(..).partition(|queued| Self::is_in_way(queued, &task));
+        self.tasks = kept.into();
+        if !displaced.is_empty() {
+            displaced.extend(self.drop_redundant_switches());
+            displaced.sort_by_key(Task::t);
+        }
+        self.push(task);
+        displaced
+    }
+
+    /// Returns a task that was taken out for execution but preempted by an urgent task.
+    ///
+    /// The task was not queued when the urgent task was spliced in, so it is displaced now if
+    /// it is in the way of a queued critical task, see [`TaskQueue::splice_urgent`].
+    ///
+    /// # Arguments
+    /// - `task`: The preempted task.
+    ///
+    /// # Returns
+    /// - The displaced tasks in their former order.
+    pub fn requeue(&mut self, task: Task) -> Vec<Task> {
+        if !self.tasks.iter().any(|queued| Self::is_in_way(&task, queued)) {
+            self.push(task);
+            return Vec::new();
+        }
+        let mut displaced = vec![task];
+        displaced.extend(self.drop_redundant_switches());
+        displaced.sort_by_key(Task::t);
+        displaced
+    }
+
+    /// Returns `true` if `queued` is a routine state switch that would keep MELVIN out of
+    /// `Acquisition` within the protected window of the `urgent` task.
+    ///
+    /// The window spans from the time needed to return from `Charge` before the urgent task
+    /// until the end of its burn, if any.
+    fn is_in_way(queued: &Task, urgent: &Task) -> bool {
+        if urgent.priority() == TaskPriority::Routine
+            || queued.priority() != TaskPriority::Routine
+        {
+            return false;
+        }
+        let BaseTask::SwitchState(switch) = queued.task_type() else { return false };
+        let busy = match urgent.task_type() {
+            BaseTask::ChangeVelocity(vel_change) => TimeBudget::secs(vel_change.burn().acc_dt()),
+            BaseTask::TakeImage(_) | BaseTask::SwitchState(_) => TimeBudget::ZERO,
+        };
+        let from = urgent.t() - FlightState::Charge.td_dt_to(FlightState::Acquisition);
+        switch.target_state() != FlightState::Acquisition
+            && (from..=urgent.t() + busy).contains(&queued.t())
+    }
+
+    /// Removes all unpinned state switches targeting the state of the preceding switch.
+    ///
+    /// # Returns
+    /// - The removed switches.
+    fn drop_redundant_switches(&mut self) -> Vec<Task> {
+        let mut last_target = None;
+        let mut redundant = Vec::new();
+        for queued in std::mem::take(&mut self.tasks) {
+            let BaseTask::SwitchState(switch) = queued.task_type() else {
+                self.tasks.push_back(queued);
+                continue;
+            };
+            let target = switch.target_state();
+            if last_target == Some(target) && !queued.is_pinned() {
+                redundant.push(queued);
+            } else {
+                last_target = Some(target);
+                self.tasks.push_back(queued);
+            }
+        }
+        redundant
+    }
+}
+
+impl Extend<Task> for TaskQueue {
+    /// Inserts all tasks in order, see [`TaskQueue::push`].
+    fn extend<I: IntoIterator<Item = Task>>(&mut self, iter: I) {
+        for task in iter {
+            self.push(task);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a TaskQueue {
+    type Item = &'a Task;
+    type IntoIter = vec_deque::Iter<'a, Task>;
+
+    fn into_iter(self) -> Self::IntoIter { self.tasks.iter() }
+}
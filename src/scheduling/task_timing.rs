@@ -0,0 +1,231 @@
+use crate::util::logger::JsonDump;
+use chrono::{DateTime, TimeDelta, Utc};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::{Display, Formatter};
+
+/// Aggregated planned vs. actual execution timing for a single task type.
+///
+/// Start drift is measured as `actual_start + lead - planned_start`, where `lead` is the
+/// command latency the task was fired ahead by, so positive values indicate that a task
+/// took effect late.
+#[derive(Debug, Clone, Copy, Default, serde::Serialize)]
+pub struct TaskTimingStats {
+    /// Number of recorded executions.
+    count: usize,
+    /// Number of executions that started later than the lateness threshold.
+    late_count: usize,
+    /// Sum of all start drifts in milliseconds.
+    drift_sum_ms: i64,
+    /// Minimum observed start drift in milliseconds.
+    min_drift_ms: i64,
+    /// Maximum observed start drift in milliseconds.
+    max_drift_ms: i64,
+    /// Sum of all execution durations in milliseconds.
+    duration_sum_ms: i64,
+    /// Maximum observed execution duration in milliseconds.
+    max_duration_ms: i64,
+    /// Sum of all latency leads in milliseconds.
+    lead_sum_ms: i64,
+}
+
+impl TaskTimingStats {
+    /// Adds a single execution to the aggregate.
+    ///
+    /// # Arguments
+    /// * `drift_ms` – Start drift of the execution in milliseconds.
+    /// * `duration_ms` – Duration of the execution in milliseconds.
+    /// * `lead_ms` – Latency lead the execution was fired ahead by in milliseconds.
+    fn add(&mut self, drift_ms: i64, duration_ms: i64, lead_ms: i64) {
+        if self.count == 0 {
+            self.min_drift_ms = drift_ms;
+            self.max_drift_ms = drift_ms;
+        } else {
+            self.min_drift_ms = self.min_drift_ms.min(drift_ms);
+            self.max_drift_ms = self.max_drift_ms.max(drift_ms);
+        }
+        if drift_ms > TaskTimingReport::LATENESS_THRESHOLD.num_milliseconds() {
+            self.late_count += 1;
+        }
+        self.count += 1;
+        self.drift_sum_ms += drift_ms;
+        self.duration_sum_ms += duration_ms;
+        self.max_duration_ms = self.max_duration_ms.max(duration_ms);
+        self.lead_sum_ms += lead_ms;
+    }
+
+    /// Returns the number of recorded executions.
+    pub fn count(&self) -> usize { self.count }
+
+    /// Returns the mean start drift, or zero if nothing was recorded.
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn mean_drift(&self) -> TimeDelta {
+        if self.count == 0 {
+            return TimeDelta::zero();
+        }
+        TimeDelta::milliseconds(self.drift_sum_ms / self.count as i64)
+    }
+
+    /// Returns the mean execution duration, or zero if nothing was recorded.
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn mean_duration(&self) -> TimeDelta {
+        if self.count == 0 {
+            return TimeDelta::zero();
+        }
+        TimeDelta::milliseconds(self.duration_sum_ms / self.count as i64)
+    }
+
+    /// Returns the mean latency lead, or zero if nothing was recorded.
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn mean_lead(&self) -> TimeDelta {
+        if self.count == 0 {
+            return TimeDelta::zero();
+        }
+        TimeDelta::milliseconds(self.lead_sum_ms / self.count as i64)
+    }
+}
+
+impl Display for TaskTimingStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "n={}, late={}, drift mean/min/max: {}/{}/{}ms, duration mean/max: {}/{}ms, lead {}ms",
+            self.count,
+            self.late_count,
+            self.mean_drift().num_milliseconds(),
+            self.min_drift_ms,
+            self.max_drift_ms,
+            self.mean_duration().num_milliseconds(),
+            self.max_duration_ms,
+            self.mean_lead().num_milliseconds()
+        )
+    }
+}
+
+/// The planned vs. actual execution timing of a single task.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TaskTimingSample {
+    /// Name of the executed task type.
+    task_type: String,
+    /// Planned start time of the task.
+    planned: DateTime<Utc>,
+    /// Actual start time of the task.
+    started: DateTime<Utc>,
+    /// Latency lead the task was fired ahead by in milliseconds.
+    lead_ms: i64,
+    /// Start drift of the task in milliseconds.
+    drift_ms: i64,
+    /// Duration of the task in milliseconds.
+    duration_ms: i64,
+}
+
+impl TaskTimingSample {
+    /// Returns the start drift of the task in milliseconds.
+    pub fn drift_ms(&self) -> i64 { self.drift_ms }
+}
+
+/// Collects [`TaskTimingStats`] per task type and derives a scheduling lead time
+/// from systematic lateness. The most recent executions are kept as [`TaskTimingSample`]s.
+#[derive(Debug, Default, serde::Serialize)]
+pub struct TaskTimingReport {
+    /// Aggregated statistics keyed by the task type name.
+    per_type: BTreeMap<String, TaskTimingStats>,
+    /// The most recent executions, oldest first.
+    recent: VecDeque<TaskTimingSample>,
+    /// Number of executions recorded since the last report was emitted.
+    #[serde(skip)]
+    since_report: usize,
+}
+
+impl TaskTimingReport {
+    /// Start drift above which an execution is counted as late.
+    pub const LATENESS_THRESHOLD: TimeDelta = TimeDelta::milliseconds(500);
+    /// Number of recorded executions after which a drift report is emitted.
+    pub const REPORT_INTERVAL: usize = 25;
+    /// Minimum number of samples of a task type before its drift is fed back into scheduling.
+    const MIN_FEEDBACK_SAMPLES: usize = 5;
+    /// Upper bound for the lead time applied to scheduled tasks.
+    const MAX_LEAD: TimeDelta = TimeDelta::seconds(5);
+    /// Number of recent executions kept as samples.
+    const MAX_SAMPLES: usize = 200;
+
+    /// Records a single task execution.
+    ///
+    /// # Arguments
+    /// * `task_type` – Name of the executed task type.
+    /// * `planned` – Planned start time of the task.
+    /// * `started` – Actual start time of the task.
+    /// * `finished` – Actual end time of the task.
+    /// * `lead` – Command latency the task was fired ahead of its planned start by.
+    ///
+    /// # Returns
+    /// * `true` if a periodic drift report is due.
+    pub fn record(
+        &mut self,
+        task_type: &str,
+        planned: DateTime<Utc>,
+        started: DateTime<Utc>,
+        finished: DateTime<Utc>,
+        lead: TimeDelta,
+    ) -> bool {
+        let drift_ms = (started + lead - planned).num_milliseconds();
+        let duration_ms = (finished - started).num_milliseconds();
+        let lead_ms = lead.num_milliseconds();
+        self.per_type.entry(task_type.to_string()).or_default().add(drift_ms, duration_ms, lead_ms);
+        if self.recent.len() == Self::MAX_SAMPLES {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(TaskTimingSample {
+            task_type: task_type.to_string(),
+            planned,
+            started,
+            lead_ms,
+            drift_ms,
+            duration_ms,
+        });
+        self.since_report += 1;
+        if self.since_report >= Self::REPORT_INTERVAL {
+            self.since_report = 0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns the most recent executions, oldest first.
+    pub fn recent(&self) -> &VecDeque<TaskTimingSample> { &self.recent }
+
+    /// Returns the statistics for a specific task type, if any were recorded.
+    pub fn stats(&self, task_type: &str) -> Option<&TaskTimingStats> {
+        self.per_type.get(task_type)
+    }
+
+    /// Returns the lead time that tasks of the given type should be scheduled earlier by.
+    ///
+    /// The lead time is the mean start drift if it exceeds the lateness threshold
+    /// over enough samples, bounded by `MAX_LEAD`. Otherwise it is zero.
+    pub fn lead_time(&self, task_type: &str) -> TimeDelta {
+        self.stats(task_type)
+            .filter(|s| s.count() >= Self::MIN_FEEDBACK_SAMPLES)
+            .map(TaskTimingStats::mean_drift)
+            .filter(|drift| *drift > Self::LATENESS_THRESHOLD)
+            .map_or(TimeDelta::zero(), |drift| drift.min(Self::MAX_LEAD))
+    }
+}
+
+impl Display for TaskTimingReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Task timing drift report:")?;
+        for (task_type, stats) in &self.per_type {
+            writeln!(f, "  {task_type}: {stats}")?;
+        }
+        Ok(())
+    }
+}
+
+impl JsonDump for TaskTimingReport {
+    /// Returns the file name for the JSON dump of the timing report.
+    fn file_name(&self) -> String { format!("timing_{}", Utc::now().format("%d_%H_%M_%S")) }
+
+    /// Returns the directory name for the timing report JSON files.
+    fn dir_name(&self) -> &'static str { "task_timing" }
+}
@@ -1,12 +1,24 @@
-use super::task_controller::TaskController;
+use super::{
+    BlendedPlan, CommsBias, EndCondition, MissionTimeline, ScheduleSimReport, ScheduleSimulator,
+    SimViolation, TaskQueue, TaskTimingReport, VirtualClock, ZoCandidate,
+    task::{
+        BaseTask, ExternalEvent, NotEarlierThan, Task, TaskClass, TimeResolution, TimeoutPolicy,
+    },
+    task_controller::TaskController,
+};
 use crate::imaging::CameraAngle;
-use crate::util::Vec2D;
-use crate::flight_control::orbit::IndexedOrbitPosition;
+use crate::util::{ImgObjectiveId, Vec2D};
+use crate::flight_control::{
+    FlightState,
+    orbit::{BurnSequence, ClosedOrbit, ExitBurnResult, IndexedOrbitPosition, OrbitBase},
+};
 use crate::{STATIC_ORBIT_VEL, fatal, info, log};
-use chrono::{DateTime, TimeDelta, Utc};
+use chrono::{DateTime, SubsecRound, TimeDelta, Timelike, Utc};
 use fixed::types::I32F32;
 use num::Zero;
 use rand::Rng;
+use std::{collections::VecDeque, sync::Arc};
+use tokio::sync::RwLock;
 
 const STATIC_PERIOD: usize = 54000;
 
@@ -75,7 +87,8 @@ async fn test_single_target_burn_calculator() {
         mock_start_t,
         mock_end_t,
         mock_fuel_left,
-        1,
+        ImgObjectiveId::new(1),
+        I32F32::MAX,
     )
     .unwrap();
     let exit_burn = res.sequence();
@@ -145,7 +158,8 @@ async fn test_multi_target_burn_calculator() {
         mock_start_t,
         mock_end_t,
         mock_fuel_left,
-        1,
+        ImgObjectiveId::new(1),
+        I32F32::from(rand_angle.get_square_side_length() / 2),
     )
     .unwrap();
     let exit_burn = res.sequence();
@@ -208,3 +222,579 @@ fn test_zo_retrieval_burn_calculator() {
     log!("Velocity change sequence is {:?}", res.0);
 }
 */
+
+#[test]
+fn test_task_timing_lead_time() {
+    let mut report = TaskTimingReport::default();
+    let planned = Utc::now();
+    assert_eq!(report.lead_time("SwitchState"), TimeDelta::zero());
+    let no_lead = TimeDelta::zero();
+    for _ in 0..5 {
+        let started = planned + TimeDelta::milliseconds(1500);
+        let finished = started + TimeDelta::seconds(180);
+        report.record("SwitchState", planned, started, finished, no_lead);
+        report.record("TakeImage", planned, planned, planned + TimeDelta::seconds(2), no_lead);
+    }
+    assert_eq!(report.lead_time("SwitchState"), TimeDelta::milliseconds(1500));
+    assert_eq!(report.lead_time("TakeImage"), TimeDelta::zero());
+    for _ in 0..20 {
+        let started = planned + TimeDelta::seconds(30);
+        report.record("SwitchState", planned, started, started, no_lead);
+    }
+    assert_eq!(report.lead_time("SwitchState"), TimeDelta::seconds(5));
+
+    // Burns fired ahead by the command latency take effect on time
+    let latency = TimeDelta::milliseconds(400);
+    for _ in 0..5 {
+        let started = planned - latency;
+        report.record("ChangeVelocity", planned, started, started, latency);
+    }
+    assert_eq!(report.lead_time("ChangeVelocity"), TimeDelta::zero());
+    assert_eq!(report.stats("ChangeVelocity").unwrap().mean_lead(), latency);
+    assert_eq!(report.recent().len(), 35);
+    assert_eq!(report.recent().back().unwrap().drift_ms(), 0);
+}
+
+#[test]
+fn test_blended_plan() {
+    let id = ImgObjectiveId::new(1);
+    let start = Utc::now().trunc_subsecs(0);
+    let t_time = FlightState::Charge.td_dt_to(FlightState::Comms);
+    let in_comms = TaskController::IN_COMMS_SCHED_DT.to_delta();
+    let period = in_comms + t_time * 2 + TaskController::comms_usable_time();
+    let end = EndCondition::new(start + TimeDelta::days(1), I32F32::lit("50"), FlightState::Charge);
+    let deadline = end.time() - end.abs_charge_dt() - t_time * 2;
+
+    // The last active beacon objective limits the windows, the last one is shortened
+    let last_bo_end = start + period * 2 + TimeDelta::seconds(300);
+    let plan = BlendedPlan::new(id, start, &end, last_bo_end).unwrap();
+    assert_eq!(plan.comms_deadline(), deadline);
+    assert_eq!(plan.n_windows(), 3);
+    assert_eq!(plan.total_comms_time(), in_comms * 2 + TimeDelta::seconds(300));
+
+    // The deadline limits the windows if the burn is due before the beacon objectives end
+    let short_end = EndCondition::new(start + period, I32F32::lit("50"), FlightState::Charge);
+    let short_deadline = short_end.time() - short_end.abs_charge_dt() - t_time * 2;
+    let far_bo_end = start + TimeDelta::days(2);
+    let short_plan = BlendedPlan::new(id, start - period, &short_end, far_bo_end);
+    assert!(short_plan.is_some_and(|p| p.comms_deadline() == short_deadline));
+
+    // No window fits if the slack is too short
+    let no_slack = end.time() - end.abs_charge_dt() - t_time * 2 - TimeDelta::seconds(299);
+    assert!(BlendedPlan::new(id, no_slack, &end, last_bo_end + TimeDelta::days(1)).is_none());
+    assert!(BlendedPlan::new(id, last_bo_end, &end, last_bo_end).is_none());
+}
+
+#[tokio::test]
+async fn test_not_earlier_than_dependency() {
+    let t_cont = Arc::new(TaskController::new());
+    let due = Utc::now();
+    let accepted = ExternalEvent::ObjectiveAccepted(ImgObjectiveId::new(7));
+    let hold = NotEarlierThan::new(accepted, TimeDelta::seconds(5), TimeoutPolicy::Skip);
+    let t_cont_clone = Arc::clone(&t_cont);
+    let waiter = tokio::spawn(async move { t_cont_clone.await_dependency(&hold, due).await });
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    assert!(!waiter.is_finished());
+    t_cont.events().fire(accepted);
+    assert!(waiter.await.unwrap());
+
+    let booked = ExternalEvent::SlotBooked(1);
+    let skip = NotEarlierThan::new(booked, TimeDelta::milliseconds(50), TimeoutPolicy::Skip);
+    assert!(!t_cont.await_dependency(&skip, due).await);
+    let exec = NotEarlierThan::new(booked, TimeDelta::milliseconds(50), TimeoutPolicy::Execute);
+    assert!(t_cont.await_dependency(&exec, due).await);
+}
+
+#[tokio::test]
+async fn test_comms_switch_held_for_slot_booking() {
+    let t_cont = Arc::new(TaskController::new());
+    let now = Utc::now();
+    let comms = Task::switch_target(FlightState::Comms, now);
+    let comms_t = comms.t();
+    {
+        let sched_arc = t_cont.sched_arc();
+        let mut sched = sched_arc.write().await;
+        sched.push(comms);
+        sched.push(Task::switch_target(FlightState::Charge, comms_t + TimeDelta::seconds(600)));
+        sched.push(Task::switch_target(FlightState::Comms, comms_t + TimeDelta::seconds(900)));
+    }
+    t_cont.gate_comms_switches(&[(comms_t, 4), (comms_t + TimeDelta::hours(1), 5)]).await;
+    let deps: Vec<_> = t_cont
+        .sched_arc()
+        .read()
+        .await
+        .iter()
+        .map(|task| task.dependency().map(NotEarlierThan::event))
+        .collect();
+    assert_eq!(deps, [Some(ExternalEvent::SlotBooked(4)), None, None]);
+
+    // The due switch is held by the executor until the slot booking is confirmed
+    let task = t_cont.sched_arc().write().await.pop_front().unwrap();
+    let dep = *task.dependency().unwrap();
+    let t_cont_clone = Arc::clone(&t_cont);
+    let waiter = tokio::spawn(async move { t_cont_clone.await_dependency(&dep, task.t()).await });
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    assert!(!waiter.is_finished());
+    t_cont.events().fire(ExternalEvent::SlotBooked(4));
+    assert!(waiter.await.unwrap());
+}
+
+#[test]
+fn test_task_time_resolution() {
+    let t = DateTime::from_timestamp_millis(1_700_000_000_123).unwrap() + TimeDelta::microseconds(7);
+    let img = Task::image_task(Vec2D::new(0, 0), CameraAngle::Narrow, t);
+    assert_eq!(img.task_type().resolution(), TimeResolution::Millis);
+    assert_eq!(img.t().nanosecond(), 123_000_000);
+    let switch = Task::switch_target(FlightState::Charge, t);
+    assert_eq!(switch.task_type().resolution(), TimeResolution::Seconds);
+    assert_eq!(switch.t().nanosecond(), 0);
+}
+
+/// Tolerance of the simulated battery levels to the discretized battery levels of the DP.
+const SIM_BATT_TOLERANCE: I32F32 = I32F32::lit("1.0");
+
+/// Replays a DP schedule starting in `Charge` with `batt` until `end_t` and asserts that it
+/// never breaks the execution rules or falls below the minimum battery threshold.
+fn replay_dp_schedule(
+    sched: &TaskQueue,
+    start: DateTime<Utc>,
+    batt: I32F32,
+    end_t: DateTime<Utc>,
+) -> ScheduleSimReport {
+    let threshold = TaskController::min_battery_threshold() - SIM_BATT_TOLERANCE;
+    let report = ScheduleSimulator::new(start, FlightState::Charge, batt, I32F32::lit("100"))
+        .with_threshold(threshold)
+        .run(sched, end_t);
+    assert!(report.is_valid(), "{:?}", report.violations());
+    report
+}
+
+/// Asserts that a replayed schedule reaches the state and charge of its end condition.
+fn assert_end_reached(report: &ScheduleSimReport, end: &EndCondition) {
+    let last = report.trajectory().last().unwrap();
+    assert_eq!(last.state, end.state());
+    assert!(last.batt >= end.charge() - SIM_BATT_TOLERANCE, "{last:?}");
+}
+
+#[tokio::test]
+async fn test_simulated_orbit_schedule() {
+    let o_b = OrbitBase::test(get_rand_pos(), Vec2D::from(STATIC_ORBIT_VEL));
+    let c_orbit = ClosedOrbit::new(o_b, CameraAngle::Narrow).unwrap();
+    let period = c_orbit.period().0.to_num::<usize>();
+    let start_i = IndexedOrbitPosition::new(0, period, get_rand_pos());
+    let start = start_i.t();
+    let end_t = start + TimeDelta::hours(3);
+    let end = EndCondition::new(end_t, I32F32::lit("60"), FlightState::Acquisition);
+    let batt = I32F32::lit("30");
+    let t_cont = TaskController::new();
+    let clock = VirtualClock::new(start);
+    let st_batt = (batt, FlightState::Charge.to_dp_usize());
+    let orbit_lock = RwLock::new(c_orbit);
+    let n_tasks =
+        t_cont.sched_opt_orbit_from(&orbit_lock, start_i, Some(end), st_batt, &clock).await;
+    assert!(n_tasks > 0);
+
+    let report = replay_dp_schedule(&*t_cont.sched_arc().read().await, start, batt, end_t);
+    assert_end_reached(&report, &end);
+}
+
+#[tokio::test]
+async fn test_pinned_tasks_schedule() {
+    let o_b = OrbitBase::test(get_rand_pos(), Vec2D::from(STATIC_ORBIT_VEL));
+    let c_orbit = ClosedOrbit::new(o_b, CameraAngle::Narrow).unwrap();
+    let period = c_orbit.period().0.to_num::<usize>();
+    let start_i = IndexedOrbitPosition::new(0, period, get_rand_pos());
+    let start = start_i.t();
+    let (img_t, charge_t) = (start + TimeDelta::hours(1), start + TimeDelta::hours(2));
+    let end_t = start + TimeDelta::hours(3);
+    let end = EndCondition::new(end_t, I32F32::lit("60"), FlightState::Acquisition);
+    let batt = I32F32::lit("30");
+    let t_cont = TaskController::new();
+    t_cont.schedule_pinned(Task::switch_target(FlightState::Charge, charge_t)).await;
+    t_cont.schedule_pinned(Task::image_task(Vec2D::new(0, 0), CameraAngle::Wide, img_t)).await;
+    let clock = VirtualClock::new(start);
+    let st_batt = (batt, FlightState::Charge.to_dp_usize());
+    let orbit_lock = RwLock::new(c_orbit);
+    t_cont.sched_opt_orbit_from(&orbit_lock, start_i, Some(end), st_batt, &clock).await;
+
+    let sched_lock = t_cont.sched_arc();
+    let sched = sched_lock.read().await;
+    assert_eq!(sched.iter().filter(|t| t.is_pinned()).count(), 2);
+    assert!(sched.iter().zip(sched.iter().skip(1)).all(|(a, b)| a.t() <= b.t()));
+    let threshold = TaskController::min_battery_threshold() - SIM_BATT_TOLERANCE;
+    let sim = ScheduleSimulator::new(start, FlightState::Charge, batt, I32F32::lit("100"));
+    let report = sim.with_threshold(threshold).run(sched.iter(), end_t);
+    // A pinned switch is redundant if the scheduler already planned to be in its state
+    let violations = report.violations();
+    assert!(violations.iter().all(|v| matches!(v, SimViolation::RedundantSwitch { .. })));
+    drop(sched);
+    t_cont.clear_schedule().await;
+    assert_eq!(t_cont.cancel_pinned().await, 2);
+    assert!(sched_lock.read().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_clear_schedule_expires_pins() {
+    let now = Utc::now();
+    let t_cont = TaskController::new();
+    let (charge_t, img_t) = (now - TimeDelta::minutes(5), now + TimeDelta::hours(1));
+    t_cont.schedule_pinned(Task::switch_target(FlightState::Charge, charge_t)).await;
+    t_cont.schedule_pinned(Task::image_task(Vec2D::new(10, 10), CameraAngle::Wide, img_t)).await;
+    let sched_lock = t_cont.sched_arc();
+    sched_lock.write().await.push(Task::switch_target(FlightState::Acquisition, img_t));
+    t_cont.clear_schedule().await;
+
+    let sched = sched_lock.read().await;
+    assert_eq!(sched.len(), 1);
+    let BaseTask::TakeImage(image) = sched.iter().next().unwrap().task_type() else {
+        panic!("Only the pending pinned image task survives the clearing");
+    };
+    let half_side = i32::from(CameraAngle::Wide.get_square_side_length() / 2);
+    assert!(image.covers(Vec2D::new(I32F32::from_num(10 + half_side), I32F32::lit("10"))));
+    assert!(!image.covers(Vec2D::new(I32F32::from_num(11 + half_side), I32F32::lit("10"))));
+    // The footprint wraps around the map border
+    assert!(image.covers(Vec2D::new(I32F32::from_num(21_600 - 5), I32F32::lit("10"))));
+}
+
+#[tokio::test]
+async fn test_comms_dp_schedule() {
+    let o_b = OrbitBase::test(get_rand_pos(), Vec2D::from(STATIC_ORBIT_VEL));
+    let mut c_orbit = ClosedOrbit::new(o_b, CameraAngle::Narrow).unwrap();
+    let period = c_orbit.period().0.to_num::<usize>();
+    // Without anything left to image, beacon scanning is the only way to score
+    c_orbit.mark_done(0, period - 1);
+    let start = Utc::now().trunc_subsecs(0);
+    let last_bo_end = start + TimeDelta::hours(2);
+    let end_t = start + TimeDelta::hours(3);
+    let end = EndCondition::new(end_t, I32F32::lit("50"), FlightState::Acquisition);
+    let batt = I32F32::lit("40");
+    let t_cont = TaskController::new();
+    let st_batt = (batt, FlightState::Charge.to_dp_usize());
+    let orbit_lock = RwLock::new(c_orbit);
+    let n_tasks = t_cont
+        .sched_opt_orbit_w_comms_from(
+            &orbit_lock,
+            (start, 0),
+            last_bo_end,
+            Some(end),
+            st_batt,
+            &CommsBias::uniform(),
+            &[],
+        )
+        .await;
+    assert!(n_tasks > 0);
+
+    let sched_lock = t_cont.sched_arc();
+    let sched = sched_lock.read().await;
+    let comms_switch = sched.iter().find(|t| {
+        matches!(t.task_type(), BaseTask::SwitchState(sw) if sw.target_state() == FlightState::Comms)
+    });
+    assert!(comms_switch.is_some_and(|t| t.t() < last_bo_end));
+    let report = replay_dp_schedule(&sched, start, batt, end_t);
+    assert_end_reached(&report, &end);
+}
+
+#[tokio::test]
+async fn test_booked_slot_schedule() {
+    let o_b = OrbitBase::test(get_rand_pos(), Vec2D::from(STATIC_ORBIT_VEL));
+    let c_orbit = ClosedOrbit::new(o_b, CameraAngle::Narrow).unwrap();
+    let start = Utc::now().trunc_subsecs(0);
+    let last_bo_end = start + TimeDelta::hours(2);
+    let slot = (start + TimeDelta::hours(1), start + TimeDelta::minutes(70));
+    let end_t = start + TimeDelta::hours(3);
+    let end = EndCondition::new(end_t, I32F32::lit("50"), FlightState::Acquisition);
+    let batt = I32F32::lit("60");
+    let t_cont = TaskController::new();
+    let st_batt = (batt, FlightState::Charge.to_dp_usize());
+    let orbit_lock = RwLock::new(c_orbit);
+    t_cont
+        .sched_opt_orbit_w_comms_from(
+            &orbit_lock,
+            (start, 0),
+            last_bo_end,
+            Some(end),
+            st_batt,
+            &CommsBias::uniform(),
+            &[slot],
+        )
+        .await;
+
+    let windows = t_cont.comms_windows(None, last_bo_end).await;
+    assert!(windows.iter().any(|(s, e)| *s <= slot.0 && *e >= slot.1), "{windows:?}");
+    let report = replay_dp_schedule(&*t_cont.sched_arc().read().await, start, batt, end_t);
+    assert_end_reached(&report, &end);
+}
+
+#[test]
+fn test_schedule_simulator_violations() {
+    let start = Utc::now().trunc_subsecs(0);
+    let at = |secs| start + TimeDelta::seconds(secs);
+    let sched = VecDeque::from([
+        Task::image_task(Vec2D::new(0, 0), CameraAngle::Narrow, at(10)),
+        Task::switch_target(FlightState::Acquisition, at(20)),
+        Task::switch_target(FlightState::Charge, at(60)),
+        Task::switch_target(FlightState::Charge, at(1000)),
+    ]);
+    let sim = ScheduleSimulator::new(start, FlightState::Charge, I32F32::lit("5"), I32F32::ZERO);
+    let report = sim.run(sched.iter(), at(2000));
+    let trans_end = at(20) + FlightState::Charge.td_dt_to(FlightState::Acquisition);
+    assert_eq!(report.violations(), &[
+        SimViolation::WrongState {
+            task: 0,
+            t: at(10),
+            required: FlightState::Acquisition,
+            actual: FlightState::Charge,
+        },
+        SimViolation::Busy { task: 2, t: at(60), busy_until: trans_end },
+        SimViolation::RedundantSwitch { task: 3, t: at(1000), state: FlightState::Charge },
+    ]);
+    assert_eq!(report.trajectory().len(), 6);
+
+    // Safe mode can not be left for communication, and acquisition drains the battery
+    let sched = [Task::switch_target(FlightState::Comms, at(10))];
+    let sim = ScheduleSimulator::new(start, FlightState::Safe, I32F32::lit("50"), I32F32::ZERO);
+    let report = sim.run(&sched, at(20));
+    assert_eq!(report.violations(), &[SimViolation::IllegalTransition {
+        task: 0,
+        t: at(10),
+        from: FlightState::Safe,
+        to: FlightState::Comms,
+    }]);
+    let (batt, threshold) = (I32F32::lit("5"), I32F32::lit("4"));
+    let sim = ScheduleSimulator::new(start, FlightState::Acquisition, batt, I32F32::ZERO);
+    let report = sim.with_threshold(threshold).run(&[], at(1000));
+    assert_eq!(report.violations(), &[
+        SimViolation::BelowThreshold { t: at(1000), batt: I32F32::ZERO, threshold },
+        SimViolation::BatteryDepleted { t: at(1000) },
+    ]);
+}
+
+#[test]
+fn test_zo_leg_sequencing() {
+    let pos = |x: i32, y: i32| Vec2D::new(I32F32::from_num(x), I32F32::from_num(y));
+    let start_i = IndexedOrbitPosition::new(0, STATIC_PERIOD, pos(1000, 1000));
+    let burn = ExitBurnResult::straight_fixture(start_i, I32F32::zero(), ImgObjectiveId::new(1));
+    let arrival = start_i.t() + TimeDelta::seconds(1100);
+    let candidate = |id: usize, p: Vec2D<I32F32>, end_h: i64| ZoCandidate {
+        id: ImgObjectiveId::new(id),
+        pos: p,
+        start: start_i.t(),
+        end: arrival + TimeDelta::hours(end_h),
+    };
+    let candidates = [
+        candidate(2, pos(6800, 1000), 3),
+        candidate(3, pos(9000, 1000), 3),
+        candidate(4, pos(6000, 1050), 1),
+        candidate(5, pos(5500, 1000), 0),
+    ];
+    let ids = |budget: I32F32| -> Vec<usize> {
+        TaskController::sequence_zo_legs(&burn, &candidates, budget)
+            .iter()
+            .map(|leg| leg.id().raw())
+            .collect()
+    };
+    // The earlier deadline is retrieved first, out of reach and expired zones are skipped
+    assert_eq!(ids(I32F32::lit("100")), vec![4, 2]);
+    // Without fuel for turns, only the zone straight ahead can be retrieved
+    assert_eq!(ids(I32F32::zero()), vec![2]);
+}
+
+#[test]
+fn test_emergency_burn_plan() {
+    let pos = |x: i32, y: i32| Vec2D::new(I32F32::from_num(x), I32F32::from_num(y));
+    let now = Utc::now().trunc_subsecs(0);
+    let burn_at = |t: DateTime<Utc>| {
+        let start_i = IndexedOrbitPosition::new(0, STATIC_PERIOD, pos(1000, 1000));
+        let burn_i = start_i.new_from_future_pos(pos(1000, 1000), t);
+        ExitBurnResult::straight_fixture(burn_i, I32F32::zero(), ImgObjectiveId::new(1))
+    };
+    let steps = |state: FlightState, burn: &BurnSequence| -> Vec<(Option<FlightState>, i64)> {
+        TaskController::emergency_burn_plan(now, state, burn)
+            .unwrap()
+            .iter()
+            .map(|task| {
+                let target = match task.task_type() {
+                    BaseTask::SwitchState(sw) => Some(sw.target_state()),
+                    _ => None,
+                };
+                (target, (task.t() - now).num_seconds())
+            })
+            .collect()
+    };
+    let far_t = now + TimeDelta::minutes(30);
+    let far = burn_at(far_t);
+    let charge_until = (far_t - FlightState::Charge.td_dt_to(FlightState::Acquisition) - now)
+        .num_seconds();
+    let burn_secs = (far_t - now).num_seconds();
+    let acq = Some(FlightState::Acquisition);
+    // With enough time left, MELVIN charges until the transition to the burn
+    assert_eq!(steps(FlightState::Charge, far.sequence()), [
+        (acq, charge_until),
+        (None, burn_secs)
+    ]);
+    assert_eq!(steps(FlightState::Acquisition, far.sequence()), [
+        (Some(FlightState::Charge), 0),
+        (acq, charge_until),
+        (None, burn_secs)
+    ]);
+    // Without time to charge, the burn is only prepared
+    let near = burn_at(now + TimeDelta::seconds(200));
+    assert_eq!(steps(FlightState::Acquisition, near.sequence()), [(None, 200)]);
+    assert_eq!(steps(FlightState::Comms, near.sequence()), [(acq, 0), (None, 200)]);
+
+    // The relaxed burn is feasible on a battery the nominal burn rejects
+    let instant = burn_at(now);
+    let batt = instant.sequence().min_charge() - I32F32::lit("2");
+    let simulate = |burn: &BurnSequence| {
+        let plan = TaskController::emergency_burn_plan(now, FlightState::Acquisition, burn);
+        let sim = ScheduleSimulator::new(now, FlightState::Acquisition, batt, I32F32::lit("100"));
+        sim.run(plan.unwrap().iter(), now + TimeDelta::seconds(100))
+    };
+    let nominal = simulate(instant.sequence());
+    assert!(matches!(nominal.violations(), [SimViolation::InsufficientCharge { .. }]));
+    let relaxed = instant.with_charge_floor(I32F32::lit("4"));
+    let report = simulate(relaxed.sequence());
+    assert!(report.is_valid(), "{:?}", report.violations());
+    assert!(report.min_batt() >= I32F32::lit("4"));
+}
+
+#[test]
+fn test_retrieval_phase_plan() {
+    let pos = Vec2D::new(I32F32::lit("5000"), I32F32::lit("1000"));
+    let now = Utc::now().trunc_subsecs(0);
+    let steps = |t: DateTime<Utc>| -> Vec<(Option<FlightState>, i64)> {
+        TaskController::retrieval_phase_plan(now, t, pos, CameraAngle::Normal)
+            .iter()
+            .map(|task| {
+                let target = match task.task_type() {
+                    BaseTask::SwitchState(sw) => Some(sw.target_state()),
+                    _ => None,
+                };
+                (target, (task.t() - now).num_seconds())
+            })
+            .collect()
+    };
+    let far_t = now + TimeDelta::minutes(30);
+    let t_first = far_t - TaskController::ZO_IMAGE_FIRST_DEL;
+    let acq_from =
+        (t_first - FlightState::Acquisition.td_dt_to(FlightState::Charge) - now).num_seconds();
+    // With enough time left, MELVIN charges until the transition to the first image
+    assert_eq!(steps(far_t), [
+        (Some(FlightState::Charge), 0),
+        (Some(FlightState::Acquisition), acq_from),
+        (None, (t_first - now).num_seconds())
+    ]);
+    assert_eq!(steps(now + TimeDelta::seconds(60)), [(None, 55)]);
+
+    // MELVIN is charged and back in acquisition when the first image is due
+    let batt = I32F32::lit("20");
+    let plan = TaskController::retrieval_phase_plan(now, far_t, pos, CameraAngle::Normal);
+    let sim = ScheduleSimulator::new(now, FlightState::Acquisition, batt, I32F32::lit("100"));
+    let report = sim.run(&plan, t_first + TimeDelta::seconds(10));
+    assert!(report.is_valid(), "{:?}", report.violations());
+    let image = &report.trajectory()[plan.len()];
+    assert_eq!(image.state, FlightState::Acquisition);
+    assert!(image.batt > batt);
+}
+
+#[test]
+fn test_comms_bias() {
+    let o_b = OrbitBase::test(get_rand_pos(), Vec2D::from(STATIC_ORBIT_VEL));
+    let c_orbit = ClosedOrbit::new(o_b, CameraAngle::Narrow).unwrap();
+    let period = c_orbit.period().0.to_num::<usize>();
+    let uniform = CommsBias::uniform();
+    assert!(uniform.nearest_pass(&c_orbit, 0).is_none());
+    assert!(uniform.scores(&c_orbit, 0, 100, 2.0).iter().all(|s| *s == 2));
+
+    // A candidate right on the orbit, passed a quarter period after the start
+    let pass_dt = period / 4;
+    let target = c_orbit.pos_at(pass_dt);
+    let bias = CommsBias::new(&[(target, 0.5)], 1.0);
+    let (offset, dist) = bias.nearest_pass(&c_orbit, 0).unwrap();
+    assert!(offset.abs_diff(pass_dt) <= 1 && dist < 1.0);
+    let scores = bias.scores(&c_orbit, 0, period, 8.0);
+    assert_eq!(scores.len(), period);
+    assert_eq!(scores[pass_dt], 16);
+    assert!(scores.iter().all(|s| (8..=16).contains(s)));
+    assert_eq!(*scores.iter().min().unwrap(), 8);
+    // The phase offset is counted from the given orbit index
+    let (shifted, _) = bias.nearest_pass(&c_orbit, pass_dt - 10).unwrap();
+    assert!(shifted.abs_diff(10) <= 1);
+}
+
+#[test]
+fn test_mission_timeline() {
+    let now = Utc::now().trunc_subsecs(0);
+    let at = |s: i64| now + TimeDelta::seconds(s);
+    let tasks = [
+        Task::switch_target(FlightState::Acquisition, at(60)),
+        Task::image_task(Vec2D::new(0, 0), CameraAngle::Narrow, at(300)),
+        Task::image_task(Vec2D::new(10, 0), CameraAngle::Narrow, at(330)),
+        Task::image_task(Vec2D::new(20, 0), CameraAngle::Narrow, at(600)),
+        Task::switch_target(FlightState::Comms, at(900)),
+    ];
+    let mut timeline = MissionTimeline::from_schedule(now, FlightState::Charge, tasks.iter());
+    timeline.add_objective("ZO 3-'<east>'".to_string(), at(100), at(1200));
+
+    let json: serde_json::Value = serde_json::from_str(&timeline.to_json()).unwrap();
+    let bars = json["bars"].as_array().unwrap();
+    let lane = |l: &str| bars.iter().filter(|b| b["lane"] == l).collect::<Vec<_>>();
+    let states: Vec<_> = lane("state").iter().map(|b| b["label"].as_str().unwrap()).collect();
+    assert_eq!(states, ["Charge", "Acquisition", "Comms"]);
+    assert_eq!(lane("comms").len(), 1);
+    let imaging: Vec<_> = lane("imaging").iter().map(|b| b["label"].as_str().unwrap()).collect();
+    assert_eq!(imaging, ["2 images", "1 images"]);
+    assert_eq!(lane("objective").len(), 1);
+
+    let svg = timeline.to_svg();
+    assert!(svg.starts_with("<svg") && svg.trim_end().ends_with("</svg>"));
+    assert!(svg.contains("&lt;east&gt;") && !svg.contains("<east>"));
+}
+
+#[test]
+fn test_task_queue_splice_urgent() {
+    let pos = |x: i32, y: i32| Vec2D::new(I32F32::from_num(x), I32F32::from_num(y));
+    let now = Utc::now().trunc_subsecs(0);
+    let at = |s: i64| now + TimeDelta::seconds(s);
+    let switch = |state: FlightState, s: i64| Task::switch_target(state, at(s));
+    let mut queue = TaskQueue::new();
+    queue.extend([
+        switch(FlightState::Charge, 100),
+        switch(FlightState::Acquisition, 400),
+        switch(FlightState::Comms, 450),
+        switch(FlightState::Acquisition, 700),
+        switch(FlightState::Charge, 2000),
+    ]);
+    queue.push(switch(FlightState::Charge, 330).pin());
+    // Critical tasks precede routine tasks due at the same time
+    queue.push(Task::image_task(Vec2D::new(0, 0), CameraAngle::Narrow, at(100)));
+    assert_eq!(queue.front().unwrap().task_type().class(), TaskClass::Image);
+    assert_eq!(queue.next_of(TaskClass::Switch).unwrap().t(), at(100));
+    assert!(queue.next_of(TaskClass::Burn).is_none());
+
+    // The burn displaces the switch to Comms before it and the now redundant return
+    let start_i = IndexedOrbitPosition::new(0, STATIC_PERIOD, pos(1000, 1000));
+    let burn = BurnSequence::new(
+        start_i.new_from_future_pos(pos(1000, 1000), at(500)),
+        Box::from([pos(1000, 1000), pos(1010, 1000)]),
+        Box::from([pos(10, 0), pos(10, 0)]),
+        100,
+        1000,
+        I32F32::zero(),
+        0,
+    );
+    let displaced = queue.splice_urgent(Task::vel_change_task(burn, at(500)));
+    let secs = |task: &Task| (task.t() - now).num_seconds();
+    assert_eq!(displaced.iter().map(secs).collect::<Vec<_>>(), [450, 700]);
+    assert_eq!(queue.iter().map(secs).collect::<Vec<_>>(), [100, 100, 330, 400, 500, 2000]);
+    assert_eq!(queue.next_of(TaskClass::Burn).unwrap().t(), at(500));
+
+    // A preempted task is displaced on its return if it is in the way of the burn
+    assert_eq!(queue.requeue(switch(FlightState::Comms, 550)).len(), 1);
+    assert!(queue.requeue(switch(FlightState::Comms, 1000)).is_empty());
+    assert_eq!(queue.len(), 7);
+    let full = I32F32::lit("100");
+    let report = ScheduleSimulator::new(now, FlightState::Acquisition, full, full)
+        .run(queue.iter(), at(3000));
+    assert!(report.violations().iter().all(|v| !matches!(v, SimViolation::OutOfOrder { .. })));
+}
@@ -0,0 +1,64 @@
+use crate::util::{ImgObjectiveId, Vec2D};
+use chrono::{DateTime, Utc};
+use fixed::types::I32F32;
+
+/// A zoned objective that may be retrieved during the off-orbit phase of another one.
+#[derive(Debug, Clone, Copy)]
+pub struct ZoCandidate {
+    /// The ID of the candidate objective.
+    pub id: ImgObjectiveId,
+    /// The imaging position of the candidate objective.
+    pub pos: Vec2D<I32F32>,
+    /// The start of the candidate objective window.
+    pub start: DateTime<Utc>,
+    /// The end of the candidate objective window.
+    pub end: DateTime<Utc>,
+}
+
+/// A single leg of a combined zoned objective retrieval.
+///
+/// After the imaging of the previous zone, MELVIN turns towards the imaging position of the
+/// leg and retrieves its objective without returning to the closed orbit in between.
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+pub struct ZoLeg {
+    /// The ID of the retrieved objective.
+    id: ImgObjectiveId,
+    /// The imaging position of the retrieved objective.
+    pos: Vec2D<I32F32>,
+    /// The distance from the previous imaging position.
+    dist: I32F32,
+    /// The estimated time the imaging position is reached.
+    t: DateTime<Utc>,
+    /// The estimated fuel needed for the turn towards the imaging position.
+    turn_fuel: I32F32,
+    /// The estimated fuel saved compared to a separate exit/return cycle.
+    fuel_saved: I32F32,
+}
+
+impl ZoLeg {
+    /// Creates a new [`ZoLeg`].
+    pub(super) fn new(
+        candidate: &ZoCandidate,
+        dist: I32F32,
+        t: DateTime<Utc>,
+        turn_fuel: I32F32,
+        fuel_saved: I32F32,
+    ) -> Self {
+        Self { id: candidate.id, pos: candidate.pos, dist, t, turn_fuel, fuel_saved }
+    }
+
+    /// Returns the ID of the retrieved objective.
+    pub fn id(&self) -> ImgObjectiveId { self.id }
+
+    /// Returns the imaging position of the retrieved objective.
+    pub fn pos(&self) -> Vec2D<I32F32> { self.pos }
+
+    /// Returns the estimated time the imaging position is reached.
+    pub fn t(&self) -> DateTime<Utc> { self.t }
+
+    /// Returns the estimated fuel needed for the turn towards the imaging position.
+    pub fn turn_fuel(&self) -> I32F32 { self.turn_fuel }
+
+    /// Returns the estimated fuel saved compared to a separate exit/return cycle.
+    pub fn fuel_saved(&self) -> I32F32 { self.fuel_saved }
+}
@@ -0,0 +1,324 @@
+use super::{
+    ImgObjectiveId,
+    logger::{JsonDump, LogLevel},
+};
+use crate::scheduling::task::ExternalEvent;
+use crate::warn;
+use chrono::{DateTime, Utc};
+use fixed::types::I32F32;
+use std::{
+    sync::{
+        LazyLock,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+use tokio::{
+    sync::broadcast::{self, error::RecvError},
+    time::Instant,
+};
+
+/// The process-wide event bus.
+pub(crate) static EVENT_BUS: LazyLock<EventBus> = LazyLock::new(EventBus::new);
+
+/// Events concerning the lifecycle of imaging objectives.
+#[derive(Debug, Clone)]
+pub(crate) enum ObjectiveEvent {
+    /// An announced zoned objective was deleted by the backend before its end.
+    ZoRemoved(ImgObjectiveId),
+}
+
+/// Events concerning beacon objectives.
+#[derive(Debug, Clone)]
+pub(crate) enum BeaconEvent {
+    /// A message received on the backend announcement stream, possibly a beacon ping.
+    Announcement(DateTime<Utc>, String),
+}
+
+/// Events concerning the safety of the satellite and the mission.
+#[derive(Debug, Clone)]
+pub(crate) enum SafetyEvent {
+    /// An unplanned safe mode was entered.
+    SafeModeEntered,
+    /// The satellite left safe mode again.
+    SafeModeLeft,
+    /// The end-of-mission routine was requested.
+    EndOfMission,
+    /// Observation requests succeed again after the link outage between the two times.
+    LinkRestored(DateTime<Utc>, DateTime<Utc>),
+    /// The circuit breaker of the DRS client opened at the given time after persistent failures.
+    BackendOutage(DateTime<Utc>),
+    /// The DRS backend answers again after the breaker was open between the two times.
+    BackendRestored(DateTime<Utc>, DateTime<Utc>),
+    /// MELVIN persistently deviates from the closed orbit by the given distance.
+    OrbitDeviation(I32F32),
+    /// An operator aborted the current global mode from the console.
+    OperatorAbort,
+    /// The free disk space fell to the given number of bytes, below `min_free_disk_mib`.
+    DiskSpaceLow(u64),
+}
+
+/// Events concerning the task schedule.
+#[derive(Debug, Clone)]
+pub(crate) enum SchedulingEvent {
+    /// An external event fired, releasing dependent tasks.
+    ExternalFired(ExternalEvent),
+    /// An urgent task due at the given time was spliced into the schedule.
+    UrgentSpliced(DateTime<Utc>),
+}
+
+/// Events concerning image acquisition.
+#[derive(Debug, Clone)]
+pub(crate) enum ImagingEvent {
+    /// Imaging became degraded after a streak of failed captures.
+    CaptureDegraded,
+    /// Imaging recovered from a degraded state.
+    CaptureRecovered,
+    /// The daily map coverage check failed and stale stripes were reopened for imaging.
+    CoverageCatchUp,
+}
+
+/// Log messages routed to the operator console.
+#[derive(Debug, Clone)]
+pub(crate) enum LogEvent {
+    /// A message at or above `console_log_level` was emitted.
+    Message(LogLevel, String),
+}
+
+/// An event type that is published on its own topic of the [`EventBus`].
+pub(crate) trait BusEvent: Clone + Send + 'static {
+    /// Returns the topic of the event type on the given bus.
+    fn topic(bus: &EventBus) -> &Topic<Self>;
+}
+
+/// Implements [`BusEvent`] for an event type by mapping it to a field of the [`EventBus`].
+macro_rules! bus_topic {
+    ($event:ty, $field:ident) => {
+        impl BusEvent for $event {
+            fn topic(bus: &EventBus) -> &Topic<Self> { &bus.$field }
+        }
+    };
+}
+
+bus_topic!(ObjectiveEvent, objectives);
+bus_topic!(BeaconEvent, beacons);
+bus_topic!(SafetyEvent, safety);
+bus_topic!(SchedulingEvent, scheduling);
+bus_topic!(ImagingEvent, imaging);
+bus_topic!(LogEvent, logs);
+
+/// An event together with its publication time.
+#[derive(Debug, Clone)]
+struct Envelope<E> {
+    /// The time the event was published.
+    sent: Instant,
+    /// The published event.
+    event: E,
+}
+
+/// A single typed topic of the [`EventBus`] with its instrumentation counters.
+#[derive(Debug)]
+pub(crate) struct Topic<E> {
+    /// The name of the topic used in reports.
+    name: &'static str,
+    /// The broadcast sender distributing the events to all subscribers.
+    sender: broadcast::Sender<Envelope<E>>,
+    /// The number of published events.
+    published: AtomicU64,
+    /// The number of events published without any subscriber.
+    unheard: AtomicU64,
+    /// The number of events delivered to subscribers.
+    delivered: AtomicU64,
+    /// The number of events lagging subscribers skipped.
+    lagged: AtomicU64,
+    /// The highest delivery latency since the last report in microseconds.
+    peak_latency_us: AtomicU64,
+}
+
+impl<E: Clone> Topic<E> {
+    /// The number of events a topic buffers per subscriber.
+    const CAPACITY: usize = 32;
+
+    /// Creates a new, empty [`Topic`].
+    ///
+    /// # Arguments
+    /// * `name` – The name of the topic used in reports.
+    fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            sender: broadcast::Sender::new(Self::CAPACITY),
+            published: AtomicU64::new(0),
+            unheard: AtomicU64::new(0),
+            delivered: AtomicU64::new(0),
+            lagged: AtomicU64::new(0),
+            peak_latency_us: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the current statistics and resets the peak latency.
+    fn take_stats(&self) -> TopicStats {
+        TopicStats {
+            name: self.name,
+            subscribers: self.sender.receiver_count(),
+            published: self.published.load(Ordering::Relaxed),
+            unheard: self.unheard.load(Ordering::Relaxed),
+            delivered: self.delivered.load(Ordering::Relaxed),
+            lagged: self.lagged.load(Ordering::Relaxed),
+            peak_latency_us: self.peak_latency_us.swap(0, Ordering::Relaxed),
+        }
+    }
+}
+
+/// A subscription to a single topic of the [`EventBus`].
+pub(crate) struct Subscription<E: BusEvent> {
+    /// The subscribed topic.
+    topic: &'static Topic<E>,
+    /// The broadcast receiver of the topic.
+    receiver: broadcast::Receiver<Envelope<E>>,
+}
+
+impl<E: BusEvent> Subscription<E> {
+    /// Waits for the next event on the topic.
+    ///
+    /// Events skipped because the subscriber lagged behind are counted and logged, the
+    /// subscription then continues with the oldest retained event.
+    ///
+    /// # Returns
+    /// * `Some(E)` for the next event, `None` once the bus is closed.
+    #[allow(clippy::cast_possible_truncation)]
+    pub(crate) async fn recv(&mut self) -> Option<E> {
+        let topic = self.topic;
+        loop {
+            match self.receiver.recv().await {
+                Ok(envelope) => {
+                    let latency_us = envelope.sent.elapsed().as_micros() as u64;
+                    topic.delivered.fetch_add(1, Ordering::Relaxed);
+                    topic.peak_latency_us.fetch_max(latency_us, Ordering::Relaxed);
+                    return Some(envelope.event);
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    topic.lagged.fetch_add(skipped, Ordering::Relaxed);
+                    warn!("Subscriber of topic {} skipped {skipped} events.", topic.name);
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+/// Typed publish/subscribe bus connecting the subsystems.
+///
+/// Every event type lives on its own topic, so subscribers only ever see the events they
+/// asked for and publishers need no knowledge of their consumers. Each topic counts
+/// published, delivered and skipped events and tracks the delivery latency, which is
+/// periodically dumped as a [`BusReport`].
+#[derive(Debug)]
+pub(crate) struct EventBus {
+    /// Objective lifecycle events.
+    objectives: Topic<ObjectiveEvent>,
+    /// Beacon events.
+    beacons: Topic<BeaconEvent>,
+    /// Safety events.
+    safety: Topic<SafetyEvent>,
+    /// Scheduling events.
+    scheduling: Topic<SchedulingEvent>,
+    /// Imaging events.
+    imaging: Topic<ImagingEvent>,
+    /// Log messages routed to the console.
+    logs: Topic<LogEvent>,
+}
+
+impl EventBus {
+    /// Interval between two reports.
+    const REPORT_INTERVAL: Duration = Duration::from_secs(60);
+
+    /// Creates a new [`EventBus`] with empty topics.
+    fn new() -> Self {
+        Self {
+            objectives: Topic::new("objectives"),
+            beacons: Topic::new("beacons"),
+            safety: Topic::new("safety"),
+            scheduling: Topic::new("scheduling"),
+            imaging: Topic::new("imaging"),
+            logs: Topic::new("logs"),
+        }
+    }
+
+    /// Publishes an event on its topic.
+    ///
+    /// # Arguments
+    /// * `event` – The event to publish.
+    ///
+    /// # Returns
+    /// * `true` if at least one subscriber received the event.
+    pub(crate) fn publish<E: BusEvent>(&self, event: E) -> bool {
+        let topic = E::topic(self);
+        topic.published.fetch_add(1, Ordering::Relaxed);
+        let heard = topic.sender.send(Envelope { sent: Instant::now(), event }).is_ok();
+        if !heard {
+            topic.unheard.fetch_add(1, Ordering::Relaxed);
+        }
+        heard
+    }
+
+    /// Subscribes to all events published on the topic of `E` from now on.
+    pub(crate) fn subscribe<E: BusEvent>(&'static self) -> Subscription<E> {
+        let topic = E::topic(self);
+        Subscription { topic, receiver: topic.sender.subscribe() }
+    }
+
+    /// Periodically dumps a [`BusReport`] with the statistics of all topics.
+    pub(crate) async fn run_monitor(&self) {
+        loop {
+            tokio::time::sleep(Self::REPORT_INTERVAL).await;
+            BusReport {
+                t: Utc::now(),
+                topics: vec![
+                    self.objectives.take_stats(),
+                    self.beacons.take_stats(),
+                    self.safety.take_stats(),
+                    self.scheduling.take_stats(),
+                    self.imaging.take_stats(),
+                    self.logs.take_stats(),
+                ],
+            }
+            .dump_json();
+        }
+    }
+}
+
+/// Statistics of a single [`Topic`].
+#[derive(Debug, serde::Serialize)]
+struct TopicStats {
+    /// The name of the topic.
+    name: &'static str,
+    /// The number of current subscribers.
+    subscribers: usize,
+    /// The number of published events.
+    published: u64,
+    /// The number of events published without any subscriber.
+    unheard: u64,
+    /// The number of events delivered to subscribers.
+    delivered: u64,
+    /// The number of events lagging subscribers skipped.
+    lagged: u64,
+    /// The highest delivery latency since the last report in microseconds.
+    peak_latency_us: u64,
+}
+
+/// Periodic report on the event bus load.
+#[derive(Debug, serde::Serialize)]
+struct BusReport {
+    /// The time the report was taken.
+    t: DateTime<Utc>,
+    /// The statistics of all topics.
+    topics: Vec<TopicStats>,
+}
+
+impl JsonDump for BusReport {
+    /// Returns the file name for the JSON dump of the bus report.
+    fn file_name(&self) -> String { "event_bus".to_string() }
+
+    /// Returns the directory name for the bus report JSON file.
+    fn dir_name(&self) -> &'static str { "runtime" }
+}
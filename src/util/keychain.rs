@@ -1,9 +1,10 @@
 use crate::console_communication::ConsoleMessenger;
-use crate::flight_control::{FlightComputer, Supervisor, orbit::ClosedOrbit};
+use crate::flight_control::{FlightComputer, FuelBudget, Supervisor, orbit::ClosedOrbit};
 use crate::http_handler::http_client::HTTPClient;
 use crate::imaging::CameraController;
-use crate::scheduling::TaskController;
-use crate::objective::{BeaconObjective, KnownImgObjective};
+use crate::scheduling::{SlotManager, TaskController};
+use crate::objective::{BeaconObjective, KnownImgObjective, ScoreLedger};
+use crate::util::MissionConfig;
 use std::sync::Arc;
 use tokio::sync::{RwLock, mpsc::Receiver};
 
@@ -24,23 +25,33 @@ pub struct Keychain {
     t_cont: Arc<TaskController>,
     /// The camera controller for handling camera-related operations.
     c_cont: Arc<CameraController>,
+    /// The ledger estimating the earned challenge score.
+    score: Arc<ScoreLedger>,
+    /// The fuel reservations of planned maneuvers.
+    fuel: Arc<FuelBudget>,
+    /// The booking of the announced communication slots.
+    slots: Arc<SlotManager>,
 }
 
 impl Keychain {
     /// Creates a new instance of [`Keychain`] asynchronously.
     ///
     /// # Arguments
-    /// - `url`: The base URL to initialize the HTTP client.
+    /// - `http_client`: The HTTP client used for all DRS requests.
     ///
     /// # Returns
     /// A new instance of [`Keychain`] containing initialized subsystems.
-    pub async fn new(url: &str) -> (Self, Receiver<KnownImgObjective>, Receiver<BeaconObjective>) {
-        let client = Arc::new(HTTPClient::new(url));
+    pub(crate) async fn new(
+        http_client: HTTPClient,
+    ) -> (Self, Receiver<KnownImgObjective>, Receiver<BeaconObjective>) {
+        let client = Arc::new(http_client);
         let c_cont = Arc::new(CameraController::start(
-            "./".to_string(),
+            MissionConfig::get().imaging.base_path.clone(),
             Arc::clone(&client),
         ));
         let t_cont = Arc::new(TaskController::new());
+        let slots = Arc::new(SlotManager::new(Arc::clone(&client)));
+        let score = Arc::new(ScoreLedger::new());
 
         let f_cont = Arc::new(RwLock::new(FlightComputer::new(Arc::clone(&client)).await));
         let (supervisor, obj_rx, beac_rx) = {
@@ -51,9 +62,20 @@ impl Keychain {
             Arc::clone(&c_cont),
             Arc::clone(&t_cont),
             Arc::clone(&supervisor),
+            Arc::clone(&score),
         ));
         (
-            Self { client, supervisor, con, f_cont, t_cont, c_cont },
+            Self {
+                client,
+                supervisor,
+                con,
+                f_cont,
+                t_cont,
+                c_cont,
+                score,
+                fuel: Arc::new(FuelBudget::new()),
+                slots,
+            },
             obj_rx,
             beac_rx,
         )
@@ -76,6 +98,15 @@ impl Keychain {
 
     /// Provides a cloned reference to the camera controller.
     pub fn c_cont(&self) -> Arc<CameraController> { Arc::clone(&self.c_cont) }
+
+    /// Provides a cloned reference to the score ledger.
+    pub fn score(&self) -> Arc<ScoreLedger> { Arc::clone(&self.score) }
+
+    /// Provides a cloned reference to the fuel budget.
+    pub fn fuel(&self) -> Arc<FuelBudget> { Arc::clone(&self.fuel) }
+
+    /// Provides a cloned reference to the communication slot manager.
+    pub fn slots(&self) -> Arc<SlotManager> { Arc::clone(&self.slots) }
 }
 
 /// Struct representing an enhanced [`Keychain`] that includes a [`ClosedOrbit`].
@@ -95,6 +126,12 @@ pub struct KeychainWithOrbit {
     c_cont: Arc<CameraController>,
     /// The closed orbit object, protected by a read-write lock for thread-safe access.
     c_orbit: Arc<RwLock<ClosedOrbit>>,
+    /// The ledger estimating the earned challenge score.
+    score: Arc<ScoreLedger>,
+    /// The fuel reservations of planned maneuvers.
+    fuel: Arc<FuelBudget>,
+    /// The booking of the announced communication slots.
+    slots: Arc<SlotManager>,
 }
 
 impl KeychainWithOrbit {
@@ -116,6 +153,9 @@ impl KeychainWithOrbit {
             t_cont: keychain.t_cont,
             c_cont: keychain.c_cont,
             c_orbit: Arc::new(RwLock::new(orbit)),
+            score: keychain.score,
+            fuel: keychain.fuel,
+            slots: keychain.slots,
         }
     }
 
@@ -136,4 +176,13 @@ impl KeychainWithOrbit {
 
     /// Provides a cloned reference to the console messenger.
     pub fn con(&self) -> Arc<ConsoleMessenger> { Arc::clone(&self.con) }
+
+    /// Provides a cloned reference to the score ledger.
+    pub fn score(&self) -> Arc<ScoreLedger> { Arc::clone(&self.score) }
+
+    /// Provides a cloned reference to the fuel budget.
+    pub fn fuel(&self) -> Arc<FuelBudget> { Arc::clone(&self.fuel) }
+
+    /// Provides a cloned reference to the communication slot manager.
+    pub fn slots(&self) -> Arc<SlotManager> { Arc::clone(&self.slots) }
 }
@@ -1,33 +1,196 @@
+use super::event_bus::{EVENT_BUS, LogEvent};
 use serde_json::to_string_pretty;
 use std::fs;
 use std::path::Path;
+use std::sync::{
+    Mutex,
+    atomic::{AtomicU8, AtomicU32, Ordering},
+};
+use std::time::{Duration, Instant};
+use strum_macros::Display as StrumDisplay;
 
+/// Severity levels of the console log macros, in ascending order.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    StrumDisplay,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    /// Detailed progress messages printed by `log!`.
+    #[default]
+    Log,
+    /// General information printed by `info!` and `log_burn!`.
+    Info,
+    /// Warnings printed by `warn!`.
+    Warn,
+    /// Errors printed by `error!`.
+    Error,
+}
+
+/// The minimum level of printed log messages.
+static LOG_LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Log as u8);
+/// The minimum level of log messages forwarded to the operator console.
+static CONSOLE_LOG_LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Warn as u8);
+/// The window in seconds in which each log site emits at most [`RATE_BURST`] messages.
+static RATE_WINDOW_S: AtomicU32 = AtomicU32::new(10);
+/// The number of messages each log site may emit per window.
+static RATE_BURST: AtomicU32 = AtomicU32::new(5);
+
+/// Sets the minimum level of printed log messages.
+pub fn set_log_level(level: LogLevel) { LOG_LEVEL.store(level as u8, Ordering::Relaxed); }
+
+/// Sets the minimum level of log messages forwarded to the operator console.
+pub fn set_console_log_level(level: LogLevel) {
+    CONSOLE_LOG_LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+/// Sets the rate limit of every log site.
+///
+/// # Arguments
+/// * `window_s` – The window in seconds, `0` disables rate limiting.
+/// * `burst` – The number of messages a log site may emit per window.
+pub fn set_rate_limit(window_s: u32, burst: u32) {
+    RATE_WINDOW_S.store(window_s, Ordering::Relaxed);
+    RATE_BURST.store(burst, Ordering::Relaxed);
+}
+
+/// Returns `true` if messages of the given level are printed.
+pub fn log_enabled(level: LogLevel) -> bool { level as u8 >= LOG_LEVEL.load(Ordering::Relaxed) }
+
+/// Returns `true` if messages of the given level are forwarded to the operator console.
+fn console_enabled(level: LogLevel) -> bool {
+    level as u8 >= CONSOLE_LOG_LEVEL.load(Ordering::Relaxed)
+}
+
+/// Returns `true` if messages of the given level are printed or forwarded anywhere.
+pub fn log_wanted(level: LogLevel) -> bool { log_enabled(level) || console_enabled(level) }
+
+/// Emits a message admitted by its [`LogSite`], printing it locally and forwarding it to the
+/// operator console according to the configured levels.
+///
+/// # Arguments
+/// * `level` – The severity of the message.
+/// * `prefix` – The colored tag printed in front of the timestamp.
+/// * `suppressed` – The number of messages of the site suppressed since the last one.
+/// * `message` – The formatted message.
+pub fn emit(level: LogLevel, prefix: &str, suppressed: u32, message: String) {
+    let text = match suppressed {
+        0 => message,
+        n => format!("{message} ({n} similar messages suppressed)"),
+    };
+    if log_enabled(level) {
+        println!("{prefix}[{}]\x1b[0m {text}", chrono::Utc::now().format("%H:%M:%S"));
+    }
+    if console_enabled(level) {
+        EVENT_BUS.publish(LogEvent::Message(level, text));
+    }
+}
+
+/// The rate limiting state of a single log macro invocation.
+///
+/// Every leveled log macro declares its own static [`LogSite`], so a loop repeating the same
+/// message can not drown out other messages. A site emits at most `log_rate_burst` messages
+/// per `log_rate_window_s` seconds and counts the messages suppressed beyond that.
+pub struct LogSite {
+    /// The current window of the site.
+    state: Mutex<SiteState>,
+}
+
+/// The current rate limiting window of a [`LogSite`].
+struct SiteState {
+    /// The start of the window, `None` before the first message.
+    start: Option<Instant>,
+    /// The number of messages emitted in the window.
+    emitted: u32,
+    /// The number of messages suppressed since the last emitted one.
+    suppressed: u32,
+}
+
+impl LogSite {
+    /// Creates a new [`LogSite`] without any emitted messages.
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        Self { state: Mutex::new(SiteState { start: None, emitted: 0, suppressed: 0 }) }
+    }
+
+    /// Decides whether the next message of the site is emitted under the configured limit.
+    ///
+    /// # Returns
+    /// The number of previously suppressed messages if the message is emitted, `None` if it
+    /// is suppressed.
+    pub fn admit(&self) -> Option<u32> {
+        let window = Duration::from_secs(u64::from(RATE_WINDOW_S.load(Ordering::Relaxed)));
+        self.admit_at(Instant::now(), window, RATE_BURST.load(Ordering::Relaxed))
+    }
+
+    /// Decides whether a message of the site is emitted at `now`.
+    ///
+    /// # Arguments
+    /// * `now` – The time of the message.
+    /// * `window` – The rate limiting window, zero disables rate limiting.
+    /// * `burst` – The number of messages emitted per window.
+    fn admit_at(&self, now: Instant, window: Duration, burst: u32) -> Option<u32> {
+        let mut state = self.state.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        if state.start.is_none_or(|start| now.duration_since(start) >= window) {
+            state.start = Some(now);
+            state.emitted = 0;
+        }
+        if window.is_zero() || state.emitted < burst {
+            state.emitted += 1;
+            Some(std::mem::take(&mut state.suppressed))
+        } else {
+            state.suppressed += 1;
+            None
+        }
+    }
+}
+
+/// Logs a message at the given level through a rate-limited [`LogSite`] of its own.
 #[macro_export]
-macro_rules! info {
-    ($($arg:tt)*) => {
-        println!("\x1b[32m[INFO] [{}]\x1b[0m {}", chrono::Utc::now().format("%H:%M:%S"), format!($($arg)*))
+macro_rules! log_at {
+    ($level:ident, $prefix:expr, $($arg:tt)*) => {
+        if $crate::util::logger::log_wanted($crate::util::logger::LogLevel::$level) {
+            static SITE: $crate::util::logger::LogSite = $crate::util::logger::LogSite::new();
+            if let Some(suppressed) = SITE.admit() {
+                $crate::util::logger::emit(
+                    $crate::util::logger::LogLevel::$level,
+                    $prefix,
+                    suppressed,
+                    format!($($arg)*),
+                );
+            }
+        }
     };
 }
 
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => { $crate::log_at!(Info, "\x1b[32m[INFO] ", $($arg)*) };
+}
+
 #[macro_export]
 macro_rules! log {
-    ($($arg:tt)*) => {
-        println!("\x1b[33m[LOG]  [{}]\x1b[0m {}", chrono::Utc::now().format("%H:%M:%S"), format!($($arg)*))
-    };
+    ($($arg:tt)*) => { $crate::log_at!(Log, "\x1b[33m[LOG]  ", $($arg)*) };
 }
 
 #[macro_export]
 macro_rules! warn {
-    ($($arg:tt)*) => {
-        println!("\x1b[35m[WARN] [{}]\x1b[0m {}", chrono::Utc::now().format("%H:%M:%S"), format!($($arg)*))
-    };
+    ($($arg:tt)*) => { $crate::log_at!(Warn, "\x1b[35m[WARN] ", $($arg)*) };
 }
 
 #[macro_export]
 macro_rules! error {
-    ($($arg:tt)*) => {
-        println!("\x1b[31m[ERROR][{}]\x1b[0m {}", chrono::Utc::now().format("%H:%M:%S"), format!($($arg)*))
-    };
+    ($($arg:tt)*) => { $crate::log_at!(Error, "\x1b[31m[ERROR]", $($arg)*) };
 }
 
 #[macro_export]
@@ -55,27 +218,50 @@ macro_rules! event {
 
 #[macro_export]
 macro_rules! log_burn {
-    ($($arg:tt)*) => {
-            println!("\x1b[36m[BURN] [{}]\x1b[0m {}", chrono::Utc::now().format("%H:%M:%S"), format!($($arg)*))
-    };
+    ($($arg:tt)*) => { $crate::log_at!(Info, "\x1b[36m[BURN] ", $($arg)*) };
 }
 
 pub trait JsonDump: serde::Serialize {
     fn file_name(&self) -> String;
     fn dir_name(&self) -> &'static str;
-    fn dump_json(&self) {
-        let path_str = format!("./dumps/{}/{}.json", self.dir_name(), self.file_name());
-        let path = Path::new(&path_str);
-
-        if let Ok(json_data) = to_string_pretty(&self) {
-            if let Some(parent) = Path::new(&path).parent() {
-                fs::create_dir_all(parent)
-                    .is_err()
-                    .then(|| warn!("Failed creating directory for JSON file: {parent:?}."));
-            }
-            fs::write(path, json_data)
-                .is_err()
-                .then(|| warn!("Failed writing JSON to file {path:?}."));
-        };
+    fn dump_json(&self) { self.dump_json_to(Path::new("./dumps")); }
+    /// Dumps the JSON file below `base` instead of `./dumps`.
+    fn dump_json_to(&self, base: &Path) {
+        if let Err(e) = self.try_dump_json_to(base) {
+            warn!("Failed dumping JSON file {}/{}.json: {e}.", self.dir_name(), self.file_name());
+        }
+    }
+    /// Dumps the JSON file like [`JsonDump::dump_json`], returning failures to the caller.
+    fn try_dump_json(&self) -> std::io::Result<()> { self.try_dump_json_to(Path::new("./dumps")) }
+    /// Dumps the JSON file like [`JsonDump::dump_json_to`], returning failures to the caller.
+    fn try_dump_json_to(&self, base: &Path) -> std::io::Result<()> {
+        let path = base.join(self.dir_name()).join(format!("{}.json", self.file_name()));
+        let json_data = to_string_pretty(&self)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, json_data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_site_rate_limit() {
+        let site = LogSite::new();
+        let (t0, window) = (Instant::now(), Duration::from_secs(10));
+        assert_eq!(site.admit_at(t0, window, 2), Some(0));
+        assert_eq!(site.admit_at(t0 + Duration::from_secs(1), window, 2), Some(0));
+        assert_eq!(site.admit_at(t0 + Duration::from_secs(2), window, 2), None);
+        assert_eq!(site.admit_at(t0 + Duration::from_secs(3), window, 2), None);
+        // A new window reports the suppressed messages with its first message
+        assert_eq!(site.admit_at(t0 + Duration::from_secs(10), window, 2), Some(2));
+        assert_eq!(site.admit_at(t0 + Duration::from_secs(11), window, 2), Some(0));
+        assert_eq!(site.admit_at(t0 + Duration::from_secs(12), window, 2), None);
+        // A zero window disables rate limiting
+        assert_eq!(site.admit_at(t0 + Duration::from_secs(12), Duration::ZERO, 2), Some(1));
+        assert_eq!(site.admit_at(t0 + Duration::from_secs(12), Duration::ZERO, 2), Some(0));
     }
 }
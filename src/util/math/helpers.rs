@@ -174,3 +174,35 @@ pub fn find_min_y_abs_for_x_range(
     // Return the clamped t_min and the corresponding position
     (t_min_clamped, pos_min)
 }
+
+/// Lookup table of the reflected CRC-32 (IEEE 802.3) polynomial `0xEDB88320`.
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i: u32 = 0;
+    while i < 256 {
+        let mut c = i;
+        let mut bit = 0;
+        while bit < 8 {
+            c = (c >> 1) ^ (0xEDB8_8320 & (c & 1).wrapping_neg());
+            bit += 1;
+        }
+        table[i as usize] = c;
+        i += 1;
+    }
+    table
+};
+
+/// Continues a CRC-32 (IEEE 802.3) checksum over `bytes`.
+///
+/// Starting from `0` and chaining the calls over consecutive slices yields the checksum of
+/// their concatenation.
+///
+/// # Arguments
+/// - `crc`: The checksum of the preceding bytes.
+/// - `bytes`: The bytes to append.
+pub fn crc32_update(crc: u32, bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!crc, |c, b| CRC32_TABLE[((c ^ u32::from(*b)) & 0xFF) as usize] ^ (c >> 8))
+}
+
+/// Computes the CRC-32 (IEEE 802.3) checksum of `bytes`.
+pub fn crc32(bytes: &[u8]) -> u32 { crc32_update(0, bytes) }
@@ -1,4 +1,8 @@
 //! This module provides submodules for helper functionalities.
 
 pub mod helpers;
-pub mod vec2d;
\ No newline at end of file
+pub mod vec2d;
+pub mod zone_rect;
+
+#[cfg(test)]
+mod tests;
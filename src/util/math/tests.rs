@@ -0,0 +1,104 @@
+use super::helpers::{crc32, crc32_update};
+use super::vec2d::{MapSize, Vec2D};
+use fixed::types::I32F32;
+use proptest::prelude::*;
+
+/// Returns a strategy for coordinates up to two map sizes beyond both map edges.
+fn coord(size: u32) -> impl Strategy<Value = I32F32> {
+    let size_fixed = f64::from(size);
+    (-2.0 * size_fixed..3.0 * size_fixed).prop_map(I32F32::from_num)
+}
+
+/// Returns a strategy for positions up to two map sizes beyond the map boundary.
+fn pos() -> impl Strategy<Value = Vec2D<I32F32>> {
+    let size = u32::map_size();
+    (coord(size.x()), coord(size.y())).prop_map(|(x, y)| Vec2D::new(x, y))
+}
+
+/// Returns `true` if the position lies within the map.
+fn on_map(p: Vec2D<I32F32>) -> bool {
+    let size = I32F32::map_size();
+    (I32F32::ZERO..size.x()).contains(&p.x()) && (I32F32::ZERO..size.y()).contains(&p.y())
+}
+
+proptest! {
+    #[test]
+    fn prop_wrap_idempotent(p in pos()) {
+        let wrapped = p.wrap_around_map();
+        prop_assert!(on_map(wrapped), "{p} wrapped to {wrapped}");
+        prop_assert_eq!(wrapped.wrap_around_map(), wrapped);
+    }
+
+    #[test]
+    fn prop_wrap_periodic(p in pos(), kx in -3i32..=3, ky in -3i32..=3) {
+        let size = I32F32::map_size();
+        let (kx_fixed, ky_fixed) = (I32F32::from_num(kx), I32F32::from_num(ky));
+        let shifted = Vec2D::new(p.x() + size.x() * kx_fixed, p.y() + size.y() * ky_fixed);
+        prop_assert_eq!(shifted.wrap_around_map(), p.wrap_around_map());
+    }
+
+    #[test]
+    fn prop_wrap_int_matches_fixed(x in -50_000i32..50_000, y in -50_000i32..50_000) {
+        let wrapped = Vec2D::new(x, y).wrap_around_map();
+        let fixed = Vec2D::new(I32F32::from_num(x), I32F32::from_num(y)).wrap_around_map();
+        prop_assert_eq!(wrapped.x(), fixed.x().to_num::<i32>());
+        prop_assert_eq!(wrapped.y(), fixed.y().to_num::<i32>());
+    }
+
+    #[test]
+    fn prop_wrap_commutes_with_steps(p in pos(), vx in -10.0f64..10.0, vy in -10.0f64..10.0) {
+        let vel = Vec2D::new(I32F32::from_num(vx), I32F32::from_num(vy));
+        let mut unwrapped = p;
+        let mut wrapped = p.wrap_around_map();
+        for _ in 0..100 {
+            unwrapped = unwrapped + vel;
+            wrapped = (wrapped + vel).wrap_around_map();
+        }
+        prop_assert_eq!(unwrapped.wrap_around_map(), wrapped);
+    }
+
+    #[test]
+    fn prop_unwrapped_to_reaches_target(a in pos(), b in pos()) {
+        let to = a.unwrapped_to(&b);
+        prop_assert_eq!((a + to).wrap_around_map(), b.wrap_around_map());
+    }
+
+    #[test]
+    fn prop_unwrapped_to_minimal(a in pos(), b in pos()) {
+        let half = I32F32::map_size() / I32F32::from_num(2);
+        let to = a.unwrapped_to(&b);
+        prop_assert!(to.x().abs() <= half.x() && to.y().abs() <= half.y(), "{a} to {b}: {to}");
+        let size = I32F32::map_size();
+        for other in [
+            Vec2D::new(to.x() + size.x(), to.y()),
+            Vec2D::new(to.x() - size.x(), to.y()),
+            Vec2D::new(to.x(), to.y() + size.y()),
+            Vec2D::new(to.x(), to.y() - size.y()),
+        ] {
+            prop_assert!(to.abs_sq() <= other.abs_sq());
+        }
+    }
+
+    #[test]
+    fn prop_unwrapped_to_symmetric(a in pos(), b in pos()) {
+        let (ab, ba) = (a.unwrapped_to(&b), b.unwrapped_to(&a));
+        prop_assert_eq!(ab.abs_sq(), ba.abs_sq());
+        let half = I32F32::map_size() / I32F32::from_num(2);
+        if ab.x().abs() != half.x() && ab.y().abs() != half.y() {
+            prop_assert_eq!(ab, Vec2D::new(-ba.x(), -ba.y()));
+        }
+    }
+
+    #[test]
+    fn prop_crc32_chains(data in prop::collection::vec(any::<u8>(), 0..512), split in 0..512usize) {
+        let split_at = split.min(data.len());
+        let chained = crc32_update(crc32_update(0, &data[..split_at]), &data[split_at..]);
+        prop_assert_eq!(chained, crc32(&data));
+    }
+}
+
+#[test]
+fn test_crc32_check_value() {
+    assert_eq!(crc32(b""), 0);
+    assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+}
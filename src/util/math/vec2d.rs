@@ -9,6 +9,7 @@ use std::{
     cmp::Ordering,
     fmt::Display,
     ops::{Add, Deref, Div, Mul, Rem, Sub},
+    sync::atomic::{AtomicU32, Ordering as AtomicOrdering},
 };
 use strum_macros::Display;
 
@@ -27,7 +28,7 @@ pub struct Vec2D<T> {
     y: T,
 }
 
-#[derive(Debug, Copy, Clone, Display)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Display)]
 pub enum VecAxis {
     X,
     Y,
@@ -99,9 +100,33 @@ where T: Display
     }
 }
 
+/// The width of the map in px, `21600` unless configured otherwise at startup.
+static MAP_WIDTH: AtomicU32 = AtomicU32::new(21600);
+/// The height of the map in px, `10800` unless configured otherwise at startup.
+static MAP_HEIGHT: AtomicU32 = AtomicU32::new(10800);
+
+/// Configures the dimensions of the map used by all [`MapSize`] implementations.
+///
+/// This must happen at startup before any map buffer or orbit is created, as existing ones
+/// keep the dimensions they were created with.
+///
+/// # Arguments
+/// * `width` - The width of the map in px.
+/// * `height` - The height of the map in px.
+pub fn set_map_size(width: u32, height: u32) {
+    MAP_WIDTH.store(width, AtomicOrdering::Relaxed);
+    MAP_HEIGHT.store(height, AtomicOrdering::Relaxed);
+}
+
+/// Returns the configured map dimensions as `(width, height)`.
+fn map_dims() -> (u32, u32) {
+    (MAP_WIDTH.load(AtomicOrdering::Relaxed), MAP_HEIGHT.load(AtomicOrdering::Relaxed))
+}
+
 /// A trait providing a method to define the size of a 2D map.
 ///
-/// This is used to determine the dimensions of the map for wrapping operations.
+/// This is used to determine the dimensions of the map for wrapping operations. The dimensions
+/// default to 21600 x 10800 and may be changed at startup through [`set_map_size`].
 pub trait MapSize {
     /// The output type of the map dimensions.
     type Output;
@@ -117,12 +142,13 @@ pub trait MapSize {
 impl MapSize for I32F32 {
     type Output = I32F32;
 
-    /// Defines the size of the map as a `Vec2D` with dimensions 21600.0 x 10800.0.
+    /// Returns the configured size of the map.
     ///
     /// # Returns
     /// A `Vec2D` with fixed-point components representing the map dimensions.
     fn map_size() -> Vec2D<I32F32> {
-        Vec2D { x: I32F32::from_num(21600.0), y: I32F32::from_num(10800.0) }
+        let (w, h) = map_dims();
+        Vec2D { x: I32F32::from_num(w), y: I32F32::from_num(h) }
     }
 }
 
@@ -130,55 +156,69 @@ impl MapSize for I32F32 {
 impl MapSize for I96F32 {
     type Output = I96F32;
 
-    /// Defines the size of the map as a `Vec2D` with dimensions 21600.0 x 10800.0.
+    /// Returns the configured size of the map.
     ///
     /// # Returns
     /// A `Vec2D` with fixed-point components representing the map dimensions.
     fn map_size() -> Vec2D<I96F32> {
-        Vec2D { x: I96F32::from_num(21600.0), y: I96F32::from_num(10800.0) }
+        let (w, h) = map_dims();
+        Vec2D { x: I96F32::from_num(w), y: I96F32::from_num(h) }
     }
 }
 
 impl MapSize for f64 {
     type Output = f64;
-    /// Defines the size of the map as a `Vec2D` with dimensions 21600.0 x 10800.0.
+    /// Returns the configured size of the map.
     ///
     /// # Returns
     /// A `Vec2D` with floating-point components representing the map dimensions.
-    fn map_size() -> Vec2D<f64> { Vec2D { x: 21600.0, y: 10800.0 } }
+    fn map_size() -> Vec2D<f64> {
+        let (w, h) = map_dims();
+        Vec2D { x: f64::from(w), y: f64::from(h) }
+    }
 }
 
 /// Implementation of the `MapSize` trait for the `I32F0` fixed-point number type.
 impl MapSize for I32F0 {
     type Output = I32F0;
 
-    /// Defines the size of the map as a `Vec2D` with dimensions 21600 x 10800.
+    /// Returns the configured size of the map.
     ///
     /// # Returns
     /// A `Vec2D` with fixed-point integer components representing the map dimensions.
-    fn map_size() -> Vec2D<I32F0> { Vec2D { x: I32F0::from_num(21600), y: I32F0::from_num(10800) } }
+    fn map_size() -> Vec2D<I32F0> {
+        let (w, h) = map_dims();
+        Vec2D { x: I32F0::from_num(w), y: I32F0::from_num(h) }
+    }
 }
 
 /// Implementation of the `MapSize` trait for the `u32` type.
 impl MapSize for u32 {
     type Output = u32;
 
-    /// Defines the size of the map as a `Vec2D` with dimensions 21600 x 10800.
+    /// Returns the configured size of the map.
     ///
     /// # Returns
     /// A `Vec2D` with unsigned 32-bit integer components representing the map dimensions.
-    fn map_size() -> Vec2D<u32> { Vec2D { x: 21600, y: 10800 } }
+    fn map_size() -> Vec2D<u32> {
+        let (x, y) = map_dims();
+        Vec2D { x, y }
+    }
 }
 
 /// Implementation of the `MapSize` trait for the `i32` type.
 impl MapSize for i32 {
     type Output = i32;
 
-    /// Defines the size of the map as a `Vec2D` with dimensions 21600 x 10800.
+    /// Returns the configured size of the map.
     ///
     /// # Returns
     /// A `Vec2D` with signed 32-bit integer components representing the map dimensions.
-    fn map_size() -> Vec2D<i32> { Vec2D { x: 21600, y: 10800 } }
+    #[allow(clippy::cast_possible_wrap)]
+    fn map_size() -> Vec2D<i32> {
+        let (w, h) = map_dims();
+        Vec2D { x: w as i32, y: h as i32 }
+    }
 }
 
 /// Implementation of the `MapSize` trait for a `Vec2D` type with components
@@ -283,7 +323,7 @@ where T: FixedSigned + NumAssignOps
     ///
     /// This method considers potential wrapping around a 2D map (based on the map size) and calculates
     /// the smallest vector that connects `self` to `other`. The wrapping allows for efficient navigation
-    /// across boundaries.
+    /// across boundaries. Both vectors may lie beyond the map boundary.
     ///
     /// # Arguments
     /// * `other` - The target vector to which the direction is computed.
@@ -311,14 +351,17 @@ where T: FixedSigned + NumAssignOps
     }
 
     fn get_projected_in_range(&self, to: &Self, range: (&[i8], &[i8])) -> Vec<(Self, I64F64)> {
+        let size = Vec2D::new(T::from_num(u32::map_size().x()), T::from_num(u32::map_size().y()));
+        // Positions beyond the map boundary may be more than one map size apart
+        let diff = self.to(to);
+        let direct = Vec2D::new(diff.x % size.x, diff.y % size.y);
         let mut options = Vec::new();
         for x_sign in range.0 {
             for y_sign in range.1 {
-                let target: Vec2D<T> = Vec2D::new(
-                    to.x + T::from_num(u32::map_size().x()) * T::from_num(*x_sign),
-                    to.y + T::from_num(u32::map_size().y()) * T::from_num(*y_sign),
+                let to_target = Vec2D::new(
+                    direct.x + size.x * T::from_num(*x_sign),
+                    direct.y + size.y * T::from_num(*y_sign),
                 );
-                let to_target = self.to(&target);
                 let tt_scale =
                     Vec2D::new(I64F64::from_num(to_target.x), I64F64::from_num(to_target.y));
                 let to_target_abs_sq = tt_scale.abs_sq();
@@ -0,0 +1,122 @@
+use super::vec2d::{MapSize, Vec2D};
+use fixed::types::I32F32;
+
+/// A rectangular objective zone on the toroidal map.
+///
+/// Zones are given by the DRS as `[x_min, y_min, x_max, y_max]` and may extend beyond the map
+/// boundary, in which case they continue on the opposite side of the map. All geometry
+/// is therefore evaluated modulo the map size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(from = "[i32; 4]", into = "[i32; 4]")]
+pub struct ZoneRect {
+    /// The lower x-coordinate.
+    x_min: i32,
+    /// The lower y-coordinate.
+    y_min: i32,
+    /// The upper x-coordinate.
+    x_max: i32,
+    /// The upper y-coordinate.
+    y_max: i32,
+}
+
+impl ZoneRect {
+    /// Creates a new [`ZoneRect`] from its lower and upper corner coordinates.
+    pub const fn new(x_min: i32, y_min: i32, x_max: i32, y_max: i32) -> Self {
+        Self { x_min, y_min, x_max, y_max }
+    }
+
+    /// Creates a new [`ZoneRect`] from its lower corner and its size.
+    pub fn from_offset(offset: Vec2D<i32>, size: Vec2D<i32>) -> Self {
+        Self::new(offset.x(), offset.y(), offset.x() + size.x(), offset.y() + size.y())
+    }
+
+    /// Returns the lower corner of the zone.
+    pub fn offset(&self) -> Vec2D<i32> { Vec2D::new(self.x_min, self.y_min) }
+    /// Returns the upper corner of the zone.
+    pub fn end(&self) -> Vec2D<i32> { Vec2D::new(self.x_max, self.y_max) }
+    /// Returns the width of the zone.
+    pub fn width(&self) -> i32 { self.x_max - self.x_min }
+    /// Returns the height of the zone.
+    pub fn height(&self) -> i32 { self.y_max - self.y_min }
+    /// Returns the size of the zone.
+    pub fn size(&self) -> Vec2D<i32> { Vec2D::new(self.width(), self.height()) }
+    /// Returns the area of the zone.
+    pub fn area(&self) -> i32 { self.width() * self.height() }
+
+    /// Returns the central point of the zone, wrapped onto the map.
+    pub fn center(&self) -> Vec2D<I32F32> {
+        let center = self.offset() + self.size() / 2;
+        Vec2D::new(I32F32::from(center.x()), I32F32::from(center.y())).wrap_around_map()
+    }
+
+    /// Returns the corners of the zone as `[lower-left, upper-left, lower-right, upper-right]`.
+    pub fn corners(&self) -> [Vec2D<I32F32>; 4] {
+        let (x_min, x_max) = (I32F32::from(self.x_min), I32F32::from(self.x_max));
+        let (y_min, y_max) = (I32F32::from(self.y_min), I32F32::from(self.y_max));
+        [
+            Vec2D::new(x_min, y_min),
+            Vec2D::new(x_min, y_max),
+            Vec2D::new(x_max, y_min),
+            Vec2D::new(x_max, y_max),
+        ]
+    }
+
+    /// Checks whether a map position lies within the zone, respecting the map wrap.
+    ///
+    /// # Arguments
+    /// * `pos` – The position to check.
+    pub fn contains(&self, pos: Vec2D<I32F32>) -> bool {
+        let map = Vec2D::<I32F32>::map_size();
+        let dx = (pos.x() - I32F32::from(self.x_min)).rem_euclid(map.x());
+        let dy = (pos.y() - I32F32::from(self.y_min)).rem_euclid(map.y());
+        dx < I32F32::from(self.width()) && dy < I32F32::from(self.height())
+    }
+
+    /// Checks whether two zones overlap, respecting the map wrap.
+    ///
+    /// # Arguments
+    /// * `other` – The other zone.
+    pub fn intersects(&self, other: &Self) -> bool {
+        let map = Vec2D::<i32>::map_size();
+        let overlaps = |a_min: i32, a_len: i32, b_min: i32, b_len: i32, len: i32| {
+            let d = (b_min - a_min).rem_euclid(len);
+            d < a_len || d + b_len > len
+        };
+        overlaps(self.x_min, self.width(), other.x_min, other.width(), map.x())
+            && overlaps(self.y_min, self.height(), other.y_min, other.height(), map.y())
+    }
+}
+
+impl From<[i32; 4]> for ZoneRect {
+    fn from(zone: [i32; 4]) -> Self { Self::new(zone[0], zone[1], zone[2], zone[3]) }
+}
+
+impl From<ZoneRect> for [i32; 4] {
+    fn from(zone: ZoneRect) -> Self { [zone.x_min, zone.y_min, zone.x_max, zone.y_max] }
+}
+
+impl std::fmt::Display for ZoneRect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}, {}, {}, {}]", self.x_min, self.y_min, self.x_max, self.y_max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zone_wrap_geometry() {
+        let wrapping = ZoneRect::new(21400, 10700, 21800, 10900);
+        let pos = |x: i32, y: i32| Vec2D::new(I32F32::from_num(x), I32F32::from_num(y));
+        assert!(wrapping.contains(pos(21500, 10750)));
+        assert!(wrapping.contains(pos(100, 50)));
+        assert!(!wrapping.contains(pos(300, 50)));
+        assert_eq!(wrapping.center(), pos(0, 0));
+
+        assert!(wrapping.intersects(&ZoneRect::new(0, 0, 100, 100)));
+        assert!(ZoneRect::new(0, 0, 100, 100).intersects(&wrapping));
+        assert!(!wrapping.intersects(&ZoneRect::new(300, 0, 400, 100)));
+        assert_eq!(serde_json::to_string(&wrapping).unwrap(), "[21400,10700,21800,10900]");
+    }
+}
@@ -0,0 +1,1199 @@
+use crate::flight_control::{RecoveryAction, orbit::OrbitProfile};
+use crate::imaging::{CameraAngle, ImageCodec, MapBlendMode, ThumbnailMapImage};
+use crate::util::logger::{self, JsonDump, LogLevel};
+use crate::util::Vec2D;
+use crate::util::math::vec2d::set_map_size;
+use crate::{STATIC_ORBIT_VEL, fatal, info, warn};
+use chrono::{DateTime, Utc};
+use fixed::types::I32F32;
+use serde_json::Value;
+use std::{
+    collections::BTreeMap,
+    env,
+    fmt::{Display, Formatter},
+    path::Path,
+    sync::{Arc, LazyLock, RwLock},
+};
+use strum_macros::Display as StrumDisplay;
+
+/// Challenge scoring rules used to estimate the points earned by MELVIN.
+///
+/// The defaults mirror the published challenge rules and can be overridden
+/// through the mission configuration file.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ScoringRules {
+    /// Points for a successfully submitted zoned objective image.
+    pub zo_points: f64,
+    /// Points for a beacon found with the first guess.
+    pub beacon_points: f64,
+    /// Points deducted for every additional beacon guess.
+    pub beacon_guess_penalty: f64,
+    /// Minimum points for a beacon that was found at all.
+    pub beacon_min_points: f64,
+    /// Coverage milestones as `(coverage fraction, points)` tuples in ascending order.
+    pub coverage_milestones: Vec<(f64, f64)>,
+}
+
+impl Default for ScoringRules {
+    fn default() -> Self {
+        Self {
+            zo_points: 100.0,
+            beacon_points: 100.0,
+            beacon_guess_penalty: 10.0,
+            beacon_min_points: 10.0,
+            coverage_milestones: vec![(0.25, 50.0), (0.5, 100.0), (0.75, 150.0), (0.9, 200.0)],
+        }
+    }
+}
+
+impl ScoringRules {
+    /// Returns the estimated points for a beacon found with the given number of guesses.
+    ///
+    /// # Arguments
+    /// * `guesses` – The number of guesses needed, including the successful one.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn beacon_points_for(&self, guesses: usize) -> f64 {
+        let penalty = self.beacon_guess_penalty * guesses.saturating_sub(1) as f64;
+        (self.beacon_points - penalty).max(self.beacon_min_points)
+    }
+}
+
+/// Runtime behavior tunables that may be overridden live from the operator console.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct RuntimeTunables {
+    /// The minimum level of printed log messages.
+    pub log_level: LogLevel,
+    /// The minimum level of log messages forwarded to the operator console.
+    pub console_log_level: LogLevel,
+    /// Window in seconds in which each log statement emits at most `log_rate_burst` messages;
+    /// `0` disables rate limiting.
+    pub log_rate_window_s: u32,
+    /// Messages each log statement may emit per rate limiting window.
+    pub log_rate_burst: u32,
+    /// Lower bound of the interval between two mapping images in seconds.
+    pub img_min_dt_secs: u32,
+    /// Upper bound of the interval between two mapping images in seconds.
+    pub img_max_dt_secs: u32,
+    /// Upper bound in seconds to which the mapping image interval is lengthened while the ground
+    /// track ahead is already covered; values up to the nominal interval disable adaptation.
+    pub img_covered_max_dt_secs: u32,
+    /// Factor by which comms windows are scheduled more often and weighted higher than nominal.
+    pub comms_aggressiveness: f64,
+    /// Additional weight of comms seconds on orbit segments passing directly above candidate
+    /// beacon locations, decreasing to none at the ping range; `0` disables the bias.
+    pub comms_geometry_bias: f64,
+    /// Maximum duration of a mode initialization in seconds before its fallback is taken.
+    pub init_timeout_secs: u32,
+    /// Maximum age in hours of any orbit stripe before the daily map is considered degraded.
+    pub coverage_max_age_h: u32,
+    /// Minimum share of imaged orbit stripes before the daily map is considered degraded.
+    pub coverage_min: f64,
+    /// Hours before the daily map upload at which the coverage is checked.
+    pub coverage_check_lead_h: u32,
+    /// Seconds before a beacon objective ends at which a last-chance centroid guess is
+    /// submitted if its estimate is still wide; `0` disables the guard.
+    pub beacon_fallback_lead_s: u32,
+    /// Error radius in px below which a beacon estimate is submitted before the objective ends.
+    pub beacon_submit_radius: f64,
+    /// Codec of images kept internally or sent to the console.
+    pub internal_img_codec: ImageCodec,
+    /// Codec of objective images uploaded to the DRS; the daily map is always sent as PNG.
+    pub objective_img_codec: ImageCodec,
+    /// Quality of lossy image codecs from `1` to `100`.
+    pub img_lossy_quality: u8,
+    /// Strategy for merging new images into previously imaged map areas. Blending modes other
+    /// than `last_wins` keep pixels that no longer exactly match new images, which weakens the
+    /// small window offset match.
+    pub map_blend_mode: MapBlendMode,
+    /// Seconds without an applied observation before the watchdog triggers a recovery.
+    pub watchdog_stale_obs_s: u32,
+    /// Consecutive failed observation requests before the watchdog triggers a recovery.
+    pub watchdog_max_http_failures: u32,
+    /// Seconds MELVIN may remain in `Deployment`, `Transition` or `Safe` before the watchdog
+    /// triggers a recovery.
+    pub watchdog_state_grace_s: u32,
+    /// Recovery triggered for stale observations.
+    pub watchdog_stale_obs_action: RecoveryAction,
+    /// Recovery triggered for repeated observation request failures.
+    pub watchdog_http_action: RecoveryAction,
+    /// Recovery triggered for unexpected flight states.
+    pub watchdog_state_action: RecoveryAction,
+    /// Seconds after a recovery within which another fault escalates the recovery.
+    pub watchdog_escalation_window_s: u32,
+    /// Minutes between two orbit coverage checkpoints; `0` pauses periodic checkpoints.
+    pub orbit_checkpoint_interval_min: u32,
+    /// Angle in degrees between the velocity and a target within which both exit turn
+    /// directions are evaluated; `0` only evaluates the direction facing the target.
+    pub exit_turn_ambiguity_deg: f64,
+    /// Fuel that is never reserved for planned maneuvers.
+    pub fuel_safety_margin: f64,
+    /// Seconds between two precomputed positions of the orbit and burn planning position
+    /// tables; `0` computes every position directly. Applies to tables built afterwards.
+    pub orbit_table_stride_s: u32,
+    /// Retries of failed polling requests, e.g. observations, objectives and images.
+    pub http_poll_retries: u32,
+    /// Retries of failed satellite control commands.
+    pub http_command_retries: u32,
+    /// Retries of failed image uploads.
+    pub http_upload_retries: u32,
+    /// Delay in milliseconds before the first retry, doubled with every further retry.
+    pub http_retry_base_ms: u32,
+    /// Consecutive failed requests after which the circuit breaker opens.
+    pub http_breaker_threshold: u32,
+    /// Seconds an open circuit breaker rejects requests before letting a probe pass.
+    pub http_breaker_cooldown_s: u32,
+    /// Candidate regions probed per secret objective before its hunt is given up; `0`
+    /// disables the autonomous hunt, leaving secret zones to the operator.
+    pub secret_hunt_max_probes: u32,
+    /// Chunk size of the daily map upload in KiB; `0` uploads the snapshot in one request.
+    pub daily_map_chunk_kib: u32,
+    /// Radius in px of the wide image offset search; `0` only searches the small window.
+    pub offset_search_radius_px: u32,
+    /// Share of differing pixels of the best small window match above which the wide image
+    /// offset search is run.
+    pub offset_match_max_mismatch: f64,
+    /// Zstd level of image payloads sent to consoles speaking protocol version `2`; `0`
+    /// sends them uncompressed.
+    pub console_zstd_level: u32,
+    /// Persistent deviation in px from the closed orbit above which the orbit is replanned
+    /// from the current state; `0` disables replanning.
+    pub orbit_replan_deviation_px: f64,
+    /// Battery level below which thumbnail updates and console thumbnail notifications are
+    /// deferred until the next switch to `Charge`; `0` disables the deferral.
+    pub thumb_defer_batt: f64,
+    /// Share of imaged pixels of a zoned objective mosaic below which its upload is held back
+    /// for an imaging pass on the next flyover; `0` uploads any mosaic.
+    pub zo_min_coverage: f64,
+    /// Further imaging passes on later flyovers of an incomplete zoned objective mosaic before
+    /// the best mosaic imaged so far is uploaded.
+    pub zo_max_extra_passes: u32,
+    /// Minutes before a zoned objective ends within which its exit burn may be prepared with
+    /// the battery reserve lowered to `emergency_batt_floor`; `0` disables emergency retrievals.
+    pub emergency_lead_min: u32,
+    /// Battery level an emergency retrieval plan may never fall below, it must lie below
+    /// `scheduling.min_battery`.
+    pub emergency_batt_floor: f64,
+    /// Lens whose speed limit no velocity command may exceed; `wide` imposes no limit.
+    pub envelope_lens: CameraAngle,
+    /// Whether velocity commands above the speed limit of `envelope_lens` are clamped to it
+    /// instead of being rejected.
+    pub envelope_clamp_speed: bool,
+    /// Fuel that velocity commands and burn sequences may never burn.
+    pub envelope_fuel_floor: f64,
+    /// Upper bound in milliseconds of the measured command latency that state switches and
+    /// burns are fired ahead of their due time; `0` disables the compensation.
+    pub latency_lead_max_ms: u32,
+}
+
+impl Default for RuntimeTunables {
+    fn default() -> Self {
+        Self {
+            log_level: LogLevel::Log,
+            console_log_level: LogLevel::Warn,
+            log_rate_window_s: 10,
+            log_rate_burst: 5,
+            img_min_dt_secs: 1,
+            img_max_dt_secs: 600,
+            img_covered_max_dt_secs: 300,
+            comms_aggressiveness: 1.0,
+            comms_geometry_bias: 1.0,
+            init_timeout_secs: 1800,
+            coverage_max_age_h: 48,
+            coverage_min: 0.5,
+            coverage_check_lead_h: 6,
+            beacon_fallback_lead_s: 300,
+            beacon_submit_radius: 150.0,
+            internal_img_codec: ImageCodec::Png,
+            objective_img_codec: ImageCodec::Png,
+            img_lossy_quality: 85,
+            map_blend_mode: MapBlendMode::LastWins,
+            watchdog_stale_obs_s: 60,
+            watchdog_max_http_failures: 20,
+            watchdog_state_grace_s: 900,
+            watchdog_stale_obs_action: RecoveryAction::ReInit,
+            watchdog_http_action: RecoveryAction::ReInit,
+            watchdog_state_action: RecoveryAction::ForceCharge,
+            watchdog_escalation_window_s: 1800,
+            orbit_checkpoint_interval_min: 5,
+            exit_turn_ambiguity_deg: 10.0,
+            fuel_safety_margin: 5.0,
+            orbit_table_stride_s: 1,
+            http_poll_retries: 2,
+            http_command_retries: 2,
+            http_upload_retries: 1,
+            http_retry_base_ms: 200,
+            http_breaker_threshold: 10,
+            http_breaker_cooldown_s: 5,
+            secret_hunt_max_probes: 0,
+            daily_map_chunk_kib: 0,
+            offset_search_radius_px: 24,
+            offset_match_max_mismatch: 0.25,
+            console_zstd_level: 3,
+            orbit_replan_deviation_px: 50.0,
+            thumb_defer_batt: 15.0,
+            zo_min_coverage: 0.95,
+            zo_max_extra_passes: 2,
+            emergency_lead_min: 60,
+            emergency_batt_floor: 4.0,
+            envelope_lens: CameraAngle::Normal,
+            envelope_clamp_speed: true,
+            envelope_fuel_floor: 1.0,
+            latency_lead_max_ms: 2000,
+        }
+    }
+}
+
+impl RuntimeTunables {
+    /// The valid range of `comms_aggressiveness`.
+    const COMMS_AGGRESSIVENESS_RANGE: (f64, f64) = (0.25, 4.0);
+    /// The maximum `comms_geometry_bias`.
+    const MAX_COMMS_GEOMETRY_BIAS: f64 = 4.0;
+    /// The maximum `orbit_table_stride_s`.
+    const MAX_ORBIT_TABLE_STRIDE_S: u32 = 600;
+    /// The maximum `offset_search_radius_px`.
+    const MAX_OFFSET_SEARCH_RADIUS_PX: u32 = 128;
+    /// The maximum `latency_lead_max_ms`, the timeout of a single request.
+    const MAX_LATENCY_LEAD_MS: u32 = 5000;
+
+    /// Checks the tunables for consistency.
+    fn validate(&self) -> Result<(), String> {
+        let (min_aggr, max_aggr) = Self::COMMS_AGGRESSIVENESS_RANGE;
+        if self.img_min_dt_secs == 0 || self.img_min_dt_secs > self.img_max_dt_secs {
+            Err("imaging cadence bounds must satisfy 0 < min <= max".to_string())
+        } else if !(min_aggr..=max_aggr).contains(&self.comms_aggressiveness) {
+            Err(format!("comms aggressiveness must be within [{min_aggr}, {max_aggr}]"))
+        } else if !(0.0..=Self::MAX_COMMS_GEOMETRY_BIAS).contains(&self.comms_geometry_bias) {
+            Err(format!(
+                "comms geometry bias must be within [0, {}]",
+                Self::MAX_COMMS_GEOMETRY_BIAS
+            ))
+        } else if self.init_timeout_secs == 0 {
+            Err("mode init timeout must be positive".to_string())
+        } else if self.coverage_max_age_h == 0 || !(0.0..=1.0).contains(&self.coverage_min) {
+            Err("coverage criteria must satisfy max age > 0 and 0 <= min <= 1".to_string())
+        } else if !(1..=22).contains(&self.coverage_check_lead_h) {
+            Err("coverage check lead must be within [1, 22] hours".to_string())
+        } else if self.watchdog_stale_obs_s == 0
+            || self.watchdog_max_http_failures == 0
+            || self.watchdog_state_grace_s == 0
+        {
+            Err("watchdog thresholds must be positive".to_string())
+        } else if !(0.0..=90.0).contains(&self.exit_turn_ambiguity_deg) {
+            Err("exit turn ambiguity must be within [0, 90] degrees".to_string())
+        } else if !(1..=100).contains(&self.img_lossy_quality) {
+            Err("lossy image quality must be within [1, 100]".to_string())
+        } else if !(0.0..100.0).contains(&self.fuel_safety_margin) {
+            Err("fuel safety margin must be within [0, 100)".to_string())
+        } else if self.orbit_table_stride_s > Self::MAX_ORBIT_TABLE_STRIDE_S {
+            Err(format!(
+                "orbit table stride must not exceed {} seconds",
+                Self::MAX_ORBIT_TABLE_STRIDE_S
+            ))
+        } else if self.beacon_submit_radius.is_nan() || self.beacon_submit_radius < 0.0 {
+            Err("beacon submit radius must not be negative".to_string())
+        } else if self.http_breaker_threshold == 0 {
+            Err("circuit breaker threshold must be at least 1".to_string())
+        } else if self.latency_lead_max_ms > Self::MAX_LATENCY_LEAD_MS {
+            Err(format!(
+                "latency lead must not exceed {} milliseconds",
+                Self::MAX_LATENCY_LEAD_MS
+            ))
+        } else if self.offset_search_radius_px > Self::MAX_OFFSET_SEARCH_RADIUS_PX {
+            Err(format!(
+                "image offset search radius must not exceed {} px",
+                Self::MAX_OFFSET_SEARCH_RADIUS_PX
+            ))
+        } else if !(0.0..=1.0).contains(&self.offset_match_max_mismatch) {
+            Err("image offset mismatch threshold must be within [0, 1]".to_string())
+        } else if self.console_zstd_level > 22 {
+            Err("console zstd level must be within [0, 22]".to_string())
+        } else if self.orbit_replan_deviation_px.is_nan() || self.orbit_replan_deviation_px < 0.0 {
+            Err("orbit replan deviation must not be negative".to_string())
+        } else if !(0.0..=100.0).contains(&self.thumb_defer_batt) {
+            Err("thumbnail deferral battery level must be within [0, 100]".to_string())
+        } else if !(0.0..=1.0).contains(&self.zo_min_coverage) {
+            Err("zoned objective minimum coverage must be within [0, 1]".to_string())
+        } else if self.emergency_batt_floor.is_nan() || self.emergency_batt_floor <= 0.0 {
+            Err("emergency battery floor must be positive".to_string())
+        } else if !(0.0..100.0).contains(&self.envelope_fuel_floor) {
+            Err("envelope fuel floor must be within [0, 100)".to_string())
+        } else if self.log_rate_burst == 0 {
+            Err("log rate burst must be at least 1".to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Applies the log levels and the log rate limit to the logger.
+    fn apply_logging(&self) {
+        logger::set_log_level(self.log_level);
+        logger::set_console_log_level(self.console_log_level);
+        logger::set_rate_limit(self.log_rate_window_s, self.log_rate_burst);
+    }
+}
+
+/// Sizing of the async runtime and the dedicated worker pools.
+///
+/// These values are applied once when the runtime is built and can therefore not be
+/// overridden live. A value of `0` derives the size from the available cores.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ThreadConfig {
+    /// The number of async worker threads.
+    pub worker_threads: usize,
+    /// The maximum number of threads in the blocking pool.
+    pub max_blocking_threads: usize,
+    /// The maximum number of concurrent jobs in the imaging pool.
+    pub imaging_jobs: usize,
+    /// The maximum number of concurrent jobs in the planning pool.
+    pub planning_jobs: usize,
+}
+
+impl Default for ThreadConfig {
+    fn default() -> Self {
+        Self { worker_threads: 0, max_blocking_threads: 512, imaging_jobs: 0, planning_jobs: 0 }
+    }
+}
+
+impl ThreadConfig {
+    /// The bounds of the automatically detected number of worker threads.
+    const AUTO_WORKER_RANGE: (usize, usize) = (2, 16);
+    /// The maximum number of explicitly configured worker threads.
+    const MAX_WORKER_THREADS: usize = 256;
+
+    /// Returns the number of async worker threads, detecting the available cores if unset.
+    pub fn resolved_workers(&self) -> usize {
+        if self.worker_threads > 0 {
+            return self.worker_threads;
+        }
+        let (min, max) = Self::AUTO_WORKER_RANGE;
+        std::thread::available_parallelism().map_or(min, std::num::NonZero::get).clamp(min, max)
+    }
+
+    /// Returns the number of concurrent imaging jobs, defaulting to half the worker threads.
+    pub fn resolved_imaging_jobs(&self) -> usize {
+        if self.imaging_jobs > 0 { self.imaging_jobs } else { (self.resolved_workers() / 2).max(1) }
+    }
+
+    /// Returns the number of concurrent planning jobs, defaulting to a quarter of the worker
+    /// threads, as every dynamic program holds a large decision table.
+    pub fn resolved_planning_jobs(&self) -> usize {
+        if self.planning_jobs > 0 {
+            self.planning_jobs
+        } else {
+            (self.resolved_workers() / 4).max(1)
+        }
+    }
+
+    /// Checks the sizing for consistency.
+    fn validate(&self) -> Result<(), String> {
+        if self.worker_threads > Self::MAX_WORKER_THREADS {
+            Err(format!("worker threads must not exceed {}", Self::MAX_WORKER_THREADS))
+        } else if self.max_blocking_threads == 0 {
+            Err("blocking pool must hold at least one thread".to_string())
+        } else if self.imaging_jobs + self.planning_jobs > self.resolved_workers() * 2 {
+            Err("dedicated pools must not exceed twice the worker threads".to_string())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Dimensions of the simulated world map.
+///
+/// These values are applied once at startup, before any map buffer or orbit is created, and can
+/// therefore not be overridden live. They default to the challenge map of 21600 x 10800 px.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct WorldConfig {
+    /// The width of the map in px.
+    pub map_width: u32,
+    /// The height of the map in px.
+    pub map_height: u32,
+}
+
+impl Default for WorldConfig {
+    fn default() -> Self { Self { map_width: 21600, map_height: 10800 } }
+}
+
+impl WorldConfig {
+    /// The maximum width and height of the map in px.
+    const MAX_MAP_DIM: u32 = 1 << 16;
+
+    /// Checks the map dimensions for consistency.
+    fn validate(&self) -> Result<(), String> {
+        let scale = ThumbnailMapImage::THUMBNAIL_SCALE_FACTOR;
+        let dims = [self.map_width, self.map_height];
+        if dims.iter().any(|d| *d == 0 || *d > Self::MAX_MAP_DIM) {
+            Err(format!("map dimensions must be between 1 and {}", Self::MAX_MAP_DIM))
+        } else if dims.iter().any(|d| d % scale != 0) {
+            Err(format!("map dimensions must be multiples of the thumbnail scale {scale}"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Orbit injection profiles and their selection per mission phase.
+///
+/// These values are applied when an orbit is entered and can therefore not be overridden live.
+/// The closure and overlap of a selected profile are checked before its velocity is committed.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct OrbitConfig {
+    /// All available orbit profiles, the file replaces the whole list.
+    pub profiles: Vec<OrbitProfile>,
+    /// The profile used to create a new closed orbit at startup.
+    pub init_profile: String,
+    /// The profile the orbit return switches to; empty returns to the current closed orbit.
+    pub return_profile: String,
+    /// Whether a previously exported closed orbit is imported at startup.
+    pub try_import: bool,
+    /// Whether the closed orbit is exported to disk.
+    pub export: bool,
+    /// Whether featureless orbit seconds are not re-imaged once the orbit is fully done.
+    pub skip_featureless: bool,
+}
+
+impl Default for OrbitConfig {
+    fn default() -> Self {
+        let (vel_x, vel_y) = STATIC_ORBIT_VEL;
+        Self {
+            profiles: vec![OrbitProfile {
+                name: Self::DEFAULT_PROFILE.to_string(),
+                vel: (vel_x.to_num(), vel_y.to_num()),
+                lens: CameraAngle::Wide,
+                min_overlap: 0.25,
+            }],
+            init_profile: Self::DEFAULT_PROFILE.to_string(),
+            return_profile: String::new(),
+            try_import: false,
+            export: false,
+            skip_featureless: false,
+        }
+    }
+}
+
+impl OrbitConfig {
+    /// The name of the built-in static orbit profile.
+    const DEFAULT_PROFILE: &'static str = "static";
+
+    /// Returns the profile with the given name, if any.
+    ///
+    /// # Arguments
+    /// * `name` – The name of the profile.
+    pub fn profile(&self, name: &str) -> Option<&OrbitProfile> {
+        self.profiles.iter().find(|p| p.name == name)
+    }
+
+    /// Returns the profile used to create a new closed orbit at startup.
+    pub fn init_profile(&self) -> Option<&OrbitProfile> { self.profile(&self.init_profile) }
+
+    /// Returns the profile the orbit return switches to, if any.
+    pub fn return_profile(&self) -> Option<&OrbitProfile> { self.profile(&self.return_profile) }
+
+    /// Returns the profile a closed orbit is flown with, i.e. the first profile matching its
+    /// velocity, or the init profile if no profile matches.
+    ///
+    /// # Arguments
+    /// * `orbit_vel` – The velocity of the closed orbit.
+    pub fn active_profile(&self, orbit_vel: Vec2D<I32F32>) -> Option<&OrbitProfile> {
+        self.profiles.iter().find(|p| p.matches(orbit_vel)).or_else(|| self.init_profile())
+    }
+
+    /// Checks the profiles and their selection for consistency.
+    fn validate(&self) -> Result<(), String> {
+        for (i, profile) in self.profiles.iter().enumerate() {
+            profile.validate()?;
+            if self.profiles[..i].iter().any(|p| p.name == profile.name) {
+                return Err(format!("orbit profile {} is defined twice", profile.name));
+            }
+        }
+        if self.init_profile().is_none() {
+            Err(format!("unknown init orbit profile {}", self.init_profile))
+        } else if !self.return_profile.is_empty() && self.return_profile().is_none() {
+            Err(format!("unknown return orbit profile {}", self.return_profile))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Connection to the DRS backend.
+///
+/// These values are applied once at startup and can therefore not be overridden live.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct NetworkConfig {
+    /// The base URL of the DRS backend.
+    pub base_url: String,
+    /// Whether the initial reset of the satellite is skipped. Required to resume the plan of the
+    /// mode persisted before a restart, since a reset moves MELVIN away from it.
+    pub skip_reset: bool,
+    /// The session file all DRS requests of a live run are recorded to; empty disables
+    /// recording.
+    pub record_session: String,
+    /// The rate of MELVINs network link in KiB/s, shared by image downloads and console traffic.
+    pub link_rate_kibps: f64,
+    /// The port of the HTTP endpoint serving telemetry and metrics.
+    pub telemetry_port: u16,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            base_url: "http://localhost:33000".to_string(),
+            skip_reset: false,
+            record_session: String::new(),
+            link_rate_kibps: 8192.0,
+            telemetry_port: 1338,
+        }
+    }
+}
+
+impl NetworkConfig {
+    /// Checks the backend URL for a supported scheme and the link rate for a positive value.
+    fn validate(&self) -> Result<(), String> {
+        if !self.base_url.starts_with("http://") && !self.base_url.starts_with("https://") {
+            Err(format!("network.base_url '{}' is no http(s) URL", self.base_url))
+        } else if self.link_rate_kibps.is_nan() || self.link_rate_kibps <= 0.0 {
+            Err("network.link_rate_kibps must be positive".to_string())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Battery thresholds bounding every planned schedule and the end of the mission window.
+///
+/// These values are applied once when the first schedule is planned and can therefore not be
+/// overridden live.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct SchedulingConfig {
+    /// The battery level in percent schedules never fall below.
+    pub min_battery: f64,
+    /// The battery level in percent schedules never charge beyond.
+    pub max_battery: f64,
+    /// The end of the mission window as an RFC 3339 timestamp, ahead of which the final
+    /// dataset is exported; unset if the window end is unknown.
+    pub mission_end: Option<DateTime<Utc>>,
+}
+
+impl Default for SchedulingConfig {
+    fn default() -> Self { Self { min_battery: 10.0, max_battery: 90.0, mission_end: None } }
+}
+
+impl SchedulingConfig {
+    /// The minimum usable battery range in percent, leaving room for a burn and its charge.
+    const MIN_BATTERY_SPAN: f64 = 40.0;
+
+    /// Checks the battery thresholds for consistency.
+    fn validate(&self) -> Result<(), String> {
+        if !self.min_battery.is_finite() || !self.max_battery.is_finite() {
+            Err("scheduling battery thresholds must be finite".to_string())
+        } else if self.min_battery < 0.0 || self.max_battery > 100.0 {
+            Err("scheduling battery thresholds must be between 0 and 100".to_string())
+        } else if self.max_battery - self.min_battery < Self::MIN_BATTERY_SPAN {
+            Err(format!(
+                "scheduling.max_battery must exceed scheduling.min_battery by at least {}",
+                Self::MIN_BATTERY_SPAN
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Storage locations and disk limits of the imaging pipeline.
+///
+/// These values are applied once at startup and can therefore not be overridden live.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ImagingConfig {
+    /// The directory holding the map buffer, the snapshots and the pending uploads.
+    pub base_path: String,
+    /// The disk budget of the exported objective images in MiB.
+    pub zo_img_budget_mib: u64,
+    /// The free disk space in MiB below which objective images are garbage collected.
+    pub min_free_disk_mib: u64,
+}
+
+impl Default for ImagingConfig {
+    fn default() -> Self {
+        Self { base_path: "./".to_string(), zo_img_budget_mib: 512, min_free_disk_mib: 1024 }
+    }
+}
+
+impl ImagingConfig {
+    /// The number of bytes in a MiB.
+    const MIB: u64 = 1 << 20;
+
+    /// Returns the disk budget of the exported objective images in bytes.
+    pub fn zo_img_budget(&self) -> u64 { self.zo_img_budget_mib * Self::MIB }
+
+    /// Returns the free disk space in bytes below which objective images are garbage collected.
+    pub fn min_free_disk(&self) -> u64 { self.min_free_disk_mib * Self::MIB }
+
+    /// Checks that the storage directory and the objective image budget are set.
+    fn validate(&self) -> Result<(), String> {
+        if self.base_path.is_empty() {
+            Err("imaging.base_path must not be empty".to_string())
+        } else if self.zo_img_budget_mib == 0 || self.zo_img_budget_mib > u64::MAX / Self::MIB {
+            Err("imaging.zo_img_budget_mib must be a positive number of MiB".to_string())
+        } else if self.min_free_disk_mib > u64::MAX / Self::MIB {
+            Err("imaging.min_free_disk_mib is out of range".to_string())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Mission-wide configuration.
+///
+/// Every value is taken from, in ascending priority, its default, the configuration file and
+/// an environment variable named after its key (e.g. `MELVIN_RUNTIME_LOG_LEVEL` for
+/// `runtime.log_level`). The file is given by `MISSION_CONFIG` and defaults to `melvin.toml`,
+/// files ending in `.toml` are read as TOML, all others as JSON. The log levels, imaging cadence
+/// bounds and comms aggressiveness may additionally be overridden live, with every change being
+/// journaled and revertible.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct MissionConfig {
+    /// The challenge scoring rules.
+    pub scoring: ScoringRules,
+    /// Runtime behavior tunables.
+    pub runtime: RuntimeTunables,
+    /// Sizing of the async runtime and the worker pools.
+    pub threads: ThreadConfig,
+    /// Dimensions of the world map.
+    pub world: WorldConfig,
+    /// Orbit injection profiles and orbit persistence.
+    pub orbit: OrbitConfig,
+    /// Connection to the DRS backend.
+    pub network: NetworkConfig,
+    /// Battery thresholds of the scheduler.
+    pub scheduling: SchedulingConfig,
+    /// Storage locations of the imaging pipeline.
+    pub imaging: ImagingConfig,
+}
+
+/// The origin of an effective configuration value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, StrumDisplay, serde::Serialize)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigSource {
+    /// The built-in default.
+    Default,
+    /// The mission configuration file.
+    File,
+    /// An environment variable.
+    Env,
+    /// A live override.
+    Override,
+}
+
+/// A single effective configuration value.
+#[derive(Debug, Clone)]
+pub struct ConfigEntry {
+    /// The dotted key of the value, e.g. `runtime.log_level`.
+    pub key: String,
+    /// The JSON representation of the value.
+    pub value: String,
+    /// The origin of the value.
+    pub source: ConfigSource,
+    /// Whether the value may be overridden live.
+    pub overridable: bool,
+}
+
+/// A journaled live change of a configuration value.
+#[derive(Debug, Clone, serde::Serialize)]
+struct ConfigChange {
+    /// The time of the change.
+    t: DateTime<Utc>,
+    /// The dotted key of the changed value.
+    key: String,
+    /// The value before the change.
+    old: Value,
+    /// The value after the change.
+    new: Value,
+    /// The origin of the value before the change.
+    old_source: ConfigSource,
+}
+
+/// Errors that may occur when overriding configuration values.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The key does not exist or is not whitelisted for live overrides.
+    NotOverridable(String),
+    /// The resulting configuration is invalid.
+    Invalid(String),
+    /// The journal holds no change to revert.
+    NothingToRevert,
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotOverridable(key) => write!(f, "'{key}' can not be overridden"),
+            Self::Invalid(reason) => write!(f, "invalid configuration: {reason}"),
+            Self::NothingToRevert => write!(f, "no override to revert"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// The effective configuration together with the origin of each value and the override journal.
+#[derive(Debug, serde::Serialize)]
+struct ConfigStore {
+    /// The effective configuration, replaced as a whole on every override.
+    config: Arc<MissionConfig>,
+    /// The origin of every leaf value by dotted key.
+    sources: BTreeMap<String, ConfigSource>,
+    /// All live changes in chronological order.
+    journal: Vec<ConfigChange>,
+    /// The reason the configured values were rejected at startup, if they were.
+    #[serde(skip)]
+    load_error: Option<String>,
+}
+
+impl JsonDump for ConfigStore {
+    /// Returns the file name for the JSON dump of the configuration.
+    fn file_name(&self) -> String { "mission_config".to_string() }
+
+    /// Returns the directory name for the configuration JSON files.
+    fn dir_name(&self) -> &'static str { "config" }
+}
+
+impl ConfigStore {
+    /// Overrides a whitelisted value, see [`MissionConfig::set_override`].
+    fn set_override(&mut self, key: &str, raw: &str) -> Result<(), ConfigError> {
+        if !MissionConfig::OVERRIDABLE.contains(&key) {
+            return Err(ConfigError::NotOverridable(key.to_string()));
+        }
+        let new = MissionConfig::parse_raw(raw);
+        let old = MissionConfig::replace_value(Arc::make_mut(&mut self.config), key, new.clone())?;
+        let old_source = self
+            .sources
+            .insert(key.to_string(), ConfigSource::Override)
+            .unwrap_or(ConfigSource::Default);
+        warn!("Config override: {key} changed from {old} to {new}.");
+        let change = ConfigChange { t: Utc::now(), key: key.to_string(), old, new, old_source };
+        self.journal.push(change);
+        Ok(())
+    }
+
+    /// Reverts the most recent live override, see [`MissionConfig::revert_last`].
+    fn revert_last(&mut self) -> Result<String, ConfigError> {
+        let change = self.journal.pop().ok_or(ConfigError::NothingToRevert)?;
+        let config = Arc::make_mut(&mut self.config);
+        MissionConfig::replace_value(config, &change.key, change.old.clone())?;
+        self.sources.insert(change.key.clone(), change.old_source);
+        warn!("Config override reverted: {} is {} again.", change.key, change.old);
+        Ok(change.key)
+    }
+
+    /// Returns all effective values with their origin, see [`MissionConfig::describe`].
+    fn describe(&self) -> Vec<ConfigEntry> {
+        let value = serde_json::to_value(&self.config).unwrap_or(Value::Null);
+        MissionConfig::leaves(&value, "")
+            .into_iter()
+            .map(|(key, v)| ConfigEntry {
+                source: self.sources.get(&key).copied().unwrap_or(ConfigSource::Default),
+                overridable: MissionConfig::OVERRIDABLE.contains(&key.as_str()),
+                value: v.to_string(),
+                key,
+            })
+            .collect()
+    }
+}
+
+/// The lazily loaded mission configuration.
+static MISSION_CONFIG: LazyLock<RwLock<ConfigStore>> =
+    LazyLock::new(|| RwLock::new(MissionConfig::load()));
+
+impl MissionConfig {
+    /// ENV Var holding the path to the mission configuration file
+    const CONFIG_PATH_ENV: &'static str = "MISSION_CONFIG";
+    /// Prefix of the ENV Vars overriding single configuration values.
+    const VALUE_ENV_PREFIX: &'static str = "MELVIN_";
+    /// Keys of the values that may be overridden live. Safety envelope, emergency, watchdog and
+    /// HTTP resilience values are deliberately absent.
+    const OVERRIDABLE: &'static [&'static str] = &[
+        "runtime.log_level",
+        "runtime.console_log_level",
+        "runtime.img_min_dt_secs",
+        "runtime.img_max_dt_secs",
+        "runtime.img_covered_max_dt_secs",
+        "runtime.comms_aggressiveness",
+    ];
+    /// The configuration file used if `MISSION_CONFIG` is not set.
+    const DEFAULT_CONFIG_PATH: &'static str = "melvin.toml";
+    /// Legacy ENV Vars and the keys they still set, superseded by `MELVIN_` variables.
+    const LEGACY_ENV: [(&'static str, &'static str); 9] = [
+        ("DRS_BASE_URL", "network.base_url"),
+        ("SKIP_RESET", "network.skip_reset"),
+        ("DRS_RECORD_SESSION", "network.record_session"),
+        ("LINK_RATE_KIBPS", "network.link_rate_kibps"),
+        ("TELEMETRY_PORT", "network.telemetry_port"),
+        ("TRY_IMPORT_ORBIT", "orbit.try_import"),
+        ("EXPORT_ORBIT", "orbit.export"),
+        ("SKIP_FEATURELESS", "orbit.skip_featureless"),
+        ("MISSION_END", "scheduling.mission_end"),
+    ];
+
+    /// Loads the global mission configuration, applies the world map dimensions and logs the
+    /// origin of its values.
+    ///
+    /// An invalid configuration file or environment variable aborts the startup, naming the
+    /// offending value.
+    pub fn init() {
+        let store = MISSION_CONFIG.read().unwrap();
+        if let Some(e) = &store.load_error {
+            fatal!("Invalid mission config: {e}");
+        }
+        let world = &store.config.world;
+        set_map_size(world.map_width, world.map_height);
+        let count = |src| store.sources.values().filter(|s| **s == src).count();
+        info!(
+            "Loaded mission config: {} values from file, {} from env.",
+            count(ConfigSource::File),
+            count(ConfigSource::Env)
+        );
+        if world.map_width != WorldConfig::default().map_width
+            || world.map_height != WorldConfig::default().map_height
+        {
+            info!("Using a map size of {}x{}.", world.map_width, world.map_height);
+        }
+    }
+
+    /// Returns a snapshot of the effective mission configuration.
+    ///
+    /// The snapshot is shared and cheap to obtain, but does not reflect later overrides. Read
+    /// it once per operation rather than once per loop iteration.
+    pub fn get() -> Arc<MissionConfig> { Arc::clone(&MISSION_CONFIG.read().unwrap().config) }
+
+    /// Returns all effective configuration values together with their origin.
+    pub fn describe() -> Vec<ConfigEntry> { MISSION_CONFIG.read().unwrap().describe() }
+
+    /// Overrides a whitelisted configuration value at runtime.
+    ///
+    /// # Arguments
+    /// * `key` – The dotted key of the value, e.g. `runtime.log_level`.
+    /// * `raw` – The new value as JSON; plain strings may be given without quotes.
+    ///
+    /// # Returns
+    /// * `Err(ConfigError)` if the key is not whitelisted or the new value is invalid.
+    pub fn set_override(key: &str, raw: &str) -> Result<(), ConfigError> {
+        let mut store = MISSION_CONFIG.write().unwrap();
+        store.set_override(key, raw)?;
+        store.config.runtime.apply_logging();
+        store.dump_json();
+        Ok(())
+    }
+
+    /// Reverts the most recent live override.
+    ///
+    /// # Returns
+    /// * The dotted key of the reverted value, or `ConfigError::NothingToRevert`.
+    pub fn revert_last() -> Result<String, ConfigError> {
+        let mut store = MISSION_CONFIG.write().unwrap();
+        let key = store.revert_last()?;
+        store.config.runtime.apply_logging();
+        store.dump_json();
+        Ok(key)
+    }
+
+    /// Replaces a single value of a configuration and validates the result.
+    ///
+    /// # Returns
+    /// * The replaced value, or an error leaving `config` untouched.
+    fn replace_value(config: &mut Self, key: &str, new: Value) -> Result<Value, ConfigError> {
+        let mut value =
+            serde_json::to_value(&*config).map_err(|e| ConfigError::Invalid(e.to_string()))?;
+        let slot = value
+            .pointer_mut(&Self::pointer(key))
+            .filter(|slot| !slot.is_object())
+            .ok_or_else(|| ConfigError::NotOverridable(key.to_string()))?;
+        let old = std::mem::replace(slot, new);
+        let updated: Self =
+            serde_json::from_value(value).map_err(|e| ConfigError::Invalid(e.to_string()))?;
+        updated.validate().map_err(ConfigError::Invalid)?;
+        *config = updated;
+        Ok(old)
+    }
+
+    /// Checks all validated sections of the configuration.
+    fn validate(&self) -> Result<(), String> {
+        self.runtime.validate()?;
+        self.threads.validate()?;
+        self.world.validate()?;
+        self.orbit.validate()?;
+        self.network.validate()?;
+        self.scheduling.validate()?;
+        self.imaging.validate()?;
+        if self.runtime.emergency_batt_floor >= self.scheduling.min_battery {
+            return Err("emergency floor must lie below scheduling.min_battery".to_string());
+        }
+        let lens = self.runtime.envelope_lens;
+        match self.orbit.profiles.iter().find(|p| p.vel().abs() > lens.get_max_speed()) {
+            Some(p) => {
+                Err(format!("orbit profile {} exceeds the speed of the {lens} lens", p.name))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Loads the mission configuration from defaults, file and environment.
+    fn load() -> ConfigStore {
+        let mut value = serde_json::to_value(Self::default()).unwrap_or(Value::Null);
+        let mut sources: BTreeMap<String, ConfigSource> = Self::leaves(&value, "")
+            .into_iter()
+            .map(|(key, _)| (key, ConfigSource::Default))
+            .collect();
+        let file = Self::read_file();
+        let file_error = file.as_ref().err().cloned();
+        for (key, file_val) in file.ok().flatten().map(|f| Self::leaves(&f, "")).unwrap_or_default()
+        {
+            if let Some(slot) = value.pointer_mut(&Self::pointer(&key)) {
+                *slot = file_val;
+                sources.insert(key, ConfigSource::File);
+            } else {
+                warn!("Unknown mission config key {key}. Ignoring.");
+            }
+        }
+        for (var, key) in Self::LEGACY_ENV {
+            if let (Ok(raw), Some(slot)) = (env::var(var), value.pointer_mut(&Self::pointer(key))) {
+                let var_key = key.replace('.', "_").to_uppercase();
+                warn!("{var} is deprecated, use {}{var_key}.", Self::VALUE_ENV_PREFIX);
+                *slot = match slot {
+                    Value::Bool(_) => Value::Bool(raw == "1"),
+                    Value::Number(_) => Self::parse_raw(&raw),
+                    _ => Value::String(raw),
+                };
+                sources.insert(key.to_string(), ConfigSource::Env);
+            }
+        }
+        for (key, source) in &mut sources {
+            let var = format!("{}{}", Self::VALUE_ENV_PREFIX, key.replace('.', "_").to_uppercase());
+            if let (Ok(raw), Some(slot)) = (env::var(var), value.pointer_mut(&Self::pointer(key))) {
+                *slot = Self::parse_raw(&raw);
+                *source = ConfigSource::Env;
+            }
+        }
+        let loaded = file_error.map_or(Ok(()), Err).and_then(|()| {
+            let config = serde_json::from_value::<Self>(value).map_err(|e| e.to_string())?;
+            config.validate().map(|()| config)
+        });
+        match loaded {
+            Ok(valid) => {
+                valid.runtime.apply_logging();
+                let config = Arc::new(valid);
+                ConfigStore { config, sources, journal: Vec::new(), load_error: None }
+            }
+            Err(e) => {
+                let defaults = sources.into_keys().map(|k| (k, ConfigSource::Default)).collect();
+                let config = Arc::new(Self::default());
+                ConfigStore { config, sources: defaults, journal: Vec::new(), load_error: Some(e) }
+            }
+        }
+    }
+
+    /// Reads the mission configuration file given by `MISSION_CONFIG` or, if unset, the
+    /// default file if it exists.
+    ///
+    /// # Returns
+    /// * `Ok(None)` if no file is configured, or an error naming the file.
+    fn read_file() -> Result<Option<Value>, String> {
+        let path = match env::var(Self::CONFIG_PATH_ENV) {
+            Ok(path) => path,
+            Err(_) if Path::new(Self::DEFAULT_CONFIG_PATH).exists() => {
+                Self::DEFAULT_CONFIG_PATH.to_string()
+            }
+            Err(_) => return Ok(None),
+        };
+        let raw =
+            std::fs::read_to_string(&path).map_err(|e| format!("could not read {path}: {e}"))?;
+        Self::parse_file(&path, &raw).map(Some).map_err(|e| format!("{path}: {e}"))
+    }
+
+    /// Parses the content of a configuration file, as TOML if `path` ends in `.toml` and as
+    /// JSON otherwise.
+    fn parse_file(path: &str, raw: &str) -> Result<Value, String> {
+        if Path::new(path).extension().is_some_and(|ext| ext == "toml") {
+            toml::from_str(raw).map_err(|e| e.to_string().trim_end().to_string())
+        } else {
+            serde_json::from_str(raw).map_err(|e| e.to_string())
+        }
+    }
+
+    /// Parses a raw value as JSON, falling back to a plain string.
+    fn parse_raw(raw: &str) -> Value {
+        serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()))
+    }
+
+    /// Converts a dotted key to a JSON pointer.
+    fn pointer(key: &str) -> String { format!("/{}", key.replace('.', "/")) }
+
+    /// Flattens a JSON value into `(dotted key, leaf value)` pairs, treating arrays as leaves.
+    fn leaves(value: &Value, prefix: &str) -> Vec<(String, Value)> {
+        match value {
+            Value::Object(map) => map
+                .iter()
+                .flat_map(|(k, v)| {
+                    let key = if prefix.is_empty() { k.clone() } else { format!("{prefix}.{k}") };
+                    Self::leaves(v, &key)
+                })
+                .collect(),
+            leaf => vec![(prefix.to_string(), leaf.clone())],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_override_and_revert() {
+        // A local store keeps the global configuration of concurrently running tests intact
+        let mut store = ConfigStore {
+            config: Arc::new(MissionConfig::default()),
+            sources: BTreeMap::new(),
+            journal: Vec::new(),
+            load_error: None,
+        };
+        let source_of = |store: &ConfigStore, key: &str| {
+            store.describe().into_iter().find(|e| e.key == key).map(|e| e.source)
+        };
+        let key = "runtime.img_max_dt_secs";
+        let initial = store.config.runtime.img_max_dt_secs;
+        assert!(matches!(
+            store.set_override("scoring.zo_points", "1"),
+            Err(ConfigError::NotOverridable(_))
+        ));
+        assert!(matches!(store.set_override(key, "0"), Err(ConfigError::Invalid(_))));
+        assert_eq!(store.config.runtime.img_max_dt_secs, initial);
+
+        store.set_override(key, "300").unwrap();
+        assert_eq!(store.config.runtime.img_max_dt_secs, 300);
+        assert_eq!(source_of(&store, key), Some(ConfigSource::Override));
+
+        assert_eq!(store.revert_last().unwrap(), key);
+        assert_eq!(store.config.runtime.img_max_dt_secs, initial);
+        assert_eq!(source_of(&store, key), Some(ConfigSource::Default));
+        assert!(matches!(store.revert_last(), Err(ConfigError::NothingToRevert)));
+        assert!(matches!(
+            store.set_override("world.map_width", "1000"),
+            Err(ConfigError::NotOverridable(_))
+        ));
+    }
+
+    #[test]
+    fn test_config_override_rejects_safety_keys() {
+        let mut store = ConfigStore {
+            config: Arc::new(MissionConfig::default()),
+            sources: BTreeMap::new(),
+            journal: Vec::new(),
+            load_error: None,
+        };
+        let initial = store.config.runtime.envelope_fuel_floor;
+        for key in [
+            "runtime.envelope_fuel_floor",
+            "runtime.envelope_clamp_speed",
+            "runtime.emergency_batt_floor",
+            "runtime.watchdog_state_action",
+            "runtime.http_breaker_threshold",
+            "runtime.http_command_retries",
+        ] {
+            let res = store.set_override(key, "1");
+            assert!(matches!(res, Err(ConfigError::NotOverridable(_))), "{key}");
+            let entry = store.describe().into_iter().find(|e| e.key == key).unwrap();
+            assert!(!entry.overridable, "{key}");
+        }
+        assert!((store.config.runtime.envelope_fuel_floor - initial).abs() < f64::EPSILON);
+        assert!(store.journal.is_empty());
+        let entry = store.describe().into_iter().find(|e| e.key == "runtime.log_level").unwrap();
+        assert!(entry.overridable);
+    }
+
+    #[test]
+    fn test_world_config_validation() {
+        assert!(WorldConfig::default().validate().is_ok());
+        assert!(WorldConfig { map_width: 0, map_height: 10800 }.validate().is_err());
+        assert!(WorldConfig { map_width: 21610, map_height: 10800 }.validate().is_err());
+        assert!(WorldConfig { map_width: 10800, map_height: 5400 }.validate().is_ok());
+    }
+
+    #[test]
+    fn test_scheduling_config_validation() {
+        let default = SchedulingConfig::default();
+        assert!(default.validate().is_ok());
+        assert!(SchedulingConfig { min_battery: f64::NAN, ..default.clone() }.validate().is_err());
+        assert!(SchedulingConfig { max_battery: f64::NAN, ..default.clone() }.validate().is_err());
+        let infinite = SchedulingConfig { max_battery: f64::INFINITY, ..default.clone() };
+        assert!(infinite.validate().is_err());
+        assert!(SchedulingConfig { min_battery: -1.0, ..default }.validate().is_err());
+    }
+
+    #[test]
+    fn test_orbit_config_validation() {
+        let mut orbit = OrbitConfig::default();
+        assert!(orbit.validate().is_ok());
+        orbit.return_profile = "fast".to_string();
+        assert!(orbit.validate().is_err());
+        let mut fast = orbit.profiles[0].clone();
+        fast.name = "fast".to_string();
+        fast.vel = (20.0, 10.0);
+        orbit.profiles.push(fast.clone());
+        assert!(orbit.validate().is_ok());
+        assert_eq!(orbit.return_profile(), Some(&fast));
+        assert_eq!(orbit.active_profile(fast.vel()), Some(&fast));
+        let unknown_vel = Vec2D::new(I32F32::from_num(3), I32F32::from_num(3));
+        assert_eq!(orbit.active_profile(unknown_vel), orbit.init_profile());
+        orbit.profiles.push(fast);
+        assert!(orbit.validate().is_err());
+    }
+
+    #[test]
+    fn test_envelope_lens_validation() {
+        let mut config = MissionConfig::default();
+        config.runtime.envelope_lens = CameraAngle::Narrow;
+        assert!(config.validate().is_ok());
+        config.orbit.profiles[0].vel = (20.0, 10.0);
+        assert!(config.validate().is_err());
+        config.runtime.envelope_lens = CameraAngle::Wide;
+        assert!(config.validate().is_ok());
+        config.runtime.emergency_batt_floor = 12.0;
+        assert!(config.validate().is_err());
+        config.scheduling.min_battery = 15.0;
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_toml_config_file() {
+        let raw = "[network]\nbase_url = \"https://drs:33000\"\n[scheduling]\nmin_battery = 20.0";
+        let file = MissionConfig::parse_file("melvin.toml", raw).unwrap();
+        let url = Value::String("https://drs:33000".to_string());
+        assert!(MissionConfig::leaves(&file, "").contains(&("network.base_url".to_string(), url)));
+        let mut config: MissionConfig = serde_json::from_value(file).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.network.base_url, "https://drs:33000");
+        assert!(config.scheduling.min_battery > SchedulingConfig::default().min_battery);
+        config.scheduling.min_battery = 60.0;
+        assert!(config.validate().is_err());
+        config.scheduling = SchedulingConfig::default();
+        config.network.base_url = "drs:33000".to_string();
+        assert!(config.validate().is_err());
+
+        let raw = "[scheduling]\nmission_end = \"2025-01-31T12:00:00Z\"";
+        let file = MissionConfig::parse_file("melvin.toml", raw).unwrap();
+        let config: MissionConfig = serde_json::from_value(file).unwrap();
+        assert!(config.scheduling.mission_end.is_some());
+        assert!(MissionConfig::parse_file("melvin.toml", "[network").is_err());
+        assert!(MissionConfig::parse_file("mission.json", raw).is_err());
+    }
+}
@@ -0,0 +1,250 @@
+use super::{EVENT_BUS, ImagingEvent, SafetyEvent, Vec2D};
+use crate::objective::ScoreSource;
+use crate::warn;
+use chrono::{DateTime, Utc};
+use fixed::types::I32F32;
+use std::{
+    collections::VecDeque,
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::Path,
+    sync::{LazyLock, Mutex},
+};
+
+/// The process-wide mission journal.
+pub(crate) static MISSION_JOURNAL: LazyLock<MissionJournal> = LazyLock::new(MissionJournal::new);
+
+/// A structured mission event recorded in the [`MissionJournal`].
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum JournalEvent {
+    /// The main loop switched to another global mode.
+    ModeSwitch {
+        /// The previously active mode, `None` at mission start.
+        from: Option<&'static str>,
+        /// The newly active mode.
+        to: &'static str,
+    },
+    /// A burn sequence was executed.
+    BurnExecuted {
+        /// The duration of the burn in seconds.
+        duration_s: i64,
+        /// The position after the burn.
+        pos: Vec2D<I32F32>,
+        /// The velocity after the burn.
+        vel: Vec2D<I32F32>,
+        /// The planned position after the burn.
+        target_pos: Vec2D<I32F32>,
+        /// The planned velocity after the burn.
+        target_vel: Vec2D<I32F32>,
+    },
+    /// A burn sequence was aborted and its velocity ramped back.
+    BurnAborted {
+        /// The number of velocity changes completed before the abort.
+        steps_done: usize,
+        /// The number of velocity changes in the burn sequence.
+        steps_total: usize,
+        /// The position at the time of the abort.
+        pos: Vec2D<I32F32>,
+        /// The velocity at the time of the abort.
+        vel: Vec2D<I32F32>,
+        /// The velocity that was restored.
+        rollback_vel: Vec2D<I32F32>,
+    },
+    /// An objective was completed or a coverage milestone reached.
+    ObjectiveCompleted {
+        /// What the points were earned for.
+        source: ScoreSource,
+        /// The estimated points.
+        points: f64,
+    },
+    /// An unplanned safe mode was entered.
+    SafeModeEntered,
+    /// The satellite left safe mode again.
+    SafeModeLeft,
+    /// The end-of-mission routine was requested.
+    EndOfMission,
+    /// Observations were restored after an outage between the two times.
+    LinkRestored {
+        /// The start of the outage.
+        start: DateTime<Utc>,
+        /// The end of the outage.
+        end: DateTime<Utc>,
+    },
+    /// The DRS backend became unreachable.
+    BackendOutage,
+    /// The DRS backend answers again after an outage since `start`.
+    BackendRestored {
+        /// The start of the outage.
+        start: DateTime<Utc>,
+    },
+    /// MELVIN persistently deviated from the closed orbit.
+    OrbitDeviation {
+        /// The distance to the closest orbit segment.
+        deviation: I32F32,
+    },
+    /// An operator aborted the current global mode.
+    OperatorAbort,
+    /// The free disk space fell below the configured minimum.
+    DiskSpaceLow {
+        /// The free disk space in bytes.
+        free_bytes: u64,
+    },
+    /// The closed orbit was replanned from MELVINs current state.
+    OrbitReplanned {
+        /// The period of the replanned orbit in seconds.
+        period: usize,
+        /// The number of orbit seconds whose coverage was carried over.
+        carried: usize,
+    },
+    /// Corrupted tiles of the map buffer were reset and their map regions reopened.
+    MapQuarantined {
+        /// The number of quarantined tiles.
+        tiles: usize,
+        /// The number of orbit seconds reopened for imaging.
+        reopened: usize,
+    },
+    /// Imaging became degraded after a streak of failed captures.
+    CaptureDegraded,
+    /// Imaging recovered from a degraded state.
+    CaptureRecovered,
+    /// The process is shutting down gracefully.
+    Shutdown {
+        /// The signal that triggered the shutdown.
+        signal: &'static str,
+    },
+}
+
+impl From<SafetyEvent> for JournalEvent {
+    fn from(value: SafetyEvent) -> Self {
+        match value {
+            SafetyEvent::SafeModeEntered => Self::SafeModeEntered,
+            SafetyEvent::SafeModeLeft => Self::SafeModeLeft,
+            SafetyEvent::EndOfMission => Self::EndOfMission,
+            SafetyEvent::LinkRestored(start, end) => Self::LinkRestored { start, end },
+            SafetyEvent::BackendOutage(_) => Self::BackendOutage,
+            SafetyEvent::BackendRestored(start, _) => Self::BackendRestored { start },
+            SafetyEvent::OrbitDeviation(deviation) => Self::OrbitDeviation { deviation },
+            SafetyEvent::OperatorAbort => Self::OperatorAbort,
+            SafetyEvent::DiskSpaceLow(free_bytes) => Self::DiskSpaceLow { free_bytes },
+        }
+    }
+}
+
+/// A single entry of the [`MissionJournal`].
+#[derive(Debug, Clone, serde::Serialize)]
+pub(crate) struct JournalEntry {
+    /// The consecutive number of the entry.
+    seq: u64,
+    /// The time the event was recorded.
+    t: DateTime<Utc>,
+    /// The recorded event.
+    #[serde(flatten)]
+    event: JournalEvent,
+}
+
+/// The mutable state of the [`MissionJournal`].
+struct JournalState {
+    /// The number of entries recorded so far.
+    next_seq: u64,
+    /// The most recent entries, oldest first.
+    recent: VecDeque<JournalEntry>,
+    /// The JSONL file all entries are appended to, `None` if it could not be opened.
+    file: Option<File>,
+}
+
+/// Journal of structured mission events for post-pass analysis.
+///
+/// Unlike the text log, every entry is a typed event that is appended as a single JSON line
+/// to `./dumps/journal/events.jsonl`. The most recent entries are additionally kept in memory
+/// so that the operator console can fetch them.
+pub(crate) struct MissionJournal {
+    /// The lock-protected journal state.
+    state: Mutex<JournalState>,
+}
+
+impl MissionJournal {
+    /// The path of the JSONL journal file.
+    const PATH: &'static str = "./dumps/journal/events.jsonl";
+    /// The number of entries kept in memory.
+    const RECENT_CAPACITY: usize = 1000;
+
+    /// Creates a new [`MissionJournal`] appending to [`Self::PATH`].
+    fn new() -> Self {
+        let path = Path::new(Self::PATH);
+        let file = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| OpenOptions::new().create(true).append(true).open(path))
+            .inspect_err(|e| warn!("Failed opening mission journal {path:?}: {e}"))
+            .ok();
+        let recent = VecDeque::with_capacity(Self::RECENT_CAPACITY);
+        Self { state: Mutex::new(JournalState { next_seq: 0, recent, file }) }
+    }
+
+    /// Records a mission event.
+    ///
+    /// # Arguments
+    /// * `event` – The event to record.
+    pub(crate) fn record(&self, event: JournalEvent) {
+        let mut state = self.state.lock().unwrap();
+        let entry = JournalEntry { seq: state.next_seq, t: Utc::now(), event };
+        state.next_seq += 1;
+        if let Some(file) = state.file.as_mut() {
+            let line = serde_json::to_string(&entry).unwrap_or_default();
+            if writeln!(file, "{line}").is_err() {
+                warn!("Failed appending to mission journal, disabling the journal file.");
+                state.file = None;
+            }
+        }
+        if state.recent.len() == Self::RECENT_CAPACITY {
+            state.recent.pop_front();
+        }
+        state.recent.push_back(entry);
+    }
+
+    /// Returns up to `n` of the most recent entries, oldest first.
+    ///
+    /// # Arguments
+    /// * `n` – The maximum number of entries.
+    pub(crate) fn last(&self, n: usize) -> Vec<JournalEntry> {
+        let state = self.state.lock().unwrap();
+        let skip = state.recent.len().saturating_sub(n);
+        state.recent.iter().skip(skip).cloned().collect()
+    }
+
+    /// Records all safety and capture health events published on the [`EVENT_BUS`].
+    pub(crate) async fn run_bus_recorder(&self) {
+        let mut safety_rx = EVENT_BUS.subscribe::<SafetyEvent>();
+        let mut imaging_rx = EVENT_BUS.subscribe::<ImagingEvent>();
+        loop {
+            tokio::select! {
+                Some(event) = safety_rx.recv() => self.record(event.into()),
+                Some(event) = imaging_rx.recv() => match event {
+                    ImagingEvent::CaptureDegraded => self.record(JournalEvent::CaptureDegraded),
+                    ImagingEvent::CaptureRecovered => self.record(JournalEvent::CaptureRecovered),
+                    ImagingEvent::CoverageCatchUp => {}
+                },
+                else => return,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_journal_keeps_last_entries() {
+        let journal = MissionJournal::new();
+        journal.record(JournalEvent::ModeSwitch { from: None, to: "a" });
+        journal.record(JournalEvent::SafeModeEntered);
+        journal.record(JournalEvent::SafeModeLeft);
+        let last = journal.last(2);
+        assert_eq!(last.iter().map(|e| e.seq).collect::<Vec<_>>(), vec![1, 2]);
+        let line = serde_json::to_string(&last[1]).unwrap();
+        assert!(line.contains(r#""kind":"safe_mode_left""#));
+        assert_eq!(journal.last(10).len(), 3);
+    }
+}
@@ -0,0 +1,93 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// The process-wide mission metrics.
+pub(crate) static MISSION_METRICS: MissionMetrics = MissionMetrics::new();
+
+/// Monotonic counters of mission activity, exported to dashboards by the telemetry endpoint.
+///
+/// Gauges like battery or fuel are read from the current state when exported, only events
+/// that leave no state behind are counted here.
+#[derive(Debug)]
+pub(crate) struct MissionMetrics {
+    /// The number of images received and decoded.
+    pictures_taken: AtomicU64,
+    /// The number of image acquisitions that failed.
+    picture_failures: AtomicU64,
+    /// The number of DRS requests that failed with a transient error, e.g. 5xx or timeouts.
+    http_transient_errors: AtomicU64,
+    /// The number of DRS requests that failed with any other error.
+    http_other_errors: AtomicU64,
+    /// The number of DRS requests rejected by the open circuit breaker.
+    http_rejected: AtomicU64,
+    /// The number of global mode switches.
+    mode_switches: AtomicU64,
+}
+
+/// A point-in-time copy of the [`MissionMetrics`] counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct MetricsSnapshot {
+    /// The number of images received and decoded.
+    pub(crate) pictures_taken: u64,
+    /// The number of image acquisitions that failed.
+    pub(crate) picture_failures: u64,
+    /// The number of DRS requests that failed with a transient error.
+    pub(crate) http_transient_errors: u64,
+    /// The number of DRS requests that failed with any other error.
+    pub(crate) http_other_errors: u64,
+    /// The number of DRS requests rejected by the open circuit breaker.
+    pub(crate) http_rejected: u64,
+    /// The number of global mode switches.
+    pub(crate) mode_switches: u64,
+}
+
+impl MissionMetrics {
+    /// Creates a new [`MissionMetrics`] instance with all counters at zero.
+    const fn new() -> Self {
+        Self {
+            pictures_taken: AtomicU64::new(0),
+            picture_failures: AtomicU64::new(0),
+            http_transient_errors: AtomicU64::new(0),
+            http_other_errors: AtomicU64::new(0),
+            http_rejected: AtomicU64::new(0),
+            mode_switches: AtomicU64::new(0),
+        }
+    }
+
+    /// Counts the outcome of an image acquisition.
+    ///
+    /// # Arguments
+    /// * `success` – Whether the image was received and decoded.
+    pub(crate) fn record_picture(&self, success: bool) {
+        let counter = if success { &self.pictures_taken } else { &self.picture_failures };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Counts a failed DRS request.
+    ///
+    /// # Arguments
+    /// * `transient` – Whether the failure was transient and thus counts towards the breaker.
+    pub(crate) fn record_http_error(&self, transient: bool) {
+        let counter = if transient { &self.http_transient_errors } else { &self.http_other_errors };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Counts a DRS request rejected by the open circuit breaker.
+    pub(crate) fn record_http_rejected(&self) {
+        self.http_rejected.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Counts a global mode switch.
+    pub(crate) fn record_mode_switch(&self) { self.mode_switches.fetch_add(1, Ordering::Relaxed); }
+
+    /// Returns the current value of all counters.
+    pub(crate) fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            pictures_taken: self.pictures_taken.load(Ordering::Relaxed),
+            picture_failures: self.picture_failures.load(Ordering::Relaxed),
+            http_transient_errors: self.http_transient_errors.load(Ordering::Relaxed),
+            http_other_errors: self.http_other_errors.load(Ordering::Relaxed),
+            http_rejected: self.http_rejected.load(Ordering::Relaxed),
+            mode_switches: self.mode_switches.load(Ordering::Relaxed),
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use std::{
+    fmt,
+    ops::{Add, AddAssign},
+    time::Duration,
+};
+
+/// A non-negative number of whole seconds, the time unit of all scheduling computations.
+///
+/// Conversions from `chrono` and `std` durations saturate at zero instead of failing, and all
+/// arithmetic saturates, so that deadlines that already passed yield an empty budget rather than
+/// a wrapped or negative number of seconds.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TimeBudget(usize);
+
+impl TimeBudget {
+    /// The empty budget.
+    pub const ZERO: Self = Self(0);
+
+    /// Creates a budget of `secs` seconds.
+    pub const fn secs(secs: usize) -> Self { Self(secs) }
+
+    /// Returns the number of whole seconds, e.g. to index a DP time step.
+    pub const fn as_secs(self) -> usize { self.0 }
+
+    /// Returns `true` if the budget is empty.
+    pub const fn is_zero(self) -> bool { self.0 == 0 }
+
+    /// Creates a budget from a `TimeDelta`, truncating partial seconds.
+    ///
+    /// Negative deltas yield [`TimeBudget::ZERO`].
+    pub fn from_delta(dt: TimeDelta) -> Self {
+        Self(usize::try_from(dt.num_seconds()).unwrap_or(0))
+    }
+
+    /// Creates a budget from a `TimeDelta`, rounding partial seconds up.
+    ///
+    /// Negative deltas yield [`TimeBudget::ZERO`].
+    pub fn from_delta_ceil(dt: TimeDelta) -> Self {
+        let partial = dt.subsec_nanos() > 0 && dt > TimeDelta::zero();
+        Self::from_delta(dt) + Self(usize::from(partial))
+    }
+
+    /// Returns the budget from `start` until `end`, zero if `end` is not after `start`.
+    ///
+    /// # Arguments
+    /// * `start` – The start of the interval.
+    /// * `end` – The end of the interval.
+    pub fn between(start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        Self::from_delta(end - start)
+    }
+
+    /// Returns the budget from `start` until `end`, or `None` if `end` is before `start`.
+    ///
+    /// # Arguments
+    /// * `start` – The start of the interval.
+    /// * `end` – The end of the interval.
+    pub fn try_between(start: DateTime<Utc>, end: DateTime<Utc>) -> Option<Self> {
+        usize::try_from((end - start).num_seconds()).ok().map(Self)
+    }
+
+    /// Subtracts `rhs`, yielding [`TimeBudget::ZERO`] instead of wrapping.
+    pub const fn saturating_sub(self, rhs: Self) -> Self { Self(self.0.saturating_sub(rhs.0)) }
+
+    /// Converts the budget to a `TimeDelta`, clamped to the largest delta `chrono` can hold.
+    pub fn to_delta(self) -> TimeDelta {
+        const MAX_SECS: i64 = i64::MAX / 1000;
+        TimeDelta::seconds(i64::try_from(self.0).map_or(MAX_SECS, |secs| secs.min(MAX_SECS)))
+    }
+
+    /// Converts the budget to a `std::time::Duration`.
+    pub const fn to_std(self) -> Duration { Duration::from_secs(self.0 as u64) }
+}
+
+impl From<Duration> for TimeBudget {
+    /// Converts a `std::time::Duration`, truncating partial seconds.
+    fn from(dt: Duration) -> Self { Self(usize::try_from(dt.as_secs()).unwrap_or(usize::MAX)) }
+}
+
+impl Add for TimeBudget {
+    type Output = Self;
+
+    /// Adds two budgets, saturating at the maximum budget.
+    fn add(self, rhs: Self) -> Self { Self(self.0.saturating_add(rhs.0)) }
+}
+
+impl AddAssign for TimeBudget {
+    fn add_assign(&mut self, rhs: Self) { *self = *self + rhs; }
+}
+
+impl Add<TimeBudget> for DateTime<Utc> {
+    type Output = Self;
+
+    /// Returns the point in time `rhs` after `self`, saturating at the latest representable time.
+    fn add(self, rhs: TimeBudget) -> Self {
+        self.checked_add_signed(rhs.to_delta()).unwrap_or(DateTime::<Utc>::MAX_UTC)
+    }
+}
+
+impl fmt::Display for TimeBudget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "{}s", self.0) }
+}
+
+/// A point in mission time, the counterpart of [`TimeBudget`] for absolute times.
+///
+/// Adding a budget saturates at the latest representable time, and the budget between two
+/// points in time saturates at zero if they are out of order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MissionTime(DateTime<Utc>);
+
+impl MissionTime {
+    /// Returns the current point in mission time.
+    pub fn now() -> Self { Self(Utc::now()) }
+
+    /// Returns the UTC timestamp of this point in time.
+    pub const fn utc(self) -> DateTime<Utc> { self.0 }
+
+    /// Returns the budget from `self` until `end`, zero if `end` is not after `self`.
+    pub fn until(self, end: Self) -> TimeBudget { TimeBudget::between(self.0, end.0) }
+
+    /// Returns the budget from `start` until `self`, zero if `start` is not before `self`.
+    pub fn since(self, start: Self) -> TimeBudget { TimeBudget::between(start.0, self.0) }
+
+    /// Returns the point in time `rhs` before `self`, saturating at the earliest representable
+    /// time.
+    pub fn saturating_sub(self, rhs: TimeBudget) -> Self {
+        Self(self.0.checked_sub_signed(rhs.to_delta()).unwrap_or(DateTime::<Utc>::MIN_UTC))
+    }
+}
+
+impl From<DateTime<Utc>> for MissionTime {
+    fn from(t: DateTime<Utc>) -> Self { Self(t) }
+}
+
+impl From<MissionTime> for DateTime<Utc> {
+    fn from(t: MissionTime) -> Self { t.0 }
+}
+
+impl Add<TimeBudget> for MissionTime {
+    type Output = Self;
+
+    /// Returns the point in time `rhs` after `self`, saturating at the latest representable time.
+    fn add(self, rhs: TimeBudget) -> Self { Self(self.0 + rhs) }
+}
+
+impl AddAssign<TimeBudget> for MissionTime {
+    fn add_assign(&mut self, rhs: TimeBudget) { *self = *self + rhs; }
+}
+
+impl fmt::Display for MissionTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "{}", self.0) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_budget_saturates() {
+        let t = Utc::now();
+        assert_eq!(TimeBudget::between(t, t + TimeDelta::seconds(90)), TimeBudget::secs(90));
+        assert_eq!(TimeBudget::between(t, t - TimeDelta::seconds(90)), TimeBudget::ZERO);
+        assert_eq!(TimeBudget::try_between(t, t - TimeDelta::seconds(1)), None);
+        assert_eq!(TimeBudget::secs(50).saturating_sub(TimeBudget::secs(100)), TimeBudget::ZERO);
+        assert_eq!(TimeBudget::from_delta_ceil(TimeDelta::milliseconds(1500)).as_secs(), 2);
+        assert_eq!(TimeBudget::from_delta_ceil(TimeDelta::milliseconds(-1500)), TimeBudget::ZERO);
+        assert_eq!(TimeBudget::from(Duration::from_millis(2999)).as_secs(), 2);
+        assert_eq!(t + TimeBudget::secs(3), t + TimeDelta::seconds(3));
+        assert_eq!(TimeBudget::secs(usize::MAX).to_delta(), TimeDelta::seconds(i64::MAX / 1000));
+        assert_eq!(t + TimeBudget::secs(usize::MAX), DateTime::<Utc>::MAX_UTC);
+    }
+
+    #[test]
+    fn test_mission_time() {
+        let t = MissionTime::from(Utc::now());
+        let later = t + TimeBudget::secs(90);
+        assert_eq!(t.until(later), TimeBudget::secs(90));
+        assert_eq!(later.until(t), TimeBudget::ZERO);
+        assert_eq!(later.since(t), TimeBudget::secs(90));
+        assert_eq!(later.saturating_sub(TimeBudget::secs(90)), t);
+        assert_eq!(t.saturating_sub(TimeBudget::secs(usize::MAX)).utc(), DateTime::<Utc>::MIN_UTC);
+        assert_eq!(DateTime::<Utc>::from(later), t.utc() + TimeDelta::seconds(90));
+    }
+}
@@ -1,12 +1,34 @@
 //! This module provides utilities and functionalities for mathematical operations,
-//! logging, and the controller keychain.
+//! logging, the mission event journal and metrics, the controller keychain, the event bus, typed
+//! objective identifiers, scheduling time budgets and points in mission time and the supervision
+//! of long-running subsystem tasks.
+mod event_bus;
 mod keychain;
 pub mod logger;
 mod math;
+mod mission_config;
+mod mission_journal;
+mod mission_metrics;
+mod mission_time;
+mod objective_id;
+mod task_supervision;
+mod worker_pool;
 
+pub(crate) use event_bus::{
+    BeaconEvent, EVENT_BUS, ImagingEvent, LogEvent, ObjectiveEvent, SafetyEvent, SchedulingEvent,
+    Subscription,
+};
 pub use keychain::{Keychain, KeychainWithOrbit};
+pub use mission_config::MissionConfig;
+pub(crate) use mission_journal::{JournalEvent, MISSION_JOURNAL};
+pub(crate) use mission_metrics::{MISSION_METRICS, MetricsSnapshot};
+pub use mission_time::{MissionTime, TimeBudget};
+pub(crate) use task_supervision::spawn_supervised;
+pub(crate) use worker_pool::{IMAGING_POOL, PLANNING_POOL, RuntimeReport, WorkerPool};
 pub use math::vec2d::Vec2D;
 pub use math::vec2d::MapSize;
 pub use math::helpers;
 pub use math::vec2d::WrapDirection;
 pub use math::vec2d::VecAxis;
+pub use math::zone_rect::ZoneRect;
+pub use objective_id::{BeaconObjectiveId, ImgObjectiveId, ObjectiveId, ObjectiveKind};
@@ -0,0 +1,88 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+};
+
+/// The kind of objective an [`ObjectiveId`] refers to.
+pub trait ObjectiveKind {
+    /// A short name of the kind used in logs.
+    const NAME: &'static str;
+}
+
+/// Marker for imaging objectives, both known and secret zones.
+#[derive(Debug)]
+pub enum ImgObjective {}
+
+/// Marker for beacon objectives.
+#[derive(Debug)]
+pub enum BeaconObj {}
+
+impl ObjectiveKind for ImgObjective {
+    const NAME: &'static str = "zo";
+}
+
+impl ObjectiveKind for BeaconObj {
+    const NAME: &'static str = "bo";
+}
+
+/// The DRS identifier of an objective, typed by the kind of objective.
+///
+/// Imaging and beacon objectives are numbered independently by the DRS, the kind parameter
+/// prevents an image objective ID from being used where a beacon ID is expected and vice versa.
+/// IDs are serialized as plain numbers.
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(transparent, bound = "")]
+pub struct ObjectiveId<K: ObjectiveKind> {
+    /// The raw DRS identifier.
+    id: usize,
+    /// The kind of the objective.
+    #[serde(skip)]
+    kind: PhantomData<K>,
+}
+
+/// The ID of an imaging objective.
+pub type ImgObjectiveId = ObjectiveId<ImgObjective>;
+/// The ID of a beacon objective.
+pub type BeaconObjectiveId = ObjectiveId<BeaconObj>;
+
+impl<K: ObjectiveKind> ObjectiveId<K> {
+    /// Creates a new [`ObjectiveId`] from the raw DRS identifier.
+    pub const fn new(id: usize) -> Self { Self { id, kind: PhantomData } }
+
+    /// Returns the raw DRS identifier.
+    pub const fn raw(self) -> usize { self.id }
+}
+
+impl<K: ObjectiveKind> Clone for ObjectiveId<K> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<K: ObjectiveKind> Copy for ObjectiveId<K> {}
+
+impl<K: ObjectiveKind> PartialEq for ObjectiveId<K> {
+    fn eq(&self, other: &Self) -> bool { self.id == other.id }
+}
+
+impl<K: ObjectiveKind> Eq for ObjectiveId<K> {}
+
+impl<K: ObjectiveKind> PartialOrd for ObjectiveId<K> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl<K: ObjectiveKind> Ord for ObjectiveId<K> {
+    fn cmp(&self, other: &Self) -> Ordering { self.id.cmp(&other.id) }
+}
+
+impl<K: ObjectiveKind> Hash for ObjectiveId<K> {
+    fn hash<H: Hasher>(&self, state: &mut H) { self.id.hash(state); }
+}
+
+impl<K: ObjectiveKind> fmt::Display for ObjectiveId<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { self.id.fmt(f) }
+}
+
+impl<K: ObjectiveKind> fmt::Debug for ObjectiveId<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "{}#{}", K::NAME, self.id) }
+}
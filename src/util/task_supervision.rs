@@ -0,0 +1,228 @@
+use super::logger::JsonDump;
+use crate::{error, info};
+use chrono::{DateTime, Utc};
+use std::{any::Any, future::Future, path::PathBuf, time::Duration};
+use tokio::{task::JoinError, time::Instant};
+
+/// The way a supervised subsystem task terminated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub(crate) enum SubsystemExit {
+    /// The task panicked.
+    Panicked,
+    /// The task returned although it is meant to run for the whole mission.
+    Returned,
+    /// The task was cancelled by the runtime.
+    Cancelled,
+}
+
+/// Structured alert raised whenever a supervised subsystem task terminates.
+#[derive(Debug, Clone, serde::Serialize)]
+pub(crate) struct SubsystemAlert {
+    /// The name of the subsystem.
+    subsystem: &'static str,
+    /// The way the task terminated.
+    exit: SubsystemExit,
+    /// The captured panic message, if the task panicked.
+    payload: Option<String>,
+    /// The number of restarts before this termination.
+    restarts: u32,
+    /// The time the task ran before terminating, in seconds.
+    uptime_s: u64,
+    /// The delay before the next restart, in seconds.
+    backoff_s: u64,
+    /// The time the termination was detected.
+    t: DateTime<Utc>,
+}
+
+impl JsonDump for SubsystemAlert {
+    /// Returns the file name of the latest alert of the subsystem.
+    fn file_name(&self) -> String { format!("alert_{}", self.subsystem) }
+
+    /// Returns the directory name for the subsystem alerts.
+    fn dir_name(&self) -> &'static str { "runtime" }
+}
+
+impl SubsystemAlert {
+    /// Returns the way the task terminated.
+    pub(crate) fn exit(&self) -> SubsystemExit { self.exit }
+
+    /// Returns the captured panic message, if the task panicked.
+    pub(crate) fn payload(&self) -> Option<&str> { self.payload.as_deref() }
+}
+
+/// Restart policy for long-running subsystem tasks.
+///
+/// The restart delay doubles with every termination up to [`Self::MAX_BACKOFF`], and is reset
+/// once a task ran for at least [`Self::STABLE_RUN`] before terminating again.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Backoff {
+    /// The current restart delay.
+    delay: Duration,
+}
+
+impl Backoff {
+    /// The restart delay after the first termination.
+    const MIN_BACKOFF: Duration = Duration::from_secs(1);
+    /// The maximum restart delay.
+    const MAX_BACKOFF: Duration = Duration::from_secs(300);
+    /// The uptime after which a task is considered to have recovered.
+    const STABLE_RUN: Duration = Duration::from_secs(600);
+
+    /// Creates a new [`Backoff`] starting at the minimum delay.
+    pub(crate) fn new() -> Self { Self { delay: Self::MIN_BACKOFF } }
+
+    /// Returns the delay before the next restart and advances the policy.
+    ///
+    /// # Arguments
+    /// * `uptime` – The time the terminated task ran.
+    pub(crate) fn next_delay(&mut self, uptime: Duration) -> Duration {
+        if uptime >= Self::STABLE_RUN {
+            self.delay = Self::MIN_BACKOFF;
+        }
+        let delay = self.delay;
+        self.delay = (self.delay * 2).min(Self::MAX_BACKOFF);
+        delay
+    }
+}
+
+/// Spawns a long-running subsystem task and restarts it whenever it terminates.
+///
+/// Background tasks like the announcement hub or the beacon controller are meant to run for the
+/// whole mission. If one of them panics or returns, a [`SubsystemAlert`] containing the captured
+/// panic message is logged and dumped, and the task is recreated by `factory` after a backoff
+/// delay.
+///
+/// # Arguments
+/// * `subsystem` – The name of the subsystem used in alerts.
+/// * `factory` – Creates a fresh instance of the task future.
+pub(crate) fn spawn_supervised<F, Fut>(subsystem: &'static str, factory: F)
+where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    tokio::spawn(supervise(subsystem, factory, PathBuf::from("./dumps"), |_| {}));
+}
+
+/// Runs the supervision loop of [`spawn_supervised`], handing each alert to `on_alert`.
+///
+/// Uptimes and restart delays follow the tokio clock.
+///
+/// # Arguments
+/// * `subsystem` – The name of the subsystem used in alerts.
+/// * `factory` – Creates a fresh instance of the task future.
+/// * `dump_dir` – The directory the alerts are dumped to.
+/// * `on_alert` – Called with every raised alert before the restart delay.
+pub(crate) async fn supervise<F, Fut>(
+    subsystem: &'static str,
+    factory: F,
+    dump_dir: PathBuf,
+    mut on_alert: impl FnMut(&SubsystemAlert) + Send,
+) where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let mut backoff = Backoff::new();
+    let mut restarts = 0;
+    loop {
+        let started = Instant::now();
+        let res = tokio::spawn(factory()).await;
+        let uptime = started.elapsed();
+        let (exit, payload) = match res {
+            Ok(()) => (SubsystemExit::Returned, None),
+            Err(e) => exit_cause(e),
+        };
+        let delay = backoff.next_delay(uptime);
+        let alert = SubsystemAlert {
+            subsystem,
+            exit,
+            payload,
+            restarts,
+            uptime_s: uptime.as_secs(),
+            backoff_s: delay.as_secs(),
+            t: Utc::now(),
+        };
+        error!(
+            "Subsystem {subsystem} {exit:?} after {}s: {}. Restarting in {}s.",
+            alert.uptime_s,
+            alert.payload().unwrap_or("no panic message"),
+            alert.backoff_s
+        );
+        alert.dump_json_to(&dump_dir);
+        on_alert(&alert);
+        tokio::time::sleep(delay).await;
+        restarts += 1;
+        info!("Restarting subsystem {subsystem} (restart #{restarts}).");
+    }
+}
+
+/// Classifies a failed subsystem task and extracts its panic message.
+///
+/// # Arguments
+/// * `err` – The error returned by the task handle.
+fn exit_cause(err: JoinError) -> (SubsystemExit, Option<String>) {
+    if err.is_panic() {
+        (SubsystemExit::Panicked, Some(panic_message(&*err.into_panic())))
+    } else {
+        (SubsystemExit::Cancelled, None)
+    }
+}
+
+/// Converts a captured panic payload into a readable message.
+///
+/// # Arguments
+/// * `payload` – The payload of the panic.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(ToString::to_string)
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "non-string panic payload".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{
+        Arc,
+        atomic::{AtomicU32, Ordering},
+    };
+
+    #[test]
+    fn test_backoff_growth_and_reset() {
+        let mut backoff = Backoff::new();
+        let short = Duration::from_secs(1);
+        let delays: Vec<u64> = (0..10).map(|_| backoff.next_delay(short).as_secs()).collect();
+        assert_eq!(delays, vec![1, 2, 4, 8, 16, 32, 64, 128, 256, 300]);
+        assert_eq!(backoff.next_delay(Backoff::STABLE_RUN), Backoff::MIN_BACKOFF);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_panicking_subsystem_restarts() {
+        let dump_dir = std::env::temp_dir().join(format!("melvin_alerts_{}", std::process::id()));
+        let runs = Arc::new(AtomicU32::new(0));
+        let factory_runs = Arc::clone(&runs);
+        let factory = move || {
+            let run = factory_runs.fetch_add(1, Ordering::SeqCst);
+            async move {
+                assert!(run != 0, "first run fails");
+                std::future::pending::<()>().await;
+            }
+        };
+        let alerts = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let alerts_sink = Arc::clone(&alerts);
+        let on_alert = move |alert: &SubsystemAlert| {
+            alerts_sink.lock().unwrap().push(alert.clone());
+        };
+        let handle = tokio::spawn(supervise("test", factory, dump_dir.clone(), on_alert));
+        // The paused clock skips the restart delay once all tasks are idle
+        tokio::time::sleep(Backoff::MIN_BACKOFF * 2).await;
+        handle.abort();
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+        let recorded = alerts.lock().unwrap();
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(recorded[0].exit(), SubsystemExit::Panicked);
+        assert_eq!(recorded[0].payload(), Some("first run fails"));
+        assert!(dump_dir.join("runtime").join("alert_test.json").exists());
+        std::fs::remove_dir_all(&dump_dir).unwrap();
+    }
+}
@@ -0,0 +1,305 @@
+use super::{MissionConfig, logger::JsonDump};
+use crate::warn;
+use chrono::{DateTime, Utc};
+use std::{
+    collections::BTreeMap,
+    sync::{
+        LazyLock, Mutex,
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+use tokio::{
+    runtime::{Builder, Handle, Runtime, RuntimeFlavor},
+    sync::Semaphore,
+    time::Instant,
+};
+
+/// The pool for CPU-heavy image decoding and processing.
+pub(crate) static IMAGING_POOL: LazyLock<WorkerPool> = LazyLock::new(|| {
+    WorkerPool::new("imaging", MissionConfig::get().threads.resolved_imaging_jobs())
+});
+
+/// The pool for CPU-heavy schedule optimization.
+pub(crate) static PLANNING_POOL: LazyLock<WorkerPool> = LazyLock::new(|| {
+    WorkerPool::new("planning", MissionConfig::get().threads.resolved_planning_jobs())
+});
+
+/// Timing statistics of all jobs of a pool with the same name.
+#[derive(Debug, Clone, Copy, Default, serde::Serialize)]
+struct JobTiming {
+    /// The number of finished jobs.
+    count: usize,
+    /// The cumulative running time in milliseconds.
+    total_ms: f64,
+    /// The longest running time since the last report in milliseconds.
+    max_ms: f64,
+}
+
+/// A bounded pool for CPU-heavy jobs that would otherwise stall the async worker threads.
+///
+/// Jobs run on the calling worker thread, which is handed over to the runtime for the
+/// duration of the job, and at most `size` jobs of a pool run concurrently. Queue depth and
+/// waiting times are tracked to validate the configured sizing, running times are tracked
+/// per job name to find the jobs that keep a pool busy.
+#[derive(Debug)]
+pub(crate) struct WorkerPool {
+    /// The name of the pool used in reports.
+    name: &'static str,
+    /// The maximum number of concurrent jobs.
+    size: usize,
+    /// The permits limiting the number of concurrent jobs.
+    permits: Semaphore,
+    /// The number of jobs waiting for a permit.
+    queued: AtomicUsize,
+    /// The number of currently running jobs.
+    running: AtomicUsize,
+    /// The highest number of waiting jobs since the last report.
+    peak_queued: AtomicUsize,
+    /// The number of finished jobs.
+    completed: AtomicUsize,
+    /// The cumulative waiting time of all jobs in milliseconds.
+    total_wait_ms: AtomicU64,
+    /// The cumulative running time of all jobs in microseconds.
+    total_busy_us: AtomicU64,
+    /// The running times by job name.
+    timings: Mutex<BTreeMap<&'static str, JobTiming>>,
+}
+
+impl WorkerPool {
+    /// Creates a new [`WorkerPool`].
+    ///
+    /// # Arguments
+    /// * `name` – The name of the pool used in reports.
+    /// * `size` – The maximum number of concurrent jobs.
+    fn new(name: &'static str, size: usize) -> Self {
+        Self {
+            name,
+            size,
+            permits: Semaphore::new(size),
+            queued: AtomicUsize::new(0),
+            running: AtomicUsize::new(0),
+            peak_queued: AtomicUsize::new(0),
+            completed: AtomicUsize::new(0),
+            total_wait_ms: AtomicU64::new(0),
+            total_busy_us: AtomicU64::new(0),
+            timings: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Runs a CPU-heavy job as soon as the pool has a free slot.
+    ///
+    /// On a multi-threaded runtime the job runs in place of the calling worker thread, which
+    /// keeps other tasks responsive. On a current-thread runtime, e.g. in tests, the job is
+    /// simply run inline.
+    ///
+    /// # Arguments
+    /// * `job_name` – The name under which the running time of the job is tracked.
+    /// * `job` – The job to run.
+    ///
+    /// # Returns
+    /// * The result of the job.
+    #[allow(clippy::cast_possible_truncation)]
+    pub(crate) async fn run<R>(&self, job_name: &'static str, job: impl FnOnce() -> R) -> R {
+        let depth = self.queued.fetch_add(1, Ordering::Relaxed) + 1;
+        self.peak_queued.fetch_max(depth, Ordering::Relaxed);
+        let enqueued = Instant::now();
+        let _permit = self.permits.acquire().await.expect("[FATAL] Worker pool closed!");
+        self.queued.fetch_sub(1, Ordering::Relaxed);
+        let wait_ms = enqueued.elapsed().as_millis() as u64;
+        self.total_wait_ms.fetch_add(wait_ms, Ordering::Relaxed);
+        self.running.fetch_add(1, Ordering::Relaxed);
+        let started = Instant::now();
+        let res = if Handle::current().runtime_flavor() == RuntimeFlavor::MultiThread {
+            tokio::task::block_in_place(job)
+        } else {
+            job()
+        };
+        let busy = started.elapsed();
+        self.total_busy_us.fetch_add(busy.as_micros() as u64, Ordering::Relaxed);
+        self.record_timing(job_name, busy);
+        self.running.fetch_sub(1, Ordering::Relaxed);
+        self.completed.fetch_add(1, Ordering::Relaxed);
+        res
+    }
+
+    /// Adds the running time of a finished job to the timings of its name.
+    ///
+    /// # Arguments
+    /// * `job_name` – The name of the job.
+    /// * `busy` – The running time of the job.
+    fn record_timing(&self, job_name: &'static str, busy: Duration) {
+        let busy_ms = busy.as_secs_f64() * 1000.0;
+        let mut timings = self.timings.lock().unwrap();
+        let timing = timings.entry(job_name).or_default();
+        timing.count += 1;
+        timing.total_ms += busy_ms;
+        timing.max_ms = timing.max_ms.max(busy_ms);
+    }
+
+    /// Returns the name of the pool used in reports.
+    pub(crate) fn name(&self) -> &'static str { self.name }
+
+    /// Returns the cumulative running time of all finished jobs in seconds.
+    #[allow(clippy::cast_precision_loss)]
+    pub(crate) fn busy_secs(&self) -> f64 {
+        self.total_busy_us.load(Ordering::Relaxed) as f64 / 1_000_000.0
+    }
+
+    /// Returns the current statistics and resets the peak queue depth and the longest job
+    /// running times.
+    #[allow(clippy::cast_precision_loss)]
+    fn take_stats(&self) -> PoolStats {
+        let completed = self.completed.load(Ordering::Relaxed);
+        let total_wait_ms = self.total_wait_ms.load(Ordering::Relaxed);
+        let jobs = {
+            let mut timings = self.timings.lock().unwrap();
+            let jobs = timings.clone();
+            timings.values_mut().for_each(|t| t.max_ms = 0.0);
+            jobs
+        };
+        PoolStats {
+            name: self.name,
+            size: self.size,
+            queued: self.queued.load(Ordering::Relaxed),
+            running: self.running.load(Ordering::Relaxed),
+            peak_queued: self.peak_queued.swap(0, Ordering::Relaxed),
+            completed,
+            avg_wait_ms: if completed == 0 { 0.0 } else { total_wait_ms as f64 / completed as f64 },
+            jobs,
+        }
+    }
+
+    /// Builds the multi-threaded runtime sized by the `threads` section of the
+    /// [`MissionConfig`].
+    pub(crate) fn build_runtime() -> std::io::Result<Runtime> {
+        let config = MissionConfig::get();
+        let threads = &config.threads;
+        Builder::new_multi_thread()
+            .worker_threads(threads.resolved_workers())
+            .max_blocking_threads(threads.max_blocking_threads)
+            .enable_all()
+            .build()
+    }
+}
+
+/// Statistics of a single [`WorkerPool`].
+#[derive(Debug, serde::Serialize)]
+struct PoolStats {
+    /// The name of the pool.
+    name: &'static str,
+    /// The maximum number of concurrent jobs.
+    size: usize,
+    /// The number of jobs waiting for a permit.
+    queued: usize,
+    /// The number of currently running jobs.
+    running: usize,
+    /// The highest number of waiting jobs since the last report.
+    peak_queued: usize,
+    /// The number of finished jobs.
+    completed: usize,
+    /// The average waiting time of a job in milliseconds.
+    avg_wait_ms: f64,
+    /// The running times by job name.
+    jobs: BTreeMap<&'static str, JobTiming>,
+}
+
+/// Periodic report on the runtime and worker pool load.
+#[derive(Debug, serde::Serialize)]
+pub(crate) struct RuntimeReport {
+    /// The time the report was taken.
+    t: DateTime<Utc>,
+    /// The number of async worker threads.
+    workers: usize,
+    /// The number of alive tasks.
+    alive_tasks: usize,
+    /// The number of tasks in the global scheduler queue.
+    global_queue_depth: usize,
+    /// The time a newly spawned task waited for a worker thread in milliseconds.
+    spawn_latency_ms: f64,
+    /// The statistics of the dedicated pools.
+    pools: Vec<PoolStats>,
+}
+
+impl JsonDump for RuntimeReport {
+    /// Returns the file name for the JSON dump of the runtime report.
+    fn file_name(&self) -> String { "worker_pools".to_string() }
+
+    /// Returns the directory name for the runtime report JSON file.
+    fn dir_name(&self) -> &'static str { "runtime" }
+}
+
+impl RuntimeReport {
+    /// Interval between two reports.
+    const INTERVAL: Duration = Duration::from_secs(60);
+    /// Peak queue depth relative to the pool size above which a pool is considered undersized.
+    const QUEUE_WARN_FACTOR: usize = 4;
+    /// Spawn latency above which the async worker threads are considered starved, e.g. by
+    /// CPU-heavy work outside of the dedicated pools.
+    const SPAWN_LATENCY_WARN: Duration = Duration::from_millis(100);
+
+    /// Measures the time a newly spawned task waits until it is polled by a worker thread.
+    async fn spawn_latency() -> Duration {
+        let spawned = Instant::now();
+        tokio::spawn(async move { spawned.elapsed() }).await.unwrap_or_default()
+    }
+
+    /// Collects the current runtime and pool metrics.
+    async fn collect() -> Self {
+        let spawn_latency = Self::spawn_latency().await;
+        if spawn_latency > Self::SPAWN_LATENCY_WARN {
+            warn!("Async workers are starved, a spawned task waited {spawn_latency:.0?}.");
+        }
+        let metrics = Handle::current().metrics();
+        Self {
+            t: Utc::now(),
+            workers: metrics.num_workers(),
+            alive_tasks: metrics.num_alive_tasks(),
+            global_queue_depth: metrics.global_queue_depth(),
+            spawn_latency_ms: spawn_latency.as_secs_f64() * 1000.0,
+            pools: vec![IMAGING_POOL.take_stats(), PLANNING_POOL.take_stats()],
+        }
+    }
+
+    /// Periodically dumps a [`RuntimeReport`] and warns about undersized pools and starved
+    /// async worker threads.
+    pub(crate) async fn run_monitor() {
+        loop {
+            tokio::time::sleep(Self::INTERVAL).await;
+            let report = Self::collect().await;
+            let undersized = |p: &&PoolStats| p.peak_queued > p.size * Self::QUEUE_WARN_FACTOR;
+            for pool in report.pools.iter().filter(undersized) {
+                warn!(
+                    "Worker pool {} peaked at {} queued jobs with {} slots (avg wait {:.0}ms).",
+                    pool.name, pool.peak_queued, pool.size, pool.avg_wait_ms
+                );
+            }
+            report.dump_json();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_job_timings() {
+        let pool = WorkerPool::new("test", 2);
+        assert_eq!(pool.run("sum", || (1..=10).sum::<u32>()).await, 55);
+        pool.run("sum", || std::thread::sleep(Duration::from_millis(5))).await;
+        pool.run("noop", || ()).await;
+
+        let stats = pool.take_stats();
+        assert_eq!(stats.completed, 3);
+        assert_eq!(stats.jobs.len(), 2);
+        let sum = stats.jobs["sum"];
+        assert_eq!(sum.count, 2);
+        assert!(sum.max_ms >= 5.0 && sum.total_ms >= sum.max_ms);
+        assert_eq!(stats.jobs["noop"].count, 1);
+
+        let stats = pool.take_stats();
+        assert_eq!(stats.jobs["sum"].count, 2);
+        assert!(stats.jobs["sum"].max_ms.abs() < f64::EPSILON);
+    }
+}